@@ -0,0 +1,340 @@
+//! 多账号注册表。
+//!
+//! 活动账号始终占用 [`AppState::client`] 这一个共享句柄，既有 Tauri 命令 / Bridge 路由 /
+//! 后台任务无需改动；其余已登录账号的 `HbutClient` 停放在 [`AccountRegistry`] 中，
+//! 各自持有独立的 cookie jar、凭据与一码通 token。切换账号时把活动句柄里的客户端换出停放，
+//! 再换入目标账号的客户端。
+//!
+//! 按学号隔离的存储沿用既有结构：Cookie 写 auth_cookie_v2、密码写密钥环、缓存键以学号为前缀；
+//! 注册表本身只在 kv_store `account.<学号>` 记录账号资料（[`AccountEntry`]）。
+//! 全局 Cookie 快照文件只由活动账号读写，停放账号不会覆盖它。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::AppState;
+use crate::application::ApplicationError;
+use crate::http_client::HbutClient;
+use crate::{db, UserInfo, DB_FILENAME};
+
+const ACCOUNT_KEY_PREFIX: &str = "account.";
+
+/// 注册表中的一个账号（kv_store 持久化）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountEntry {
+    pub user: UserInfo,
+    pub added_at: String,
+    /// 最近一次登录 / 切换为活动账号的时间
+    pub last_active_at: String,
+}
+
+/// 对外展示的账号状态。
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSummary {
+    pub student_id: String,
+    pub student_name: String,
+    /// 是否为当前活动账号
+    pub active: bool,
+    /// 客户端是否已在内存中（活动或已停放）；否则首次使用时从已保存的会话恢复
+    pub loaded: bool,
+    pub last_active_at: String,
+}
+
+/// 停放的非活动账号客户端，按学号索引。
+#[derive(Default)]
+pub struct AccountRegistry {
+    parked: Mutex<BTreeMap<String, Arc<RwLock<HbutClient>>>>,
+    /// 串行化切换 / 移除，避免两次切换交错换出同一个客户端
+    switching: tokio::sync::Mutex<()>,
+}
+
+impl AccountRegistry {
+    fn parked(&self, student_id: &str) -> Option<Arc<RwLock<HbutClient>>> {
+        self.parked.lock().ok()?.get(student_id).cloned()
+    }
+
+    fn park(&self, student_id: &str, handle: Arc<RwLock<HbutClient>>) {
+        if let Ok(mut parked) = self.parked.lock() {
+            parked.insert(student_id.to_string(), handle);
+        }
+    }
+
+    fn unpark(&self, student_id: &str) -> Option<Arc<RwLock<HbutClient>>> {
+        self.parked.lock().ok()?.remove(student_id)
+    }
+
+    fn parked_ids(&self) -> Vec<String> {
+        self.parked
+            .lock()
+            .map(|parked| parked.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl AppState {
+    /// 当前活动账号学号（未登录时为 `None`）。
+    pub async fn active_student_id(&self) -> Option<String> {
+        let client = self.client.read().await;
+        client.user_info.as_ref().map(|u| u.student_id.clone())
+    }
+
+    /// 解析目标账号的客户端句柄：未指定或指定活动账号时返回 [`AppState::client`]，
+    /// 否则返回停放的客户端（首次使用时从该学号已保存的会话恢复）。
+    pub async fn client_for(
+        &self,
+        student_id: Option<&str>,
+    ) -> Result<Arc<RwLock<HbutClient>>, ApplicationError> {
+        let Some(sid) = student_id.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(self.client.clone());
+        };
+        if self.active_student_id().await.as_deref() == Some(sid) {
+            return Ok(self.client.clone());
+        }
+        if let Some(handle) = self.accounts.parked(sid) {
+            return Ok(handle);
+        }
+        let entry = load_account(DB_FILENAME, sid)
+            .ok_or_else(|| ApplicationError::validation(format!("未添加账号 {sid}")))?;
+        let client = HbutClient::from_stored_account(entry.user).ok_or_else(|| {
+            ApplicationError::session_expired(format!("账号 {sid} 没有已保存的会话，请重新登录"))
+        })?;
+        let handle = Arc::new(RwLock::new(client));
+        self.accounts.park(sid, handle.clone());
+        Ok(handle)
+    }
+
+    /// 所有可用账号的客户端句柄（活动账号在前），供后台任务逐账号执行。
+    ///
+    /// 注册表中尚未载入内存的账号会按已保存的会话恢复；没有已保存会话的账号跳过。
+    pub async fn account_handles(&self) -> Vec<Arc<RwLock<HbutClient>>> {
+        let active = self.active_student_id().await;
+        let mut handles = Vec::new();
+        if active.is_some() {
+            handles.push(self.client.clone());
+        }
+        for entry in list_accounts(DB_FILENAME) {
+            let sid = entry.user.student_id.as_str();
+            if active.as_deref() == Some(sid) {
+                continue;
+            }
+            if let Ok(handle) = self.client_for(Some(sid)).await {
+                handles.push(handle);
+            }
+        }
+        handles
+    }
+
+    /// 切换活动账号；`None` 表示停放当前账号并换入空白客户端，用于登录新账号。
+    ///
+    /// 返回新的活动账号信息（`None` 时返回 `None`）。
+    pub async fn switch_account(
+        &self,
+        student_id: Option<&str>,
+    ) -> Result<Option<UserInfo>, ApplicationError> {
+        let _switching = self.accounts.switching.lock().await;
+        let target = student_id.map(str::trim).filter(|s| !s.is_empty());
+        let active = self.active_student_id().await;
+        if target.is_some() && target == active.as_deref() {
+            let client = self.client.read().await;
+            return Ok(client.user_info.clone());
+        }
+
+        let mut incoming = match target {
+            // 克隆共享同一 cookie jar：仍持有旧停放句柄的在途请求继续作用于同一账号
+            Some(sid) => {
+                let handle = self.client_for(Some(sid)).await?;
+                let client = handle.read().await;
+                client.clone()
+            }
+            None => HbutClient::without_snapshot(),
+        };
+        let mut shared = self.client.write().await;
+        incoming.inherit_runtime_config(&shared);
+        incoming.set_cookie_file_snapshot(true);
+        let mut outgoing = std::mem::replace(&mut *shared, incoming);
+        if let Some(sid) = target {
+            self.accounts.unpark(sid);
+        }
+        // 全局快照文件随活动账号切换，冷启动回退时与活动账号一致
+        shared.save_cookie_snapshot_to_file();
+        let user = shared.user_info.clone();
+        drop(shared);
+
+        if let Some(sid) = active {
+            outgoing.set_cookie_file_snapshot(false);
+            outgoing.persist_session_cookies(&sid);
+            self.accounts.park(&sid, Arc::new(RwLock::new(outgoing)));
+        }
+        if let Some(user) = user.as_ref() {
+            remember_account(DB_FILENAME, user).map_err(ApplicationError::storage)?;
+        }
+        Ok(user)
+    }
+
+    /// 移除非活动账号：丢弃停放的客户端，并清理该学号的注册信息、Cookie、会话行、个人缓存与密钥环条目。
+    pub async fn remove_account(&self, student_id: &str) -> Result<(), ApplicationError> {
+        let _switching = self.accounts.switching.lock().await;
+        let sid = student_id.trim();
+        if sid.is_empty() {
+            return Err(ApplicationError::validation("学号不能为空"));
+        }
+        if self.active_student_id().await.as_deref() == Some(sid) {
+            return Err(ApplicationError::validation(
+                "不能移除当前活动账号，请先切换到其他账号",
+            ));
+        }
+        self.accounts.unpark(sid);
+        forget_account(DB_FILENAME, sid).map_err(ApplicationError::storage)?;
+        db::clear_auth_cookies(DB_FILENAME, Some(sid))
+            .map_err(|e| ApplicationError::storage(e.to_string()))?;
+        db::delete_user_session(DB_FILENAME, sid)
+            .map_err(|e| ApplicationError::storage(e.to_string()))?;
+        db::invalidate_caches(DB_FILENAME, db::CacheDependency::Account, Some(sid))
+            .map_err(|e| ApplicationError::storage(e.to_string()))?;
+        crate::credential_store::delete_password(sid);
+        crate::credential_store::delete_remembered_credential(&format!("hbut:{sid}"));
+        Ok(())
+    }
+
+    /// 注册表中的账号及其载入状态（活动账号在前，其余按最近活动时间倒序）。
+    pub async fn account_summaries(&self) -> Vec<AccountSummary> {
+        let active = self.active_student_id().await;
+        let parked = self.accounts.parked_ids();
+        let mut summaries = list_accounts(DB_FILENAME)
+            .into_iter()
+            .map(|entry| {
+                let is_active = active.as_deref() == Some(entry.user.student_id.as_str());
+                AccountSummary {
+                    loaded: is_active || parked.contains(&entry.user.student_id),
+                    active: is_active,
+                    student_id: entry.user.student_id,
+                    student_name: entry.user.student_name,
+                    last_active_at: entry.last_active_at,
+                }
+            })
+            .collect::<Vec<_>>();
+        summaries.sort_by_key(|summary| !summary.active);
+        summaries
+    }
+}
+
+fn account_key(student_id: &str) -> String {
+    format!("{ACCOUNT_KEY_PREFIX}{}", student_id.trim())
+}
+
+/// 登录 / 恢复会话成功后登记账号，并刷新最近活动时间。
+pub fn remember_account<P: AsRef<Path>>(path: P, user: &UserInfo) -> Result<(), String> {
+    let sid = user.student_id.trim();
+    if sid.is_empty() {
+        return Ok(());
+    }
+    let now = chrono::Local::now().to_rfc3339();
+    let added_at = load_account(&path, sid)
+        .map(|entry| entry.added_at)
+        .unwrap_or_else(|| now.clone());
+    let entry = AccountEntry {
+        user: user.clone(),
+        added_at,
+        last_active_at: now,
+    };
+    let value = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    db::set_kv(path, &account_key(sid), &value).map_err(|e| e.to_string())
+}
+
+/// 读取单个账号的登记信息。
+pub fn load_account<P: AsRef<Path>>(path: P, student_id: &str) -> Option<AccountEntry> {
+    db::get_kv(path, &account_key(student_id))
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+/// 注册表中的全部账号，按最近活动时间倒序。
+pub fn list_accounts<P: AsRef<Path>>(path: P) -> Vec<AccountEntry> {
+    let mut entries = db::list_kv_by_prefix(path, ACCOUNT_KEY_PREFIX)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(_, raw)| serde_json::from_str::<AccountEntry>(&raw).ok())
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.last_active_at.cmp(&a.last_active_at));
+    entries
+}
+
+/// 从注册表删除账号（不涉及 Cookie / 密钥环，见 [`AppState::remove_account`]）。
+pub fn forget_account<P: AsRef<Path>>(path: P, student_id: &str) -> Result<(), String> {
+    db::delete_kv(path, &account_key(student_id))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_accounts_{label}_{nanos}.db"))
+    }
+
+    fn user(student_id: &str, name: &str) -> UserInfo {
+        UserInfo {
+            student_id: student_id.to_string(),
+            student_name: name.to_string(),
+            college: None,
+            major: None,
+            class_name: None,
+            grade: None,
+        }
+    }
+
+    #[test]
+    fn registry_keeps_added_at_and_orders_by_last_activity() {
+        let path = temp_db_path("registry");
+        init_db(&path).unwrap();
+
+        remember_account(&path, &user("2021001", "甲")).unwrap();
+        let first_added = load_account(&path, "2021001").unwrap().added_at;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        remember_account(&path, &user("2021002", "乙")).unwrap();
+        assert_eq!(
+            list_accounts(&path)
+                .iter()
+                .map(|e| e.user.student_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2021002", "2021001"]
+        );
+
+        // 再次登录只刷新最近活动时间与资料，保留首次登记时间
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        remember_account(&path, &user("2021001", "甲（改名）")).unwrap();
+        let entry = load_account(&path, "2021001").unwrap();
+        assert_eq!(entry.added_at, first_added);
+        assert_eq!(entry.user.student_name, "甲（改名）");
+        assert_eq!(list_accounts(&path)[0].user.student_id, "2021001");
+
+        forget_account(&path, "2021002").unwrap();
+        assert!(load_account(&path, "2021002").is_none());
+        assert_eq!(list_accounts(&path).len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn blank_student_id_is_not_registered() {
+        let path = temp_db_path("blank");
+        init_db(&path).unwrap();
+        remember_account(&path, &user("  ", "无名")).unwrap();
+        assert!(list_accounts(&path).is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
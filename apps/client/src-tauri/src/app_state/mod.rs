@@ -2,6 +2,11 @@
 //!
 //! `HbutClient` 使用 `tokio::sync::RwLock`：只读路径（cookie 快照、缓存读取）用 `read()`，
 //! 登录/同步等会改变会话状态的路径用 `write()`，避免长同步饿死短操作。
+//!
+//! 多账号：`client` 始终是活动账号；其余账号停放在 [`AccountRegistry`]，
+//! 按学号解析 / 切换见 [`accounts`]。
+
+pub mod accounts;
 
 use std::sync::Arc;
 
@@ -9,15 +14,19 @@ use tokio::sync::RwLock;
 
 use crate::http_client::HbutClient;
 
-/// 全局共享的教务 HTTP 客户端实例。
+pub use accounts::{AccountEntry, AccountRegistry, AccountSummary};
+
+/// 全局共享的教务 HTTP 客户端实例（活动账号）与多账号注册表。
 pub struct AppState {
     pub client: Arc<RwLock<HbutClient>>,
+    pub accounts: AccountRegistry,
 }
 
 impl AppState {
     pub fn new(client: HbutClient) -> Self {
         Self {
             client: Arc::new(RwLock::new(client)),
+            accounts: AccountRegistry::default(),
         }
     }
 }
@@ -4,7 +4,7 @@
 //! - 网络成功：返回 `attach_sync_time(offline=false)` 的结果；缓存写失败仅告警，
 //!   不拖垮成功网络结果（#578 缓存失败可降级返回网络结果）。
 //! - 网络失败：命中缓存则返回 `attach_sync_time(offline=true)` 的旧快照；
-//!   未命中缓存则返回 [`ApplicationError::network`]。
+//!   未命中缓存则返回 [`ApplicationError::upstream`]（按上游文案归类，默认网络错误）。
 //!
 //! 所有只读路径通过 [`ApplicationContext::client_snapshot`] 短时持有读锁，
 //! 网络 await 期间不持有任何全局锁，避免阻塞登录等写型业务。
@@ -17,6 +17,8 @@ use serde_json::{json, Value};
 
 use super::{ApplicationContext, ApplicationError};
 use crate::http_client::HbutClient;
+use crate::modules::library;
+use crate::runtime_events::SyncSpan;
 use crate::{attach_sync_time, db};
 
 #[derive(Clone)]
@@ -29,12 +31,25 @@ impl AcademicReadService {
         Self { context }
     }
 
+    /// 考试安排同步；开始与结束各发布一条 `exams` 同步事件。
     pub async fn fetch_exams(&self, semester: Option<String>) -> Result<Value, ApplicationError> {
         let client = self.context.client_snapshot().await;
         let uid = client
             .user_info
             .as_ref()
             .map(|user| user.student_id.clone());
+        let span = SyncSpan::start("exams", uid.as_deref());
+        let result = self.fetch_and_cache_exams(client, uid, semester).await;
+        span.finish(result.as_ref());
+        result
+    }
+
+    async fn fetch_and_cache_exams(
+        &self,
+        client: HbutClient,
+        uid: Option<String>,
+        semester: Option<String>,
+    ) -> Result<Value, ApplicationError> {
         let semester_key = semester.clone().unwrap_or_else(|| "current".to_string());
         let cache_key = uid.as_ref().map(|value| format!("{value}:{semester_key}"));
 
@@ -65,7 +80,7 @@ impl AcademicReadService {
                         return Ok(attach_sync_time(cached, &sync_time, true));
                     }
                 }
-                Err(ApplicationError::network(error.to_string()))
+                Err(ApplicationError::upstream(error.to_string()))
             }
         }
     }
@@ -77,7 +92,7 @@ impl AcademicReadService {
     ) -> Result<Value, ApplicationError> {
         let client = self.context.client_snapshot().await;
         if !client.is_logged_in && client.user_info.is_none() {
-            return Err(ApplicationError::unauthorized("请先登录后再查询排名"));
+            return Err(ApplicationError::session_expired("请先登录后再查询排名"));
         }
         let sid = student_id.or_else(|| {
             client
@@ -113,7 +128,7 @@ impl AcademicReadService {
                         return Ok(attach_sync_time(cached, &sync_time, true));
                     }
                 }
-                Err(ApplicationError::network(error.to_string()))
+                Err(ApplicationError::upstream(error.to_string()))
             }
         }
     }
@@ -148,7 +163,7 @@ impl AcademicReadService {
                         return Ok(attach_sync_time(cached, &sync_time, true));
                     }
                 }
-                Err(ApplicationError::network(error.to_string()))
+                Err(ApplicationError::upstream(error.to_string()))
             }
         }
     }
@@ -420,7 +435,7 @@ impl AcademicReadService {
                 {
                     return Ok(attach_sync_time(cached, &sync_time, true));
                 }
-                Err(ApplicationError::network(error.to_string()))
+                Err(ApplicationError::upstream(error.to_string()))
             }
         }
     }
@@ -512,6 +527,97 @@ impl AcademicReadService {
         )
         .await
     }
+
+    /// 图书馆当前借阅（按学号缓存于 library_account_cache，载荷已归一化）。
+    pub async fn fetch_library_loans(&self) -> Result<Value, ApplicationError> {
+        let (mut client, uid) = self.client_with_uid().await;
+        fetch_with_cache(
+            &self.context,
+            &mut client,
+            library::ACCOUNT_CACHE_TABLE,
+            uid.map(|u| library::account_cache_key(&u, library::SECTION_LOANS)),
+            |client| async move {
+                client
+                    .fetch_library_current_loans()
+                    .await
+                    .map(|raw| library::loans_payload(&raw))
+                    .map_err(|e| e.to_string())
+            },
+        )
+        .await
+    }
+
+    /// 图书馆借阅历史（按学号 + 页码缓存）。
+    pub async fn fetch_library_loan_history(
+        &self,
+        page: Option<i64>,
+        rows: Option<i64>,
+    ) -> Result<Value, ApplicationError> {
+        let (mut client, uid) = self.client_with_uid().await;
+        let page = page.unwrap_or(1).max(1);
+        let rows = rows.unwrap_or(20).clamp(1, 100);
+        fetch_with_cache(
+            &self.context,
+            &mut client,
+            library::ACCOUNT_CACHE_TABLE,
+            uid.map(|u| library::history_cache_key(&u, page, rows)),
+            |client| async move {
+                client
+                    .fetch_library_loan_history(page, rows)
+                    .await
+                    .map(|raw| library::history_payload(&raw, page, rows))
+                    .map_err(|e| e.to_string())
+            },
+        )
+        .await
+    }
+
+    /// 图书馆预约记录。
+    pub async fn fetch_library_holds(&self) -> Result<Value, ApplicationError> {
+        let (mut client, uid) = self.client_with_uid().await;
+        fetch_with_cache(
+            &self.context,
+            &mut client,
+            library::ACCOUNT_CACHE_TABLE,
+            uid.map(|u| library::account_cache_key(&u, library::SECTION_HOLDS)),
+            |client| async move {
+                client
+                    .fetch_library_holds()
+                    .await
+                    .map(|raw| library::holds_payload(&raw))
+                    .map_err(|e| e.to_string())
+            },
+        )
+        .await
+    }
+
+    /// 图书馆欠款记录。
+    pub async fn fetch_library_fines(&self) -> Result<Value, ApplicationError> {
+        let (mut client, uid) = self.client_with_uid().await;
+        fetch_with_cache(
+            &self.context,
+            &mut client,
+            library::ACCOUNT_CACHE_TABLE,
+            uid.map(|u| library::account_cache_key(&u, library::SECTION_FINES)),
+            |client| async move {
+                client
+                    .fetch_library_fines()
+                    .await
+                    .map(|raw| library::fines_payload(&raw))
+                    .map_err(|e| e.to_string())
+            },
+        )
+        .await
+    }
+
+    async fn client_with_uid(&self) -> (HbutClient, Option<String>) {
+        let client = self.context.client_snapshot().await;
+        let uid = client
+            .user_info
+            .as_ref()
+            .map(|user| user.student_id.clone());
+        (client, uid)
+    }
 }
 
 /// 构造公共缓存键（与历史 Tauri 实现一致：base64(prefix:payload)）。
@@ -572,7 +678,7 @@ where
                     return Ok(attach_sync_time(cached, &sync_time, true));
                 }
             }
-            Err(ApplicationError::network(error))
+            Err(ApplicationError::upstream(error))
         }
     }
 }
@@ -28,7 +28,8 @@ impl AuthService {
     /// - 密钥环双写：学号键 + 登录用户名键，供静默 SSO 续期
     /// - 记住密码（`hbut:学号` 键）
     ///
-    /// 返回 [`UserInfo`]；登录失败返回 [`ApplicationError::network`]（消息透传）。
+    /// 返回 [`UserInfo`]；登录失败经 [`ApplicationError::from_upstream`] 归类
+    /// （登录冷却、验证码、账号密码等），消息透传。
     pub async fn login(
         &self,
         username: &str,
@@ -48,7 +49,7 @@ impl AuthService {
                 &execution.unwrap_or_default(),
             )
             .await
-            .map_err(|e| ApplicationError::network(e.to_string()))?;
+            .map_err(|e| ApplicationError::from_upstream(e.as_ref()))?;
         client.set_chaoxing_login_mode(false);
 
         let user_info = client.user_info.clone().ok_or_else(|| {
@@ -92,6 +93,7 @@ impl AuthService {
         }
         let _ =
             credential_store::save_remembered_credential(&format!("hbut:{session_key}"), password);
+        self.remember_account(&user_info);
 
         Ok(user_info)
     }
@@ -107,7 +109,7 @@ impl AuthService {
         let user_info = client
             .restore_session(cookies)
             .await
-            .map_err(|e| ApplicationError::network(e.to_string()))?;
+            .map_err(|e| ApplicationError::from_upstream(e.as_ref()))?;
 
         let mut session_opt =
             match db::get_user_session(self.context.db_path(), &user_info.student_id) {
@@ -158,10 +160,20 @@ impl AuthService {
             }
             client.persist_session_cookies(&user_info.student_id);
         }
+        self.remember_account(&user_info);
 
         Ok(user_info)
     }
 
+    /// 登记到多账号注册表；失败只记录日志，不影响登录结果。
+    fn remember_account(&self, user_info: &UserInfo) {
+        if let Err(e) =
+            crate::app_state::accounts::remember_account(self.context.db_path(), user_info)
+        {
+            eprintln!("[application] 登记账号失败: {e}");
+        }
+    }
+
     /// 登出：仅清理内存会话并失效学习通 SSO 缓存；
     /// 保留密钥环中的「记住密码」与会话密码，供下次自动登录/表单回填。
     pub async fn logout(&self) -> Result<(), ApplicationError> {
@@ -179,7 +191,7 @@ impl AuthService {
         client
             .refresh_session()
             .await
-            .map_err(|e| ApplicationError::network(e.to_string()))
+            .map_err(|e| ApplicationError::from_upstream(e.as_ref()))
     }
 
     /// 从结构化 Cookie 快照恢复（仅写入 Cookie），随后拉取用户信息校验会话。
@@ -193,11 +205,11 @@ impl AuthService {
         let mut client = handle.write().await;
         client
             .restore_cookie_snapshot(code, auth, jwxt)
-            .map_err(|e| ApplicationError::network(e.to_string()))?;
+            .map_err(|e| ApplicationError::from_upstream(e.as_ref()))?;
         client
             .fetch_user_info()
             .await
-            .map_err(|e| ApplicationError::network(e.to_string()))
+            .map_err(|e| ApplicationError::from_upstream(e.as_ref()))
     }
 }
 
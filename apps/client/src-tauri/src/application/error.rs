@@ -1,54 +1,332 @@
+//! 应用层统一错误模型。
+//!
+//! [`ApplicationErrorKind`] 是 Tauri 命令与 HTTP Bridge 共用的错误分类：每个分类对应
+//! 稳定的机器可读 `code`（前端 / 第三方 Bridge 客户端据此决定重登、退避，而不是匹配中文文案），
+//! 以及是否可重试、建议等待时长与建议动作。`message` 仍为面向用户的原始中文提示。
+//!
+//! 上游（`http_client` / 各模块）错误多为 `Box<dyn Error>` 或字符串，经
+//! [`ApplicationError::from_upstream`] / [`ApplicationError::upstream`] 归类：
+//! 先识别传输层失败，再按已知文案特征归类，最后参考 [`HttpClientError`] 的 kind。
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::http_client::{HttpClientError, HttpClientErrorKind};
+use crate::modules::chaoxing_checkin::errors::CheckinErrorCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ApplicationErrorKind {
-    Unauthorized,
+    /// 未登录或上游会话失效，需要重新登录
+    SessionExpired,
+    /// 登录需要（或验证码校验失败）人工输入验证码
+    CaptchaRequired,
+    /// 上游系统维护 / 暂不可用
+    UpstreamMaintenance,
+    /// 登录冷却或上游限流
+    RateLimited,
+    /// 参数或输入不合法
     Validation,
+    /// 网络不可达、超时等传输层失败
     Network,
+    /// 本地数据库 / 缓存读写失败
     Storage,
     Internal,
 }
 
+impl ApplicationErrorKind {
+    /// 稳定的机器可读错误码（与 serde 表示一致）。
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::SessionExpired => "session_expired",
+            Self::CaptchaRequired => "captcha_required",
+            Self::UpstreamMaintenance => "upstream_maintenance",
+            Self::RateLimited => "rate_limited",
+            Self::Validation => "validation",
+            Self::Network => "network",
+            Self::Storage => "storage",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// 原样重试是否可能成功（会话 / 验证码类需要用户介入，不算可重试）。
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::UpstreamMaintenance | Self::RateLimited | Self::Network
+        )
+    }
+
+    /// 未给出具体等待时长时的建议退避秒数。
+    pub fn default_retry_after_secs(self) -> Option<u64> {
+        match self {
+            Self::RateLimited => Some(60),
+            Self::UpstreamMaintenance => Some(1800),
+            Self::Network => Some(30),
+            _ => None,
+        }
+    }
+
+    /// 建议客户端采取的动作。
+    pub fn action(self) -> Option<&'static str> {
+        match self {
+            Self::SessionExpired => Some("relogin"),
+            Self::CaptchaRequired => Some("solve_captcha"),
+            _ => None,
+        }
+    }
+
+    /// 按已知上游文案特征归类；无法识别时返回 `None`。
+    ///
+    /// 状态码与英文关键词只按完整短语匹配（如 `HTTP 503`），避免把 URL、数字或
+    /// 「captcha 图片过小」之类的内部失败误判为维护 / 需要验证码。
+    pub fn classify_message(message: &str) -> Option<Self> {
+        let lower = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+        if has(&["频率过高", "太频繁", "过于频繁", "too many requests"]) {
+            return Some(Self::RateLimited);
+        }
+        if has(&[
+            "验证码错误",
+            "需要验证码",
+            "请输入验证码",
+            "captcha required",
+            "invalid captcha",
+            "captcha is incorrect",
+        ]) {
+            return Some(Self::CaptchaRequired);
+        }
+        if has(&["用户名或密码", "账号或密码", "账号密码", "密码错误"]) {
+            return Some(Self::Validation);
+        }
+        if has(&[
+            "维护",
+            "暂不可用",
+            "暂时不可用",
+            "service unavailable",
+            "http 503",
+            "状态码 503",
+            "状态码异常: 503",
+            "status=503",
+            "status: 503",
+        ]) {
+            return Some(Self::UpstreamMaintenance);
+        }
+        if has(&[
+            "会话已过期",
+            "会话已失效",
+            "会话未就绪",
+            "登录已过期",
+            "请先登录",
+            "请重新登录",
+            "未登录",
+        ]) {
+            return Some(Self::SessionExpired);
+        }
+        None
+    }
+}
+
+impl From<CheckinErrorCode> for ApplicationErrorKind {
+    fn from(code: CheckinErrorCode) -> Self {
+        match code {
+            CheckinErrorCode::NetworkError => Self::Network,
+            CheckinErrorCode::SessionExpired => Self::SessionExpired,
+            CheckinErrorCode::BadRequest | CheckinErrorCode::AlreadySigned => Self::Validation,
+            CheckinErrorCode::ServerError => Self::UpstreamMaintenance,
+            CheckinErrorCode::RateLimited => Self::RateLimited,
+            CheckinErrorCode::PermissionDenied | CheckinErrorCode::Unknown => Self::Internal,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("{message}")]
 pub struct ApplicationError {
     pub kind: ApplicationErrorKind,
     pub message: String,
+    /// 上游明确给出的等待秒数（如登录冷却剩余时间）；为空时取分类默认值
+    pub retry_after_secs: Option<u64>,
 }
 
 impl ApplicationError {
-    pub fn unauthorized(message: impl Into<String>) -> Self {
+    pub fn new(kind: ApplicationErrorKind, message: impl Into<String>) -> Self {
         Self {
-            kind: ApplicationErrorKind::Unauthorized,
+            kind,
             message: message.into(),
+            retry_after_secs: None,
         }
     }
 
+    pub fn session_expired(message: impl Into<String>) -> Self {
+        Self::new(ApplicationErrorKind::SessionExpired, message)
+    }
+
     pub fn validation(message: impl Into<String>) -> Self {
-        Self {
-            kind: ApplicationErrorKind::Validation,
-            message: message.into(),
-        }
+        Self::new(ApplicationErrorKind::Validation, message)
     }
 
     pub fn network(message: impl Into<String>) -> Self {
-        Self {
-            kind: ApplicationErrorKind::Network,
-            message: message.into(),
-        }
+        Self::new(ApplicationErrorKind::Network, message)
     }
 
     pub fn storage(message: impl Into<String>) -> Self {
-        Self {
-            kind: ApplicationErrorKind::Storage,
-            message: message.into(),
-        }
+        Self::new(ApplicationErrorKind::Storage, message)
     }
 
     pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ApplicationErrorKind::Internal, message)
+    }
+
+    /// 上游返回的字符串错误：按文案归类，无法识别时视为网络失败（与历史映射一致）。
+    pub fn upstream(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let kind = ApplicationErrorKind::classify_message(&message)
+            .unwrap_or(ApplicationErrorKind::Network);
+        let retry_after_secs = if kind == ApplicationErrorKind::RateLimited {
+            parse_wait_secs(&message)
+        } else {
+            None
+        };
         Self {
-            kind: ApplicationErrorKind::Internal,
-            message: message.into(),
+            kind,
+            message,
+            retry_after_secs,
+        }
+    }
+
+    /// 上游 `Box<dyn Error>`：传输层失败归为网络错误，其余按文案归类；
+    /// 都未命中时，认证服务器拒绝视为参数错误，其它视为内部错误。
+    pub fn from_upstream(error: &(dyn std::error::Error + Send + Sync + 'static)) -> Self {
+        if crate::http_client::is_transport_error(error) {
+            return Self::network(error.to_string());
+        }
+        let message = error.to_string();
+        if ApplicationErrorKind::classify_message(&message).is_some() {
+            return Self::upstream(message);
+        }
+        match error
+            .downcast_ref::<HttpClientError>()
+            .map(HttpClientError::kind)
+        {
+            Some(HttpClientErrorKind::AuthFailed) => Self::validation(message),
+            _ => Self::internal(message),
+        }
+    }
+
+    /// 建议等待秒数：显式值优先，否则取分类默认值。
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        self.retry_after_secs
+            .or_else(|| self.kind.default_retry_after_secs())
+    }
+}
+
+/// 从「请N秒后再试」之类文案中取出秒数。
+fn parse_wait_secs(message: &str) -> Option<u64> {
+    let (head, _) = message.split_once('秒')?;
+    let digits: String = head
+        .chars()
+        .rev()
+        .take_while(char::is_ascii_digit)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upstream_messages_map_to_stable_codes() {
+        let cooldown = ApplicationError::upstream("登录频率过高，请42秒后再试");
+        assert_eq!(cooldown.kind.code(), "rate_limited");
+        assert_eq!(cooldown.retry_after_secs(), Some(42));
+        assert!(cooldown.kind.retryable());
+
+        let expired = ApplicationError::upstream("教务会话已过期，请重新登录");
+        assert_eq!(expired.kind, ApplicationErrorKind::SessionExpired);
+        assert_eq!(expired.kind.action(), Some("relogin"));
+        assert!(!expired.kind.retryable());
+
+        assert_eq!(
+            ApplicationError::upstream("验证码错误").kind,
+            ApplicationErrorKind::CaptchaRequired
+        );
+        assert_eq!(
+            ApplicationError::upstream("教务系统维护中").kind,
+            ApplicationErrorKind::UpstreamMaintenance
+        );
+        assert_eq!(
+            ApplicationError::upstream("教务通知 HTTP 503").kind,
+            ApplicationErrorKind::UpstreamMaintenance
+        );
+        // 数字或英文单词只作为完整短语识别：编号、内部失败不误判
+        assert_eq!(
+            ApplicationErrorKind::classify_message("课程 1503 已满"),
+            None
+        );
+        assert_eq!(
+            ApplicationErrorKind::classify_message("Captcha image is too small: 12 bytes"),
+            None
+        );
+        // 未识别的上游失败保持历史语义：网络错误，可退避重试
+        let unknown = ApplicationError::upstream("error sending request");
+        assert_eq!(unknown.kind, ApplicationErrorKind::Network);
+        assert_eq!(unknown.retry_after_secs(), Some(30));
+    }
+
+    #[test]
+    fn boxed_upstream_errors_are_classified() {
+        let transport = HttpClientError::new(HttpClientErrorKind::Transport, "请重新登录");
+        assert_eq!(
+            ApplicationError::from_upstream(&transport).kind,
+            ApplicationErrorKind::Network
+        );
+
+        let rejected = HttpClientError::auth_failed("账号已被锁定");
+        assert_eq!(
+            ApplicationError::from_upstream(&rejected).kind,
+            ApplicationErrorKind::Validation
+        );
+
+        let captcha = HttpClientError::auth_failed("验证码错误");
+        assert_eq!(
+            ApplicationError::from_upstream(&captcha).kind,
+            ApplicationErrorKind::CaptchaRequired
+        );
+
+        // 非传输层的未知失败不冒充网络错误，避免客户端无意义地退避重试
+        let boxed: Box<dyn std::error::Error + Send + Sync> = "登录失败，请检查账号密码".into();
+        assert_eq!(
+            ApplicationError::from_upstream(boxed.as_ref()).kind,
+            ApplicationErrorKind::Validation
+        );
+        let boxed: Box<dyn std::error::Error + Send + Sync> = "登录页结构异常".into();
+        assert_eq!(
+            ApplicationError::from_upstream(boxed.as_ref()).kind,
+            ApplicationErrorKind::Internal
+        );
+    }
+
+    #[test]
+    fn serde_representation_matches_code() {
+        for kind in [
+            ApplicationErrorKind::SessionExpired,
+            ApplicationErrorKind::CaptchaRequired,
+            ApplicationErrorKind::UpstreamMaintenance,
+            ApplicationErrorKind::RateLimited,
+            ApplicationErrorKind::Validation,
+            ApplicationErrorKind::Network,
+            ApplicationErrorKind::Storage,
+            ApplicationErrorKind::Internal,
+        ] {
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::json!(kind.code())
+            );
         }
     }
 }
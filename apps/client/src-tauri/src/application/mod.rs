@@ -8,7 +8,8 @@
 //!
 //! Service 语义：
 //! - 输入/输出：JSON payload（与历史 Tauri Command / HTTP 响应结构兼容）
-//! - 错误：[`ApplicationError`]（kind + message），传输层负责映射
+//! - 错误：[`ApplicationError`]（kind + message + 重试提示），kind 带稳定错误码；
+//!   传输层映射为 Bridge `error.code` / Tauri `CommandError`
 //! - 会话：写型业务收敛在 [`AuthService`]；只读业务用快照克隆
 //! - 缓存：网络优先 + 缓存降级（offline=true）；缓存写失败仅告警，不拖垮网络结果
 
@@ -2,7 +2,8 @@
 //!
 //! 统一语义（与历史 Tauri `sync_schedule` 完全一致）：
 //! - 目标学期解析：显式传参优先，其次 `resolve_schedule_context` 上下文，兜底默认学期
-//! - 网络成功：meta 补充 `semester` / `total_courses` / `query_time`，写 `schedule_cache`
+//! - 网络成功：meta 补充 `semester` / `total_courses` / `query_time`，写 `schedule_cache`；
+//!   未显式指定学期且当前学期较上次变化时，先失效该账号的学期相关缓存（考试、教室、校历）
 //! - 网络失败：无课表类错误固定返回「暂无可用课表」；显式指定学期时透传错误；
 //!   未显式指定时命中缓存则降级返回 `offline=true` 旧快照，否则透传错误
 //!
@@ -12,6 +13,8 @@
 use serde_json::{json, Value};
 
 use super::{ApplicationContext, ApplicationError};
+use crate::http_client::HbutClient;
+use crate::runtime_events::SyncSpan;
 use crate::{attach_sync_time, db};
 
 #[derive(Clone)]
@@ -24,13 +27,25 @@ impl ScheduleService {
         Self { context }
     }
 
-    /// 统一课表同步用例（见模块文档）。
+    /// 统一课表同步用例（见模块文档）；开始与结束各发布一条 `schedule` 同步事件。
     pub async fn sync_schedule(&self, semester: Option<String>) -> Result<Value, ApplicationError> {
         let client = self.context.client_snapshot().await;
         let uid = client
             .user_info
             .as_ref()
             .map(|user| user.student_id.clone());
+        let span = SyncSpan::start("schedule", uid.as_deref());
+        let result = self.fetch_and_cache_schedule(client, uid, semester).await;
+        span.finish(result.as_ref());
+        result
+    }
+
+    async fn fetch_and_cache_schedule(
+        &self,
+        client: HbutClient,
+        uid: Option<String>,
+        semester: Option<String>,
+    ) -> Result<Value, ApplicationError> {
         let requested_semester = semester
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty());
@@ -69,6 +84,22 @@ impl ScheduleService {
                     "offline": false
                 });
                 if let Some(uid) = uid.as_ref() {
+                    if !explicit_semester {
+                        match db::switch_current_semester(
+                            self.context.db_path(),
+                            uid,
+                            &semester_to_query,
+                        ) {
+                            Ok(Some(removed)) => crate::runtime_log::log_info(
+                                "cache",
+                                format!("当前学期切换为 {semester_to_query}，已失效 {removed} 条学期缓存"),
+                            ),
+                            Ok(None) => {}
+                            Err(error) => eprintln!(
+                                "[application] 学期切换检测失败 key={uid}: {error}（不影响课表结果）"
+                            ),
+                        }
+                    }
                     if let Err(error) =
                         db::save_cache(self.context.db_path(), "schedule_cache", uid, &payload)
                     {
@@ -86,7 +117,7 @@ impl ScheduleService {
                     return Err(ApplicationError::validation("暂无可用课表"));
                 }
                 if explicit_semester {
-                    return Err(ApplicationError::network(msg));
+                    return Err(ApplicationError::upstream(msg));
                 }
                 if let Some(uid) = uid.as_ref() {
                     if let Ok(Some((cached, sync_time))) =
@@ -95,7 +126,7 @@ impl ScheduleService {
                         return Ok(attach_sync_time(cached, &sync_time, true));
                     }
                 }
-                Err(ApplicationError::network(msg))
+                Err(ApplicationError::upstream(msg))
             }
         }
     }
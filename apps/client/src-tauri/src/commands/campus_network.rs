@@ -1,9 +1,16 @@
-//! 校园网认证 Tauri commands。
+//! 校园网认证与保活 Tauri commands。
 
+use tauri::{AppHandle, State};
+
+use crate::application::ApplicationError;
 use crate::modules::campus_network::{
-    login_campus_network, logout_campus_network, probe_network, CampusCarrier,
-    CampusNetworkLoginResult, CampusNetworkProbeResult,
+    drop_campus_network_session, login_campus_network, logout_campus_network, probe_network,
+    query_campus_network_account, CampusCarrier, CampusKeepAlive, CampusNetworkAccountResult,
+    CampusNetworkDropResult, CampusNetworkLoginResult, CampusNetworkProbeResult, KeepAliveConfig,
+    KeepAliveStatus, DEFAULT_KEEPALIVE_INTERVAL_SECS,
 };
+use crate::transport::tauri::common::CommandError;
+use crate::DB_FILENAME;
 
 /// 探测校园网认证状态。
 #[tauri::command]
@@ -48,3 +55,78 @@ pub async fn campus_network_logout(
     )
     .await
 }
+
+/// 查询 Srun 账号在线设备、已用流量/时长与余额。
+#[tauri::command]
+pub async fn campus_network_account_info(
+    student_id: String,
+    carrier: CampusCarrier,
+    gateway_override: Option<String>,
+    client_ip: Option<String>,
+) -> Result<CampusNetworkAccountResult, CommandError> {
+    query_campus_network_account(
+        &student_id,
+        carrier,
+        gateway_override.as_deref(),
+        client_ip.as_deref(),
+    )
+    .await
+    .map_err(|e| ApplicationError::upstream(e).into())
+}
+
+/// 强制下线指定 IP 的在线设备。
+#[tauri::command]
+pub async fn campus_network_drop_session(
+    student_id: String,
+    carrier: CampusCarrier,
+    gateway_override: Option<String>,
+    ip: String,
+) -> Result<CampusNetworkDropResult, CommandError> {
+    drop_campus_network_session(&student_id, carrier, gateway_override.as_deref(), &ip)
+        .await
+        .map_err(|e| ApplicationError::upstream(e).into())
+}
+
+/// 启用校园网保活（桌面端）：密码取自密钥环 `campus:<学号>`，需先登录并记住密码。
+///
+/// 失败均为平台 / 配置 / 未记住密码等需用户调整的问题，归为 `validation`。
+#[tauri::command]
+pub async fn campus_network_keepalive_start(
+    app: AppHandle,
+    keepalive: State<'_, CampusKeepAlive>,
+    student_id: String,
+    carrier: CampusCarrier,
+    gateway_override: Option<String>,
+    interval_secs: Option<u64>,
+) -> Result<KeepAliveStatus, CommandError> {
+    let config = KeepAliveConfig {
+        student_id,
+        carrier,
+        gateway_override,
+        interval_secs: interval_secs.unwrap_or(DEFAULT_KEEPALIVE_INTERVAL_SECS),
+        enabled: true,
+    };
+    keepalive
+        .start(app, DB_FILENAME, config)
+        .await
+        .map_err(|e| ApplicationError::validation(e).into())
+}
+
+/// 停止校园网保活。
+#[tauri::command]
+pub async fn campus_network_keepalive_stop(
+    keepalive: State<'_, CampusKeepAlive>,
+) -> Result<KeepAliveStatus, CommandError> {
+    keepalive
+        .stop(DB_FILENAME)
+        .await
+        .map_err(|e| ApplicationError::storage(e).into())
+}
+
+/// 校园网保活状态与最近事件。
+#[tauri::command]
+pub async fn campus_network_keepalive_status(
+    keepalive: State<'_, CampusKeepAlive>,
+) -> Result<KeepAliveStatus, CommandError> {
+    Ok(keepalive.status(DB_FILENAME).await)
+}
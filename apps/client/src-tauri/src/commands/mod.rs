@@ -3,7 +3,11 @@
 pub mod campus_network;
 pub mod credentials;
 
-pub use campus_network::{campus_network_login, campus_network_logout, campus_network_probe};
+pub use campus_network::{
+    campus_network_account_info, campus_network_drop_session, campus_network_keepalive_start,
+    campus_network_keepalive_status, campus_network_keepalive_stop, campus_network_login,
+    campus_network_logout, campus_network_probe,
+};
 pub use credentials::{
     delete_remembered_credential, load_remembered_credential, load_session_password,
     save_remembered_credential,
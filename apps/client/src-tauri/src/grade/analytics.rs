@@ -0,0 +1,594 @@
+//! 成绩统计：学期 / 累计加权 GPA、加权均分、学分统计、按课程性质拆分与 what-if 推演。
+//!
+//! 纯领域计算（无网络、无存储依赖），由 [`GradeService`](crate::grade::service::GradeService)
+//! 暴露给 Tauri Command 与 HTTP Bridge `/sync_grades`。
+//!
+//! 计入规则（均可经 [`GpaRules`] 配置）：
+//! - 待录入（Pending）：不计入；可经 what-if 假设分数推演；
+//! - 缓考（Deferred）：默认按待录入处理（可推演），也可配置为直接排除；
+//! - 免修（Exempt）：不计入 GPA / 均分，默认计入已获学分；
+//! - 缺考（Absent）：默认按 0 分、0 绩点计入；
+//! - 补考/重修（Retake）：累计口径下同一课程多次修读按 [`RetakePolicy`] 取舍；
+//!   学期口径保留该学期内的每一次修读。
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::grade::diff::grade_identity_key;
+use crate::grade::domain::{GradeOutcome, GradeRecord};
+
+/// 同一课程多次修读时累计口径的取舍方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetakePolicy {
+    /// 取绩点（无绩点时取分数）最高的一次
+    #[default]
+    Highest,
+    /// 取最近学期的一次
+    Latest,
+    /// 每次修读都计入
+    All,
+}
+
+/// 统计规则。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpaRules {
+    pub retake: RetakePolicy,
+    /// 免修课程是否计入已修/已获学分（始终不计入 GPA 与均分）
+    pub exempt_counts_credit: bool,
+    /// 缺考是否按 0 分计入（否则忽略该记录）
+    pub absent_as_zero: bool,
+    /// 缓考是否按待录入处理（可参与 what-if）；否则直接排除
+    pub deferred_as_pending: bool,
+    /// 是否统计选修课
+    pub include_electives: bool,
+    /// 及格线（教务未给出获得学分时用于判定是否取得学分）
+    pub pass_score: f64,
+}
+
+impl Default for GpaRules {
+    fn default() -> Self {
+        Self {
+            retake: RetakePolicy::Highest,
+            exempt_counts_credit: true,
+            absent_as_zero: true,
+            deferred_as_pending: true,
+            include_electives: true,
+            pass_score: 60.0,
+        }
+    }
+}
+
+/// what-if 假设分数：`key` 为 [`grade_identity_key`]（见 [`GradeAnalytics::pending`]）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HypotheticalScore {
+    pub key: String,
+    pub score: f64,
+}
+
+/// 统计请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsRequest {
+    pub rules: GpaRules,
+    pub what_if: Vec<HypotheticalScore>,
+}
+
+/// 一组成绩的汇总指标。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpaSummary {
+    /// 学分加权平均绩点（无可计入课程时为 `None`）
+    pub gpa: Option<f64>,
+    /// 学分加权平均分
+    pub weighted_average: Option<f64>,
+    /// 已修学分（有结果的课程）
+    pub attempted_credits: f64,
+    /// 已获学分
+    pub earned_credits: f64,
+    /// 计入 GPA 的学分
+    pub gpa_credits: f64,
+    pub course_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermSummary {
+    pub term: String,
+    #[serde(flatten)]
+    pub summary: GpaSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NatureSummary {
+    /// 课程性质（必修 / 选修 …；缺失为「未分类」）
+    pub nature: String,
+    #[serde(flatten)]
+    pub summary: GpaSummary,
+}
+
+/// 可参与 what-if 推演的课程（待录入 / 缓考）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCourse {
+    pub key: String,
+    pub term: String,
+    pub course_name: String,
+    pub course_nature: String,
+    pub credit: f64,
+    pub outcome: GradeOutcome,
+}
+
+/// 完整统计结果。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradeAnalytics {
+    pub rules: GpaRules,
+    pub cumulative: GpaSummary,
+    /// 按学期升序
+    pub terms: Vec<TermSummary>,
+    pub by_nature: Vec<NatureSummary>,
+    pub pending: Vec<PendingCourse>,
+    /// 代入 what-if 假设分数后的累计指标（未提供假设分数时为 `None`）
+    pub projection: Option<GpaSummary>,
+}
+
+/// 单条记录按规则归类后的计入方式。
+#[derive(Debug, Clone, Copy)]
+enum Counted {
+    Graded {
+        score: Option<f64>,
+        grade_point: Option<f64>,
+        passed: bool,
+    },
+    /// 只计学分（免修）
+    CreditOnly,
+    Pending,
+    Excluded,
+}
+
+#[derive(Debug, Clone)]
+struct Attempt<'a> {
+    record: &'a GradeRecord,
+    course_key: String,
+    credit: f64,
+    counted: Counted,
+}
+
+/// 按规则计算统计结果。
+pub fn analyze_grades(grades: &[GradeRecord], request: &AnalyticsRequest) -> GradeAnalytics {
+    let rules = &request.rules;
+    let attempts = classify_all(grades, rules, &HashMap::new());
+
+    let cumulative = summarize(&select_cumulative(&attempts, rules.retake), rules);
+
+    let mut by_term: BTreeMap<String, Vec<&Attempt>> = BTreeMap::new();
+    for attempt in &attempts {
+        let term = attempt.record.term.trim();
+        if !term.is_empty() {
+            by_term.entry(term.to_string()).or_default().push(attempt);
+        }
+    }
+    let terms = by_term
+        .into_iter()
+        .map(|(term, items)| TermSummary {
+            summary: summarize(&items, rules),
+            term,
+        })
+        .filter(|item| item.summary.course_count > 0)
+        .collect();
+
+    let mut by_nature: BTreeMap<String, Vec<&Attempt>> = BTreeMap::new();
+    for attempt in select_cumulative(&attempts, rules.retake) {
+        by_nature
+            .entry(nature_label(attempt.record))
+            .or_default()
+            .push(attempt);
+    }
+    let by_nature = by_nature
+        .into_iter()
+        .map(|(nature, items)| NatureSummary {
+            summary: summarize(&items, rules),
+            nature,
+        })
+        .collect();
+
+    let pending = attempts
+        .iter()
+        .filter(|attempt| matches!(attempt.counted, Counted::Pending))
+        .map(|attempt| PendingCourse {
+            key: grade_identity_key(attempt.record),
+            term: attempt.record.term.trim().to_string(),
+            course_name: attempt.record.course_name.trim().to_string(),
+            course_nature: nature_label(attempt.record),
+            credit: attempt.credit,
+            outcome: attempt.record.outcome(),
+        })
+        .collect();
+
+    let projection = if request.what_if.is_empty() {
+        None
+    } else {
+        let what_if = request
+            .what_if
+            .iter()
+            .map(|item| (item.key.trim().to_string(), item.score))
+            .collect::<HashMap<_, _>>();
+        let projected = classify_all(grades, rules, &what_if);
+        Some(summarize(
+            &select_cumulative(&projected, rules.retake),
+            rules,
+        ))
+    };
+
+    GradeAnalytics {
+        rules: rules.clone(),
+        cumulative,
+        terms,
+        by_nature,
+        pending,
+        projection,
+    }
+}
+
+fn classify_all<'a>(
+    grades: &'a [GradeRecord],
+    rules: &GpaRules,
+    what_if: &HashMap<String, f64>,
+) -> Vec<Attempt<'a>> {
+    grades
+        .iter()
+        .map(|record| Attempt {
+            record,
+            course_key: course_key(record),
+            credit: parse_number(&record.course_credit).unwrap_or(0.0),
+            counted: classify(record, rules, what_if),
+        })
+        .filter(|attempt| !matches!(attempt.counted, Counted::Excluded))
+        .collect()
+}
+
+fn classify(record: &GradeRecord, rules: &GpaRules, what_if: &HashMap<String, f64>) -> Counted {
+    if !rules.include_electives && record.course_nature.contains("选修") {
+        return Counted::Excluded;
+    }
+    match record.outcome() {
+        GradeOutcome::Pending => hypothetical(record, rules, what_if),
+        GradeOutcome::Deferred if rules.deferred_as_pending => hypothetical(record, rules, what_if),
+        GradeOutcome::Deferred => Counted::Excluded,
+        GradeOutcome::Exempt if rules.exempt_counts_credit => Counted::CreditOnly,
+        GradeOutcome::Exempt => Counted::Excluded,
+        GradeOutcome::Absent if rules.absent_as_zero => Counted::Graded {
+            score: Some(0.0),
+            grade_point: Some(0.0),
+            passed: false,
+        },
+        GradeOutcome::Absent => Counted::Excluded,
+        GradeOutcome::Normal | GradeOutcome::Retake => {
+            let score = record.numeric_score();
+            let passed = match parse_number(&record.earned_credit) {
+                Some(earned) => earned > 0.0,
+                None => score.map(|s| s >= rules.pass_score).unwrap_or(false),
+            };
+            Counted::Graded {
+                score,
+                grade_point: record.grade_point(),
+                passed,
+            }
+        }
+    }
+}
+
+/// 待录入课程：有假设分数时按估算绩点计入，否则保持待录入。
+fn hypothetical(record: &GradeRecord, rules: &GpaRules, what_if: &HashMap<String, f64>) -> Counted {
+    let Some(score) = what_if.get(&grade_identity_key(record)).copied() else {
+        return Counted::Pending;
+    };
+    let mut projected = record.clone();
+    projected.final_score = score.to_string();
+    projected.xfjd.clear();
+    Counted::Graded {
+        score: Some(score),
+        grade_point: projected.estimated_grade_point(),
+        passed: score >= rules.pass_score,
+    }
+}
+
+/// 累计口径：同一课程多次修读按策略取舍，每门课程只保留一次修读。
+fn select_cumulative<'a, 'b>(
+    attempts: &'b [Attempt<'a>],
+    policy: RetakePolicy,
+) -> Vec<&'b Attempt<'a>> {
+    if policy == RetakePolicy::All {
+        return attempts.iter().collect();
+    }
+    let mut chosen: BTreeMap<&str, &Attempt> = BTreeMap::new();
+    for attempt in attempts {
+        let slot = chosen.entry(attempt.course_key.as_str()).or_insert(attempt);
+        if prefers(attempt, slot, policy) {
+            *slot = attempt;
+        }
+    }
+    chosen.into_values().collect()
+}
+
+/// 已出结果（计分或只计学分）的修读总是优先于待录入的修读，其余按策略比较。
+fn prefers(candidate: &Attempt, current: &Attempt, policy: RetakePolicy) -> bool {
+    let settled = |attempt: &Attempt| {
+        matches!(
+            attempt.counted,
+            Counted::Graded { .. } | Counted::CreditOnly
+        )
+    };
+    if settled(candidate) != settled(current) {
+        return settled(candidate);
+    }
+    let newer = candidate.record.term.trim() > current.record.term.trim();
+    match policy {
+        RetakePolicy::Latest | RetakePolicy::All => newer,
+        RetakePolicy::Highest => {
+            let (a, b) = (rank(candidate), rank(current));
+            a > b || (a == b && newer)
+        }
+    }
+}
+
+/// Highest 策略的比较值：优先绩点，其次分数。
+fn rank(attempt: &Attempt) -> (f64, f64) {
+    match attempt.counted {
+        Counted::Graded {
+            score, grade_point, ..
+        } => (grade_point.unwrap_or(-1.0), score.unwrap_or(-1.0)),
+        _ => (-1.0, -1.0),
+    }
+}
+
+fn summarize(attempts: &[&Attempt], rules: &GpaRules) -> GpaSummary {
+    let mut summary = GpaSummary::default();
+    let (mut point_sum, mut score_sum, mut score_credits) = (0.0, 0.0, 0.0);
+    for attempt in attempts {
+        let credit = attempt.credit;
+        match attempt.counted {
+            Counted::Graded {
+                score,
+                grade_point,
+                passed,
+            } => {
+                summary.course_count += 1;
+                summary.attempted_credits += credit;
+                if passed {
+                    summary.earned_credits += credit;
+                }
+                if let Some(point) = grade_point {
+                    point_sum += point * credit;
+                    summary.gpa_credits += credit;
+                }
+                if let Some(score) = score {
+                    score_sum += score * credit;
+                    score_credits += credit;
+                }
+            }
+            Counted::CreditOnly if rules.exempt_counts_credit => {
+                summary.course_count += 1;
+                summary.attempted_credits += credit;
+                summary.earned_credits += credit;
+            }
+            _ => {}
+        }
+    }
+    summary.gpa = (summary.gpa_credits > 0.0).then(|| round2(point_sum / summary.gpa_credits));
+    summary.weighted_average = (score_credits > 0.0).then(|| round2(score_sum / score_credits));
+    summary.attempted_credits = round2(summary.attempted_credits);
+    summary.earned_credits = round2(summary.earned_credits);
+    summary.gpa_credits = round2(summary.gpa_credits);
+    summary
+}
+
+/// 课程身份（跨学期）：课程号优先，缺失时用课程名。
+fn course_key(record: &GradeRecord) -> String {
+    record
+        .course_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .unwrap_or_else(|| record.course_name.trim())
+        .to_string()
+}
+
+fn nature_label(record: &GradeRecord) -> String {
+    let nature = record.course_nature.trim();
+    if nature.is_empty() {
+        "未分类".to_string()
+    } else {
+        nature.to_string()
+    }
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(term: &str, code: &str, credit: &str, score: &str) -> GradeRecord {
+        GradeRecord {
+            term: term.to_string(),
+            course_name: format!("课程{code}"),
+            grade_id: Some(format!("{term}-{code}")),
+            course_code: Some(code.to_string()),
+            course_nature: "必修".to_string(),
+            course_nature_code: "1".to_string(),
+            course_credit: credit.to_string(),
+            final_score: score.to_string(),
+            earned_credit: String::new(),
+            xfjd: String::new(),
+            sfbk: String::new(),
+            sfsq: String::new(),
+            cjbj: String::new(),
+            teacher: None,
+            kcbh: None,
+            course_teacher: None,
+        }
+    }
+
+    #[test]
+    fn computes_weighted_gpa_average_and_credits_per_term_and_cumulative() {
+        let grades = vec![
+            record("2024-2025-1", "A", "4", "90"),
+            record("2024-2025-1", "B", "2", "70"),
+            record("2024-2025-2", "C", "3", "50"),
+        ];
+        let analytics = analyze_grades(&grades, &AnalyticsRequest::default());
+
+        // (4*4.0 + 2*2.0 + 3*0.0) / 9
+        assert_eq!(analytics.cumulative.gpa, Some(2.22));
+        assert_eq!(analytics.cumulative.weighted_average, Some(72.22));
+        assert_eq!(analytics.cumulative.attempted_credits, 9.0);
+        assert_eq!(analytics.cumulative.earned_credits, 6.0);
+
+        assert_eq!(analytics.terms.len(), 2);
+        assert_eq!(analytics.terms[0].term, "2024-2025-1");
+        assert_eq!(analytics.terms[0].summary.gpa, Some(3.33));
+        assert_eq!(analytics.terms[1].summary.gpa, Some(0.0));
+        assert!(analytics.projection.is_none());
+    }
+
+    #[test]
+    fn official_grade_point_and_earned_credit_take_precedence() {
+        let mut graded = record("2024-2025-1", "A", "2", "75");
+        graded.xfjd = "3.0".to_string();
+        let mut failed_but_credited = record("2024-2025-1", "B", "1", "55");
+        failed_but_credited.earned_credit = "1".to_string();
+        let analytics =
+            analyze_grades(&[graded, failed_but_credited], &AnalyticsRequest::default());
+        // (2*3.0 + 1*0.5) / 3
+        assert_eq!(analytics.cumulative.gpa, Some(2.17));
+        assert_eq!(analytics.cumulative.earned_credits, 3.0);
+    }
+
+    #[test]
+    fn retake_policy_controls_cumulative_but_not_term_view() {
+        let first = record("2023-2024-2", "A", "4", "50");
+        let mut retake = record("2024-2025-1", "A", "4", "75");
+        retake.sfbk = "1".to_string();
+        let grades = vec![first, retake];
+
+        let highest = analyze_grades(&grades, &AnalyticsRequest::default());
+        assert_eq!(highest.cumulative.course_count, 1);
+        assert_eq!(highest.cumulative.gpa, Some(2.5));
+        assert_eq!(highest.terms.len(), 2);
+
+        let mut request = AnalyticsRequest::default();
+        request.rules.retake = RetakePolicy::All;
+        let all = analyze_grades(&grades, &request);
+        assert_eq!(all.cumulative.course_count, 2);
+        assert_eq!(all.cumulative.gpa, Some(1.25));
+        assert_eq!(all.cumulative.earned_credits, 4.0);
+    }
+
+    #[test]
+    fn each_course_counts_once_across_graded_and_ungraded_attempts() {
+        let mut exempt = record("2023-2024-2", "A", "4", "免修");
+        exempt.cjbj = "3".to_string();
+        let retake = record("2024-2025-1", "A", "4", "85");
+        let pending = record("2024-2025-2", "A", "4", "");
+        let grades = vec![exempt, retake, pending];
+
+        let mut request = AnalyticsRequest::default();
+        request.rules.retake = RetakePolicy::Latest;
+        let latest = analyze_grades(&grades, &request);
+        // 最新一次仍待录入，取最近一次已出结果的修读
+        assert_eq!(latest.cumulative.course_count, 1);
+        assert_eq!(latest.cumulative.attempted_credits, 4.0);
+        assert_eq!(latest.cumulative.gpa, Some(3.5));
+        assert_eq!(latest.by_nature[0].summary.attempted_credits, 4.0);
+
+        let highest = analyze_grades(&grades, &AnalyticsRequest::default());
+        assert_eq!(highest.cumulative.course_count, 1);
+        assert_eq!(highest.cumulative.earned_credits, 4.0);
+    }
+
+    #[test]
+    fn exempt_absent_and_deferred_follow_configured_rules() {
+        let mut exempt = record("2024-2025-1", "E", "2", "免修");
+        exempt.cjbj = "3".to_string();
+        let absent = record("2024-2025-1", "X", "2", "缺考");
+        let mut deferred = record("2024-2025-1", "D", "3", "");
+        deferred.sfsq = "1".to_string();
+        let normal = record("2024-2025-1", "A", "2", "80");
+        let grades = vec![exempt, absent, deferred, normal];
+
+        let defaults = analyze_grades(&grades, &AnalyticsRequest::default());
+        // 免修只计学分；缺考按 0 计入 GPA；缓考进入待推演列表
+        assert_eq!(defaults.cumulative.gpa, Some(1.5));
+        assert_eq!(defaults.cumulative.attempted_credits, 6.0);
+        assert_eq!(defaults.cumulative.earned_credits, 4.0);
+        assert_eq!(defaults.pending.len(), 1);
+        assert_eq!(defaults.pending[0].outcome, GradeOutcome::Deferred);
+
+        let request = AnalyticsRequest {
+            rules: GpaRules {
+                exempt_counts_credit: false,
+                absent_as_zero: false,
+                deferred_as_pending: false,
+                ..GpaRules::default()
+            },
+            what_if: Vec::new(),
+        };
+        let strict = analyze_grades(&grades, &request);
+        assert_eq!(strict.cumulative.gpa, Some(3.0));
+        assert_eq!(strict.cumulative.attempted_credits, 2.0);
+        assert!(strict.pending.is_empty());
+    }
+
+    #[test]
+    fn breaks_down_by_course_nature_and_can_skip_electives() {
+        let mut elective = record("2024-2025-1", "S", "2", "95");
+        elective.course_nature = "选修".to_string();
+        let mut unnamed = record("2024-2025-1", "U", "1", "60");
+        unnamed.course_nature = String::new();
+        let grades = vec![record("2024-2025-1", "A", "4", "80"), elective, unnamed];
+
+        let analytics = analyze_grades(&grades, &AnalyticsRequest::default());
+        let natures = analytics
+            .by_nature
+            .iter()
+            .map(|item| item.nature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(natures, vec!["必修", "未分类", "选修"]);
+
+        let mut request = AnalyticsRequest::default();
+        request.rules.include_electives = false;
+        let required_only = analyze_grades(&grades, &request);
+        assert_eq!(required_only.cumulative.attempted_credits, 5.0);
+    }
+
+    #[test]
+    fn what_if_projects_pending_courses_without_touching_actuals() {
+        let grades = vec![
+            record("2024-2025-1", "A", "4", "80"),
+            record("2024-2025-2", "P", "2", ""),
+        ];
+        let base = analyze_grades(&grades, &AnalyticsRequest::default());
+        assert_eq!(base.pending.len(), 1);
+        let key = base.pending[0].key.clone();
+
+        let request = AnalyticsRequest {
+            rules: GpaRules::default(),
+            what_if: vec![HypotheticalScore { key, score: 95.0 }],
+        };
+        let analytics = analyze_grades(&grades, &request);
+        assert_eq!(analytics.cumulative, base.cumulative);
+        let projection = analytics.projection.unwrap();
+        // (4*3.0 + 2*4.5) / 6
+        assert_eq!(projection.gpa, Some(3.5));
+        assert_eq!(projection.attempted_credits, 6.0);
+    }
+}
@@ -0,0 +1,358 @@
+//! 成绩变更比对（diff engine）：后台检查与通知共用。
+//!
+//! 以「学期 + 课程号/kcbh/成绩记录号」为身份键，对比上一次已通报的快照与最新成绩，
+//! 把变化归类为 [`GradeChangeKind`]，每条变化对应一条可直接展示的通知文案。
+//!
+//! 归类优先级（同一条记录只产出一条变化）：
+//! 1. 新记录（含新发布的补考/重修记录）→ `New`
+//! 2. 结果变为补考/重修或免修 → `OutcomeChanged`
+//! 3. 成绩文本变化（含待录入 → 已出分）→ `ScoreChanged`
+//! 4. 官方绩点 `xfjd` 从无到有 → `OfficialGradePointPosted`
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grade::domain::{GradeOutcome, GradeRecord};
+
+/// 成绩变化类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeChangeKind {
+    /// 新出现的成绩记录
+    New,
+    /// 成绩文本变化（含待录入 → 已出分）
+    ScoreChanged,
+    /// 结果变为补考/重修或免修
+    OutcomeChanged,
+    /// 官方绩点首次出现
+    OfficialGradePointPosted,
+}
+
+/// 单条成绩变化（通知与 Bridge 展示共用）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradeChange {
+    pub kind: GradeChangeKind,
+    /// 身份键（term|课程标识）
+    pub key: String,
+    pub term: String,
+    pub course_name: String,
+    /// 旧成绩文本（新记录为 `None`）
+    pub previous_score: Option<String>,
+    pub score: String,
+    pub previous_outcome: Option<GradeOutcome>,
+    pub outcome: GradeOutcome,
+    /// 有效绩点（官方优先）
+    pub grade_point: Option<f64>,
+}
+
+impl GradeChange {
+    /// 通知标题。
+    pub fn title(&self) -> String {
+        match self.kind {
+            GradeChangeKind::New if self.outcome == GradeOutcome::Retake => {
+                "补考/重修成绩发布".to_string()
+            }
+            GradeChangeKind::New => "新成绩发布".to_string(),
+            GradeChangeKind::ScoreChanged => {
+                if self
+                    .previous_score
+                    .as_deref()
+                    .unwrap_or("")
+                    .trim()
+                    .is_empty()
+                {
+                    "新成绩发布".to_string()
+                } else {
+                    "成绩有变动".to_string()
+                }
+            }
+            GradeChangeKind::OutcomeChanged => "成绩状态变更".to_string(),
+            GradeChangeKind::OfficialGradePointPosted => "官方绩点已更新".to_string(),
+        }
+    }
+
+    /// 通知正文：课程名 + 成绩（+ 绩点）。
+    pub fn body(&self) -> String {
+        let score = display_score(&self.score);
+        let point = self
+            .grade_point
+            .map(|value| format!("，绩点 {value:.1}"))
+            .unwrap_or_default();
+        match self.kind {
+            GradeChangeKind::New | GradeChangeKind::OfficialGradePointPosted => {
+                format!("{}（{}）：{}{}", self.course_name, self.term, score, point)
+            }
+            GradeChangeKind::ScoreChanged => {
+                let previous = self.previous_score.as_deref().unwrap_or("").trim();
+                if previous.is_empty() {
+                    format!("{}（{}）：{}{}", self.course_name, self.term, score, point)
+                } else {
+                    format!(
+                        "{}（{}）：{} → {}{}",
+                        self.course_name, self.term, previous, score, point
+                    )
+                }
+            }
+            GradeChangeKind::OutcomeChanged => format!(
+                "{}（{}）：{}，成绩 {}",
+                self.course_name,
+                self.term,
+                outcome_label(self.outcome),
+                score
+            ),
+        }
+    }
+}
+
+fn display_score(score: &str) -> &str {
+    let score = score.trim();
+    if score.is_empty() {
+        "待录入"
+    } else {
+        score
+    }
+}
+
+fn outcome_label(outcome: GradeOutcome) -> &'static str {
+    match outcome {
+        GradeOutcome::Normal => "正常",
+        GradeOutcome::Pending => "待录入",
+        GradeOutcome::Absent => "缺考",
+        GradeOutcome::Deferred => "缓考",
+        GradeOutcome::Exempt => "免修",
+        GradeOutcome::Retake => "补考/重修",
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 成绩身份键：`学期|课程号`，课程号缺失时依次回退 kcbh / 成绩记录号 / 课程名。
+pub fn grade_identity_key(grade: &GradeRecord) -> String {
+    let course = non_empty(grade.course_code.as_deref())
+        .or_else(|| non_empty(grade.kcbh.as_deref()))
+        .or_else(|| non_empty(grade.grade_id.as_deref()))
+        .unwrap_or_else(|| grade.course_name.trim());
+    format!("{}|{}", grade.term.trim(), course)
+}
+
+/// 为一组成绩生成唯一身份键。
+///
+/// 同一学期同一课程出现多条（如补考记录）时，非补考且记录号最小的一条保留基础键，
+/// 其余追加成绩记录号消歧，保证原记录不因新补考记录出现而被误判为「新成绩」。
+fn keyed(grades: &[GradeRecord]) -> HashMap<String, &GradeRecord> {
+    let mut groups: HashMap<String, Vec<&GradeRecord>> = HashMap::new();
+    for grade in grades {
+        groups
+            .entry(grade_identity_key(grade))
+            .or_default()
+            .push(grade);
+    }
+    let mut map = HashMap::new();
+    for (base, mut group) in groups {
+        group.sort_by_key(|grade| {
+            (
+                grade.outcome() == GradeOutcome::Retake,
+                non_empty(grade.grade_id.as_deref())
+                    .unwrap_or("")
+                    .to_string(),
+            )
+        });
+        for (index, grade) in group.into_iter().enumerate() {
+            let key = if index == 0 {
+                base.clone()
+            } else {
+                let suffix = non_empty(grade.grade_id.as_deref())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| index.to_string());
+                format!("{base}#{suffix}")
+            };
+            map.insert(key, grade);
+        }
+    }
+    map
+}
+
+/// 比对上一次快照与最新成绩，返回按学期/课程名排序的变化列表。
+pub fn diff_grades(previous: &[GradeRecord], current: &[GradeRecord]) -> Vec<GradeChange> {
+    let previous = keyed(previous);
+    let current = keyed(current);
+    let mut changes = Vec::new();
+
+    for (key, grade) in &current {
+        let outcome = grade.outcome();
+        let Some(old) = previous.get(key) else {
+            changes.push(GradeChange {
+                kind: GradeChangeKind::New,
+                key: key.clone(),
+                term: grade.term.clone(),
+                course_name: grade.course_name.clone(),
+                previous_score: None,
+                score: grade.final_score.clone(),
+                previous_outcome: None,
+                outcome,
+                grade_point: grade.grade_point(),
+            });
+            continue;
+        };
+
+        let old_outcome = old.outcome();
+        let kind = if outcome != old_outcome
+            && matches!(outcome, GradeOutcome::Retake | GradeOutcome::Exempt)
+        {
+            Some(GradeChangeKind::OutcomeChanged)
+        } else if old.final_score.trim() != grade.final_score.trim() {
+            Some(GradeChangeKind::ScoreChanged)
+        } else if old.official_grade_point().is_none() && grade.official_grade_point().is_some() {
+            Some(GradeChangeKind::OfficialGradePointPosted)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            changes.push(GradeChange {
+                kind,
+                key: key.clone(),
+                term: grade.term.clone(),
+                course_name: grade.course_name.clone(),
+                previous_score: Some(old.final_score.clone()),
+                score: grade.final_score.clone(),
+                previous_outcome: Some(old_outcome),
+                outcome,
+                grade_point: grade.grade_point(),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| {
+        b.term
+            .cmp(&a.term)
+            .then_with(|| a.course_name.cmp(&b.course_name))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(term: &str, code: &str, name: &str, score: &str) -> GradeRecord {
+        GradeRecord {
+            term: term.to_string(),
+            course_name: name.to_string(),
+            grade_id: Some(format!("g-{code}")),
+            course_code: Some(code.to_string()),
+            course_nature: "必修".to_string(),
+            course_nature_code: "1".to_string(),
+            course_credit: "3".to_string(),
+            final_score: score.to_string(),
+            earned_credit: "3".to_string(),
+            xfjd: String::new(),
+            sfbk: String::new(),
+            sfsq: String::new(),
+            cjbj: String::new(),
+            teacher: None,
+            kcbh: None,
+            course_teacher: None,
+        }
+    }
+
+    #[test]
+    fn identical_lists_produce_no_changes() {
+        let list = vec![grade("2024-2025-1", "K1", "高等数学", "85")];
+        assert!(diff_grades(&list, &list).is_empty());
+    }
+
+    #[test]
+    fn new_course_and_score_change_are_reported_per_course() {
+        let previous = vec![
+            grade("2024-2025-1", "K1", "高等数学", "85"),
+            grade("2024-2025-1", "K2", "大学英语", ""),
+        ];
+        let current = vec![
+            grade("2024-2025-1", "K1", "高等数学", "88"),
+            grade("2024-2025-1", "K2", "大学英语", "76"),
+            grade("2024-2025-1", "K3", "线性代数", "90"),
+        ];
+        let changes = diff_grades(&previous, &current);
+        assert_eq!(changes.len(), 3);
+
+        let by_name = |name: &str| changes.iter().find(|c| c.course_name == name).unwrap();
+        assert_eq!(by_name("高等数学").kind, GradeChangeKind::ScoreChanged);
+        assert_eq!(
+            by_name("高等数学").body(),
+            "高等数学（2024-2025-1）：85 → 88，绩点 3.8"
+        );
+        assert_eq!(by_name("大学英语").kind, GradeChangeKind::ScoreChanged);
+        assert_eq!(by_name("大学英语").title(), "新成绩发布");
+        assert_eq!(by_name("线性代数").kind, GradeChangeKind::New);
+    }
+
+    #[test]
+    fn course_replaced_in_place_is_detected_even_with_same_length() {
+        let previous = vec![grade("2024-2025-1", "K1", "高等数学", "85")];
+        let current = vec![grade("2024-2025-1", "K9", "概率论", "80")];
+        let changes = diff_grades(&previous, &current);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, GradeChangeKind::New);
+        assert_eq!(changes[0].course_name, "概率论");
+    }
+
+    #[test]
+    fn outcome_change_to_retake_or_exempt_is_classified() {
+        let previous = vec![grade("2024-2025-1", "K1", "高等数学", "50")];
+        let mut retake = grade("2024-2025-1", "K1", "高等数学", "50");
+        retake.sfbk = "1".to_string();
+        let changes = diff_grades(&previous, &[retake]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, GradeChangeKind::OutcomeChanged);
+        assert_eq!(changes[0].outcome, GradeOutcome::Retake);
+
+        let mut exempt = grade("2024-2025-1", "K1", "高等数学", "50");
+        exempt.cjbj = "3".to_string();
+        let changes = diff_grades(&previous, &[exempt]);
+        assert_eq!(changes[0].kind, GradeChangeKind::OutcomeChanged);
+        assert!(changes[0].body().contains("免修"));
+    }
+
+    #[test]
+    fn newly_posted_retake_record_is_disambiguated_from_original() {
+        let original = grade("2024-2025-1", "K1", "高等数学", "50");
+        let mut retake = grade("2024-2025-1", "K1", "高等数学", "62");
+        retake.grade_id = Some("g-K1-bk".to_string());
+        retake.sfbk = "1".to_string();
+        let changes = diff_grades(std::slice::from_ref(&original), &[original.clone(), retake]);
+        // 原记录保留基础键，不被重复通报；只有补考记录作为新成绩出现
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, GradeChangeKind::New);
+        assert_eq!(changes[0].title(), "补考/重修成绩发布");
+        assert_eq!(changes[0].score, "62");
+    }
+
+    #[test]
+    fn official_grade_point_appearing_is_reported() {
+        let previous = vec![grade("2024-2025-1", "K1", "高等数学", "85")];
+        let mut official = grade("2024-2025-1", "K1", "高等数学", "85");
+        official.xfjd = "3.7".to_string();
+        let changes = diff_grades(&previous, &[official]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, GradeChangeKind::OfficialGradePointPosted);
+        assert_eq!(changes[0].grade_point, Some(3.7));
+    }
+
+    #[test]
+    fn identity_key_falls_back_to_kcbh_then_grade_id_then_name() {
+        let mut g = grade("2024-2025-1", "K1", "高等数学", "85");
+        assert_eq!(grade_identity_key(&g), "2024-2025-1|K1");
+        g.course_code = None;
+        g.kcbh = Some("B1".to_string());
+        assert_eq!(grade_identity_key(&g), "2024-2025-1|B1");
+        g.kcbh = None;
+        assert_eq!(grade_identity_key(&g), "2024-2025-1|g-K1");
+        g.grade_id = Some("  ".to_string());
+        assert_eq!(grade_identity_key(&g), "2024-2025-1|高等数学");
+    }
+}
@@ -6,14 +6,23 @@
 //! 分层：
 //! - [`domain`]：纯领域模型与计算（无网络、无存储依赖）。
 //! - [`service`]：共享 use-case（抓取 → 教师合并 → 缓存成功替换/失败保留 → offline 元数据）。
+//! - [`diff`]：成绩变更比对（后台检查按课程逐条通知）。
+//! - [`analytics`]：GPA / 加权均分 / 学分统计与 what-if 推演（纯计算，规则可配置）。
 
+pub mod analytics;
+pub mod diff;
 pub mod domain;
 pub mod service;
 
+pub use analytics::{
+    analyze_grades, AnalyticsRequest, GpaRules, GpaSummary, GradeAnalytics, HypotheticalScore,
+    NatureSummary, PendingCourse, RetakePolicy, TermSummary,
+};
+pub use diff::{diff_grades, grade_identity_key, GradeChange, GradeChangeKind};
 pub use domain::{
     current_grade_semester, grade_terms, Grade, GradeOutcome, GradePointSource, GradeRecord,
 };
 pub use service::{
-    merge_teacher_cache_into_payload, EnrichmentJob, GradeCacheStore, GradeService, GradeSource,
-    SqliteGradeCache, SyncGradesResult,
+    merge_teacher_cache_into_payload, EnrichmentJob, GradeCacheStore, GradeCheckResult,
+    GradeService, GradeSource, SqliteGradeCache, SyncGradesResult,
 };
@@ -5,12 +5,19 @@
 //! - 教师合并：抓取成功后用本地教师缓存按 kcbh/课程号/记录号补任课教师
 //! - 缓存：**成功整表替换**；**失败保留旧快照**并标记 `offline=true`
 //! - 元数据：`sync_time` / `offline` / `teacher_enrichment_pending` 两通道一致
+//! - 变更检查：后台任务经 [`GradeService::check_grade_changes`] 同步并与已通报快照比对，
+//!   通知送达后经 [`GradeService::commit_grade_changes`] 推进快照
+//! - 统计：[`GradeService::attach_analytics`] / [`GradeService::analyze_cached`] 基于
+//!   [`crate::grade::analytics`] 计算 GPA / 学分，可随同步结果一并返回
 //!
 //! Tauri Command 与 HTTP Bridge handler 均只做传输适配（取 uid、spawn 后台任务），
 //! 业务逻辑全部收敛在本模块，保证双通道返回一致。
 
+use crate::grade::analytics::{analyze_grades, AnalyticsRequest, GradeAnalytics};
+use crate::grade::diff::{diff_grades, grade_identity_key, GradeChange};
 use crate::grade::domain::{grade_terms, GradeRecord};
 use crate::http_client::HbutClient;
+use crate::runtime_events::SyncSpan;
 use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -64,6 +71,10 @@ pub trait GradeCacheStore: Send + Sync {
         semester: &str,
         courses: &[(String, String)],
     ) -> Result<Value, String>;
+    /// 读取上一次已通报的成绩快照（变更比对基线）。
+    fn load_change_snapshot(&self, uid: &str) -> Option<Vec<GradeRecord>>;
+    /// 保存已通报的成绩快照。
+    fn save_change_snapshot(&self, uid: &str, grades: &[GradeRecord]) -> Result<(), String>;
 }
 
 /// 生产缓存实现：SQLite（`db` 模块）。
@@ -98,8 +109,25 @@ impl GradeCacheStore for SqliteGradeCache {
         crate::db::merge_grade_teacher_cache(crate::DB_FILENAME, uid, semester, courses)
             .map_err(|error| error.to_string())
     }
+
+    fn load_change_snapshot(&self, uid: &str) -> Option<Vec<GradeRecord>> {
+        crate::db::get_cache(crate::DB_FILENAME, GRADE_SNAPSHOT_TABLE, uid)
+            .ok()
+            .flatten()
+            .and_then(|(data, _)| data.get("data").cloned())
+            .and_then(|data| serde_json::from_value(data).ok())
+    }
+
+    fn save_change_snapshot(&self, uid: &str, grades: &[GradeRecord]) -> Result<(), String> {
+        let payload = serde_json::json!({ "data": grades });
+        crate::db::save_cache(crate::DB_FILENAME, GRADE_SNAPSHOT_TABLE, uid, &payload)
+            .map_err(|e| e.to_string())
+    }
 }
 
+/// 成绩变更比对快照表（与 `grades_cache` 分离：前台同步不会吞掉待通报的变化）。
+pub(crate) const GRADE_SNAPSHOT_TABLE: &str = "grade_snapshot_cache";
+
 /// 后台补齐任课教师任务（由调用方决定如何 spawn）。
 #[derive(Debug, Clone)]
 pub struct EnrichmentJob {
@@ -114,6 +142,21 @@ pub struct SyncGradesResult {
     pub enrichment: Option<EnrichmentJob>,
 }
 
+/// 成绩变更检查结果。
+#[derive(Debug, Clone, Default)]
+pub struct GradeCheckResult {
+    /// 本次相对已通报快照的变化（每条对应一条通知）
+    pub changes: Vec<GradeChange>,
+    /// 首次检查：仅建立基线，不产出变化
+    pub baseline: bool,
+    /// 远端失败、使用了离线快照：不比对、不推进基线
+    pub offline: bool,
+    /// 比对基线（上一次已通报快照），供 [`GradeService::commit_grade_changes`] 回填未送达记录
+    previous: Vec<GradeRecord>,
+    /// 本次同步到的成绩
+    latest: Vec<GradeRecord>,
+}
+
 /// 共享成绩服务：Tauri Command 与 HTTP Bridge 均通过它完成同步。
 #[derive(Clone)]
 pub struct GradeService<S, C> {
@@ -132,10 +175,23 @@ impl<S: GradeSource, C: GradeCacheStore> GradeService<S, C> {
     /// 统一成绩同步用例：
     /// 1. 抓取成绩；2. 合并本地教师缓存；3. 成功 → 整表替换缓存（offline=false）；
     /// 4. 失败 → 保留旧缓存快照（offline=true）；无缓存则透传错误。
+    ///
+    /// 开始与结束各发布一条 `grades` 同步事件（见 [`crate::runtime_events`]）。
     pub async fn sync_grades(
         &self,
         uid: Option<&str>,
         current_only: bool,
+    ) -> Result<SyncGradesResult, String> {
+        let span = SyncSpan::start("grades", uid);
+        let result = self.fetch_and_cache_grades(uid, current_only).await;
+        span.finish(result.as_ref().map(|result| &result.payload));
+        result
+    }
+
+    async fn fetch_and_cache_grades(
+        &self,
+        uid: Option<&str>,
+        current_only: bool,
     ) -> Result<SyncGradesResult, String> {
         match self.source.fetch_grades().await {
             Ok(mut grades) => {
@@ -199,6 +255,99 @@ impl<S: GradeSource, C: GradeCacheStore> GradeService<S, C> {
         }
     }
 
+    /// 后台成绩变更检查：经 [`Self::sync_grades`] 同步（与前台共用缓存语义），
+    /// 再与上一次已通报的快照比对。首次检查直接建立基线；有变化时不推进快照，
+    /// 由调用方送达通知后经 [`Self::commit_grade_changes`] 推进，送达失败的变化下一轮补发。
+    pub async fn check_grade_changes(&self, uid: &str) -> Result<GradeCheckResult, String> {
+        let result = self.sync_grades(Some(uid), true).await?;
+        if result
+            .payload
+            .get("offline")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            return Ok(GradeCheckResult {
+                offline: true,
+                ..GradeCheckResult::default()
+            });
+        }
+        let grades = payload_grades(&result.payload)?;
+
+        let Some(previous) = self.cache.load_change_snapshot(uid) else {
+            self.cache.save_change_snapshot(uid, &grades)?;
+            return Ok(GradeCheckResult {
+                baseline: true,
+                ..GradeCheckResult::default()
+            });
+        };
+        Ok(GradeCheckResult {
+            changes: diff_grades(&previous, &grades),
+            previous,
+            latest: grades,
+            ..GradeCheckResult::default()
+        })
+    }
+
+    /// 通知送达后推进已通报快照：`delivered` 为已送达变化的 [`GradeChange::key`]。
+    ///
+    /// 未送达的变化保留旧记录（新记录则暂不写入），下一轮检查会再次比对出来并补发。
+    pub fn commit_grade_changes(
+        &self,
+        uid: &str,
+        result: &GradeCheckResult,
+        delivered: &[String],
+    ) -> Result<(), String> {
+        if result.baseline || result.offline {
+            return Ok(());
+        }
+        let pending = result
+            .changes
+            .iter()
+            .map(|change| change.key.as_str())
+            .filter(|key| !delivered.iter().any(|done| done == key))
+            .collect::<Vec<_>>();
+        let snapshot = result
+            .latest
+            .iter()
+            .filter_map(|grade| {
+                let key = grade_identity_key(grade);
+                if !pending.contains(&key.as_str()) {
+                    return Some(grade.clone());
+                }
+                result
+                    .previous
+                    .iter()
+                    .find(|old| grade_identity_key(old) == key)
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+        self.cache.save_change_snapshot(uid, &snapshot)
+    }
+
+    /// 在同步 payload 上附加 `analytics` 字段（基于 payload 的 `data`，离线快照同样适用）。
+    pub fn attach_analytics(&self, mut payload: Value, request: &AnalyticsRequest) -> Value {
+        let grades = payload_grades(&payload).unwrap_or_default();
+        let analytics = serde_json::to_value(analyze_grades(&grades, request));
+        if let (Some(object), Ok(analytics)) = (payload.as_object_mut(), analytics) {
+            object.insert("analytics".to_string(), analytics);
+        }
+        payload
+    }
+
+    /// 基于本地成绩缓存计算统计（不触发远端同步）。
+    pub fn analyze_cached(
+        &self,
+        uid: &str,
+        request: &AnalyticsRequest,
+    ) -> Result<GradeAnalytics, String> {
+        let (payload, _) = self
+            .cache
+            .load_grades(uid)
+            .ok_or_else(|| "暂无成绩缓存".to_string())?;
+        let grades = payload_grades(&payload)?;
+        Ok(analyze_grades(&grades, request))
+    }
+
     /// 读取本地任课教师缓存（供 get_grade_teacher_cache 等 handler 使用）。
     pub fn read_teacher_cache(&self, uid: &str) -> Option<Value> {
         self.cache.load_teacher_cache(uid)
@@ -315,6 +464,17 @@ pub fn merge_teacher_cache_into_payload(
     payload
 }
 
+/// 解析 payload 的 `data` 成绩数组（缺失视为空）。
+fn payload_grades(payload: &Value) -> Result<Vec<GradeRecord>, String> {
+    payload
+        .get("data")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("成绩数据解析失败: {e}"))
+        .map(Option::unwrap_or_default)
+}
+
 /// 给缓存 payload 附加 sync_time / offline 元数据（offline 回退路径）。
 fn attach_sync_time(payload: Value, sync_time: &str, offline: bool) -> Value {
     match payload {
@@ -426,6 +586,7 @@ mod tests {
     struct MockCache {
         grades: Mutex<HashMap<String, (Value, String)>>,
         teachers: Mutex<HashMap<String, Value>>,
+        snapshots: Mutex<HashMap<String, Vec<GradeRecord>>>,
     }
 
     impl GradeCacheStore for MockCache {
@@ -466,6 +627,18 @@ mod tests {
                 .insert(uid.to_string(), value.clone());
             Ok(value)
         }
+
+        fn load_change_snapshot(&self, uid: &str) -> Option<Vec<GradeRecord>> {
+            self.snapshots.lock().unwrap().get(uid).cloned()
+        }
+
+        fn save_change_snapshot(&self, uid: &str, grades: &[GradeRecord]) -> Result<(), String> {
+            self.snapshots
+                .lock()
+                .unwrap()
+                .insert(uid.to_string(), grades.to_vec());
+            Ok(())
+        }
     }
 
     struct FailingSaveCache;
@@ -491,6 +664,14 @@ mod tests {
         ) -> Result<Value, String> {
             Ok(serde_json::json!({ "success": true, "by_kcbh": {}, "semesters": {} }))
         }
+
+        fn load_change_snapshot(&self, _uid: &str) -> Option<Vec<GradeRecord>> {
+            None
+        }
+
+        fn save_change_snapshot(&self, _uid: &str, _grades: &[GradeRecord]) -> Result<(), String> {
+            Ok(())
+        }
     }
 
     fn sample_grade(term: &str, kcbh: Option<&str>) -> GradeRecord {
@@ -519,6 +700,7 @@ mod tests {
             MockCache {
                 grades: Mutex::new(self.grades.lock().unwrap().clone()),
                 teachers: Mutex::new(self.teachers.lock().unwrap().clone()),
+                snapshots: Mutex::new(self.snapshots.lock().unwrap().clone()),
             }
         }
     }
@@ -702,6 +884,154 @@ mod tests {
         assert_eq!(stored["by_kcbh"]["K001"], "张老师");
     }
 
+    /// 变更检查：首次只建立基线；之后按课程产出变化并推进快照（同一变化只通报一次）。
+    #[tokio::test]
+    async fn check_grade_changes_baselines_then_reports_each_change_once() {
+        let cache = MockCache::default();
+        let first = GradeService::new(
+            MockSource {
+                grades: Ok(vec![sample_grade("2024-2025-1", Some("K001"))]),
+                teachers: HashMap::new(),
+            },
+            cache,
+        );
+        let result = first.check_grade_changes("20240001").await.unwrap();
+        assert!(result.baseline);
+        assert!(result.changes.is_empty());
+
+        let mut updated = sample_grade("2024-2025-1", Some("K001"));
+        updated.final_score = "90".to_string();
+        let mut added = sample_grade("2024-2025-2", Some("K002"));
+        added.course_name = "线性代数".to_string();
+        let second = GradeService::new(
+            MockSource {
+                grades: Ok(vec![updated.clone(), added.clone()]),
+                teachers: HashMap::new(),
+            },
+            first.cache.clone_for_test(),
+        );
+        let result = second.check_grade_changes("20240001").await.unwrap();
+        assert!(!result.baseline);
+        assert_eq!(result.changes.len(), 2);
+        assert!(result
+            .changes
+            .iter()
+            .any(|c| c.course_name == "线性代数"
+                && c.kind == crate::grade::diff::GradeChangeKind::New));
+        // 成绩缓存经 GradeService 写入，与前台同步一致
+        assert!(second.cache.load_grades("20240001").is_some());
+
+        let delivered = result
+            .changes
+            .iter()
+            .map(|c| c.key.clone())
+            .collect::<Vec<_>>();
+        second
+            .commit_grade_changes("20240001", &result, &delivered)
+            .unwrap();
+        let again = second.check_grade_changes("20240001").await.unwrap();
+        assert!(again.changes.is_empty());
+    }
+
+    /// 变更检查：通知未送达的变化不推进快照，下一轮重新产出；已送达的不重复。
+    #[tokio::test]
+    async fn undelivered_grade_changes_are_reported_again() {
+        let cache = MockCache::default();
+        let first = GradeService::new(
+            MockSource {
+                grades: Ok(vec![sample_grade("2024-2025-1", Some("K001"))]),
+                teachers: HashMap::new(),
+            },
+            cache,
+        );
+        first.check_grade_changes("20240001").await.unwrap();
+
+        let mut updated = sample_grade("2024-2025-1", Some("K001"));
+        updated.final_score = "90".to_string();
+        let mut added = sample_grade("2024-2025-2", Some("K002"));
+        added.course_name = "线性代数".to_string();
+        let second = GradeService::new(
+            MockSource {
+                grades: Ok(vec![updated, added]),
+                teachers: HashMap::new(),
+            },
+            first.cache.clone_for_test(),
+        );
+        // 检查后未提交（例如通知全部失败）：快照不变
+        let result = second.check_grade_changes("20240001").await.unwrap();
+        assert_eq!(result.changes.len(), 2);
+        let result = second.check_grade_changes("20240001").await.unwrap();
+        assert_eq!(result.changes.len(), 2);
+
+        // 仅新增课程送达：成绩变动下一轮补发
+        let delivered = result
+            .changes
+            .iter()
+            .filter(|c| c.course_name == "线性代数")
+            .map(|c| c.key.clone())
+            .collect::<Vec<_>>();
+        second
+            .commit_grade_changes("20240001", &result, &delivered)
+            .unwrap();
+        let again = second.check_grade_changes("20240001").await.unwrap();
+        assert_eq!(again.changes.len(), 1);
+        assert_eq!(
+            again.changes[0].kind,
+            crate::grade::diff::GradeChangeKind::ScoreChanged
+        );
+    }
+
+    /// 变更检查：远端失败回退离线快照时不比对、不推进基线。
+    #[tokio::test]
+    async fn check_grade_changes_skips_offline_fallback() {
+        let cache = MockCache::default();
+        cache.grades.lock().unwrap().insert(
+            "20240001".to_string(),
+            (
+                serde_json::json!({ "success": true, "data": [] }),
+                "2024-01-01T00:00:00+08:00".to_string(),
+            ),
+        );
+        let service = GradeService::new(
+            MockSource {
+                grades: Err("网络错误".to_string()),
+                teachers: HashMap::new(),
+            },
+            cache,
+        );
+        let result = service.check_grade_changes("20240001").await.unwrap();
+        assert!(result.offline);
+        assert!(result.changes.is_empty());
+        assert!(service.cache.load_change_snapshot("20240001").is_none());
+    }
+
+    /// 统计：同步 payload 附加 analytics，与基于缓存的计算结果一致。
+    #[tokio::test]
+    async fn analytics_attach_to_sync_payload_and_match_cache() {
+        let service = GradeService::new(
+            MockSource {
+                grades: Ok(vec![
+                    sample_grade("2024-2025-1", Some("K001")),
+                    sample_grade("2024-2025-2", Some("K002")),
+                ]),
+                teachers: HashMap::new(),
+            },
+            MockCache::default(),
+        );
+        let request = crate::grade::analytics::AnalyticsRequest::default();
+        let result = service.sync_grades(Some("20240001"), false).await.unwrap();
+        let payload = service.attach_analytics(result.payload, &request);
+        assert_eq!(payload["analytics"]["cumulative"]["attempted_credits"], 8.0);
+        assert_eq!(
+            payload["analytics"]["terms"].as_array().map(Vec::len),
+            Some(2)
+        );
+
+        let cached = service.analyze_cached("20240001", &request).unwrap();
+        assert_eq!(serde_json::to_value(&cached).unwrap(), payload["analytics"]);
+        assert!(service.analyze_cached("unknown", &request).is_err());
+    }
+
     /// 共享用例：合并函数不覆盖已有 course_teacher。
     #[test]
     fn merge_teacher_cache_does_not_override_existing_teacher() {
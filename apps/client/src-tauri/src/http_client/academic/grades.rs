@@ -8,10 +8,10 @@ impl HbutClient {
     pub async fn fetch_grades(
         &self,
     ) -> Result<Vec<Grade>, Box<dyn std::error::Error + Send + Sync>> {
-        let grades_url = format!(
+        let grades_url = self.upstream_url(&format!(
             "{}/admin/xsd/xsdcjcx/xsdQueryXscjList",
             self.academic_base_url()
-        );
+        ));
 
         println!("[调试] 获取成绩: {}", grades_url);
 
@@ -130,7 +130,10 @@ impl HbutClient {
         &self,
         semester: &str,
     ) -> Result<Vec<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/admin/xsd/yxkccx/listYxkc", self.academic_base_url());
+        let url = self.upstream_url(&format!(
+            "{}/admin/xsd/yxkccx/listYxkc",
+            self.academic_base_url()
+        ));
 
         let mut repaired = false;
         let text = loop {
@@ -421,7 +421,7 @@ impl HbutClient {
     async fn finalize_jwxt_user_session(
         &mut self,
     ) -> Result<UserInfo, Box<dyn std::error::Error + Send + Sync>> {
-        let caslogin_url = format!("{}/admin/caslogin", super::JWXT_BASE_URL);
+        let caslogin_url = self.upstream_url(&format!("{}/admin/caslogin", super::JWXT_BASE_URL));
         crate::hbut_debug!("[调试] 访问教务 CAS 入口: {}", caslogin_url);
         let _ = self.client.get(&caslogin_url).send().await?;
         match self.fetch_user_info().await {
@@ -460,12 +460,12 @@ impl HbutClient {
             }
             let http = client.clone();
             let svc = service.to_string();
+            let login_url = self.upstream_url(&format!(
+                "{}/login?service={}",
+                AUTH_BASE_URL,
+                urlencoding::encode(&svc)
+            ));
             tasks.push(async move {
-                let login_url = format!(
-                    "{}/login?service={}",
-                    AUTH_BASE_URL,
-                    urlencoding::encode(&svc)
-                );
                 let response = http.get(&login_url).send().await.ok()?;
                 let html = response.text().await.ok()?;
                 if html_looks_like_login_form(&html) {
@@ -502,7 +502,10 @@ impl HbutClient {
         service_url: &str,
     ) -> Result<LoginPageInfo, Box<dyn std::error::Error + Send + Sync>> {
         let encoded_service = urlencoding::encode(service_url);
-        let login_url = format!("{}/login?service={}", AUTH_BASE_URL, encoded_service);
+        let login_url = self.upstream_url(&format!(
+            "{}/login?service={}",
+            AUTH_BASE_URL, encoded_service
+        ));
         crate::hbut_debug!("[调试] 获取登录页: {}", login_url);
 
         let response = match self.client.get(&login_url).send().await {
@@ -870,7 +873,11 @@ impl HbutClient {
 
     /// 获取验证码图片并返回 Base64 字符串
     pub async fn get_captcha(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let captcha_url = format!("{}/getCaptcha.htl?{}", AUTH_BASE_URL, chrono_timestamp());
+        let captcha_url = self.upstream_url(&format!(
+            "{}/getCaptcha.htl?{}",
+            AUTH_BASE_URL,
+            chrono_timestamp()
+        ));
         crate::hbut_debug!("[调试] 获取 captcha： {}", captcha_url);
 
         let response = self.client.get(&captcha_url).send().await?;
@@ -993,7 +1000,11 @@ impl HbutClient {
     async fn fetch_and_recognize_captcha(
         &mut self,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let captcha_url = format!("{}/getCaptcha.htl?{}", AUTH_BASE_URL, chrono_timestamp());
+        let captcha_url = self.upstream_url(&format!(
+            "{}/getCaptcha.htl?{}",
+            AUTH_BASE_URL,
+            chrono_timestamp()
+        ));
         crate::hbut_debug!("[调试] 获取验证码用于 OCR: {}", captcha_url);
 
         let response = self.client.get(&captcha_url).send().await?;
@@ -1027,7 +1038,10 @@ impl HbutClient {
         }
 
         let encoded_service = urlencoding::encode(TARGET_SERVICE);
-        let login_url = format!("{}/login?service={}", AUTH_BASE_URL, encoded_service);
+        let login_url = self.upstream_url(&format!(
+            "{}/login?service={}",
+            AUTH_BASE_URL, encoded_service
+        ));
         crate::hbut_debug!("[调试] 登录地址: {}", login_url);
         crate::hbut_debug!("[调试] 用户名: {}", username);
         crate::hbut_debug!("[调试] 密码长度 (plain): {}", password.len());
@@ -0,0 +1,606 @@
+//! 上游 HTTP fixture：录制脱敏的请求/响应对，并在本地回放，供 CI 离线跑通整条链路。
+//!
+//! - 录制：[`FixtureServer::record`] 作为反向代理转发到真实上游，按 [`FixtureSanitizer`]
+//!   脱敏（Cookie 值 / 票据 / 密码字段一律替换为 `***`，学号姓名经 [`crate::utils::mask`]）后存入
+//!   [`FixtureCassette`]，写入 `tests/fixtures/http/*.json`。
+//! - 回放：[`FixtureServer::replay`] 按录制顺序应答；`HbutClient` 通过
+//!   [`HbutClient::set_upstream_base`](super::HbutClient::set_upstream_base) 指向它。
+//!
+//! 回放地址形如 `{base}/{host}/{path}`：主机名留在路径里，Location / Set-Cookie 在出站时
+//! 改写到同一前缀下，cookie jar 的路径隔离与各处 `final_url.contains(..)` 判定保持原样。
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{is_plain_http_upstream, upstream_host};
+use crate::utils::mask::mask_student_id;
+
+mod server;
+
+pub use server::FixtureServer;
+
+/// 录制时保留的响应头（其余如 Date / Server / 压缩相关头一律丢弃）。
+const KEPT_RESPONSE_HEADERS: &[&str] = &["content-type", "location", "set-cookie"];
+/// 表单 / 查询串中整体替换为 [`MASKED`] 的字段。
+const SECRET_FIELDS: &[&str] = &[
+    "password",
+    "passwordText",
+    "captcha",
+    "captchaResponse",
+    "ticket",
+];
+/// 密码、验证码、票据与 Cookie 值的固定替换值，不保留原值的任何字符。
+const MASKED: &str = "***";
+/// 回放匹配时忽略的查询参数（时间戳、防缓存随机数）。
+const VOLATILE_QUERY_KEYS: &[&str] = &["_", "t", "timestamp"];
+
+/// 一次上游往返。`host` 为真实上游主机（可含端口），Location 等头保存真实绝对地址。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixtureExchange {
+    pub method: String,
+    pub host: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+    /// `body` 是否为 base64（验证码图片等二进制响应）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub body_base64: bool,
+}
+
+impl FixtureExchange {
+    /// 响应体原始字节。
+    pub fn body_bytes(&self) -> Vec<u8> {
+        if self.body_base64 {
+            base64::engine::general_purpose::STANDARD
+                .decode(self.body.as_bytes())
+                .unwrap_or_default()
+        } else {
+            self.body.as_bytes().to_vec()
+        }
+    }
+
+    fn matches(&self, method: &str, host: &str, path: &str) -> bool {
+        self.method.eq_ignore_ascii_case(method)
+            && self.host.eq_ignore_ascii_case(host)
+            && self.path == path
+    }
+}
+
+/// 一组按顺序录制的往返（一个文件对应一条业务链路）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FixtureCassette {
+    pub name: String,
+    pub exchanges: Vec<FixtureExchange>,
+}
+
+impl FixtureCassette {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            exchanges: Vec::new(),
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("fixture 解析失败: {e}"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取 fixture 失败 {}: {e}", path.display()))?;
+        Self::from_json(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text + "\n")
+            .map_err(|e| format!("写入 fixture 失败 {}: {e}", path.display()))
+    }
+
+    /// 查找下一条可回放的往返：优先未使用且查询串（去掉易变参数后）一致的，
+    /// 其次未使用且 method/host/path 一致的。
+    pub(crate) fn next_match(
+        &self,
+        used: &[bool],
+        method: &str,
+        host: &str,
+        path: &str,
+        query: &str,
+    ) -> Option<usize> {
+        let wanted = normalize_query(query);
+        let candidates = || {
+            self.exchanges
+                .iter()
+                .enumerate()
+                .filter(|(i, ex)| !used[*i] && ex.matches(method, host, path))
+        };
+        candidates()
+            .find(|(_, ex)| normalize_query(&ex.query) == wanted)
+            .or_else(|| candidates().next())
+            .map(|(i, _)| i)
+    }
+}
+
+/// 录制脱敏规则：Cookie 值、票据与密码类字段一律掩码；登记的身份信息（学号、姓名）
+/// 在 URL、请求体与响应体中替换为掩码形式。
+#[derive(Debug, Clone, Default)]
+pub struct FixtureSanitizer {
+    identities: Vec<(String, String)>,
+}
+
+impl FixtureSanitizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记需要在所有文本中替换的身份值（学号、姓名、手机号等）。
+    pub fn mask_identity(mut self, value: &str) -> Self {
+        let value = value.trim();
+        if !value.is_empty() {
+            self.identities
+                .push((value.to_string(), mask_student_id(value)));
+            // 长值优先替换，避免短值先命中长值的子串
+            self.identities
+                .sort_by_key(|(raw, _)| std::cmp::Reverse(raw.chars().count()));
+        }
+        self
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        self.identities
+            .iter()
+            .fold(text.to_string(), |acc, (raw, masked)| {
+                acc.replace(raw, masked)
+            })
+    }
+
+    /// 查询串 / urlencoded 表单：敏感字段替换为 [`MASKED`]，再替换身份值。
+    pub fn form(&self, encoded: &str) -> String {
+        let masked = encoded
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if SECRET_FIELDS.contains(&key) => format!("{key}={MASKED}"),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        self.text(&masked)
+    }
+
+    pub fn url(&self, url: &str) -> String {
+        match url.split_once('?') {
+            Some((head, query)) => format!("{}?{}", self.text(head), self.form(query)),
+            None => self.text(url),
+        }
+    }
+
+    /// `Set-Cookie: NAME=VALUE; attrs` → VALUE 替换为 [`MASKED`]，属性原样保留。
+    pub fn set_cookie(&self, raw: &str) -> String {
+        let (pair, attrs) = match raw.split_once(';') {
+            Some((pair, attrs)) => (pair, Some(attrs)),
+            None => (raw, None),
+        };
+        let pair = match pair.split_once('=') {
+            Some((name, _)) => format!("{}={MASKED}", name.trim()),
+            None => pair.to_string(),
+        };
+        match attrs {
+            Some(attrs) => format!("{pair};{attrs}"),
+            None => pair,
+        }
+    }
+
+    /// 按字段语义脱敏一个已保留的响应头。
+    pub(crate) fn header(&self, name: &str, value: &str) -> String {
+        match name.to_ascii_lowercase().as_str() {
+            "set-cookie" => self.set_cookie(value),
+            "location" => self.url(value),
+            _ => self.text(value),
+        }
+    }
+}
+
+/// 是否录制该响应头。
+pub(crate) fn keep_response_header(name: &str) -> bool {
+    KEPT_RESPONSE_HEADERS
+        .iter()
+        .any(|kept| kept.eq_ignore_ascii_case(name))
+}
+
+/// 去掉易变参数（时间戳、纯数字键）后的查询串，用于回放匹配。
+pub(crate) fn normalize_query(query: &str) -> String {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let key = pair.split_once('=').map_or(*pair, |(key, _)| key);
+            !VOLATILE_QUERY_KEYS.contains(&key) && !key.chars().all(|c| c.is_ascii_digit())
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 录制时转发的真实上游地址：明文 HTTP 上游用 `http`，其余 `https`。
+pub(crate) fn upstream_origin(host: &str) -> String {
+    let scheme = if is_plain_http_upstream(host) {
+        "http"
+    } else {
+        "https"
+    };
+    format!("{scheme}://{host}")
+}
+
+/// 出站 Location 改写：绝对 https 地址（及明文 HTTP 上游）→ `{base}/{host}/..`；
+/// 站内绝对路径 → `{base}/{host}/..`。
+pub(crate) fn rewrite_location(location: &str, host: &str, base: &str) -> String {
+    let absolute = location.strip_prefix("https://").or_else(|| {
+        location
+            .strip_prefix("http://")
+            .filter(|rest| is_plain_http_upstream(upstream_host(rest)))
+    });
+    if let Some(rest) = absolute {
+        format!("{base}/{rest}")
+    } else if location.starts_with('/') && !location.starts_with("//") {
+        format!("{base}/{host}{location}")
+    } else {
+        location.to_string()
+    }
+}
+
+/// 出站 Set-Cookie 改写：所有上游共用回放主机，带 Domain 的 Cookie 去掉 Domain 并放宽到 `/`，
+/// 其余把 Path 收窄到 `/{host}` 前缀下，保持原有的按路径隔离。
+pub(crate) fn rewrite_set_cookie(raw: &str, host: &str) -> String {
+    let mut parts = raw.split(';').map(str::trim);
+    let pair = parts.next().unwrap_or_default().to_string();
+    let attrs: Vec<&str> = parts.filter(|attr| !attr.is_empty()).collect();
+    let attr_name = |attr: &str| {
+        attr.split_once('=')
+            .map_or(attr, |(name, _)| name)
+            .trim()
+            .to_ascii_lowercase()
+    };
+    let has_domain = attrs.iter().any(|attr| attr_name(attr) == "domain");
+    let path = attrs
+        .iter()
+        .find(|attr| attr_name(attr) == "path")
+        .and_then(|attr| attr.split_once('='))
+        .map(|(_, value)| value.trim().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    let path = if has_domain {
+        "/".to_string()
+    } else {
+        format!(
+            "/{host}{}",
+            if path.starts_with('/') {
+                path
+            } else {
+                format!("/{path}")
+            }
+        )
+    };
+
+    let mut out = vec![pair];
+    out.extend(
+        attrs
+            .into_iter()
+            .filter(|attr| !matches!(attr_name(attr).as_str(), "domain" | "path" | "secure"))
+            .map(str::to_string),
+    );
+    out.push(format!("Path={path}"));
+    out.join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grade::domain::GradeRecord;
+    use crate::grade::service::{GradeCacheStore, GradeService};
+    use crate::http_client::HbutClient;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::RwLock;
+
+    const LOGIN_SYNC_GRADES: &str =
+        include_str!("../../../tests/fixtures/http/login_sync_grades.json");
+
+    fn exchange(method: &str, host: &str, path: &str, query: &str) -> FixtureExchange {
+        FixtureExchange {
+            method: method.to_string(),
+            host: host.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            request_body: None,
+            status: 200,
+            headers: Vec::new(),
+            body: String::new(),
+            body_base64: false,
+        }
+    }
+
+    #[test]
+    fn upstream_url_keeps_host_in_path() {
+        let mut client = HbutClient::without_snapshot();
+        let url = "https://auth.hbut.edu.cn/authserver/login?service=x";
+        assert_eq!(client.upstream_url(url), url);
+
+        client.set_upstream_base(Some("http://127.0.0.1:9/".to_string()));
+        assert_eq!(
+            client.upstream_url(url),
+            "http://127.0.0.1:9/auth.hbut.edu.cn/authserver/login?service=x"
+        );
+        assert_eq!(
+            client.upstream_url("http://1.94.167.18:5080/api"),
+            "http://1.94.167.18:5080/api"
+        );
+        assert_eq!(
+            client.upstream_url("http://172.16.54.20:9000/api/login"),
+            "http://127.0.0.1:9/172.16.54.20:9000/api/login"
+        );
+        assert_eq!(
+            rewrite_location(
+                "http://172.16.54.20:9000/#/home",
+                "auth.hbut.edu.cn",
+                "http://127.0.0.1:9"
+            ),
+            "http://127.0.0.1:9/172.16.54.20:9000/#/home"
+        );
+        assert_eq!(
+            upstream_origin("172.16.54.20:9000"),
+            "http://172.16.54.20:9000"
+        );
+        assert_eq!(
+            upstream_origin("auth.hbut.edu.cn"),
+            "https://auth.hbut.edu.cn"
+        );
+    }
+
+    #[test]
+    fn sanitizer_masks_cookies_secrets_and_identities() {
+        let sanitizer = FixtureSanitizer::new()
+            .mask_identity("2023123456")
+            .mask_identity("张三丰");
+
+        assert_eq!(
+            sanitizer.set_cookie("CASTGC=TGT-12345-abcdef; Path=/authserver; HttpOnly"),
+            "CASTGC=***; Path=/authserver; HttpOnly"
+        );
+        assert_eq!(
+            sanitizer.form("username=2023123456&password=secretpw&execution=e1s1"),
+            "username=20****56&password=***&execution=e1s1"
+        );
+        assert_eq!(
+            sanitizer.url("https://jwxt.hbut.edu.cn/admin/?loginType=1&ticket=ST-998877"),
+            "https://jwxt.hbut.edu.cn/admin/?loginType=1&ticket=***"
+        );
+        assert_eq!(
+            sanitizer.text(r#"<input id="xm" value="张三丰">"#),
+            r#"<input id="xm" value="****">"#
+        );
+        assert!(keep_response_header("Set-Cookie"));
+        assert!(!keep_response_header("Date"));
+    }
+
+    #[test]
+    fn outbound_headers_are_rewritten_under_host_prefix() {
+        let base = "http://127.0.0.1:9";
+        assert_eq!(
+            rewrite_location(
+                "https://jwxt.hbut.edu.cn/admin/?t=1",
+                "auth.hbut.edu.cn",
+                base
+            ),
+            "http://127.0.0.1:9/jwxt.hbut.edu.cn/admin/?t=1"
+        );
+        assert_eq!(
+            rewrite_location("/authserver/login", "auth.hbut.edu.cn", base),
+            "http://127.0.0.1:9/auth.hbut.edu.cn/authserver/login"
+        );
+        assert_eq!(
+            rewrite_set_cookie(
+                "CASTGC=x; Path=/authserver; Secure; HttpOnly",
+                "auth.hbut.edu.cn"
+            ),
+            "CASTGC=x; HttpOnly; Path=/auth.hbut.edu.cn/authserver"
+        );
+        assert_eq!(
+            rewrite_set_cookie(
+                "route=y; Domain=.hbut.edu.cn; Path=/admin",
+                "jwxt.hbut.edu.cn"
+            ),
+            "route=y; Path=/"
+        );
+        assert_eq!(
+            rewrite_set_cookie("JSESSIONID=z", "jwxt.hbut.edu.cn"),
+            "JSESSIONID=z; Path=/jwxt.hbut.edu.cn/"
+        );
+    }
+
+    #[test]
+    fn replay_matching_ignores_volatile_query_and_consumes_in_order() {
+        let mut cassette = FixtureCassette::new("match");
+        cassette.exchanges.push(exchange(
+            "GET",
+            "a.example",
+            "/list",
+            "xnxq=2024-2025-1&_=1",
+        ));
+        cassette.exchanges.push(exchange(
+            "GET",
+            "a.example",
+            "/list",
+            "xnxq=2024-2025-2&_=2",
+        ));
+        cassette
+            .exchanges
+            .push(exchange("GET", "a.example", "/captcha", "1700000000000"));
+        let mut used = vec![false; cassette.exchanges.len()];
+
+        let hit = cassette.next_match(&used, "get", "a.example", "/list", "xnxq=2024-2025-2&_=9");
+        assert_eq!(hit, Some(1));
+        used[1] = true;
+        assert_eq!(
+            cassette.next_match(&used, "GET", "a.example", "/captcha", "1800000000000"),
+            Some(2)
+        );
+        // 查询串不一致时退回同路径的下一条未使用记录
+        assert_eq!(
+            cassette.next_match(&used, "GET", "a.example", "/list", "xnxq=other"),
+            Some(0)
+        );
+        used[0] = true;
+        assert_eq!(
+            cassette.next_match(&used, "GET", "a.example", "/list", ""),
+            None
+        );
+        assert_eq!(
+            cassette.next_match(&used, "POST", "a.example", "/captcha", ""),
+            None
+        );
+    }
+
+    #[derive(Default)]
+    struct MemoryGradeCache {
+        grades: Mutex<HashMap<String, Value>>,
+        teachers: Mutex<HashMap<String, HashMap<String, String>>>,
+    }
+
+    impl GradeCacheStore for MemoryGradeCache {
+        fn load_grades(&self, uid: &str) -> Option<(Value, String)> {
+            let grades = self.grades.lock().unwrap();
+            grades.get(uid).map(|v| (v.clone(), "now".to_string()))
+        }
+
+        fn save_grades(&self, uid: &str, payload: &Value) -> Result<(), String> {
+            let mut grades = self.grades.lock().unwrap();
+            grades.insert(uid.to_string(), payload.clone());
+            Ok(())
+        }
+
+        fn load_teacher_cache(&self, uid: &str) -> Option<Value> {
+            let teachers = self.teachers.lock().unwrap();
+            teachers
+                .get(uid)
+                .map(|by_kcbh| serde_json::json!({ "by_kcbh": by_kcbh }))
+        }
+
+        fn save_teacher_cache(
+            &self,
+            uid: &str,
+            _semester: &str,
+            courses: &[(String, String)],
+        ) -> Result<Value, String> {
+            let mut teachers = self.teachers.lock().unwrap();
+            let entry = teachers.entry(uid.to_string()).or_default();
+            entry.extend(courses.iter().cloned());
+            Ok(serde_json::json!({ "by_kcbh": entry.clone() }))
+        }
+
+        fn load_change_snapshot(&self, _uid: &str) -> Option<Vec<GradeRecord>> {
+            None
+        }
+
+        fn save_change_snapshot(&self, _uid: &str, _grades: &[GradeRecord]) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// 构造指向 fixture 服务的空白客户端（不读写全局 Cookie 快照文件）。
+    fn replay_client(base: &str) -> HbutClient {
+        let mut client = HbutClient::without_snapshot();
+        client.set_cookie_file_snapshot(false);
+        client.set_upstream_base(Some(base.to_string()));
+        client
+    }
+
+    #[tokio::test]
+    async fn login_sync_grades_and_teacher_enrichment_replay_offline() {
+        let cassette = FixtureCassette::from_json(LOGIN_SYNC_GRADES).expect("fixture");
+        let server = FixtureServer::replay(cassette)
+            .await
+            .expect("fixture server");
+
+        let mut client = replay_client(server.base_url());
+        let user = client
+            .login("20****56", "fixture-password", "", "", "")
+            .await
+            .expect("login replay");
+        assert_eq!(user.student_id, "20****56");
+        assert_eq!(user.student_name, "****");
+
+        let service = GradeService::new(Arc::new(RwLock::new(client)), MemoryGradeCache::default());
+        let synced = service
+            .sync_grades(Some(&user.student_id), false)
+            .await
+            .expect("sync_grades replay");
+        assert_eq!(synced.payload["offline"], Value::Bool(false));
+        assert_eq!(synced.payload["data"].as_array().map(Vec::len), Some(2));
+
+        let job = synced.enrichment.expect("enrichment job");
+        assert_eq!(job.semesters, vec!["2024-2025-1", "2024-2025-2"]);
+        let results = service.enrich_teachers(job).await;
+        assert!(results.iter().all(|(_, outcome)| outcome.is_ok()));
+        let teachers = service
+            .read_teacher_cache(&user.student_id)
+            .expect("teacher cache");
+        assert_eq!(teachers["by_kcbh"]["K0001"], "李**");
+        assert_eq!(teachers["by_kcbh"]["K0002"], "王**");
+
+        assert!(
+            server.unused().is_empty(),
+            "未回放的 fixture: {:?}",
+            server.unused()
+        );
+    }
+
+    /// 录制真实链路：需校园网与真实账号，默认忽略。
+    ///
+    /// `HBUT_FIXTURE_USERNAME=... HBUT_FIXTURE_PASSWORD=... [HBUT_FIXTURE_NAME=...]
+    /// cargo test --lib record_login_sync_grades -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn record_login_sync_grades() {
+        let username = std::env::var("HBUT_FIXTURE_USERNAME").expect("HBUT_FIXTURE_USERNAME");
+        let password = std::env::var("HBUT_FIXTURE_PASSWORD").expect("HBUT_FIXTURE_PASSWORD");
+        let mut sanitizer = FixtureSanitizer::new().mask_identity(&username);
+        if let Ok(name) = std::env::var("HBUT_FIXTURE_NAME") {
+            sanitizer = sanitizer.mask_identity(&name);
+        }
+        let server = FixtureServer::record("login_sync_grades", sanitizer)
+            .await
+            .expect("fixture server");
+
+        let mut client = replay_client(server.base_url());
+        let user = client
+            .login(&username, &password, "", "", "")
+            .await
+            .expect("login");
+        let service = GradeService::new(Arc::new(RwLock::new(client)), MemoryGradeCache::default());
+        let synced = service
+            .sync_grades(Some(&user.student_id), false)
+            .await
+            .expect("sync_grades");
+        if let Some(job) = synced.enrichment {
+            service.enrich_teachers(job).await;
+        }
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/http/login_sync_grades.recorded.json");
+        server.cassette().save(&path).expect("save fixture");
+        println!(
+            "fixture 已写入 {}，检查脱敏结果后再替换已提交版本",
+            path.display()
+        );
+    }
+}
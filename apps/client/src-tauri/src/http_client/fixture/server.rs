@@ -0,0 +1,416 @@
+//! fixture 本地服务：极简 HTTP/1.1（每连接一个请求、`Connection: close`），
+//! 回放模式按 [`FixtureCassette`] 应答，录制模式转发到真实上游并记录脱敏结果。
+
+use base64::Engine;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{
+    keep_response_header, rewrite_location, rewrite_set_cookie, upstream_origin, FixtureCassette,
+    FixtureExchange, FixtureSanitizer,
+};
+use crate::http_client::upstream_host;
+
+/// 请求头上限，防止异常客户端把测试进程拖死。
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+enum Mode {
+    Replay {
+        cassette: FixtureCassette,
+        used: Vec<bool>,
+    },
+    Record {
+        upstream: reqwest::Client,
+        sanitizer: FixtureSanitizer,
+        cassette: FixtureCassette,
+    },
+}
+
+/// 本地 fixture 服务；drop 时停止监听。
+pub struct FixtureServer {
+    base_url: String,
+    mode: Arc<Mutex<Mode>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+struct IncomingRequest {
+    method: String,
+    host: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+struct OutgoingResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl FixtureServer {
+    /// 以回放模式启动（监听 127.0.0.1 随机端口）。
+    pub async fn replay(cassette: FixtureCassette) -> std::io::Result<Self> {
+        let used = vec![false; cassette.exchanges.len()];
+        Self::start(Mode::Replay { cassette, used }).await
+    }
+
+    /// 以录制模式启动：请求 `{base}/{host}/..` 被转发到 `https://{host}/..`
+    /// （明文 HTTP 上游为 `http://{host}/..`）。
+    pub async fn record(
+        name: impl Into<String>,
+        sanitizer: FixtureSanitizer,
+    ) -> std::io::Result<Self> {
+        let upstream = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(std::io::Error::other)?;
+        Self::start(Mode::Record {
+            upstream,
+            sanitizer,
+            cassette: FixtureCassette::new(name),
+        })
+        .await
+    }
+
+    async fn start(mode: Mode) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let mode = Arc::new(Mutex::new(mode));
+        let task = tokio::spawn({
+            let mode = Arc::clone(&mode);
+            let base_url = base_url.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let mode = Arc::clone(&mode);
+                    let base_url = base_url.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_connection(stream, &mode, &base_url).await {
+                            eprintln!("[fixture] 连接处理失败: {e}");
+                        }
+                    });
+                }
+            }
+        });
+        Ok(Self {
+            base_url,
+            mode,
+            task,
+        })
+    }
+
+    /// 供 [`HbutClient::set_upstream_base`](crate::http_client::HbutClient::set_upstream_base) 使用的根地址。
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 回放模式下尚未被请求的往返（`METHOD host/path?query`），用于断言链路完整走完。
+    pub fn unused(&self) -> Vec<String> {
+        match &*self.mode.lock().unwrap() {
+            Mode::Replay { cassette, used } => cassette
+                .exchanges
+                .iter()
+                .zip(used)
+                .filter(|(_, used)| !**used)
+                .map(|(ex, _)| format!("{} {}{}?{}", ex.method, ex.host, ex.path, ex.query))
+                .collect(),
+            Mode::Record { .. } => Vec::new(),
+        }
+    }
+
+    /// 当前（已录制 / 正在回放的）fixture。
+    pub fn cassette(&self) -> FixtureCassette {
+        match &*self.mode.lock().unwrap() {
+            Mode::Replay { cassette, .. } | Mode::Record { cassette, .. } => cassette.clone(),
+        }
+    }
+}
+
+impl Drop for FixtureServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve_connection(
+    mut stream: TcpStream,
+    mode: &Mutex<Mode>,
+    base_url: &str,
+) -> std::io::Result<()> {
+    let response = match read_request(&mut stream).await? {
+        Some(request) => respond(request, mode, base_url).await,
+        None => text_response(400, "fixture: 无法解析请求"),
+    };
+    write_response(&mut stream, response).await
+}
+
+async fn respond(request: IncomingRequest, mode: &Mutex<Mode>, base_url: &str) -> OutgoingResponse {
+    let forward = {
+        let mut mode = mode.lock().unwrap();
+        match &mut *mode {
+            Mode::Replay { cassette, used } => {
+                return match cassette.next_match(
+                    used,
+                    &request.method,
+                    &request.host,
+                    &request.path,
+                    &request.query,
+                ) {
+                    Some(index) => {
+                        used[index] = true;
+                        replay_response(&cassette.exchanges[index], base_url)
+                    }
+                    None => text_response(
+                        404,
+                        &format!(
+                            "fixture 未录制: {} {}{}?{}",
+                            request.method, request.host, request.path, request.query
+                        ),
+                    ),
+                };
+            }
+            Mode::Record {
+                upstream,
+                sanitizer,
+                ..
+            } => (upstream.clone(), sanitizer.clone()),
+        }
+    };
+
+    let (upstream, sanitizer) = forward;
+    match forward_upstream(&upstream, &request, base_url).await {
+        Ok((exchange, response)) => {
+            let exchange = sanitize_exchange(exchange, &sanitizer);
+            if let Mode::Record { cassette, .. } = &mut *mode.lock().unwrap() {
+                cassette.exchanges.push(exchange);
+            }
+            let headers = outbound_headers(&response.headers, &request.host, base_url);
+            OutgoingResponse {
+                headers,
+                ..response
+            }
+        }
+        Err(e) => text_response(502, &format!("fixture 转发失败: {e}")),
+    }
+}
+
+fn replay_response(exchange: &FixtureExchange, base_url: &str) -> OutgoingResponse {
+    OutgoingResponse {
+        status: exchange.status,
+        headers: outbound_headers(&exchange.headers, &exchange.host, base_url),
+        body: exchange.body_bytes(),
+    }
+}
+
+fn outbound_headers(
+    headers: &[(String, String)],
+    host: &str,
+    base_url: &str,
+) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = match name.to_ascii_lowercase().as_str() {
+                "location" => rewrite_location(value, host, base_url),
+                "set-cookie" => rewrite_set_cookie(value, host),
+                _ => value.clone(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// 转发到真实上游，返回未脱敏的往返记录与原始响应（响应头为真实上游值）。
+async fn forward_upstream(
+    upstream: &reqwest::Client,
+    request: &IncomingRequest,
+    base_url: &str,
+) -> Result<(FixtureExchange, OutgoingResponse), reqwest::Error> {
+    let mut url = format!("{}{}", upstream_origin(&request.host), request.path);
+    if !request.query.is_empty() {
+        url = format!("{url}?{}", request.query);
+    }
+    let method =
+        reqwest::Method::from_bytes(request.method.as_bytes()).unwrap_or(reqwest::Method::GET);
+    let mut builder = upstream.request(method, &url);
+    for (name, value) in &request.headers {
+        if matches!(
+            name.to_ascii_lowercase().as_str(),
+            "host" | "connection" | "content-length" | "accept-encoding"
+        ) {
+            continue;
+        }
+        // Referer / Origin 还原成真实上游地址
+        let value = match value.strip_prefix(&format!("{base_url}/")) {
+            Some(rest) => {
+                let host = upstream_host(rest);
+                format!("{}{}", upstream_origin(host), &rest[host.len()..])
+            }
+            None => value.clone(),
+        };
+        builder = builder.header(name.as_str(), value);
+    }
+    let response = builder.body(request.body.clone()).send().await?;
+
+    let status = response.status().as_u16();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .filter(|(name, _)| keep_response_header(name.as_str()))
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect();
+    let body = response.bytes().await?.to_vec();
+    let (text, body_base64) = match String::from_utf8(body.clone()) {
+        Ok(text) => (text, false),
+        Err(_) => (
+            base64::engine::general_purpose::STANDARD.encode(&body),
+            true,
+        ),
+    };
+    let exchange = FixtureExchange {
+        method: request.method.clone(),
+        host: request.host.clone(),
+        path: request.path.clone(),
+        query: request.query.clone(),
+        request_body: (!request.body.is_empty())
+            .then(|| String::from_utf8_lossy(&request.body).into_owned()),
+        status,
+        headers: headers.clone(),
+        body: text,
+        body_base64,
+    };
+    Ok((
+        exchange,
+        OutgoingResponse {
+            status,
+            headers,
+            body,
+        },
+    ))
+}
+
+fn sanitize_exchange(exchange: FixtureExchange, sanitizer: &FixtureSanitizer) -> FixtureExchange {
+    FixtureExchange {
+        path: sanitizer.text(&exchange.path),
+        query: sanitizer.form(&exchange.query),
+        request_body: exchange.request_body.as_deref().map(|b| sanitizer.form(b)),
+        headers: exchange
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), sanitizer.header(name, value)))
+            .collect(),
+        body: if exchange.body_base64 {
+            exchange.body.clone()
+        } else {
+            sanitizer.text(&exchange.body)
+        },
+        ..exchange
+    }
+}
+
+fn text_response(status: u16, message: &str) -> OutgoingResponse {
+    OutgoingResponse {
+        status,
+        headers: vec![(
+            "Content-Type".to_string(),
+            "text/plain; charset=utf-8".to_string(),
+        )],
+        body: message.as_bytes().to_vec(),
+    }
+}
+
+/// 读取一个请求；目标形如 `/{host}/{path}?{query}`。不支持 chunked 请求体。
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<IncomingRequest>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Ok(None);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    if header("transfer-encoding").is_some() {
+        return Ok(None);
+    }
+    let content_length: usize = header("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let mut body = buf[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    let (path_part, query) = target.split_once('?').unwrap_or((target, ""));
+    let Some((host, rest)) = path_part
+        .strip_prefix('/')
+        .map(|p| p.split_once('/').unwrap_or((p, "")))
+    else {
+        return Ok(None);
+    };
+    if host.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(IncomingRequest {
+        method: method.to_string(),
+        host: host.to_string(),
+        path: format!("/{rest}"),
+        query: query.to_string(),
+        headers,
+        body,
+    }))
+}
+
+async fn write_response(stream: &mut TcpStream, response: OutgoingResponse) -> std::io::Result<()> {
+    let reason = reqwest::StatusCode::from_u16(response.status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+    let mut head = format!("HTTP/1.1 {} {}\r\n", response.status, reason);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    ));
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
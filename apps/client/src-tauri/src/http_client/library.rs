@@ -6,6 +6,15 @@ const OPAC_BASE_URL: &str = "https://opac.hbut.edu.cn:8013";
 const OPAC_SERVICE_URL: &str = "https://opac.hbut.edu.cn:8013/";
 const BOOKCOVERS_CLIENT_ID: &str = "800512";
 
+// 读者中心（我的图书馆）接口：依赖 OPAC CAS 会话，未登录时返回 401 或 success=false。
+const OPAC_READER_LOANS_PATH: &str = "/find/loanInfo/loanList";
+const OPAC_READER_LOAN_HISTORY_PATH: &str = "/find/loanInfo/loanHistoryList";
+const OPAC_READER_HOLDS_PATH: &str = "/find/reservation/reservationList";
+const OPAC_READER_FINES_PATH: &str = "/find/fine/fineList";
+const OPAC_READER_RENEW_PATH: &str = "/find/loanInfo/renew";
+/// 读者中心列表单页上限：在借 / 预约 / 欠款一次取完。
+const OPAC_READER_ROWS: i64 = 100;
+
 fn with_opac_headers(builder: RequestBuilder) -> RequestBuilder {
     builder
         .header("Accept", "application/json, text/plain, */*")
@@ -40,6 +49,20 @@ fn ensure_opac_success(
     Err(format!("{}失败: {}", action, message).into())
 }
 
+/// 读者中心接口是否因未建立 OPAC 登录态而失败。
+fn is_opac_login_required(payload: &Value) -> bool {
+    if payload
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return false;
+    }
+    let code = to_string_or(payload.get("errorCode"), "");
+    let message = to_string_or(payload.get("message"), "");
+    matches!(code.as_str(), "401" | "403") || message.contains("登录")
+}
+
 fn to_array(input: Option<&Value>) -> Value {
     match input {
         Some(Value::Array(arr)) => Value::Array(arr.clone()),
@@ -208,7 +231,7 @@ impl HbutClient {
     async fn ensure_opac_session(
         &mut self,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/", OPAC_BASE_URL);
+        let url = self.upstream_url(&format!("{}/", OPAC_BASE_URL));
         let response = self.client.get(url).send().await?;
         if !response.status().is_success() {
             return Err(format!("初始化图书检索会话失败: {}", response.status()).into());
@@ -223,11 +246,11 @@ impl HbutClient {
     async fn establish_opac_sso_session(
         &mut self,
     ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        let cas_url = format!(
+        let cas_url = self.upstream_url(&format!(
             "{}/login?service={}",
             AUTH_BASE_URL,
             urlencoding::encode(OPAC_SERVICE_URL)
-        );
+        ));
 
         let check_resp = self.client.get(&cas_url).send().await?;
         let check_url = check_resp.url().to_string();
@@ -247,7 +270,11 @@ impl HbutClient {
             println!("[调试] 图书会话兜底：CAS 会话可用，直接建立 OPAC 会话");
         }
 
-        let final_resp = self.client.get(OPAC_SERVICE_URL).send().await?;
+        let final_resp = self
+            .client
+            .get(self.upstream_url(OPAC_SERVICE_URL))
+            .send()
+            .await?;
         if !final_resp.status().is_success() {
             return Err(format!("建立 OPAC 会话失败: {}", final_resp.status()).into());
         }
@@ -259,7 +286,7 @@ impl HbutClient {
         path: &str,
         payload: &Value,
     ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}{}", OPAC_BASE_URL, path);
+        let url = self.upstream_url(&format!("{}{}", OPAC_BASE_URL, path));
         let response = with_opac_headers(self.client.post(url).json(payload))
             .send()
             .await?;
@@ -280,7 +307,7 @@ impl HbutClient {
         path: &str,
         query: &[(String, String)],
     ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}{}", OPAC_BASE_URL, path);
+        let url = self.upstream_url(&format!("{}{}", OPAC_BASE_URL, path));
         let response = with_opac_headers(self.client.get(url).query(query))
             .send()
             .await?;
@@ -296,6 +323,69 @@ impl HbutClient {
             .map_err(|e| format!("图书 GET 接口响应解析失败: {}", e).into())
     }
 
+    /// 读者中心单次请求；服务端明确要求登录（401 / 403 或提示未登录）时返回 `Ok(None)`。
+    async fn post_opac_reader_once(
+        &self,
+        path: &str,
+        payload: &Value,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let url = self.upstream_url(&format!("{}{}", OPAC_BASE_URL, path));
+        let response = with_opac_headers(self.client.post(url).json(payload))
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if matches!(
+            status,
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+        ) {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let preview = body.chars().take(200).collect::<String>();
+            return Err(format!("图书接口请求失败({}): {}", status, preview).into());
+        }
+
+        let result = serde_json::from_str::<Value>(&body)
+            .map_err(|e| format!("图书接口响应解析失败: {}", e))?;
+        Ok((!is_opac_login_required(&result)).then_some(result))
+    }
+
+    /// 读者中心请求：只有服务端明确要求登录（请求未被受理）时才经 OPAC SSO 重建会话后重试一次；
+    /// 传输层失败不重试，续借等写操作可能已在服务端生效。
+    async fn post_opac_reader_json(
+        &mut self,
+        path: &str,
+        payload: &Value,
+        action: &str,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        self.ensure_opac_session().await?;
+        if let Some(result) = self.post_opac_reader_once(path, payload).await? {
+            ensure_opac_success(&result, action)?;
+            return Ok(result);
+        }
+
+        let expired = || format!("{}失败: 图书馆会话已失效，请重新登录", action).into();
+        match self.establish_opac_sso_session().await {
+            Ok(true) => {}
+            Ok(false) => {
+                println!("[调试] {}重试跳过：无可用凭据", action);
+                return Err(expired());
+            }
+            Err(err) => {
+                println!("[警告] {}重建 OPAC 会话失败：{}", action, err);
+                return Err(expired());
+            }
+        }
+        let retry = self
+            .post_opac_reader_once(path, payload)
+            .await?
+            .ok_or_else(expired)?;
+        ensure_opac_success(&retry, action)?;
+        Ok(retry)
+    }
+
     pub async fn fetch_library_dict(
         &mut self,
     ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
@@ -482,4 +572,52 @@ impl HbutClient {
             "cover_url": cover_url
         }))
     }
+
+    /// 当前借阅（含应还日期）；返回 OPAC 原始 JSON，由 `modules::library` 归一化。
+    pub async fn fetch_library_current_loans(
+        &mut self,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let payload = json!({ "page": 1, "rows": OPAC_READER_ROWS });
+        self.post_opac_reader_json(OPAC_READER_LOANS_PATH, &payload, "获取当前借阅")
+            .await
+    }
+
+    pub async fn fetch_library_loan_history(
+        &mut self,
+        page: i64,
+        rows: i64,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let payload = json!({ "page": page.max(1), "rows": rows.clamp(1, OPAC_READER_ROWS) });
+        self.post_opac_reader_json(OPAC_READER_LOAN_HISTORY_PATH, &payload, "获取借阅历史")
+            .await
+    }
+
+    pub async fn fetch_library_holds(
+        &mut self,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let payload = json!({ "page": 1, "rows": OPAC_READER_ROWS });
+        self.post_opac_reader_json(OPAC_READER_HOLDS_PATH, &payload, "获取预约记录")
+            .await
+    }
+
+    pub async fn fetch_library_fines(
+        &mut self,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let payload = json!({ "page": 1, "rows": OPAC_READER_ROWS });
+        self.post_opac_reader_json(OPAC_READER_FINES_PATH, &payload, "获取欠款记录")
+            .await
+    }
+
+    /// 按条码批量续借；逐册结果在响应 `data` 中（格式由 `modules::library` 容错解析）。
+    pub async fn renew_library_loans(
+        &mut self,
+        barcodes: &[String],
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        if barcodes.is_empty() {
+            return Err("barcodes are required".into());
+        }
+        let payload = json!({ "barcodes": barcodes });
+        self.post_opac_reader_json(OPAC_READER_RENEW_PATH, &payload, "续借")
+            .await
+    }
 }
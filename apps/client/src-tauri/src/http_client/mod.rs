@@ -57,6 +57,8 @@ mod academic;
 mod ai;
 mod auth;
 mod electricity;
+#[cfg(any(test, feature = "testing"))]
+pub mod fixture;
 mod library;
 mod qxzkb;
 mod session;
@@ -70,6 +72,8 @@ pub(super) const AUTH_BASE_URL: &str = "https://auth.hbut.edu.cn/authserver";
 pub(super) const JWXT_BASE_URL: &str = "https://jwxt.hbut.edu.cn";
 pub(super) const CHAOXING_JWXT_BASE_URL: &str = "https://hbut.jw.chaoxing.com";
 pub(super) const TARGET_SERVICE: &str = "https://jwxt.hbut.edu.cn/admin/?loginType=1";
+/// 只提供明文 HTTP 的校内上游（`host:port`），上游覆盖时与 https 地址一样改写。
+pub(crate) const PLAIN_HTTP_UPSTREAMS: &[&str] = &["172.16.54.20:9000"];
 pub(super) const DEFAULT_REMOTE_OCR_ENDPOINT: &str =
     "https://mini-hbut-testocr1.hf.space/api/ocr/recognize";
 pub(super) const DEFAULT_OCR_ENDPOINT: &str = "http://1.94.167.18:5080/api/ocr/recognize";
@@ -86,6 +90,16 @@ pub(super) const LOGIN_COOLDOWN: std::time::Duration = std::time::Duration::from
 /// 仅用于避免连点刷屏，远小于 60s 登录冷却，绝不把用户锁死（#659 根因 5）。
 pub(super) const TRANSPORT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
 
+/// 去掉协议后的 `host[:port]/rest` → `host[:port]`。
+pub(crate) fn upstream_host(rest: &str) -> &str {
+    rest.split(['/', '?', '#']).next().unwrap_or_default()
+}
+
+/// `host[:port]` 是否登记为明文 HTTP 上游。
+pub(crate) fn is_plain_http_upstream(host: &str) -> bool {
+    PLAIN_HTTP_UPSTREAMS.contains(&host)
+}
+
 /// 客户端错误分类（风控与错误判定用，不改变对外字符串文案）。
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpClientErrorKind {
@@ -138,7 +152,7 @@ impl std::error::Error for HttpClientError {}
 
 /// 判定错误是否为「传输层失败」（未收到认证服务器真实响应）。
 /// 识别 `HttpClientError::Transport` 与原生 `reqwest::Error`（connect/timeout/request/body）。
-pub(super) fn is_transport_error(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+pub(crate) fn is_transport_error(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
     if let Some(e) = err.downcast_ref::<HttpClientError>() {
         return e.kind() == HttpClientErrorKind::Transport;
     }
@@ -346,6 +360,12 @@ pub struct HbutClient {
     pub(super) last_relogin_attempt: Option<std::time::Instant>,
     pub(super) last_relogin_failed_at: Option<std::time::Instant>,
     pub(super) prefer_chaoxing_jwxt: bool,
+    /// 是否读写全局 Cookie 快照文件；多账号下只有活动账号写文件，
+    /// 其余账号只落 auth_cookie_v2（按学号隔离），避免互相覆盖。
+    pub(super) cookie_file_snapshot: bool,
+    /// 上游根地址覆盖（fixture 回放 / 录制用）：设置后 `https://host/path`
+    /// 改写为 `{base}/host/path`，见 [`HbutClient::upstream_url`]；生产为 `None`。
+    pub(super) upstream_base: Option<String>,
 }
 
 impl HbutClient {
@@ -381,6 +401,11 @@ impl HbutClient {
         self.prefer_chaoxing_jwxt = enabled;
     }
 
+    /// 当前是否处于“学习通教务域名优先”模式（学习通登录）。
+    pub(crate) fn chaoxing_login_mode(&self) -> bool {
+        self.prefer_chaoxing_jwxt
+    }
+
     /// 供业务模块发起教务域 HTTP 请求（如学校消息抓取）。
     pub(crate) fn http_client(&self) -> &Client {
         &self.client
@@ -454,11 +479,18 @@ impl HbutClient {
 
     /// 创建默认客户端并加载历史会话快照
     pub fn new() -> Self {
+        let mut instance = Self::without_snapshot();
+        instance.load_cookie_snapshot_from_file();
+        instance
+    }
+
+    /// 创建不加载 Cookie 快照文件的空白客户端（多账号切换 / 新账号登录用）。
+    pub(crate) fn without_snapshot() -> Self {
         let jar = Arc::new(Jar::default());
         let client = Self::build_http_client(Arc::clone(&jar));
         let ocr_client = Self::build_ocr_client();
 
-        let mut instance = Self {
+        Self {
             client,
             ocr_client,
             cookie_jar: jar,
@@ -489,9 +521,48 @@ impl HbutClient {
             last_relogin_attempt: None,
             last_relogin_failed_at: None,
             prefer_chaoxing_jwxt: false,
-        };
-        instance.load_cookie_snapshot_from_file();
-        instance
+            cookie_file_snapshot: true,
+            upstream_base: None,
+        }
+    }
+
+    /// 开关全局 Cookie 快照文件读写（见 `cookie_file_snapshot` 字段）。
+    pub(crate) fn set_cookie_file_snapshot(&mut self, enabled: bool) {
+        self.cookie_file_snapshot = enabled;
+    }
+
+    /// 沿用另一客户端的 OCR 运行配置（账号切换时新活动客户端继承前台设置）。
+    pub(crate) fn inherit_runtime_config(&mut self, other: &HbutClient) {
+        self.ocr_endpoint = other.ocr_endpoint.clone();
+        self.ocr_remote_endpoints = other.ocr_remote_endpoints.clone();
+        self.ocr_local_fallback_endpoints = other.ocr_local_fallback_endpoints.clone();
+        self.upstream_base = other.upstream_base.clone();
+    }
+
+    /// 把上游请求指向本地 fixture 服务（`None` 恢复直连）。
+    ///
+    /// 仅覆盖已接入 [`HbutClient::upstream_url`] 的链路（CAS 登录、教务用户信息、
+    /// 成绩与任课教师、图书馆、学习通、雨课堂、体育场馆）。
+    pub fn set_upstream_base(&mut self, base: Option<String>) {
+        self.upstream_base = base
+            .map(|b| b.trim().trim_end_matches('/').to_string())
+            .filter(|b| !b.is_empty());
+    }
+
+    /// 按上游覆盖改写请求地址：`https://host[:port]/rest` → `{base}/host[:port]/rest`。
+    ///
+    /// 主机名保留在路径中，`final_url.contains("authserver/login")` 之类的判定在回放时
+    /// 依旧成立；[`PLAIN_HTTP_UPSTREAMS`] 中的 http 地址同样改写，其余非 https 地址与
+    /// 未设置覆盖时原样返回。
+    pub(crate) fn upstream_url(&self, url: &str) -> String {
+        let rest = url.strip_prefix("https://").or_else(|| {
+            url.strip_prefix("http://")
+                .filter(|rest| is_plain_http_upstream(upstream_host(rest)))
+        });
+        match (&self.upstream_base, rest) {
+            (Some(base), Some(rest)) => format!("{}/{}", base, rest),
+            _ => url.to_string(),
+        }
     }
 
     /// 设置 OCR 服务端点（允许为空，空则走默认配置）
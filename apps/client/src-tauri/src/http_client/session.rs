@@ -545,7 +545,10 @@ impl HbutClient {
     pub async fn fetch_user_info(
         &self,
     ) -> Result<UserInfo, Box<dyn std::error::Error + Send + Sync>> {
-        let info_url = format!("{}/admin/xsd/xsjbxx/xskp", self.academic_base_url());
+        let info_url = self.upstream_url(&format!(
+            "{}/admin/xsd/xsjbxx/xskp",
+            self.academic_base_url()
+        ));
         println!("[调试] 获取用户信息： {}", info_url);
 
         let mut repaired = false;
@@ -578,7 +581,8 @@ impl HbutClient {
                             continue;
                         }
                     } else {
-                        let caslogin_url = format!("{}/admin/caslogin", super::JWXT_BASE_URL);
+                        let caslogin_url =
+                            self.upstream_url(&format!("{}/admin/caslogin", super::JWXT_BASE_URL));
                         println!("[调试] 用户信息请求命中登录页，尝试 /admin/caslogin 恢复");
                         let caslogin_resp = self.client.get(&caslogin_url).send().await;
                         if let Ok(resp) = caslogin_resp {
@@ -899,6 +903,34 @@ impl HbutClient {
         }
     }
 
+    /// 从按学号保存的会话（auth_cookie_v2 + 密钥环 + user_sessions）构造独立客户端，
+    /// 不读写全局快照文件；该学号没有已保存的 Cookie 时返回 `None`。
+    pub(crate) fn from_stored_account(user_info: UserInfo) -> Option<Self> {
+        let sid = user_info.student_id.trim().to_string();
+        let rows = crate::db::load_auth_cookies_for_student(crate::DB_FILENAME, &sid).ok()?;
+        if rows.is_empty() {
+            return None;
+        }
+        let mut client = Self::without_snapshot();
+        client.cookie_file_snapshot = false;
+        client.restore_auth_cookie_v2_rows(&rows);
+        if let Ok(Some(session)) = crate::db::get_user_session(crate::DB_FILENAME, &sid) {
+            if !session.one_code_token.is_empty() {
+                let expires_at = DateTime::parse_from_rfc3339(&session.token_expires_at)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc));
+                let refresh = Some(session.refresh_token).filter(|t| !t.trim().is_empty());
+                client.set_electricity_session(session.one_code_token, refresh, expires_at);
+            }
+        }
+        if let Some(password) = crate::credential_store::load_password(&sid) {
+            client.set_credentials(sid, password);
+        }
+        client.is_logged_in = true;
+        client.user_info = Some(user_info);
+        Some(client)
+    }
+
     fn restore_auth_cookie_v2_rows(&mut self, rows: &[crate::db::AuthCookieDomainRow]) {
         for row in rows {
             let host = row.domain.trim();
@@ -1044,6 +1076,9 @@ impl HbutClient {
 
     /// 将 Cookie 快照写入本地文件（tmp+rename 原子写，避免写一半被读取，见 #550）
     pub fn save_cookie_snapshot_to_file(&self) {
+        if !self.cookie_file_snapshot {
+            return;
+        }
         if let Some(path) = Self::cookie_snapshot_path() {
             let payload = self.get_cookie_snapshot();
             if let Ok(text) = serde_json::to_string(&payload) {
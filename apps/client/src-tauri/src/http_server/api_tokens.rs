@@ -0,0 +1,464 @@
+//! Bridge API 令牌：用户在应用内签发的限定权限、可过期令牌，供自动化脚本访问少量数据路由。
+//!
+//! - 与 Bridge 会话令牌 / `HBUT_BRIDGE_TOKEN`（全部权限）并存；明文带 `hbt_` 前缀，仅签发时返回一次，
+//!   库中只存 SHA-256 摘要（`bridge_api_tokens.token_hash`）
+//! - 每个令牌携带若干 [`BridgeScope`]；路由所需权限见 [`bridge_route_scope`]，未登记权限的路由
+//!   （登录、Cookie 导出、调试等）一律不接受此类令牌
+//! - 过期 / 吊销即时生效；最近使用时间按分钟粒度记录
+
+use base64::{engine::general_purpose, Engine as _};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::application::ApplicationError;
+use crate::db::{self, BridgeApiTokenRecord};
+
+/// 用户签发令牌的明文前缀，用于与会话令牌区分（无前缀的令牌不查库）。
+pub(crate) const TOKEN_PREFIX: &str = "hbt_";
+
+/// 缺省有效期（天）。
+const DEFAULT_TTL_DAYS: u32 = 30;
+
+/// 有效期上限（天）。
+const MAX_TTL_DAYS: u32 = 365;
+
+/// 令牌名称上限（字符）。
+const MAX_NAME_CHARS: usize = 32;
+
+/// 最近使用时间的最小写入间隔（秒）。
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// 令牌权限范围。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BridgeScope {
+    GradesRead,
+    ScheduleRead,
+    ScheduleWrite,
+    ElectricityRead,
+    OnlineLearningSync,
+}
+
+impl BridgeScope {
+    pub(crate) const ALL: [BridgeScope; 5] = [
+        BridgeScope::GradesRead,
+        BridgeScope::ScheduleRead,
+        BridgeScope::ScheduleWrite,
+        BridgeScope::ElectricityRead,
+        BridgeScope::OnlineLearningSync,
+    ];
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::GradesRead => "grades:read",
+            Self::ScheduleRead => "schedule:read",
+            Self::ScheduleWrite => "schedule:write",
+            Self::ElectricityRead => "electricity:read",
+            Self::OnlineLearningSync => "online_learning:sync",
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::GradesRead => "读取成绩与排名",
+            Self::ScheduleRead => "读取课表、考试与校历",
+            Self::ScheduleWrite => "增删改自定义课程、导入日历",
+            Self::ElectricityRead => "查询电费与关注房间",
+            Self::OnlineLearningSync => "查看并触发网课同步",
+        }
+    }
+
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        Self::ALL.into_iter().find(|scope| scope.as_str() == raw)
+    }
+}
+
+/// 接受用户签发令牌的路由及所需权限；其余路由一律拒绝此类令牌。
+pub(crate) const SCOPED_ROUTES: &[(&str, BridgeScope)] = &[
+    ("/sync_grades", BridgeScope::GradesRead),
+    ("/fetch_ranking", BridgeScope::GradesRead),
+    ("/degree_audit", BridgeScope::GradesRead),
+    ("/sync_schedule", BridgeScope::ScheduleRead),
+    ("/fetch_exams", BridgeScope::ScheduleRead),
+    ("/exam/countdown", BridgeScope::ScheduleRead),
+    ("/fetch_calendar_data", BridgeScope::ScheduleRead),
+    ("/schedule/custom/list", BridgeScope::ScheduleRead),
+    ("/schedule/custom/list_all", BridgeScope::ScheduleRead),
+    ("/schedule/conflicts", BridgeScope::ScheduleRead),
+    ("/schedule/free_slots", BridgeScope::ScheduleRead),
+    ("/schedule/plans/list", BridgeScope::ScheduleRead),
+    ("/schedule/plans/simulate", BridgeScope::ScheduleRead),
+    ("/schedule/plans/export_ics", BridgeScope::ScheduleRead),
+    ("/export_schedule_ics", BridgeScope::ScheduleRead),
+    ("/schedule/custom/add", BridgeScope::ScheduleWrite),
+    ("/schedule/custom/update", BridgeScope::ScheduleWrite),
+    ("/schedule/custom/delete", BridgeScope::ScheduleWrite),
+    ("/schedule/plans/save", BridgeScope::ScheduleWrite),
+    ("/schedule/plans/delete", BridgeScope::ScheduleWrite),
+    ("/import_schedule_ics", BridgeScope::ScheduleWrite),
+    ("/electricity_query_location", BridgeScope::ElectricityRead),
+    ("/electricity_query_account", BridgeScope::ElectricityRead),
+    ("/electricity/rooms", BridgeScope::ElectricityRead),
+    ("/online_learning/overview", BridgeScope::OnlineLearningSync),
+    ("/online_learning/sync_now", BridgeScope::OnlineLearningSync),
+    (
+        "/online_learning/sync_runs",
+        BridgeScope::OnlineLearningSync,
+    ),
+    (
+        "/online_learning/list_sync_runs",
+        BridgeScope::OnlineLearningSync,
+    ),
+];
+
+/// 路由所需权限；`None` 表示该路由不接受用户签发的令牌。
+pub(crate) fn bridge_route_scope(path: &str) -> Option<BridgeScope> {
+    SCOPED_ROUTES
+        .iter()
+        .find(|(route, _)| *route == path)
+        .map(|(_, scope)| *scope)
+}
+
+/// 签发令牌的请求（Tauri 入参）。
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct IssueBridgeTokenRequest {
+    pub(crate) name: String,
+    pub(crate) scopes: Vec<String>,
+    /// 有效期（天），缺省 30，上限 365
+    #[serde(default)]
+    pub(crate) expires_in_days: Option<u32>,
+}
+
+/// 令牌信息（不含明文与摘要）。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct BridgeApiToken {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) scopes: Vec<String>,
+    pub(crate) created_at: i64,
+    pub(crate) expires_at: i64,
+    pub(crate) revoked_at: Option<i64>,
+    pub(crate) last_used_at: Option<i64>,
+    /// `active` / `expired` / `revoked`
+    pub(crate) status: &'static str,
+}
+
+/// 令牌校验结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ScopedTokenCheck {
+    Valid { scopes: Vec<BridgeScope> },
+    Unknown,
+    Expired,
+    Revoked,
+}
+
+impl ScopedTokenCheck {
+    pub(crate) fn grants(&self, scope: BridgeScope) -> bool {
+        matches!(self, Self::Valid { scopes, .. } if scopes.contains(&scope))
+    }
+}
+
+/// 签发令牌，返回明文（仅此一次）与令牌信息。
+pub(crate) fn issue_bridge_token<P: AsRef<Path>>(
+    path: P,
+    req: &IssueBridgeTokenRequest,
+    now: i64,
+) -> Result<(String, BridgeApiToken), ApplicationError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApplicationError::validation("令牌名称不能为空"));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(ApplicationError::validation(format!(
+            "令牌名称不能超过 {MAX_NAME_CHARS} 个字符"
+        )));
+    }
+    let mut scopes: Vec<BridgeScope> = Vec::new();
+    for raw in &req.scopes {
+        let scope = BridgeScope::parse(raw)
+            .ok_or_else(|| ApplicationError::validation(format!("未知权限: {}", raw.trim())))?;
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    if scopes.is_empty() {
+        return Err(ApplicationError::validation("至少选择一个权限"));
+    }
+    let days = req.expires_in_days.unwrap_or(DEFAULT_TTL_DAYS);
+    if !(1..=MAX_TTL_DAYS).contains(&days) {
+        return Err(ApplicationError::validation(format!(
+            "有效期需在 1-{MAX_TTL_DAYS} 天之间"
+        )));
+    }
+
+    let mut bytes = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = format!(
+        "{TOKEN_PREFIX}{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    );
+    let mut id_bytes = [0_u8; 8];
+    rand::thread_rng().fill_bytes(&mut id_bytes);
+
+    let record = BridgeApiTokenRecord {
+        id: hex(&id_bytes),
+        name: name.to_string(),
+        token_hash: token_hash(&token),
+        scopes: scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        created_at: now,
+        expires_at: now + i64::from(days) * 86_400,
+        revoked_at: None,
+        last_used_at: None,
+    };
+    db::insert_bridge_api_token(path, &record)
+        .map_err(|e| ApplicationError::storage(e.to_string()))?;
+    Ok((token, token_info(&record, now)))
+}
+
+/// 列出全部令牌（含已过期 / 已吊销）。
+pub(crate) fn list_bridge_tokens<P: AsRef<Path>>(
+    path: P,
+    now: i64,
+) -> Result<Vec<BridgeApiToken>, ApplicationError> {
+    let records =
+        db::list_bridge_api_tokens(path).map_err(|e| ApplicationError::storage(e.to_string()))?;
+    Ok(records
+        .iter()
+        .map(|record| token_info(record, now))
+        .collect())
+}
+
+/// 吊销令牌；未知或已吊销返回 `false`。
+pub(crate) fn revoke_bridge_token<P: AsRef<Path>>(
+    path: P,
+    id: &str,
+    now: i64,
+) -> Result<bool, ApplicationError> {
+    let revoked = db::revoke_bridge_api_token(path, id.trim(), now)
+        .map_err(|e| ApplicationError::storage(e.to_string()))?;
+    Ok(revoked > 0)
+}
+
+/// 校验明文令牌；有效时记录最近使用时间。
+pub(crate) fn verify_bridge_token<P: AsRef<Path>>(
+    path: P,
+    token: &str,
+    now: i64,
+) -> Result<ScopedTokenCheck, String> {
+    let path = path.as_ref();
+    let token = token.trim();
+    if !token.starts_with(TOKEN_PREFIX) {
+        return Ok(ScopedTokenCheck::Unknown);
+    }
+    let Some(record) =
+        db::find_bridge_api_token_by_hash(path, &token_hash(token)).map_err(|e| e.to_string())?
+    else {
+        return Ok(ScopedTokenCheck::Unknown);
+    };
+    if record.revoked_at.is_some() {
+        return Ok(ScopedTokenCheck::Revoked);
+    }
+    if record.expires_at <= now {
+        return Ok(ScopedTokenCheck::Expired);
+    }
+    db::touch_bridge_api_token(path, &record.id, now, TOUCH_INTERVAL_SECS)
+        .map_err(|e| e.to_string())?;
+    Ok(ScopedTokenCheck::Valid {
+        scopes: record
+            .scopes
+            .split_whitespace()
+            .filter_map(BridgeScope::parse)
+            .collect(),
+    })
+}
+
+/// 可选权限列表（供设置页渲染）。
+pub(crate) fn scope_catalog() -> serde_json::Value {
+    BridgeScope::ALL
+        .iter()
+        .map(|scope| serde_json::json!({ "scope": scope.as_str(), "label": scope.label() }))
+        .collect()
+}
+
+fn token_info(record: &BridgeApiTokenRecord, now: i64) -> BridgeApiToken {
+    let status = if record.revoked_at.is_some() {
+        "revoked"
+    } else if record.expires_at <= now {
+        "expired"
+    } else {
+        "active"
+    };
+    BridgeApiToken {
+        id: record.id.clone(),
+        name: record.name.clone(),
+        scopes: record
+            .scopes
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        created_at: record.created_at,
+        expires_at: record.expires_at,
+        revoked_at: record.revoked_at,
+        last_used_at: record.last_used_at,
+        status,
+    }
+}
+
+fn token_hash(token: &str) -> String {
+    hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_api_tokens_{label}_{nanos}.db"))
+    }
+
+    fn request(scopes: &[&str], days: Option<u32>) -> IssueBridgeTokenRequest {
+        IssueBridgeTokenRequest {
+            name: " grades-export ".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            expires_in_days: days,
+        }
+    }
+
+    #[test]
+    fn issued_tokens_verify_until_expiry_or_revocation() {
+        let path = temp_db_path("lifecycle");
+        init_db(&path).unwrap();
+        let now = 1_700_000_000;
+
+        let (token, info) = issue_bridge_token(
+            &path,
+            &request(&["grades:read", "schedule:read", "grades:read"], Some(2)),
+            now,
+        )
+        .unwrap();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(info.name, "grades-export");
+        assert_eq!(info.scopes, vec!["grades:read", "schedule:read"]);
+        assert_eq!(info.expires_at, now + 2 * 86_400);
+        assert_eq!(info.status, "active");
+        // 库中只存摘要
+        let stored = db::list_bridge_api_tokens(&path).unwrap();
+        assert_ne!(stored[0].token_hash, token);
+
+        let check = verify_bridge_token(&path, &token, now + 10).unwrap();
+        assert!(check.grants(BridgeScope::GradesRead));
+        assert!(check.grants(BridgeScope::ScheduleRead));
+        assert!(!check.grants(BridgeScope::ScheduleWrite));
+        let listed = list_bridge_tokens(&path, now + 10).unwrap();
+        assert_eq!(listed[0].last_used_at, Some(now + 10));
+
+        assert_eq!(
+            verify_bridge_token(&path, &token, now + 2 * 86_400).unwrap(),
+            ScopedTokenCheck::Expired
+        );
+        assert_eq!(
+            list_bridge_tokens(&path, now + 2 * 86_400).unwrap()[0].status,
+            "expired"
+        );
+
+        assert!(revoke_bridge_token(&path, &info.id, now + 20).unwrap());
+        assert!(!revoke_bridge_token(&path, &info.id, now + 30).unwrap());
+        assert_eq!(
+            verify_bridge_token(&path, &token, now + 40).unwrap(),
+            ScopedTokenCheck::Revoked
+        );
+
+        assert_eq!(
+            verify_bridge_token(&path, "hbt_unknown", now).unwrap(),
+            ScopedTokenCheck::Unknown
+        );
+        // 无前缀的令牌（会话令牌等）不查库
+        assert_eq!(
+            verify_bridge_token(&path, "session-token", now).unwrap(),
+            ScopedTokenCheck::Unknown
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn issue_rejects_invalid_requests() {
+        let path = temp_db_path("invalid");
+        init_db(&path).unwrap();
+        assert!(issue_bridge_token(&path, &request(&[], None), 0).is_err());
+        assert!(issue_bridge_token(&path, &request(&["cookies:export"], None), 0).is_err());
+        assert!(issue_bridge_token(&path, &request(&["grades:read"], Some(0)), 0).is_err());
+        assert!(issue_bridge_token(&path, &request(&["grades:read"], Some(366)), 0).is_err());
+        let blank = IssueBridgeTokenRequest {
+            name: "  ".to_string(),
+            ..request(&["grades:read"], None)
+        };
+        assert!(issue_bridge_token(&path, &blank, 0).is_err());
+        assert!(list_bridge_tokens(&path, 0).unwrap().is_empty());
+
+        let (_, info) = issue_bridge_token(&path, &request(&["grades:read"], None), 0).unwrap();
+        assert_eq!(info.expires_at, i64::from(DEFAULT_TTL_DAYS) * 86_400);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn sensitive_routes_have_no_scope() {
+        assert_eq!(
+            bridge_route_scope("/sync_grades"),
+            Some(BridgeScope::GradesRead)
+        );
+        assert_eq!(
+            bridge_route_scope("/schedule/custom/add"),
+            Some(BridgeScope::ScheduleWrite)
+        );
+        for path in [
+            "/login",
+            "/export_cookies",
+            "/accounts/switch",
+            "/schedule/feed/issue",
+            "/electricity/rooms/watch",
+            "/debug/state",
+            "/cache/get",
+        ] {
+            assert_eq!(bridge_route_scope(path), None, "{path}");
+        }
+        for scope in BridgeScope::ALL {
+            assert_eq!(BridgeScope::parse(scope.as_str()), Some(scope));
+            assert!(
+                SCOPED_ROUTES.iter().any(|(_, s)| *s == scope),
+                "{} grants no route",
+                scope.as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn scoped_routes_are_registered_post_routes() {
+        let baseline = include_str!("../../tests/http_route_baseline.txt");
+        for (path, _) in SCOPED_ROUTES {
+            assert!(
+                baseline
+                    .lines()
+                    .any(|line| line.trim() == format!("POST {path}")),
+                "scoped route not registered: {path}"
+            );
+        }
+    }
+}
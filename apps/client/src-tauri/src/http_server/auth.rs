@@ -1,5 +1,5 @@
 //! Bridge 鉴权公共层：路由策略、可信 Origin、Bearer/会话令牌校验、
-//! 访问决策中间件与 CORS 层。
+//! 用户签发的限定权限令牌（见 [`super::api_tokens`]）、访问决策中间件与 CORS 层。
 //!
 //! 拆分自原 http_server.rs，鉴权语义、状态码与错误响应完全不变。
 
@@ -18,6 +18,7 @@ use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
 
 use crate::debug_bridge;
 
+use super::api_tokens::{bridge_route_scope, verify_bridge_token, BridgeScope, ScopedTokenCheck};
 use super::response::{err, ApiResponse};
 use super::state::HttpState;
 
@@ -160,6 +161,23 @@ pub(crate) fn ensure_sensitive_bridge_auth(
     ))
 }
 
+// ────────────────────────────────────────────────────────────
+/// 同 [`ensure_sensitive_bridge_auth`]，另接受携带 `scope` 权限的用户签发令牌。
+pub(crate) fn ensure_scoped_bridge_auth(
+    headers: &HeaderMap,
+    state: &HttpState,
+    scope: BridgeScope,
+) -> Result<(), (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    let full = ensure_sensitive_bridge_auth(headers, state);
+    if full.is_err()
+        && !has_explicit_untrusted_origin(headers)
+        && scoped_token_check(headers).grants(scope)
+    {
+        return Ok(());
+    }
+    full
+}
+
 // ────────────────────────────────────────────────────────────
 pub(crate) fn is_allowed_cache_table(table: &str) -> bool {
     matches!(
@@ -189,6 +207,8 @@ pub(crate) fn is_allowed_cache_table(table: &str) -> bool {
 pub(crate) enum BridgeRoutePolicy {
     PublicHealth,
     PublicEmbed,
+    /// 日历订阅源：桌面日历客户端直接拉取（无 Origin / Bridge 令牌），由路径中的订阅令牌鉴权
+    PublicFeed,
     Protected,
     DebugOnly,
 }
@@ -205,6 +225,8 @@ pub(crate) fn bridge_route_policy(path: &str) -> BridgeRoutePolicy {
         // 这些 URL 会作为子 WebView 的顶层导航地址，请求可能没有 Origin。
         // 仅允许 GET/HEAD，且不得携带显式不可信 Origin。
         BridgeRoutePolicy::PublicEmbed
+    } else if path.starts_with("/webcal/") {
+        BridgeRoutePolicy::PublicFeed
     } else if path.starts_with("/debug/") || path.starts_with("/campus-guide-debug/") {
         BridgeRoutePolicy::DebugOnly
     } else {
@@ -294,6 +316,23 @@ pub(crate) fn bridge_token_matches(headers: &HeaderMap, state: &HttpState) -> bo
             .is_some_and(|expected| tokens_equal(&provided, expected))
 }
 
+// ────────────────────────────────────────────────────────────
+/// 校验请求中的用户签发令牌；无令牌、非 `hbt_` 前缀或查库失败均视为 `Unknown`。
+pub(crate) fn scoped_token_check(headers: &HeaderMap) -> ScopedTokenCheck {
+    let Some(provided) = extract_bearer(headers) else {
+        return ScopedTokenCheck::Unknown;
+    };
+    verify_bridge_token(
+        crate::DB_FILENAME,
+        &provided,
+        chrono::Utc::now().timestamp(),
+    )
+    .unwrap_or_else(|e| {
+        crate::runtime_log::log_warn("bridge", format!("API 令牌校验失败: {e}"));
+        ScopedTokenCheck::Unknown
+    })
+}
+
 // ────────────────────────────────────────────────────────────
 pub(crate) fn bridge_auth_error(
     status: StatusCode,
@@ -309,6 +348,10 @@ pub(crate) enum BridgeAccessDecision {
     Unauthorized,
     ForbiddenOrigin,
     DebugRouteUnavailable,
+    /// 用户签发令牌缺少路由所需权限；`None` 表示该路由不接受此类令牌
+    InsufficientScope(Option<BridgeScope>),
+    TokenExpired,
+    TokenRevoked,
 }
 
 pub(crate) fn decide_bridge_access(
@@ -330,7 +373,11 @@ pub(crate) fn decide_bridge_access(
         return BridgeAccessDecision::ForbiddenOrigin;
     }
 
-    if policy == BridgeRoutePolicy::PublicEmbed && matches!(*method, Method::GET | Method::HEAD) {
+    if matches!(
+        policy,
+        BridgeRoutePolicy::PublicEmbed | BridgeRoutePolicy::PublicFeed
+    ) && matches!(*method, Method::GET | Method::HEAD)
+    {
         return BridgeAccessDecision::Allow;
     }
 
@@ -341,6 +388,30 @@ pub(crate) fn decide_bridge_access(
     }
 }
 
+/// 用户签发令牌的访问决策：仅 Protected 路由、且令牌带有路由所需权限时放行。
+///
+/// 仅在 [`decide_bridge_access`] 判定为 `Unauthorized`（无可信 Origin、无会话令牌）后调用。
+pub(crate) fn decide_scoped_token_access(
+    policy: BridgeRoutePolicy,
+    required: Option<BridgeScope>,
+    check: &ScopedTokenCheck,
+) -> BridgeAccessDecision {
+    match check {
+        ScopedTokenCheck::Unknown => BridgeAccessDecision::Unauthorized,
+        ScopedTokenCheck::Expired => BridgeAccessDecision::TokenExpired,
+        ScopedTokenCheck::Revoked => BridgeAccessDecision::TokenRevoked,
+        ScopedTokenCheck::Valid { .. } => match required {
+            Some(scope) if policy == BridgeRoutePolicy::Protected && check.grants(scope) => {
+                BridgeAccessDecision::Allow
+            }
+            Some(scope) if policy == BridgeRoutePolicy::Protected => {
+                BridgeAccessDecision::InsufficientScope(Some(scope))
+            }
+            _ => BridgeAccessDecision::InsufficientScope(None),
+        },
+    }
+}
+
 // ────────────────────────────────────────────────────────────
 pub(crate) async fn bridge_access_middleware(
     State(state): State<HttpState>,
@@ -352,13 +423,18 @@ pub(crate) async fn bridge_access_middleware(
     let policy = bridge_route_policy(&path);
 
     let token_valid = bridge_token_matches(request.headers(), &state);
-    match decide_bridge_access(
+    let mut decision = decide_bridge_access(
         policy,
         &method,
         request.headers(),
         token_valid,
         cfg!(debug_assertions),
-    ) {
+    );
+    if decision == BridgeAccessDecision::Unauthorized {
+        let check = scoped_token_check(request.headers());
+        decision = decide_scoped_token_access(policy, bridge_route_scope(&path), &check);
+    }
+    match decision {
         BridgeAccessDecision::Allow => next.run(request).await,
         BridgeAccessDecision::Unauthorized => bridge_auth_error(
             StatusCode::UNAUTHORIZED,
@@ -373,6 +449,21 @@ pub(crate) async fn bridge_access_middleware(
             bridge_auth_error(StatusCode::NOT_FOUND, "调试路由在 Release 构建中不存在")
                 .into_response()
         }
+        BridgeAccessDecision::InsufficientScope(Some(scope)) => bridge_auth_error(
+            StatusCode::FORBIDDEN,
+            &format!("API 令牌缺少 {} 权限", scope.as_str()),
+        )
+        .into_response(),
+        BridgeAccessDecision::InsufficientScope(None) => {
+            bridge_auth_error(StatusCode::FORBIDDEN, "该路由不接受限定权限的 API 令牌")
+                .into_response()
+        }
+        BridgeAccessDecision::TokenExpired => {
+            bridge_auth_error(StatusCode::UNAUTHORIZED, "API 令牌已过期").into_response()
+        }
+        BridgeAccessDecision::TokenRevoked => {
+            bridge_auth_error(StatusCode::UNAUTHORIZED, "API 令牌已吊销").into_response()
+        }
     }
 }
 
@@ -14,8 +14,12 @@
 //! - `state`：共享状态
 //! - `auth`：鉴权公共层 / CORS / 路由策略
 //! - `routes`：按领域拆分的 Router 与 Handler
+//! - `openapi`：路由 DTO 登记与 `/openapi.json` 文档生成
+//! - `api_tokens`：用户签发的限定权限、可过期 API 令牌
 
+pub(crate) mod api_tokens;
 mod auth;
+mod openapi;
 mod response;
 mod routes;
 mod state;
@@ -281,12 +285,15 @@ fn build_router() -> Router<HttpState> {
     let app: Router<HttpState> = Router::new()
         .merge(routes::auth::router())
         .merge(routes::academic::router())
+        .merge(routes::accounts::router())
         .merge(routes::schedule::router())
         .merge(routes::course_selection::router())
         .merge(routes::online_learning::router())
         .merge(routes::system::router())
         .merge(routes::proxy::router())
-        .merge(routes::ai::router());
+        .merge(routes::ai::router())
+        .merge(routes::scheduler::router())
+        .merge(routes::events::router());
 
     #[cfg(debug_assertions)]
     let app = app
@@ -357,9 +364,10 @@ async fn run_http_server(
 
 #[cfg(test)]
 mod ensure_http_bridge_tests {
+    use super::api_tokens::{BridgeScope, ScopedTokenCheck};
     use super::auth::{
-        bridge_route_policy, decide_bridge_access, is_trusted_bridge_origin, tokens_equal,
-        BridgeAccessDecision, BridgeRoutePolicy,
+        bridge_route_policy, decide_bridge_access, decide_scoped_token_access,
+        is_trusted_bridge_origin, tokens_equal, BridgeAccessDecision, BridgeRoutePolicy,
     };
     use super::{bridge_listen_addr, is_http_bridge_enabled, EnsureHttpBridgeResult};
     use axum::http::Method;
@@ -430,6 +438,14 @@ mod ensure_http_bridge_tests {
             bridge_route_policy("/school-website/news/info/1"),
             BridgeRoutePolicy::PublicEmbed
         );
+        assert_eq!(
+            bridge_route_policy("/webcal/opaque-token.ics"),
+            BridgeRoutePolicy::PublicFeed
+        );
+        assert_eq!(
+            bridge_route_policy("/schedule/feed/issue"),
+            BridgeRoutePolicy::Protected
+        );
         assert_eq!(
             bridge_route_policy("/module_bundle/prepare"),
             BridgeRoutePolicy::Protected
@@ -486,6 +502,47 @@ mod ensure_http_bridge_tests {
         );
     }
 
+    #[test]
+    fn public_feed_routes_are_read_only_and_reject_hostile_origins() {
+        let empty = HeaderMap::new();
+        assert_eq!(
+            decide_bridge_access(
+                BridgeRoutePolicy::PublicFeed,
+                &Method::GET,
+                &empty,
+                false,
+                false,
+            ),
+            BridgeAccessDecision::Allow
+        );
+        assert_eq!(
+            decide_bridge_access(
+                BridgeRoutePolicy::PublicFeed,
+                &Method::POST,
+                &empty,
+                false,
+                false,
+            ),
+            BridgeAccessDecision::Unauthorized
+        );
+
+        let mut hostile = HeaderMap::new();
+        hostile.insert(
+            "origin",
+            HeaderValue::from_static("https://attacker.example"),
+        );
+        assert_eq!(
+            decide_bridge_access(
+                BridgeRoutePolicy::PublicFeed,
+                &Method::GET,
+                &hostile,
+                false,
+                false,
+            ),
+            BridgeAccessDecision::ForbiddenOrigin
+        );
+    }
+
     #[test]
     fn runtime_origins_cover_tauri_vite_and_capacitor_without_trusting_lan_hosts() {
         for origin in [
@@ -633,6 +690,59 @@ mod ensure_http_bridge_tests {
         );
     }
 
+    #[test]
+    fn scoped_tokens_only_unlock_protected_routes_within_their_scopes() {
+        let valid = ScopedTokenCheck::Valid {
+            scopes: vec![BridgeScope::GradesRead],
+        };
+        assert_eq!(
+            decide_scoped_token_access(
+                BridgeRoutePolicy::Protected,
+                Some(BridgeScope::GradesRead),
+                &valid,
+            ),
+            BridgeAccessDecision::Allow
+        );
+        assert_eq!(
+            decide_scoped_token_access(
+                BridgeRoutePolicy::Protected,
+                Some(BridgeScope::ScheduleWrite),
+                &valid,
+            ),
+            BridgeAccessDecision::InsufficientScope(Some(BridgeScope::ScheduleWrite))
+        );
+        for policy in [BridgeRoutePolicy::Protected, BridgeRoutePolicy::DebugOnly] {
+            assert_eq!(
+                decide_scoped_token_access(policy, None, &valid),
+                BridgeAccessDecision::InsufficientScope(None)
+            );
+        }
+        assert_eq!(
+            decide_scoped_token_access(
+                BridgeRoutePolicy::Protected,
+                Some(BridgeScope::GradesRead),
+                &ScopedTokenCheck::Expired,
+            ),
+            BridgeAccessDecision::TokenExpired
+        );
+        assert_eq!(
+            decide_scoped_token_access(
+                BridgeRoutePolicy::Protected,
+                Some(BridgeScope::GradesRead),
+                &ScopedTokenCheck::Revoked,
+            ),
+            BridgeAccessDecision::TokenRevoked
+        );
+        assert_eq!(
+            decide_scoped_token_access(
+                BridgeRoutePolicy::Protected,
+                Some(BridgeScope::GradesRead),
+                &ScopedTokenCheck::Unknown,
+            ),
+            BridgeAccessDecision::Unauthorized
+        );
+    }
+
     #[test]
     fn bridge_token_comparison_checks_length_and_content() {
         assert!(tokens_equal("same-token", "same-token"));
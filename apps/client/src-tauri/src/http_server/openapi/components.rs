@@ -0,0 +1,1329 @@
+//! DTO 组件登记：字段表按名称排序，`source` 为 Rust 定义所在文件（相对 `src/`）。
+
+use super::{
+    dto, dto_flatten, enumeration, opt, req, Component, Schema, INTEGERS, OBJECTS, SKIPPED_ENTRIES,
+    STRINGS,
+};
+
+const SRC_ACADEMIC: &str = "http_server/routes/academic.rs";
+const SRC_ACCOUNTS: &str = "http_server/routes/accounts.rs";
+const SRC_AI: &str = "http_server/routes/ai.rs";
+const SRC_AUTH: &str = "http_server/routes/auth.rs";
+const SRC_DEBUG: &str = "http_server/routes/debug.rs";
+const SRC_EVENTS: &str = "http_server/routes/events.rs";
+const SRC_PROXY: &str = "http_server/routes/proxy.rs";
+const SRC_SCHEDULE: &str = "http_server/routes/schedule.rs";
+const SRC_SCHEDULER: &str = "http_server/routes/scheduler.rs";
+const SRC_SYSTEM: &str = "http_server/routes/system.rs";
+const SRC_CHAOXING: &str = "transport/tauri/chaoxing.rs";
+const SRC_COURSE_SELECTION: &str = "transport/tauri/course_selection.rs";
+const SRC_COURSE_WATCH: &str = "modules/course_selection/watch.rs";
+const SRC_TAURI_SCHEDULE: &str = "transport/tauri/schedule.rs";
+const SRC_DEBUG_BRIDGE: &str = "debug_bridge.rs";
+const SRC_ICS: &str = "modules/ics_calendar/mod.rs";
+const SRC_ICS_BUILDER: &str = "modules/ics_calendar/builder.rs";
+const SRC_SCHEDULE_ANALYSIS: &str = "modules/schedule_analysis/mod.rs";
+const SRC_SCHEDULE_ANALYSIS_ENGINE: &str = "modules/schedule_analysis/engine.rs";
+const SRC_DEGREE_AUDIT: &str = "modules/degree_audit/mod.rs";
+const SRC_DEGREE_AUDIT_ENGINE: &str = "modules/degree_audit/engine.rs";
+const SRC_ELECTRICITY_MONITOR: &str = "modules/electricity/monitor.rs";
+const SRC_EXAM_TRACKER: &str = "modules/exam/tracker.rs";
+const SRC_LIBRARY: &str = "modules/library/mod.rs";
+const SRC_LIBRARY_SAVED_SEARCH: &str = "modules/library/saved_search.rs";
+const SRC_PLAN_SIMULATOR: &str = "modules/plan_simulator/mod.rs";
+const SRC_PLAN_SIMULATOR_ENGINE: &str = "modules/plan_simulator/engine.rs";
+const SRC_MODULE_BUNDLE: &str = "modules/module_bundle.rs";
+const SRC_SCHEDULER_CONFIG: &str = "modules/scheduler/config.rs";
+const SRC_SCHEDULER_MOD: &str = "modules/scheduler/mod.rs";
+
+/// 全部 DTO 组件（按名称排序）。
+pub(crate) const COMPONENTS: &[Component] = &[
+    dto(
+        "AcademicProgressRequest",
+        SRC_ACADEMIC,
+        &[opt("fasz", Schema::Integer)],
+    ),
+    dto(
+        "AccountRemoveRequest",
+        SRC_ACCOUNTS,
+        &[req("student_id", Schema::String)],
+    ),
+    dto(
+        "AccountSummary",
+        "app_state/accounts.rs",
+        &[
+            req("student_id", Schema::String),
+            req("student_name", Schema::String),
+            req("active", Schema::Boolean),
+            req("loaded", Schema::Boolean),
+            req("last_active_at", Schema::String),
+        ],
+    ),
+    dto(
+        "AccountSwitchRequest",
+        SRC_ACCOUNTS,
+        &[opt("student_id", Schema::String)],
+    ),
+    dto(
+        "AddCustomScheduleCourseRequest",
+        SRC_TAURI_SCHEDULE,
+        &[
+            req("student_id", Schema::String),
+            req("semester", Schema::String),
+            req("name", Schema::String),
+            opt("teacher", Schema::String),
+            req("weekday", Schema::Integer),
+            req("period", Schema::Integer),
+            req("djs", Schema::Integer),
+            req("weeks", INTEGERS),
+            opt("room", Schema::String),
+            opt("color", Schema::String),
+        ],
+    ),
+    dto(
+        "AiChatRequest",
+        SRC_AI,
+        &[
+            req("token", Schema::String),
+            req("blade_auth", Schema::String),
+            req("question", Schema::String),
+            opt("upload_url", Schema::String),
+            opt("user_attachment", Schema::String),
+            opt("model", Schema::String),
+            opt("session_id", Schema::String),
+        ],
+    ),
+    dto(
+        "AiSessionDeleteRequest",
+        SRC_AI,
+        &[
+            req("token", Schema::String),
+            req("blade_auth", Schema::String),
+            req("session_id", Schema::String),
+        ],
+    ),
+    dto(
+        "AiSessionHistoryRequest",
+        SRC_AI,
+        &[
+            req("token", Schema::String),
+            req("blade_auth", Schema::String),
+            opt("current", Schema::Integer),
+            opt("size", Schema::Integer),
+            opt("ask", Schema::String),
+        ],
+    ),
+    dto(
+        "AiSessionMessagesRequest",
+        SRC_AI,
+        &[
+            req("token", Schema::String),
+            req("blade_auth", Schema::String),
+            req("session_id", Schema::String),
+        ],
+    ),
+    dto(
+        "AiSessionNewRequest",
+        SRC_AI,
+        &[
+            req("token", Schema::String),
+            req("blade_auth", Schema::String),
+        ],
+    ),
+    dto(
+        "AiUploadRequest",
+        SRC_AI,
+        &[
+            req("token", Schema::String),
+            req("blade_auth", Schema::String),
+            opt("file_content", Schema::String),
+            opt("file_base64", Schema::String),
+            opt("file_mime", Schema::String),
+            req("file_name", Schema::String),
+        ],
+    ),
+    dto(
+        "AnalyticsRequest",
+        "grade/analytics.rs",
+        &[opt("rules", Schema::Object), opt("what_if", OBJECTS)],
+    ),
+    dto(
+        "ApiError",
+        "http_server/response.rs",
+        &[
+            req("kind", Schema::String),
+            req("message", Schema::String),
+            req("code", Schema::String),
+            req("retryable", Schema::Boolean),
+            opt("retry_after_secs", Schema::Integer),
+            opt("action", Schema::String),
+        ],
+    ),
+    dto(
+        "AuditOptions",
+        "modules/degree_audit/engine.rs",
+        &[
+            opt("requirements", Schema::Object),
+            opt("max_credits_per_term", Schema::Number),
+        ],
+    ),
+    dto(
+        "CacheGetQuery",
+        SRC_SYSTEM,
+        &[req("table", Schema::String), req("key", Schema::String)],
+    ),
+    dto(
+        "CalendarRequest",
+        SRC_ACADEMIC,
+        &[opt("semester", Schema::String)],
+    ),
+    dto(
+        "CampusCodeRequest",
+        SRC_SYSTEM,
+        &[req("payload", Schema::Any)],
+    ),
+    dto(
+        "CampusGuideDebugProbeRequest",
+        SRC_PROXY,
+        &[
+            opt("scenic_id", Schema::String),
+            opt("path", Schema::String),
+            opt("field", STRINGS),
+            opt("extra", Schema::Object),
+        ],
+    ),
+    enumeration(
+        "CandidateSource",
+        SRC_PLAN_SIMULATOR_ENGINE,
+        &["course_selection", "public_schedule", "manual"],
+    ),
+    dto(
+        "ChaoxingCourseOutlineRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("course_id", Schema::String),
+            req("clazz_id", Schema::String),
+            req("cpi", Schema::String),
+            opt("course_url", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "ChaoxingCourseProgressRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("course_id", Schema::String),
+            req("clazz_id", Schema::String),
+            req("cpi", Schema::String),
+            opt("course_url", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "ChaoxingCourseScoreRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("course_id", Schema::String),
+            req("clazz_id", Schema::String),
+            opt("cpi", Schema::String),
+        ],
+    ),
+    dto(
+        "ChaoxingCoursesRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "ChaoxingKnowledgeCardsRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("clazz_id", Schema::String),
+            req("course_id", Schema::String),
+            req("knowledge_id", Schema::String),
+            opt("cpi", Schema::String),
+        ],
+    ),
+    dto(
+        "ChaoxingLaunchUrlRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("course_id", Schema::String),
+            req("clazz_id", Schema::String),
+            opt("chapter_id", Schema::String),
+            opt("knowledge_id", Schema::String),
+            opt("cpi", Schema::String),
+            opt("launch_url", Schema::String),
+        ],
+    ),
+    dto(
+        "ChaoxingReportProgressRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("report_url", Schema::String),
+            req("dtoken", Schema::String),
+            req("clazz_id", Schema::String),
+            req("object_id", Schema::String),
+            req("jobid", Schema::String),
+            req("userid", Schema::String),
+            req("other_info", Schema::String),
+            req("playing_time", Schema::Integer),
+            req("duration", Schema::Integer),
+            opt("isdrag", Schema::Integer),
+            opt("video_face_capture_enc", Schema::String),
+            opt("att_duration", Schema::String),
+            opt("att_duration_enc", Schema::String),
+        ],
+    ),
+    dto(
+        "ChaoxingSessionStatusRequest",
+        SRC_CHAOXING,
+        &[opt("student_id", Schema::String)],
+    ),
+    dto(
+        "ChaoxingVideoStatusRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("object_id", Schema::String),
+            opt("fid", Schema::String),
+        ],
+    ),
+    dto(
+        "ClassroomQueryRequest",
+        SRC_ACADEMIC,
+        &[
+            opt("week", Schema::Integer),
+            opt("weekday", Schema::Integer),
+            opt("periods", INTEGERS),
+            opt("building", Schema::String),
+        ],
+    ),
+    dto(
+        "CookieSnapshotRequest",
+        SRC_AUTH,
+        &[
+            opt("code", Schema::String),
+            opt("auth", Schema::String),
+            opt("jwxt", Schema::String),
+        ],
+    ),
+    dto(
+        "CourseSelectionChildClassesRequest",
+        SRC_COURSE_SELECTION,
+        &[
+            req("pcid", Schema::String),
+            req("pcenc", Schema::String),
+            req("jxbid", Schema::String),
+            opt("from", Schema::String),
+        ],
+    ),
+    dto(
+        "CourseSelectionDetailRequest",
+        SRC_COURSE_SELECTION,
+        &[req("jxbid", Schema::String)],
+    ),
+    dto(
+        "CourseSelectionEndTimeRequest",
+        SRC_COURSE_SELECTION,
+        &[req("pcid", Schema::String), req("kklx", Schema::String)],
+    ),
+    dto(
+        "CourseSelectionListRequest",
+        SRC_COURSE_SELECTION,
+        &[
+            req("pcid", Schema::String),
+            opt("from", Schema::String),
+            req("pcenc", Schema::String),
+            opt("kcmc", Schema::String),
+            opt("kcxz", Schema::String),
+            opt("jxms", Schema::String),
+            opt("kcgs", Schema::String),
+            opt("teacher", Schema::String),
+            opt("kkxq", Schema::String),
+            opt("kclb", Schema::String),
+            opt("kclx", Schema::String),
+        ],
+    ),
+    dto(
+        "CourseSelectionSelectRequest",
+        SRC_COURSE_SELECTION,
+        &[
+            req("pcid", Schema::String),
+            req("jxbid", Schema::String),
+            opt("zjxbid", Schema::String),
+            opt("from", Schema::String),
+            opt("sksjdd", Schema::String),
+            opt("kcmc", Schema::String),
+            opt("ignore_conflicts", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "CourseSelectionSelectedCoursesRequest",
+        SRC_COURSE_SELECTION,
+        &[opt("semester", Schema::String)],
+    ),
+    dto(
+        "CourseSelectionUnwatchRequest",
+        SRC_COURSE_WATCH,
+        &[
+            req("pcid", Schema::String),
+            opt("jxbid", Schema::String),
+            opt("zjxbid", Schema::String),
+        ],
+    ),
+    dto(
+        "CourseSelectionWatchRequest",
+        SRC_COURSE_WATCH,
+        &[
+            req("pcid", Schema::String),
+            req("pcenc", Schema::String),
+            opt("from", Schema::String),
+            req("kklx", Schema::String),
+            opt("round_name", Schema::String),
+            req("jxbid", Schema::String),
+            opt("zjxbid", Schema::String),
+            opt("kcmc", Schema::String),
+            opt("jxbmc", Schema::String),
+            opt("sksjdd", Schema::String),
+        ],
+    ),
+    dto(
+        "CourseSelectionWithdrawRequest",
+        SRC_COURSE_SELECTION,
+        &[req("pcid", Schema::String), req("jxbid", Schema::String)],
+    ),
+    dto(
+        "CustomScheduleListAllRequest",
+        SRC_SCHEDULE,
+        &[req("student_id", Schema::String)],
+    ),
+    dto(
+        "CustomScheduleListRequest",
+        SRC_SCHEDULE,
+        &[
+            req("student_id", Schema::String),
+            req("semester", Schema::String),
+        ],
+    ),
+    dto(
+        "DebugCustomScheduleCourseInput",
+        SRC_SCHEDULE,
+        &[
+            opt("id", Schema::String),
+            req("semester", Schema::String),
+            req("name", Schema::String),
+            opt("teacher", Schema::String),
+            opt("room", Schema::String),
+            req("weekday", Schema::Integer),
+            req("period", Schema::Integer),
+            req("djs", Schema::Integer),
+            req("weeks", INTEGERS),
+            opt("color", Schema::String),
+        ],
+    ),
+    dto(
+        "DebugCustomScheduleUpsertRequest",
+        SRC_SCHEDULE,
+        &[
+            req("student_id", Schema::String),
+            req(
+                "courses",
+                Schema::Array(&Schema::Ref("DebugCustomScheduleCourseInput")),
+            ),
+            opt("dry_run", Schema::Boolean),
+            opt("return_conflicts", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "DebugForceBody",
+        SRC_DEBUG,
+        &[
+            opt("force", Schema::Boolean),
+            opt("login_mode", Schema::String),
+            opt("student_id", Schema::String),
+        ],
+    ),
+    dto(
+        "DebugFrontendEvalRequest",
+        SRC_DEBUG,
+        &[req("js", Schema::String)],
+    ),
+    dto(
+        "DebugIdentityIntentRequest",
+        SRC_DEBUG,
+        &[
+            req("request_id", Schema::String),
+            req("handoff", Schema::String),
+        ],
+    ),
+    dto(
+        "DebugKeyringProbeRequest",
+        SRC_DEBUG,
+        &[req("value", Schema::String)],
+    ),
+    dto(
+        "DebugLogPushBody",
+        SRC_DEBUG,
+        &[
+            opt("scope", Schema::String),
+            req("message", Schema::String),
+            opt("level", Schema::String),
+            opt("details", Schema::Any),
+        ],
+    ),
+    dto(
+        "DebugLogsQuery",
+        SRC_DEBUG,
+        &[
+            opt("limit", Schema::Integer),
+            opt("since_id", Schema::Integer),
+            opt("scope", Schema::String),
+            opt("level", Schema::String),
+            opt("q", Schema::String),
+        ],
+    ),
+    dto(
+        "DebugNavigateRequest",
+        SRC_DEBUG,
+        &[
+            req("view", Schema::String),
+            opt("student_id", Schema::String),
+            opt("payload", Schema::Any),
+        ],
+    ),
+    dto(
+        "DebugOpenModuleRequest",
+        SRC_DEBUG_BRIDGE,
+        &[
+            req("moduleId", Schema::String),
+            opt("studentId", Schema::String),
+        ],
+    ),
+    dto(
+        "DebugResetMoreModulesRequest",
+        SRC_DEBUG_BRIDGE,
+        &[opt("cdnBaseOverride", Schema::String)],
+    ),
+    dto(
+        "DebugScreenshotRequest",
+        SRC_DEBUG_BRIDGE,
+        &[
+            opt("selector", Schema::String),
+            opt("format", Schema::String),
+            opt("return", Schema::String),
+            opt("filename", Schema::String),
+        ],
+    ),
+    dto(
+        "DegreeAudit",
+        SRC_DEGREE_AUDIT_ENGINE,
+        &[
+            req("categories", OBJECTS),
+            req("earned_credits", Schema::Number),
+            req("outstanding_required", OBJECTS),
+            req("retakes_needed", OBJECTS),
+            req("unmatched_passed", OBJECTS),
+            req("estimate", Schema::Object),
+        ],
+    ),
+    dto_flatten(
+        "DegreeAuditReport",
+        SRC_DEGREE_AUDIT,
+        &["DegreeAudit"],
+        &[req("sources", Schema::Object)],
+    ),
+    dto_flatten(
+        "DegreeAuditRequest",
+        SRC_DEGREE_AUDIT,
+        &["AuditOptions"],
+        &[
+            opt("student_id", Schema::String),
+            opt("fasz", Schema::Integer),
+        ],
+    ),
+    dto(
+        "DeleteCustomScheduleCourseRequest",
+        SRC_TAURI_SCHEDULE,
+        &[
+            req("student_id", Schema::String),
+            req("semester", Schema::String),
+            req("course_id", Schema::String),
+            opt("mode", Schema::String),
+            opt("current_week", Schema::Integer),
+        ],
+    ),
+    dto(
+        "DraftPlan",
+        SRC_PLAN_SIMULATOR,
+        &[
+            req("id", Schema::String),
+            req("name", Schema::String),
+            req("semester", Schema::String),
+            req("courses", OBJECTS),
+            req("created_at", Schema::String),
+            req("updated_at", Schema::String),
+        ],
+    ),
+    dto(
+        "ElectricityOverviewRequest",
+        SRC_SYSTEM,
+        &[opt("room_id", Schema::String), opt("days", Schema::Integer)],
+    ),
+    dto(
+        "ElectricityRequest",
+        SRC_SYSTEM,
+        &[req("payload", Schema::Any)],
+    ),
+    dto(
+        "ElectricityRoomRequest",
+        SRC_SYSTEM,
+        &[req("room_id", Schema::String)],
+    ),
+    dto(
+        "ElectricityWatchRequest",
+        "modules/electricity/monitor.rs",
+        &[
+            opt("location", Schema::Any),
+            opt("share_code", Schema::String),
+            opt("label", Schema::String),
+            opt("threshold_balance", Schema::Number),
+            opt("alert_days", Schema::Integer),
+        ],
+    ),
+    dto(
+        "EventStreamQuery",
+        SRC_EVENTS,
+        &[
+            opt("types", Schema::String),
+            opt("log_level", Schema::String),
+            opt("last_event_id", Schema::Integer),
+        ],
+    ),
+    dto(
+        "ExamCountdown",
+        SRC_EXAM_TRACKER,
+        &[
+            req("course_name", Schema::String),
+            req("exam_date", Schema::String),
+            req("exam_time", Schema::String),
+            req("location", Schema::String),
+            opt("seat_number", Schema::String),
+            req("starts_at", Schema::String),
+            req("minutes_left", Schema::Integer),
+            req("days_left", Schema::Integer),
+        ],
+    ),
+    dto(
+        "ExamCountdownRequest",
+        SRC_ACADEMIC,
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+        ],
+    ),
+    dto(
+        "ExamReminderLeadsRequest",
+        SRC_ACADEMIC,
+        &[req("leads", INTEGERS)],
+    ),
+    dto(
+        "ExamRequest",
+        SRC_ACADEMIC,
+        &[opt("semester", Schema::String)],
+    ),
+    dto(
+        "FreeBlock",
+        SRC_SCHEDULE_ANALYSIS_ENGINE,
+        &[
+            req("week", Schema::Integer),
+            req("weekday", Schema::Integer),
+            req("start_period", Schema::Integer),
+            req("end_period", Schema::Integer),
+            req("start_time", Schema::String),
+            req("end_time", Schema::String),
+        ],
+    ),
+    dto(
+        "FreeSlotQuery",
+        "modules/schedule_analysis/engine.rs",
+        &[
+            opt("week_from", Schema::Integer),
+            opt("week_to", Schema::Integer),
+            opt("weekdays", INTEGERS),
+            opt("min_periods", Schema::Integer),
+        ],
+    ),
+    dto_flatten(
+        "FreeSlotRequest",
+        SRC_SCHEDULE_ANALYSIS,
+        &["FreeSlotQuery"],
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+            opt("semester_start", Schema::String),
+            opt("peers", STRINGS),
+        ],
+    ),
+    dto(
+        "IcsExportOptions",
+        "modules/ics_calendar/builder.rs",
+        &[
+            opt("class_alarms", INTEGERS),
+            opt("exam_alarms", INTEGERS),
+            opt("include_exams", Schema::Boolean),
+            opt("split_exams", Schema::Boolean),
+            opt("calendar_name", Schema::String),
+            opt("refresh_interval_minutes", Schema::Integer),
+        ],
+    ),
+    dto(
+        "JobConfig",
+        SRC_SCHEDULER_CONFIG,
+        &[
+            opt("enabled", Schema::Boolean),
+            req("interval_minutes", Schema::Integer),
+            opt("quiet_hours", Schema::Ref("QuietHours")),
+        ],
+    ),
+    dto(
+        "JobEnabledRequest",
+        SRC_SCHEDULER,
+        &[req("job", Schema::String), req("enabled", Schema::Boolean)],
+    ),
+    enumeration(
+        "JobKind",
+        SRC_SCHEDULER_CONFIG,
+        &[
+            "grades",
+            "exams",
+            "electricity",
+            "electricity_balance",
+            "inbox",
+            "online_learning",
+            "database_backup",
+            "course_selection_watch",
+            "library_due",
+            "library_searches",
+        ],
+    ),
+    enumeration(
+        "JobOutcome",
+        SRC_SCHEDULER_MOD,
+        &["success", "skipped", "network_error", "failed"],
+    ),
+    dto(
+        "JobRunRequest",
+        SRC_SCHEDULER,
+        &[req("job", Schema::String)],
+    ),
+    dto(
+        "JobStatus",
+        SRC_SCHEDULER_MOD,
+        &[
+            req("job", Schema::Ref("JobKind")),
+            req("label", Schema::String),
+            req("enabled", Schema::Boolean),
+            req("interval_minutes", Schema::Integer),
+            opt("quiet_hours", Schema::Ref("QuietHours")),
+            req("in_quiet_hours", Schema::Boolean),
+            req("running", Schema::Boolean),
+            opt("last_started_at", Schema::String),
+            opt("last_finished_at", Schema::String),
+            opt("last_outcome", Schema::Ref("JobOutcome")),
+            opt("last_message", Schema::String),
+            req("consecutive_failures", Schema::Integer),
+            opt("next_run_at", Schema::String),
+        ],
+    ),
+    dto(
+        "JobUpdateRequest",
+        SRC_SCHEDULER,
+        &[
+            req("job", Schema::String),
+            req("config", Schema::Ref("JobConfig")),
+        ],
+    ),
+    dto(
+        "LibraryDetailRequest",
+        SRC_SYSTEM,
+        &[
+            req("title", Schema::String),
+            req("isbn", Schema::String),
+            opt("record_id", Schema::Integer),
+        ],
+    ),
+    dto(
+        "LibraryHistoryRequest",
+        SRC_SYSTEM,
+        &[opt("page", Schema::Integer), opt("rows", Schema::Integer)],
+    ),
+    dto(
+        "LibraryReminderDaysRequest",
+        SRC_SYSTEM,
+        &[req("days", INTEGERS)],
+    ),
+    dto(
+        "LibraryRenewRequest",
+        SRC_LIBRARY,
+        &[req("barcodes", STRINGS)],
+    ),
+    dto(
+        "LibrarySavedSearchIdRequest",
+        SRC_LIBRARY_SAVED_SEARCH,
+        &[req("id", Schema::String)],
+    ),
+    dto(
+        "LibrarySavedSearchRequest",
+        SRC_LIBRARY_SAVED_SEARCH,
+        &[
+            opt("id", Schema::String),
+            req("name", Schema::String),
+            req("params", Schema::Any),
+            opt("enabled", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "LibrarySearchRequest",
+        SRC_SYSTEM,
+        &[req("params", Schema::Any)],
+    ),
+    dto(
+        "LoginRequest",
+        SRC_AUTH,
+        &[
+            req("username", Schema::String),
+            req("password", Schema::String),
+            opt("captcha", Schema::String),
+            opt("lt", Schema::String),
+            opt("execution", Schema::String),
+        ],
+    ),
+    dto(
+        "ModuleBundlePrepareRequest",
+        SRC_MODULE_BUNDLE,
+        &[
+            opt("channel", Schema::String),
+            req("module_id", Schema::String),
+            req("version", Schema::String),
+            req("package_url", Schema::String),
+            opt("package_urls", STRINGS),
+            opt("package_sha256", Schema::String),
+            opt("min_compatible_version", Schema::String),
+            opt("entry_path", Schema::String),
+            opt("module_name", Schema::String),
+        ],
+    ),
+    dto(
+        "ModuleBundlePrepareResult",
+        SRC_MODULE_BUNDLE,
+        &[
+            req("channel", Schema::String),
+            req("module_id", Schema::String),
+            req("module_name", Schema::String),
+            req("version", Schema::String),
+            req("entry_path", Schema::String),
+            req("preview_url", Schema::String),
+            req("cache_dir", Schema::String),
+            req("bundle_path", Schema::String),
+            req("min_compatible_version", Schema::String),
+            req("source", Schema::String),
+        ],
+    ),
+    dto(
+        "OnlineLearningClearCacheRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            opt("platform", Schema::String),
+        ],
+    ),
+    dto(
+        "OnlineLearningOverviewRequest",
+        SRC_CHAOXING,
+        &[opt("student_id", Schema::String)],
+    ),
+    dto(
+        "OnlineLearningSyncRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            opt("platform", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "OnlineLearningSyncRunsRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            opt("platform", Schema::String),
+            opt("limit", Schema::Integer),
+        ],
+    ),
+    dto_flatten(
+        "OpenModuleBundleWindowRequest",
+        SRC_MODULE_BUNDLE,
+        &["ModuleBundlePrepareRequest"],
+        &[
+            opt("title", Schema::String),
+            opt("label", Schema::String),
+            opt("width", Schema::Number),
+            opt("height", Schema::Number),
+        ],
+    ),
+    dto_flatten(
+        "OpenModuleBundleWindowResult",
+        SRC_MODULE_BUNDLE,
+        &["ModuleBundlePrepareResult"],
+        &[req("window_label", Schema::String)],
+    ),
+    dto(
+        "PersonalLoginAccessRequest",
+        SRC_ACADEMIC,
+        &[
+            opt("page", Schema::Integer),
+            opt("page_size", Schema::Integer),
+        ],
+    ),
+    dto(
+        "PlanCourseInput",
+        SRC_PLAN_SIMULATOR,
+        &[
+            opt("source", Schema::Ref("CandidateSource")),
+            req("row", Schema::Any),
+        ],
+    ),
+    dto(
+        "PlanIcsRequest",
+        SRC_PLAN_SIMULATOR,
+        &[
+            opt("student_id", Schema::String),
+            req("id", Schema::String),
+            opt("semester_start", Schema::String),
+            opt("include_existing", Schema::Boolean),
+            opt("options", Schema::Ref("IcsExportOptions")),
+        ],
+    ),
+    dto(
+        "PlanRequest",
+        SRC_PLAN_SIMULATOR,
+        &[opt("student_id", Schema::String), req("id", Schema::String)],
+    ),
+    dto(
+        "PlanSaveRequest",
+        SRC_PLAN_SIMULATOR,
+        &[
+            opt("student_id", Schema::String),
+            opt("id", Schema::String),
+            req("name", Schema::String),
+            opt("semester", Schema::String),
+            opt("courses", Schema::Array(&Schema::Ref("PlanCourseInput"))),
+        ],
+    ),
+    dto(
+        "PlanSimulateRequest",
+        SRC_PLAN_SIMULATOR,
+        &[
+            opt("student_id", Schema::String),
+            req("id", Schema::String),
+            opt("fasz", Schema::Integer),
+            opt("max_credits_per_term", Schema::Number),
+        ],
+    ),
+    dto(
+        "PlanSimulation",
+        SRC_PLAN_SIMULATOR_ENGINE,
+        &[
+            req("grid", OBJECTS),
+            req("conflicts", OBJECTS),
+            req("unplaced", SKIPPED_ENTRIES),
+            req("already_scheduled", STRINGS),
+            req("credits", Schema::Object),
+            req("categories", OBJECTS),
+            req("covered_required", OBJECTS),
+        ],
+    ),
+    dto_flatten(
+        "PlanSimulationReport",
+        SRC_PLAN_SIMULATOR,
+        &["PlanSimulation"],
+        &[req("plan", Schema::Ref("DraftPlan")), req("notes", STRINGS)],
+    ),
+    dto(
+        "QuietHours",
+        SRC_SCHEDULER_CONFIG,
+        &[req("start", Schema::String), req("end", Schema::String)],
+    ),
+    dto(
+        "QxzkbJcinfoRequest",
+        SRC_SYSTEM,
+        &[req("xnxq", Schema::String)],
+    ),
+    dto(
+        "QxzkbKkjysRequest",
+        SRC_SYSTEM,
+        &[req("kkyxid", Schema::String)],
+    ),
+    dto(
+        "QxzkbQuery",
+        "transport/tauri/qxzkb.rs",
+        &[
+            req("xnxq", Schema::String),
+            opt("xqid", Schema::String),
+            opt("nj", Schema::String),
+            opt("yxid", Schema::String),
+            opt("zyid", Schema::String),
+            opt("kkyxid", Schema::String),
+            opt("kkjysid", Schema::String),
+            opt("kcxz", Schema::String),
+            opt("kclb", Schema::String),
+            opt("xslx", Schema::String),
+            opt("kcmc", Schema::String),
+            opt("skjs", Schema::String),
+            opt("jxlid", Schema::String),
+            opt("jslx", Schema::String),
+            opt("ksxs", Schema::String),
+            opt("ksfs", Schema::String),
+            opt("jsmc", Schema::String),
+            opt("zxjc", Schema::String),
+            opt("zdjc", Schema::String),
+            opt("zxzc", Schema::String),
+            opt("zdzc", Schema::String),
+            opt("zxxq", Schema::String),
+            opt("zdxq", Schema::String),
+            opt("xsqbkb", Schema::String),
+            opt("kklx", STRINGS),
+            opt("page", Schema::Integer),
+            opt("page_size", Schema::Integer),
+            opt("sort", Schema::String),
+            opt("order", Schema::String),
+        ],
+    ),
+    dto(
+        "QxzkbZyxxRequest",
+        SRC_SYSTEM,
+        &[req("yxid", Schema::String), req("nj", Schema::String)],
+    ),
+    dto(
+        "RankingRequest",
+        SRC_ACADEMIC,
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+        ],
+    ),
+    dto(
+        "ResourceShareProxyQuery",
+        SRC_PROXY,
+        &[
+            req("endpoint", Schema::String),
+            req("path", Schema::String),
+            opt("username", Schema::String),
+            opt("password", Schema::String),
+        ],
+    ),
+    dto(
+        "RestoreRequest",
+        SRC_AUTH,
+        &[req("cookies", Schema::String)],
+    ),
+    dto(
+        "SaveExportFileRequest",
+        "transport/tauri/update.rs",
+        &[
+            req("fileName", Schema::String),
+            req("mimeType", Schema::String),
+            req("contentBase64", Schema::String),
+            opt("preferMedia", Schema::Boolean),
+            opt("debugSaveDir", Schema::String),
+        ],
+    ),
+    dto(
+        "SaveExportFileResult",
+        "transport/tauri/update.rs",
+        &[
+            req("path", Schema::String),
+            req("saved_to", Schema::String),
+            req("size", Schema::Integer),
+            req("needs_manual_import", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "SavedSearch",
+        SRC_LIBRARY_SAVED_SEARCH,
+        &[
+            req("id", Schema::String),
+            req("name", Schema::String),
+            req("params", Schema::Any),
+            req("enabled", Schema::Boolean),
+            req("created_at", Schema::String),
+            req("updated_at", Schema::String),
+            opt("known", Schema::Object),
+            opt("last_check", Schema::Ref("SearchCheck")),
+        ],
+    ),
+    dto(
+        "ScheduleConflictRequest",
+        SRC_SCHEDULE_ANALYSIS,
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+            opt("semester_start", Schema::String),
+        ],
+    ),
+    dto(
+        "ScheduleExportEvent",
+        SRC_SCHEDULE,
+        &[
+            req("summary", Schema::String),
+            req("start", Schema::String),
+            req("end", Schema::String),
+            opt("description", Schema::String),
+            opt("location", Schema::String),
+        ],
+    ),
+    dto(
+        "ScheduleExportRequest",
+        SRC_SCHEDULE,
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+            opt("week", Schema::Integer),
+            req("events", Schema::Array(&Schema::Ref("ScheduleExportEvent"))),
+            opt("upload_endpoint", Schema::String),
+            opt("ttl_seconds", Schema::Integer),
+        ],
+    ),
+    dto(
+        "ScheduleFeedRequest",
+        "modules/ics_calendar/feed.rs",
+        &[
+            opt("student_id", Schema::String),
+            opt("device", Schema::String),
+        ],
+    ),
+    dto(
+        "ScheduleIcsImportRequest",
+        SRC_ICS,
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+            opt("semester_start", Schema::String),
+            req("content", Schema::String),
+            opt("color", Schema::String),
+            opt("commit", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "ScheduleIcsRequest",
+        SRC_ICS,
+        &[
+            opt("student_id", Schema::String),
+            opt("semester", Schema::String),
+            opt("semester_start", Schema::String),
+            opt("options", Schema::Ref("IcsExportOptions")),
+        ],
+    ),
+    dto(
+        "SchedulePlanListRequest",
+        SRC_SCHEDULE,
+        &[opt("student_id", Schema::String)],
+    ),
+    dto(
+        "ScheduleQueryRequest",
+        SRC_ACADEMIC,
+        &[opt("semester", Schema::String)],
+    ),
+    dto(
+        "SearchCheck",
+        SRC_LIBRARY_SAVED_SEARCH,
+        &[
+            req("search_id", Schema::String),
+            req("name", Schema::String),
+            req("checked_at", Schema::String),
+            req("baseline", Schema::Boolean),
+            req("total", Schema::Integer),
+            req("new_hits", OBJECTS),
+            req("back_on_shelf", OBJECTS),
+        ],
+    ),
+    dto(
+        "SkippedEntry",
+        SRC_ICS_BUILDER,
+        &[req("name", Schema::String), req("reason", Schema::String)],
+    ),
+    dto(
+        "SyncGradesRequest",
+        SRC_ACADEMIC,
+        &[
+            opt("current_only", Schema::Boolean),
+            opt("teacher_current_only", Schema::Boolean),
+            opt("analytics", Schema::Ref("AnalyticsRequest")),
+        ],
+    ),
+    dto(
+        "TrainingPlanCoursesRequest",
+        SRC_ACADEMIC,
+        &[
+            opt("grade", Schema::String),
+            opt("kkxq", Schema::String),
+            opt("kkyx", Schema::String),
+            opt("kkjys", Schema::String),
+            opt("kcxz", Schema::String),
+            opt("kcgs", Schema::String),
+            opt("kcbh", Schema::String),
+            opt("kcmc", Schema::String),
+            opt("page", Schema::Integer),
+            opt("page_size", Schema::Integer),
+        ],
+    ),
+    dto(
+        "TrainingPlanJysRequest",
+        SRC_ACADEMIC,
+        &[req("yxid", Schema::String)],
+    ),
+    dto(
+        "TransactionRequest",
+        SRC_SYSTEM,
+        &[
+            req("start_date", Schema::String),
+            req("end_date", Schema::String),
+            req("page_no", Schema::Integer),
+            req("page_size", Schema::Integer),
+        ],
+    ),
+    dto(
+        "UpdateCustomScheduleCourseRequest",
+        SRC_TAURI_SCHEDULE,
+        &[
+            req("student_id", Schema::String),
+            req("semester", Schema::String),
+            req("course_id", Schema::String),
+            req("name", Schema::String),
+            opt("teacher", Schema::String),
+            req("weekday", Schema::Integer),
+            req("period", Schema::Integer),
+            req("djs", Schema::Integer),
+            req("weeks", INTEGERS),
+            opt("room", Schema::String),
+            opt("color", Schema::String),
+        ],
+    ),
+    dto(
+        "UserInfo",
+        "transport/tauri/auth.rs",
+        &[
+            req("student_id", Schema::String),
+            req("student_name", Schema::String),
+            opt("college", Schema::String),
+            opt("major", Schema::String),
+            opt("class_name", Schema::String),
+            opt("grade", Schema::String),
+        ],
+    ),
+    dto(
+        "WatchRound",
+        SRC_COURSE_WATCH,
+        &[
+            req("pcid", Schema::String),
+            req("pcenc", Schema::String),
+            req("from", Schema::String),
+            req("kklx", Schema::String),
+            req("name", Schema::String),
+            opt("ends_at", Schema::String),
+            opt("closed", Schema::Boolean),
+            req("items", OBJECTS),
+            req("created_at", Schema::String),
+        ],
+    ),
+    dto(
+        "WatchedRoom",
+        SRC_ELECTRICITY_MONITOR,
+        &[
+            req("id", Schema::String),
+            req("label", Schema::String),
+            req("location", Schema::Any),
+            req("threshold_balance", Schema::Number),
+            req("alert_days", Schema::Integer),
+            req("added_at", Schema::String),
+            opt("alerts", Schema::Object),
+        ],
+    ),
+    dto(
+        "YuketangCourseChaptersRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("classroom_id", Schema::String),
+            req("sign", Schema::String),
+        ],
+    ),
+    dto(
+        "YuketangCourseOutlineRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("classroom_id", Schema::String),
+            opt("sign", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "YuketangCourseProgressRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("classroom_id", Schema::String),
+            opt("sku_id", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "YuketangCoursesRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            opt("force", Schema::Boolean),
+        ],
+    ),
+    dto(
+        "YuketangHeartbeatRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("classroom_id", Schema::String),
+            req("events", Schema::Any),
+        ],
+    ),
+    dto(
+        "YuketangLeafInfoRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("classroom_id", Schema::String),
+            req("leaf_id", Schema::String),
+        ],
+    ),
+    dto(
+        "YuketangPollQrLoginRequest",
+        SRC_CHAOXING,
+        &[
+            opt("student_id", Schema::String),
+            req("session_id", Schema::String),
+        ],
+    ),
+    dto(
+        "YuketangQrCreateRequest",
+        SRC_CHAOXING,
+        &[opt("student_id", Schema::String)],
+    ),
+];
@@ -0,0 +1,954 @@
+//! Bridge OpenAPI 3 文档：按路由登记请求 / 响应 DTO，生成 `GET /openapi.json`。
+//!
+//! - [`operations`]：[`OPERATIONS`] 每条 `method + path` 一项，与 `tests/http_route_baseline.txt`
+//!   一一对应；新增路由未在此登记时单测失败。
+//! - [`components`]：[`COMPONENTS`] DTO 字段表，`source` 指向对应 Rust 结构体所在文件（相对 `src/`），
+//!   单测按源码核对字段名（含 serde rename）、必填性与类型，避免文档与实现漂移。
+//! - 统一信封 `{ success, data, error, time }` 与 `ApiError` 由生成器套在每个 JSON 响应外；
+//!   每个操作须显式登记响应（`.returns(..)` / `.raw(..)` / `.stream()`），未登记时单测失败。
+//!   模块返回的结构化结果（`{ success, plan }` 之类的包装）用 [`Schema::Fields`] 内联描述，
+//!   原样透传上游 JSON 的 handler 以 `Schema::Any` 描述 `data`。
+
+mod components;
+mod operations;
+
+use serde_json::{json, Map, Value};
+
+use super::api_tokens::bridge_route_scope;
+use super::auth::{bridge_route_policy, BridgeRoutePolicy};
+use components::COMPONENTS;
+use operations::OPERATIONS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Method {
+    Get,
+    Post,
+    Delete,
+    /// axum `any(..)`：透传代理，文档中展开为 GET / POST
+    Any,
+}
+
+impl Method {
+    fn openapi_methods(self) -> &'static [&'static str] {
+        match self {
+            Self::Get => &["get"],
+            Self::Post => &["post"],
+            Self::Delete => &["delete"],
+            Self::Any => &["get", "post"],
+        }
+    }
+}
+
+/// 字段 / 响应数据的 JSON Schema 形状。
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Schema {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// 任意键值对象（`Map` / 未单独登记的嵌套结构体）
+    Object,
+    /// 任意 JSON（`serde_json::Value`）
+    Any,
+    Array(&'static Schema),
+    Ref(&'static str),
+    /// 内联对象：handler 以 `json!` 拼出的包装层（`{ success, plan }` 等）
+    Fields(&'static [Field]),
+}
+
+const STRINGS: Schema = Schema::Array(&Schema::String);
+const INTEGERS: Schema = Schema::Array(&Schema::Integer);
+const OBJECTS: Schema = Schema::Array(&Schema::Object);
+const SKIPPED_ENTRIES: Schema = Schema::Array(&Schema::Ref("SkippedEntry"));
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Field {
+    pub(crate) name: &'static str,
+    pub(crate) schema: Schema,
+    pub(crate) required: bool,
+}
+
+const fn req(name: &'static str, schema: Schema) -> Field {
+    Field {
+        name,
+        schema,
+        required: true,
+    }
+}
+
+const fn opt(name: &'static str, schema: Schema) -> Field {
+    Field {
+        name,
+        schema,
+        required: false,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ComponentBody {
+    /// 结构体字段；`flatten` 为 `#[serde(flatten)]` 引入的其他组件
+    Fields {
+        fields: &'static [Field],
+        flatten: &'static [&'static str],
+    },
+    /// `rename_all = "snake_case"` 的单元枚举
+    Enum(&'static [&'static str]),
+}
+
+/// 一个 DTO 组件；`source` 为 Rust 定义所在文件（相对 `src/`）。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Component {
+    pub(crate) name: &'static str,
+    pub(crate) source: &'static str,
+    pub(crate) body: ComponentBody,
+}
+
+const fn dto(name: &'static str, source: &'static str, fields: &'static [Field]) -> Component {
+    Component {
+        name,
+        source,
+        body: ComponentBody::Fields {
+            fields,
+            flatten: &[],
+        },
+    }
+}
+
+const fn dto_flatten(
+    name: &'static str,
+    source: &'static str,
+    flatten: &'static [&'static str],
+    fields: &'static [Field],
+) -> Component {
+    Component {
+        name,
+        source,
+        body: ComponentBody::Fields { fields, flatten },
+    }
+}
+
+const fn enumeration(
+    name: &'static str,
+    source: &'static str,
+    values: &'static [&'static str],
+) -> Component {
+    Component {
+        name,
+        source,
+        body: ComponentBody::Enum(values),
+    }
+}
+
+/// 请求入参。
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Request {
+    None,
+    Json(&'static str),
+    /// `Option<Json<..>>`：可省略请求体
+    OptionalJson(&'static str),
+    /// `Query<..>`：组件字段展开为查询参数
+    Query(&'static str),
+    /// 代理类：原样透传查询串与请求体
+    Passthrough,
+}
+
+/// 响应形态。
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Reply {
+    /// 未登记（`route(..)` 的初始值；单测要求每个操作显式登记）
+    Unspecified,
+    /// 统一信封，`data` 为给定结构
+    Envelope(Schema),
+    /// 非信封响应（文件、代理、订阅源），值为 Content-Type
+    Raw(&'static str),
+    /// `text/event-stream`
+    EventStream,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Operation {
+    pub(crate) method: Method,
+    /// axum 写法（`:param` / `*rest`）
+    pub(crate) path: &'static str,
+    pub(crate) tag: &'static str,
+    pub(crate) summary: &'static str,
+    pub(crate) request: Request,
+    pub(crate) reply: Reply,
+    /// 是否支持 `X-Hbut-Account` 指定目标账号
+    pub(crate) account: bool,
+}
+
+const fn route(
+    method: Method,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+) -> Operation {
+    Operation {
+        method,
+        path,
+        tag,
+        summary,
+        request: Request::None,
+        reply: Reply::Unspecified,
+        account: false,
+    }
+}
+
+const fn get(path: &'static str, tag: &'static str, summary: &'static str) -> Operation {
+    route(Method::Get, path, tag, summary)
+}
+
+const fn post(path: &'static str, tag: &'static str, summary: &'static str) -> Operation {
+    route(Method::Post, path, tag, summary)
+}
+
+const fn delete(path: &'static str, tag: &'static str, summary: &'static str) -> Operation {
+    route(Method::Delete, path, tag, summary)
+}
+
+const fn any(path: &'static str, tag: &'static str, summary: &'static str) -> Operation {
+    route(Method::Any, path, tag, summary).passthrough()
+}
+
+impl Operation {
+    const fn body(self, component: &'static str) -> Self {
+        Self {
+            request: Request::Json(component),
+            ..self
+        }
+    }
+
+    const fn optional_body(self, component: &'static str) -> Self {
+        Self {
+            request: Request::OptionalJson(component),
+            ..self
+        }
+    }
+
+    const fn query(self, component: &'static str) -> Self {
+        Self {
+            request: Request::Query(component),
+            ..self
+        }
+    }
+
+    const fn passthrough(self) -> Self {
+        Self {
+            request: Request::Passthrough,
+            ..self
+        }
+    }
+
+    const fn returns(self, data: Schema) -> Self {
+        Self {
+            reply: Reply::Envelope(data),
+            ..self
+        }
+    }
+
+    const fn raw(self, content_type: &'static str) -> Self {
+        Self {
+            reply: Reply::Raw(content_type),
+            ..self
+        }
+    }
+
+    const fn stream(self) -> Self {
+        Self {
+            reply: Reply::EventStream,
+            ..self
+        }
+    }
+
+    const fn account(self) -> Self {
+        Self {
+            account: true,
+            ..self
+        }
+    }
+}
+
+const JSON: &str = "application/json";
+const ANY_CONTENT: &str = "*/*";
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn schema_json(schema: Schema) -> Value {
+    match schema {
+        Schema::String => json!({ "type": "string" }),
+        Schema::Integer => json!({ "type": "integer" }),
+        Schema::Number => json!({ "type": "number" }),
+        Schema::Boolean => json!({ "type": "boolean" }),
+        Schema::Object => json!({ "type": "object", "additionalProperties": true }),
+        Schema::Any => json!({}),
+        Schema::Array(item) => json!({ "type": "array", "items": schema_json(*item) }),
+        Schema::Ref(name) => schema_ref(name),
+        Schema::Fields(fields) => object_schema(fields),
+    }
+}
+
+fn object_schema(fields: &[Field]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let mut value = schema_json(field.schema);
+        if !field.required && !matches!(field.schema, Schema::Ref(_) | Schema::Any) {
+            value["nullable"] = Value::Bool(true);
+        }
+        properties.insert(field.name.to_string(), value);
+        if field.required {
+            required.push(Value::String(field.name.to_string()));
+        }
+    }
+    let mut object = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        object["required"] = Value::Array(required);
+    }
+    object
+}
+
+fn component_json(component: &Component) -> Value {
+    let mut schema = match component.body {
+        ComponentBody::Enum(values) => json!({ "type": "string", "enum": values }),
+        ComponentBody::Fields {
+            fields,
+            flatten: [],
+        } => object_schema(fields),
+        ComponentBody::Fields { fields, flatten } => {
+            let mut all_of: Vec<Value> = flatten.iter().map(|name| schema_ref(name)).collect();
+            all_of.push(object_schema(fields));
+            json!({ "allOf": all_of })
+        }
+    };
+    schema["x-rust-source"] = Value::String(format!("src/{}", component.source));
+    schema
+}
+
+fn envelope(data: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": ["success", "time"],
+        "properties": {
+            "success": { "type": "boolean" },
+            "data": data,
+            "error": { "allOf": [schema_ref("ApiError")], "nullable": true },
+            "time": { "type": "string", "example": "2026-01-01 08:00:00" },
+        },
+    })
+}
+
+/// axum 路径 → OpenAPI 路径与路径参数名（`*rest` 按单个参数描述，值可含 `/`）。
+pub(crate) fn openapi_path(path: &str) -> (String, Vec<&str>) {
+    let mut params = Vec::new();
+    let converted = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix([':', '*']) {
+            Some(name) => {
+                params.push(name);
+                format!("{{{name}}}")
+            }
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (converted, params)
+}
+
+fn operation_id(method: &str, path: &str) -> String {
+    let mut id = method.to_string();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        id.push('_');
+        id.extend(segment.trim_start_matches([':', '*']).chars().map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c
+            } else {
+                '_'
+            }
+        }));
+    }
+    if path.len() > 1 && path.ends_with('/') {
+        id.push_str("_index");
+    }
+    id
+}
+
+fn component(name: &str) -> Option<&'static Component> {
+    COMPONENTS.iter().find(|c| c.name == name)
+}
+
+fn query_parameters(name: &str) -> Vec<Value> {
+    let Some(Component {
+        body: ComponentBody::Fields { fields, .. },
+        ..
+    }) = component(name)
+    else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .map(|field| {
+            json!({
+                "name": field.name,
+                "in": "query",
+                "required": field.required,
+                "schema": schema_json(field.schema),
+            })
+        })
+        .collect()
+}
+
+fn operation_json(op: &Operation, method: &str, path_params: &[&str]) -> Value {
+    let mut parameters: Vec<Value> = path_params
+        .iter()
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+    if let Request::Query(name) = op.request {
+        parameters.extend(query_parameters(name));
+    }
+    if op.account {
+        parameters.push(json!({ "$ref": "#/components/parameters/AccountHeader" }));
+    }
+
+    let mut operation = json!({
+        "operationId": operation_id(method, op.path),
+        "tags": [op.tag],
+        "summary": op.summary,
+    });
+    if !parameters.is_empty() {
+        operation["parameters"] = Value::Array(parameters);
+    }
+
+    let request_body = match op.request {
+        Request::Json(name) => Some((true, json!({ JSON: { "schema": schema_ref(name) } }))),
+        Request::OptionalJson(name) => {
+            Some((false, json!({ JSON: { "schema": schema_ref(name) } })))
+        }
+        Request::Passthrough if method != "get" => Some((
+            false,
+            json!({ ANY_CONTENT: { "schema": { "type": "string", "format": "binary" } } }),
+        )),
+        _ => None,
+    };
+    if let Some((required, content)) = request_body {
+        operation["requestBody"] = json!({ "required": required, "content": content });
+    }
+    if matches!(op.request, Request::Passthrough) {
+        operation["description"] = Value::String("查询串与请求体原样转发到上游。".to_string());
+    }
+
+    let ok = match op.reply {
+        // 未登记的操作由单测拦下；文档生成仍按自由结构兜底
+        Reply::Unspecified => json!({
+            "description": "成功",
+            "content": { JSON: { "schema": envelope(schema_json(Schema::Any)) } },
+        }),
+        Reply::Envelope(data) => json!({
+            "description": "成功",
+            "content": { JSON: { "schema": envelope(schema_json(data)) } },
+        }),
+        Reply::Raw(content_type) => json!({
+            "description": "成功（非信封响应）",
+            "content": { content_type: { "schema": { "type": "string", "format": "binary" } } },
+        }),
+        Reply::EventStream => json!({
+            "description": "SSE 事件流",
+            "content": { "text/event-stream": { "schema": { "type": "string" } } },
+        }),
+    };
+    operation["responses"] = json!({
+        "200": ok,
+        "default": { "$ref": "#/components/responses/Error" },
+    });
+
+    if matches!(
+        bridge_route_policy(op.path),
+        BridgeRoutePolicy::PublicHealth
+            | BridgeRoutePolicy::PublicEmbed
+            | BridgeRoutePolicy::PublicFeed
+    ) {
+        operation["security"] = json!([]);
+    }
+    if let Some(scope) = bridge_route_scope(op.path) {
+        // 用户签发的 API 令牌访问此路由所需权限
+        operation["x-hbut-scope"] = Value::String(scope.as_str().to_string());
+    }
+    operation
+}
+
+/// 调试路由仅在 debug 构建注册，文档保持一致。
+fn is_served(op: &Operation) -> bool {
+    cfg!(debug_assertions) || bridge_route_policy(op.path) != BridgeRoutePolicy::DebugOnly
+}
+
+/// 生成 OpenAPI 3.0 文档。
+pub(crate) fn openapi_document() -> Value {
+    let mut paths = Map::new();
+    let mut tags: Vec<&str> = Vec::new();
+    for op in OPERATIONS.iter().filter(|op| is_served(op)) {
+        let (path, params) = openapi_path(op.path);
+        let item = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        for method in op.method.openapi_methods() {
+            item[*method] = operation_json(op, method, &params);
+        }
+        if !tags.contains(&op.tag) {
+            tags.push(op.tag);
+        }
+    }
+
+    let schemas: Map<String, Value> = COMPONENTS
+        .iter()
+        .map(|c| (c.name.to_string(), component_json(c)))
+        .collect();
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Mini-HBUT HTTP Bridge",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "本地 HTTP Bridge。JSON 接口统一返回 `{ success, data, error, time }`；\
+                失败时 `error.code` 取值：session_expired / captcha_required / upstream_maintenance / \
+                rate_limited / validation / network / storage / internal / forbidden。",
+        },
+        "servers": [{ "url": format!("http://{}", super::bridge_listen_addr()) }],
+        "tags": tags.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+        "security": [{ "bridgeToken": [] }],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "responses": {
+                "Error": {
+                    "description": "失败：`success=false`，`error` 见 ApiError",
+                    "content": { JSON: { "schema": envelope(json!({ "nullable": true })) } },
+                },
+            },
+            "parameters": {
+                "AccountHeader": {
+                    "name": "X-Hbut-Account",
+                    "in": "header",
+                    "required": false,
+                    "description": "目标账号学号；缺省作用于活动账号",
+                    "schema": { "type": "string" },
+                },
+            },
+            "securitySchemes": {
+                "bridgeToken": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Bridge 会话令牌或 HBUT_BRIDGE_TOKEN（也可放在 X-Local-Token 头）；\
+                        可信 WebView Origin 无需令牌。应用内签发的 `hbt_` API 令牌仅可访问带 \
+                        `x-hbut-scope` 的操作，且须含对应权限",
+                },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::Path;
+
+    fn baseline_routes() -> BTreeSet<String> {
+        include_str!("../../../tests/http_route_baseline.txt")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// 与 `http_route_baseline.txt` 相同的写法。
+    fn method_name(method: Method) -> &'static str {
+        match method {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Delete => "DELETE",
+            Method::Any => "ANY",
+        }
+    }
+
+    #[test]
+    fn every_route_has_an_operation() {
+        let documented: Vec<String> = OPERATIONS
+            .iter()
+            .map(|op| format!("{} {}", method_name(op.method), op.path))
+            .collect();
+        let unique: BTreeSet<String> = documented.iter().cloned().collect();
+        assert_eq!(documented.len(), unique.len(), "duplicate operations");
+
+        let routes = baseline_routes();
+        let missing: Vec<_> = routes.difference(&unique).collect();
+        let stale: Vec<_> = unique.difference(&routes).collect();
+        assert!(
+            missing.is_empty(),
+            "routes without an OpenAPI operation (add them to OPERATIONS): {missing:?}"
+        );
+        assert!(stale.is_empty(), "operations without a route: {stale:?}");
+    }
+
+    #[test]
+    fn every_operation_documents_its_response() {
+        let undocumented: Vec<String> = OPERATIONS
+            .iter()
+            .filter(|op| matches!(op.reply, Reply::Unspecified))
+            .map(|op| format!("{:?} {}", op.method, op.path))
+            .collect();
+        assert!(
+            undocumented.is_empty(),
+            "operations without a response schema (add .returns/.raw/.stream): {undocumented:?}"
+        );
+    }
+
+    #[test]
+    fn referenced_components_exist_and_are_used() {
+        fn collect_refs(schema: Schema, out: &mut BTreeSet<&'static str>) {
+            match schema {
+                Schema::Ref(name) => {
+                    out.insert(name);
+                }
+                Schema::Array(item) => collect_refs(*item, out),
+                Schema::Fields(fields) => {
+                    for field in fields {
+                        collect_refs(field.schema, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut referenced = BTreeSet::from(["ApiError"]);
+        for op in OPERATIONS {
+            match op.request {
+                Request::Json(name) | Request::OptionalJson(name) | Request::Query(name) => {
+                    referenced.insert(name);
+                }
+                Request::None | Request::Passthrough => {}
+            }
+            if let Reply::Envelope(data) = op.reply {
+                collect_refs(data, &mut referenced);
+            }
+        }
+        for c in COMPONENTS {
+            if let ComponentBody::Fields { fields, flatten } = c.body {
+                referenced.extend(flatten.iter().copied());
+                for field in fields {
+                    collect_refs(field.schema, &mut referenced);
+                }
+            }
+        }
+
+        let names: Vec<&str> = COMPONENTS.iter().map(|c| c.name).collect();
+        let declared: BTreeSet<&str> = names.iter().copied().collect();
+        assert_eq!(names.len(), declared.len(), "duplicate components");
+        let missing: Vec<_> = referenced.difference(&declared).collect();
+        let unused: Vec<_> = declared.difference(&referenced).collect();
+        assert!(missing.is_empty(), "undeclared components: {missing:?}");
+        assert!(unused.is_empty(), "unused components: {unused:?}");
+    }
+
+    #[test]
+    fn document_is_well_formed() {
+        let doc = openapi_document();
+        assert_eq!(doc["openapi"], "3.0.3");
+
+        let mut operation_ids = BTreeSet::new();
+        for (path, item) in doc["paths"].as_object().expect("paths") {
+            let declared: BTreeSet<&str> = path
+                .split('/')
+                .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+                .collect();
+            for (method, operation) in item.as_object().expect("path item") {
+                let id = operation["operationId"].as_str().expect("operationId");
+                assert!(operation_ids.insert(id.to_string()), "duplicate {id}");
+                let params: BTreeSet<&str> = operation["parameters"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|p| p["in"] == "path")
+                    .filter_map(|p| p["name"].as_str())
+                    .collect();
+                assert_eq!(params, declared, "{method} {path} path parameters");
+            }
+        }
+
+        fn check_refs(value: &Value, doc: &Value) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(target) = map.get("$ref").and_then(Value::as_str) {
+                        let pointer = target.trim_start_matches('#');
+                        assert!(doc.pointer(pointer).is_some(), "dangling {target}");
+                    }
+                    map.values().for_each(|v| check_refs(v, doc));
+                }
+                Value::Array(items) => items.iter().for_each(|v| check_refs(v, doc)),
+                _ => {}
+            }
+        }
+        check_refs(&doc, &doc);
+
+        let health = &doc["paths"]["/health"]["get"];
+        assert_eq!(health["security"], json!([]));
+        let login = &doc["paths"]["/login"]["post"];
+        assert_eq!(
+            login["requestBody"]["content"][JSON]["schema"]["$ref"],
+            "#/components/schemas/LoginRequest"
+        );
+        assert_eq!(
+            login["responses"]["200"]["content"][JSON]["schema"]["properties"]["data"]["$ref"],
+            "#/components/schemas/UserInfo"
+        );
+        assert_eq!(
+            doc["paths"]["/sync_grades"]["post"]["x-hbut-scope"],
+            "grades:read"
+        );
+        assert!(login.get("x-hbut-scope").is_none());
+        let audit = &doc["paths"]["/degree_audit"]["post"]["responses"]["200"]["content"][JSON];
+        assert_eq!(
+            audit["schema"]["properties"]["data"]["properties"]["data"]["$ref"],
+            "#/components/schemas/DegreeAuditReport"
+        );
+        let bundle = &doc["paths"]["/module_bundle/content/{channel}/{module_id}/{version}/{path}"];
+        assert!(bundle["get"].is_object());
+    }
+
+    /// 源码中的结构体字段：`(serde 名, Rust 类型, 是否必填, 是否 flatten)`。
+    struct RustField {
+        name: String,
+        ty: String,
+        required: bool,
+        flatten: bool,
+    }
+
+    fn serde_attr_values<'a>(attrs: &'a [String], key: &str) -> Vec<&'a str> {
+        let needle = format!("{key} = \"");
+        let mut values = Vec::new();
+        for attr in attrs.iter().filter(|a| a.starts_with("#[serde(")) {
+            for (index, _) in attr.match_indices(&needle) {
+                let rest = &attr[index + needle.len()..];
+                values.extend(rest.split('"').next());
+            }
+        }
+        values
+    }
+
+    fn has_serde_flag(attrs: &[String], flag: &str) -> bool {
+        attrs.iter().filter(|a| a.starts_with("#[serde(")).any(|a| {
+            a["#[serde(".len()..]
+                .trim_end_matches(")]")
+                .split(',')
+                .any(|part| part.trim() == flag)
+        })
+    }
+
+    fn camel_case(name: &str) -> String {
+        let mut out = String::new();
+        let mut upper = false;
+        for c in name.chars() {
+            if c == '_' {
+                upper = true;
+            } else if upper {
+                out.extend(c.to_uppercase());
+                upper = false;
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    /// 读取 `struct <name> { .. }` 的字段（单行字段声明，属性位于字段上方）。
+    fn rust_struct(source: &str, name: &str) -> Option<Vec<RustField>> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join(source);
+        let text = std::fs::read_to_string(&path).ok()?.replace("\r\n", "\n");
+        let lines: Vec<&str> = text.lines().collect();
+        let header = format!("struct {name} ");
+        let start = lines.iter().position(|line| {
+            let line = line.trim_start();
+            (line.starts_with(&header) || line.contains(&format!(" {header}")))
+                && line.trim_end().ends_with('{')
+        })?;
+
+        let struct_attrs: Vec<String> = lines[..start]
+            .iter()
+            .rev()
+            .map(|line| line.trim())
+            .take_while(|line| line.starts_with("#[") || line.starts_with("///"))
+            .map(str::to_string)
+            .collect();
+        let struct_default = has_serde_flag(&struct_attrs, "default");
+        let camel = serde_attr_values(&struct_attrs, "rename_all").contains(&"camelCase");
+
+        let mut fields = Vec::new();
+        let mut attrs: Vec<String> = Vec::new();
+        for line in lines[start + 1..].iter().map(|line| line.trim()) {
+            if line == "}" {
+                break;
+            }
+            if line.starts_with("#[") {
+                attrs.push(line.to_string());
+                continue;
+            }
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let decl = line
+                .trim_start_matches("pub(crate) ")
+                .trim_start_matches("pub ")
+                .trim_end_matches(',');
+            let (field, ty) = decl.split_once(": ")?;
+            let optional = ty.starts_with("Option<");
+            let ty = if optional {
+                &ty["Option<".len()..ty.len() - 1]
+            } else {
+                ty
+            };
+            let name = serde_attr_values(&attrs, "rename")
+                .first()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| {
+                    if camel {
+                        camel_case(field)
+                    } else {
+                        field.to_string()
+                    }
+                });
+            fields.push(RustField {
+                name,
+                ty: ty.to_string(),
+                required: !optional && !struct_default && !has_serde_flag(&attrs, "default"),
+                flatten: has_serde_flag(&attrs, "flatten"),
+            });
+            attrs.clear();
+        }
+        Some(fields)
+    }
+
+    /// 文档类型与 Rust 类型是否相容（嵌套结构体可登记为组件引用或自由对象）。
+    fn type_matches(schema: Schema, ty: &str) -> bool {
+        let ty = ty.trim();
+        if let Some(inner) = ty.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+            return matches!(schema, Schema::Array(item) if type_matches(*item, inner));
+        }
+        let base = ty.rsplit("::").next().unwrap_or(ty);
+        match base {
+            "String" | "&'static str" | "&str" => matches!(schema, Schema::String),
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "usize" | "isize" => {
+                matches!(schema, Schema::Integer)
+            }
+            "f32" | "f64" => matches!(schema, Schema::Number),
+            "bool" => matches!(schema, Schema::Boolean),
+            "Value" => matches!(schema, Schema::Any),
+            _ if base.starts_with("Map<") || base.starts_with("HashMap<") => {
+                matches!(schema, Schema::Object)
+            }
+            _ => match schema {
+                Schema::Ref(name) => name == base,
+                Schema::Object => true,
+                _ => false,
+            },
+        }
+    }
+
+    #[test]
+    fn components_match_rust_dtos() {
+        let mut problems = Vec::new();
+        for c in COMPONENTS {
+            let Some(rust) = rust_struct_or_enum(c) else {
+                problems.push(format!("{}: not found in src/{}", c.name, c.source));
+                continue;
+            };
+            let ComponentBody::Fields { fields, flatten } = c.body else {
+                continue;
+            };
+            let rust_flatten: BTreeSet<&str> = rust
+                .iter()
+                .filter(|f| f.flatten)
+                .map(|f| f.ty.rsplit("::").next().unwrap_or(&f.ty))
+                .collect();
+            if rust_flatten != flatten.iter().copied().collect() {
+                problems.push(format!("{}: flatten {rust_flatten:?}", c.name));
+            }
+            let rust_fields: BTreeMap<&str, &RustField> = rust
+                .iter()
+                .filter(|f| !f.flatten)
+                .map(|f| (f.name.as_str(), f))
+                .collect();
+            let documented: BTreeSet<&str> = fields.iter().map(|f| f.name).collect();
+            let actual: BTreeSet<&str> = rust_fields.keys().copied().collect();
+            if documented != actual {
+                problems.push(format!(
+                    "{}: fields differ, missing {:?}, stale {:?}",
+                    c.name,
+                    actual.difference(&documented).collect::<Vec<_>>(),
+                    documented.difference(&actual).collect::<Vec<_>>(),
+                ));
+                continue;
+            }
+            for field in fields.iter() {
+                let rust_field = rust_fields[field.name];
+                if rust_field.required != field.required {
+                    problems.push(format!("{}.{}: required mismatch", c.name, field.name));
+                }
+                if !type_matches(field.schema, &rust_field.ty) {
+                    problems.push(format!(
+                        "{}.{}: {:?} vs `{}`",
+                        c.name, field.name, field.schema, rust_field.ty
+                    ));
+                }
+            }
+        }
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+
+    /// 枚举组件只核对定义存在与取值；结构体组件返回字段列表。
+    fn rust_struct_or_enum(c: &Component) -> Option<Vec<RustField>> {
+        match c.body {
+            ComponentBody::Fields { .. } => rust_struct(c.source, c.name),
+            ComponentBody::Enum(values) => {
+                let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("src")
+                    .join(c.source);
+                let text = std::fs::read_to_string(path).ok()?;
+                let body = text.split(&format!("enum {} {{", c.name)).nth(1)?;
+                let body = &body[..body.find('}')?];
+                let variants: Vec<String> = body
+                    .lines()
+                    .map(|line| line.trim().trim_end_matches(','))
+                    .filter(|line| !line.is_empty() && !line.starts_with("//"))
+                    .map(snake_case)
+                    .collect();
+                (variants == values).then(Vec::new)
+            }
+        }
+    }
+
+    fn snake_case(name: &str) -> String {
+        let mut out = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() {
+                if i > 0 {
+                    out.push('_');
+                }
+                out.push(c.to_ascii_lowercase());
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,867 @@
+//! Bridge 路由登记：每条 `method + path` 一项，按领域 router 顺序排列。
+
+use super::{
+    any, delete, get, post, req, Field, Operation, Schema, ANY_CONTENT, INTEGERS, JSON, OBJECTS,
+    SKIPPED_ENTRIES,
+};
+
+const TAG_AUTH: &str = "auth";
+const TAG_ACADEMIC: &str = "academic";
+const TAG_ACCOUNTS: &str = "accounts";
+const TAG_SCHEDULE: &str = "schedule";
+const TAG_COURSE_SELECTION: &str = "course_selection";
+const TAG_ONLINE_LEARNING: &str = "online_learning";
+const TAG_SYSTEM: &str = "system";
+const TAG_PROXY: &str = "proxy";
+const TAG_AI: &str = "ai";
+const TAG_SCHEDULER: &str = "scheduler";
+const TAG_EVENTS: &str = "events";
+const TAG_DEBUG: &str = "debug";
+
+const CALENDAR: &str = "text/calendar";
+const BINARY: &str = "application/octet-stream";
+
+const ACCOUNT_SUMMARIES: Schema = Schema::Array(&Schema::Ref("AccountSummary"));
+const JOB_STATUSES: Schema = Schema::Array(&Schema::Ref("JobStatus"));
+
+/// `{ success, removed }`：删除 / 取消关注类操作。
+const REMOVED: Schema = Schema::Fields(&[
+    req("success", Schema::Boolean),
+    req("removed", Schema::Boolean),
+]);
+/// `{ success, calendars, semester, skipped }`：课表 / 草案导出 .ics 预览。
+const ICS_CALENDARS: &[Field] = &[
+    req("success", Schema::Boolean),
+    req("calendars", OBJECTS),
+    req("semester", Schema::String),
+    req("skipped", SKIPPED_ENTRIES),
+];
+/// `{ success, reminder_days }`：图书馆到期提醒提前量读写。
+const REMINDER_DAYS: Schema = Schema::Fields(&[
+    req("success", Schema::Boolean),
+    req("reminder_days", INTEGERS),
+]);
+
+/// 全部 Bridge 路由（按领域 router 顺序）。
+pub(crate) const OPERATIONS: &[Operation] = &[
+    // ── auth ──
+    post("/login", TAG_AUTH, "教务账号登录")
+        .body("LoginRequest")
+        .returns(Schema::Ref("UserInfo")),
+    post("/restore_session", TAG_AUTH, "以 Cookie 快照恢复会话")
+        .body("RestoreRequest")
+        .returns(Schema::Ref("UserInfo")),
+    get("/export_cookies", TAG_AUTH, "导出当前会话 Cookie").returns(Schema::Any),
+    post("/import_cookies", TAG_AUTH, "导入各站点 Cookie")
+        .body("CookieSnapshotRequest")
+        .returns(Schema::Any),
+    // ── academic ──
+    post("/sync_grades", TAG_ACADEMIC, "同步成绩（可附带 GPA 统计）")
+        .optional_body("SyncGradesRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/sync_schedule", TAG_ACADEMIC, "同步课表")
+        .optional_body("ScheduleQueryRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_exams", TAG_ACADEMIC, "获取考试安排")
+        .body("ExamRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/exam/countdown", TAG_ACADEMIC, "考试倒计时")
+        .body("ExamCountdownRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("semester", Schema::String),
+            req("data", Schema::Array(&Schema::Ref("ExamCountdown"))),
+            req("reminder_leads", INTEGERS),
+        ])),
+    post("/exam/reminder_leads", TAG_ACADEMIC, "设置考试提醒提前量")
+        .body("ExamReminderLeadsRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("reminder_leads", INTEGERS),
+        ])),
+    post("/fetch_ranking", TAG_ACADEMIC, "获取绩点排名")
+        .body("RankingRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_student_info", TAG_ACADEMIC, "获取学籍信息")
+        .account()
+        .returns(Schema::Any),
+    post(
+        "/fetch_personal_login_access_info",
+        TAG_ACADEMIC,
+        "个人登录访问记录",
+    )
+    .optional_body("PersonalLoginAccessRequest")
+    .account()
+    .returns(Schema::Any),
+    post("/fetch_semesters", TAG_ACADEMIC, "学期列表")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_classroom_buildings", TAG_ACADEMIC, "教学楼列表")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_classrooms", TAG_ACADEMIC, "空教室查询")
+        .body("ClassroomQueryRequest")
+        .account()
+        .returns(Schema::Any),
+    post(
+        "/fetch_training_plan_options",
+        TAG_ACADEMIC,
+        "培养方案筛选项",
+    )
+    .account()
+    .returns(Schema::Any),
+    post("/fetch_training_plan_jys", TAG_ACADEMIC, "培养方案教研室")
+        .body("TrainingPlanJysRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_training_plan_courses", TAG_ACADEMIC, "培养方案课程")
+        .body("TrainingPlanCoursesRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_calendar_data", TAG_ACADEMIC, "校历")
+        .body("CalendarRequest")
+        .account()
+        .returns(Schema::Any),
+    post("/fetch_academic_progress", TAG_ACADEMIC, "学业进度")
+        .body("AcademicProgressRequest")
+        .account()
+        .returns(Schema::Any),
+    post(
+        "/degree_audit",
+        TAG_ACADEMIC,
+        "学业审核（培养方案 × 学业进度 × 成绩，离线）",
+    )
+    .body("DegreeAuditRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("offline", Schema::Boolean),
+        req("data", Schema::Ref("DegreeAuditReport")),
+    ])),
+    // ── accounts ──
+    get("/accounts", TAG_ACCOUNTS, "已登录账号列表").returns(ACCOUNT_SUMMARIES),
+    post(
+        "/accounts/switch",
+        TAG_ACCOUNTS,
+        "切换活动账号（缺省学号表示停放当前账号）",
+    )
+    .optional_body("AccountSwitchRequest")
+    .returns(Schema::Any),
+    post("/accounts/remove", TAG_ACCOUNTS, "移除账号")
+        .body("AccountRemoveRequest")
+        .returns(ACCOUNT_SUMMARIES),
+    // ── schedule ──
+    post(
+        "/schedule/custom/list",
+        TAG_SCHEDULE,
+        "自定义课程（单学期）",
+    )
+    .body("CustomScheduleListRequest")
+    .returns(Schema::Any),
+    post(
+        "/schedule/custom/list_all",
+        TAG_SCHEDULE,
+        "自定义课程（全部学期）",
+    )
+    .body("CustomScheduleListAllRequest")
+    .returns(Schema::Any),
+    post("/schedule/custom/add", TAG_SCHEDULE, "新增自定义课程")
+        .body("AddCustomScheduleCourseRequest")
+        .returns(Schema::Any),
+    post("/schedule/custom/delete", TAG_SCHEDULE, "删除自定义课程")
+        .body("DeleteCustomScheduleCourseRequest")
+        .returns(Schema::Any),
+    post("/schedule/custom/update", TAG_SCHEDULE, "更新自定义课程")
+        .body("UpdateCustomScheduleCourseRequest")
+        .returns(Schema::Any),
+    post("/schedule/conflicts", TAG_SCHEDULE, "课表 / 考试冲突检测")
+        .body("ScheduleConflictRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("semester", Schema::String),
+            req("count", Schema::Integer),
+            req("conflicts", OBJECTS),
+            req("unplaced", SKIPPED_ENTRIES),
+        ])),
+    post("/schedule/free_slots", TAG_SCHEDULE, "空闲时间查询")
+        .body("FreeSlotRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("semester", Schema::String),
+            req("peers", Schema::Integer),
+            req("count", Schema::Integer),
+            req("blocks", Schema::Array(&Schema::Ref("FreeBlock"))),
+            req("skipped", SKIPPED_ENTRIES),
+        ])),
+    post("/schedule/plans/list", TAG_SCHEDULE, "排课草案列表")
+        .body("SchedulePlanListRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("plans", Schema::Array(&Schema::Ref("DraftPlan"))),
+            req("max_plans", Schema::Integer),
+            req("max_courses", Schema::Integer),
+        ])),
+    post("/schedule/plans/save", TAG_SCHEDULE, "新建 / 覆盖排课草案")
+        .body("PlanSaveRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("plan", Schema::Ref("DraftPlan")),
+        ])),
+    post("/schedule/plans/delete", TAG_SCHEDULE, "删除排课草案")
+        .body("PlanRequest")
+        .returns(REMOVED),
+    post(
+        "/schedule/plans/simulate",
+        TAG_SCHEDULE,
+        "排课草案模拟（课表合并 / 冲突 / 学分）",
+    )
+    .body("PlanSimulateRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("offline", Schema::Boolean),
+        req("data", Schema::Ref("PlanSimulationReport")),
+    ])),
+    post(
+        "/schedule/plans/export_ics",
+        TAG_SCHEDULE,
+        "排课草案导出 .ics 预览",
+    )
+    .body("PlanIcsRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("plan_id", Schema::String),
+        req("calendars", OBJECTS),
+        req("semester", Schema::String),
+        req("skipped", SKIPPED_ENTRIES),
+    ])),
+    post(
+        "/export_schedule_calendar",
+        TAG_SCHEDULE,
+        "导出日历事件文件",
+    )
+    .body("ScheduleExportRequest")
+    .returns(Schema::Any),
+    post("/export_schedule_ics", TAG_SCHEDULE, "导出课表 .ics")
+        .body("ScheduleIcsRequest")
+        .returns(Schema::Fields(ICS_CALENDARS)),
+    post(
+        "/import_schedule_ics",
+        TAG_SCHEDULE,
+        "导入 .ics（预览 / 提交）",
+    )
+    .body("ScheduleIcsImportRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("semester", Schema::String),
+        req("committed", Schema::Boolean),
+        req("imported", Schema::Integer),
+        req("courses", OBJECTS),
+        req("conflicts", OBJECTS),
+        req("skipped", SKIPPED_ENTRIES),
+    ])),
+    post("/schedule/feed/issue", TAG_SCHEDULE, "签发日历订阅令牌")
+        .body("ScheduleFeedRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("token", Schema::String),
+            req("device", Schema::String),
+            req("created_at", Schema::String),
+            req("path", Schema::String),
+            req("url", Schema::String),
+            req("webcal_url", Schema::String),
+            req("refresh_minutes", Schema::Integer),
+        ])),
+    post("/schedule/feed/revoke", TAG_SCHEDULE, "作废日历订阅令牌")
+        .body("ScheduleFeedRequest")
+        .returns(Schema::Fields(&[
+            req("success", Schema::Boolean),
+            req("revoked", Schema::Integer),
+        ])),
+    get("/webcal/:token", TAG_SCHEDULE, "日历订阅源").raw(CALENDAR),
+    get("/exports/:filename", TAG_SCHEDULE, "下载导出文件").raw(CALENDAR),
+    post(
+        "/debug/custom_schedule/upsert",
+        TAG_DEBUG,
+        "批量写入自定义课程（调试）",
+    )
+    .body("DebugCustomScheduleUpsertRequest")
+    .returns(Schema::Any),
+    // ── course_selection ──
+    post(
+        "/course_selection/overview",
+        TAG_COURSE_SELECTION,
+        "选课批次概览",
+    )
+    .returns(Schema::Any),
+    post(
+        "/course_selection/list",
+        TAG_COURSE_SELECTION,
+        "可选课程列表",
+    )
+    .body("CourseSelectionListRequest")
+    .returns(Schema::Any),
+    post(
+        "/course_selection/end_time",
+        TAG_COURSE_SELECTION,
+        "选课截止时间",
+    )
+    .body("CourseSelectionEndTimeRequest")
+    .returns(Schema::Any),
+    post(
+        "/course_selection/child_classes",
+        TAG_COURSE_SELECTION,
+        "子教学班",
+    )
+    .body("CourseSelectionChildClassesRequest")
+    .returns(Schema::Any),
+    post("/course_selection/select", TAG_COURSE_SELECTION, "选课")
+        .body("CourseSelectionSelectRequest")
+        .returns(Schema::Any),
+    post("/course_selection/withdraw", TAG_COURSE_SELECTION, "退课")
+        .body("CourseSelectionWithdrawRequest")
+        .returns(Schema::Any),
+    post(
+        "/course_selection/selected_courses",
+        TAG_COURSE_SELECTION,
+        "已选课程",
+    )
+    .body("CourseSelectionSelectedCoursesRequest")
+    .returns(Schema::Any),
+    post(
+        "/course_selection/detail_intro",
+        TAG_COURSE_SELECTION,
+        "课程简介",
+    )
+    .body("CourseSelectionDetailRequest")
+    .returns(Schema::Any),
+    post(
+        "/course_selection/detail_teacher",
+        TAG_COURSE_SELECTION,
+        "任课教师介绍",
+    )
+    .body("CourseSelectionDetailRequest")
+    .returns(Schema::Any),
+    post(
+        "/course_selection/watchlist",
+        TAG_COURSE_SELECTION,
+        "选课余量监控：关注列表",
+    )
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("rounds", Schema::Array(&Schema::Ref("WatchRound"))),
+        req("max_rounds", Schema::Integer),
+        req("max_items", Schema::Integer),
+    ])),
+    post(
+        "/course_selection/watch",
+        TAG_COURSE_SELECTION,
+        "选课余量监控：关注教学班",
+    )
+    .body("CourseSelectionWatchRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("round", Schema::Ref("WatchRound")),
+    ])),
+    post(
+        "/course_selection/unwatch",
+        TAG_COURSE_SELECTION,
+        "选课余量监控：取消关注",
+    )
+    .body("CourseSelectionUnwatchRequest")
+    .returns(REMOVED),
+    // ── online_learning ──
+    post(
+        "/online_learning/chaoxing/session_status",
+        TAG_ONLINE_LEARNING,
+        "学习通会话状态",
+    )
+    .body("ChaoxingSessionStatusRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/courses",
+        TAG_ONLINE_LEARNING,
+        "学习通课程",
+    )
+    .body("ChaoxingCoursesRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/outline",
+        TAG_ONLINE_LEARNING,
+        "学习通课程目录",
+    )
+    .body("ChaoxingCourseOutlineRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/course_outline",
+        TAG_ONLINE_LEARNING,
+        "学习通课程目录（别名）",
+    )
+    .body("ChaoxingCourseOutlineRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/progress",
+        TAG_ONLINE_LEARNING,
+        "学习通课程进度",
+    )
+    .body("ChaoxingCourseProgressRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/course_progress",
+        TAG_ONLINE_LEARNING,
+        "学习通课程进度（别名）",
+    )
+    .body("ChaoxingCourseProgressRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/knowledge_cards",
+        TAG_ONLINE_LEARNING,
+        "学习通知识点任务卡",
+    )
+    .body("ChaoxingKnowledgeCardsRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/course_score",
+        TAG_ONLINE_LEARNING,
+        "学习通课程成绩",
+    )
+    .body("ChaoxingCourseScoreRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/video_status",
+        TAG_ONLINE_LEARNING,
+        "学习通视频状态",
+    )
+    .body("ChaoxingVideoStatusRequest")
+    .returns(Schema::Any),
+    post("/online_learning/overview", TAG_ONLINE_LEARNING, "网课总览")
+        .body("OnlineLearningOverviewRequest")
+        .returns(Schema::Any),
+    post(
+        "/online_learning/sync_now",
+        TAG_ONLINE_LEARNING,
+        "立即同步网课",
+    )
+    .body("OnlineLearningSyncRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/sync_runs",
+        TAG_ONLINE_LEARNING,
+        "同步记录",
+    )
+    .body("OnlineLearningSyncRunsRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/list_sync_runs",
+        TAG_ONLINE_LEARNING,
+        "同步记录（别名）",
+    )
+    .body("OnlineLearningSyncRunsRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/clear_cache",
+        TAG_ONLINE_LEARNING,
+        "清除网课缓存",
+    )
+    .body("OnlineLearningClearCacheRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/launch_url",
+        TAG_ONLINE_LEARNING,
+        "学习通章节启动地址",
+    )
+    .body("ChaoxingLaunchUrlRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/create_qr_login",
+        TAG_ONLINE_LEARNING,
+        "雨课堂扫码登录：创建",
+    )
+    .body("YuketangQrCreateRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/qr_login/create",
+        TAG_ONLINE_LEARNING,
+        "雨课堂扫码登录：创建（别名）",
+    )
+    .body("YuketangQrCreateRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/poll_qr_login",
+        TAG_ONLINE_LEARNING,
+        "雨课堂扫码登录：轮询",
+    )
+    .body("YuketangPollQrLoginRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/qr_login/poll",
+        TAG_ONLINE_LEARNING,
+        "雨课堂扫码登录：轮询（别名）",
+    )
+    .body("YuketangPollQrLoginRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/courses",
+        TAG_ONLINE_LEARNING,
+        "雨课堂课程",
+    )
+    .body("YuketangCoursesRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/outline",
+        TAG_ONLINE_LEARNING,
+        "雨课堂课程目录",
+    )
+    .body("YuketangCourseOutlineRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/course_outline",
+        TAG_ONLINE_LEARNING,
+        "雨课堂课程目录（别名）",
+    )
+    .body("YuketangCourseOutlineRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/progress",
+        TAG_ONLINE_LEARNING,
+        "雨课堂课程进度",
+    )
+    .body("YuketangCourseProgressRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/course_progress",
+        TAG_ONLINE_LEARNING,
+        "雨课堂课程进度（别名）",
+    )
+    .body("YuketangCourseProgressRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/chaoxing/report_progress",
+        TAG_ONLINE_LEARNING,
+        "学习通上报视频进度",
+    )
+    .body("ChaoxingReportProgressRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/course_chapters",
+        TAG_ONLINE_LEARNING,
+        "雨课堂章节",
+    )
+    .body("YuketangCourseChaptersRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/leaf_info",
+        TAG_ONLINE_LEARNING,
+        "雨课堂学习单元",
+    )
+    .body("YuketangLeafInfoRequest")
+    .returns(Schema::Any),
+    post(
+        "/online_learning/yuketang/heartbeat",
+        TAG_ONLINE_LEARNING,
+        "雨课堂观看心跳",
+    )
+    .body("YuketangHeartbeatRequest")
+    .returns(Schema::Any),
+    // ── system ──
+    get("/health", TAG_SYSTEM, "健康检查").returns(Schema::Any),
+    get("/openapi.json", TAG_SYSTEM, "本文档（OpenAPI 3）").raw(JSON),
+    post("/module_bundle/prepare", TAG_SYSTEM, "下载并校验本地模块包")
+        .body("ModuleBundlePrepareRequest")
+        .returns(Schema::Ref("ModuleBundlePrepareResult")),
+    post("/module_bundle/open", TAG_SYSTEM, "在独立窗口打开本地模块")
+        .body("OpenModuleBundleWindowRequest")
+        .returns(Schema::Ref("OpenModuleBundleWindowResult")),
+    get(
+        "/module_bundle/content/:channel/:module_id/:version",
+        TAG_SYSTEM,
+        "模块入口页",
+    )
+    .raw(ANY_CONTENT),
+    get(
+        "/module_bundle/content/:channel/:module_id/:version/*path",
+        TAG_SYSTEM,
+        "模块静态资源",
+    )
+    .raw(ANY_CONTENT),
+    get(
+        "/cache/get",
+        TAG_SYSTEM,
+        "读取本地缓存（需本地缓存 API 令牌）",
+    )
+    .query("CacheGetQuery")
+    .returns(Schema::Any),
+    get("/qxzkb/options", TAG_SYSTEM, "全校课表筛选项").returns(Schema::Any),
+    post("/qxzkb/jcinfo", TAG_SYSTEM, "全校课表节次信息")
+        .body("QxzkbJcinfoRequest")
+        .returns(Schema::Any),
+    post("/qxzkb/zyxx", TAG_SYSTEM, "全校课表专业列表")
+        .body("QxzkbZyxxRequest")
+        .returns(Schema::Any),
+    post("/qxzkb/kkjys", TAG_SYSTEM, "全校课表开课教研室")
+        .body("QxzkbKkjysRequest")
+        .returns(Schema::Any),
+    post("/qxzkb/query", TAG_SYSTEM, "全校课表查询")
+        .body("QxzkbQuery")
+        .returns(Schema::Any),
+    post("/library/dict", TAG_SYSTEM, "图书馆检索字典").returns(Schema::Any),
+    post("/library/search", TAG_SYSTEM, "图书检索")
+        .body("LibrarySearchRequest")
+        .returns(Schema::Any),
+    post("/library/detail", TAG_SYSTEM, "图书详情")
+        .body("LibraryDetailRequest")
+        .returns(Schema::Any),
+    post("/library/loans", TAG_SYSTEM, "图书馆：当前借阅").returns(Schema::Any),
+    post("/library/loan_history", TAG_SYSTEM, "图书馆：借阅历史")
+        .body("LibraryHistoryRequest")
+        .returns(Schema::Any),
+    post("/library/holds", TAG_SYSTEM, "图书馆：预约记录").returns(Schema::Any),
+    post("/library/fines", TAG_SYSTEM, "图书馆：欠款记录").returns(Schema::Any),
+    post("/library/renew", TAG_SYSTEM, "图书馆：按条码续借")
+        .body("LibraryRenewRequest")
+        .returns(Schema::Any),
+    get(
+        "/library/reminder_days",
+        TAG_SYSTEM,
+        "图书馆：到期提醒提前量",
+    )
+    .returns(REMINDER_DAYS),
+    post(
+        "/library/reminder_days",
+        TAG_SYSTEM,
+        "图书馆：设置到期提醒提前量",
+    )
+    .body("LibraryReminderDaysRequest")
+    .returns(REMINDER_DAYS),
+    get(
+        "/library/saved_searches",
+        TAG_SYSTEM,
+        "图书馆：检索订阅列表",
+    )
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("searches", Schema::Array(&Schema::Ref("SavedSearch"))),
+        req("max_searches", Schema::Integer),
+    ])),
+    post(
+        "/library/saved_searches/save",
+        TAG_SYSTEM,
+        "图书馆：新建 / 更新检索订阅",
+    )
+    .body("LibrarySavedSearchRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("search", Schema::Ref("SavedSearch")),
+    ])),
+    post(
+        "/library/saved_searches/delete",
+        TAG_SYSTEM,
+        "图书馆：删除检索订阅",
+    )
+    .body("LibrarySavedSearchIdRequest")
+    .returns(REMOVED),
+    post(
+        "/library/saved_searches/run",
+        TAG_SYSTEM,
+        "图书馆：立即检查检索订阅",
+    )
+    .body("LibrarySavedSearchIdRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("check", Schema::Ref("SearchCheck")),
+    ])),
+    post(
+        "/electricity_query_location",
+        TAG_SYSTEM,
+        "电费：宿舍位置查询",
+    )
+    .body("ElectricityRequest")
+    .returns(Schema::Any),
+    post("/electricity_query_account", TAG_SYSTEM, "电费：余额查询")
+        .body("ElectricityRequest")
+        .returns(Schema::Any),
+    post(
+        "/electricity/rooms",
+        TAG_SYSTEM,
+        "电费监控：房间总览与用量预测",
+    )
+    .body("ElectricityOverviewRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("count", Schema::Integer),
+        req("rooms", OBJECTS),
+    ])),
+    post(
+        "/electricity/rooms/watch",
+        TAG_SYSTEM,
+        "电费监控：关注 / 更新房间",
+    )
+    .body("ElectricityWatchRequest")
+    .returns(Schema::Fields(&[
+        req("success", Schema::Boolean),
+        req("room", Schema::Ref("WatchedRoom")),
+        req("share_code", Schema::String),
+    ])),
+    post(
+        "/electricity/rooms/unwatch",
+        TAG_SYSTEM,
+        "电费监控：取消关注",
+    )
+    .body("ElectricityRoomRequest")
+    .returns(REMOVED),
+    post("/fetch_transaction_history", TAG_SYSTEM, "一卡通交易记录")
+        .body("TransactionRequest")
+        .returns(Schema::Any),
+    post("/one_code_token", TAG_SYSTEM, "一码通令牌").returns(Schema::Any),
+    post("/campus_code/config", TAG_SYSTEM, "校园码配置")
+        .body("CampusCodeRequest")
+        .returns(Schema::Any),
+    post("/campus_code/qrcode", TAG_SYSTEM, "校园码二维码")
+        .body("CampusCodeRequest")
+        .returns(Schema::Any),
+    post("/campus_code/order_status", TAG_SYSTEM, "校园码订单状态")
+        .body("CampusCodeRequest")
+        .returns(Schema::Any),
+    // ── proxy ──
+    any("/towergo/*path", TAG_PROXY, "TowerGo 代理").raw(ANY_CONTENT),
+    get(
+        "/campus-map/direction",
+        TAG_PROXY,
+        "校园地图步行路线（from / to 透传）",
+    )
+    .passthrough()
+    .raw(JSON),
+    any("/campus-guide/*path", TAG_PROXY, "校园导览签名代理").raw(ANY_CONTENT),
+    any("/school-website", TAG_PROXY, "学校官网代理（首页）").raw(ANY_CONTENT),
+    any("/school-website/", TAG_PROXY, "学校官网代理（首页）").raw(ANY_CONTENT),
+    any("/school-website/*path", TAG_PROXY, "学校官网代理").raw(ANY_CONTENT),
+    get("/resource_share/direct_url", TAG_PROXY, "资源共享直链")
+        .query("ResourceShareProxyQuery")
+        .returns(Schema::Any),
+    get("/resource_share/proxy", TAG_PROXY, "资源共享文件代理")
+        .query("ResourceShareProxyQuery")
+        .raw(BINARY),
+    get("/proxy/video", TAG_PROXY, "学习通视频代理（url 参数透传）")
+        .passthrough()
+        .raw(ANY_CONTENT),
+    post("/campus-guide-debug/probe", TAG_DEBUG, "校园导览上游探测")
+        .body("CampusGuideDebugProbeRequest")
+        .returns(Schema::Any),
+    get(
+        "/campus-guide-debug/field-matrix",
+        TAG_DEBUG,
+        "校园导览字段矩阵",
+    )
+    .returns(Schema::Any),
+    // ── ai ──
+    post("/ai_init", TAG_AI, "初始化 AI 助手会话凭据").returns(Schema::Any),
+    post("/ai_upload", TAG_AI, "上传附件")
+        .body("AiUploadRequest")
+        .returns(Schema::Any),
+    post("/ai_chat", TAG_AI, "对话")
+        .body("AiChatRequest")
+        .returns(Schema::Any),
+    post("/ai_chat_stream", TAG_AI, "流式对话（SSE）")
+        .body("AiChatRequest")
+        .stream(),
+    post("/ai_chat_session/new", TAG_AI, "新建会话")
+        .body("AiSessionNewRequest")
+        .returns(Schema::Any),
+    post("/ai_chat_session/history", TAG_AI, "会话列表")
+        .body("AiSessionHistoryRequest")
+        .returns(Schema::Any),
+    post("/ai_chat_session/messages", TAG_AI, "会话消息")
+        .body("AiSessionMessagesRequest")
+        .returns(Schema::Any),
+    post("/ai_chat_session/delete", TAG_AI, "删除会话")
+        .body("AiSessionDeleteRequest")
+        .returns(Schema::Any),
+    // ── scheduler ──
+    get("/background_jobs", TAG_SCHEDULER, "后台任务状态").returns(JOB_STATUSES),
+    post(
+        "/background_jobs/set_enabled",
+        TAG_SCHEDULER,
+        "启停后台任务",
+    )
+    .body("JobEnabledRequest")
+    .returns(Schema::Ref("JobStatus")),
+    post("/background_jobs/update", TAG_SCHEDULER, "更新后台任务配置")
+        .body("JobUpdateRequest")
+        .returns(Schema::Ref("JobStatus")),
+    post(
+        "/background_jobs/run_now",
+        TAG_SCHEDULER,
+        "立即执行后台任务",
+    )
+    .body("JobRunRequest")
+    .returns(Schema::Ref("JobStatus")),
+    // ── events ──
+    get(
+        "/events/stream",
+        TAG_EVENTS,
+        "运行时事件订阅（SSE，支持 Last-Event-ID 续传）",
+    )
+    .query("EventStreamQuery")
+    .stream(),
+    // ── debug ──
+    post("/debug/navigate", TAG_DEBUG, "前端导航")
+        .body("DebugNavigateRequest")
+        .returns(Schema::Any),
+    post("/debug/open_module", TAG_DEBUG, "打开模块")
+        .body("DebugOpenModuleRequest")
+        .returns(Schema::Any),
+    post(
+        "/debug/reset_more_modules",
+        TAG_DEBUG,
+        "重置「更多」模块缓存",
+    )
+    .optional_body("DebugResetMoreModulesRequest")
+    .returns(Schema::Any),
+    post("/debug/screenshot", TAG_DEBUG, "窗口截图")
+        .body("DebugScreenshotRequest")
+        .returns(Schema::Any),
+    post("/debug/dom_screenshot", TAG_DEBUG, "DOM 截图")
+        .body("DebugScreenshotRequest")
+        .returns(Schema::Any),
+    get("/debug/state", TAG_DEBUG, "前端状态快照").returns(Schema::Any),
+    post("/debug/save_export_file", TAG_DEBUG, "保存导出文件")
+        .body("SaveExportFileRequest")
+        .returns(Schema::Ref("SaveExportFileResult")),
+    get("/debug/logs", TAG_DEBUG, "运行时日志")
+        .query("DebugLogsQuery")
+        .returns(Schema::Any),
+    delete("/debug/logs", TAG_DEBUG, "清空运行时日志").returns(Schema::Any),
+    post("/debug/logs/query", TAG_DEBUG, "运行时日志（请求体过滤）")
+        .body("DebugLogsQuery")
+        .returns(Schema::Any),
+    post("/debug/logs/push", TAG_DEBUG, "写入运行时日志")
+        .body("DebugLogPushBody")
+        .returns(Schema::Any),
+    get("/debug/diag", TAG_DEBUG, "登录态 / Cookie / 日志诊断").returns(Schema::Any),
+    get("/debug/routes", TAG_DEBUG, "接口清单").returns(Schema::Any),
+    post("/debug/chaoxing/session", TAG_DEBUG, "学习通会话探测")
+        .optional_body("DebugForceBody")
+        .returns(Schema::Any),
+    post("/debug/chaoxing/courses", TAG_DEBUG, "学习通课程计时")
+        .optional_body("DebugForceBody")
+        .returns(Schema::Any),
+    post("/debug/inbox", TAG_DEBUG, "收件箱拉取")
+        .optional_body("DebugForceBody")
+        .returns(Schema::Any),
+    get("/debug/identity-core-diag", TAG_DEBUG, "身份核心诊断").returns(Schema::Any),
+    post(
+        "/debug/identity-intent",
+        TAG_DEBUG,
+        "模拟身份授权 deep link",
+    )
+    .body("DebugIdentityIntentRequest")
+    .returns(Schema::Any),
+    post("/debug/frontend-eval", TAG_DEBUG, "在 WebView 中执行 JS")
+        .body("DebugFrontendEvalRequest")
+        .returns(Schema::Any),
+    post("/debug/keyring-probe", TAG_DEBUG, "密钥环读写探测")
+        .body("DebugKeyringProbeRequest")
+        .returns(Schema::Any),
+];
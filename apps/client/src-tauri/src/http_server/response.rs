@@ -1,15 +1,28 @@
 //! HTTP Bridge 统一响应结构（协议固定：`{ success, data, error, time }`）。
 //!
-//! 拆分自原 `http_server.rs`，响应 JSON 结构、字段名与状态码语义完全不变。
+//! 拆分自原 `http_server.rs`，响应 JSON 结构与字段名保持兼容；`error` 在原有
+//! `kind`（中文类别）/ `message` 之外附带机器可读的 `code`、`retryable` 与可选的
+//! `retry_after_secs` / `action`，取值见 [`ApplicationErrorKind`]。
 
 use axum::http::StatusCode;
 use axum::Json;
 use serde::Serialize;
 
+use crate::application::{ApplicationError, ApplicationErrorKind};
+
+/// Bridge 访问控制（令牌缺失 / 无效）专用错误码，不属于业务错误分类。
+const FORBIDDEN_CODE: &str = "forbidden";
+
 #[derive(Serialize)]
 pub(crate) struct ApiError {
     kind: String,
     message: String,
+    code: &'static str,
+    retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<&'static str>,
 }
 
 #[derive(Serialize)]
@@ -30,20 +43,87 @@ pub(crate) fn ok<T: Serialize>(data: T) -> Json<ApiResponse<T>> {
 }
 
 /// 失败响应包装。
+///
+/// 未经应用层归类的错误按类别与文案推断 `code`：「参数错误」为 validation，
+/// 「权限不足」为 forbidden，其余按已知上游文案归类，无法识别时为 internal。
 pub(crate) fn err(
     status: StatusCode,
     kind: &str,
     message: String,
 ) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+    let error_kind = match kind {
+        "参数错误" => Some(ApplicationErrorKind::Validation),
+        "权限不足" => None,
+        _ => Some(
+            ApplicationErrorKind::classify_message(&message)
+                .unwrap_or(ApplicationErrorKind::Internal),
+        ),
+    };
+    let error = match error_kind {
+        Some(error_kind) => ApiError {
+            kind: kind.to_string(),
+            message,
+            code: error_kind.code(),
+            retryable: error_kind.retryable(),
+            retry_after_secs: error_kind.default_retry_after_secs(),
+            action: error_kind.action(),
+        },
+        None => ApiError {
+            kind: kind.to_string(),
+            message,
+            code: FORBIDDEN_CODE,
+            retryable: false,
+            retry_after_secs: None,
+            action: None,
+        },
+    };
+    failure(status, error)
+}
+
+/// 应用层错误的失败响应：状态码与 `code` 均由 [`ApplicationErrorKind`] 决定。
+pub(crate) fn app_err(
+    error: ApplicationError,
+) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+    let retry_after_secs = error.retry_after_secs();
+    let ApplicationError { kind, message, .. } = error;
+    failure(
+        status_for(kind),
+        ApiError {
+            kind: "业务错误".to_string(),
+            message,
+            code: kind.code(),
+            retryable: kind.retryable(),
+            retry_after_secs,
+            action: kind.action(),
+        },
+    )
+}
+
+/// 会话 / 验证码类返回 401，限流 429，上游维护 503；其余沿用历史的 400。
+fn status_for(kind: ApplicationErrorKind) -> StatusCode {
+    match kind {
+        ApplicationErrorKind::SessionExpired | ApplicationErrorKind::CaptchaRequired => {
+            StatusCode::UNAUTHORIZED
+        }
+        ApplicationErrorKind::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        ApplicationErrorKind::UpstreamMaintenance => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+fn failure(
+    status: StatusCode,
+    error: ApiError,
+) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+    if error.code == ApplicationErrorKind::SessionExpired.code() {
+        crate::runtime_events::publish_session_expired(&error.message);
+    }
     (
         status,
         Json(ApiResponse {
             success: false,
             data: None,
-            error: Some(ApiError {
-                kind: kind.to_string(),
-                message,
-            }),
+            error: Some(error),
             time: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         }),
     )
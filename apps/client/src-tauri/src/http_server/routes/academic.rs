@@ -8,8 +8,9 @@ use axum::{Json, Router};
 use reqwest::header::HeaderMap;
 use serde::Deserialize;
 
-use crate::http_server::auth::ensure_sensitive_bridge_auth;
-use crate::http_server::response::{err, ok, ApiResponse};
+use crate::http_server::api_tokens::BridgeScope;
+use crate::http_server::auth::{ensure_scoped_bridge_auth, ensure_sensitive_bridge_auth};
+use crate::http_server::response::{app_err, err, ok, ApiResponse};
 use crate::http_server::state::HttpState;
 
 // ────────────────────────────────────────────────────────────
@@ -17,6 +18,8 @@ use crate::http_server::state::HttpState;
 struct SyncGradesRequest {
     current_only: Option<bool>,
     teacher_current_only: Option<bool>,
+    /// 提供时在返回 payload 上附加 `analytics`（GPA / 学分统计）
+    analytics: Option<crate::grade::analytics::AnalyticsRequest>,
 }
 
 // ────────────────────────────────────────────────────────────
@@ -25,6 +28,19 @@ struct ExamRequest {
     semester: Option<String>,
 }
 
+// ────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize, Default)]
+struct ExamCountdownRequest {
+    student_id: Option<String>,
+    semester: Option<String>,
+}
+
+// ────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize)]
+struct ExamReminderLeadsRequest {
+    leads: Vec<u32>,
+}
+
 // ────────────────────────────────────────────────────────────
 #[derive(Debug, Deserialize)]
 struct RankingRequest {
@@ -94,12 +110,13 @@ async fn sync_grades(
     payload: Option<Json<SyncGradesRequest>>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
-    ensure_sensitive_bridge_auth(&headers, &state)?;
+    ensure_scoped_bridge_auth(&headers, &state, BridgeScope::GradesRead)?;
     let current_only = payload
         .as_ref()
         .and_then(|Json(req)| req.current_only.or(req.teacher_current_only))
         .unwrap_or(false);
-    let client_handle = state.client.clone();
+    let analytics = payload.and_then(|Json(req)| req.analytics);
+    let client_handle = state.client_for(&headers).await?;
     let uid = {
         let client = client_handle.read().await;
         client.user_info.as_ref().map(|u| u.student_id.clone())
@@ -115,12 +132,51 @@ async fn sync_grades(
             if let Some(job) = result.enrichment {
                 service.spawn_enrichment(job);
             }
-            Ok(ok(result.payload))
+            Ok(ok(match analytics {
+                Some(request) => service.attach_analytics(result.payload, &request),
+                None => result.payload,
+            }))
         }
         Err(e) => Err(err(StatusCode::BAD_REQUEST, "业务错误", e.to_string())),
     }
 }
 
+// ────────────────────────────────────────────────────────────
+async fn degree_audit(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<crate::modules::degree_audit::DegreeAuditRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    // 学号只取目标账号的会话，不信任请求体中的 student_id
+    let handle = state.client_for(&headers).await?;
+    let sid = handle
+        .read()
+        .await
+        .user_info
+        .as_ref()
+        .map(|u| u.student_id.clone())
+        .ok_or_else(|| {
+            app_err(crate::application::ApplicationError::session_expired(
+                "当前未登录",
+            ))
+        })?;
+    // 与 Tauri get_degree_audit 共用：只读本地缓存，离线可用
+    let report = crate::db::run_blocking(move || {
+        Ok(crate::modules::degree_audit::run_degree_audit(
+            crate::DB_FILENAME,
+            &sid,
+            &req,
+        ))
+    })
+    .await
+    .map_err(|e| app_err(crate::application::ApplicationError::storage(e)))?
+    .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        crate::modules::degree_audit::build_degree_audit_payload(report),
+    ))
+}
+
 // ────────────────────────────────────────────────────────────
 async fn sync_schedule(
     State(state): State<HttpState>,
@@ -128,184 +184,233 @@ async fn sync_schedule(
     payload: Option<Json<ScheduleQueryRequest>>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
-    ensure_sensitive_bridge_auth(&headers, &state)?;
+    ensure_scoped_bridge_auth(&headers, &state, BridgeScope::ScheduleRead)?;
     let semester = payload.and_then(|Json(req)| req.semester);
     // 统一课表同步用例：Tauri 与 HTTP Bridge 走同一 ScheduleService
     // （学期解析 → 抓取 → 成功写缓存 → 失败保留 offline 快照），本 handler 只做传输适配。
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::ScheduleService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .sync_schedule(semester)
         .await
         .map(ok)
-        .map_err(|error| err(StatusCode::BAD_REQUEST, "业务错误", error.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_exams(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<ExamRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_exams(req.semester)
         .await
         .map(ok)
-        .map_err(|error| err(StatusCode::BAD_REQUEST, "业务错误", error.to_string()))
+        .map_err(app_err)
+}
+
+// ────────────────────────────────────────────────────────────
+async fn exam_countdown(
+    State(state): State<HttpState>,
+    Json(req): Json<ExamCountdownRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = match req.student_id.filter(|sid| !sid.trim().is_empty()) {
+        Some(sid) => sid.trim().to_string(),
+        None => {
+            let client = state.client.read().await;
+            client
+                .user_info
+                .as_ref()
+                .map(|u| u.student_id.clone())
+                .ok_or_else(|| {
+                    app_err(crate::application::ApplicationError::session_expired(
+                        "当前未登录",
+                    ))
+                })?
+        }
+    };
+    Ok(ok(crate::modules::exam::build_countdown_payload(
+        crate::DB_FILENAME,
+        &sid,
+        req.semester.as_deref(),
+    )))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn update_exam_reminder_leads(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<ExamReminderLeadsRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let leads = crate::modules::exam::save_reminder_leads(crate::DB_FILENAME, req.leads)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "参数错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "reminder_leads": leads }),
+    ))
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_ranking(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<RankingRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_ranking(req.student_id, req.semester)
         .await
         .map(ok)
-        .map_err(|error| {
-            let status = if error.kind == crate::application::ApplicationErrorKind::Unauthorized {
-                StatusCode::UNAUTHORIZED
-            } else {
-                StatusCode::BAD_REQUEST
-            };
-            err(status, "业务错误", error.to_string())
-        })
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_student_info(
     State(state): State<HttpState>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
-    service
-        .fetch_student_info()
-        .await
-        .map(ok)
-        .map_err(|error| err(StatusCode::BAD_REQUEST, "业务错误", error.to_string()))
+    service.fetch_student_info().await.map(ok).map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_personal_login_access_info(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     body: Option<Json<PersonalLoginAccessRequest>>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     let req = body.map(|b| b.0).unwrap_or_default();
     // 与 Tauri fetch_personal_login_access_info 共用同一 AcademicReadService
     // （网络 → 缓存 → offline 降级），本 handler 只做传输适配。
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_personal_login_access_info(req.page, req.page_size)
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "请求失败", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_semesters(
     State(state): State<HttpState>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_semesters 共用同一 AcademicReadService（网络 → 缓存 → offline 降级）
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
-    service
-        .fetch_semesters()
-        .await
-        .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+    service.fetch_semesters().await.map(ok).map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_classroom_buildings(
     State(state): State<HttpState>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_classroom_buildings 共用同一 AcademicReadService
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_classroom_buildings()
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_classrooms(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<ClassroomQueryRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_classrooms 共用同一 AcademicReadService
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_classrooms(req.week, req.weekday, req.periods, req.building)
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_training_plan_options(
     State(state): State<HttpState>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_training_plan_options 共用同一 AcademicReadService
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_training_plan_options()
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_training_plan_jys(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<TrainingPlanJysRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_training_plan_jys 共用同一 AcademicReadService
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_training_plan_jys(req.yxid)
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_training_plan_courses(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<TrainingPlanCoursesRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_training_plan_courses 共用同一 AcademicReadService
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_training_plan_courses(
@@ -322,41 +427,45 @@ async fn fetch_training_plan_courses(
         )
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_calendar_data(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<CalendarRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_calendar_data 共用同一 AcademicReadService（含 #489 语义）
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_calendar_data(req.semester)
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
 async fn fetch_academic_progress(
     State(state): State<HttpState>,
+    headers: HeaderMap,
     Json(req): Json<AcademicProgressRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     // 与 Tauri fetch_academic_progress 共用同一 AcademicReadService
+    let handle = state.client_for(&headers).await?;
     let service = crate::application::AcademicReadService::new(
-        crate::application::ApplicationContext::new(state.client, crate::DB_FILENAME),
+        crate::application::ApplicationContext::new(handle, crate::DB_FILENAME),
     );
     service
         .fetch_academic_progress(req.fasz)
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // GENERATED DOMAIN ROUTERS — 路由协议由原始 method+path 清单生成。
@@ -364,8 +473,11 @@ async fn fetch_academic_progress(
 pub(crate) fn router() -> Router<HttpState> {
     Router::new()
         .route("/sync_grades", post(sync_grades))
+        .route("/degree_audit", post(degree_audit))
         .route("/sync_schedule", post(sync_schedule))
         .route("/fetch_exams", post(fetch_exams))
+        .route("/exam/countdown", post(exam_countdown))
+        .route("/exam/reminder_leads", post(update_exam_reminder_leads))
         .route("/fetch_ranking", post(fetch_ranking))
         .route("/fetch_student_info", post(fetch_student_info))
         .route(
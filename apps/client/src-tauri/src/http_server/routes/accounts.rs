@@ -0,0 +1,82 @@
+//! 多账号路由与 Handler：列出、切换、移除已登记账号。
+//!
+//! 其余路由通过 `X-Hbut-Account` 请求头指定目标账号（见 [`HttpState::client_for`]）。
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use tauri::Manager;
+
+use crate::app_state::{AccountSummary, AppState};
+use crate::http_server::auth::ensure_sensitive_bridge_auth;
+use crate::http_server::response::{app_err, ok, ApiResponse};
+use crate::http_server::state::HttpState;
+
+// ────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize, Default)]
+struct AccountSwitchRequest {
+    /// 为空表示停放当前账号，随后可登录新账号
+    student_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountRemoveRequest {
+    student_id: String,
+}
+
+// ────────────────────────────────────────────────────────────
+async fn list_accounts(
+    State(state): State<HttpState>,
+) -> Result<
+    Json<ApiResponse<Vec<AccountSummary>>>,
+    (StatusCode, Json<ApiResponse<serde_json::Value>>),
+> {
+    Ok(ok(state.app.state::<AppState>().account_summaries().await))
+}
+
+async fn switch_account(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    payload: Option<Json<AccountSwitchRequest>>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let req = payload.map(|Json(req)| req).unwrap_or_default();
+    let app_state = state.app.state::<AppState>();
+    let user = app_state
+        .switch_account(req.student_id.as_deref())
+        .await
+        .map_err(app_err)?;
+    Ok(ok(serde_json::json!({
+        "success": true,
+        "user": user,
+        "accounts": app_state.account_summaries().await,
+    })))
+}
+
+async fn remove_account(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<AccountRemoveRequest>,
+) -> Result<
+    Json<ApiResponse<Vec<AccountSummary>>>,
+    (StatusCode, Json<ApiResponse<serde_json::Value>>),
+> {
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let app_state = state.app.state::<AppState>();
+    app_state
+        .remove_account(&req.student_id)
+        .await
+        .map_err(app_err)?;
+    Ok(ok(app_state.account_summaries().await))
+}
+
+pub(crate) fn router() -> Router<HttpState> {
+    Router::new()
+        .route("/accounts", get(list_accounts))
+        .route("/accounts/switch", post(switch_account))
+        .route("/accounts/remove", post(remove_account))
+}
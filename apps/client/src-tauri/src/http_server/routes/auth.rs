@@ -8,7 +8,7 @@ use reqwest::header::HeaderMap;
 use serde::Deserialize;
 
 use crate::http_server::auth::ensure_sensitive_bridge_auth;
-use crate::http_server::response::{err, ok, ApiResponse};
+use crate::http_server::response::{app_err, ok, ApiResponse};
 use crate::http_server::state::HttpState;
 use crate::UserInfo;
 
@@ -57,7 +57,7 @@ async fn login(
         )
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
@@ -75,7 +75,7 @@ async fn restore_session(
         .restore_session(&req.cookies)
         .await
         .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
@@ -107,7 +107,7 @@ async fn import_cookies(
         .import_cookies(req.code, req.auth, req.jwxt)
         .await
         .map(|user| ok(crate::application::import_cookies_ok_payload(user)))
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(app_err)
 }
 
 // GENERATED DOMAIN ROUTERS — 路由协议由原始 method+path 清单生成。
@@ -5,8 +5,11 @@ use axum::http::StatusCode;
 use axum::routing::post;
 use axum::{Json, Router};
 
-use crate::http_server::response::{err, ok, ApiResponse};
+use crate::http_server::response::{app_err, err, ok, ApiResponse};
 use crate::http_server::state::HttpState;
+use crate::modules::course_selection::{
+    self, CourseSelectionUnwatchRequest, CourseSelectionWatchRequest,
+};
 use crate::{
     CourseSelectionChildClassesRequest, CourseSelectionDetailRequest,
     CourseSelectionEndTimeRequest, CourseSelectionListRequest, CourseSelectionSelectRequest,
@@ -121,6 +124,61 @@ async fn fetch_course_selection_detail_teacher(
         .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
 }
 
+async fn current_student_id(
+    state: &HttpState,
+) -> Result<String, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    let client = state.client.read().await;
+    client
+        .user_info
+        .as_ref()
+        .map(|u| u.student_id.clone())
+        .ok_or_else(|| {
+            app_err(crate::application::ApplicationError::session_expired(
+                "当前未登录",
+            ))
+        })
+}
+
+// ────────────────────────────────────────────────────────────
+async fn course_selection_watchlist(
+    State(state): State<HttpState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = current_student_id(&state).await?;
+    let rounds = course_selection::list_watch_rounds(crate::DB_FILENAME, &sid)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(serde_json::json!({
+        "success": true,
+        "rounds": rounds,
+        "max_rounds": course_selection::MAX_WATCH_ROUNDS,
+        "max_items": course_selection::MAX_WATCH_ITEMS,
+    })))
+}
+
+async fn course_selection_watch_course(
+    State(state): State<HttpState>,
+    Json(req): Json<CourseSelectionWatchRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = current_student_id(&state).await?;
+    let round = course_selection::watch_course(crate::DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "参数错误", e))?;
+    Ok(ok(serde_json::json!({ "success": true, "round": round })))
+}
+
+async fn course_selection_unwatch_course(
+    State(state): State<HttpState>,
+    Json(req): Json<CourseSelectionUnwatchRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = current_student_id(&state).await?;
+    let removed = course_selection::unwatch_course(crate::DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "removed": removed }),
+    ))
+}
+
 // GENERATED DOMAIN ROUTERS — 路由协议由原始 method+path 清单生成。
 
 pub(crate) fn router() -> Router<HttpState> {
@@ -158,4 +216,16 @@ pub(crate) fn router() -> Router<HttpState> {
             "/course_selection/detail_teacher",
             post(fetch_course_selection_detail_teacher),
         )
+        .route(
+            "/course_selection/watchlist",
+            post(course_selection_watchlist),
+        )
+        .route(
+            "/course_selection/watch",
+            post(course_selection_watch_course),
+        )
+        .route(
+            "/course_selection/unwatch",
+            post(course_selection_unwatch_course),
+        )
 }
@@ -0,0 +1,145 @@
+//! 运行时事件订阅路由：`GET /events/stream` 以 SSE 推送 [`crate::runtime_events`] 中的
+//! 同步、成绩 / 考试变化、会话失效、后台插件事件与运行时日志，支持 `Last-Event-ID` 续传。
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use axum::{Json, Router};
+use futures::Stream;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::http_server::response::{err, ApiResponse};
+use crate::http_server::state::HttpState;
+use crate::runtime_events::{self, EventFilter, Replay, RuntimeEventEnvelope};
+
+/// 读取 hbut-background 插件收件箱的间隔（插件无推送通道，只能轮询）。
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+// ────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize)]
+struct EventStreamQuery {
+    /// 逗号分隔的类别：sync / grades / exams / session / background / log
+    types: Option<String>,
+    /// 日志最低级别：debug / info / warn / error
+    log_level: Option<String>,
+    /// 首次连接时的续传起点（浏览器 EventSource 无法自定义请求头）；`Last-Event-ID` 优先
+    last_event_id: Option<u64>,
+}
+
+fn resume_from(headers: &HeaderMap, query: &EventStreamQuery) -> Option<u64> {
+    headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .or(query.last_event_id)
+}
+
+fn sse_event(envelope: &RuntimeEventEnvelope) -> Event {
+    Event::default()
+        .id(envelope.id.to_string())
+        .event(envelope.event.name())
+        .data(serde_json::to_string(envelope).unwrap_or_default())
+}
+
+/// 续传起点之后已有事件被挤出缓冲：提示客户端重新拉取全量状态。
+fn resync_event(last_event_id: u64) -> Event {
+    Event::default()
+        .event("resync")
+        .data(serde_json::json!({ "last_event_id": last_event_id }).to_string())
+}
+
+/// 只读转发插件收件箱中的新事件，不代为消费（前端仍按原流程 ack）。
+fn ingest_background(app: &AppHandle) {
+    let Some(plugin) = app.try_state::<tauri_plugin_hbut_background::PluginState>() else {
+        return;
+    };
+    match plugin.peek_events(None) {
+        Ok(result) => {
+            runtime_events::ingest_background_events(result.events);
+        }
+        Err(e) => crate::runtime_log::log_warn("events", format!("读取后台事件失败: {e}")),
+    }
+}
+
+// ────────────────────────────────────────────────────────────
+async fn event_stream(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<
+    Sse<impl Stream<Item = Result<Event, Infallible>>>,
+    (StatusCode, Json<ApiResponse<serde_json::Value>>),
+> {
+    let filter = EventFilter::parse(query.types.as_deref(), query.log_level.as_deref())
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "参数错误", e))?;
+    let resume = resume_from(&headers, &query);
+    // 先订阅再补发，按 id 去重衔接，避免两者之间发布的事件丢失
+    let mut receiver = runtime_events::subscribe();
+    let app = state.app.clone();
+
+    let stream = async_stream::stream! {
+        let mut last_sent = 0u64;
+        if let Some(resume) = resume {
+            let Replay { events, gap, cursor } = runtime_events::replay_since(resume, &filter);
+            if gap {
+                yield Ok(resync_event(resume));
+            }
+            // 游标可能小于 `resume`（id 来自上一进程），以补发结果为准
+            last_sent = cursor;
+            for envelope in events {
+                yield Ok(sse_event(&envelope));
+            }
+        }
+
+        let mut poll = tokio::time::interval(BACKGROUND_POLL_INTERVAL);
+        loop {
+            let received = tokio::select! {
+                received = receiver.recv() => received,
+                _ = poll.tick() => {
+                    ingest_background(&app);
+                    continue;
+                }
+            };
+            match received {
+                Ok(envelope) => {
+                    if envelope.id <= last_sent {
+                        continue;
+                    }
+                    last_sent = envelope.id;
+                    if filter.matches(&envelope) {
+                        yield Ok(sse_event(&envelope));
+                    }
+                }
+                Err(RecvError::Lagged(_)) => {
+                    // 实时通道溢出：从环形缓冲补齐
+                    let Replay { events, gap, cursor } =
+                        runtime_events::replay_since(last_sent, &filter);
+                    if gap {
+                        yield Ok(resync_event(last_sent));
+                    }
+                    last_sent = cursor;
+                    for envelope in events {
+                        yield Ok(sse_event(&envelope));
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+pub(crate) fn router() -> Router<HttpState> {
+    Router::new().route("/events/stream", get(event_stream))
+}
@@ -1,9 +1,9 @@
-//! 课表领域路由与 Handler：自定义课表 CRUD、冲突检测、debug upsert、
-//! 课表导出（ICS 生成 + 临时存储上传）。
+//! 课表领域路由与 Handler：自定义课表 CRUD、冲突检测、排课草案模拟、debug upsert、
+//! 课表导出（ICS 生成 + 临时存储上传）、webcal 订阅源。
 
 use axum::body::Body;
 use axum::extract::{Path, State};
-use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
@@ -16,9 +16,12 @@ use serde::Deserialize;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::http_server::auth::{ensure_debug_bridge_enabled, ensure_local_cache_auth};
-use crate::http_server::response::{err, ok, ApiResponse};
+use crate::http_server::auth::{
+    ensure_debug_bridge_enabled, ensure_local_cache_auth, ensure_sensitive_bridge_auth,
+};
+use crate::http_server::response::{app_err, err, ok, ApiResponse};
 use crate::http_server::state::HttpState;
+use crate::modules::schedule_analysis::ScheduleSlot;
 use crate::utils::ics::{
     escape_ics_text, fold_ics_line, parse_ics_datetime, sanitize_filename_part,
 };
@@ -40,6 +43,12 @@ struct CustomScheduleListAllRequest {
     student_id: String,
 }
 
+// ────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize)]
+struct SchedulePlanListRequest {
+    student_id: Option<String>,
+}
+
 // ────────────────────────────────────────────────────────────
 #[derive(Debug, Deserialize)]
 struct DebugCustomScheduleUpsertRequest {
@@ -225,30 +234,6 @@ fn validate_debug_custom_schedule_course(
     })
 }
 
-// ────────────────────────────────────────────────────────────
-fn schedule_ranges_overlap(
-    left_period: i32,
-    left_span: i32,
-    right_period: i32,
-    right_span: i32,
-) -> bool {
-    let left_end = left_period + left_span - 1;
-    let right_end = right_period + right_span - 1;
-    left_period <= right_end && right_period <= left_end
-}
-
-// ────────────────────────────────────────────────────────────
-fn weeks_intersection(left: &[i32], right: &[i32]) -> Vec<i32> {
-    let right_set = right
-        .iter()
-        .copied()
-        .collect::<std::collections::BTreeSet<_>>();
-    left.iter()
-        .copied()
-        .filter(|item| right_set.contains(item))
-        .collect::<Vec<_>>()
-}
-
 // ────────────────────────────────────────────────────────────
 fn build_custom_schedule_conflicts(
     courses: &[db::CustomScheduleCourseRecord],
@@ -263,21 +248,17 @@ fn build_custom_schedule_conflicts(
     }
     let mut output = Vec::new();
     for (semester, items) in grouped {
+        let slots = items
+            .iter()
+            .map(|course| ScheduleSlot::from(*course))
+            .collect::<Vec<_>>();
         let mut semester_conflicts = Vec::new();
         for i in 0..items.len() {
             for j in (i + 1)..items.len() {
-                let left = items[i];
-                let right = items[j];
-                if left.weekday != right.weekday {
+                let (left, right) = (items[i], items[j]);
+                let Some((_, overlap_weeks)) = slots[i].overlap(&slots[j]) else {
                     continue;
-                }
-                if !schedule_ranges_overlap(left.period, left.djs, right.period, right.djs) {
-                    continue;
-                }
-                let overlap_weeks = weeks_intersection(&left.weeks, &right.weeks);
-                if overlap_weeks.is_empty() {
-                    continue;
-                }
+                };
                 semester_conflicts.push(serde_json::json!({
                     "left": custom_course_payload(left),
                     "right": custom_course_payload(right),
@@ -470,9 +451,11 @@ async fn schedule_custom_add(
             )
         })?
         .unwrap_or(record);
+    let conflicts = crate::modules::schedule_analysis::custom_course_conflicts(DB_FILENAME, &saved);
     Ok(ok(serde_json::json!({
         "success": true,
-        "data": custom_course_payload(&saved)
+        "data": custom_course_payload(&saved),
+        "conflicts": conflicts
     })))
 }
 
@@ -769,9 +752,12 @@ async fn schedule_custom_update(
             )
         })?;
 
+    let conflicts =
+        crate::modules::schedule_analysis::custom_course_conflicts(DB_FILENAME, &updated);
     Ok(ok(serde_json::json!({
         "success": true,
-        "data": custom_course_payload(&updated)
+        "data": custom_course_payload(&updated),
+        "conflicts": conflicts
     })))
 }
 
@@ -938,6 +924,235 @@ fn export_upload_endpoint(req: &ScheduleExportRequest) -> String {
     "https://mini-hbut-testocr1.hf.space/api/temp/upload".to_string()
 }
 
+// ────────────────────────────────────────────────────────────
+async fn export_schedule_ics(
+    Json(req): Json<crate::modules::ics_calendar::ScheduleIcsRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = req
+        .student_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            err(
+                StatusCode::BAD_REQUEST,
+                "参数错误",
+                "student_id 不能为空".to_string(),
+            )
+        })?;
+    let build = crate::modules::ics_calendar::build_schedule_ics(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(crate::modules::ics_calendar::build_payload(&sid, build)))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn import_schedule_ics(
+    Json(req): Json<crate::modules::ics_calendar::ScheduleIcsImportRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = req
+        .student_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            err(
+                StatusCode::BAD_REQUEST,
+                "参数错误",
+                "student_id 不能为空".to_string(),
+            )
+        })?;
+    let import = crate::modules::ics_calendar::import_schedule_ics(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(crate::modules::ics_calendar::build_import_payload(
+        import,
+    )))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_feed_issue(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<crate::modules::ics_calendar::ScheduleFeedRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    // 订阅令牌等同于课表只读凭据，按敏感路由二次校验
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let (token, record) =
+        crate::modules::ics_calendar::issue_feed_token(DB_FILENAME, &sid, req.device.as_deref())
+            .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(crate::modules::ics_calendar::build_feed_payload(
+        crate::http_server::bridge_listen_addr(),
+        &token,
+        &record,
+    )))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_feed_revoke(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<crate::modules::ics_calendar::ScheduleFeedRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let revoked =
+        crate::modules::ics_calendar::revoke_feed_tokens(DB_FILENAME, &sid, req.device.as_deref())
+            .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "revoked": revoked }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_conflicts(
+    State(state): State<HttpState>,
+    Json(req): Json<crate::modules::schedule_analysis::ScheduleConflictRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let report = crate::modules::schedule_analysis::analyze_conflicts(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        crate::modules::schedule_analysis::build_conflicts_payload(report),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_free_slots(
+    State(state): State<HttpState>,
+    Json(req): Json<crate::modules::schedule_analysis::FreeSlotRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let report = crate::modules::schedule_analysis::find_free_slots(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        crate::modules::schedule_analysis::build_free_slots_payload(report),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_plan_list(
+    State(state): State<HttpState>,
+    Json(req): Json<SchedulePlanListRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let plans = crate::modules::plan_simulator::list_plans(DB_FILENAME, &sid)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(serde_json::json!({
+        "success": true,
+        "plans": plans,
+        "max_plans": crate::modules::plan_simulator::MAX_DRAFT_PLANS,
+        "max_courses": crate::modules::plan_simulator::MAX_DRAFT_COURSES,
+    })))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_plan_save(
+    State(state): State<HttpState>,
+    Json(req): Json<crate::modules::plan_simulator::PlanSaveRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let plan = crate::modules::plan_simulator::save_plan(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(serde_json::json!({ "success": true, "plan": plan })))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_plan_delete(
+    State(state): State<HttpState>,
+    Json(req): Json<crate::modules::plan_simulator::PlanRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let removed = crate::modules::plan_simulator::delete_plan(DB_FILENAME, &sid, &req.id)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "removed": removed }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_plan_simulate(
+    State(state): State<HttpState>,
+    Json(req): Json<crate::modules::plan_simulator::PlanSimulateRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let report = crate::modules::plan_simulator::simulate_plan(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        crate::modules::plan_simulator::build_simulation_payload(report),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn schedule_plan_export_ics(
+    State(state): State<HttpState>,
+    Json(req): Json<crate::modules::plan_simulator::PlanIcsRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let payload = crate::modules::plan_simulator::export_plan_ics(DB_FILENAME, &sid, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(payload))
+}
+
+/// 请求中的学号：显式传入优先，否则取当前登录用户。
+async fn request_student_id(
+    state: &HttpState,
+    student_id: Option<&str>,
+) -> Result<String, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    if let Some(sid) = student_id.map(str::trim).filter(|v| !v.is_empty()) {
+        return Ok(sid.to_string());
+    }
+    let client = state.client.read().await;
+    client
+        .user_info
+        .as_ref()
+        .map(|u| u.student_id.clone())
+        .ok_or_else(|| {
+            app_err(crate::application::ApplicationError::session_expired(
+                "当前未登录",
+            ))
+        })
+}
+
+// ────────────────────────────────────────────────────────────
+/// webcal 订阅源：每次请求都从本地缓存实时构建，令牌只读、与 Bridge 会话令牌无关。
+async fn schedule_feed(Path(token): Path<String>) -> impl IntoResponse {
+    let token = token.strip_suffix(".ics").unwrap_or(&token);
+    let record = match crate::modules::ics_calendar::resolve_feed_token(DB_FILENAME, token) {
+        Ok(Some(record)) => record,
+        Ok(None) => return (StatusCode::NOT_FOUND, "feed not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "read error").into_response(),
+    };
+    let content = match crate::modules::ics_calendar::build_feed_calendar(DB_FILENAME, &record) {
+        Ok(content) => content,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e).into_response(),
+    };
+    let mut resp = Response::new(Body::from(content));
+    resp.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/calendar; charset=utf-8"),
+    );
+    resp.headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    resp.headers_mut().insert(
+        CONTENT_DISPOSITION,
+        HeaderValue::from_static("inline; filename=\"schedule.ics\""),
+    );
+    resp
+}
+
 // ────────────────────────────────────────────────────────────
 async fn export_schedule_calendar(
     State(_state): State<HttpState>,
@@ -1155,7 +1370,19 @@ pub(crate) fn router() -> Router<HttpState> {
         .route("/schedule/custom/add", post(schedule_custom_add))
         .route("/schedule/custom/delete", post(schedule_custom_delete))
         .route("/schedule/custom/update", post(schedule_custom_update))
+        .route("/schedule/conflicts", post(schedule_conflicts))
+        .route("/schedule/free_slots", post(schedule_free_slots))
+        .route("/schedule/plans/list", post(schedule_plan_list))
+        .route("/schedule/plans/save", post(schedule_plan_save))
+        .route("/schedule/plans/delete", post(schedule_plan_delete))
+        .route("/schedule/plans/simulate", post(schedule_plan_simulate))
+        .route("/schedule/plans/export_ics", post(schedule_plan_export_ics))
         .route("/export_schedule_calendar", post(export_schedule_calendar))
+        .route("/export_schedule_ics", post(export_schedule_ics))
+        .route("/import_schedule_ics", post(import_schedule_ics))
+        .route("/schedule/feed/issue", post(schedule_feed_issue))
+        .route("/schedule/feed/revoke", post(schedule_feed_revoke))
+        .route("/webcal/:token", get(schedule_feed))
         .route("/exports/:filename", get(download_export))
 }
 
@@ -0,0 +1,109 @@
+//! 后台任务调度路由与 Handler：任务状态快照、启停、配置更新与立即执行。
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use tauri::Manager;
+
+use crate::http_server::auth::ensure_sensitive_bridge_auth;
+use crate::http_server::response::{err, ok, ApiResponse};
+use crate::http_server::state::HttpState;
+use crate::modules::scheduler::{JobConfig, JobKind, JobScheduler, JobStatus};
+use crate::DB_FILENAME;
+
+// ────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize)]
+struct JobEnabledRequest {
+    job: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobUpdateRequest {
+    job: String,
+    config: JobConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobRunRequest {
+    job: String,
+}
+
+fn parse_job(job: &str) -> Result<JobKind, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    JobKind::parse(job).ok_or_else(|| {
+        err(
+            StatusCode::BAD_REQUEST,
+            "参数错误",
+            format!("未知后台任务: {}", job),
+        )
+    })
+}
+
+// ────────────────────────────────────────────────────────────
+async fn list_background_jobs(
+    State(state): State<HttpState>,
+) -> Result<Json<ApiResponse<Vec<JobStatus>>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    Ok(ok(state.app.state::<JobScheduler>().snapshot().await))
+}
+
+async fn set_background_job_enabled(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<JobEnabledRequest>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let job = parse_job(&req.job)?;
+    state
+        .app
+        .state::<JobScheduler>()
+        .set_enabled(DB_FILENAME, job, req.enabled)
+        .await
+        .map(ok)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))
+}
+
+async fn update_background_job(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<JobUpdateRequest>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let job = parse_job(&req.job)?;
+    state
+        .app
+        .state::<JobScheduler>()
+        .update(DB_FILENAME, job, req.config)
+        .await
+        .map(ok)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))
+}
+
+async fn run_background_job_now(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<JobRunRequest>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let job = parse_job(&req.job)?;
+    state
+        .app
+        .state::<JobScheduler>()
+        .run_now(job)
+        .await
+        .map(ok)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))
+}
+
+pub(crate) fn router() -> Router<HttpState> {
+    Router::new()
+        .route("/background_jobs", get(list_background_jobs))
+        .route(
+            "/background_jobs/set_enabled",
+            post(set_background_job_enabled),
+        )
+        .route("/background_jobs/update", post(update_background_job))
+        .route("/background_jobs/run_now", post(run_background_job_now))
+}
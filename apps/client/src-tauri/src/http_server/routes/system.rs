@@ -1,5 +1,5 @@
-//! 系统级路由与 Handler：健康检查、本地缓存读取、qxzkb 课表查询、
-//! module_bundle 静态资源、图书馆、电费、交易、一码通、校园码。
+//! 系统级路由与 Handler：健康检查、OpenAPI 文档、本地缓存读取、qxzkb 课表查询、
+//! module_bundle 静态资源、图书馆（含读者账户、续借与检索订阅）、电费（含余额监控）、交易、一码通、校园码。
 
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
@@ -13,9 +13,15 @@ use reqwest::header::{HeaderMap, HeaderValue};
 use serde::Deserialize;
 use std::collections::HashMap;
 
-use crate::http_server::auth::{ensure_local_cache_auth, is_allowed_cache_table};
-use crate::http_server::response::{err, ok, ApiResponse};
+use crate::http_server::auth::{
+    ensure_local_cache_auth, ensure_sensitive_bridge_auth, is_allowed_cache_table,
+};
+use crate::http_server::response::{app_err, err, ok, ApiResponse};
 use crate::http_server::state::HttpState;
+use crate::modules::electricity::{self, ElectricityWatchRequest};
+use crate::modules::library::{
+    self, LibraryRenewRequest, LibrarySavedSearchIdRequest, LibrarySavedSearchRequest,
+};
 use crate::modules::module_bundle::{
     self, ModuleBundlePrepareRequest, OpenModuleBundleWindowRequest,
 };
@@ -58,12 +64,34 @@ struct LibraryDetailRequest {
     record_id: Option<i64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct LibraryHistoryRequest {
+    page: Option<i64>,
+    rows: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibraryReminderDaysRequest {
+    days: Vec<u32>,
+}
+
 // ────────────────────────────────────────────────────────────
 #[derive(Debug, Deserialize)]
 struct ElectricityRequest {
     payload: serde_json::Value,
 }
 
+#[derive(Debug, Deserialize)]
+struct ElectricityRoomRequest {
+    room_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ElectricityOverviewRequest {
+    room_id: Option<String>,
+    days: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 struct TransactionRequest {
     start_date: String,
@@ -85,6 +113,11 @@ async fn health(State(state): State<HttpState>) -> Json<ApiResponse<serde_json::
     ok(service.health().await)
 }
 
+/// GET /openapi.json：Bridge 接口描述（OpenAPI 3，非统一信封）
+async fn openapi_json() -> Json<serde_json::Value> {
+    Json(crate::http_server::openapi::openapi_document())
+}
+
 // ────────────────────────────────────────────────────────────
 fn module_content_type(path: &std::path::Path) -> &'static str {
     match path
@@ -224,13 +257,16 @@ async fn cache_get(
         ));
     }
 
-    match db::get_cache(DB_FILENAME, table, key) {
-        Ok(Some((data, sync_time))) => {
+    match db::read_cache_entry(DB_FILENAME, table, key, Utc::now().timestamp()) {
+        Ok(Some(entry)) => {
             let payload = serde_json::json!({
                 "success": true,
-                "data": data,
-                "sync_time": sync_time,
-                "offline": true
+                "data": entry.data,
+                "sync_time": entry.sync_time,
+                "offline": true,
+                "freshness": entry.freshness,
+                "age_secs": entry.age_secs,
+                "byte_size": entry.byte_size
             });
             Ok(ok(payload))
         }
@@ -463,7 +499,7 @@ async fn fetch_library_dict(
     .fetch_library_dict()
     .await
     .map(ok)
-    .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+    .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
@@ -479,7 +515,7 @@ async fn search_library_books(
     .search_library_books(req.params)
     .await
     .map(ok)
-    .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+    .map_err(app_err)
 }
 
 // ────────────────────────────────────────────────────────────
@@ -495,7 +531,178 @@ async fn fetch_library_book_detail(
     .fetch_library_book_detail(req.title.clone(), req.isbn.clone(), req.record_id)
     .await
     .map(ok)
-    .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+    .map_err(app_err)
+}
+
+// ────────────────────────────────────────────────────────────
+/// 读者账户按请求头选择账号（缺省为活动账号）。
+async fn library_account_service(
+    state: &HttpState,
+    headers: &HeaderMap,
+) -> Result<
+    crate::application::AcademicReadService,
+    (StatusCode, Json<ApiResponse<serde_json::Value>>),
+> {
+    let handle = state.client_for(headers).await?;
+    Ok(crate::application::AcademicReadService::new(
+        crate::application::ApplicationContext::new(handle, DB_FILENAME),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn fetch_library_loans(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    library_account_service(&state, &headers)
+        .await?
+        .fetch_library_loans()
+        .await
+        .map(ok)
+        .map_err(app_err)
+}
+
+// ────────────────────────────────────────────────────────────
+async fn fetch_library_loan_history(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<LibraryHistoryRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    library_account_service(&state, &headers)
+        .await?
+        .fetch_library_loan_history(req.page, req.rows)
+        .await
+        .map(ok)
+        .map_err(app_err)
+}
+
+// ────────────────────────────────────────────────────────────
+async fn fetch_library_holds(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    library_account_service(&state, &headers)
+        .await?
+        .fetch_library_holds()
+        .await
+        .map(ok)
+        .map_err(app_err)
+}
+
+// ────────────────────────────────────────────────────────────
+async fn fetch_library_fines(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    library_account_service(&state, &headers)
+        .await?
+        .fetch_library_fines()
+        .await
+        .map(ok)
+        .map_err(app_err)
+}
+
+// ────────────────────────────────────────────────────────────
+async fn renew_library_loans(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<LibraryRenewRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let handle = state.client_for(&headers).await?;
+    let mut client = handle.write().await;
+    library::renew_loans(&mut client, DB_FILENAME, req.barcodes)
+        .await
+        .map(ok)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn get_library_reminder_days(
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let days = library::load_reminder_days(DB_FILENAME);
+    Ok(ok(
+        serde_json::json!({ "success": true, "reminder_days": days }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn update_library_reminder_days(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<LibraryReminderDaysRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let days = library::save_reminder_days(DB_FILENAME, req.days)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "参数错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "reminder_days": days }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn list_library_saved_searches(
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    let searches = library::list_saved_searches(DB_FILENAME)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(serde_json::json!({
+        "success": true,
+        "searches": searches,
+        "max_searches": library::MAX_SAVED_SEARCHES,
+    })))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn save_library_saved_search(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<LibrarySavedSearchRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let search = library::save_saved_search(DB_FILENAME, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "参数错误", e))?;
+    Ok(ok(serde_json::json!({ "success": true, "search": search })))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn delete_library_saved_search(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<LibrarySavedSearchIdRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let removed = library::delete_saved_search(DB_FILENAME, &req.id)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "removed": removed }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn run_library_saved_search(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<LibrarySavedSearchIdRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    // 立即检查会推进已见书目，后台任务不再提醒这些结果
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let handle = state.client_for(&headers).await?;
+    let mut client = handle.write().await;
+    let check = library::run_saved_search(&mut client, DB_FILENAME, &req.id)
+        .await
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(serde_json::json!({ "success": true, "check": check })))
 }
 
 // ────────────────────────────────────────────────────────────
@@ -519,11 +726,65 @@ async fn electricity_query_account(
 ) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
 {
     let mut client = state.client.write().await;
-    client
+    let location = req.payload.clone();
+    let data = client
         .query_electricity_account(req.payload)
         .await
-        .map(ok)
-        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e.to_string()))?;
+    // 关注房间顺带追加一条余额读数
+    let _ = electricity::record_account_response(
+        DB_FILENAME,
+        &location,
+        &data,
+        chrono::Local::now().timestamp(),
+    );
+    Ok(ok(data))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn electricity_watch_room(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<ElectricityWatchRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let room = electricity::watch_room(DB_FILENAME, &req)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "参数错误", e))?;
+    let share_code = electricity::share_code(&room);
+    Ok(ok(
+        serde_json::json!({ "success": true, "room": room, "share_code": share_code }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn electricity_unwatch_room(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(req): Json<ElectricityRoomRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    ensure_sensitive_bridge_auth(&headers, &state)?;
+    let removed = electricity::unwatch_room(DB_FILENAME, &req.room_id)
+        .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))?;
+    Ok(ok(
+        serde_json::json!({ "success": true, "removed": removed }),
+    ))
+}
+
+// ────────────────────────────────────────────────────────────
+async fn electricity_monitor_overview(
+    Json(req): Json<ElectricityOverviewRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)>
+{
+    electricity::build_overview_payload(
+        DB_FILENAME,
+        req.room_id.as_deref(),
+        req.days,
+        chrono::Local::now().timestamp(),
+    )
+    .map(ok)
+    .map_err(|e| err(StatusCode::BAD_REQUEST, "业务错误", e))
 }
 
 // ────────────────────────────────────────────────────────────
@@ -600,6 +861,7 @@ async fn campus_code_order_status(
 pub(crate) fn router() -> Router<HttpState> {
     Router::new()
         .route("/health", get(health))
+        .route("/openapi.json", get(openapi_json))
         .route("/module_bundle/prepare", post(module_bundle_prepare))
         .route("/module_bundle/open", post(module_bundle_open))
         .route(
@@ -619,6 +881,28 @@ pub(crate) fn router() -> Router<HttpState> {
         .route("/library/dict", post(fetch_library_dict))
         .route("/library/search", post(search_library_books))
         .route("/library/detail", post(fetch_library_book_detail))
+        .route("/library/loans", post(fetch_library_loans))
+        .route("/library/loan_history", post(fetch_library_loan_history))
+        .route("/library/holds", post(fetch_library_holds))
+        .route("/library/fines", post(fetch_library_fines))
+        .route("/library/renew", post(renew_library_loans))
+        .route(
+            "/library/reminder_days",
+            get(get_library_reminder_days).post(update_library_reminder_days),
+        )
+        .route("/library/saved_searches", get(list_library_saved_searches))
+        .route(
+            "/library/saved_searches/save",
+            post(save_library_saved_search),
+        )
+        .route(
+            "/library/saved_searches/delete",
+            post(delete_library_saved_search),
+        )
+        .route(
+            "/library/saved_searches/run",
+            post(run_library_saved_search),
+        )
         .route(
             "/electricity_query_location",
             post(electricity_query_location),
@@ -627,6 +911,9 @@ pub(crate) fn router() -> Router<HttpState> {
             "/electricity_query_account",
             post(electricity_query_account),
         )
+        .route("/electricity/rooms", post(electricity_monitor_overview))
+        .route("/electricity/rooms/watch", post(electricity_watch_room))
+        .route("/electricity/rooms/unwatch", post(electricity_unwatch_room))
         .route(
             "/fetch_transaction_history",
             post(fetch_transaction_history),
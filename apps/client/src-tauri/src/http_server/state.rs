@@ -1,11 +1,19 @@
 //! HTTP Bridge 共享状态。
 
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
 use jsonwebtoken::DecodingKey;
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tokio::sync::RwLock;
 
+use crate::app_state::AppState;
+use crate::application::ApplicationError;
 use crate::http_client::HbutClient;
+use crate::http_server::response::{app_err, ApiResponse};
+
+/// 指定目标账号（学号）的请求头；缺省时作用于活动账号。
+pub(crate) const ACCOUNT_HEADER: &str = "x-hbut-account";
 
 /// Bridge 路由共享状态（字段与拆分前完全一致）。
 #[derive(Clone)]
@@ -15,3 +23,25 @@ pub(crate) struct HttpState {
     pub(crate) bridge_token: Arc<str>,
     pub(crate) app: AppHandle,
 }
+
+impl HttpState {
+    /// 按 `X-Hbut-Account` 请求头解析目标账号的客户端句柄。
+    pub(crate) async fn client_for(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<Arc<RwLock<HbutClient>>, (StatusCode, Json<ApiResponse<serde_json::Value>>)> {
+        let account = headers
+            .get(ACCOUNT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+        let Some(account) = account else {
+            return Ok(self.client.clone());
+        };
+        let app_state = self
+            .app
+            .try_state::<AppState>()
+            .ok_or_else(|| app_err(ApplicationError::internal("应用状态未初始化")))?;
+        app_state.client_for(Some(account)).await.map_err(app_err)
+    }
+}
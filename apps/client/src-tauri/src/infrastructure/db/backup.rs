@@ -1,7 +1,8 @@
 //! 数据库备份与恢复。
 //!
 //! 设计约束（#550）：
-//! - 备份是显式操作（函数或 tauri command），绝不自动恢复、绝不覆盖正式库；
+//! - 备份由用户显式触发、用户开启的定时任务（`scheduled/`）或 `init_db` 执行迁移前
+//!   （`pre-migration/`）产生；绝不自动恢复，整库恢复只写到新路径，按表恢复见 `restore`；
 //! - 备份只写入指定的 backup 子目录，文件名带 时间戳(毫秒)+pid+进程内原子序号，
 //!   同秒/同毫秒连续或并发备份时也保证唯一；
 //! - 先写临时文件，完成后 integrity_check 通过再 rename，保证任一时刻磁盘上
@@ -25,6 +26,22 @@ pub const BACKUP_KEEP_DEFAULT: usize = 5;
 /// 备份保留份数上限：防止误传超大 `keep` 导致磁盘被历史备份占满。
 pub const BACKUP_KEEP_MAX: usize = 30;
 
+/// 定时备份子目录（与手动备份分开保留，互不挤占份数）。
+pub const SCHEDULED_BACKUP_SUBDIR: &str = "scheduled";
+/// 迁移前自动备份子目录。
+pub const PRE_MIGRATION_BACKUP_SUBDIR: &str = "pre-migration";
+/// 按表恢复前的安全备份子目录。
+pub const PRE_RESTORE_BACKUP_SUBDIR: &str = "pre-restore";
+
+/// 正式库所在目录下的 `backup/`（解析 `HBUT_DB_PATH`，桌面端即应用数据目录）。
+pub fn default_backup_dir<P: AsRef<Path>>(db_path: P) -> PathBuf {
+    resolve_db_path(db_path)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+        .join("backup")
+}
+
 /// 备份结果报告。
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct BackupReport {
@@ -299,6 +316,73 @@ pub fn list_backups<P: AsRef<Path>, Q: AsRef<Path>>(
     Ok(out)
 }
 
+/// 定时备份：`scheduled/` 中最新一份距今不足 3/4 个周期时跳过（返回 `None`），
+/// 否则备份并保留最近 [`BACKUP_KEEP_MAX`] 份。
+///
+/// 调度器重启后会很快执行一次，且每次延迟带 ±20% 抖动；按文件修改时间判断可避免
+/// 频繁重启导致重复备份，同时不会因抖动错过一个周期。
+pub fn run_scheduled_backup<P: AsRef<Path>>(
+    db_path: P,
+    period: std::time::Duration,
+    now: std::time::SystemTime,
+) -> Result<Option<BackupReport>> {
+    let dir = default_backup_dir(&db_path).join(SCHEDULED_BACKUP_SUBDIR);
+    let latest = match list_backups(&dir, &db_path) {
+        Ok(backups) => backups.last().cloned(),
+        Err(_) if !dir.exists() => None,
+        Err(e) => return Err(e),
+    };
+    let elapsed = latest
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|modified| now.duration_since(modified).ok());
+    if elapsed.is_some_and(|elapsed| elapsed < period.mul_f64(0.75)) {
+        return Ok(None);
+    }
+    backup_database(db_path, dir, BACKUP_KEEP_MAX).map(Some)
+}
+
+/// 备份目录中的一份备份文件（供恢复界面列出）。
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BackupFileInfo {
+    /// `manual` / `scheduled` / `pre_migration` / `pre_restore`
+    pub kind: &'static str,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// 文件修改时间（本地时间 RFC 3339）
+    pub modified_at: Option<String>,
+}
+
+/// 列出正式库的全部明文备份（手动、定时、迁移前、恢复前），按修改时间倒序。
+pub fn list_backup_files<P: AsRef<Path>>(db_path: P) -> Result<Vec<BackupFileInfo>> {
+    let root = default_backup_dir(&db_path);
+    let mut out = Vec::new();
+    for (kind, dir) in [
+        ("manual", root.clone()),
+        ("scheduled", root.join(SCHEDULED_BACKUP_SUBDIR)),
+        ("pre_migration", root.join(PRE_MIGRATION_BACKUP_SUBDIR)),
+        ("pre_restore", root.join(PRE_RESTORE_BACKUP_SUBDIR)),
+    ] {
+        if !dir.is_dir() {
+            continue;
+        }
+        for path in list_backups(&dir, &db_path)? {
+            let meta = std::fs::metadata(&path).map_err(io_to_rusqlite_err)?;
+            out.push(BackupFileInfo {
+                kind,
+                modified_at: meta
+                    .modified()
+                    .ok()
+                    .map(|time| chrono::DateTime::<Local>::from(time).to_rfc3339()),
+                size_bytes: meta.len(),
+                path,
+            });
+        }
+    }
+    out.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(out)
+}
+
 /// 校验备份文件可读且未损坏：能打开并执行 PRAGMA integrity_check。
 /// 仅用于验证，绝不写回正式库。
 pub fn verify_backup<P: AsRef<Path>>(backup_path: P) -> Result<()> {
@@ -595,6 +679,33 @@ mod tests {
         let _ = std::fs::remove_dir_all(backup_dir);
     }
 
+    /// 定时备份：周期内重复触发只备份一次，满周期后再备份；列表按来源归类。
+    #[test]
+    fn scheduled_backup_respects_period_and_is_listed_by_kind() {
+        let root = temp_path("scheduled");
+        let db = root.join("grades.db");
+        std::fs::create_dir_all(&root).unwrap();
+        super::super::migrations::init_db(&db).expect("init");
+        let day = std::time::Duration::from_secs(24 * 60 * 60);
+        let now = std::time::SystemTime::now();
+
+        let first = run_scheduled_backup(&db, day, now).expect("first");
+        assert!(first.is_some());
+        assert!(run_scheduled_backup(&db, day, now + day / 2)
+            .expect("within period")
+            .is_none());
+        // 抖动提前到 0.8 个周期仍应执行
+        let second = run_scheduled_backup(&db, day, now + day.mul_f64(0.8)).expect("due");
+        assert!(second.is_some());
+        backup_database(&db, default_backup_dir(&db), 5).expect("manual");
+
+        let files = list_backup_files(&db).expect("list");
+        assert_eq!(files.iter().filter(|f| f.kind == "scheduled").count(), 2);
+        assert_eq!(files.iter().filter(|f| f.kind == "manual").count(), 1);
+        assert!(files.iter().all(|f| f.size_bytes > 0));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     fn list_temp_files(dir: &std::path::Path) -> Vec<PathBuf> {
         std::fs::read_dir(dir)
             .map(|rd| {
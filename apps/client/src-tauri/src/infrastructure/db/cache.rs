@@ -2,34 +2,89 @@
 //!
 //! 负责按 `student_id`（普通表）或 `cache_key`（public 表）读写缓存，
 //! 以及 `grade_teacher_cache` 的并发安全合并与异步包装。
+//!
+//! 每行写入时记录 `schema_version` 与 `byte_size`；读取时按 [`cache_policy`] 丢弃旧版本
+//! payload，并给出新鲜度（[`read_cache_entry`]）。`read_through_cache` 提供
+//! stale-while-revalidate 读取，`invalidate_caches` 按依赖（学期切换 / 账号移除）批量失效。
 
-use chrono::Local;
-use rusqlite::{params, OptionalExtension, Result, TransactionBehavior};
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OptionalExtension, Result, TransactionBehavior};
+use serde::Serialize;
 use serde_json::Value;
+use std::collections::HashSet;
+use std::future::Future;
 use std::path::Path;
+use std::sync::{Mutex, OnceLock};
 
+use super::cache_policy::{cache_policy, CacheDependency, CacheFreshness, CACHE_POLICIES};
 use super::connection::open_connection;
 
+fn key_column(table: &str) -> &'static str {
+    if table.contains("public") {
+        "cache_key"
+    } else {
+        "student_id"
+    }
+}
+
 // 保存缓存
 pub fn save_cache<P: AsRef<Path>>(path: P, table: &str, key: &str, data: &Value) -> Result<()> {
     let conn = open_connection(path)?;
     let payload = serde_json::to_string(data).unwrap_or_default();
     let sync_time = Local::now().to_rfc3339();
+    let sql = format!(
+        "INSERT OR REPLACE INTO {} ({}, data, sync_time, schema_version, byte_size)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        table,
+        key_column(table)
+    );
+    conn.execute(
+        &sql,
+        params![
+            key,
+            payload,
+            sync_time,
+            cache_policy(table).schema_version,
+            payload.len() as i64
+        ],
+    )?;
+    Ok(())
+}
 
-    let sql = if table.contains("public") {
-        format!(
-            "INSERT OR REPLACE INTO {} (cache_key, data, sync_time) VALUES (?1, ?2, ?3)",
-            table
-        )
-    } else {
-        format!(
-            "INSERT OR REPLACE INTO {} (student_id, data, sync_time) VALUES (?1, ?2, ?3)",
-            table
-        )
-    };
+/// 读取一行缓存原始数据；payload 版本与当前策略不一致时删除该行并视为未命中。
+fn load_cache_row(conn: &Connection, table: &str, key: &str) -> Result<Option<CacheRow>> {
+    let key_column = key_column(table);
+    let sql = format!(
+        "SELECT data, sync_time, schema_version, byte_size FROM {} WHERE {} = ?1",
+        table, key_column
+    );
+    let row = conn
+        .query_row(&sql, params![key], |row| {
+            Ok(CacheRow {
+                data: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                sync_time: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                schema_version: row.get(2)?,
+                byte_size: row.get(3)?,
+            })
+        })
+        .optional()?;
+    match row {
+        Some(row) if row.schema_version != cache_policy(table).schema_version => {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE {} = ?1", table, key_column),
+                params![key],
+            )?;
+            Ok(None)
+        }
+        other => Ok(other),
+    }
+}
 
-    conn.execute(&sql, params![key, payload, sync_time])?;
-    Ok(())
+struct CacheRow {
+    data: String,
+    sync_time: String,
+    schema_version: i64,
+    byte_size: i64,
 }
 
 // 读取缓存
@@ -39,27 +94,50 @@ pub fn get_cache<P: AsRef<Path>>(
     key: &str,
 ) -> Result<Option<(Value, String)>> {
     let conn = open_connection(path)?;
+    Ok(load_cache_row(&conn, table, key)?.map(|row| {
+        let data: Value = serde_json::from_str(&row.data).unwrap_or(Value::Null);
+        (data, row.sync_time)
+    }))
+}
 
-    let sql = if table.contains("public") {
-        format!("SELECT data, sync_time FROM {} WHERE cache_key = ?1", table)
-    } else {
-        format!(
-            "SELECT data, sync_time FROM {} WHERE student_id = ?1",
-            table
-        )
-    };
-
-    let mut stmt = conn.prepare(&sql)?;
-    let mut rows = stmt.query(params![key])?;
+/// 带新鲜度元数据的缓存条目。
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheEntry {
+    pub data: Value,
+    pub sync_time: String,
+    /// 写入至今的秒数；`sync_time` 无法解析时为 `None`（按过期处理）
+    pub age_secs: Option<i64>,
+    pub freshness: CacheFreshness,
+    pub schema_version: i64,
+    pub byte_size: i64,
+}
 
-    if let Some(row) = rows.next()? {
-        let data_str: String = row.get(0)?;
-        let sync_time: String = row.get(1)?;
-        let data: Value = serde_json::from_str(&data_str).unwrap_or(Value::Null);
-        Ok(Some((data, sync_time)))
-    } else {
-        Ok(None)
-    }
+/// 读取缓存并按表策略判定新鲜度；`now` 为 Unix 秒。
+pub fn read_cache_entry<P: AsRef<Path>>(
+    path: P,
+    table: &str,
+    key: &str,
+    now: i64,
+) -> Result<Option<CacheEntry>> {
+    let conn = open_connection(path)?;
+    let Some(row) = load_cache_row(&conn, table, key)? else {
+        return Ok(None);
+    };
+    let age_secs = DateTime::parse_from_rfc3339(&row.sync_time)
+        .ok()
+        .map(|synced| now - synced.timestamp());
+    let freshness = match age_secs {
+        Some(age) => cache_policy(table).freshness(age),
+        None => CacheFreshness::Expired,
+    };
+    Ok(Some(CacheEntry {
+        data: serde_json::from_str(&row.data).unwrap_or(Value::Null),
+        sync_time: row.sync_time,
+        age_secs,
+        freshness,
+        schema_version: row.schema_version,
+        byte_size: row.byte_size,
+    }))
 }
 
 pub fn delete_cache<P: AsRef<Path>>(path: P, table: &str, key: &str) -> Result<usize> {
@@ -82,6 +160,34 @@ pub fn delete_cache_by_prefix<P: AsRef<Path>>(path: P, table: &str, prefix: &str
     conn.execute(&sql, params![format!("{}%", prefix)])
 }
 
+/// 按键前缀列出缓存 `(key, data, sync_time)`，按键排序；payload 版本不符的行跳过。
+pub fn list_cache_by_prefix<P: AsRef<Path>>(
+    path: P,
+    table: &str,
+    prefix: &str,
+) -> Result<Vec<(String, Value, String)>> {
+    let conn = open_connection(path)?;
+    let key_column = key_column(table);
+    let sql = format!(
+        "SELECT {key_column}, data, sync_time FROM {table}
+         WHERE {key_column} LIKE ?1 AND schema_version = ?2
+         ORDER BY {key_column}"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(
+        params![format!("{}%", prefix), cache_policy(table).schema_version],
+        |row| {
+            let data = row.get::<_, Option<String>>(1)?.unwrap_or_default();
+            Ok((
+                row.get::<_, String>(0)?,
+                serde_json::from_str(&data).unwrap_or(Value::Null),
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            ))
+        },
+    )?;
+    rows.collect()
+}
+
 /// 在单个 IMMEDIATE 事务内合并任课教师缓存，避免并发 read-modify-write 丢更新。
 ///
 /// 该函数只操作固定表 `grade_teacher_cache`，不接受动态表名。每次只替换指定
@@ -162,17 +268,129 @@ pub fn merge_grade_teacher_cache<P: AsRef<Path>>(
         .map_err(|error| rusqlite::Error::ToSqlConversionFailure(Box::new(error)))?;
     let sync_time = Local::now().to_rfc3339();
     tx.execute(
-        "INSERT INTO grade_teacher_cache (student_id, data, sync_time)
-         VALUES (?1, ?2, ?3)
+        "INSERT INTO grade_teacher_cache (student_id, data, sync_time, schema_version, byte_size)
+         VALUES (?1, ?2, ?3, ?4, ?5)
          ON CONFLICT(student_id) DO UPDATE SET
            data = excluded.data,
-           sync_time = excluded.sync_time",
-        params![student_id, payload, sync_time],
+           sync_time = excluded.sync_time,
+           schema_version = excluded.schema_version,
+           byte_size = excluded.byte_size",
+        params![
+            student_id,
+            payload,
+            sync_time,
+            cache_policy("grade_teacher_cache").schema_version,
+            payload.len() as i64
+        ],
     )?;
     tx.commit()?;
     Ok(existing)
 }
 
+/// 按依赖批量失效缓存，返回删除行数。
+///
+/// 个人表删除 `account` 本身及以 `account:` 开头的派生 key（如 `uid:semester`）；
+/// `account` 为 `None` 时清空整表。公共表不归属账号，依赖命中时整表清空。
+/// 账号失效同时清除其学期记录（见 [`switch_current_semester`]）。
+pub fn invalidate_caches<P: AsRef<Path>>(
+    path: P,
+    dependency: CacheDependency,
+    account: Option<&str>,
+) -> Result<usize> {
+    let mut conn = open_connection(path)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut removed = 0;
+    for policy in CACHE_POLICIES
+        .iter()
+        .filter(|policy| policy.depends_on(dependency))
+    {
+        let table = policy.table;
+        removed += match account.map(str::trim).filter(|a| !a.is_empty()) {
+            Some(account) if !table.contains("public") => tx.execute(
+                &format!(
+                    "DELETE FROM {table}
+                     WHERE student_id = ?1 OR substr(student_id, 1, length(?1) + 1) = ?1 || ':'"
+                ),
+                params![account],
+            )?,
+            _ => tx.execute(&format!("DELETE FROM {table}"), [])?,
+        };
+    }
+    if let (CacheDependency::Account, Some(account)) = (dependency, account) {
+        tx.execute(
+            "DELETE FROM kv_store WHERE key = ?1",
+            params![current_semester_key(account.trim())],
+        )?;
+    }
+    tx.commit()?;
+    Ok(removed)
+}
+
+fn current_semester_key(account: &str) -> String {
+    format!("cache.current_semester.{account}")
+}
+
+/// 记录账号当前学期；与上次记录不同即视为学期切换，失效该账号的学期相关缓存。
+///
+/// 返回 `Some(删除行数)` 表示发生了切换；首次记录或学期未变返回 `None`。
+pub fn switch_current_semester<P: AsRef<Path>>(
+    path: P,
+    account: &str,
+    semester: &str,
+) -> Result<Option<usize>> {
+    let (account, semester) = (account.trim(), semester.trim());
+    if account.is_empty() || semester.is_empty() {
+        return Ok(None);
+    }
+    let key = current_semester_key(account);
+    let previous = super::repositories::kv::get_kv(&path, &key)?;
+    if previous.as_deref() == Some(semester) {
+        return Ok(None);
+    }
+    let removed = match previous {
+        Some(_) => Some(invalidate_caches(
+            &path,
+            CacheDependency::Semester,
+            Some(account),
+        )?),
+        None => None,
+    };
+    super::repositories::kv::set_kv(&path, &key, semester)?;
+    Ok(removed)
+}
+
+/// 单张缓存表的占用统计。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheTableUsage {
+    pub table: &'static str,
+    pub rows: i64,
+    pub bytes: i64,
+}
+
+/// 统计全部登记缓存表的行数与 payload 字节数（按 `byte_size` 累加）。
+pub fn cache_usage<P: AsRef<Path>>(path: P) -> Result<Vec<CacheTableUsage>> {
+    let conn = open_connection(path)?;
+    CACHE_POLICIES
+        .iter()
+        .map(|policy| {
+            conn.query_row(
+                &format!(
+                    "SELECT COUNT(*), COALESCE(SUM(byte_size), 0) FROM {}",
+                    policy.table
+                ),
+                [],
+                |row| {
+                    Ok(CacheTableUsage {
+                        table: policy.table,
+                        rows: row.get(0)?,
+                        bytes: row.get(1)?,
+                    })
+                },
+            )
+        })
+        .collect()
+}
+
 /// 在 Tokio 阻塞线程池执行同步 SQLite，避免长时间占用 async worker。
 pub async fn run_blocking<T, F>(f: F) -> std::result::Result<T, String>
 where
@@ -215,6 +433,106 @@ where
     run_blocking(move || save_cache(path, &table, &key, &data)).await
 }
 
+/// stale-while-revalidate 读取结果。
+#[derive(Debug, Clone)]
+pub struct CacheRead {
+    pub data: Value,
+    pub sync_time: String,
+    pub freshness: CacheFreshness,
+    /// 已返回旧值并在后台刷新
+    pub revalidating: bool,
+    /// 刷新失败，返回的是过期缓存
+    pub offline: bool,
+}
+
+/// 正在后台刷新的 (table, key)，避免同一条目并发重复请求。
+fn revalidating() -> &'static Mutex<HashSet<(String, String)>> {
+    static REVALIDATING: OnceLock<Mutex<HashSet<(String, String)>>> = OnceLock::new();
+    REVALIDATING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 按表策略读取缓存（stale-while-revalidate）：
+/// - 新鲜：直接返回缓存
+/// - 陈旧：返回缓存，同时后台执行 `refresh` 并回写
+/// - 过期 / 未命中：等待 `refresh` 并回写；失败时有旧缓存则以 `offline` 返回，否则透传错误
+pub async fn read_through_cache<P, F, Fut>(
+    path: P,
+    table: &'static str,
+    key: &str,
+    refresh: F,
+) -> std::result::Result<CacheRead, String>
+where
+    P: AsRef<Path> + Clone + Send + 'static,
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = std::result::Result<Value, String>> + Send + 'static,
+{
+    let key = key.to_string();
+    let entry = {
+        let (path, key) = (path.clone(), key.clone());
+        let now = chrono::Utc::now().timestamp();
+        run_blocking(move || read_cache_entry(path, table, &key, now)).await?
+    };
+
+    match entry {
+        Some(entry) if entry.freshness == CacheFreshness::Fresh => Ok(CacheRead {
+            data: entry.data,
+            sync_time: entry.sync_time,
+            freshness: CacheFreshness::Fresh,
+            revalidating: false,
+            offline: false,
+        }),
+        Some(entry) if entry.freshness == CacheFreshness::Stale => {
+            let slot = (table.to_string(), key.clone());
+            let claimed = revalidating()
+                .lock()
+                .map(|mut set| set.insert(slot.clone()))
+                .unwrap_or(false);
+            if claimed {
+                tokio::spawn(async move {
+                    if let Ok(data) = refresh().await {
+                        let _ = save_cache_async(path, table, &key, &data).await;
+                    }
+                    if let Ok(mut set) = revalidating().lock() {
+                        set.remove(&slot);
+                    }
+                });
+            }
+            Ok(CacheRead {
+                data: entry.data,
+                sync_time: entry.sync_time,
+                freshness: CacheFreshness::Stale,
+                revalidating: true,
+                offline: false,
+            })
+        }
+        entry => match refresh().await {
+            Ok(data) => {
+                if let Err(error) = save_cache_async(path, table, &key, &data).await {
+                    // 缓存失败不拖垮成功网络结果（#578）
+                    eprintln!("[db] 缓存写入失败 table={table} key={key}: {error}");
+                }
+                Ok(CacheRead {
+                    data,
+                    sync_time: Local::now().to_rfc3339(),
+                    freshness: CacheFreshness::Fresh,
+                    revalidating: false,
+                    offline: false,
+                })
+            }
+            Err(error) => match entry {
+                Some(entry) => Ok(CacheRead {
+                    data: entry.data,
+                    sync_time: entry.sync_time,
+                    freshness: entry.freshness,
+                    revalidating: false,
+                    offline: true,
+                }),
+                None => Err(error),
+            },
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,4 +675,242 @@ mod tests {
         );
         let _ = std::fs::remove_file(&path);
     }
+
+    fn backdate(path: &Path, table: &str, key: &str, secs: i64) {
+        let synced = (Local::now() - chrono::Duration::seconds(secs)).to_rfc3339();
+        open_connection(path)
+            .expect("open")
+            .execute(
+                &format!(
+                    "UPDATE {table} SET sync_time = ?1 WHERE {} = ?2",
+                    key_column(table)
+                ),
+                params![synced, key],
+            )
+            .expect("backdate");
+    }
+
+    /// payload 版本与策略不一致的行被丢弃；新鲜度按表 TTL 判定；byte_size 随写入记录。
+    #[test]
+    fn version_mismatch_is_discarded_and_freshness_follows_policy() {
+        let path = temp_db_path("versioned");
+        let _ = std::fs::remove_file(&path);
+        super::super::migrations::init_db(&path).expect("init");
+        let payload = serde_json::json!({"courses": [1, 2, 3]});
+        save_cache(&path, "schedule_cache", "2510231001", &payload).expect("save");
+
+        let now = chrono::Utc::now().timestamp();
+        let entry = read_cache_entry(&path, "schedule_cache", "2510231001", now)
+            .expect("read")
+            .expect("exists");
+        assert_eq!(entry.freshness, CacheFreshness::Fresh);
+        assert_eq!(entry.schema_version, 1);
+        assert_eq!(
+            entry.byte_size,
+            serde_json::to_string(&payload).unwrap().len() as i64
+        );
+
+        backdate(&path, "schedule_cache", "2510231001", 7 * 3600);
+        let entry = read_cache_entry(&path, "schedule_cache", "2510231001", now)
+            .expect("read stale")
+            .expect("exists");
+        assert_eq!(entry.freshness, CacheFreshness::Stale);
+        backdate(&path, "schedule_cache", "2510231001", 40 * 86400);
+        let entry = read_cache_entry(&path, "schedule_cache", "2510231001", now)
+            .expect("read expired")
+            .expect("exists");
+        assert_eq!(entry.freshness, CacheFreshness::Expired);
+        assert_eq!(entry.data, payload);
+
+        // 模拟旧版本 App 写入的 payload
+        open_connection(&path)
+            .expect("open")
+            .execute(
+                "UPDATE schedule_cache SET schema_version = 0 WHERE student_id = '2510231001'",
+                [],
+            )
+            .expect("downgrade");
+        assert!(get_cache(&path, "schedule_cache", "2510231001")
+            .expect("get")
+            .is_none());
+        let rows: i64 = open_connection(&path)
+            .expect("open")
+            .query_row("SELECT COUNT(*) FROM schedule_cache", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(rows, 0, "stale-version row should be deleted");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// 学期切换只失效该账号的学期相关缓存与公共学期缓存；账号移除清掉其全部个人缓存。
+    #[test]
+    fn dependency_invalidation_and_usage() {
+        let path = temp_db_path("invalidate");
+        let _ = std::fs::remove_file(&path);
+        super::super::migrations::init_db(&path).expect("init");
+        let data = serde_json::json!({"ok": true});
+        for (table, key) in [
+            ("schedule_cache", "2510231001"),
+            ("exams_cache", "2510231001:2025-2026-1"),
+            ("exams_cache", "25102310011:2025-2026-1"),
+            ("exams_cache", "2510239999:2025-2026-1"),
+            ("classroom_cache", "2510231001:classroom:1:1::"),
+            ("grades_cache", "2510231001"),
+            ("classroom_public_cache", "buildings"),
+            ("semesters_public_cache", "semesters"),
+        ] {
+            save_cache(&path, table, key, &data).expect("seed");
+        }
+        let usage = cache_usage(&path).expect("usage");
+        let exams = usage
+            .iter()
+            .find(|u| u.table == "exams_cache")
+            .expect("exams usage");
+        assert_eq!(exams.rows, 3);
+        assert_eq!(exams.bytes, 3 * data.to_string().len() as i64);
+
+        let removed = invalidate_caches(&path, CacheDependency::Semester, Some("2510231001"))
+            .expect("invalidate semester");
+        assert_eq!(removed, 4);
+        assert!(get_cache(&path, "schedule_cache", "2510231001")
+            .expect("get")
+            .is_none());
+        assert!(get_cache(&path, "classroom_public_cache", "buildings")
+            .expect("get")
+            .is_none());
+        // 其他账号（含前缀相同的学号）、非学期缓存保持不变
+        for (table, key) in [
+            ("exams_cache", "25102310011:2025-2026-1"),
+            ("exams_cache", "2510239999:2025-2026-1"),
+            ("grades_cache", "2510231001"),
+            ("semesters_public_cache", "semesters"),
+        ] {
+            assert!(
+                get_cache(&path, table, key).expect("get").is_some(),
+                "{table}/{key}"
+            );
+        }
+
+        // 学期切换检测：首次只记录，学期变化才失效
+        assert_eq!(
+            switch_current_semester(&path, "2510231001", "2025-2026-1").expect("record"),
+            None
+        );
+        assert_eq!(
+            switch_current_semester(&path, "2510231001", "2025-2026-1").expect("same"),
+            None
+        );
+        save_cache(&path, "schedule_cache", "2510231001", &data).expect("reseed");
+        assert_eq!(
+            switch_current_semester(&path, "2510231001", "2025-2026-2").expect("switch"),
+            Some(1)
+        );
+
+        let removed = invalidate_caches(&path, CacheDependency::Account, Some("2510231001"))
+            .expect("invalidate account");
+        assert_eq!(removed, 1);
+        assert!(get_cache(&path, "semesters_public_cache", "semesters")
+            .expect("get")
+            .is_some());
+        assert!(
+            super::super::repositories::kv::get_kv(&path, "cache.current_semester.2510231001")
+                .expect("kv")
+                .is_none()
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// 旧库缓存表（无版本 / 字节列）经 init_db 补列并回填 byte_size，既有数据仍可读。
+    #[test]
+    fn legacy_cache_table_is_upgraded() {
+        let path = temp_db_path("legacy_cache");
+        let _ = std::fs::remove_file(&path);
+        {
+            let conn = rusqlite::Connection::open(&path).expect("open legacy");
+            conn.execute_batch(
+                "CREATE TABLE grades_cache (student_id TEXT PRIMARY KEY, data TEXT, sync_time TEXT);
+                 INSERT INTO grades_cache VALUES ('legacy', '{\"n\":1}', '2024-01-01T00:00:00+08:00');",
+            )
+            .expect("seed legacy");
+        }
+        super::super::migrations::init_db(&path).expect("init");
+        let entry = read_cache_entry(
+            &path,
+            "grades_cache",
+            "legacy",
+            chrono::Utc::now().timestamp(),
+        )
+        .expect("read")
+        .expect("exists");
+        assert_eq!(entry.schema_version, 1);
+        assert_eq!(entry.byte_size, 7);
+        assert_eq!(entry.freshness, CacheFreshness::Expired);
+        let pre_migration = super::super::backup::default_backup_dir(&path)
+            .join(super::super::backup::PRE_MIGRATION_BACKUP_SUBDIR);
+        for backup in super::super::backup::list_backups(&pre_migration, &path).unwrap_or_default()
+        {
+            let _ = std::fs::remove_file(backup);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// 陈旧命中立即返回旧值并后台回写；过期且刷新失败时以 offline 兜底。
+    #[tokio::test]
+    async fn read_through_cache_revalidates_stale_entries() {
+        let path = temp_db_path("swr");
+        let _ = std::fs::remove_file(&path);
+        super::super::migrations::init_db(&path).expect("init");
+        save_cache(
+            &path,
+            "library_public_cache",
+            "hot",
+            &serde_json::json!({"v": 1}),
+        )
+        .expect("seed");
+
+        let read = read_through_cache(path.clone(), "library_public_cache", "hot", || async {
+            Err::<Value, _>("unreachable".to_string())
+        })
+        .await
+        .expect("fresh");
+        assert_eq!(read.freshness, CacheFreshness::Fresh);
+        assert!(!read.revalidating);
+
+        backdate(&path, "library_public_cache", "hot", 2 * 3600);
+        let read = read_through_cache(path.clone(), "library_public_cache", "hot", || async {
+            Ok(serde_json::json!({"v": 2}))
+        })
+        .await
+        .expect("stale");
+        assert_eq!(read.data, serde_json::json!({"v": 1}));
+        assert!(read.revalidating);
+        for _ in 0..50 {
+            let (data, _) = get_cache(&path, "library_public_cache", "hot")
+                .expect("get")
+                .expect("exists");
+            if data == serde_json::json!({"v": 2}) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let (data, _) = get_cache(&path, "library_public_cache", "hot")
+            .expect("get")
+            .expect("exists");
+        assert_eq!(data, serde_json::json!({"v": 2}));
+
+        backdate(&path, "library_public_cache", "hot", 30 * 86400);
+        let read = read_through_cache(path.clone(), "library_public_cache", "hot", || async {
+            Err::<Value, _>("offline".to_string())
+        })
+        .await
+        .expect("offline fallback");
+        assert!(read.offline);
+        assert_eq!(read.freshness, CacheFreshness::Expired);
+
+        let missing = read_through_cache(path.clone(), "library_public_cache", "cold", || async {
+            Err::<Value, _>("offline".to_string())
+        })
+        .await;
+        assert_eq!(missing.unwrap_err(), "offline");
+        let _ = std::fs::remove_file(&path);
+    }
 }
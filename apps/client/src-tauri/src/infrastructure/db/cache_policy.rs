@@ -0,0 +1,251 @@
+//! 缓存表策略：每张缓存表的 payload 版本、TTL、最长可用陈旧期与失效依赖。
+//!
+//! - `schema_version`：payload 结构变更时递增；读到旧版本行直接丢弃，不再误解析
+//! - `ttl_secs`：新鲜期，`None` 表示永不过期（快照 / 映射类缓存）
+//! - `max_stale_secs`：过了新鲜期仍可先返回旧值并后台刷新的窗口（stale-while-revalidate），
+//!   `None` 表示不限；超出窗口视为过期，只在网络失败时作离线兜底
+//! - `depends_on`：学期切换 / 账号移除时需要一并失效的依赖
+
+/// 缓存失效依赖。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheDependency {
+    /// 当前学期切换：课表、考试、教室、校历等按学期组织的缓存
+    Semester,
+    /// 账号移除：该学号名下的全部个人缓存
+    Account,
+}
+
+/// 缓存条目相对策略的新鲜度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheFreshness {
+    Fresh,
+    Stale,
+    Expired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub table: &'static str,
+    pub schema_version: i64,
+    pub ttl_secs: Option<i64>,
+    pub max_stale_secs: Option<i64>,
+    pub depends_on: &'static [CacheDependency],
+}
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+const ACCOUNT: &[CacheDependency] = &[CacheDependency::Account];
+const ACCOUNT_SEMESTER: &[CacheDependency] = &[CacheDependency::Account, CacheDependency::Semester];
+const SEMESTER: &[CacheDependency] = &[CacheDependency::Semester];
+const NONE: &[CacheDependency] = &[];
+
+const fn policy(
+    table: &'static str,
+    ttl_secs: Option<i64>,
+    max_stale_secs: Option<i64>,
+    depends_on: &'static [CacheDependency],
+) -> CachePolicy {
+    CachePolicy {
+        table,
+        schema_version: 1,
+        ttl_secs,
+        max_stale_secs,
+        depends_on,
+    }
+}
+
+/// 未登记表的兜底策略：永不过期、无依赖，等价于历史行为。
+const DEFAULT_POLICY: CachePolicy = policy("", None, None, NONE);
+
+/// 全部缓存表策略；新增缓存表时在此登记（migrations 建表列表保持一致）。
+pub const CACHE_POLICIES: &[CachePolicy] = &[
+    policy("grades_cache", Some(30 * MINUTE), Some(7 * DAY), ACCOUNT),
+    policy("grade_teacher_cache", None, None, ACCOUNT),
+    policy("grade_snapshot_cache", None, None, ACCOUNT),
+    policy(
+        "schedule_cache",
+        Some(6 * HOUR),
+        Some(30 * DAY),
+        ACCOUNT_SEMESTER,
+    ),
+    policy("exams_cache", Some(HOUR), Some(30 * DAY), ACCOUNT_SEMESTER),
+    policy("exam_snapshot_cache", None, None, ACCOUNT),
+    policy("studentinfo_cache", Some(DAY), None, ACCOUNT),
+    policy(
+        "calendar_cache",
+        Some(DAY),
+        Some(180 * DAY),
+        ACCOUNT_SEMESTER,
+    ),
+    policy("ranking_cache", Some(6 * HOUR), Some(30 * DAY), ACCOUNT),
+    policy(
+        "academic_progress_cache",
+        Some(6 * HOUR),
+        Some(30 * DAY),
+        ACCOUNT,
+    ),
+    policy("training_plan_cache", Some(DAY), Some(180 * DAY), ACCOUNT),
+    policy(
+        "classroom_cache",
+        Some(10 * MINUTE),
+        Some(DAY),
+        ACCOUNT_SEMESTER,
+    ),
+    policy("electricity_cache", Some(10 * MINUTE), Some(DAY), ACCOUNT),
+    policy(
+        "transaction_cache",
+        Some(10 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "student_login_access_cache",
+        Some(10 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy("ai_session_cache", None, None, ACCOUNT),
+    policy(
+        "calendar_public_cache",
+        Some(DAY),
+        Some(180 * DAY),
+        SEMESTER,
+    ),
+    policy(
+        "classroom_public_cache",
+        Some(DAY),
+        Some(30 * DAY),
+        SEMESTER,
+    ),
+    policy("semesters_public_cache", Some(DAY), Some(180 * DAY), NONE),
+    policy("qxzkb_public_cache", Some(DAY), Some(30 * DAY), NONE),
+    policy("library_public_cache", Some(HOUR), Some(7 * DAY), NONE),
+    policy(
+        "library_account_cache",
+        Some(30 * MINUTE),
+        Some(30 * DAY),
+        ACCOUNT,
+    ),
+    policy("weather_public_cache", Some(5 * MINUTE), Some(HOUR), NONE),
+    policy(
+        "school_inbox_cache",
+        Some(3 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_overview_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_chaoxing_courses_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_chaoxing_outline_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_chaoxing_progress_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_yuketang_courses_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_yuketang_outline_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+    policy(
+        "online_learning_yuketang_progress_cache",
+        Some(30 * MINUTE),
+        Some(7 * DAY),
+        ACCOUNT,
+    ),
+];
+
+/// 查表策略；未登记的表返回兜底策略（永不过期、版本 1、无依赖）。
+pub fn cache_policy(table: &str) -> CachePolicy {
+    CACHE_POLICIES
+        .iter()
+        .find(|policy| policy.table == table)
+        .copied()
+        .unwrap_or(DEFAULT_POLICY)
+}
+
+impl CachePolicy {
+    pub fn depends_on(&self, dependency: CacheDependency) -> bool {
+        self.depends_on.contains(&dependency)
+    }
+
+    /// 按写入后经过的秒数判定新鲜度；时间回拨（负数）按刚写入处理。
+    pub fn freshness(&self, age_secs: i64) -> CacheFreshness {
+        let age = age_secs.max(0);
+        let Some(ttl) = self.ttl_secs else {
+            return CacheFreshness::Fresh;
+        };
+        if age <= ttl {
+            return CacheFreshness::Fresh;
+        }
+        match self.max_stale_secs {
+            Some(max_stale) if age > ttl + max_stale => CacheFreshness::Expired,
+            _ => CacheFreshness::Stale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freshness_follows_ttl_and_stale_window() {
+        let schedule = cache_policy("schedule_cache");
+        assert_eq!(schedule.freshness(0), CacheFreshness::Fresh);
+        assert_eq!(schedule.freshness(6 * HOUR), CacheFreshness::Fresh);
+        assert_eq!(schedule.freshness(6 * HOUR + 1), CacheFreshness::Stale);
+        assert_eq!(
+            schedule.freshness(6 * HOUR + 30 * DAY + 1),
+            CacheFreshness::Expired
+        );
+        // 时间回拨不判过期
+        assert_eq!(schedule.freshness(-10), CacheFreshness::Fresh);
+        // 未登记表：永不过期
+        assert_eq!(
+            cache_policy("unknown_cache").freshness(365 * DAY),
+            CacheFreshness::Fresh
+        );
+    }
+
+    #[test]
+    fn semester_dependents_cover_schedule_exams_and_classroom() {
+        for table in ["schedule_cache", "exams_cache", "classroom_cache"] {
+            assert!(
+                cache_policy(table).depends_on(CacheDependency::Semester),
+                "{table} should depend on semester"
+            );
+        }
+        assert!(!cache_policy("grades_cache").depends_on(CacheDependency::Semester));
+        // 公共表不属于任何账号
+        assert!(CACHE_POLICIES
+            .iter()
+            .filter(|policy| policy.table.contains("public"))
+            .all(|policy| !policy.depends_on(CacheDependency::Account)));
+    }
+}
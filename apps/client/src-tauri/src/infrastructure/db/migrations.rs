@@ -2,15 +2,25 @@
 //!
 //! 负责：
 //! - `init_db` 统一建表（grades / cache / user_sessions / custom_schedule /
-//!   online_learning / kv_store / auth_cookie_v2 等）
+//!   online_learning / kv_store / auth_cookie_v2 / bridge_api_tokens 等）
+//! - 缓存表统一带 `schema_version` / `byte_size` 列（见 `cache_policy`）
 //! - 幂等补列（旧库 ALTER）与 schema_migrations 版本记录
+//! - 已有库存在未记录的迁移版本时，迁移前先备份到 `backup/pre-migration/`
 //!
 //! 注意：安全迁移（凭据加密重写）不在此模块自动执行，必须由用户显式触发。
 
-use rusqlite::{params, Connection, OptionalExtension, Result};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Result};
 use std::path::Path;
 
-use super::connection::open_connection;
+use super::backup::{backup_database, default_backup_dir, PRE_MIGRATION_BACKUP_SUBDIR};
+use super::connection::{open_connection, resolve_db_path};
+
+/// `init_db` 记录的全部 schema 版本（新增迁移时同步追加）；
+/// version 4 由 `migrate_session_passwords_v2` 显式流程单独记录，不在此列。
+pub(crate) const SCHEMA_VERSIONS: &[i64] = &[1, 2, 3, 5, 6, 7, 8, 9];
+
+/// 迁移前备份保留份数。
+const PRE_MIGRATION_BACKUP_KEEP: usize = 3;
 
 /// 幂等补列：仅当表存在且缺少目标列时才执行 ALTER。
 /// 表不存在（新库由 init_db 统一建表）时静默跳过；表存在但缺列时如实补列，
@@ -165,15 +175,7 @@ pub(crate) fn ensure_schema_migration(
         )",
         [],
     )?;
-    let applied: bool = conn
-        .query_row(
-            "SELECT 1 FROM schema_migrations WHERE version = ?1",
-            params![version],
-            |_| Ok(true),
-        )
-        .optional()?
-        .unwrap_or(false);
-    if !applied {
+    if !schema_migration_applied(conn, version)? {
         conn.execute(
             "INSERT INTO schema_migrations (version, description) VALUES (?1, ?2)",
             params![version, description],
@@ -182,6 +184,29 @@ pub(crate) fn ensure_schema_migration(
     Ok(())
 }
 
+/// 指定 schema 版本是否已记录；`schema_migrations` 尚未建表（新库）时视为未记录。
+fn schema_migration_applied(conn: &Connection, version: i64) -> Result<bool> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type='table' AND name='schema_migrations'",
+            [],
+            |_| Ok(true),
+        )
+        .optional()?
+        .unwrap_or(false);
+    if !has_table {
+        return Ok(false);
+    }
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM schema_migrations WHERE version = ?1",
+            params![version],
+            |_| Ok(true),
+        )
+        .optional()?
+        .unwrap_or(false))
+}
+
 /// 多域会话 cookie（#348/#349）：按 student_id + domain 存 JSON 数组。
 pub(crate) fn migrate_auth_cookie_v2_table(conn: &Connection) -> Result<()> {
     conn.execute(
@@ -298,12 +323,121 @@ pub(crate) fn migrate_add_chaoxing_checkin_log(conn: &Connection) -> Result<()>
     Ok(())
 }
 
+/// 电费余额读数（关注房间的时间序列）：按 room_id + Unix 秒存储。
+pub(crate) fn migrate_add_electricity_readings(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS electricity_readings (
+            room_id     TEXT    NOT NULL,
+            recorded_at INTEGER NOT NULL,
+            balance     REAL    NOT NULL,
+            quantity    REAL    NOT NULL DEFAULT 0,
+            PRIMARY KEY (room_id, recorded_at)
+        );",
+    )?;
+    Ok(())
+}
+
+/// Bridge API 令牌（用户签发、限定权限、可过期）：仅存令牌 SHA-256 摘要，时间为 Unix 秒。
+pub(crate) fn migrate_add_bridge_api_tokens(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS bridge_api_tokens (
+            id           TEXT    PRIMARY KEY,
+            name         TEXT    NOT NULL,
+            token_hash   TEXT    NOT NULL UNIQUE,
+            scopes       TEXT    NOT NULL DEFAULT '',
+            created_at   INTEGER NOT NULL,
+            expires_at   INTEGER NOT NULL,
+            revoked_at   INTEGER,
+            last_used_at INTEGER
+        );",
+    )?;
+    Ok(())
+}
+
+/// 缓存表补 payload 版本与字节数列；旧行按版本 1 记，并回填 `byte_size`。
+///
+/// 回填只在 schema 版本 9 记录之前执行一次：之后每次启动不再全表扫描，
+/// 新写入的行由 `save_cache` 自带 `byte_size`。
+pub(crate) fn migrate_add_cache_versioning(conn: &Connection, table: &str) -> Result<()> {
+    ensure_column(
+        conn,
+        table,
+        "schema_version",
+        &format!("ALTER TABLE {table} ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1"),
+    )?;
+    ensure_column(
+        conn,
+        table,
+        "byte_size",
+        &format!("ALTER TABLE {table} ADD COLUMN byte_size INTEGER NOT NULL DEFAULT 0"),
+    )?;
+    if schema_migration_applied(conn, 9)? {
+        return Ok(());
+    }
+    conn.execute(
+        &format!(
+            "UPDATE {table} SET byte_size = length(CAST(data AS BLOB))
+             WHERE byte_size = 0 AND data IS NOT NULL"
+        ),
+        [],
+    )?;
+    Ok(())
+}
+
+/// 已有库是否还有迁移要做：有业务表，但缺 schema_migrations 或缺任一已知版本。
+/// 新库（文件不存在或无表）返回 `false`。只读打开，不触发任何补列。
+fn has_pending_migrations(path: &Path) -> Result<bool> {
+    let resolved = resolve_db_path(path);
+    if !resolved.is_file() {
+        return Ok(false);
+    }
+    let conn = Connection::open_with_flags(&resolved, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |row| row.get(0),
+    )?;
+    if tables == 0 {
+        return Ok(false);
+    }
+    let has_migrations_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type='table' AND name='schema_migrations'",
+            [],
+            |_| Ok(true),
+        )
+        .optional()?
+        .unwrap_or(false);
+    if !has_migrations_table {
+        return Ok(true);
+    }
+    let mut stmt = conn.prepare("SELECT version FROM schema_migrations")?;
+    let applied = stmt
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(SCHEMA_VERSIONS.iter().any(|v| !applied.contains(v)))
+}
+
 /// 初始化数据库：建表 + 幂等迁移 + schema 版本记录。
 ///
+/// 已有库存在待执行迁移时，先备份到 `backup/pre-migration/`（保留最近 3 份）；
+/// 备份失败时不迁移、直接返回错误，避免无法回退的升级。
+///
 /// 安全迁移（凭据加密重写）必须由用户明确触发。启动阶段只建表，
 /// 不扫描或重写真实用户凭据。
 pub fn init_db<P: AsRef<Path>>(path: P) -> Result<()> {
     let path_ref = path.as_ref();
+    if has_pending_migrations(path_ref)? {
+        let report = backup_database(
+            path_ref,
+            default_backup_dir(path_ref).join(PRE_MIGRATION_BACKUP_SUBDIR),
+            PRE_MIGRATION_BACKUP_KEEP,
+        )?;
+        eprintln!(
+            "[db] 迁移前已备份数据库: {}",
+            report.backup_path.to_string_lossy()
+        );
+    }
     let conn = open_connection(path_ref)?;
 
     // 1. 创建 grades 表
@@ -338,8 +472,10 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> Result<()> {
     let cache_tables = vec![
         "grades_cache",
         "grade_teacher_cache",
+        "grade_snapshot_cache",
         "schedule_cache",
         "exams_cache",
+        "exam_snapshot_cache",
         "studentinfo_cache",
         "calendar_cache",
         "ranking_cache",
@@ -350,11 +486,14 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> Result<()> {
         "transaction_cache",
         "student_login_access_cache",
         "ai_session_cache",
+        "library_account_cache",
         "calendar_public_cache",  // public
         "classroom_public_cache", // public
         "semesters_public_cache", // public
         "qxzkb_public_cache",     // public
         "library_public_cache",   // public
+        "weather_public_cache",   // public
+        "school_inbox_cache",
         "online_learning_overview_cache",
         "online_learning_chaoxing_courses_cache",
         "online_learning_chaoxing_outline_cache",
@@ -370,7 +509,9 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> Result<()> {
                 "CREATE TABLE IF NOT EXISTS {} (
                 cache_key TEXT PRIMARY KEY,
                 data TEXT,
-                sync_time TEXT
+                sync_time TEXT,
+                schema_version INTEGER NOT NULL DEFAULT 1,
+                byte_size INTEGER NOT NULL DEFAULT 0
             )",
                 table
             )
@@ -379,12 +520,15 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> Result<()> {
                 "CREATE TABLE IF NOT EXISTS {} (
                 student_id TEXT PRIMARY KEY,
                 data TEXT,
-                sync_time TEXT
+                sync_time TEXT,
+                schema_version INTEGER NOT NULL DEFAULT 1,
+                byte_size INTEGER NOT NULL DEFAULT 0
             )",
                 table
             )
         };
         conn.execute(&sql, [])?;
+        migrate_add_cache_versioning(&conn, table)?;
     }
 
     // 3. 用户会话表
@@ -497,6 +641,11 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> Result<()> {
         6,
         "custom_schedule_courses.color optional user color",
     )?;
+    migrate_add_electricity_readings(&conn)?;
+    ensure_schema_migration(&conn, 7, "electricity_readings balance time series")?;
+    migrate_add_bridge_api_tokens(&conn)?;
+    ensure_schema_migration(&conn, 8, "bridge_api_tokens scoped expiring bridge tokens")?;
+    ensure_schema_migration(&conn, 9, "cache tables schema_version/byte_size columns")?;
 
     // 历史空壳 NULL 自愈（#659 根因 2）：幂等，仅契约列 NULL→''，不覆盖非空值；
     // 失败直接传播（启动阶段 lib.rs 可见），计数经 eprintln/report 可观测。
@@ -628,6 +777,33 @@ mod tests {
             .unwrap_or(false);
         assert!(has_v6, "schema migration v6 not recorded");
         drop(conn);
+
+        // 旧库迁移前留有一份备份，且备份仍是旧结构；再次 init_db 无待迁移，不再备份
+        let pre_migration = default_backup_dir(&path).join(PRE_MIGRATION_BACKUP_SUBDIR);
+        let backups = super::super::backup::list_backups(&pre_migration, &path).expect("list");
+        assert_eq!(backups.len(), 1);
+        let backup = RawConnection::open(&backups[0]).expect("open backup");
+        let has_migrations_table: bool = backup
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE name = 'schema_migrations'",
+                [],
+                |_| Ok(true),
+            )
+            .optional()
+            .unwrap()
+            .unwrap_or(false);
+        assert!(!has_migrations_table, "backup should predate migrations");
+        drop(backup);
+        init_db(&path).expect("init again");
+        assert_eq!(
+            super::super::backup::list_backups(&pre_migration, &path)
+                .expect("list again")
+                .len(),
+            1
+        );
+        for backup in backups {
+            let _ = std::fs::remove_file(backup);
+        }
         let _ = std::fs::remove_file(&path);
     }
 
@@ -644,8 +820,47 @@ mod tests {
                 row.get(0)
             })
             .expect("count");
-        // init_db 记录版本 1,2,3,5,6；version 4 由 migrate_session_passwords_v2 单独记录
-        assert_eq!(count, 5);
+        // init_db 记录版本 1,2,3,5,6,7,8,9；version 4 由 migrate_session_passwords_v2 单独记录
+        assert_eq!(count, 8);
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// byte_size 回填只随版本 9 执行一次，之后启动不再扫描缓存表。
+    #[test]
+    fn cache_byte_size_backfill_runs_once() {
+        let path = temp_db_path("byte-size-backfill");
+        let _ = std::fs::remove_file(&path);
+        init_db(&path).expect("init");
+        let conn = open_connection(&path).expect("open");
+        conn.execute(
+            "INSERT INTO grades_cache (student_id, data, sync_time, byte_size)
+             VALUES ('2510231001', '{\"v\":1}', '2026-01-01', 0)",
+            [],
+        )
+        .expect("seed");
+
+        migrate_add_cache_versioning(&conn, "grades_cache").expect("migrate again");
+        let byte_size: i64 = conn
+            .query_row(
+                "SELECT byte_size FROM grades_cache WHERE student_id = '2510231001'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("row");
+        assert_eq!(byte_size, 0, "backfill must not rerun after version 9");
+
+        conn.execute("DELETE FROM schema_migrations WHERE version = 9", [])
+            .expect("forget v9");
+        migrate_add_cache_versioning(&conn, "grades_cache").expect("backfill");
+        let byte_size: i64 = conn
+            .query_row(
+                "SELECT byte_size FROM grades_cache WHERE student_id = '2510231001'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("row");
+        assert_eq!(byte_size, 7);
         drop(conn);
         let _ = std::fs::remove_file(&path);
     }
@@ -7,30 +7,48 @@
 //! - `connection`：路径解析、连接打开与 PRAGMA、错误构造
 //! - `migrations`：schema 初始化与幂等补列
 //! - `credential`：账户主密钥、AES/HMAC 信封、凭据迁移
-//! - `cache`：JSON 缓存读写与异步包装
-//! - `backup`：明文/加密备份、恢复、校验、保留策略
+//! - `cache`：JSON 缓存读写、新鲜度 / stale-while-revalidate 读取、依赖失效与占用统计
+//! - `cache_policy`：各缓存表的 payload 版本、TTL、陈旧窗口与失效依赖
+//! - `backup`：明文/加密备份、恢复、校验、保留策略、定时备份与备份列表
+//! - `restore`：备份与正式库的按表差异预览、按表选择性恢复
+//! - `profile`：跨设备迁移的口令加密资料包导出与合并导入
 //! - `repositories`：user_sessions / auth_cookie_v2 / custom_schedule_courses /
-//!   online_learning / chaoxing_checkin_log 业务仓储
+//!   online_learning / chaoxing_checkin_log / electricity_readings / bridge_api_tokens /
+//!   kv_store 业务仓储
 
 pub mod backup;
 pub mod cache;
+pub mod cache_policy;
 pub mod connection;
 pub mod credential;
 pub mod migrations;
+pub mod profile;
 pub mod repositories;
+pub mod restore;
 
 pub use backup::{
-    backup_database, backup_database_encrypted, list_backups, restore_encrypted_backup,
-    verify_backup, BackupReport, EncryptedBackupReport, BACKUP_KEEP_DEFAULT, BACKUP_KEEP_MAX,
+    backup_database, backup_database_encrypted, default_backup_dir, list_backup_files,
+    list_backups, restore_encrypted_backup, run_scheduled_backup, verify_backup, BackupFileInfo,
+    BackupReport, EncryptedBackupReport, BACKUP_KEEP_DEFAULT, BACKUP_KEEP_MAX,
 };
 pub use cache::{
-    delete_cache, delete_cache_by_prefix, get_cache, get_cache_async, merge_grade_teacher_cache,
-    run_blocking, save_cache, save_cache_async,
+    cache_usage, delete_cache, delete_cache_by_prefix, get_cache, get_cache_async,
+    invalidate_caches, list_cache_by_prefix, merge_grade_teacher_cache, read_cache_entry,
+    read_through_cache, run_blocking, save_cache, save_cache_async, switch_current_semester,
+    CacheEntry, CacheRead, CacheTableUsage,
 };
+pub use cache_policy::{cache_policy, CacheDependency, CacheFreshness, CachePolicy};
 pub use connection::open_db_connection;
 pub use credential::{
     migrate_session_passwords_v2, migrate_session_secrets_v1, CredMigrateReport,
     SessionSecretMigrationReport,
 };
 pub use migrations::init_db;
+pub use profile::{
+    export_profile, import_profile, ProfileConflict, ProfileExportReport, ProfileImportReport,
+    ProfileManifest, PROFILE_FILE_EXTENSION, PROFILE_MIN_PASSPHRASE_CHARS,
+};
 pub use repositories::*;
+pub use restore::{
+    preview_restore, restore_tables, RestorePreview, TableRestoreReport, RESTORABLE_TABLES,
+};
@@ -0,0 +1,526 @@
+//! 跨设备迁移的口令加密资料包（profile bundle）。
+//!
+//! - 只含用户自己产生的数据：自定义课程、kv_store 中的设置类键（[`PROFILE_KV_PREFIXES`]：
+//!   关注房间、签到位置历史、考试 / 图书到期提醒提前量、图书检索订阅、后台任务配置）以及前端传入的设置（含使用统计开关）；
+//! - 刻意不含会话 Cookie、密钥环凭据、账号注册表、缓存、Bridge / 订阅令牌；
+//! - 文件为 JSON：明文头（格式、版本、KDF 参数）+ 口令派生密钥加密的载荷，载荷内含版本化 manifest；
+//! - 导入只合并不覆盖：本地缺失的条目写入，相同的跳过，内容不同的保留本地并报告冲突。
+
+use base64::{engine::general_purpose, Engine as _};
+use rand::RngCore;
+use rusqlite::{params, Connection, OptionalExtension, Result, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::connection::{io_to_rusqlite_err, open_connection};
+use super::migrations::SCHEMA_VERSIONS;
+use super::repositories::custom_schedule::{
+    map_custom_schedule_course_row, CustomScheduleCourseRecord,
+};
+
+pub const PROFILE_FORMAT: &str = "mini-hbut-profile";
+/// 资料包格式版本；载荷结构变更时递增，导入拒绝高于此版本的文件。
+pub const PROFILE_FORMAT_VERSION: u32 = 1;
+/// 导出资料包的默认扩展名。
+pub const PROFILE_FILE_EXTENSION: &str = "mhbprofile";
+/// 导出时要求的最短口令（字符数）。
+pub const PROFILE_MIN_PASSPHRASE_CHARS: usize = 8;
+
+const PROFILE_KDF: &str = "pbkdf2-sha256";
+const PROFILE_KDF_ITERATIONS: u32 = 100_000;
+/// 导入时接受的迭代次数上限，防止构造的文件拖住解密。
+const PROFILE_KDF_MAX_ITERATIONS: u32 = 5_000_000;
+const PROFILE_SALT_LEN: usize = 16;
+
+/// 随资料包迁移的 kv_store 键前缀；账号注册表、缓存学期、订阅令牌摘要等设备相关键不在此列。
+pub const PROFILE_KV_PREFIXES: &[&str] = &[
+    "electricity.room.",
+    "chaoxing_checkin.location_history.",
+    "exam.reminder_leads",
+    "library.reminder_days",
+    "library.saved_search.",
+    "scheduler.job.",
+];
+
+pub const SECTION_CUSTOM_COURSES: &str = "custom_schedule_courses";
+pub const SECTION_KV: &str = "kv_store";
+pub const SECTION_SETTINGS: &str = "settings";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileManifest {
+    pub format_version: u32,
+    /// 导出端应用版本
+    pub app_version: String,
+    /// 导出端数据库 schema 版本
+    pub schema_version: i64,
+    /// 导出时间（本地时间 RFC 3339）
+    pub created_at: String,
+    /// 各部分条目数
+    pub sections: BTreeMap<String, usize>,
+}
+
+/// 磁盘上的资料包：明文头 + 加密载荷。
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfileFile {
+    format: String,
+    format_version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    payload: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfilePayload {
+    manifest: ProfileManifest,
+    custom_schedule_courses: Vec<CustomScheduleCourseRecord>,
+    kv: BTreeMap<String, String>,
+    settings: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileExportReport {
+    pub path: PathBuf,
+    pub manifest: ProfileManifest,
+}
+
+/// 导入时内容不同的条目：保留本地值，由用户决定是否手动调整。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfileConflict {
+    pub section: &'static str,
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileImportReport {
+    pub manifest: ProfileManifest,
+    /// 为 true 时只计算合并结果，未写入数据库
+    pub dry_run: bool,
+    /// 各部分新写入的条目数
+    pub added: BTreeMap<&'static str, usize>,
+    /// 各部分与本地完全相同而跳过的条目数
+    pub unchanged: BTreeMap<&'static str, usize>,
+    pub conflicts: Vec<ProfileConflict>,
+    /// 合并后的前端设置（本地优先），由前端写回自身存储
+    pub merged_settings: Map<String, Value>,
+}
+
+fn invalid_data(message: impl Into<String>) -> rusqlite::Error {
+    io_to_rusqlite_err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message.into(),
+    ))
+}
+
+fn is_portable_kv_key(key: &str) -> bool {
+    PROFILE_KV_PREFIXES
+        .iter()
+        .any(|prefix| key.starts_with(prefix))
+}
+
+fn load_all_courses(conn: &Connection) -> Result<Vec<CustomScheduleCourseRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, student_id, semester, name, teacher, room, weekday, period, djs, weeks_json, color, created_at, updated_at
+         FROM custom_schedule_courses
+         ORDER BY student_id ASC, id ASC",
+    )?;
+    let rows = stmt.query_map([], map_custom_schedule_course_row)?;
+    rows.collect()
+}
+
+fn load_portable_kv(conn: &Connection) -> Result<BTreeMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT key, value FROM kv_store ORDER BY key ASC")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut out = BTreeMap::new();
+    for row in rows {
+        let (key, value) = row?;
+        if is_portable_kv_key(&key) {
+            out.insert(key, value);
+        }
+    }
+    Ok(out)
+}
+
+/// 课程内容是否一致（忽略创建 / 更新时间）。
+fn same_course(a: &CustomScheduleCourseRecord, b: &CustomScheduleCourseRecord) -> bool {
+    a.student_id == b.student_id
+        && a.semester == b.semester
+        && a.name == b.name
+        && a.teacher == b.teacher
+        && a.room == b.room
+        && a.weekday == b.weekday
+        && a.period == b.period
+        && a.djs == b.djs
+        && a.weeks == b.weeks
+        && a.color == b.color
+}
+
+/// 导出资料包到 `destination`（先写临时文件再原子改名）。
+///
+/// `settings` 为前端自行挑选的设置项（不应含令牌、密码等凭据）。
+pub fn export_profile<P: AsRef<Path>, Q: AsRef<Path>>(
+    db_path: P,
+    destination: Q,
+    passphrase: &str,
+    settings: &Map<String, Value>,
+) -> Result<ProfileExportReport> {
+    if passphrase.chars().count() < PROFILE_MIN_PASSPHRASE_CHARS {
+        return Err(invalid_data(format!(
+            "口令至少需要 {PROFILE_MIN_PASSPHRASE_CHARS} 个字符"
+        )));
+    }
+    let conn = open_connection(db_path)?;
+    let custom_schedule_courses = load_all_courses(&conn)?;
+    let kv = load_portable_kv(&conn)?;
+    drop(conn);
+
+    let manifest = ProfileManifest {
+        format_version: PROFILE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: SCHEMA_VERSIONS.iter().copied().max().unwrap_or_default(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        sections: BTreeMap::from([
+            (
+                SECTION_CUSTOM_COURSES.to_string(),
+                custom_schedule_courses.len(),
+            ),
+            (SECTION_KV.to_string(), kv.len()),
+            (SECTION_SETTINGS.to_string(), settings.len()),
+        ]),
+    };
+    let payload = ProfilePayload {
+        manifest: manifest.clone(),
+        custom_schedule_courses,
+        kv,
+        settings: settings.clone(),
+    };
+    let plaintext = serde_json::to_vec(&payload).map_err(|e| invalid_data(e.to_string()))?;
+
+    let mut salt = [0u8; PROFILE_SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key =
+        crate::secret_envelope::derive_passphrase_key(passphrase, &salt, PROFILE_KDF_ITERATIONS)
+            .map_err(|e| invalid_data(e.to_string()))?;
+    let encrypted = crate::secret_envelope::encrypt_bytes(&key, &plaintext)
+        .map_err(|e| invalid_data(e.to_string()))?;
+    let file = ProfileFile {
+        format: PROFILE_FORMAT.to_string(),
+        format_version: PROFILE_FORMAT_VERSION,
+        kdf: PROFILE_KDF.to_string(),
+        iterations: PROFILE_KDF_ITERATIONS,
+        salt: general_purpose::STANDARD.encode(salt),
+        payload: encrypted,
+    };
+    let raw = serde_json::to_vec_pretty(&file).map_err(|e| invalid_data(e.to_string()))?;
+
+    let destination = destination.as_ref().to_path_buf();
+    if let Some(parent) = destination.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(io_to_rusqlite_err)?;
+        }
+    }
+    let tmp = destination.with_extension("tmp");
+    let result = std::fs::write(&tmp, raw)
+        .and_then(|_| std::fs::rename(&tmp, &destination))
+        .map_err(io_to_rusqlite_err);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result?;
+    Ok(ProfileExportReport {
+        path: destination,
+        manifest,
+    })
+}
+
+/// 读取并解密资料包；口令错误与文件损坏统一报告，不区分。
+fn read_profile(bundle_path: &Path, passphrase: &str) -> Result<ProfilePayload> {
+    let raw = std::fs::read(bundle_path).map_err(io_to_rusqlite_err)?;
+    let file: ProfileFile =
+        serde_json::from_slice(&raw).map_err(|_| invalid_data("不是有效的资料包文件"))?;
+    if file.format != PROFILE_FORMAT {
+        return Err(invalid_data("不是有效的资料包文件"));
+    }
+    if file.format_version > PROFILE_FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "资料包版本 {} 高于当前支持的 {}，请先升级应用",
+            file.format_version, PROFILE_FORMAT_VERSION
+        )));
+    }
+    if file.kdf != PROFILE_KDF || !(1..=PROFILE_KDF_MAX_ITERATIONS).contains(&file.iterations) {
+        return Err(invalid_data("资料包密钥参数不受支持"));
+    }
+    let salt = general_purpose::STANDARD
+        .decode(file.salt.as_bytes())
+        .map_err(|_| invalid_data("不是有效的资料包文件"))?;
+    let key = crate::secret_envelope::derive_passphrase_key(passphrase, &salt, file.iterations)
+        .map_err(|_| invalid_data("口令不能为空"))?;
+    let plaintext = crate::secret_envelope::decrypt_bytes(&key, &file.payload)
+        .map_err(|_| invalid_data("口令错误或资料包已损坏"))?;
+    let payload: ProfilePayload =
+        serde_json::from_slice(&plaintext).map_err(|e| invalid_data(e.to_string()))?;
+    if payload.manifest.format_version != file.format_version {
+        return Err(invalid_data("资料包 manifest 与文件头版本不一致"));
+    }
+    Ok(payload)
+}
+
+fn bump(counter: &mut BTreeMap<&'static str, usize>, section: &'static str) {
+    *counter.entry(section).or_default() += 1;
+}
+
+/// 把资料包合并进本地：只新增、不覆盖，内容不同的条目保留本地值并列入冲突。
+///
+/// `current_settings` 为前端当前设置，合并结果通过 `merged_settings` 返回；
+/// `dry_run` 为 true 时在事务内计算后回滚，可用于导入前预览。
+pub fn import_profile<P: AsRef<Path>, Q: AsRef<Path>>(
+    db_path: P,
+    bundle_path: Q,
+    passphrase: &str,
+    current_settings: &Map<String, Value>,
+    dry_run: bool,
+) -> Result<ProfileImportReport> {
+    let payload = read_profile(bundle_path.as_ref(), passphrase)?;
+    let mut added = BTreeMap::new();
+    let mut unchanged = BTreeMap::new();
+    let mut conflicts = Vec::new();
+
+    let mut conn = open_connection(db_path)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    for course in &payload.custom_schedule_courses {
+        let local = tx
+            .query_row(
+                "SELECT id, student_id, semester, name, teacher, room, weekday, period, djs, weeks_json, color, created_at, updated_at
+                 FROM custom_schedule_courses WHERE id = ?1",
+                params![course.id],
+                map_custom_schedule_course_row,
+            )
+            .optional()?;
+        match local {
+            Some(local) if same_course(&local, course) => {
+                bump(&mut unchanged, SECTION_CUSTOM_COURSES)
+            }
+            Some(_) => conflicts.push(ProfileConflict {
+                section: SECTION_CUSTOM_COURSES,
+                key: course.id.clone(),
+            }),
+            None => {
+                let weeks_json =
+                    serde_json::to_string(&course.weeks).unwrap_or_else(|_| "[]".to_string());
+                tx.execute(
+                    "INSERT INTO custom_schedule_courses (
+                        id, student_id, semester, name, teacher, room, weekday, period, djs,
+                        weeks_json, color, created_at, updated_at
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    params![
+                        course.id,
+                        course.student_id,
+                        course.semester,
+                        course.name,
+                        course.teacher,
+                        course.room,
+                        course.weekday,
+                        course.period,
+                        course.djs,
+                        weeks_json,
+                        course.color,
+                        course.created_at,
+                        course.updated_at
+                    ],
+                )?;
+                bump(&mut added, SECTION_CUSTOM_COURSES);
+            }
+        }
+    }
+
+    for (key, value) in &payload.kv {
+        // 防御性过滤：即使文件被改写也只接受可迁移的键
+        if !is_portable_kv_key(key) {
+            continue;
+        }
+        let local: Option<String> = tx
+            .query_row(
+                "SELECT value FROM kv_store WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match local {
+            Some(local) if &local == value => bump(&mut unchanged, SECTION_KV),
+            Some(_) => conflicts.push(ProfileConflict {
+                section: SECTION_KV,
+                key: key.clone(),
+            }),
+            None => {
+                tx.execute(
+                    "INSERT INTO kv_store (key, value) VALUES (?1, ?2)",
+                    params![key, value],
+                )?;
+                bump(&mut added, SECTION_KV);
+            }
+        }
+    }
+
+    let mut merged_settings = current_settings.clone();
+    for (key, value) in &payload.settings {
+        match current_settings.get(key) {
+            Some(local) if local == value => bump(&mut unchanged, SECTION_SETTINGS),
+            Some(_) => conflicts.push(ProfileConflict {
+                section: SECTION_SETTINGS,
+                key: key.clone(),
+            }),
+            None => {
+                merged_settings.insert(key.clone(), value.clone());
+                bump(&mut added, SECTION_SETTINGS);
+            }
+        }
+    }
+
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+    }
+    Ok(ProfileImportReport {
+        manifest: payload.manifest,
+        dry_run,
+        added,
+        unchanged,
+        conflicts,
+        merged_settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_root(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_profile_{label}_{nanos}"))
+    }
+
+    fn add_course(db: &Path, id: &str, name: &str) {
+        open_connection(db)
+            .unwrap()
+            .execute(
+                "INSERT OR REPLACE INTO custom_schedule_courses
+                    (id, student_id, semester, name, weekday, period, djs, weeks_json)
+                 VALUES (?1, '2510231001', '2025-2026-1', ?2, 1, 1, 2, '[1]')",
+                params![id, name],
+            )
+            .unwrap();
+    }
+
+    fn settings(pairs: &[(&str, Value)]) -> Map<String, Value> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    /// 导出排除凭据与设备相关键；导入只补缺、相同跳过、不同保留本地并报告冲突。
+    #[test]
+    fn export_excludes_secrets_and_import_merges_with_conflicts() {
+        use super::super::repositories::kv::{get_kv, set_kv};
+
+        let root = temp_root("merge");
+        std::fs::create_dir_all(&root).unwrap();
+        let old_db = root.join("old.db");
+        let new_db = root.join("new.db");
+        super::super::migrations::init_db(&old_db).expect("init old");
+        super::super::migrations::init_db(&new_db).expect("init new");
+
+        add_course(&old_db, "c1", "高数");
+        add_course(&old_db, "c2", "线代");
+        set_kv(&old_db, "electricity.room.101", "{\"id\":\"101\"}").unwrap();
+        set_kv(&old_db, "exam.reminder_leads", "[60]").unwrap();
+        set_kv(&old_db, "account.2510231001", "{}").unwrap();
+        set_kv(&old_db, "ics_calendar.feed.abc", "{}").unwrap();
+        open_connection(&old_db)
+            .unwrap()
+            .execute(
+                "INSERT INTO user_sessions (student_id, cookies)
+                 VALUES ('2510231001', 'JSESSIONID=secret')",
+                [],
+            )
+            .unwrap();
+
+        let bundle = root.join("profile.mhbprofile");
+        let exported = export_profile(
+            &old_db,
+            &bundle,
+            "correct horse",
+            &settings(&[
+                ("usage_stats_enabled", Value::Bool(true)),
+                ("theme", "dark".into()),
+            ]),
+        )
+        .expect("export");
+        assert_eq!(exported.manifest.sections[SECTION_CUSTOM_COURSES], 2);
+        assert_eq!(exported.manifest.sections[SECTION_KV], 2);
+        let raw = std::fs::read_to_string(&bundle).unwrap();
+        assert!(!raw.contains("JSESSIONID") && !raw.contains("高数"));
+        assert!(
+            export_profile(&old_db, root.join("weak.mhbprofile"), "short", &Map::new()).is_err()
+        );
+        assert!(import_profile(&new_db, &bundle, "wrong passphrase", &Map::new(), false).is_err());
+
+        // 新设备已有一门同 id 但内容不同的课程与一个不同的主题设置
+        add_course(&new_db, "c1", "高等数学");
+        set_kv(&new_db, "exam.reminder_leads", "[60]").unwrap();
+        let current = settings(&[("theme", "light".into())]);
+
+        let preview =
+            import_profile(&new_db, &bundle, "correct horse", &current, true).expect("dry run");
+        assert_eq!(preview.added.get(SECTION_CUSTOM_COURSES), Some(&1));
+        assert!(get_kv(&new_db, "electricity.room.101").unwrap().is_none());
+
+        let report =
+            import_profile(&new_db, &bundle, "correct horse", &current, false).expect("import");
+        assert_eq!(report.added.get(SECTION_CUSTOM_COURSES), Some(&1));
+        assert_eq!(report.added.get(SECTION_KV), Some(&1));
+        assert_eq!(report.unchanged.get(SECTION_KV), Some(&1));
+        assert_eq!(
+            report.conflicts,
+            vec![
+                ProfileConflict {
+                    section: SECTION_CUSTOM_COURSES,
+                    key: "c1".to_string()
+                },
+                ProfileConflict {
+                    section: SECTION_SETTINGS,
+                    key: "theme".to_string()
+                },
+            ]
+        );
+        assert_eq!(report.merged_settings["theme"], "light");
+        assert_eq!(report.merged_settings["usage_stats_enabled"], true);
+
+        let conn = open_connection(&new_db).unwrap();
+        let c1: String = conn
+            .query_row(
+                "SELECT name FROM custom_schedule_courses WHERE id = 'c1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(c1, "高等数学");
+        let sessions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM user_sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(sessions, 0);
+        assert!(get_kv(&new_db, "account.2510231001").unwrap().is_none());
+        assert!(get_kv(&new_db, "electricity.room.101").unwrap().is_some());
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,188 @@
+//! Bridge API 令牌仓储（bridge_api_tokens）：用户签发的限定权限令牌。
+//!
+//! 只保存令牌 SHA-256 摘要（`token_hash`），明文仅在签发时返回一次；
+//! `scopes` 为空格分隔的权限名，时间字段均为 Unix 秒。
+
+use rusqlite::{params, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::super::connection::open_connection;
+
+/// 单个令牌记录（不含明文）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeApiTokenRecord {
+    pub id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub revoked_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+const SELECT_COLUMNS: &str =
+    "id, name, token_hash, scopes, created_at, expires_at, revoked_at, last_used_at";
+
+/// 写入新令牌。
+pub fn insert_bridge_api_token<P: AsRef<Path>>(
+    path: P,
+    record: &BridgeApiTokenRecord,
+) -> Result<()> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "INSERT INTO bridge_api_tokens
+         (id, name, token_hash, scopes, created_at, expires_at, revoked_at, last_used_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            record.id,
+            record.name,
+            record.token_hash,
+            record.scopes,
+            record.created_at,
+            record.expires_at,
+            record.revoked_at,
+            record.last_used_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// 列出全部令牌（含已过期 / 已吊销），按签发时间倒序。
+pub fn list_bridge_api_tokens<P: AsRef<Path>>(path: P) -> Result<Vec<BridgeApiTokenRecord>> {
+    let conn = open_connection(path)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {SELECT_COLUMNS} FROM bridge_api_tokens ORDER BY created_at DESC, id ASC"
+    ))?;
+    let rows = stmt.query_map([], row_to_token)?;
+    rows.collect()
+}
+
+/// 按令牌摘要查找。
+pub fn find_bridge_api_token_by_hash<P: AsRef<Path>>(
+    path: P,
+    token_hash: &str,
+) -> Result<Option<BridgeApiTokenRecord>> {
+    let conn = open_connection(path)?;
+    conn.query_row(
+        &format!("SELECT {SELECT_COLUMNS} FROM bridge_api_tokens WHERE token_hash = ?1"),
+        params![token_hash],
+        row_to_token,
+    )
+    .optional()
+}
+
+/// 吊销令牌（已吊销的保持原吊销时间），返回受影响行数。
+pub fn revoke_bridge_api_token<P: AsRef<Path>>(path: P, id: &str, at: i64) -> Result<usize> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "UPDATE bridge_api_tokens SET revoked_at = ?2 WHERE id = ?1 AND revoked_at IS NULL",
+        params![id, at],
+    )
+}
+
+/// 删除令牌记录，返回删除行数。
+pub fn delete_bridge_api_token<P: AsRef<Path>>(path: P, id: &str) -> Result<usize> {
+    let conn = open_connection(path)?;
+    conn.execute("DELETE FROM bridge_api_tokens WHERE id = ?1", params![id])
+}
+
+/// 记录最近使用时间；距上次记录不足 `min_interval` 秒时跳过写入，避免每个请求都写库。
+pub fn touch_bridge_api_token<P: AsRef<Path>>(
+    path: P,
+    id: &str,
+    at: i64,
+    min_interval: i64,
+) -> Result<usize> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "UPDATE bridge_api_tokens SET last_used_at = ?2
+         WHERE id = ?1 AND (last_used_at IS NULL OR last_used_at <= ?2 - ?3)",
+        params![id, at, min_interval],
+    )
+}
+
+fn row_to_token(row: &rusqlite::Row<'_>) -> Result<BridgeApiTokenRecord> {
+    Ok(BridgeApiTokenRecord {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        token_hash: row.get(2)?,
+        scopes: row.get(3)?,
+        created_at: row.get(4)?,
+        expires_at: row.get(5)?,
+        revoked_at: row.get(6)?,
+        last_used_at: row.get(7)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db_impl::migrations::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_bridge_token_{label}_{nanos}.db"))
+    }
+
+    fn token(id: &str, created_at: i64) -> BridgeApiTokenRecord {
+        BridgeApiTokenRecord {
+            id: id.to_string(),
+            name: format!("script-{id}"),
+            token_hash: format!("hash-{id}"),
+            scopes: "grades:read".to_string(),
+            created_at,
+            expires_at: created_at + 3600,
+            revoked_at: None,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn tokens_are_listed_revoked_once_and_touched_with_throttle() {
+        let path = temp_db_path("lifecycle");
+        init_db(&path).unwrap();
+        insert_bridge_api_token(&path, &token("a", 100)).unwrap();
+        insert_bridge_api_token(&path, &token("b", 200)).unwrap();
+        // 摘要唯一
+        assert!(insert_bridge_api_token(&path, &token("b", 300)).is_err());
+
+        let listed = list_bridge_api_tokens(&path).unwrap();
+        assert_eq!(
+            listed.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(),
+            vec!["b", "a"]
+        );
+        assert_eq!(
+            find_bridge_api_token_by_hash(&path, "hash-a").unwrap(),
+            Some(token("a", 100))
+        );
+
+        assert_eq!(revoke_bridge_api_token(&path, "a", 500).unwrap(), 1);
+        assert_eq!(revoke_bridge_api_token(&path, "a", 900).unwrap(), 0);
+        let revoked = find_bridge_api_token_by_hash(&path, "hash-a")
+            .unwrap()
+            .unwrap();
+        assert_eq!(revoked.revoked_at, Some(500));
+
+        assert_eq!(touch_bridge_api_token(&path, "b", 1000, 60).unwrap(), 1);
+        assert_eq!(touch_bridge_api_token(&path, "b", 1030, 60).unwrap(), 0);
+        assert_eq!(touch_bridge_api_token(&path, "b", 1060, 60).unwrap(), 1);
+        let touched = find_bridge_api_token_by_hash(&path, "hash-b")
+            .unwrap()
+            .unwrap();
+        assert_eq!(touched.last_used_at, Some(1060));
+
+        assert_eq!(delete_bridge_api_token(&path, "b").unwrap(), 1);
+        assert!(find_bridge_api_token_by_hash(&path, "hash-b")
+            .unwrap()
+            .is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -77,7 +77,40 @@ pub fn add_custom_schedule_course<P: AsRef<Path>>(
     Ok(())
 }
 
-fn map_custom_schedule_course_row(
+/// 批量写入（同一事务，任一失败整体回滚），返回写入条数。
+pub fn add_custom_schedule_courses<P: AsRef<Path>>(
+    path: P,
+    courses: &[CustomScheduleCourseRecord],
+) -> Result<usize> {
+    let conn = open_connection(path)?;
+    let tx = conn.unchecked_transaction()?;
+    for course in courses {
+        let weeks_json = serde_json::to_string(&course.weeks).unwrap_or_else(|_| "[]".to_string());
+        let color = normalize_course_color(Some(course.color.as_str())).unwrap_or_default();
+        tx.execute(
+            "INSERT OR REPLACE INTO custom_schedule_courses (
+                id, student_id, semester, name, teacher, room, weekday, period, djs, weeks_json, color, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, CURRENT_TIMESTAMP)",
+            params![
+                course.id,
+                course.student_id,
+                course.semester,
+                course.name,
+                course.teacher,
+                course.room,
+                course.weekday,
+                course.period,
+                course.djs,
+                weeks_json,
+                color
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(courses.len())
+}
+
+pub(crate) fn map_custom_schedule_course_row(
     row: &rusqlite::Row<'_>,
 ) -> rusqlite::Result<CustomScheduleCourseRecord> {
     let weeks_json: String = row.get(9)?;
@@ -318,4 +351,40 @@ mod tests {
 
         let _ = std::fs::remove_file(&path);
     }
+
+    #[test]
+    fn batch_insert_commits_all_records() {
+        let path = temp_db_path("batch");
+        let _ = std::fs::remove_file(&path);
+        init_db(&path).expect("init");
+
+        let now = "2026-01-01T00:00:00+08:00".to_string();
+        let records = (1..=3)
+            .map(|i| CustomScheduleCourseRecord {
+                id: format!("c_batch_{i}"),
+                student_id: "2510231000".to_string(),
+                semester: "2025-2026-1".to_string(),
+                name: format!("社团活动{i}"),
+                teacher: String::new(),
+                room: "活动中心".to_string(),
+                weekday: i,
+                period: 9,
+                djs: 2,
+                weeks: vec![1, 2],
+                color: String::new(),
+                created_at: now.clone(),
+                updated_at: now.clone(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            add_custom_schedule_courses(&path, &records).expect("batch"),
+            3
+        );
+        let listed =
+            list_custom_schedule_courses(&path, "2510231000", "2025-2026-1").expect("list");
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[0].name, "社团活动1");
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,160 @@
+//! 电费余额读数仓储（electricity_readings）：按关注房间记录的余额时间序列。
+//!
+//! `room_id` 由查询位置派生（见 `modules::electricity::room_id`），与学号无关；
+//! `recorded_at` 为 Unix 秒。
+
+use rusqlite::{params, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::super::connection::open_connection;
+
+/// 单条余额读数。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElectricityReadingRecord {
+    pub room_id: String,
+    pub recorded_at: i64,
+    /// 账户余额（元）
+    pub balance: f64,
+    /// 剩余电量（度）
+    pub quantity: f64,
+}
+
+/// 写入读数；同一房间同一时刻重复写入时覆盖。
+pub fn insert_electricity_reading<P: AsRef<Path>>(
+    path: P,
+    record: &ElectricityReadingRecord,
+) -> Result<()> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO electricity_readings (room_id, recorded_at, balance, quantity)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            record.room_id,
+            record.recorded_at,
+            record.balance,
+            record.quantity
+        ],
+    )?;
+    Ok(())
+}
+
+/// 列出 `since`（含）之后的读数，按时间升序。
+pub fn list_electricity_readings<P: AsRef<Path>>(
+    path: P,
+    room_id: &str,
+    since: i64,
+) -> Result<Vec<ElectricityReadingRecord>> {
+    let conn = open_connection(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT room_id, recorded_at, balance, quantity FROM electricity_readings
+         WHERE room_id = ?1 AND recorded_at >= ?2
+         ORDER BY recorded_at ASC",
+    )?;
+    let rows = stmt.query_map(params![room_id, since], row_to_reading)?;
+    rows.collect()
+}
+
+/// 最近一条读数。
+pub fn latest_electricity_reading<P: AsRef<Path>>(
+    path: P,
+    room_id: &str,
+) -> Result<Option<ElectricityReadingRecord>> {
+    let conn = open_connection(path)?;
+    conn.query_row(
+        "SELECT room_id, recorded_at, balance, quantity FROM electricity_readings
+         WHERE room_id = ?1
+         ORDER BY recorded_at DESC LIMIT 1",
+        params![room_id],
+        row_to_reading,
+    )
+    .optional()
+}
+
+/// 删除 `before` 之前的读数（保留期清理），返回删除行数。
+pub fn prune_electricity_readings<P: AsRef<Path>>(
+    path: P,
+    room_id: &str,
+    before: i64,
+) -> Result<usize> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "DELETE FROM electricity_readings WHERE room_id = ?1 AND recorded_at < ?2",
+        params![room_id, before],
+    )
+}
+
+/// 删除房间的全部读数（取消关注时调用）。
+pub fn delete_electricity_readings<P: AsRef<Path>>(path: P, room_id: &str) -> Result<usize> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "DELETE FROM electricity_readings WHERE room_id = ?1",
+        params![room_id],
+    )
+}
+
+fn row_to_reading(row: &rusqlite::Row<'_>) -> Result<ElectricityReadingRecord> {
+    Ok(ElectricityReadingRecord {
+        room_id: row.get(0)?,
+        recorded_at: row.get(1)?,
+        balance: row.get(2)?,
+        quantity: row.get(3)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db_impl::migrations::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_electricity_{label}_{nanos}.db"))
+    }
+
+    fn reading(room_id: &str, recorded_at: i64, balance: f64) -> ElectricityReadingRecord {
+        ElectricityReadingRecord {
+            room_id: room_id.to_string(),
+            recorded_at,
+            balance,
+            quantity: balance * 2.0,
+        }
+    }
+
+    #[test]
+    fn readings_are_listed_in_order_and_pruned_per_room() {
+        let path = temp_db_path("readings");
+        init_db(&path).unwrap();
+        for record in [
+            reading("r1", 300, 40.0),
+            reading("r1", 100, 50.0),
+            reading("r1", 200, 45.0),
+            reading("r2", 150, 9.0),
+        ] {
+            insert_electricity_reading(&path, &record).unwrap();
+        }
+
+        let listed = list_electricity_readings(&path, "r1", 150).unwrap();
+        assert_eq!(
+            listed.iter().map(|r| r.recorded_at).collect::<Vec<_>>(),
+            vec![200, 300]
+        );
+        assert_eq!(
+            latest_electricity_reading(&path, "r1").unwrap(),
+            Some(reading("r1", 300, 40.0))
+        );
+
+        assert_eq!(prune_electricity_readings(&path, "r1", 250).unwrap(), 2);
+        assert_eq!(delete_electricity_readings(&path, "r1").unwrap(), 1);
+        assert_eq!(latest_electricity_reading(&path, "r1").unwrap(), None);
+        // 其他房间不受影响
+        assert!(latest_electricity_reading(&path, "r2").unwrap().is_some());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
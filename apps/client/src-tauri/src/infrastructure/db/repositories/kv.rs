@@ -0,0 +1,89 @@
+//! 通用键值仓储（kv_store）：小型 JSON 配置/状态的读写。
+//!
+//! key 约定使用 `<模块>.<用途>[.<子键>]` 点分命名，避免不同模块互相覆盖。
+
+use rusqlite::{params, OptionalExtension, Result};
+use std::path::Path;
+
+use super::super::connection::open_connection;
+
+/// 读取单个键；不存在时返回 `None`。
+pub fn get_kv<P: AsRef<Path>>(path: P, key: &str) -> Result<Option<String>> {
+    let conn = open_connection(path)?;
+    conn.query_row(
+        "SELECT value FROM kv_store WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// 写入（覆盖）单个键。
+pub fn set_kv<P: AsRef<Path>>(path: P, key: &str, value: &str) -> Result<()> {
+    let conn = open_connection(path)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO kv_store (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// 删除单个键，返回删除行数。
+pub fn delete_kv<P: AsRef<Path>>(path: P, key: &str) -> Result<usize> {
+    let conn = open_connection(path)?;
+    conn.execute("DELETE FROM kv_store WHERE key = ?1", params![key])
+}
+
+/// 按前缀列出键值对（按 key 升序）。
+pub fn list_kv_by_prefix<P: AsRef<Path>>(path: P, prefix: &str) -> Result<Vec<(String, String)>> {
+    let conn = open_connection(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT key, value FROM kv_store
+         WHERE substr(key, 1, length(?1)) = ?1
+         ORDER BY key ASC",
+    )?;
+    let rows = stmt.query_map(params![prefix], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db_impl::migrations::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_kv_{label}_{nanos}.db"))
+    }
+
+    #[test]
+    fn kv_roundtrip_overwrite_and_prefix_listing() {
+        let path = temp_db_path("roundtrip");
+        init_db(&path).unwrap();
+
+        assert_eq!(get_kv(&path, "scheduler.job.grades").unwrap(), None);
+        set_kv(&path, "scheduler.job.grades", "{\"enabled\":true}").unwrap();
+        set_kv(&path, "scheduler.job.grades", "{\"enabled\":false}").unwrap();
+        set_kv(&path, "scheduler.job.exams", "{}").unwrap();
+        // 前缀按字面匹配（不走 LIKE），相近 key 不应被列出
+        set_kv(&path, "schedulerXjob.other", "{}").unwrap();
+
+        assert_eq!(
+            get_kv(&path, "scheduler.job.grades").unwrap().as_deref(),
+            Some("{\"enabled\":false}")
+        );
+        let listed = list_kv_by_prefix(&path, "scheduler.job.").unwrap();
+        let keys = listed.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["scheduler.job.exams", "scheduler.job.grades"]);
+
+        assert_eq!(delete_kv(&path, "scheduler.job.exams").unwrap(), 1);
+        assert_eq!(get_kv(&path, "scheduler.job.exams").unwrap(), None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
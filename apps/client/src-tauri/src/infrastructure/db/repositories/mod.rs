@@ -1,14 +1,21 @@
 //! 业务仓储：按业务域组织 user_sessions / auth_cookie_v2 /
-//! custom_schedule_courses / online_learning / chaoxing_checkin_log 的读写。
+//! custom_schedule_courses / online_learning / chaoxing_checkin_log / electricity_readings /
+//! bridge_api_tokens / kv_store 的读写。
 
 pub mod auth_cookie;
+pub mod bridge_token;
 pub mod chaoxing;
 pub mod custom_schedule;
+pub mod electricity;
+pub mod kv;
 pub mod online_learning;
 pub mod session;
 
 pub use auth_cookie::*;
+pub use bridge_token::*;
 pub use chaoxing::*;
 pub use custom_schedule::*;
+pub use electricity::*;
+pub use kv::*;
 pub use online_learning::*;
 pub use session::*;
@@ -275,6 +275,16 @@ pub fn update_user_session_cookies_only<P: AsRef<Path>>(
     Ok(())
 }
 
+/// 删除学号的会话行（多账号移除账号时调用），返回删除行数。
+pub fn delete_user_session<P: AsRef<Path>>(path: P, student_id: &str) -> Result<usize> {
+    let conn = open_connection(path)?;
+    ensure_user_session_columns(&conn)?;
+    conn.execute(
+        "DELETE FROM user_sessions WHERE student_id = ?1",
+        params![student_id.trim()],
+    )
+}
+
 /// 异步读取用户会话。
 pub async fn get_user_session_async<P>(
     path: P,
@@ -0,0 +1,510 @@
+//! 备份恢复预览与按表选择性恢复。
+//!
+//! - 预览：只读打开备份，逐表按主键比较备份与正式库，报告新增 / 缺失 / 变更的行；
+//! - 恢复：只允许 [`RESTORABLE_TABLES`] 中的用户数据表（不含会话、Cookie 等凭据表），
+//!   先把正式库安全备份到 `backup/pre-restore/`，再在单个 IMMEDIATE 事务内整表替换；
+//! - kv_store 只预览 / 替换 [`PROFILE_KV_PREFIXES`] 中的设置类键，账号注册表、订阅令牌摘要、
+//!   保活配置、缓存学期等设备相关键保持正式库现状；
+//! - 备份来自旧版本时只恢复两边都有的列，新列使用表定义的默认值。
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection, OpenFlags, Result, TransactionBehavior};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::backup::{
+    backup_database, default_backup_dir, verify_backup, BACKUP_KEEP_DEFAULT,
+    PRE_RESTORE_BACKUP_SUBDIR,
+};
+use super::connection::{io_to_rusqlite_err, open_connection};
+use super::profile::PROFILE_KV_PREFIXES;
+
+/// 可预览 / 可按表恢复的用户数据表。
+pub const RESTORABLE_TABLES: &[&str] = &[
+    "custom_schedule_courses",
+    "chaoxing_checkin_log",
+    "grades_cache",
+    "grade_teacher_cache",
+    "grade_snapshot_cache",
+    "exam_snapshot_cache",
+    "electricity_readings",
+    "kv_store",
+];
+
+/// 只按键前缀部分恢复的表：(表, 键列, 允许的前缀)。
+const PARTIAL_TABLES: &[(&str, &str, &[&str])] = &[("kv_store", "key", PROFILE_KV_PREFIXES)];
+
+/// 每张表在预览中最多列出的差异行数。
+const PREVIEW_ROW_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowChange {
+    /// 只在备份中存在（恢复后会出现）
+    Added,
+    /// 只在正式库中存在（恢复后会消失）
+    Removed,
+    /// 主键相同但内容不同（恢复后回到备份中的值）
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RowDiff {
+    pub change: RowChange,
+    /// 主键列 → 值
+    pub key: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableDiff {
+    pub table: &'static str,
+    /// 备份中是否有这张表（旧版本备份可能缺表，缺表时不可恢复）
+    pub in_backup: bool,
+    pub live_rows: usize,
+    pub backup_rows: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    /// 差异行（最多 `PREVIEW_ROW_LIMIT` 条）
+    pub rows: Vec<RowDiff>,
+}
+
+impl TableDiff {
+    pub fn is_identical(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.changed == 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestorePreview {
+    pub backup_path: PathBuf,
+    pub tables: Vec<TableDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableRestoreReport {
+    /// 恢复前正式库的安全备份
+    pub safety_backup: PathBuf,
+    /// 表名 → 恢复后的行数
+    pub restored: BTreeMap<String, usize>,
+}
+
+fn invalid_table(table: &str) -> rusqlite::Error {
+    rusqlite::Error::InvalidParameterName(format!("不支持恢复的表: {table}"))
+}
+
+fn open_backup_read_only(backup_path: &Path) -> Result<Connection> {
+    if !backup_path.is_file() {
+        return Err(io_to_rusqlite_err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "备份文件不存在",
+        )));
+    }
+    verify_backup(backup_path)?;
+    Connection::open_with_flags(backup_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+}
+
+/// 表的列名与主键列（按主键序）；表不存在时返回 `None`。
+fn table_layout(conn: &Connection, table: &str) -> Result<Option<(Vec<String>, Vec<String>)>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let mut columns = Vec::new();
+    let mut keys = Vec::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        let pk: i64 = row.get(5)?;
+        if pk > 0 {
+            keys.push((pk, name.clone()));
+        }
+        columns.push(name);
+    }
+    if columns.is_empty() {
+        return Ok(None);
+    }
+    keys.sort();
+    Ok(Some((
+        columns,
+        keys.into_iter().map(|(_, name)| name).collect(),
+    )))
+}
+
+fn read_rows(conn: &Connection, table: &str, columns: &[String]) -> Result<Vec<Vec<SqlValue>>> {
+    let list = columns
+        .iter()
+        .map(|c| format!("\"{c}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = conn.prepare(&format!("SELECT {list} FROM {table}"))?;
+    let rows = stmt.query_map([], |row| {
+        (0..columns.len())
+            .map(|i| row.get::<_, SqlValue>(i))
+            .collect::<Result<Vec<_>>>()
+    })?;
+    rows.collect()
+}
+
+fn row_scope(table: &str) -> Option<(&'static str, &'static [&'static str])> {
+    PARTIAL_TABLES
+        .iter()
+        .find(|(name, _, _)| *name == table)
+        .map(|(_, key, prefixes)| (*key, *prefixes))
+}
+
+/// 参与预览 / 恢复的行：部分恢复的表只保留键命中前缀的行。
+fn read_scoped_rows(
+    conn: &Connection,
+    table: &str,
+    columns: &[String],
+) -> Result<Vec<Vec<SqlValue>>> {
+    let mut rows = read_rows(conn, table, columns)?;
+    if let Some((key, prefixes)) = row_scope(table) {
+        let index = columns.iter().position(|c| c == key);
+        rows.retain(|row| match index.map(|i| &row[i]) {
+            Some(SqlValue::Text(value)) => prefixes.iter().any(|p| value.starts_with(p)),
+            _ => false,
+        });
+    }
+    Ok(rows)
+}
+
+fn json_of(value: &SqlValue) -> serde_json::Value {
+    match value {
+        SqlValue::Null => serde_json::Value::Null,
+        SqlValue::Integer(n) => serde_json::json!(n),
+        SqlValue::Real(f) => serde_json::json!(f),
+        SqlValue::Text(s) => serde_json::json!(s),
+        SqlValue::Blob(b) => serde_json::json!(format!("<blob {} bytes>", b.len())),
+    }
+}
+
+/// 比较一张表：列取两边交集，主键缺失（或备份缺主键列）时按整行比较。
+fn diff_table(live: &Connection, backup: &Connection, table: &'static str) -> Result<TableDiff> {
+    let (live_columns, live_keys) = table_layout(live, table)?.unwrap_or_default();
+    let Some((backup_columns, _)) = table_layout(backup, table)? else {
+        return Ok(TableDiff {
+            table,
+            in_backup: false,
+            live_rows: read_scoped_rows(live, table, &live_columns)?.len(),
+            backup_rows: 0,
+            added: 0,
+            removed: 0,
+            changed: 0,
+            rows: Vec::new(),
+        });
+    };
+    let columns: Vec<String> = live_columns
+        .into_iter()
+        .filter(|c| backup_columns.contains(c))
+        .collect();
+    let key_columns: Vec<usize> =
+        if !live_keys.is_empty() && live_keys.iter().all(|k| columns.contains(k)) {
+            live_keys
+                .iter()
+                .filter_map(|k| columns.iter().position(|c| c == k))
+                .collect()
+        } else {
+            (0..columns.len()).collect()
+        };
+
+    let index = |rows: Vec<Vec<SqlValue>>| -> BTreeMap<String, Vec<SqlValue>> {
+        rows.into_iter()
+            .map(|row| {
+                let key = key_columns
+                    .iter()
+                    .map(|&i| format!("{:?}", row[i]))
+                    .collect::<Vec<_>>()
+                    .join("\u{1f}");
+                (key, row)
+            })
+            .collect()
+    };
+    let live_rows = index(read_scoped_rows(live, table, &columns)?);
+    let backup_rows = index(read_scoped_rows(backup, table, &columns)?);
+    let key_json = |row: &[SqlValue]| {
+        serde_json::Value::Object(
+            key_columns
+                .iter()
+                .map(|&i| (columns[i].clone(), json_of(&row[i])))
+                .collect(),
+        )
+    };
+
+    let mut diff = TableDiff {
+        table,
+        in_backup: true,
+        live_rows: live_rows.len(),
+        backup_rows: backup_rows.len(),
+        added: 0,
+        removed: 0,
+        changed: 0,
+        rows: Vec::new(),
+    };
+    let push = |diff: &mut TableDiff, change: RowChange, row: &[SqlValue]| {
+        match change {
+            RowChange::Added => diff.added += 1,
+            RowChange::Removed => diff.removed += 1,
+            RowChange::Changed => diff.changed += 1,
+        }
+        if diff.rows.len() < PREVIEW_ROW_LIMIT {
+            diff.rows.push(RowDiff {
+                change,
+                key: key_json(row),
+            });
+        }
+    };
+    for (key, row) in &backup_rows {
+        match live_rows.get(key) {
+            None => push(&mut diff, RowChange::Added, row),
+            Some(current) if current != row => push(&mut diff, RowChange::Changed, row),
+            Some(_) => {}
+        }
+    }
+    for (key, row) in &live_rows {
+        if !backup_rows.contains_key(key) {
+            push(&mut diff, RowChange::Removed, row);
+        }
+    }
+    Ok(diff)
+}
+
+/// 只读打开备份，逐表报告与正式库的差异（不写任何一方）。
+pub fn preview_restore<P: AsRef<Path>, Q: AsRef<Path>>(
+    db_path: P,
+    backup_path: Q,
+) -> Result<RestorePreview> {
+    let backup_path = backup_path.as_ref();
+    let backup = open_backup_read_only(backup_path)?;
+    let live = open_connection(db_path)?;
+    let tables = RESTORABLE_TABLES
+        .iter()
+        .map(|table| diff_table(&live, &backup, table))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RestorePreview {
+        backup_path: backup_path.to_path_buf(),
+        tables,
+    })
+}
+
+/// 从备份恢复指定表（整表替换）；先安全备份正式库，任一表失败整体回滚。
+pub fn restore_tables<P: AsRef<Path>, Q: AsRef<Path>>(
+    db_path: P,
+    backup_path: Q,
+    tables: &[String],
+) -> Result<TableRestoreReport> {
+    let mut selected: Vec<&'static str> = Vec::new();
+    for table in tables {
+        let table = table.trim();
+        let known = RESTORABLE_TABLES
+            .iter()
+            .copied()
+            .find(|candidate| *candidate == table)
+            .ok_or_else(|| invalid_table(table))?;
+        if !selected.contains(&known) {
+            selected.push(known);
+        }
+    }
+    if selected.is_empty() {
+        return Err(rusqlite::Error::InvalidParameterName(
+            "未选择要恢复的表".to_string(),
+        ));
+    }
+
+    let backup = open_backup_read_only(backup_path.as_ref())?;
+    let mut snapshot = Vec::new();
+    for table in &selected {
+        let (backup_columns, _) =
+            table_layout(&backup, table)?.ok_or_else(|| invalid_table(table))?;
+        snapshot.push((*table, backup_columns));
+    }
+
+    let safety = backup_database(
+        &db_path,
+        default_backup_dir(&db_path).join(PRE_RESTORE_BACKUP_SUBDIR),
+        BACKUP_KEEP_DEFAULT,
+    )?;
+
+    let mut live = open_connection(&db_path)?;
+    let tx = live.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut restored = BTreeMap::new();
+    for (table, backup_columns) in snapshot {
+        let (live_columns, _) = table_layout(&tx, table)?.ok_or_else(|| invalid_table(table))?;
+        let columns: Vec<String> = live_columns
+            .into_iter()
+            .filter(|c| backup_columns.contains(c))
+            .collect();
+        let rows = read_scoped_rows(&backup, table, &columns)?;
+        match row_scope(table) {
+            Some((key, prefixes)) => {
+                let matches = vec![format!("instr(\"{key}\", ?) = 1"); prefixes.len()].join(" OR ");
+                tx.execute(
+                    &format!("DELETE FROM {table} WHERE {matches}"),
+                    params_from_iter(prefixes.iter()),
+                )?;
+            }
+            None => {
+                tx.execute(&format!("DELETE FROM {table}"), [])?;
+            }
+        }
+        let sql = format!(
+            "INSERT INTO {table} ({}) VALUES ({})",
+            columns
+                .iter()
+                .map(|c| format!("\"{c}\""))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        let mut stmt = tx.prepare(&sql)?;
+        for row in &rows {
+            stmt.execute(params_from_iter(row.iter()))?;
+        }
+        restored.insert(table.to_string(), rows.len());
+    }
+    tx.commit()?;
+    Ok(TableRestoreReport {
+        safety_backup: safety.backup_path,
+        restored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_root(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_restore_{label}_{nanos}"))
+    }
+
+    fn add_course(db: &Path, id: &str, name: &str) {
+        open_connection(db)
+            .unwrap()
+            .execute(
+                "INSERT OR REPLACE INTO custom_schedule_courses
+                    (id, student_id, semester, name, weekday, period, djs, weeks_json)
+                 VALUES (?1, '2510231001', '2025-2026-1', ?2, 1, 1, 2, '[1]')",
+                rusqlite::params![id, name],
+            )
+            .unwrap();
+    }
+
+    /// 预览报告新增 / 缺失 / 变更行；按表恢复只替换所选表并留下安全备份。
+    #[test]
+    fn preview_reports_row_changes_and_selective_restore_replaces_tables() {
+        let root = temp_root("preview");
+        let db = root.join("grades.db");
+        std::fs::create_dir_all(&root).unwrap();
+        super::super::migrations::init_db(&db).expect("init");
+        add_course(&db, "c1", "高数");
+        add_course(&db, "c2", "线代");
+        super::super::repositories::kv::set_kv(&db, "theme", "dark").unwrap();
+        let backup = backup_database(&db, root.join("backup"), 5)
+            .expect("backup")
+            .backup_path;
+
+        // 备份之后：改一门、删一门、加一门；kv 也变化
+        add_course(&db, "c1", "高等数学");
+        open_connection(&db)
+            .unwrap()
+            .execute("DELETE FROM custom_schedule_courses WHERE id = 'c2'", [])
+            .unwrap();
+        add_course(&db, "c3", "英语");
+        super::super::repositories::kv::set_kv(&db, "theme", "light").unwrap();
+
+        let preview = preview_restore(&db, &backup).expect("preview");
+        let courses = preview
+            .tables
+            .iter()
+            .find(|t| t.table == "custom_schedule_courses")
+            .unwrap();
+        assert_eq!((courses.added, courses.removed, courses.changed), (1, 1, 1));
+        assert_eq!(courses.rows.len(), 3);
+        assert!(courses
+            .rows
+            .iter()
+            .any(|r| r.change == RowChange::Added && r.key["id"] == "c2"));
+        let checkins = preview
+            .tables
+            .iter()
+            .find(|t| t.table == "chaoxing_checkin_log")
+            .unwrap();
+        assert!(checkins.in_backup && checkins.is_identical());
+
+        let report = restore_tables(&db, &backup, &["custom_schedule_courses".to_string()])
+            .expect("restore");
+        assert_eq!(report.restored.get("custom_schedule_courses"), Some(&2));
+        assert!(report.safety_backup.exists());
+        let names: Vec<String> = {
+            let conn = open_connection(&db).unwrap();
+            let mut stmt = conn
+                .prepare("SELECT name FROM custom_schedule_courses ORDER BY id")
+                .unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect()
+        };
+        assert_eq!(names, vec!["高数", "线代"]);
+        // 未选择的表保持不变
+        assert_eq!(
+            super::super::repositories::kv::get_kv(&db, "theme").unwrap(),
+            Some("light".to_string())
+        );
+
+        // 非白名单表（凭据）拒绝恢复
+        assert!(restore_tables(&db, &backup, &["user_sessions".to_string()]).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    /// kv_store 只恢复设置类键；账号注册表、订阅令牌等保持正式库现状。
+    #[test]
+    fn kv_restore_only_touches_profile_keys() {
+        let root = temp_root("kv");
+        let db = root.join("grades.db");
+        std::fs::create_dir_all(&root).unwrap();
+        super::super::migrations::init_db(&db).expect("init");
+        let kv = |key: &str, value: &str| {
+            super::super::repositories::kv::set_kv(&db, key, value).unwrap();
+        };
+        kv("exam.reminder_leads", "[1440]");
+        kv("account.registry", "old");
+        kv("ics_calendar.feed.revoked", "digest");
+        let backup = backup_database(&db, root.join("backup"), 5)
+            .expect("backup")
+            .backup_path;
+
+        // 备份之后：改提醒设置、切换账号、吊销订阅令牌
+        kv("exam.reminder_leads", "[60]");
+        kv("library.reminder_days", "[3]");
+        kv("account.registry", "new");
+        open_connection(&db)
+            .unwrap()
+            .execute(
+                "DELETE FROM kv_store WHERE key = 'ics_calendar.feed.revoked'",
+                [],
+            )
+            .unwrap();
+
+        let preview = preview_restore(&db, &backup).expect("preview");
+        let table = preview
+            .tables
+            .iter()
+            .find(|t| t.table == "kv_store")
+            .unwrap();
+        assert_eq!((table.added, table.removed, table.changed), (0, 1, 1));
+        assert_eq!(table.live_rows, 2);
+
+        restore_tables(&db, &backup, &["kv_store".to_string()]).expect("restore");
+        let get = |key: &str| super::super::repositories::kv::get_kv(&db, key).unwrap();
+        assert_eq!(get("exam.reminder_leads"), Some("[1440]".to_string()));
+        assert_eq!(get("library.reminder_days"), None);
+        assert_eq!(get("account.registry"), Some("new".to_string()));
+        assert_eq!(get("ics_calendar.feed.revoked"), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -23,6 +23,7 @@ pub mod identity;
 pub mod modules;
 pub mod parser;
 pub mod qxzkb_options;
+pub mod runtime_events;
 pub mod runtime_log;
 pub mod secret_envelope;
 pub mod transport;
@@ -389,6 +390,21 @@ pub fn run() {
             #[cfg(not(debug_assertions))]
             let _ = (restored_any, token_loaded);
 
+            // 后台任务调度器：配置在所有平台可读写；循环只在桌面端运行（任务默认关闭，
+            // 由用户显式开启，避免与前端通知监控重复）。移动端后台由 hbut-background 插件承载。
+            {
+                let scheduler = app.state::<modules::scheduler::JobScheduler>();
+                tauri::async_runtime::block_on(scheduler.load_configs(DB_FILENAME));
+            }
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            modules::scheduler::JobScheduler::start(app.handle().clone());
+            // 校园网保活：仅恢复用户此前启用的守护（桌面端）。
+            #[cfg(desktop)]
+            {
+                let keepalive = app.state::<modules::campus_network::CampusKeepAlive>();
+                tauri::async_runtime::block_on(keepalive.resume(app.handle().clone(), DB_FILENAME));
+            }
+
             // 启动本地 HTTP Bridge 服务；具体平台/构建开关由 http_server 统一判断（#594 bridge feature 关闭时不编译）。
             #[cfg(feature = "bridge")]
             let client = app.state::<AppState>().client.clone();
@@ -398,6 +414,8 @@ pub fn run() {
         })
         .manage(AppState::new(HbutClient::new()))
         .manage(chaoxing_checkin_cmd::CheckinState::new())
+        .manage(modules::scheduler::JobScheduler::new())
+        .manage(modules::campus_network::CampusKeepAlive::new())
         .invoke_handler(tauri::generate_handler![
             transport::tauri::auth::get_login_page,
             transport::tauri::auth::get_captcha,
@@ -433,6 +451,12 @@ pub fn run() {
             // #452：长后台回前台 ensure/respawn loopback HTTP Bridge
             #[cfg(feature = "bridge")]
             http_server::ensure_http_bridge,
+            #[cfg(feature = "bridge")]
+            transport::tauri::system::bridge_token_issue,
+            #[cfg(feature = "bridge")]
+            transport::tauri::system::bridge_token_list,
+            #[cfg(feature = "bridge")]
+            transport::tauri::system::bridge_token_revoke,
             transport::tauri::update::prepare_module_bundle,
             transport::tauri::system::open_file_with_system,
             transport::tauri::update::open_module_bundle_window,
@@ -446,6 +470,10 @@ pub fn run() {
             transport::tauri::notification::cancel_local_notifications_native,
             transport::tauri::notification::get_notification_permission_native,
             transport::tauri::notification::request_notification_permission_native,
+            transport::tauri::scheduler::get_background_jobs,
+            transport::tauri::scheduler::set_background_job_enabled,
+            transport::tauri::scheduler::update_background_job,
+            transport::tauri::scheduler::run_background_job_now,
             transport::tauri::auth::login,
             transport::tauri::auth::portal_qr_init_login,
             transport::tauri::auth::portal_qr_check_status,
@@ -463,6 +491,11 @@ pub fn run() {
             commands::campus_network::campus_network_probe,
             commands::campus_network::campus_network_login,
             commands::campus_network::campus_network_logout,
+            commands::campus_network::campus_network_account_info,
+            commands::campus_network::campus_network_drop_session,
+            commands::campus_network::campus_network_keepalive_start,
+            commands::campus_network::campus_network_keepalive_stop,
+            commands::campus_network::campus_network_keepalive_status,
             transport::tauri::auth::restore_session,
             transport::tauri::auth::restore_latest_session,
             transport::tauri::auth::has_restorable_credentials,
@@ -470,10 +503,15 @@ pub fn run() {
             transport::tauri::auth::set_offline_user_context,
             transport::tauri::auth::get_cookies,
             transport::tauri::auth::refresh_session,
+            transport::tauri::accounts::account_list,
+            transport::tauri::accounts::account_switch,
+            transport::tauri::accounts::account_remove,
             transport::tauri::grades::sync_grades,
             transport::tauri::grades::get_grade_teacher_cache,
             transport::tauri::grades::sync_grade_teachers_current_semester,
             transport::tauri::grades::get_grades_local,
+            transport::tauri::grades::get_grade_analytics,
+            transport::tauri::grades::get_degree_audit,
             transport::tauri::schedule::sync_schedule,
             transport::tauri::schedule::get_schedule_local,
             transport::tauri::schedule::list_custom_schedule_courses,
@@ -482,7 +520,22 @@ pub fn run() {
             transport::tauri::schedule::delete_custom_schedule_course,
             transport::tauri::schedule::update_custom_schedule_course,
             transport::tauri::schedule::export_schedule_calendar,
+            transport::tauri::schedule::export_schedule_ics,
+            transport::tauri::schedule::import_schedule_ics,
+            #[cfg(feature = "bridge")]
+            transport::tauri::schedule::issue_schedule_feed,
+            #[cfg(feature = "bridge")]
+            transport::tauri::schedule::revoke_schedule_feed,
+            transport::tauri::schedule::detect_schedule_conflicts,
+            transport::tauri::schedule::find_schedule_free_slots,
+            transport::tauri::schedule::schedule_plan_list,
+            transport::tauri::schedule::schedule_plan_save,
+            transport::tauri::schedule::schedule_plan_delete,
+            transport::tauri::schedule::schedule_plan_simulate,
+            transport::tauri::schedule::schedule_plan_export_ics,
             transport::tauri::academic::fetch_exams,
+            transport::tauri::academic::get_exam_countdown,
+            transport::tauri::academic::update_exam_reminder_leads,
             transport::tauri::academic::fetch_ranking,
             transport::tauri::academic::fetch_student_info,
             transport::tauri::forum::school_inbox_fetch,
@@ -515,6 +568,9 @@ pub fn run() {
             transport::tauri::course_selection::fetch_course_selection_selected_courses,
             transport::tauri::course_selection::fetch_course_selection_detail_intro,
             transport::tauri::course_selection::fetch_course_selection_detail_teacher,
+            transport::tauri::course_selection::course_selection_watchlist,
+            transport::tauri::course_selection::course_selection_watch_course,
+            transport::tauri::course_selection::course_selection_unwatch_course,
             #[cfg(feature = "mobile-full")]
             transport::tauri::chaoxing::online_learning_overview,
             #[cfg(feature = "mobile-full")]
@@ -560,8 +616,22 @@ pub fn run() {
             transport::tauri::academic::fetch_library_dict,
             transport::tauri::academic::search_library_books,
             transport::tauri::academic::fetch_library_book_detail,
+            transport::tauri::academic::fetch_library_loans,
+            transport::tauri::academic::fetch_library_loan_history,
+            transport::tauri::academic::fetch_library_holds,
+            transport::tauri::academic::fetch_library_fines,
+            transport::tauri::academic::renew_library_loans,
+            transport::tauri::academic::get_library_reminder_days,
+            transport::tauri::academic::update_library_reminder_days,
+            transport::tauri::academic::list_library_saved_searches,
+            transport::tauri::academic::save_library_saved_search,
+            transport::tauri::academic::delete_library_saved_search,
+            transport::tauri::academic::run_library_saved_search,
             transport::tauri::electricity::electricity_query_location,
             transport::tauri::electricity::electricity_query_account,
+            transport::tauri::electricity::electricity_watch_room,
+            transport::tauri::electricity::electricity_unwatch_room,
+            transport::tauri::electricity::electricity_monitor_overview,
             transport::tauri::electricity::refresh_electricity_token,
             transport::tauri::electricity::fetch_transaction_history,
             transport::tauri::electricity::campus_code_fetch_config,
@@ -590,6 +660,12 @@ pub fn run() {
             transport::tauri::widget::write_exam_snapshot,
             transport::tauri::widget::debug_widget_paths,
             transport::tauri::system::backup_database_now,
+            transport::tauri::system::cache_usage,
+            transport::tauri::system::backup_list,
+            transport::tauri::system::backup_restore_preview,
+            transport::tauri::system::backup_restore_tables,
+            transport::tauri::system::profile_export,
+            transport::tauri::system::profile_import,
             chaoxing_checkin_cmd::chaoxing_checkin_list,
             chaoxing_checkin_cmd::chaoxing_checkin_submit_common,
             chaoxing_checkin_cmd::chaoxing_checkin_submit_location,
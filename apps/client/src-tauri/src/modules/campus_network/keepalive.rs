@@ -0,0 +1,605 @@
+//! 校园网保活守护（桌面端）。
+//!
+//! 网关踢掉空闲会话、Wi-Fi 漫游后 captive portal 会重新出现；守护循环按间隔
+//! [`probe_network`]，状态变为 `NeedsAuth` 时用已保存的运营商与密钥环中的校园网密码
+//! （`campus:<学号>`，与前端「记住密码」同一键）重新认证：
+//! - 认证被拒按指数退避（1 分钟起步逐次翻倍，封顶 30 分钟），不会把网关刷成高频登录；
+//! - 掉线 / 恢复 / 重新认证 / 拒绝等事件保留最近 [`HISTORY_LIMIT`] 条，供前端展示；
+//! - 配置持久化在 kv_store（`campus_network.keepalive`），启用状态跨重启保留。
+//!
+//! [`KeepAliveTracker`] 是纯状态机，不依赖网络与 Tauri 运行时；[`CampusKeepAlive`] 是
+//! Tauri managed state，负责循环的启动 / 停止与快照。
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+use super::types::{
+    CampusCarrier, CampusNetworkLoginResult, CampusNetworkProbeResult, CampusNetworkStatus,
+};
+use super::{login_campus_network, probe_network};
+use crate::db;
+
+/// 探测间隔下限（秒）：避免高频打网关。
+pub const MIN_INTERVAL_SECS: u64 = 30;
+/// 探测间隔上限（秒）。
+pub const MAX_INTERVAL_SECS: u64 = 60 * 60;
+pub const DEFAULT_INTERVAL_SECS: u64 = 2 * 60;
+/// 认证被拒后的首次退避。
+pub const REAUTH_BACKOFF_BASE: Duration = Duration::from_secs(60);
+/// 认证退避上限。
+pub const REAUTH_BACKOFF_MAX: Duration = Duration::from_secs(30 * 60);
+/// 事件历史保留条数。
+pub const HISTORY_LIMIT: usize = 50;
+
+const KV_KEY: &str = "campus_network.keepalive";
+/// 与前端 `CAMPUS_CREDENTIAL_PREFIX` 一致的密钥环账户键前缀。
+const CREDENTIAL_PREFIX: &str = "campus:";
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+/// 保活配置（kv_store: `campus_network.keepalive`）；不含密码。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeepAliveConfig {
+    pub student_id: String,
+    pub carrier: CampusCarrier,
+    #[serde(default)]
+    pub gateway_override: Option<String>,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+impl KeepAliveConfig {
+    /// 校验并规范化（去空白、空网关视为未设置）。
+    pub fn validated(mut self) -> Result<Self, String> {
+        self.student_id = self.student_id.trim().to_string();
+        if self.student_id.is_empty() {
+            return Err("请填写校园网账号".to_string());
+        }
+        if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&self.interval_secs) {
+            return Err(format!(
+                "探测间隔需在 {} 到 {} 秒之间",
+                MIN_INTERVAL_SECS, MAX_INTERVAL_SECS
+            ));
+        }
+        self.gateway_override = self
+            .gateway_override
+            .map(|gw| gw.trim().trim_end_matches('/').to_string())
+            .filter(|gw| !gw.is_empty());
+        Ok(self)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// 密钥环中的校园网密码账户键。
+    pub fn credential_key(&self) -> String {
+        format!("{CREDENTIAL_PREFIX}{}", self.student_id)
+    }
+}
+
+/// 读取保活配置；缺失或损坏时返回 `None`。
+pub fn load_config<P: AsRef<Path>>(path: P) -> Option<KeepAliveConfig> {
+    db::get_kv(path, KV_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<KeepAliveConfig>(&raw).ok())
+        .and_then(|config| config.validated().ok())
+}
+
+/// 校验后写入保活配置，返回规范化后的配置。
+pub fn save_config<P: AsRef<Path>>(
+    path: P,
+    config: KeepAliveConfig,
+) -> Result<KeepAliveConfig, String> {
+    let config = config.validated()?;
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    db::set_kv(path, KV_KEY, &raw).map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// 认证被拒的退避：1 分钟起步逐次翻倍，封顶 30 分钟。
+pub fn reauth_backoff(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    REAUTH_BACKOFF_BASE
+        .checked_mul(1u32 << exponent)
+        .map(|delay| delay.min(REAUTH_BACKOFF_MAX))
+        .unwrap_or(REAUTH_BACKOFF_MAX)
+}
+
+/// 保活事件类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeepAliveEventKind {
+    Started,
+    Stopped,
+    /// 探测到 captive portal（会话被踢 / 漫游后需重新认证）
+    Dropped,
+    /// 守护进程重新认证成功
+    Reauthenticated,
+    /// 未经守护认证即恢复（如用户在别处手动登录）
+    Recovered,
+    /// 重新认证失败，进入退避
+    AuthRejected,
+    /// 密钥环中没有可用的校园网密码
+    MissingPassword,
+    /// 探测本身失败（网卡断开等）
+    ProbeFailed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeepAliveEvent {
+    pub kind: KeepAliveEventKind,
+    pub at: String,
+    pub message: Option<String>,
+}
+
+/// 对外暴露的保活状态快照。
+#[derive(Debug, Clone, Serialize)]
+pub struct KeepAliveStatus {
+    pub running: bool,
+    pub config: Option<KeepAliveConfig>,
+    pub network_status: Option<CampusNetworkStatus>,
+    pub last_probe_at: Option<String>,
+    pub last_reauth_at: Option<String>,
+    pub consecutive_failures: u32,
+    pub backoff_until: Option<String>,
+    pub events: Vec<KeepAliveEvent>,
+}
+
+/// 单次探测后的下一步动作。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeepAliveAction {
+    Idle,
+    /// 需要认证；携带探测得到的 eportal query，避免登录时重复探测
+    Reauthenticate {
+        query_string: Option<String>,
+    },
+}
+
+/// 保活状态机：记录网络状态、失败计数、退避截止时间与事件历史。
+#[derive(Debug, Default)]
+pub struct KeepAliveTracker {
+    network_status: Option<CampusNetworkStatus>,
+    last_probe_at: Option<DateTime<Local>>,
+    last_reauth_at: Option<DateTime<Local>>,
+    consecutive_failures: u32,
+    backoff_until: Option<DateTime<Local>>,
+    /// 最近一次掉线时间；恢复后清空
+    dropped_since: Option<DateTime<Local>>,
+    events: VecDeque<KeepAliveEvent>,
+}
+
+impl KeepAliveTracker {
+    pub fn record(
+        &mut self,
+        kind: KeepAliveEventKind,
+        message: Option<String>,
+        now: DateTime<Local>,
+    ) {
+        if self.events.len() >= HISTORY_LIMIT {
+            self.events.pop_front();
+        }
+        self.events.push_back(KeepAliveEvent {
+            kind,
+            at: now.to_rfc3339(),
+            message,
+        });
+    }
+
+    /// 处理一次探测结果，决定是否需要重新认证。
+    pub fn on_probe(
+        &mut self,
+        probe: Result<CampusNetworkProbeResult, String>,
+        now: DateTime<Local>,
+    ) -> KeepAliveAction {
+        self.last_probe_at = Some(now);
+        let previous = self.network_status.clone();
+        let probe = match probe {
+            Ok(probe) => probe,
+            Err(error) => {
+                // 连续失败只记一次，避免断网时刷满历史
+                if previous != Some(CampusNetworkStatus::Error) {
+                    self.record(KeepAliveEventKind::ProbeFailed, Some(error), now);
+                }
+                self.network_status = Some(CampusNetworkStatus::Error);
+                return KeepAliveAction::Idle;
+            }
+        };
+        self.network_status = Some(probe.status.clone());
+        match probe.status {
+            CampusNetworkStatus::Authenticated => {
+                if self.dropped_since.take().is_some() {
+                    self.record(KeepAliveEventKind::Recovered, probe.message, now);
+                }
+                self.consecutive_failures = 0;
+                self.backoff_until = None;
+                KeepAliveAction::Idle
+            }
+            CampusNetworkStatus::NeedsAuth => {
+                if self.dropped_since.is_none() {
+                    self.dropped_since = Some(now);
+                    self.record(KeepAliveEventKind::Dropped, probe.message, now);
+                }
+                if self.backoff_until.is_some_and(|until| now < until) {
+                    KeepAliveAction::Idle
+                } else {
+                    KeepAliveAction::Reauthenticate {
+                        query_string: probe.query_string,
+                    }
+                }
+            }
+            _ => KeepAliveAction::Idle,
+        }
+    }
+
+    /// 处理一次重新认证结果。
+    pub fn on_login(&mut self, result: CampusNetworkLoginResult, now: DateTime<Local>) {
+        if result.success {
+            let message = match result.adapter_used {
+                Some(adapter) => format!("{}（{}）", result.message, adapter),
+                None => result.message,
+            };
+            self.record(KeepAliveEventKind::Reauthenticated, Some(message), now);
+            self.network_status = Some(CampusNetworkStatus::Authenticated);
+            self.last_reauth_at = Some(now);
+            self.dropped_since = None;
+            self.consecutive_failures = 0;
+            self.backoff_until = None;
+        } else {
+            self.fail(KeepAliveEventKind::AuthRejected, result.message, now);
+        }
+    }
+
+    /// 密钥环中没有密码：同样进入退避，等用户重新保存密码。
+    pub fn on_missing_password(&mut self, now: DateTime<Local>) {
+        self.fail(
+            KeepAliveEventKind::MissingPassword,
+            "未找到已保存的校园网密码，请在校园网页面登录并记住密码".to_string(),
+            now,
+        );
+    }
+
+    fn fail(&mut self, kind: KeepAliveEventKind, message: String, now: DateTime<Local>) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let delay = reauth_backoff(self.consecutive_failures);
+        let until = now + ChronoDuration::from_std(delay).unwrap_or(ChronoDuration::minutes(30));
+        self.backoff_until = Some(until);
+        self.record(
+            kind,
+            Some(format!("{message}；{} 秒后重试", delay.as_secs())),
+            now,
+        );
+    }
+
+    /// 重新启动时清除退避，让新配置立即生效。
+    pub fn reset_backoff(&mut self) {
+        self.consecutive_failures = 0;
+        self.backoff_until = None;
+    }
+
+    fn snapshot(&self, running: bool, config: Option<KeepAliveConfig>) -> KeepAliveStatus {
+        KeepAliveStatus {
+            running,
+            config,
+            network_status: self.network_status.clone(),
+            last_probe_at: self.last_probe_at.map(|t| t.to_rfc3339()),
+            last_reauth_at: self.last_reauth_at.map(|t| t.to_rfc3339()),
+            consecutive_failures: self.consecutive_failures,
+            backoff_until: self.backoff_until.map(|t| t.to_rfc3339()),
+            // 新事件在前
+            events: self.events.iter().rev().cloned().collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct KeepAliveInner {
+    config: Option<KeepAliveConfig>,
+    tracker: KeepAliveTracker,
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+/// 校园网保活服务（Tauri managed state）。
+#[derive(Default)]
+pub struct CampusKeepAlive {
+    inner: Mutex<KeepAliveInner>,
+}
+
+impl CampusKeepAlive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 启用并（重新）启动守护循环；配置持久化后立即执行第一次探测。
+    pub async fn start<P: AsRef<Path>>(
+        &self,
+        app: AppHandle,
+        path: P,
+        config: KeepAliveConfig,
+    ) -> Result<KeepAliveStatus, String> {
+        if !cfg!(desktop) {
+            return Err("校园网保活仅支持桌面端".to_string());
+        }
+        let config = KeepAliveConfig {
+            enabled: true,
+            ..config
+        }
+        .validated()?;
+        if crate::credential_store::load_remembered_credential(&config.credential_key()).is_none() {
+            return Err("未找到已保存的校园网密码，请先登录校园网并记住密码".to_string());
+        }
+        let config = save_config(path, config)?;
+        Ok(self.spawn(app, config).await)
+    }
+
+    /// 启动时按持久化配置恢复（仅已启用时）。
+    pub async fn resume<P: AsRef<Path>>(&self, app: AppHandle, path: P) {
+        if let Some(config) = load_config(path).filter(|config| config.enabled) {
+            self.spawn(app, config).await;
+        }
+    }
+
+    /// 停止守护循环并持久化为关闭。
+    pub async fn stop<P: AsRef<Path>>(&self, path: P) -> Result<KeepAliveStatus, String> {
+        let mut inner = self.inner.lock().await;
+        if let Some(config) = inner.config.as_mut() {
+            config.enabled = false;
+            save_config(path, config.clone())?;
+        }
+        if let Some(task) = inner.task.take() {
+            task.abort();
+            inner
+                .tracker
+                .record(KeepAliveEventKind::Stopped, None, Local::now());
+        }
+        Ok(inner.tracker.snapshot(false, inner.config.clone()))
+    }
+
+    pub async fn status<P: AsRef<Path>>(&self, path: P) -> KeepAliveStatus {
+        let inner = self.inner.lock().await;
+        let config = inner.config.clone().or_else(|| load_config(path));
+        inner.tracker.snapshot(inner.task.is_some(), config)
+    }
+
+    async fn spawn(&self, app: AppHandle, config: KeepAliveConfig) -> KeepAliveStatus {
+        let mut inner = self.inner.lock().await;
+        if let Some(task) = inner.task.take() {
+            task.abort();
+        }
+        inner.tracker.reset_backoff();
+        inner.tracker.record(
+            KeepAliveEventKind::Started,
+            Some(format!("每 {} 秒探测一次", config.interval_secs)),
+            Local::now(),
+        );
+        inner.config = Some(config.clone());
+        inner.task = Some(tauri::async_runtime::spawn(async move {
+            crate::runtime_log::log_info("campus-keepalive", "校园网保活已启动");
+            loop {
+                app.state::<CampusKeepAlive>().tick(&config).await;
+                tokio::time::sleep(config.interval()).await;
+            }
+        }));
+        inner.tracker.snapshot(true, inner.config.clone())
+    }
+
+    /// 一次探测 + 必要时重新认证。网络请求期间不持有锁，status 查询不被阻塞。
+    async fn tick(&self, config: &KeepAliveConfig) {
+        let probe = probe_network(config.gateway_override.as_deref()).await;
+        let action = self
+            .inner
+            .lock()
+            .await
+            .tracker
+            .on_probe(probe, Local::now());
+        let KeepAliveAction::Reauthenticate { query_string } = action else {
+            return;
+        };
+
+        let Some(password) =
+            crate::credential_store::load_remembered_credential(&config.credential_key())
+        else {
+            self.inner
+                .lock()
+                .await
+                .tracker
+                .on_missing_password(Local::now());
+            return;
+        };
+        let result = login_campus_network(
+            &config.student_id,
+            &password,
+            config.carrier,
+            config.gateway_override.as_deref(),
+            query_string.as_deref(),
+        )
+        .await;
+        if !result.success {
+            crate::runtime_log::log_warn(
+                "campus-keepalive",
+                format!("校园网重新认证失败: {}", result.message),
+            );
+        }
+        self.inner
+            .lock()
+            .await
+            .tracker
+            .on_login(result, Local::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: u32, second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 2, 8, minute, second)
+            .single()
+            .unwrap()
+    }
+
+    fn probe(status: CampusNetworkStatus) -> Result<CampusNetworkProbeResult, String> {
+        Ok(CampusNetworkProbeResult {
+            query_string: (status == CampusNetworkStatus::NeedsAuth)
+                .then(|| "wlanuserip=10.0.0.2".to_string()),
+            status,
+            gateway: None,
+            client_ip: None,
+            message: None,
+        })
+    }
+
+    fn login(success: bool) -> CampusNetworkLoginResult {
+        CampusNetworkLoginResult {
+            success,
+            message: if success {
+                "认证成功"
+            } else {
+                "密码错误"
+            }
+            .to_string(),
+            adapter_used: success.then(|| "eportal:http://172.16.54.18".to_string()),
+        }
+    }
+
+    fn kinds(tracker: &KeepAliveTracker) -> Vec<KeepAliveEventKind> {
+        tracker.events.iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn drop_triggers_reauth_and_success_resets_state() {
+        let mut tracker = KeepAliveTracker::default();
+        assert_eq!(
+            tracker.on_probe(probe(CampusNetworkStatus::Authenticated), at(0, 0)),
+            KeepAliveAction::Idle
+        );
+        assert_eq!(
+            tracker.on_probe(probe(CampusNetworkStatus::NeedsAuth), at(2, 0)),
+            KeepAliveAction::Reauthenticate {
+                query_string: Some("wlanuserip=10.0.0.2".to_string())
+            }
+        );
+        tracker.on_login(login(true), at(2, 1));
+        assert_eq!(
+            kinds(&tracker),
+            vec![
+                KeepAliveEventKind::Dropped,
+                KeepAliveEventKind::Reauthenticated
+            ]
+        );
+        let status = tracker.snapshot(true, None);
+        assert_eq!(
+            status.network_status,
+            Some(CampusNetworkStatus::Authenticated)
+        );
+        assert!(status.last_reauth_at.is_some());
+        assert_eq!(status.events[0].kind, KeepAliveEventKind::Reauthenticated);
+        // 恢复后再次认证态不会重复记「恢复」
+        tracker.on_probe(probe(CampusNetworkStatus::Authenticated), at(4, 0));
+        assert_eq!(tracker.events.len(), 2);
+    }
+
+    #[test]
+    fn rejected_credentials_back_off_exponentially() {
+        let mut tracker = KeepAliveTracker::default();
+        tracker.on_probe(probe(CampusNetworkStatus::NeedsAuth), at(0, 0));
+        tracker.on_login(login(false), at(0, 1));
+        assert_eq!(tracker.consecutive_failures, 1);
+
+        // 退避期内只探测不登录，也不重复记录掉线
+        assert_eq!(
+            tracker.on_probe(probe(CampusNetworkStatus::NeedsAuth), at(0, 30)),
+            KeepAliveAction::Idle
+        );
+        assert!(matches!(
+            tracker.on_probe(probe(CampusNetworkStatus::NeedsAuth), at(1, 2)),
+            KeepAliveAction::Reauthenticate { .. }
+        ));
+        tracker.on_login(login(false), at(1, 3));
+        assert_eq!(tracker.backoff_until, Some(at(3, 3)));
+        assert_eq!(
+            kinds(&tracker),
+            vec![
+                KeepAliveEventKind::Dropped,
+                KeepAliveEventKind::AuthRejected,
+                KeepAliveEventKind::AuthRejected
+            ]
+        );
+
+        // 别处手动登录后恢复：清除退避
+        tracker.on_probe(probe(CampusNetworkStatus::Authenticated), at(2, 0));
+        assert_eq!(tracker.consecutive_failures, 0);
+        assert_eq!(tracker.backoff_until, None);
+        assert_eq!(
+            tracker.events.back().map(|event| event.kind),
+            Some(KeepAliveEventKind::Recovered)
+        );
+
+        assert_eq!(reauth_backoff(1), Duration::from_secs(60));
+        assert_eq!(reauth_backoff(3), Duration::from_secs(240));
+        assert_eq!(reauth_backoff(20), REAUTH_BACKOFF_MAX);
+    }
+
+    #[test]
+    fn probe_failures_and_history_are_bounded() {
+        let mut tracker = KeepAliveTracker::default();
+        tracker.on_probe(Err("网络不可达".to_string()), at(0, 0));
+        tracker.on_probe(Err("网络不可达".to_string()), at(0, 30));
+        assert_eq!(kinds(&tracker), vec![KeepAliveEventKind::ProbeFailed]);
+
+        for second in 0..(HISTORY_LIMIT as u32 + 10) {
+            tracker.on_missing_password(at(10 + second / 60, second % 60));
+        }
+        assert_eq!(tracker.events.len(), HISTORY_LIMIT);
+        assert_eq!(
+            tracker.events.front().map(|event| event.kind),
+            Some(KeepAliveEventKind::MissingPassword)
+        );
+    }
+
+    #[test]
+    fn config_is_validated_and_persisted() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("mini_hbut_campus_keepalive_{nanos}.db"));
+        db::init_db(&path).expect("init db");
+        assert!(load_config(&path).is_none());
+
+        let config = KeepAliveConfig {
+            student_id: " 2023123456 ".to_string(),
+            carrier: CampusCarrier::Cmcc,
+            gateway_override: Some("http://172.16.54.18/ ".to_string()),
+            interval_secs: DEFAULT_INTERVAL_SECS,
+            enabled: true,
+        };
+        let saved = save_config(&path, config.clone()).expect("save");
+        assert_eq!(saved.student_id, "2023123456");
+        assert_eq!(
+            saved.gateway_override.as_deref(),
+            Some("http://172.16.54.18")
+        );
+        assert_eq!(saved.credential_key(), "campus:2023123456");
+        assert_eq!(load_config(&path), Some(saved));
+
+        let too_fast = KeepAliveConfig {
+            interval_secs: 5,
+            ..config
+        };
+        assert!(save_config(&path, too_fast).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,20 +1,28 @@
-//! 湖工大 iHBUT 校园网认证（eportal + Srun 双适配）。
+//! 湖工大 iHBUT 校园网认证（eportal + Srun 双适配）、Srun 在线设备与用量查询（[`srun_account`]）
+//! 与桌面端保活守护（[`keepalive`]）。
 
 mod eportal;
+mod keepalive;
 mod probe;
 mod srun;
+mod srun_account;
 mod types;
 mod xencode;
 
+pub use keepalive::{
+    CampusKeepAlive, KeepAliveConfig, KeepAliveEvent, KeepAliveEventKind, KeepAliveStatus,
+    DEFAULT_INTERVAL_SECS as DEFAULT_KEEPALIVE_INTERVAL_SECS,
+};
 pub use probe::{build_probe_client, extract_client_ip, extract_query_string, probe_network};
 pub use types::{
-    CampusCarrier, CampusNetworkLoginResult, CampusNetworkProbeResult, CampusNetworkStatus,
-    DEFAULT_GATEWAYS,
+    CampusCarrier, CampusNetworkAccountResult, CampusNetworkDropResult, CampusNetworkLoginResult,
+    CampusNetworkOnlineDevice, CampusNetworkProbeResult, CampusNetworkStatus, DEFAULT_GATEWAYS,
 };
 
 use eportal::eportal_login;
 use reqwest::Client;
 use srun::srun_login;
+use srun_account::{srun_account_info, srun_drop_session};
 use std::time::Duration;
 
 const LOGIN_TIMEOUT: Duration = Duration::from_secs(12);
@@ -160,6 +168,49 @@ pub async fn logout_campus_network(
     Err("登出请求失败".to_string())
 }
 
+/// 查询 Srun 账号在线设备与用量：依次尝试各网关，返回首个有效应答。
+pub async fn query_campus_network_account(
+    student_id: &str,
+    carrier: CampusCarrier,
+    gateway_override: Option<&str>,
+    client_ip: Option<&str>,
+) -> Result<CampusNetworkAccountResult, String> {
+    let username = carrier.srun_username(student_id);
+    let mut last_error = "所有网关均无法查询在线信息，请在校内网络重试".to_string();
+    for gw in gateway_list(gateway_override) {
+        match srun_account_info(&gw, &username, client_ip).await {
+            Ok(result) if result.status != CampusNetworkStatus::Error => return Ok(result),
+            Ok(result) => {
+                if let Some(message) = result.message {
+                    last_error = message;
+                }
+            }
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// 强制下线指定 IP 的 Srun 会话（并发设备数达到上限时踢掉其他设备）。
+pub async fn drop_campus_network_session(
+    student_id: &str,
+    carrier: CampusCarrier,
+    gateway_override: Option<&str>,
+    ip: &str,
+) -> Result<CampusNetworkDropResult, String> {
+    let username = carrier.srun_username(student_id);
+    let mut last_result = None;
+    let mut last_error = "下线请求失败".to_string();
+    for gw in gateway_list(gateway_override) {
+        match srun_drop_session(&gw, &username, ip).await {
+            Ok(result) if result.success => return Ok(result),
+            Ok(result) => last_result = Some(result),
+            Err(e) => last_error = e,
+        }
+    }
+    last_result.ok_or(last_error)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
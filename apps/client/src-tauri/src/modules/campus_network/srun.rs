@@ -11,7 +11,7 @@ const PATH_PORTAL: &str = "/cgi-bin/srun_portal";
 
 const LOGIN_TIMEOUT: Duration = Duration::from_secs(8);
 
-fn build_client() -> Result<Client, String> {
+pub(super) fn build_client() -> Result<Client, String> {
     Client::builder()
         .redirect(reqwest::redirect::Policy::limited(4))
         .timeout(LOGIN_TIMEOUT)
@@ -38,7 +38,7 @@ fn hmac_md5_hex(key: &str, data: &str) -> String {
     outer.0.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
-fn unix_second() -> u64 {
+pub(super) fn unix_second() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_secs())
@@ -63,7 +63,7 @@ struct PortalResponse {
     ecode: Option<serde_json::Value>,
 }
 
-fn parse_jsonp(body: &str) -> Result<serde_json::Value, String> {
+pub(super) fn parse_jsonp(body: &str) -> Result<serde_json::Value, String> {
     let bytes = body.as_bytes();
     if bytes.len() < 6 {
         return Err("Srun 响应过短".to_string());
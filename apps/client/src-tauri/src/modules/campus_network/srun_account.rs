@@ -0,0 +1,411 @@
+//! 深澜 Srun 账号在线信息：当前会话用量（`rad_user_info`）、在线设备列表与按 IP 强制下线
+//! （`rad_user_dm`，签名规则参考 zu1k/srun）。
+
+use super::srun::{build_client, parse_jsonp, unix_second};
+use super::types::{
+    CampusNetworkAccountResult, CampusNetworkDropResult, CampusNetworkOnlineDevice,
+    CampusNetworkStatus,
+};
+use chrono::{DateTime, Local};
+use reqwest::Client;
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use std::net::IpAddr;
+
+const PATH_USER_INFO: &str = "/cgi-bin/rad_user_info";
+/// 在线设备列表（自服务接口，字段以校内抓包为准；不可用时退化为仅当前会话）。
+const PATH_ONLINE_LIST: &str = "/cgi-bin/rad_user_online";
+const PATH_USER_DM: &str = "/cgi-bin/rad_user_dm";
+
+/// 数值字段在不同 Srun 版本中可能是数字或字符串。
+fn value_u64(value: &Value, key: &str) -> Option<u64> {
+    match value.get(key)? {
+        Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f.max(0.0) as u64)),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_f64(value: &Value, key: &str) -> Option<f64> {
+    match value.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_str(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| value.get(*key).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn unix_to_rfc3339(secs: u64) -> Option<String> {
+    if secs == 0 {
+        return None;
+    }
+    DateTime::from_timestamp(secs as i64, 0).map(|t| t.with_timezone(&Local).to_rfc3339())
+}
+
+/// JSONP 或纯 JSON 均可。
+fn parse_body(body: &str) -> Result<Value, String> {
+    let trimmed = body.trim();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).map_err(|e| format!("解析 Srun 响应失败: {e}"))
+    } else {
+        parse_jsonp(trimmed)
+    }
+}
+
+fn parse_device(value: &Value, current_ip: Option<&str>) -> Option<CampusNetworkOnlineDevice> {
+    let ip = value_str(value, &["ip", "online_ip", "user_ip"])?;
+    Some(CampusNetworkOnlineDevice {
+        is_current: current_ip == Some(ip.as_str()),
+        mac: value_str(value, &["mac", "user_mac"]),
+        os_name: value_str(value, &["os_name", "os", "device_name"]),
+        login_at: value_u64(value, "add_time").and_then(unix_to_rfc3339),
+        used_bytes: value_u64(value, "sum_bytes").or_else(|| value_u64(value, "bytes")),
+        used_seconds: value_u64(value, "sum_seconds").or_else(|| value_u64(value, "seconds")),
+        ip,
+    })
+}
+
+/// 在线设备列表：兼容 `[..]`、`{"list":[..]}`、`{"data":[..]}` 与 `{"data":{"list":[..]}}`。
+fn parse_online_list(value: &Value, current_ip: Option<&str>) -> Vec<CampusNetworkOnlineDevice> {
+    let items = value
+        .as_array()
+        .or_else(|| value.get("list").and_then(Value::as_array))
+        .or_else(|| value.get("data").and_then(Value::as_array))
+        .or_else(|| {
+            value
+                .get("data")
+                .and_then(|d| d.get("list"))
+                .and_then(Value::as_array)
+        });
+    items
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| parse_device(item, current_ip))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 解析 `rad_user_info`：`error == "ok"` 为在线，`not_online_error` 为未认证。
+fn parse_user_info(auth_server: &str, value: &Value) -> CampusNetworkAccountResult {
+    let error = value_str(value, &["error"]).unwrap_or_default();
+    let status = match error.as_str() {
+        "ok" => CampusNetworkStatus::Authenticated,
+        "not_online_error" => CampusNetworkStatus::NeedsAuth,
+        _ => CampusNetworkStatus::Error,
+    };
+    let online = status == CampusNetworkStatus::Authenticated;
+    let online_ip = value_str(value, &["online_ip", "client_ip"]);
+    let devices = if online {
+        parse_device(value, online_ip.as_deref())
+            .map(|mut current| {
+                current.is_current = true;
+                vec![current]
+            })
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let message = match status {
+        CampusNetworkStatus::Authenticated => None,
+        CampusNetworkStatus::NeedsAuth => Some("当前设备未在线".to_string()),
+        _ => Some(
+            value_str(value, &["error_msg", "error"])
+                .unwrap_or_else(|| "Srun 用户信息查询失败".to_string()),
+        ),
+    };
+
+    CampusNetworkAccountResult {
+        status,
+        gateway: Some(auth_server.to_string()),
+        username: value_str(value, &["user_name"]).filter(|_| online),
+        online_ip,
+        used_bytes: value_u64(value, "sum_bytes").filter(|_| online),
+        used_seconds: value_u64(value, "sum_seconds").filter(|_| online),
+        balance: value_f64(value, "user_balance").filter(|_| online),
+        online_device_total: value_u64(value, "online_device_total")
+            .filter(|_| online)
+            .map(|n| n as u32),
+        devices,
+        message,
+    }
+}
+
+/// 合并列表接口的结果：当前会话以 `rad_user_info` 为准，其余设备按列表追加。
+fn merge_devices(result: &mut CampusNetworkAccountResult, listed: Vec<CampusNetworkOnlineDevice>) {
+    for device in listed {
+        if let Some(existing) = result.devices.iter_mut().find(|d| d.ip == device.ip) {
+            existing.mac = existing.mac.take().or(device.mac);
+            existing.os_name = existing.os_name.take().or(device.os_name);
+            existing.login_at = existing.login_at.take().or(device.login_at);
+        } else {
+            result.devices.push(device);
+        }
+    }
+    let listed_total = result.devices.len() as u32;
+    result.online_device_total = Some(
+        result
+            .online_device_total
+            .map_or(listed_total, |n| n.max(listed_total)),
+    );
+}
+
+async fn get_json(client: &Client, url: &str, query: &[(&str, &str)]) -> Result<Value, String> {
+    let resp = client
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| format!("Srun 请求失败: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Srun 接口返回 HTTP {}", resp.status().as_u16()));
+    }
+    let body = resp.text().await.map_err(|e| e.to_string())?;
+    parse_body(&body)
+}
+
+/// 查询账号在线信息与用量；在线时附带设备列表（列表接口失败不影响整体结果）。
+pub async fn srun_account_info(
+    auth_server: &str,
+    username: &str,
+    client_ip_hint: Option<&str>,
+) -> Result<CampusNetworkAccountResult, String> {
+    let auth_server = auth_server.trim().trim_end_matches('/');
+    if auth_server.is_empty() {
+        return Err("Srun 网关无效".to_string());
+    }
+    let client = build_client()?;
+    let time = unix_second().to_string();
+
+    let mut info_query = vec![("callback", "sdu"), ("_", time.as_str())];
+    if let Some(ip) = client_ip_hint.filter(|s| !s.is_empty()) {
+        info_query.push(("ip", ip));
+    }
+    let info = get_json(
+        &client,
+        &format!("{auth_server}{PATH_USER_INFO}"),
+        &info_query,
+    )
+    .await?;
+    let mut result = parse_user_info(auth_server, &info);
+    if result.status != CampusNetworkStatus::Authenticated {
+        return Ok(result);
+    }
+
+    let account = result
+        .username
+        .clone()
+        .unwrap_or_else(|| username.to_string());
+    let listed = get_json(
+        &client,
+        &format!("{auth_server}{PATH_ONLINE_LIST}"),
+        &[
+            ("callback", "sdu"),
+            ("username", account.as_str()),
+            ("_", time.as_str()),
+        ],
+    )
+    .await
+    .map(|value| parse_online_list(&value, result.online_ip.as_deref()));
+    match listed {
+        Ok(listed) => merge_devices(&mut result, listed),
+        Err(e) => println!("[调试] Srun 在线设备列表不可用: {e}"),
+    }
+    Ok(result)
+}
+
+/// `rad_user_dm` 签名：`sha1(time + username + ip + unbind + time)`。
+fn dm_sign(time: &str, username: &str, ip: &str, unbind: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("{time}{username}{ip}{unbind}{time}").as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 强制下线指定 IP 的会话（用于并发设备数达到上限时踢掉其他设备）。
+pub async fn srun_drop_session(
+    auth_server: &str,
+    username: &str,
+    ip: &str,
+) -> Result<CampusNetworkDropResult, String> {
+    let auth_server = auth_server.trim().trim_end_matches('/');
+    if auth_server.is_empty() {
+        return Err("Srun 网关无效".to_string());
+    }
+    let ip = ip.trim();
+    if ip.parse::<IpAddr>().is_err() {
+        return Err(format!("无效的设备 IP: {ip}"));
+    }
+    let client = build_client()?;
+    let time = unix_second().to_string();
+    let unbind = "1";
+    let sign = dm_sign(&time, username, ip, unbind);
+    let value = get_json(
+        &client,
+        &format!("{auth_server}{PATH_USER_DM}"),
+        &[
+            ("callback", "sdu"),
+            ("ip", ip),
+            ("username", username),
+            ("time", time.as_str()),
+            ("unbind", unbind),
+            ("sign", sign.as_str()),
+        ],
+    )
+    .await?;
+
+    let error = value_str(&value, &["error", "res"]).unwrap_or_default();
+    let success = matches!(error.as_str(), "ok" | "logout_ok");
+    let message = if success {
+        format!("已下线 {ip}")
+    } else {
+        value_str(&value, &["error_msg", "error", "res"])
+            .unwrap_or_else(|| "Srun 下线失败".to_string())
+    };
+    Ok(CampusNetworkDropResult {
+        success,
+        ip: ip.to_string(),
+        gateway: Some(auth_server.to_string()),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client::fixture::{FixtureCassette, FixtureExchange, FixtureServer};
+
+    const GATEWAY_HOST: &str = "172.16.54.18";
+
+    fn exchange(path: &str, body: &str) -> FixtureExchange {
+        FixtureExchange {
+            method: "GET".to_string(),
+            host: GATEWAY_HOST.to_string(),
+            path: path.to_string(),
+            query: String::new(),
+            request_body: None,
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+            body: body.to_string(),
+            body_base64: false,
+        }
+    }
+
+    async fn mock_gateway(exchanges: Vec<FixtureExchange>) -> (FixtureServer, String) {
+        let mut cassette = FixtureCassette::new("srun_account");
+        cassette.exchanges = exchanges;
+        let server = FixtureServer::replay(cassette).await.expect("mock gateway");
+        let gateway = format!("{}/{GATEWAY_HOST}", server.base_url());
+        (server, gateway)
+    }
+
+    const ONLINE_INFO: &str = r#"sdu({"error":"ok","user_name":"2021000001@cmcc","online_ip":"10.1.2.3","sum_bytes":"1073741824","sum_seconds":7200,"user_balance":12.5,"online_device_total":"2","add_time":1700000000,"user_mac":"aa:bb:cc:dd:ee:ff"})"#;
+
+    #[tokio::test]
+    async fn account_info_merges_online_devices() {
+        let (server, gateway) = mock_gateway(vec![
+            exchange(PATH_USER_INFO, ONLINE_INFO),
+            exchange(
+                PATH_ONLINE_LIST,
+                r#"sdu({"data":{"list":[{"ip":"10.1.2.3","os_name":"Windows"},{"ip":"10.9.8.7","mac":"11:22:33:44:55:66","os_name":"Android","add_time":"1700003600","sum_bytes":2048}]}})"#,
+            ),
+        ])
+        .await;
+
+        let info = srun_account_info(&gateway, "2021000001@cmcc", None)
+            .await
+            .expect("account info");
+        assert_eq!(info.status, CampusNetworkStatus::Authenticated);
+        assert_eq!(info.username.as_deref(), Some("2021000001@cmcc"));
+        assert_eq!(info.used_bytes, Some(1 << 30));
+        assert_eq!(info.used_seconds, Some(7200));
+        assert_eq!(info.balance, Some(12.5));
+        assert_eq!(info.online_device_total, Some(2));
+        assert_eq!(info.devices.len(), 2);
+        let current = &info.devices[0];
+        assert!(current.is_current);
+        assert_eq!(current.ip, "10.1.2.3");
+        assert_eq!(current.mac.as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+        assert_eq!(current.os_name.as_deref(), Some("Windows"));
+        let other = &info.devices[1];
+        assert!(!other.is_current);
+        assert_eq!(other.used_bytes, Some(2048));
+        assert!(other.login_at.is_some());
+        assert!(server.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn account_info_falls_back_to_current_session_without_list() {
+        let (_server, gateway) = mock_gateway(vec![exchange(PATH_USER_INFO, ONLINE_INFO)]).await;
+
+        let info = srun_account_info(&gateway, "2021000001@cmcc", None)
+            .await
+            .expect("account info");
+        assert_eq!(info.status, CampusNetworkStatus::Authenticated);
+        assert_eq!(info.devices.len(), 1);
+        assert_eq!(info.online_device_total, Some(2));
+    }
+
+    #[tokio::test]
+    async fn account_info_reports_offline() {
+        let (server, gateway) = mock_gateway(vec![exchange(
+            PATH_USER_INFO,
+            r#"sdu({"error":"not_online_error","client_ip":"10.1.2.3","sum_bytes":0})"#,
+        )])
+        .await;
+
+        let info = srun_account_info(&gateway, "2021000001", None)
+            .await
+            .expect("account info");
+        assert_eq!(info.status, CampusNetworkStatus::NeedsAuth);
+        assert_eq!(info.online_ip.as_deref(), Some("10.1.2.3"));
+        assert!(info.devices.is_empty());
+        assert_eq!(info.used_bytes, None);
+        assert!(server.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn drop_session_reports_gateway_result() {
+        let (server, gateway) = mock_gateway(vec![
+            exchange(PATH_USER_DM, r#"sdu({"error":"logout_ok"})"#),
+            exchange(
+                PATH_USER_DM,
+                r#"sdu({"error":"sign_error","error_msg":"签名错误"})"#,
+            ),
+        ])
+        .await;
+
+        let dropped = srun_drop_session(&gateway, "2021000001@cmcc", "10.9.8.7")
+            .await
+            .expect("drop session");
+        assert!(dropped.success);
+        assert_eq!(dropped.ip, "10.9.8.7");
+
+        let rejected = srun_drop_session(&gateway, "2021000001@cmcc", "10.9.8.7")
+            .await
+            .expect("drop session");
+        assert!(!rejected.success);
+        assert_eq!(rejected.message, "签名错误");
+        assert!(server.unused().is_empty());
+
+        assert!(srun_drop_session(&gateway, "2021000001", "not-an-ip")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn dm_sign_matches_reference() {
+        // sha1("1700000000" + "user" + "10.0.0.1" + "1" + "1700000000")
+        assert_eq!(
+            dm_sign("1700000000", "user", "10.0.0.1", "1"),
+            "e6304bca85916f83df84ff897c80f87885c35444"
+        );
+    }
+}
@@ -64,6 +64,48 @@ pub struct CampusNetworkLoginResult {
     pub adapter_used: Option<String>,
 }
 
+/// Srun 在线会话（一台已认证设备）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CampusNetworkOnlineDevice {
+    pub ip: String,
+    pub mac: Option<String>,
+    pub os_name: Option<String>,
+    /// 上线时间（RFC 3339）
+    pub login_at: Option<String>,
+    pub used_bytes: Option<u64>,
+    pub used_seconds: Option<u64>,
+    /// 是否为当前设备
+    pub is_current: bool,
+}
+
+/// Srun 账号在线信息与用量。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampusNetworkAccountResult {
+    pub status: CampusNetworkStatus,
+    pub gateway: Option<String>,
+    pub username: Option<String>,
+    pub online_ip: Option<String>,
+    /// 本周期已用流量（字节）
+    pub used_bytes: Option<u64>,
+    /// 本周期已用时长（秒）
+    pub used_seconds: Option<u64>,
+    /// 账户余额（元）
+    pub balance: Option<f64>,
+    /// 网关报告的在线设备数（设备列表不可用时仍可据此提示并发上限）
+    pub online_device_total: Option<u32>,
+    pub devices: Vec<CampusNetworkOnlineDevice>,
+    pub message: Option<String>,
+}
+
+/// 强制下线结果。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampusNetworkDropResult {
+    pub success: bool,
+    pub ip: String,
+    pub gateway: Option<String>,
+    pub message: String,
+}
+
 /// 湖工大预置认证网关（可被高级设置覆盖）。
 pub const DEFAULT_GATEWAYS: &[&str] = &[
     "http://172.16.54.18",
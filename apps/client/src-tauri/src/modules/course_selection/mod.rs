@@ -1,4 +1,17 @@
-use std::collections::HashMap;
+//! 选课：批次概览、教学班列表、子教学班、选课 / 退课与已选课程。
+//!
+//! 关注列表与后台余量检查见 [`watch`]；选课前会与本地课表做冲突检查。
+
+mod watch;
+
+pub use watch::{
+    apply_observations, describe_conflicts, list_watch_rounds, parse_capacity,
+    parse_schedule_slots, record_round_check, selection_conflicts, unwatch_course, watch_course,
+    CourseSelectionUnwatchRequest, CourseSelectionWatchRequest, RoundCheck, SeatAlert,
+    SeatCapacity, WatchItem, WatchRound, MAX_WATCH_ITEMS, MAX_WATCH_ROUNDS, REQUEST_SPACING,
+};
+
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::io;
 
@@ -689,6 +702,29 @@ pub async fn fetch_course_selection_child_classes(
     })))
 }
 
+/// 选课前检查与本地课表的冲突；有冲突且未要求忽略时拒绝提交。
+/// 本地课表可能滞后于教务（已退的课、调课），由调用方确认后以 `ignore_conflicts` 重新提交。
+/// 本地没有课表缓存等原因无法检查时只记录日志，不阻止选课。
+fn ensure_no_schedule_conflict(
+    client: &HbutClient,
+    req: &crate::CourseSelectionSelectRequest,
+) -> Result<(), DynError> {
+    if req.ignore_conflicts {
+        return Ok(());
+    }
+    let Some(sid) = client.user_info.as_ref().map(|u| u.student_id.clone()) else {
+        return Ok(());
+    };
+    match selection_conflicts(crate::DB_FILENAME, &sid, req) {
+        Ok(conflicts) if !conflicts.is_empty() => Err(err_box(describe_conflicts(&conflicts))),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            crate::runtime_log::log_warn("course_selection", format!("选课冲突检查跳过: {e}"));
+            Ok(())
+        }
+    }
+}
+
 pub async fn select_course_selection_course(
     client: &HbutClient,
     req: &crate::CourseSelectionSelectRequest,
@@ -696,6 +732,7 @@ pub async fn select_course_selection_course(
     if req.pcid.trim().is_empty() || req.jxbid.trim().is_empty() {
         return Err(err_box("pcid、jxbid 不能为空"));
     }
+    ensure_no_schedule_conflict(client, req)?;
 
     let base = client.academic_base_url();
     let url = format!("{}/admin/xsd/xk/xsdXkV2", base);
@@ -747,6 +784,101 @@ pub async fn select_course_selection_course(
     })))
 }
 
+/// 后台余量检查的一次批次轮询：先查批次剩余时间（已结束则不再请求列表），
+/// 再拉取一次教学班列表，关注了子教学班的按主教学班各查询一次；相邻请求间隔 [`REQUEST_SPACING`]。
+///
+/// 返回剩余秒数与 `(jxbid, zjxbid)` → 余量，交由 [`record_round_check`] 落库并评估提醒。
+pub async fn poll_watch_round(
+    client: &HbutClient,
+    round: &WatchRound,
+) -> Result<(Option<i64>, BTreeMap<(String, String), SeatCapacity>), DynError> {
+    let end_time = fetch_course_selection_end_time(
+        client,
+        &crate::CourseSelectionEndTimeRequest {
+            pcid: round.pcid.clone(),
+            kklx: round.kklx.clone(),
+        },
+    )
+    .await?;
+    let remaining_seconds = end_time.get("remaining_seconds").and_then(Value::as_i64);
+    let mut observed = BTreeMap::new();
+    if remaining_seconds.is_some_and(|remaining| remaining <= 0) {
+        return Ok((remaining_seconds, observed));
+    }
+
+    sleep(REQUEST_SPACING).await;
+    let list = fetch_course_selection_list(
+        client,
+        &crate::CourseSelectionListRequest {
+            pcid: round.pcid.clone(),
+            from: Some(round.from.clone()),
+            pcenc: round.pcenc.clone(),
+            kcmc: None,
+            kcxz: None,
+            jxms: None,
+            kcgs: None,
+            teacher: None,
+            kkxq: None,
+            kclb: None,
+            kclx: None,
+        },
+    )
+    .await?;
+    let ratio = get_string(&list, "available_ratio");
+    for course in list
+        .get("courses")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        observed.insert(
+            (get_string(course, "id"), String::new()),
+            parse_capacity(&get_string(course, "yxrl"), &ratio),
+        );
+    }
+
+    let mut parents = round
+        .items
+        .iter()
+        .filter(|item| item.zjxbid.is_some())
+        .map(|item| item.jxbid.clone())
+        .collect::<Vec<_>>();
+    parents.sort();
+    parents.dedup();
+    for jxbid in parents {
+        sleep(REQUEST_SPACING).await;
+        let children = fetch_course_selection_child_classes(
+            client,
+            &crate::CourseSelectionChildClassesRequest {
+                pcid: round.pcid.clone(),
+                pcenc: round.pcenc.clone(),
+                jxbid: jxbid.clone(),
+                from: Some(round.from.clone()),
+            },
+        )
+        .await?;
+        for class in children
+            .get("classes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let text = class
+                .get("raw")
+                .map(|raw| get_string(raw, "yxrl"))
+                .unwrap_or_default();
+            if text.is_empty() {
+                continue;
+            }
+            observed.insert(
+                (jxbid.clone(), get_string(class, "id")),
+                parse_capacity(&text, &ratio),
+            );
+        }
+    }
+    Ok((remaining_seconds, observed))
+}
+
 pub async fn withdraw_course_selection_course(
     client: &HbutClient,
     req: &crate::CourseSelectionWithdrawRequest,
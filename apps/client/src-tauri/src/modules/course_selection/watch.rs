@@ -0,0 +1,667 @@
+//! 选课余量监控：按批次关注教学班 / 子教学班，后台轮询余量并在出现空位时提醒。
+//!
+//! 关注列表按账号与批次存于 kv_store `course_selection.watch.<student_id>.<pcid>`，
+//! 余量文本的解析规则与前端 `parseCapacityInfo` 一致。与电费监控一样，只有后台任务
+//! （[`record_round_check`]）推进提醒状态：出现空位时提醒一次，重新满员后复位。
+//!
+//! 选课前与现有课表的冲突检查见 [`selection_conflicts`]。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+use crate::modules::schedule_analysis::{self, ScheduleConflict, ScheduleSlot, SlotSource};
+
+/// 同时关注的批次上限（每个批次每次检查至少请求两次上游）。
+pub const MAX_WATCH_ROUNDS: usize = 3;
+/// 单个批次关注的教学班上限。
+pub const MAX_WATCH_ITEMS: usize = 20;
+/// 后台检查时相邻两次上游请求的间隔，避免选课高峰期给教务系统添压。
+pub const REQUEST_SPACING: Duration = Duration::from_millis(1500);
+
+const WATCH_KV_PREFIX: &str = "course_selection.watch.";
+const MAX_NAME_CHARS: usize = 64;
+
+/// 教学班余量（`yxrl` 已选 / 容量）。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatCapacity {
+    /// 上游原始文本
+    pub text: String,
+    pub selected: Option<u32>,
+    pub total: Option<u32>,
+    pub full: bool,
+}
+
+impl SeatCapacity {
+    pub fn remaining(&self) -> Option<u32> {
+        Some(self.total?.saturating_sub(self.selected?))
+    }
+
+    /// 明确有空位：未满员且已选人数少于容量（只有文本、没有人数时不算）。
+    pub fn has_seat(&self) -> bool {
+        !self.full && self.remaining().is_some_and(|remaining| remaining > 0)
+    }
+}
+
+/// 解析余量文本：`"已选/容量"`（含全角斜杠）或前两个数字；
+/// 文本含「已满 / 满额」、批次开放率 `ratio` ≤ 0 或已选 ≥ 容量均视为满员。
+pub fn parse_capacity(text: &str, ratio: &str) -> SeatCapacity {
+    static SLASH: OnceLock<Regex> = OnceLock::new();
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    let slash = SLASH.get_or_init(|| Regex::new(r"(\d+)\s*[/／]\s*(\d+)").unwrap());
+    let number = NUMBER.get_or_init(|| Regex::new(r"\d+").unwrap());
+
+    let text = text.trim();
+    let ratio = ratio.trim().trim_end_matches('%').parse::<f64>().ok();
+    let (selected, total) = match slash.captures(text) {
+        Some(caps) => (caps[1].parse().ok(), caps[2].parse().ok()),
+        None => {
+            let numbers = number
+                .find_iter(text)
+                .filter_map(|m| m.as_str().parse::<u32>().ok())
+                .collect::<Vec<_>>();
+            match numbers.as_slice() {
+                [selected, total, ..] => (Some(*selected), Some(*total)),
+                [total] if ratio == Some(0.0) => (Some(*total), Some(*total)),
+                _ => (None, None),
+            }
+        }
+    };
+
+    let full_by_text = text.contains("已满") || text.contains("满额");
+    let full_by_ratio = ratio.is_some_and(|ratio| ratio <= 0.0);
+    let full_by_count = match (selected, total) {
+        (Some(selected), Some(total)) => total > 0 && selected >= total,
+        _ => false,
+    };
+    SeatCapacity {
+        text: text.to_string(),
+        selected,
+        total,
+        full: full_by_text || full_by_ratio || full_by_count,
+    }
+}
+
+/// 关注的教学班。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchItem {
+    pub jxbid: String,
+    /// 子教学班 id；为空表示关注主教学班本身
+    #[serde(default)]
+    pub zjxbid: Option<String>,
+    pub kcmc: String,
+    pub jxbmc: String,
+    /// 上课时间地点（`sksjdd`），选课前冲突检查用
+    #[serde(default)]
+    pub schedule: String,
+    /// 最近一次检查到的余量
+    #[serde(default)]
+    pub capacity: Option<SeatCapacity>,
+    #[serde(default)]
+    pub checked_at: Option<String>,
+    /// 余量最近一次变化的时间
+    #[serde(default)]
+    pub changed_at: Option<String>,
+    /// 已提醒过有空位（重新满员后复位）
+    #[serde(default)]
+    pub alerted: bool,
+    pub added_at: String,
+}
+
+impl WatchItem {
+    fn matches(&self, jxbid: &str, zjxbid: Option<&str>) -> bool {
+        self.jxbid == jxbid && self.zjxbid.as_deref() == zjxbid
+    }
+
+    /// 后台检查结果的索引键：`(jxbid, zjxbid 或空串)`。
+    pub fn key(&self) -> (String, String) {
+        (self.jxbid.clone(), self.zjxbid.clone().unwrap_or_default())
+    }
+}
+
+/// 一个选课批次的关注列表（kv_store 持久化）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchRound {
+    pub pcid: String,
+    pub pcenc: String,
+    pub from: String,
+    pub kklx: String,
+    pub name: String,
+    /// 批次结束时间，由后台检查按剩余秒数回填
+    #[serde(default)]
+    pub ends_at: Option<String>,
+    /// 批次已结束：后台检查不再请求上游，重新关注该批次的课程时复位
+    #[serde(default)]
+    pub closed: bool,
+    pub items: Vec<WatchItem>,
+    pub created_at: String,
+}
+
+/// 关注教学班请求（Tauri / Bridge 入参）；批次参数与选课列表请求一致。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CourseSelectionWatchRequest {
+    pub pcid: String,
+    pub pcenc: String,
+    pub from: Option<String>,
+    pub kklx: String,
+    /// 批次名称（展示用）
+    pub round_name: Option<String>,
+    pub jxbid: String,
+    pub zjxbid: Option<String>,
+    pub kcmc: Option<String>,
+    pub jxbmc: Option<String>,
+    pub sksjdd: Option<String>,
+}
+
+/// 取消关注请求：不带 `jxbid` 时移除整个批次。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CourseSelectionUnwatchRequest {
+    pub pcid: String,
+    pub jxbid: Option<String>,
+    pub zjxbid: Option<String>,
+}
+
+/// 待推送的有空位提醒。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeatAlert {
+    pub pcid: String,
+    pub jxbid: String,
+    pub zjxbid: Option<String>,
+    pub kcmc: String,
+    pub jxbmc: String,
+    pub capacity: SeatCapacity,
+}
+
+impl SeatAlert {
+    pub fn title(&self) -> String {
+        "选课余量提醒".to_string()
+    }
+
+    pub fn body(&self) -> String {
+        let class = if self.jxbmc.is_empty() {
+            String::new()
+        } else {
+            format!("（{}）", self.jxbmc)
+        };
+        let seats = match (
+            self.capacity.selected,
+            self.capacity.total,
+            self.capacity.remaining(),
+        ) {
+            (Some(selected), Some(total), Some(remaining)) => {
+                format!("已选 {selected}/{total}，剩余 {remaining} 个名额")
+            }
+            _ => self.capacity.text.clone(),
+        };
+        format!("{}{} 出现空位：{}，请尽快选课", self.kcmc, class, seats)
+    }
+}
+
+/// 后台检查单个批次的结论。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoundCheck {
+    pub pcid: String,
+    pub closed: bool,
+    /// 本次拿到余量的关注项数
+    pub checked: usize,
+    /// 余量发生变化的关注项数
+    pub changed: usize,
+    pub alerts: Vec<SeatAlert>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn clip(value: &str) -> String {
+    value.trim().chars().take(MAX_NAME_CHARS).collect()
+}
+
+fn round_prefix(student_id: &str) -> String {
+    format!("{WATCH_KV_PREFIX}{student_id}.")
+}
+
+fn round_key(student_id: &str, pcid: &str) -> String {
+    format!("{}{pcid}", round_prefix(student_id))
+}
+
+fn load_round(path: &Path, student_id: &str, pcid: &str) -> Result<Option<WatchRound>, String> {
+    db::get_kv(path, &round_key(student_id, pcid))
+        .map_err(|e| e.to_string())
+        .map(|raw| raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+fn save_round(path: &Path, student_id: &str, round: &WatchRound) -> Result<(), String> {
+    let raw = serde_json::to_string(round).map_err(|e| e.to_string())?;
+    db::set_kv(path, &round_key(student_id, &round.pcid), &raw).map_err(|e| e.to_string())
+}
+
+fn require_student(student_id: &str) -> Result<&str, String> {
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    Ok(sid)
+}
+
+/// 账号下全部关注批次（按创建时间排序）。
+pub fn list_watch_rounds<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+) -> Result<Vec<WatchRound>, String> {
+    let sid = require_student(student_id)?;
+    let mut rounds = db::list_kv_by_prefix(path, &round_prefix(sid))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|(_, raw)| serde_json::from_str::<WatchRound>(&raw).ok())
+        .collect::<Vec<_>>();
+    rounds.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.pcid.cmp(&b.pcid)));
+    Ok(rounds)
+}
+
+/// 关注教学班（或更新其名称 / 上课时间）；已关注时保留余量与提醒状态。
+pub fn watch_course<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &CourseSelectionWatchRequest,
+) -> Result<WatchRound, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let (pcid, pcenc, kklx, jxbid) = match (
+        non_empty(Some(&req.pcid)),
+        non_empty(Some(&req.pcenc)),
+        non_empty(Some(&req.kklx)),
+        non_empty(Some(&req.jxbid)),
+    ) {
+        (Some(pcid), Some(pcenc), Some(kklx), Some(jxbid)) => (pcid, pcenc, kklx, jxbid),
+        _ => return Err("pcid、pcenc、kklx、jxbid 不能为空".to_string()),
+    };
+    let zjxbid = non_empty(req.zjxbid.as_deref());
+    let now = Local::now().to_rfc3339();
+
+    let mut round = match load_round(path, sid, pcid)? {
+        Some(round) => round,
+        None => {
+            if list_watch_rounds(path, sid)?.len() >= MAX_WATCH_ROUNDS {
+                return Err(format!("最多同时关注 {MAX_WATCH_ROUNDS} 个选课批次"));
+            }
+            WatchRound {
+                pcid: pcid.to_string(),
+                pcenc: String::new(),
+                from: String::new(),
+                kklx: String::new(),
+                name: String::new(),
+                ends_at: None,
+                closed: false,
+                items: Vec::new(),
+                created_at: now.clone(),
+            }
+        }
+    };
+    // 批次参数以最新一次为准（pcenc 随会话变化）
+    round.pcenc = pcenc.to_string();
+    round.kklx = kklx.to_string();
+    round.from = non_empty(req.from.as_deref())
+        .unwrap_or("ggxxk")
+        .to_string();
+    if let Some(name) = non_empty(req.round_name.as_deref()) {
+        round.name = clip(name);
+    }
+    round.closed = false;
+
+    let kcmc = non_empty(req.kcmc.as_deref()).map(clip);
+    let jxbmc = non_empty(req.jxbmc.as_deref()).map(clip);
+    let schedule = non_empty(req.sksjdd.as_deref()).map(str::to_string);
+    match round
+        .items
+        .iter_mut()
+        .find(|item| item.matches(jxbid, zjxbid))
+    {
+        Some(item) => {
+            item.kcmc = kcmc.unwrap_or_else(|| item.kcmc.clone());
+            item.jxbmc = jxbmc.unwrap_or_else(|| item.jxbmc.clone());
+            item.schedule = schedule.unwrap_or_else(|| item.schedule.clone());
+        }
+        None => {
+            if round.items.len() >= MAX_WATCH_ITEMS {
+                return Err(format!("每个选课批次最多关注 {MAX_WATCH_ITEMS} 个教学班"));
+            }
+            round.items.push(WatchItem {
+                jxbid: jxbid.to_string(),
+                zjxbid: zjxbid.map(str::to_string),
+                kcmc: kcmc.unwrap_or_else(|| jxbid.to_string()),
+                jxbmc: jxbmc.unwrap_or_default(),
+                schedule: schedule.unwrap_or_default(),
+                capacity: None,
+                checked_at: None,
+                changed_at: None,
+                alerted: false,
+                added_at: now,
+            });
+        }
+    }
+    save_round(path, sid, &round)?;
+    Ok(round)
+}
+
+/// 取消关注；批次内最后一项被移除时一并删除批次。未找到时返回 false。
+pub fn unwatch_course<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &CourseSelectionUnwatchRequest,
+) -> Result<bool, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let Some(mut round) = load_round(path, sid, req.pcid.trim())? else {
+        return Ok(false);
+    };
+    if let Some(jxbid) = non_empty(req.jxbid.as_deref()) {
+        let zjxbid = non_empty(req.zjxbid.as_deref());
+        let before = round.items.len();
+        round.items.retain(|item| !item.matches(jxbid, zjxbid));
+        if round.items.len() == before {
+            return Ok(false);
+        }
+        if !round.items.is_empty() {
+            save_round(path, sid, &round)?;
+            return Ok(true);
+        }
+    }
+    db::delete_kv(path, &round_key(sid, &round.pcid)).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 用一次检查拿到的余量更新关注项并评估提醒：出现空位且未提醒过时提醒，重新满员后复位。
+pub fn apply_observations(
+    round: &mut WatchRound,
+    observed: &BTreeMap<(String, String), SeatCapacity>,
+    now: &str,
+) -> (usize, usize, Vec<SeatAlert>) {
+    let (mut checked, mut changed) = (0, 0);
+    let mut alerts = Vec::new();
+    for item in &mut round.items {
+        let Some(capacity) = observed.get(&item.key()) else {
+            continue;
+        };
+        checked += 1;
+        if item.capacity.as_ref() != Some(capacity) {
+            changed += 1;
+            item.changed_at = Some(now.to_string());
+        }
+        item.capacity = Some(capacity.clone());
+        item.checked_at = Some(now.to_string());
+
+        let open = capacity.has_seat();
+        if open && !item.alerted {
+            alerts.push(SeatAlert {
+                pcid: round.pcid.clone(),
+                jxbid: item.jxbid.clone(),
+                zjxbid: item.zjxbid.clone(),
+                kcmc: item.kcmc.clone(),
+                jxbmc: item.jxbmc.clone(),
+                capacity: capacity.clone(),
+            });
+        }
+        item.alerted = open;
+    }
+    (checked, changed, alerts)
+}
+
+/// 后台检查：记录批次剩余时间与本次余量，推进提醒状态。
+///
+/// `remaining_seconds` ≤ 0 时把批次标记为已结束，不再评估提醒。
+/// 以最新存储的关注列表为准（检查期间用户可能增删了关注项）。
+pub fn record_round_check<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    pcid: &str,
+    remaining_seconds: Option<i64>,
+    observed: &BTreeMap<(String, String), SeatCapacity>,
+    now: DateTime<Local>,
+) -> Result<RoundCheck, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let Some(mut round) = load_round(path, sid, pcid)? else {
+        return Ok(RoundCheck {
+            pcid: pcid.to_string(),
+            closed: false,
+            checked: 0,
+            changed: 0,
+            alerts: Vec::new(),
+        });
+    };
+    if let Some(remaining) = remaining_seconds {
+        round.ends_at = Some((now + chrono::Duration::seconds(remaining.max(0))).to_rfc3339());
+        round.closed = remaining <= 0;
+    }
+    let (checked, changed, alerts) = if round.closed {
+        (0, 0, Vec::new())
+    } else {
+        apply_observations(&mut round, observed, &now.to_rfc3339())
+    };
+    save_round(path, sid, &round)?;
+    Ok(RoundCheck {
+        pcid: round.pcid,
+        closed: round.closed,
+        checked,
+        changed,
+        alerts,
+    })
+}
+
+/// 解析 `sksjdd`（如 `第1-15周(单) 星期二 9-10节【3-001】`，多段以 `/`、`;` 分隔）为占用。
+/// 无法识别的段（如线上课程）被忽略。
+pub fn parse_schedule_slots(id: &str, name: &str, text: &str) -> Vec<ScheduleSlot> {
+    static SEGMENT: OnceLock<Regex> = OnceLock::new();
+    let segment = SEGMENT.get_or_init(|| {
+        Regex::new(
+            r"第?\s*([0-9,，\-\s]+)周\s*([(（][单双][)）])?\s*星期([一二三四五六日天])\s*第?\s*(\d+)\s*(?:-\s*(\d+))?\s*节",
+        )
+        .unwrap()
+    });
+    segment
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let weeks = crate::parser::parse_weeks(&format!(
+                "{}{}",
+                caps[1].replace('，', ",").replace(' ', ""),
+                caps.get(2).map_or("", |m| m.as_str())
+            ));
+            let weekday = "一二三四五六日"
+                .chars()
+                .position(|c| caps[3].starts_with(c))
+                .map_or(7, |index| index as i32 + 1);
+            let start = caps[4].parse::<i32>().ok()?;
+            let end = caps
+                .get(5)
+                .and_then(|m| m.as_str().parse::<i32>().ok())
+                .unwrap_or(start);
+            (!weeks.is_empty() && end >= start).then(|| ScheduleSlot {
+                source: SlotSource::Selection,
+                id: id.to_string(),
+                name: name.to_string(),
+                weekday,
+                period: start,
+                djs: end - start + 1,
+                weeks,
+            })
+        })
+        .collect()
+}
+
+/// 待选教学班与本学期现有课表（官方 + 自定义课程）的冲突。
+///
+/// 上课时间取请求中的 `sksjdd`，缺省时查关注列表；都没有时无法检查，返回空列表。
+pub fn selection_conflicts<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &crate::CourseSelectionSelectRequest,
+) -> Result<Vec<ScheduleConflict>, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let zjxbid = non_empty(req.zjxbid.as_deref());
+    let watched = load_round(path, sid, req.pcid.trim())?.and_then(|round| {
+        round
+            .items
+            .into_iter()
+            .find(|item| item.matches(req.jxbid.trim(), zjxbid))
+    });
+    let schedule = non_empty(req.sksjdd.as_deref())
+        .map(str::to_string)
+        .or_else(|| watched.as_ref().map(|item| item.schedule.clone()))
+        .unwrap_or_default();
+    let name = non_empty(req.kcmc.as_deref())
+        .map(str::to_string)
+        .or_else(|| watched.map(|item| item.kcmc))
+        .unwrap_or_else(|| req.jxbid.trim().to_string());
+
+    let candidates = parse_schedule_slots(req.jxbid.trim(), &name, &schedule);
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    schedule_analysis::candidate_conflicts(path, sid, &candidates)
+}
+
+/// 冲突的简短描述（选课被拦截时的错误信息，前端据 `与现有课表冲突` 前缀提供忽略冲突重试）。
+pub fn describe_conflicts(conflicts: &[ScheduleConflict]) -> String {
+    const WEEKDAYS: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+    let details = conflicts
+        .iter()
+        .map(|conflict| {
+            let weekday = usize::try_from(conflict.weekday - 1)
+                .ok()
+                .and_then(|index| WEEKDAYS.get(index))
+                .unwrap_or(&"?");
+            format!(
+                "星期{} 第{}-{}节与「{}」",
+                weekday, conflict.periods.0, conflict.periods.1, conflict.right.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("；");
+    format!("与现有课表冲突：{details}。确认仍要选课请选择忽略冲突后重试")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("mini_hbut_course_watch_{label}_{nanos}.db"));
+        init_db(&path).unwrap();
+        path
+    }
+
+    fn watch_request(jxbid: &str, zjxbid: Option<&str>) -> CourseSelectionWatchRequest {
+        CourseSelectionWatchRequest {
+            pcid: "pc1".to_string(),
+            pcenc: "enc".to_string(),
+            kklx: "10".to_string(),
+            jxbid: jxbid.to_string(),
+            zjxbid: zjxbid.map(str::to_string),
+            kcmc: Some("数据库原理".to_string()),
+            sksjdd: Some("第1-16周 星期二 9-10节【3-001】".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn capacity_parsing_matches_frontend_rules() {
+        let open = parse_capacity("58/60", "35");
+        assert_eq!(
+            (open.selected, open.total, open.full),
+            (Some(58), Some(60), false)
+        );
+        assert_eq!(open.remaining(), Some(2));
+        assert!(open.has_seat());
+
+        assert!(parse_capacity("60／60", "").full);
+        assert!(parse_capacity("已选 12 容量 30（已满）", "").full);
+        assert!(parse_capacity("12/30", "0").full);
+        let single = parse_capacity("40", "0");
+        assert_eq!((single.selected, single.total), (Some(40), Some(40)));
+        assert!(!parse_capacity("--", "").has_seat());
+    }
+
+    #[test]
+    fn seat_alert_fires_once_and_resets_when_full_again() {
+        let path = temp_db_path("alerts");
+        watch_course(&path, "2024001", &watch_request("jxb1", None)).unwrap();
+        let now = Local::now();
+        let key = ("jxb1".to_string(), String::new());
+        let check = |text: &str| {
+            let observed = BTreeMap::from([(key.clone(), parse_capacity(text, ""))]);
+            record_round_check(&path, "2024001", "pc1", Some(3600), &observed, now).unwrap()
+        };
+
+        assert!(check("60/60").alerts.is_empty());
+        let opened = check("59/60");
+        assert_eq!(opened.alerts.len(), 1);
+        assert!(opened.alerts[0].body().contains("剩余 1 个名额"));
+        assert!(check("58/60").alerts.is_empty(), "still open: no repeat");
+        assert!(check("60/60").alerts.is_empty());
+        assert_eq!(check("59/60").alerts.len(), 1, "re-armed after full");
+
+        let ended =
+            record_round_check(&path, "2024001", "pc1", Some(0), &BTreeMap::new(), now).unwrap();
+        assert!(ended.closed);
+        watch_course(&path, "2024001", &watch_request("jxb1", None)).unwrap();
+        assert!(!list_watch_rounds(&path, "2024001").unwrap()[0].closed);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn watchlist_is_scoped_per_account_and_round() {
+        let path = temp_db_path("scope");
+        watch_course(&path, "2024001", &watch_request("jxb1", None)).unwrap();
+        watch_course(&path, "2024001", &watch_request("jxb1", Some("child1"))).unwrap();
+        let round = watch_course(&path, "2024001", &watch_request("jxb1", None)).unwrap();
+        assert_eq!(round.items.len(), 2, "re-watching updates in place");
+        assert!(list_watch_rounds(&path, "2024002").unwrap().is_empty());
+
+        let unwatch = |jxbid: Option<&str>, zjxbid: Option<&str>| {
+            let req = CourseSelectionUnwatchRequest {
+                pcid: "pc1".to_string(),
+                jxbid: jxbid.map(str::to_string),
+                zjxbid: zjxbid.map(str::to_string),
+            };
+            unwatch_course(&path, "2024001", &req).unwrap()
+        };
+        assert!(unwatch(Some("jxb1"), Some("child1")));
+        assert!(!unwatch(Some("jxb1"), Some("child1")));
+        assert_eq!(
+            list_watch_rounds(&path, "2024001").unwrap()[0].items.len(),
+            1
+        );
+        assert!(unwatch(None, None));
+        assert!(list_watch_rounds(&path, "2024001").unwrap().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn schedule_text_parses_into_slots() {
+        let slots = parse_schedule_slots(
+            "jxb1",
+            "数据库原理",
+            "第1-15周(单) 星期二 9-10节【3-001】 / 第2-8周 星期日 1-2节【线上】",
+        );
+        assert_eq!(slots.len(), 2);
+        assert_eq!((slots[0].weekday, slots[0].period, slots[0].djs), (2, 9, 2));
+        assert_eq!(slots[0].weeks, vec![1, 3, 5, 7, 9, 11, 13, 15]);
+        assert_eq!((slots[1].weekday, slots[1].weeks.len()), (7, 7));
+        assert!(parse_schedule_slots("jxb2", "网课", "--/--").is_empty());
+    }
+}
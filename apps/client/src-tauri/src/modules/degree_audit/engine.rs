@@ -0,0 +1,866 @@
+//! 学业审核引擎（纯函数，无 IO）。
+//!
+//! 培养方案课程（[`PlanCourse`]）与成绩记录按课程编号、再按课程名匹配，得出各类别
+//! （必修 / 选修 / 通识 / 实践）已获与在修学分、学分缺口、未完成的必修课、仍需重修的
+//! 挂科课程，并按每学期可修学分上限估算最早满足毕业要求的学期。
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::grade::domain::{GradeOutcome, GradeRecord};
+
+/// 及格线（教务未给出获得学分时用于判定是否通过）。
+const PASS_SCORE: f64 = 60.0;
+
+/// 每学期可修学分上限缺省值。
+pub const DEFAULT_MAX_CREDITS_PER_TERM: f64 = 25.0;
+
+/// 每学期可修学分上限的取值范围；入参超过上界时拒绝，估算时再夹到此范围内。
+pub const MIN_CREDITS_PER_TERM: f64 = 1.0;
+pub const MAX_CREDITS_PER_TERM: f64 = 60.0;
+
+/// 毕业估算最多推算的学期数（十年），超出时截断并在 notes 中说明。
+const MAX_TERMS_NEEDED: u32 = 20;
+
+/// 课程性质代码 → 名称（教务下拉选项缓存缺失时兜底，与前端 TrainingPlanView 一致）。
+const COURSE_NATURE_FALLBACK: &[(&str, &str)] = &[
+    ("11", "通识教育必修课"),
+    ("12", "通识教育选修课"),
+    ("16", "限定性选修课"),
+    ("31", "学科基础课"),
+    ("32", "工程基础课"),
+    ("40", "专业核心课"),
+    ("41", "专业方向组选课"),
+    ("42", "专业任选课"),
+    ("43", "专业基础课"),
+    ("44", "专业必修课"),
+    ("45", "专业选修课"),
+    ("50", "基础实践"),
+    ("51", "专业实践"),
+    ("52", "综合实践"),
+    ("53", "其他实践"),
+    ("54", "短学期实践"),
+    ("70", "辅修双学位理论"),
+    ("71", "辅修双学位实践"),
+    ("90", "必修"),
+    ("98", "重修课"),
+    ("99", "公共选修课"),
+];
+
+/// 审核类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    /// 必修
+    Required,
+    /// 选修
+    Elective,
+    /// 通识
+    General,
+    /// 实践
+    Practice,
+}
+
+impl AuditCategory {
+    pub const ALL: [AuditCategory; 4] = [
+        AuditCategory::Required,
+        AuditCategory::Elective,
+        AuditCategory::General,
+        AuditCategory::Practice,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Required => "必修",
+            Self::Elective => "选修",
+            Self::General => "通识",
+            Self::Practice => "实践",
+        }
+    }
+
+    /// 按课程性质 / 进度节点名归类（实践 > 通识 > 必修 > 选修）。
+    ///
+    /// 数字性质代码先经 [`course_nature_label`] 转为名称；`required` 为培养方案的「是否必修」。
+    pub fn classify(nature: &str, required: bool) -> Self {
+        let nature = course_nature_label(nature);
+        if ["实践", "实习", "实训", "课程设计", "毕业设计", "毕业论文"]
+            .iter()
+            .any(|key| nature.contains(key))
+        {
+            Self::Practice
+        } else if ["通识", "公选", "公共选修", "素质"]
+            .iter()
+            .any(|key| nature.contains(key))
+        {
+            Self::General
+        } else if required || nature.contains("必修") {
+            Self::Required
+        } else {
+            Self::Elective
+        }
+    }
+}
+
+/// 课程性质代码转名称；非数字或未知代码原样返回。
+pub fn course_nature_label(nature: &str) -> &str {
+    let nature = nature.trim();
+    COURSE_NATURE_FALLBACK
+        .iter()
+        .find(|(code, _)| *code == nature)
+        .map(|(_, label)| *label)
+        .unwrap_or(nature)
+}
+
+/// 培养方案中的一门课（来自培养方案课程缓存或学业进度 `kcList`）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanCourse {
+    pub code: String,
+    pub name: String,
+    pub credit: f64,
+    pub category: AuditCategory,
+    /// 是否必修（未完成时列入待修必修课，挂科时列入待重修）
+    pub required: bool,
+    /// 培养方案建议修读学期（YYYY-YYYY-N）
+    pub suggested_term: Option<String>,
+}
+
+/// 审核选项（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditOptions {
+    /// 覆盖各类别最低学分要求（优先于学业进度与培养方案推导）
+    pub requirements: BTreeMap<AuditCategory, f64>,
+    /// 每学期可修学分上限，缺省 [`DEFAULT_MAX_CREDITS_PER_TERM`]，
+    /// 取值夹在 [`MIN_CREDITS_PER_TERM`]..=[`MAX_CREDITS_PER_TERM`]
+    pub max_credits_per_term: Option<f64>,
+}
+
+/// 类别学分要求来源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementSource {
+    /// 请求中显式指定
+    Override,
+    /// 学业进度树节点的最低学分（yqzdxf）
+    Progress,
+    /// 培养方案中该类别必修课学分合计
+    Plan,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryProgress {
+    pub category: AuditCategory,
+    pub label: String,
+    /// 最低学分要求（无从得知时为 `None`，不计入毕业估算）
+    pub required_credits: Option<f64>,
+    pub source: Option<RequirementSource>,
+    pub earned_credits: f64,
+    /// 待录入 / 缓考课程学分（出成绩且通过后计入）
+    pub in_progress_credits: f64,
+    pub gap: Option<f64>,
+}
+
+/// 培养方案课程的修读状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CourseStatus {
+    Passed,
+    /// 待录入 / 缓考
+    InProgress,
+    /// 修读过但从未通过
+    Failed,
+    NotTaken,
+}
+
+/// 尚未完成的必修课。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutstandingCourse {
+    pub code: String,
+    pub name: String,
+    pub category: AuditCategory,
+    pub credit: f64,
+    pub status: CourseStatus,
+    pub suggested_term: Option<String>,
+}
+
+/// 挂科且从未通过、仍需重修的必修课。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetakeCourse {
+    pub code: String,
+    pub name: String,
+    pub category: AuditCategory,
+    pub credit: f64,
+    pub attempts: usize,
+    pub last_term: String,
+    pub last_score: String,
+    /// 是否在培养方案中匹配到（未匹配时按成绩的课程性质判定必修）
+    pub in_plan: bool,
+}
+
+/// 已通过但未匹配到培养方案的课程（按成绩的课程性质计入类别）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnmatchedCourse {
+    pub term: String,
+    pub code: String,
+    pub name: String,
+    pub category: AuditCategory,
+    pub credit: f64,
+}
+
+/// 毕业时间估算。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraduationEstimate {
+    /// 已知学分要求均已满足且无待重修、在修课程
+    pub requirements_met: bool,
+    /// 最早满足要求的学期（YYYY-YYYY-N）；成绩中没有任何学期时为 `None`
+    pub earliest_term: Option<String>,
+    /// 扣除在修学分后仍缺的学分
+    pub remaining_credits: f64,
+    /// 最新成绩学期之后还需的学期数
+    pub terms_needed: u32,
+    pub max_credits_per_term: f64,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DegreeAudit {
+    pub categories: Vec<CategoryProgress>,
+    pub earned_credits: f64,
+    pub outstanding_required: Vec<OutstandingCourse>,
+    pub retakes_needed: Vec<RetakeCourse>,
+    pub unmatched_passed: Vec<UnmatchedCourse>,
+    pub estimate: GraduationEstimate,
+}
+
+/// 单条成绩的审核口径。
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AttemptState {
+    Passed(f64),
+    InProgress,
+    Failed,
+}
+
+fn attempt_state(record: &GradeRecord) -> AttemptState {
+    let credit = parse_credit(&record.course_credit);
+    let earned = record.earned_credit.trim().parse::<f64>().ok();
+    match record.outcome() {
+        GradeOutcome::Exempt => AttemptState::Passed(earned.filter(|v| *v > 0.0).unwrap_or(credit)),
+        GradeOutcome::Pending | GradeOutcome::Deferred => AttemptState::InProgress,
+        GradeOutcome::Absent => AttemptState::Failed,
+        GradeOutcome::Normal | GradeOutcome::Retake => {
+            if let Some(earned) = earned.filter(|v| *v > 0.0) {
+                return AttemptState::Passed(earned);
+            }
+            match record.numeric_score() {
+                Some(score) if score >= PASS_SCORE => AttemptState::Passed(credit),
+                Some(_) => AttemptState::Failed,
+                None if earned.is_some() => AttemptState::Failed,
+                // 无法识别的定性成绩：等教务给出学分再判定
+                None => AttemptState::InProgress,
+            }
+        }
+    }
+}
+
+/// 同一课程的全部修读记录。
+#[derive(Debug, Default)]
+struct Attempts<'a> {
+    records: Vec<&'a GradeRecord>,
+    passed_credit: Option<f64>,
+    in_progress: bool,
+}
+
+impl<'a> Attempts<'a> {
+    fn push(&mut self, record: &'a GradeRecord) {
+        match attempt_state(record) {
+            AttemptState::Passed(credit) => {
+                self.passed_credit = Some(self.passed_credit.unwrap_or(0.0).max(credit));
+            }
+            AttemptState::InProgress => self.in_progress = true,
+            AttemptState::Failed => {}
+        }
+        self.records.push(record);
+    }
+
+    fn status(&self) -> CourseStatus {
+        if self.passed_credit.is_some() {
+            CourseStatus::Passed
+        } else if self.in_progress {
+            CourseStatus::InProgress
+        } else if self.records.is_empty() {
+            CourseStatus::NotTaken
+        } else {
+            CourseStatus::Failed
+        }
+    }
+
+    fn last(&self) -> Option<&'a GradeRecord> {
+        self.records
+            .iter()
+            .copied()
+            .max_by_key(|record| parse_term(&record.term))
+    }
+}
+
+/// 匹配用课程编号：去空白、统一大写。
+fn code_key(code: &str) -> String {
+    code.split_whitespace().collect::<String>().to_uppercase()
+}
+
+/// 匹配用课程名：去空白、全角括号转半角、统一大写。
+fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '（' => '(',
+            '）' => ')',
+            other => other,
+        })
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn grade_code(record: &GradeRecord) -> String {
+    record
+        .course_code
+        .as_deref()
+        .or(record.kcbh.as_deref())
+        .map(code_key)
+        .unwrap_or_default()
+}
+
+fn parse_credit(text: &str) -> f64 {
+    text.trim().parse::<f64>().unwrap_or(0.0).max(0.0)
+}
+
+/// 解析学年学期 `YYYY-YYYY-N` 为 `(起始年, 学期序号)`。
+pub fn parse_term(term: &str) -> Option<(i32, u32)> {
+    let mut parts = term.trim().split('-');
+    let start = parts.next()?.parse::<i32>().ok()?;
+    let end = parts.next()?.parse::<i32>().ok()?;
+    let index = parts.next()?.parse::<u32>().ok()?;
+    (end == start + 1 && (1..=3).contains(&index) && parts.next().is_none())
+        .then_some((start, index))
+}
+
+fn format_term((start, index): (i32, u32)) -> String {
+    format!("{}-{}-{}", start, start + 1, index)
+}
+
+/// 下一个常规学期（第 1 学期 → 第 2 学期 → 下一学年第 1 学期；短学期视同第 2 学期）。
+fn next_term((start, index): (i32, u32)) -> (i32, u32) {
+    if index == 1 {
+        (start, 2)
+    } else {
+        (start + 1, 1)
+    }
+}
+
+/// 培养方案建议学期：`kkxq` 已是学年学期时原样使用；否则按入学年份 + 年级（第几学年）+
+/// 学期序号推算，`kkxq` 为 1..=12 的总学期序号时不需要年级。
+pub fn suggested_term(entry_year: Option<i32>, grade: &str, semester: &str) -> Option<String> {
+    if let Some(term) = parse_term(semester) {
+        return Some(format_term(term));
+    }
+    let entry_year = entry_year?;
+    let semester = semester.trim().parse::<i32>().ok()?;
+    let year_of_study = grade
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|g| (1..=6).contains(g));
+    let (offset, index) = match year_of_study {
+        Some(year) if (1..=2).contains(&semester) => (year - 1, semester),
+        _ if (1..=12).contains(&semester) => ((semester - 1) / 2, (semester - 1) % 2 + 1),
+        _ => return None,
+    };
+    Some(format_term((entry_year + offset, index as u32)))
+}
+
+/// 执行审核。
+///
+/// `progress_minimums` 为学业进度树按类别汇总的最低学分；类别要求的优先级为
+/// 请求覆盖 > 学业进度 > 培养方案必修课学分合计。
+pub fn audit(
+    plan: &[PlanCourse],
+    progress_minimums: &BTreeMap<AuditCategory, f64>,
+    grades: &[GradeRecord],
+    options: &AuditOptions,
+) -> DegreeAudit {
+    let mut by_code = HashMap::new();
+    let mut by_name = HashMap::new();
+    for (index, course) in plan.iter().enumerate() {
+        let code = code_key(&course.code);
+        if !code.is_empty() {
+            by_code.entry(code).or_insert(index);
+        }
+        by_name.entry(name_key(&course.name)).or_insert(index);
+    }
+
+    let mut matched = plan.iter().map(|_| Attempts::default()).collect::<Vec<_>>();
+    // 未匹配课程按编号（缺失时按课程名）归并多次修读
+    let mut unmatched: BTreeMap<String, Attempts> = BTreeMap::new();
+    for record in grades {
+        let code = grade_code(record);
+        let name = name_key(&record.course_name);
+        let index = by_code
+            .get(&code)
+            .filter(|_| !code.is_empty())
+            .or_else(|| by_name.get(&name))
+            .copied();
+        match index {
+            Some(index) => matched[index].push(record),
+            None => {
+                let key = if code.is_empty() { name } else { code };
+                unmatched.entry(key).or_default().push(record);
+            }
+        }
+    }
+
+    let mut earned = BTreeMap::<AuditCategory, f64>::new();
+    let mut in_progress = BTreeMap::<AuditCategory, f64>::new();
+    let mut outstanding_required = Vec::new();
+    let mut retakes_needed = Vec::new();
+    for (course, attempts) in plan.iter().zip(&matched) {
+        let status = attempts.status();
+        match status {
+            CourseStatus::Passed => {
+                let credit = if course.credit > 0.0 {
+                    course.credit
+                } else {
+                    attempts.passed_credit.unwrap_or(0.0)
+                };
+                *earned.entry(course.category).or_default() += credit;
+            }
+            CourseStatus::InProgress => {
+                *in_progress.entry(course.category).or_default() += course.credit;
+            }
+            CourseStatus::Failed | CourseStatus::NotTaken => {}
+        }
+        if !course.required || status == CourseStatus::Passed {
+            continue;
+        }
+        outstanding_required.push(OutstandingCourse {
+            code: course.code.clone(),
+            name: course.name.clone(),
+            category: course.category,
+            credit: course.credit,
+            status,
+            suggested_term: course.suggested_term.clone(),
+        });
+        if status == CourseStatus::Failed {
+            retakes_needed.push(retake(attempts, course.category, course.credit, true));
+        }
+    }
+
+    let mut unmatched_passed = Vec::new();
+    for attempts in unmatched.values() {
+        let Some(last) = attempts.last() else {
+            continue;
+        };
+        let required = course_nature_label(&last.course_nature).contains("必修");
+        let category = AuditCategory::classify(&last.course_nature, required);
+        match attempts.status() {
+            CourseStatus::Passed => {
+                let credit = attempts.passed_credit.unwrap_or(0.0);
+                *earned.entry(category).or_default() += credit;
+                unmatched_passed.push(UnmatchedCourse {
+                    term: last.term.clone(),
+                    code: grade_code(last),
+                    name: last.course_name.clone(),
+                    category,
+                    credit,
+                });
+            }
+            CourseStatus::InProgress => {
+                *in_progress.entry(category).or_default() += parse_credit(&last.course_credit);
+            }
+            // 选修课挂科可改修其他课程，只有必修课列入待重修
+            CourseStatus::Failed if required => {
+                let credit = parse_credit(&last.course_credit);
+                retakes_needed.push(retake(attempts, category, credit, false));
+            }
+            CourseStatus::Failed | CourseStatus::NotTaken => {}
+        }
+    }
+
+    let categories = AuditCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let (required_credits, source) =
+                category_requirement(category, plan, progress_minimums, &options.requirements);
+            let earned_credits = round2(earned.get(&category).copied().unwrap_or(0.0));
+            CategoryProgress {
+                category,
+                label: category.label().to_string(),
+                required_credits,
+                source,
+                earned_credits,
+                in_progress_credits: round2(in_progress.get(&category).copied().unwrap_or(0.0)),
+                gap: required_credits.map(|required| round2((required - earned_credits).max(0.0))),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let estimate = estimate_graduation(
+        &categories,
+        &outstanding_required,
+        &retakes_needed,
+        grades,
+        options,
+    );
+    DegreeAudit {
+        earned_credits: round2(categories.iter().map(|c| c.earned_credits).sum()),
+        categories,
+        outstanding_required,
+        retakes_needed,
+        unmatched_passed,
+        estimate,
+    }
+}
+
+fn retake(
+    attempts: &Attempts,
+    category: AuditCategory,
+    credit: f64,
+    in_plan: bool,
+) -> RetakeCourse {
+    let last = attempts.last();
+    RetakeCourse {
+        code: last.map(grade_code).unwrap_or_default(),
+        name: last.map(|r| r.course_name.clone()).unwrap_or_default(),
+        category,
+        credit,
+        attempts: attempts.records.len(),
+        last_term: last.map(|r| r.term.clone()).unwrap_or_default(),
+        last_score: last.map(|r| r.final_score.clone()).unwrap_or_default(),
+        in_plan,
+    }
+}
+
+fn category_requirement(
+    category: AuditCategory,
+    plan: &[PlanCourse],
+    progress_minimums: &BTreeMap<AuditCategory, f64>,
+    overrides: &BTreeMap<AuditCategory, f64>,
+) -> (Option<f64>, Option<RequirementSource>) {
+    if let Some(credits) = overrides.get(&category).filter(|v| **v >= 0.0) {
+        return (Some(*credits), Some(RequirementSource::Override));
+    }
+    if let Some(credits) = progress_minimums.get(&category).filter(|v| **v > 0.0) {
+        return (Some(*credits), Some(RequirementSource::Progress));
+    }
+    // 培养方案只列出可选课程池，选修类学分要求无法由课程合计得出
+    let required_sum = plan
+        .iter()
+        .filter(|course| course.category == category && course.required)
+        .map(|course| course.credit)
+        .sum::<f64>();
+    if required_sum > 0.0 {
+        (Some(round2(required_sum)), Some(RequirementSource::Plan))
+    } else {
+        (None, None)
+    }
+}
+
+fn estimate_graduation(
+    categories: &[CategoryProgress],
+    outstanding: &[OutstandingCourse],
+    retakes: &[RetakeCourse],
+    grades: &[GradeRecord],
+    options: &AuditOptions,
+) -> GraduationEstimate {
+    let max_credits_per_term = options
+        .max_credits_per_term
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .map_or(DEFAULT_MAX_CREDITS_PER_TERM, |v| {
+            v.clamp(MIN_CREDITS_PER_TERM, MAX_CREDITS_PER_TERM)
+        });
+    let mut notes = Vec::new();
+
+    let remaining_credits = round2(
+        categories
+            .iter()
+            .filter_map(|c| {
+                c.required_credits
+                    .map(|required| (required - c.earned_credits - c.in_progress_credits).max(0.0))
+            })
+            .sum(),
+    );
+    let unknown = categories
+        .iter()
+        .filter(|c| c.required_credits.is_none())
+        .map(|c| c.label.as_str())
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        notes.push(format!(
+            "{} 类别缺少学分要求，未计入估算",
+            unknown.join(" / ")
+        ));
+    }
+
+    let mut terms_needed =
+        ((remaining_credits / max_credits_per_term).ceil() as u32).min(MAX_TERMS_NEEDED);
+    if !retakes.is_empty() {
+        terms_needed = terms_needed.max(1);
+        notes.push(format!("{} 门必修课需重修", retakes.len()));
+    }
+    let not_taken = outstanding
+        .iter()
+        .filter(|course| course.status == CourseStatus::NotTaken)
+        .count();
+    if not_taken > 0 {
+        terms_needed = terms_needed.max(1);
+    }
+    let in_progress = categories.iter().any(|c| c.in_progress_credits > 0.0)
+        || outstanding
+            .iter()
+            .any(|course| course.status == CourseStatus::InProgress);
+    if in_progress {
+        notes.push("在修课程按全部通过估算".to_string());
+    }
+
+    let latest = grades.iter().filter_map(|r| parse_term(&r.term)).max();
+    let earliest_term = latest.map(|latest| {
+        let mut term = latest;
+        for _ in 0..terms_needed {
+            term = next_term(term);
+        }
+        // 未修必修课不早于培养方案建议学期
+        let suggested = outstanding
+            .iter()
+            .filter(|course| course.status == CourseStatus::NotTaken)
+            .filter_map(|course| course.suggested_term.as_deref().and_then(parse_term))
+            .max();
+        if let Some(suggested) = suggested.filter(|suggested| *suggested > term) {
+            while term < suggested && terms_needed < MAX_TERMS_NEEDED {
+                term = next_term(term);
+                terms_needed += 1;
+            }
+        }
+        format_term(term)
+    });
+    if latest.is_none() {
+        notes.push("暂无成绩学期，无法推算毕业学期".to_string());
+    }
+    if terms_needed >= MAX_TERMS_NEEDED {
+        notes.push(format!(
+            "所需学期数超过 {MAX_TERMS_NEEDED} 个，仅推算到第 {MAX_TERMS_NEEDED} 个学期"
+        ));
+    }
+
+    GraduationEstimate {
+        requirements_met: remaining_credits == 0.0
+            && outstanding.is_empty()
+            && retakes.is_empty()
+            && !in_progress
+            && unknown.len() < categories.len(),
+        earliest_term,
+        remaining_credits,
+        terms_needed,
+        max_credits_per_term,
+        notes,
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(term: &str, code: &str, name: &str, credit: &str, score: &str) -> GradeRecord {
+        GradeRecord {
+            term: term.to_string(),
+            course_name: name.to_string(),
+            grade_id: Some(format!("{term}-{code}")),
+            course_code: (!code.is_empty()).then(|| code.to_string()),
+            course_nature: "专业必修课".to_string(),
+            course_nature_code: "44".to_string(),
+            course_credit: credit.to_string(),
+            final_score: score.to_string(),
+            earned_credit: String::new(),
+            xfjd: String::new(),
+            sfbk: String::new(),
+            sfsq: String::new(),
+            cjbj: String::new(),
+            teacher: None,
+            kcbh: None,
+            course_teacher: None,
+        }
+    }
+
+    fn plan(code: &str, name: &str, credit: f64, nature: &str, required: bool) -> PlanCourse {
+        PlanCourse {
+            code: code.to_string(),
+            name: name.to_string(),
+            credit,
+            category: AuditCategory::classify(nature, required),
+            required,
+            suggested_term: None,
+        }
+    }
+
+    #[test]
+    fn classifies_categories_by_nature_and_code() {
+        assert_eq!(
+            AuditCategory::classify("44", false),
+            AuditCategory::Required
+        );
+        assert_eq!(AuditCategory::classify("12", false), AuditCategory::General);
+        assert_eq!(
+            AuditCategory::classify("毕业设计（论文）", true),
+            AuditCategory::Practice
+        );
+        assert_eq!(
+            AuditCategory::classify("专业任选课", false),
+            AuditCategory::Elective
+        );
+        assert_eq!(
+            suggested_term(Some(2022), "3", "2").as_deref(),
+            Some("2024-2025-2")
+        );
+        assert_eq!(
+            suggested_term(Some(2022), "", "5").as_deref(),
+            Some("2024-2025-1")
+        );
+        assert_eq!(
+            suggested_term(None, "", "2025-2026-1").as_deref(),
+            Some("2025-2026-1")
+        );
+    }
+
+    #[test]
+    fn matches_by_code_then_name_and_reports_gaps_and_retakes() {
+        let plan = vec![
+            plan("B001", "高等数学A（一）", 5.0, "通识教育必修课", true),
+            plan("B002", "数据结构", 4.0, "专业必修课", true),
+            plan("B003", "操作系统", 3.0, "专业必修课", true),
+            plan("B004", "编译原理", 3.0, "专业必修课", true),
+            plan("E001", "机器学习", 2.0, "专业选修课", false),
+            plan("P001", "生产实习", 2.0, "专业实践", true),
+        ];
+        let grades = vec![
+            // 课程名全角 / 半角括号不同，无编号按名称匹配
+            record("2022-2023-1", "", "高等数学A(一)", "5", "88"),
+            record("2022-2023-2", "b002", "数据结构", "4", "52"),
+            record("2023-2024-1", "B002", "数据结构", "4", "71"),
+            record("2023-2024-1", "B003", "操作系统", "3", "45"),
+            record("2023-2024-2", "B003", "操作系统", "3", "缺考"),
+            record("2023-2024-2", "E001", "机器学习", "2", "优秀"),
+            record("2023-2024-2", "X900", "大学生心理健康", "1", "90"),
+        ];
+        let mut options = AuditOptions::default();
+        options.requirements.insert(AuditCategory::Elective, 6.0);
+        let mut progress = BTreeMap::new();
+        progress.insert(AuditCategory::Required, 12.0);
+
+        let report = audit(&plan, &progress, &grades, &options);
+        let category = |c: AuditCategory| {
+            report
+                .categories
+                .iter()
+                .find(|p| p.category == c)
+                .unwrap()
+                .clone()
+        };
+
+        // 数据结构重修通过；未匹配的必修课按成绩性质计入
+        let required = category(AuditCategory::Required);
+        assert_eq!(required.earned_credits, 5.0);
+        assert_eq!(required.required_credits, Some(12.0));
+        assert_eq!(required.source, Some(RequirementSource::Progress));
+        assert_eq!(required.gap, Some(7.0));
+        assert_eq!(category(AuditCategory::General).earned_credits, 5.0);
+        assert_eq!(
+            category(AuditCategory::General).source,
+            Some(RequirementSource::Plan)
+        );
+        let elective = category(AuditCategory::Elective);
+        assert_eq!((elective.earned_credits, elective.gap), (2.0, Some(4.0)));
+        assert_eq!(
+            category(AuditCategory::Practice).gap,
+            Some(2.0),
+            "实践必修课学分合计作为要求"
+        );
+        assert_eq!(report.unmatched_passed.len(), 1);
+        assert_eq!(report.unmatched_passed[0].name, "大学生心理健康");
+
+        let outstanding = report
+            .outstanding_required
+            .iter()
+            .map(|c| (c.code.as_str(), c.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outstanding,
+            vec![
+                ("B003", CourseStatus::Failed),
+                ("B004", CourseStatus::NotTaken),
+                ("P001", CourseStatus::NotTaken),
+            ]
+        );
+        assert_eq!(report.retakes_needed.len(), 1);
+        assert_eq!(report.retakes_needed[0].attempts, 2);
+        assert_eq!(report.retakes_needed[0].last_term, "2023-2024-2");
+
+        // 缺口 7 + 4 + 2 = 13 学分，每学期上限 25 → 下一学期
+        assert_eq!(report.estimate.remaining_credits, 13.0);
+        assert_eq!(report.estimate.terms_needed, 1);
+        assert_eq!(
+            report.estimate.earliest_term.as_deref(),
+            Some("2024-2025-1")
+        );
+        assert!(!report.estimate.requirements_met);
+    }
+
+    #[test]
+    fn estimate_clamps_tiny_credit_cap_and_bounds_terms() {
+        let grades = vec![record("2023-2024-2", "A", "课程A", "4", "90")];
+        let options = AuditOptions {
+            max_credits_per_term: Some(1e-9),
+            ..AuditOptions::default()
+        };
+        let mut progress = BTreeMap::new();
+        progress.insert(AuditCategory::Required, 100.0);
+
+        let report = audit(&[], &progress, &grades, &options);
+        // 1e-9 夹到 1 学分 / 学期；缺 96 学分只推算 20 个学期
+        assert_eq!(report.estimate.max_credits_per_term, MIN_CREDITS_PER_TERM);
+        assert_eq!(report.estimate.remaining_credits, 96.0);
+        assert_eq!(report.estimate.terms_needed, MAX_TERMS_NEEDED);
+        assert_eq!(
+            report.estimate.earliest_term.as_deref(),
+            Some("2033-2034-2")
+        );
+        assert!(report
+            .estimate
+            .notes
+            .iter()
+            .any(|note| note.contains("超过 20 个")));
+    }
+
+    #[test]
+    fn estimate_waits_for_suggested_term_and_credit_cap() {
+        let mut late = plan("P009", "毕业设计", 8.0, "综合实践", true);
+        late.suggested_term = Some("2025-2026-2".to_string());
+        let grades = vec![record("2023-2024-2", "A", "课程A", "4", "90")];
+        let options = AuditOptions {
+            max_credits_per_term: Some(20.0),
+            ..AuditOptions::default()
+        };
+        let mut progress = BTreeMap::new();
+        progress.insert(AuditCategory::Required, 30.0);
+
+        let report = audit(&[late], &progress, &grades, &options);
+        // 缺 26 + 8 学分、每学期 20 → 2 学期到 2024-2025-2，毕业设计建议学期更晚
+        assert_eq!(report.estimate.remaining_credits, 34.0);
+        assert_eq!(
+            report.estimate.earliest_term.as_deref(),
+            Some("2025-2026-2")
+        );
+        assert_eq!(report.estimate.terms_needed, 4);
+    }
+}
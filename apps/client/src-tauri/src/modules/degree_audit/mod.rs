@@ -0,0 +1,357 @@
+//! 学业审核：培养方案 × 学业进度 × 成绩记录的对账。
+//!
+//! 纯计算见 [`engine`]；本模块只从本地缓存装载数据（`training_plan_cache` 中已查询过的
+//! 课程列表分页、`academic_progress_cache` 学业进度树、`grades_cache` 成绩），不发起网络
+//! 请求，离线可用。Tauri Command 与 HTTP Bridge 共用。
+
+mod engine;
+
+pub use engine::{
+    audit, course_nature_label, parse_term, suggested_term, AuditCategory, AuditOptions,
+    CategoryProgress, CourseStatus, DegreeAudit, GraduationEstimate, OutstandingCourse, PlanCourse,
+    RequirementSource, RetakeCourse, UnmatchedCourse, DEFAULT_MAX_CREDITS_PER_TERM,
+    MAX_CREDITS_PER_TERM,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::db;
+use crate::grade::domain::GradeRecord;
+use crate::modules::training_plan::Course;
+
+/// 学业审核请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DegreeAuditRequest {
+    pub student_id: Option<String>,
+    /// 学业进度方案（与 `fetch_academic_progress` 一致），缺省 1
+    pub fasz: Option<i32>,
+    #[serde(flatten)]
+    pub options: AuditOptions,
+}
+
+/// 审核所用的缓存来源。
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditSources {
+    /// 参与匹配的培养方案课程数（培养方案缓存 + 学业进度课程去重）
+    pub plan_courses: usize,
+    pub training_plan_sync_time: Option<String>,
+    pub progress_sync_time: Option<String>,
+    pub grades_sync_time: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DegreeAuditReport {
+    #[serde(flatten)]
+    pub audit: DegreeAudit,
+    pub sources: AuditSources,
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(v) => v.as_f64(),
+        Value::String(v) => v.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(v)) => v.trim().to_string(),
+        Some(Value::Number(v)) => v.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// 课程去重键：有编号按编号，否则按课程名。
+fn course_key(code: &str, name: &str) -> String {
+    let code = code.trim().to_uppercase();
+    if code.is_empty() {
+        format!("name:{}", name.split_whitespace().collect::<String>())
+    } else {
+        format!("code:{code}")
+    }
+}
+
+/// 学业进度树装载结果。
+#[derive(Default)]
+struct ProgressTree {
+    minimums: BTreeMap<AuditCategory, f64>,
+    courses: Vec<PlanCourse>,
+}
+
+/// 遍历进度树：子节点带学分要求时只累计子节点，避免父子节点重复计入；
+/// `kcList` 课程按所在节点名归类。
+fn walk_progress(nodes: &[Value], tree: &mut ProgressTree) {
+    for node in nodes {
+        let name = text(node.get("nodeName"));
+        let category = AuditCategory::classify(&name, false);
+        let children = node
+            .get("children")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let children_have_requirement = children
+            .iter()
+            .any(|child| number(child.get("yqzdxf")).is_some_and(|v| v > 0.0));
+        if !children_have_requirement {
+            if let Some(min) = number(node.get("yqzdxf")).filter(|v| *v > 0.0) {
+                *tree.minimums.entry(category).or_default() += min;
+            }
+        }
+        for course in node
+            .get("kcList")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let course_name = text(course.get("kcmc"));
+            if course_name.is_empty() {
+                continue;
+            }
+            let nature = course_nature_label(&text(course.get("kcxz"))).to_string();
+            tree.courses.push(PlanCourse {
+                code: text(course.get("kcbh")),
+                name: course_name,
+                credit: number(course.get("xf")).unwrap_or(0.0),
+                category,
+                required: category == AuditCategory::Required
+                    || name.contains("必修")
+                    || nature.contains("必修"),
+                suggested_term: None,
+            });
+        }
+        walk_progress(children, tree);
+    }
+}
+
+/// 入学年份：最早成绩学期的起始年，缺失时按学号前两位推断。
+fn entry_year(student_id: &str, grades: &[GradeRecord]) -> Option<i32> {
+    grades
+        .iter()
+        .filter_map(|record| parse_term(&record.term))
+        .map(|(start, _)| start)
+        .min()
+        .or_else(|| {
+            let prefix = student_id.get(..2)?;
+            prefix.parse::<i32>().ok().map(|year| 2000 + year)
+        })
+}
+
+/// 培养方案课程：合并缓存中所有已查询过的课程列表分页并去重。
+fn load_plan_courses(
+    path: &Path,
+    sid: &str,
+    entry_year: Option<i32>,
+) -> Result<(Vec<PlanCourse>, Option<String>), String> {
+    // 课程性质下拉选项：把数字代码转为名称
+    let nature_labels = db::get_cache(path, "training_plan_cache", &format!("{sid}:options"))
+        .map_err(|e| e.to_string())?
+        .and_then(|(data, _)| data.pointer("/options/kcxz").cloned())
+        .and_then(|options| options.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .map(|option| (text(option.get("value")), text(option.get("label"))))
+        .filter(|(value, label)| !value.is_empty() && !label.is_empty())
+        .collect::<HashMap<_, _>>();
+
+    let pages = db::list_cache_by_prefix(path, "training_plan_cache", &format!("{sid}:courses:"))
+        .map_err(|e| e.to_string())?;
+    let mut seen = HashSet::new();
+    let mut courses = Vec::new();
+    let mut sync_time = None;
+    for (_, data, synced) in pages {
+        if data.get("success").and_then(Value::as_bool) == Some(false) {
+            continue;
+        }
+        let rows = data.get("data").and_then(Value::as_array);
+        for course in rows.into_iter().flatten().filter_map(Course::from_plan_row) {
+            if !seen.insert(course_key(&course.code, &course.name)) {
+                continue;
+            }
+            let nature = nature_labels
+                .get(&course.course_type)
+                .map(String::as_str)
+                .unwrap_or_else(|| course_nature_label(&course.course_type));
+            let required = course.required || nature.contains("必修");
+            courses.push(PlanCourse {
+                suggested_term: suggested_term(entry_year, &course.grade, &course.semester),
+                category: AuditCategory::classify(nature, required),
+                credit: course.credit.trim().parse::<f64>().unwrap_or(0.0),
+                code: course.code,
+                name: course.name,
+                required,
+            });
+        }
+        sync_time = sync_time.max(Some(synced));
+    }
+    Ok((courses, sync_time))
+}
+
+/// 基于本地缓存执行学业审核。
+pub fn run_degree_audit<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &DegreeAuditRequest,
+) -> Result<DegreeAuditReport, String> {
+    let path = path.as_ref();
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    if let Some(max) = req.options.max_credits_per_term {
+        if !max.is_finite() || max > MAX_CREDITS_PER_TERM {
+            return Err(format!(
+                "max_credits_per_term 不能超过 {MAX_CREDITS_PER_TERM}（每学期学分上限）"
+            ));
+        }
+    }
+
+    let (grades_payload, grades_sync_time) = db::get_cache(path, "grades_cache", sid)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "暂无成绩缓存，请先同步成绩".to_string())?;
+    let grades: Vec<GradeRecord> = grades_payload
+        .get("data")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("成绩数据解析失败: {e}"))?
+        .unwrap_or_default();
+
+    let fasz = req.fasz.unwrap_or(1);
+    let progress = db::get_cache(path, "academic_progress_cache", &format!("{sid}:{fasz}"))
+        .map_err(|e| e.to_string())?;
+    let mut tree = ProgressTree::default();
+    if let Some(nodes) = progress
+        .as_ref()
+        .and_then(|(data, _)| data.pointer("/data/tree"))
+        .and_then(Value::as_array)
+    {
+        walk_progress(nodes, &mut tree);
+    }
+
+    let (plan_courses, training_plan_sync_time) =
+        load_plan_courses(path, sid, entry_year(sid, &grades))?;
+    if plan_courses.is_empty() && tree.courses.is_empty() && tree.minimums.is_empty() {
+        return Err("暂无培养方案或学业进度缓存，请先在线查询一次".to_string());
+    }
+
+    // 学业进度按个人方案归类，优先于培养方案课程列表
+    let mut seen = HashSet::new();
+    let plan = tree
+        .courses
+        .into_iter()
+        .chain(plan_courses)
+        .filter(|course| seen.insert(course_key(&course.code, &course.name)))
+        .collect::<Vec<_>>();
+
+    let audit = audit(&plan, &tree.minimums, &grades, &req.options);
+    Ok(DegreeAuditReport {
+        audit,
+        sources: AuditSources {
+            plan_courses: plan.len(),
+            training_plan_sync_time,
+            progress_sync_time: progress.map(|(_, sync_time)| sync_time),
+            grades_sync_time,
+        },
+    })
+}
+
+/// 组装学业审核返回体（两通道一致）。
+pub fn build_degree_audit_payload(report: DegreeAuditReport) -> Value {
+    serde_json::json!({
+        "success": true,
+        "offline": true,
+        "data": report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_degree_audit_{label}_{nanos}.db"))
+    }
+
+    #[test]
+    fn audits_from_cached_progress_plan_pages_and_grades() {
+        let path = temp_db_path("cached");
+        init_db(&path).unwrap();
+        let sid = "2220000001";
+        assert!(run_degree_audit(&path, sid, &DegreeAuditRequest::default()).is_err());
+
+        db::save_cache(
+            &path,
+            "grades_cache",
+            sid,
+            &serde_json::json!({ "success": true, "data": [
+                { "term": "2022-2023-1", "course_name": "大学英语", "course_nature": "通识教育必修课",
+                  "course_nature_code": "11", "course_credit": "3", "final_score": "81",
+                  "earned_credit": "3", "xfjd": "", "sfbk": "", "sfsq": "", "cjbj": "" },
+                { "term": "2023-2024-1", "course_name": "数据结构", "course_code": "B002",
+                  "course_nature": "专业必修课", "course_nature_code": "44", "course_credit": "4",
+                  "final_score": "41", "earned_credit": "0", "xfjd": "", "sfbk": "", "sfsq": "", "cjbj": "" }
+            ]}),
+        )
+        .unwrap();
+        db::save_cache(
+            &path,
+            "academic_progress_cache",
+            &format!("{sid}:1"),
+            &serde_json::json!({ "success": true, "data": { "tree": [
+                { "nodeName": "专业教育", "yqzdxf": "20", "children": [
+                    { "nodeName": "专业必修课", "yqzdxf": "12", "kcList": [
+                        { "kcbh": "B002", "kcmc": "数据结构", "xf": "4", "kcxz": "44" }
+                    ]}
+                ]},
+                { "nodeName": "通识教育必修课", "yqzdxf": 3, "kcList": [
+                    { "kcbh": "G001", "kcmc": "大学英语", "xf": "3" }
+                ]}
+            ]}}),
+        )
+        .unwrap();
+        for page in [1, 2] {
+            db::save_cache(
+                &path,
+                "training_plan_cache",
+                &format!("{sid}:courses::::::::{page}:50"),
+                &serde_json::json!({ "success": true, "data": [
+                    { "id": "1", "kcbh": "B002", "kcmc": "数据结构", "kcxz": "44", "sfbx": "是", "xf": "4" },
+                    { "id": format!("p{page}"), "kcbh": format!("B10{page}"), "kcmc": format!("课程{page}"),
+                      "kcxz": "45", "sfbx": "否", "xf": "2", "gradename": "3", "kkxq": "1" }
+                ]}),
+            )
+            .unwrap();
+        }
+
+        let report = run_degree_audit(&path, sid, &DegreeAuditRequest::default()).unwrap();
+        // 进度课程 2 门 + 两页培养方案去重后新增 2 门
+        assert_eq!(report.sources.plan_courses, 4);
+        assert!(report.sources.training_plan_sync_time.is_some());
+        let required = &report.audit.categories[0];
+        assert_eq!(required.category, AuditCategory::Required);
+        // 只取子节点要求，父节点 20 学分不重复计入
+        assert_eq!(required.required_credits, Some(12.0));
+        assert_eq!(required.gap, Some(12.0));
+        let general = &report.audit.categories[2];
+        assert_eq!((general.earned_credits, general.gap), (3.0, Some(0.0)));
+        assert_eq!(report.audit.retakes_needed.len(), 1);
+        assert_eq!(report.audit.retakes_needed[0].name, "数据结构");
+
+        let payload = build_degree_audit_payload(report);
+        assert_eq!(payload["data"]["sources"]["plan_courses"], 4);
+        assert_eq!(payload["data"]["estimate"]["requirements_met"], false);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
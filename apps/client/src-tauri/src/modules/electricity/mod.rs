@@ -4,9 +4,20 @@
 //! 1. 封装 Fusion Portal (能耗系统) 的 API。
 //! 2. 提供位置层级 (Location) 的递归查询 (区域 -> 楼栋 -> 楼层 -> 房间)。
 //! 3. 查询特定房间的电费账户余额和状态。
+//! 4. 关注房间的余额读数、用电估算与低余额提醒 (见 [`monitor`])。
 //!
 //! 注意: 使用前必须先通过 OAuth 流程获取 Token (由 `http_client.rs` 负责)。
 
+mod monitor;
+
+pub use monitor::{
+    build_overview_payload, check_room, decode_share_code, estimate_daily_usage, evaluate_alerts,
+    list_watched_rooms, normalize_location, record_account_response, room_id, share_code,
+    unwatch_room, watch_room, AlertState, BalanceReading, ElectricityAlert, ElectricityAlertKind,
+    ElectricityWatchRequest, RoomCheck, WatchedRoom, DEFAULT_ALERT_DAYS, DEFAULT_THRESHOLD_BALANCE,
+    MAX_WATCHED_ROOMS,
+};
+
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -28,6 +39,63 @@ pub struct ElectricityBalance {
     pub room_name: String,
 }
 
+/// 解析 `/server/utilities/account` 响应 (余额/电量/供电状态)。
+///
+/// `HbutClient::query_electricity_account` 返回原始 JSON，后台监控与读数记录经此统一解析。
+pub fn parse_account(json: &Value) -> ElectricityBalance {
+    if !json
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let msg = json
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("未知错误");
+        return ElectricityBalance {
+            success: false,
+            balance: "0.00".to_string(),
+            quantity: "0.00".to_string(),
+            status: msg.to_string(),
+            room_name: String::new(),
+        };
+    }
+
+    // 解析结果
+    let result_data = json.get("resultData").unwrap_or(&Value::Null);
+    let template_list = result_data.get("templateList").and_then(|v| v.as_array());
+
+    let mut balance = "0.00".to_string();
+    let mut quantity = "0.00".to_string();
+
+    if let Some(items) = template_list {
+        for item in items {
+            let code = item.get("code").and_then(|v| v.as_str()).unwrap_or("");
+            let value = item.get("value").and_then(|v| v.as_str()).unwrap_or("0.00");
+
+            match code {
+                "balance" => balance = value.to_string(),
+                "quantity" => quantity = value.to_string(),
+                _ => {}
+            }
+        }
+    }
+
+    let status_name = result_data
+        .get("utilityStatusName")
+        .and_then(|v| v.as_str())
+        .unwrap_or("未知")
+        .to_string();
+
+    ElectricityBalance {
+        success: true,
+        balance,
+        quantity,
+        status: status_name,
+        room_name: String::new(),
+    }
+}
+
 /// 位置节点信息 (区域/楼栋/房间通用)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationItem {
@@ -121,58 +189,7 @@ impl ElectricityModule {
         }
 
         let json: Value = response.json().await?;
-
-        if !json
-            .get("success")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false)
-        {
-            let msg = json
-                .get("message")
-                .and_then(|v| v.as_str())
-                .unwrap_or("未知错误");
-            return Ok(ElectricityBalance {
-                success: false,
-                balance: "0.00".to_string(),
-                quantity: "0.00".to_string(),
-                status: msg.to_string(),
-                room_name: String::new(),
-            });
-        }
-
-        // 解析结果
-        let result_data = json.get("resultData").unwrap_or(&Value::Null);
-        let template_list = result_data.get("templateList").and_then(|v| v.as_array());
-
-        let mut balance = "0.00".to_string();
-        let mut quantity = "0.00".to_string();
-
-        if let Some(items) = template_list {
-            for item in items {
-                let code = item.get("code").and_then(|v| v.as_str()).unwrap_or("");
-                let value = item.get("value").and_then(|v| v.as_str()).unwrap_or("0.00");
-
-                match code {
-                    "balance" => balance = value.to_string(),
-                    "quantity" => quantity = value.to_string(),
-                    _ => {}
-                }
-            }
-        }
-
-        let status_name = result_data
-            .get("utilityStatusName")
-            .and_then(|v| v.as_str())
-            .unwrap_or("未知")
-            .to_string();
-
-        Ok(ElectricityBalance {
-            success: true,
-            balance,
-            quantity,
-            status: status_name,
-            room_name: String::new(),
-        })
+        Ok(parse_account(&json))
     }
 
     pub async fn get_root_areas(
@@ -0,0 +1,753 @@
+//! 电费余额监控：关注房间、余额读数时间序列、日均用电估算与可用天数预测。
+//!
+//! 房间以查询位置（区域 / 楼栋 / 楼层 / 房间）标识，不依赖一码通的房间绑定：
+//! 室友导入同一房间的分享码即可在各自设备上跟踪。关注配置与提醒去重状态存于
+//! kv_store `electricity.room.<room_id>`，读数写入 `electricity_readings`。
+//!
+//! 与考试跟踪一样，只有后台任务（[`check_room`]）评估并推进提醒状态；
+//! 前台查询余额（[`record_account_response`]）只追加读数，不会吞掉待发送的提醒。
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use super::parse_account;
+use crate::db::{self, ElectricityReadingRecord};
+
+/// 最多关注的房间数（每个房间每次检查都会请求一次上游）。
+pub const MAX_WATCHED_ROOMS: usize = 8;
+/// 默认低余额提醒阈值（元）。
+pub const DEFAULT_THRESHOLD_BALANCE: f64 = 10.0;
+/// 默认「预计可用天数」提醒阈值（天）。
+pub const DEFAULT_ALERT_DAYS: u32 = 3;
+
+const ROOM_KV_PREFIX: &str = "electricity.room.";
+const SHARE_CODE_PREFIX: &str = "HBUTE1.";
+/// 参与房间标识与查询的位置字段（与前端选择器提交的 payload 一致）。
+const LOCATION_FIELDS: [&str; 8] = [
+    "utilityType",
+    "bigArea",
+    "area",
+    "building",
+    "unit",
+    "level",
+    "room",
+    "subArea",
+];
+const MAX_THRESHOLD_BALANCE: f64 = 1000.0;
+const MAX_ALERT_DAYS: u32 = 60;
+const MAX_LABEL_CHARS: usize = 32;
+/// 日均用电估算窗口。
+const ESTIMATE_WINDOW_SECONDS: i64 = 14 * 24 * 3600;
+/// 估算所需的最短有效观测时长，避免两次相邻刷新得出离谱的日均值。
+const MIN_ESTIMATE_SECONDS: i64 = 12 * 3600;
+/// 同一余额在该间隔内重复出现时不追加读数（前台反复刷新）。
+const DEDUPE_SECONDS: i64 = 30 * 60;
+const RETENTION_SECONDS: i64 = 180 * 24 * 3600;
+const MAX_HISTORY_DAYS: u32 = 180;
+
+/// 提醒去重状态：条件成立时只提醒一次，条件解除（如充值）后复位。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertState {
+    #[serde(default)]
+    pub low_balance: bool,
+    #[serde(default)]
+    pub forecast: bool,
+}
+
+/// 关注的房间（kv_store 持久化）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchedRoom {
+    pub id: String,
+    pub label: String,
+    /// 规范化后的查询位置，可直接作为 `query_electricity_account` 的 payload
+    pub location: Value,
+    /// 余额低于该值（元）时提醒；0 表示关闭
+    pub threshold_balance: f64,
+    /// 预计可用天数低于该值时提醒；0 表示关闭
+    pub alert_days: u32,
+    pub added_at: String,
+    #[serde(default)]
+    pub alerts: AlertState,
+}
+
+/// 关注 / 更新房间请求（Tauri / Bridge 入参）：`location` 与 `share_code` 二选一。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ElectricityWatchRequest {
+    pub location: Option<Value>,
+    pub share_code: Option<String>,
+    pub label: Option<String>,
+    pub threshold_balance: Option<f64>,
+    pub alert_days: Option<u32>,
+}
+
+/// 对外输出的读数。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BalanceReading {
+    pub recorded_at: String,
+    pub balance: f64,
+    pub quantity: f64,
+}
+
+impl From<&ElectricityReadingRecord> for BalanceReading {
+    fn from(record: &ElectricityReadingRecord) -> Self {
+        Self {
+            recorded_at: Local
+                .timestamp_opt(record.recorded_at, 0)
+                .single()
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            balance: record.balance,
+            quantity: record.quantity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElectricityAlertKind {
+    LowBalance,
+    Forecast,
+}
+
+/// 待推送的电费提醒。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ElectricityAlert {
+    pub kind: ElectricityAlertKind,
+    pub room_id: String,
+    pub label: String,
+    pub balance: f64,
+    pub days_remaining: Option<f64>,
+}
+
+impl ElectricityAlert {
+    pub fn title(&self) -> String {
+        match self.kind {
+            ElectricityAlertKind::LowBalance => "电费余额不足".to_string(),
+            ElectricityAlertKind::Forecast => "电费即将用完".to_string(),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let forecast = self
+            .days_remaining
+            .map(|days| format!("，按近期用电预计还能用 {days:.1} 天"))
+            .unwrap_or_default();
+        format!(
+            "{} 当前余额 {:.2} 元{}，请及时充值",
+            self.label, self.balance, forecast
+        )
+    }
+}
+
+/// 后台检查单个房间的结论。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoomCheck {
+    pub room_id: String,
+    pub balance: f64,
+    pub daily_usage: Option<f64>,
+    pub days_remaining: Option<f64>,
+    pub alerts: Vec<ElectricityAlert>,
+}
+
+/// 校验并规范化查询位置：只保留位置字段、统一为字符串，`room` 必填。
+pub fn normalize_location(location: &Value) -> Result<Value, String> {
+    let object = location
+        .as_object()
+        .ok_or_else(|| "房间位置格式错误".to_string())?;
+    let mut normalized = Map::new();
+    for field in LOCATION_FIELDS {
+        let value = match object.get(field) {
+            Some(Value::String(text)) => text.trim().to_string(),
+            Some(Value::Number(number)) => number.to_string(),
+            _ => String::new(),
+        };
+        normalized.insert(field.to_string(), Value::String(value));
+    }
+    if normalized["utilityType"].as_str().unwrap_or("").is_empty() {
+        normalized.insert(
+            "utilityType".to_string(),
+            Value::String("electric".to_string()),
+        );
+    }
+    if normalized["room"].as_str().unwrap_or("").is_empty() {
+        return Err("请先选择到具体房间".to_string());
+    }
+    Ok(Value::Object(normalized))
+}
+
+/// 房间标识：由规范化位置派生，同一房间在不同设备、不同账号下一致。
+pub fn room_id(location: &Value) -> String {
+    let key = LOCATION_FIELDS
+        .iter()
+        .map(|field| location.get(field).and_then(Value::as_str).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("|");
+    format!("e{:x}", md5::compute(key))
+}
+
+/// 房间分享码：携带名称与位置，供室友导入后各自跟踪。
+pub fn share_code(room: &WatchedRoom) -> String {
+    let payload = serde_json::json!({ "label": room.label, "location": room.location });
+    format!(
+        "{SHARE_CODE_PREFIX}{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(payload.to_string())
+    )
+}
+
+/// 解析分享码，返回（名称，规范化位置）。
+pub fn decode_share_code(code: &str) -> Result<(String, Value), String> {
+    let invalid = || "分享码无效".to_string();
+    let encoded = code
+        .trim()
+        .strip_prefix(SHARE_CODE_PREFIX)
+        .ok_or_else(invalid)?;
+    let bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|_| invalid())?;
+    let payload: Value = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+    let location = normalize_location(payload.get("location").ok_or_else(invalid)?)?;
+    let label = payload
+        .get("label")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string();
+    Ok((label, location))
+}
+
+fn room_key(room_id: &str) -> String {
+    format!("{ROOM_KV_PREFIX}{room_id}")
+}
+
+fn load_room(path: &Path, room_id: &str) -> Result<Option<WatchedRoom>, String> {
+    db::get_kv(path, &room_key(room_id))
+        .map_err(|e| e.to_string())
+        .map(|raw| raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+fn save_room(path: &Path, room: &WatchedRoom) -> Result<(), String> {
+    let raw = serde_json::to_string(room).map_err(|e| e.to_string())?;
+    db::set_kv(path, &room_key(&room.id), &raw).map_err(|e| e.to_string())
+}
+
+/// 全部关注房间（按添加时间排序）。
+pub fn list_watched_rooms<P: AsRef<Path>>(path: P) -> Result<Vec<WatchedRoom>, String> {
+    let mut rooms = db::list_kv_by_prefix(path, ROOM_KV_PREFIX)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|(_, raw)| serde_json::from_str::<WatchedRoom>(&raw).ok())
+        .collect::<Vec<_>>();
+    rooms.sort_by(|a, b| a.added_at.cmp(&b.added_at).then(a.id.cmp(&b.id)));
+    Ok(rooms)
+}
+
+/// 关注房间或更新提醒设置；重复关注同一房间时保留原有读数。
+pub fn watch_room<P: AsRef<Path>>(
+    path: P,
+    req: &ElectricityWatchRequest,
+) -> Result<WatchedRoom, String> {
+    let path = path.as_ref();
+    let (shared_label, location) = match (&req.location, req.share_code.as_deref()) {
+        (Some(location), _) => (String::new(), normalize_location(location)?),
+        (None, Some(code)) if !code.trim().is_empty() => decode_share_code(code)?,
+        _ => return Err("请提供房间位置或分享码".to_string()),
+    };
+    let id = room_id(&location);
+    let existing = load_room(path, &id)?;
+    if existing.is_none() && list_watched_rooms(path)?.len() >= MAX_WATCHED_ROOMS {
+        return Err(format!("最多关注 {MAX_WATCHED_ROOMS} 个房间"));
+    }
+
+    let threshold_balance = req
+        .threshold_balance
+        .or(existing.as_ref().map(|room| room.threshold_balance))
+        .unwrap_or(DEFAULT_THRESHOLD_BALANCE);
+    if !threshold_balance.is_finite() || !(0.0..=MAX_THRESHOLD_BALANCE).contains(&threshold_balance)
+    {
+        return Err(format!(
+            "余额提醒阈值需在 0 到 {MAX_THRESHOLD_BALANCE} 元之间"
+        ));
+    }
+    let alert_days = req
+        .alert_days
+        .or(existing.as_ref().map(|room| room.alert_days))
+        .unwrap_or(DEFAULT_ALERT_DAYS);
+    if alert_days > MAX_ALERT_DAYS {
+        return Err(format!("可用天数提醒阈值需在 0 到 {MAX_ALERT_DAYS} 天之间"));
+    }
+
+    let label = [
+        req.label.as_deref().unwrap_or(""),
+        existing
+            .as_ref()
+            .map(|room| room.label.as_str())
+            .unwrap_or(""),
+        shared_label.as_str(),
+    ]
+    .into_iter()
+    .map(str::trim)
+    .find(|label| !label.is_empty())
+    .map(|label| label.chars().take(MAX_LABEL_CHARS).collect::<String>())
+    .unwrap_or_else(|| default_label(&location));
+
+    let room = WatchedRoom {
+        id,
+        label,
+        location,
+        threshold_balance,
+        alert_days,
+        added_at: existing
+            .as_ref()
+            .map(|room| room.added_at.clone())
+            .unwrap_or_else(|| Local::now().to_rfc3339()),
+        // 阈值可能已变化，由下一次检查重新评估
+        alerts: AlertState::default(),
+    };
+    save_room(path, &room)?;
+    Ok(room)
+}
+
+fn default_label(location: &Value) -> String {
+    format!(
+        "房间 {}",
+        location.get("room").and_then(Value::as_str).unwrap_or("")
+    )
+}
+
+/// 取消关注并删除该房间的读数；房间不存在时返回 false。
+pub fn unwatch_room<P: AsRef<Path>>(path: P, room_id: &str) -> Result<bool, String> {
+    let path = path.as_ref();
+    let room_id = room_id.trim();
+    if load_room(path, room_id)?.is_none() {
+        return Ok(false);
+    }
+    db::delete_kv(path, &room_key(room_id)).map_err(|e| e.to_string())?;
+    db::delete_electricity_readings(path, room_id).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+fn parse_amount(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// 追加读数（去重 + 保留期清理），返回是否写入。
+fn record_reading(
+    path: &Path,
+    room_id: &str,
+    balance: f64,
+    quantity: f64,
+    now: i64,
+) -> Result<bool, String> {
+    let latest = db::latest_electricity_reading(path, room_id).map_err(|e| e.to_string())?;
+    if latest.is_some_and(|last| {
+        now - last.recorded_at < DEDUPE_SECONDS && (last.balance - balance).abs() < 0.005
+    }) {
+        return Ok(false);
+    }
+    db::insert_electricity_reading(
+        path,
+        &ElectricityReadingRecord {
+            room_id: room_id.to_string(),
+            recorded_at: now,
+            balance,
+            quantity,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    db::prune_electricity_readings(path, room_id, now - RETENTION_SECONDS)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 前台余额查询成功后调用：查询位置属于关注房间时追加一条读数。
+pub fn record_account_response<P: AsRef<Path>>(
+    path: P,
+    location: &Value,
+    response: &Value,
+    now: i64,
+) -> Result<bool, String> {
+    let path = path.as_ref();
+    let Ok(location) = normalize_location(location) else {
+        return Ok(false);
+    };
+    let id = room_id(&location);
+    if load_room(path, &id)?.is_none() {
+        return Ok(false);
+    }
+    let account = parse_account(response);
+    match (account.success, parse_amount(&account.balance)) {
+        (true, Some(balance)) => record_reading(
+            path,
+            &id,
+            balance,
+            parse_amount(&account.quantity).unwrap_or(0.0),
+            now,
+        ),
+        _ => Ok(false),
+    }
+}
+
+/// 日均消耗（元/天）：累计相邻读数间的余额下降，跳过充值区间；
+/// 有效观测不足 12 小时时返回 `None`。
+pub fn estimate_daily_usage(readings: &[ElectricityReadingRecord]) -> Option<f64> {
+    let mut consumed = 0.0;
+    let mut elapsed = 0_i64;
+    for pair in readings.windows(2) {
+        let (prev, next) = (&pair[0], &pair[1]);
+        let span = next.recorded_at - prev.recorded_at;
+        // 充值区间内的实际消耗无法还原，整段不计入
+        if span <= 0 || next.balance > prev.balance {
+            continue;
+        }
+        consumed += prev.balance - next.balance;
+        elapsed += span;
+    }
+    (elapsed >= MIN_ESTIMATE_SECONDS).then(|| consumed * 86400.0 / elapsed as f64)
+}
+
+fn days_remaining(balance: f64, daily_usage: Option<f64>) -> Option<f64> {
+    daily_usage
+        .filter(|daily| *daily > 0.0)
+        .map(|daily| (balance.max(0.0) / daily * 10.0).round() / 10.0)
+}
+
+/// 评估提醒并更新去重状态；两项同时新触发时只推送低余额提醒。
+pub fn evaluate_alerts(
+    room: &mut WatchedRoom,
+    balance: f64,
+    days_remaining: Option<f64>,
+) -> Vec<ElectricityAlert> {
+    let low = room.threshold_balance > 0.0 && balance < room.threshold_balance;
+    let short =
+        room.alert_days > 0 && days_remaining.is_some_and(|days| days < f64::from(room.alert_days));
+
+    let kind = if low && !room.alerts.low_balance {
+        Some(ElectricityAlertKind::LowBalance)
+    } else if short && !room.alerts.forecast && !room.alerts.low_balance {
+        Some(ElectricityAlertKind::Forecast)
+    } else {
+        None
+    };
+    room.alerts = AlertState {
+        low_balance: low,
+        forecast: short,
+    };
+    kind.map(|kind| ElectricityAlert {
+        kind,
+        room_id: room.id.clone(),
+        label: room.label.clone(),
+        balance,
+        days_remaining,
+    })
+    .into_iter()
+    .collect()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// 后台检查：解析上游余额响应、追加读数、更新预测并评估提醒。
+pub fn check_room<P: AsRef<Path>>(
+    path: P,
+    room: &WatchedRoom,
+    response: &Value,
+    now: i64,
+) -> Result<RoomCheck, String> {
+    let path = path.as_ref();
+    let account = parse_account(response);
+    if !account.success {
+        return Err(format!("{}: {}", room.label, account.status));
+    }
+    let balance = parse_amount(&account.balance)
+        .ok_or_else(|| format!("{}: 余额格式异常 {}", room.label, account.balance))?;
+    record_reading(
+        path,
+        &room.id,
+        balance,
+        parse_amount(&account.quantity).unwrap_or(0.0),
+        now,
+    )?;
+
+    let readings = db::list_electricity_readings(path, &room.id, now - ESTIMATE_WINDOW_SECONDS)
+        .map_err(|e| e.to_string())?;
+    let daily_usage = estimate_daily_usage(&readings);
+    let days_remaining = days_remaining(balance, daily_usage);
+
+    // 以最新存储的设置为准（检查期间用户可能修改了阈值或取消了关注）
+    let alerts = match load_room(path, &room.id)? {
+        Some(mut current) => {
+            let alerts = evaluate_alerts(&mut current, balance, days_remaining);
+            save_room(path, &current)?;
+            alerts
+        }
+        None => Vec::new(),
+    };
+
+    Ok(RoomCheck {
+        room_id: room.id.clone(),
+        balance,
+        daily_usage: daily_usage.map(round2),
+        days_remaining,
+        alerts,
+    })
+}
+
+/// 组装关注房间概览（两通道一致）：最新读数、日均消耗、预计可用天数与近 `days` 天历史。
+pub fn build_overview_payload<P: AsRef<Path>>(
+    path: P,
+    room_id: Option<&str>,
+    days: Option<u32>,
+    now: i64,
+) -> Result<Value, String> {
+    let path = path.as_ref();
+    let days = days.unwrap_or(14).clamp(1, MAX_HISTORY_DAYS);
+    let room_id = room_id.map(str::trim).filter(|id| !id.is_empty());
+    let rooms = list_watched_rooms(path)?
+        .into_iter()
+        .filter(|room| room_id.is_none_or(|id| id == room.id))
+        .map(|room| {
+            let since = now - ESTIMATE_WINDOW_SECONDS.max(i64::from(days) * 86400);
+            let readings =
+                db::list_electricity_readings(path, &room.id, since).map_err(|e| e.to_string())?;
+            let latest = readings.last();
+            let estimate_from = latest.map_or(now, |r| r.recorded_at) - ESTIMATE_WINDOW_SECONDS;
+            let recent = readings
+                .iter()
+                .filter(|r| r.recorded_at >= estimate_from)
+                .cloned()
+                .collect::<Vec<_>>();
+            let daily_usage = estimate_daily_usage(&recent);
+            let history_from = now - i64::from(days) * 86400;
+            Ok(serde_json::json!({
+                "id": room.id,
+                "label": room.label,
+                "location": room.location,
+                "threshold_balance": room.threshold_balance,
+                "alert_days": room.alert_days,
+                "added_at": room.added_at,
+                "share_code": share_code(&room),
+                "latest": latest.map(BalanceReading::from),
+                "daily_usage": daily_usage.map(round2),
+                "days_remaining": latest.and_then(|r| days_remaining(r.balance, daily_usage)),
+                "history": readings
+                    .iter()
+                    .filter(|r| r.recorded_at >= history_from)
+                    .map(BalanceReading::from)
+                    .collect::<Vec<_>>(),
+            }))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if let Some(id) = room_id {
+        if rooms.is_empty() {
+            return Err(format!("未关注房间 {id}"));
+        }
+    }
+    Ok(serde_json::json!({
+        "success": true,
+        "count": rooms.len(),
+        "rooms": rooms,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const HOUR: i64 = 3600;
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_electricity_monitor_{label}_{nanos}.db"))
+    }
+
+    fn location(room: &str) -> Value {
+        serde_json::json!({
+            "utilityType": "electric",
+            "bigArea": "",
+            "area": "1",
+            "building": "12",
+            "unit": "",
+            "level": "3",
+            "room": room,
+            "subArea": "",
+        })
+    }
+
+    fn account(balance: &str) -> Value {
+        serde_json::json!({
+            "success": true,
+            "resultData": {
+                "utilityStatusName": "正常供电",
+                "templateList": [
+                    { "code": "balance", "value": balance },
+                    { "code": "quantity", "value": "20.00" },
+                ],
+            },
+        })
+    }
+
+    fn reading(hours: i64, balance: f64) -> ElectricityReadingRecord {
+        ElectricityReadingRecord {
+            room_id: "e1".to_string(),
+            recorded_at: hours * HOUR,
+            balance,
+            quantity: 0.0,
+        }
+    }
+
+    #[test]
+    fn daily_usage_skips_top_ups_and_needs_enough_observation() {
+        // 24 小时内 50 → 44，充值到 94，再 24 小时降到 88
+        let readings = [
+            reading(0, 50.0),
+            reading(12, 47.0),
+            reading(24, 44.0),
+            reading(30, 94.0),
+            reading(54, 88.0),
+        ];
+        let daily = estimate_daily_usage(&readings).unwrap();
+        assert!((daily - 6.0).abs() < 1e-9, "{daily}");
+        assert_eq!(days_remaining(88.0, Some(daily)), Some(14.7));
+
+        assert_eq!(
+            estimate_daily_usage(&[reading(0, 50.0), reading(6, 49.0)]),
+            None
+        );
+        // 长期无消耗不预测
+        assert_eq!(days_remaining(30.0, Some(0.0)), None);
+    }
+
+    #[test]
+    fn share_code_round_trips_to_the_same_room() {
+        let path = temp_db_path("share");
+        init_db(&path).unwrap();
+        let mine = watch_room(
+            &path,
+            &ElectricityWatchRequest {
+                location: Some(location("305")),
+                label: Some("12栋305".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(mine.threshold_balance, DEFAULT_THRESHOLD_BALANCE);
+
+        let code = share_code(&mine);
+        let (label, decoded) = decode_share_code(&code).unwrap();
+        assert_eq!(label, "12栋305");
+        assert_eq!(room_id(&decoded), mine.id);
+        assert!(decode_share_code("HBUTE1.not-json").is_err());
+
+        // 室友设备：导入分享码得到同一房间标识
+        let mate_path = temp_db_path("share_mate");
+        init_db(&mate_path).unwrap();
+        let mate = watch_room(
+            &mate_path,
+            &ElectricityWatchRequest {
+                share_code: Some(code),
+                alert_days: Some(5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            (mate.id.as_str(), mate.label.as_str()),
+            (mine.id.as_str(), "12栋305")
+        );
+        assert_eq!(mate.alert_days, 5);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&mate_path);
+    }
+
+    #[test]
+    fn check_room_alerts_once_until_balance_recovers() {
+        let path = temp_db_path("alerts");
+        init_db(&path).unwrap();
+        let room = watch_room(
+            &path,
+            &ElectricityWatchRequest {
+                location: Some(location("306")),
+                threshold_balance: Some(10.0),
+                alert_days: Some(3),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let first = check_room(&path, &room, &account("30.00"), 0).unwrap();
+        assert!(first.alerts.is_empty());
+        assert_eq!(first.days_remaining, None);
+
+        // 一天用掉 12 元：预计 1.5 天用完 → 预测提醒
+        let second = check_room(&path, &room, &account("18.00"), 24 * HOUR).unwrap();
+        assert_eq!(second.daily_usage, Some(12.0));
+        assert_eq!(second.alerts.len(), 1);
+        assert_eq!(second.alerts[0].kind, ElectricityAlertKind::Forecast);
+
+        // 跌破阈值 → 低余额提醒；之后不再重复
+        let third = check_room(&path, &room, &account("8.00"), 36 * HOUR).unwrap();
+        assert_eq!(third.alerts[0].kind, ElectricityAlertKind::LowBalance);
+        assert!(check_room(&path, &room, &account("7.50"), 40 * HOUR)
+            .unwrap()
+            .alerts
+            .is_empty());
+
+        // 充值后复位，再次跌破时重新提醒
+        check_room(&path, &room, &account("100.00"), 41 * HOUR).unwrap();
+        let again = check_room(&path, &room, &account("9.00"), 200 * HOUR).unwrap();
+        assert_eq!(again.alerts[0].kind, ElectricityAlertKind::LowBalance);
+
+        let overview = build_overview_payload(&path, Some(&room.id), Some(30), 200 * HOUR).unwrap();
+        assert_eq!(overview["rooms"][0]["history"].as_array().unwrap().len(), 6);
+
+        assert!(unwatch_room(&path, &room.id).unwrap());
+        assert!(list_watched_rooms(&path).unwrap().is_empty());
+        assert!(db::latest_electricity_reading(&path, &room.id)
+            .unwrap()
+            .is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn foreground_queries_record_readings_only_for_watched_rooms() {
+        let path = temp_db_path("record");
+        init_db(&path).unwrap();
+        assert!(!record_account_response(&path, &location("401"), &account("20.00"), 0).unwrap());
+
+        let room = watch_room(
+            &path,
+            &ElectricityWatchRequest {
+                location: Some(location("401")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(record_account_response(&path, &location("401"), &account("20.00"), 0).unwrap());
+        // 短时间内同一余额不重复记录
+        assert!(!record_account_response(&path, &location("401"), &account("20.00"), 60).unwrap());
+        // 前台查询不推进提醒状态
+        assert_eq!(
+            list_watched_rooms(&path).unwrap()[0].alerts,
+            AlertState::default()
+        );
+        assert_eq!(
+            db::list_electricity_readings(&path, &room.id, 0)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
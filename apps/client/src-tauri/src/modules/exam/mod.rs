@@ -4,9 +4,19 @@
 //! 主要功能：
 //! 1. 查询指定学期的考试安排。
 //! 2. 解析考场、座位号、考试时间等关键信息。
+//! 3. 按学期持久化考试快照，识别变更并生成倒计时与提前提醒（见 [`tracker`]）。
 //!
 //! API: 通常涉及教务系统的 `kwgl` (考务管理) 路径。
 
+mod tracker;
+
+pub use tracker::{
+    build_countdown_payload, countdown, diff_exams, due_reminders, load_cached_exams,
+    load_reminder_leads, save_reminder_leads, track_exams, tracking_semester, ExamChange,
+    ExamChangeKind, ExamCheckResult, ExamCountdown, ExamField, ExamFieldChange, ExamReminder,
+    ExamSnapshot, DEFAULT_REMINDER_LEADS, EXAM_SNAPSHOT_TABLE,
+};
+
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,7 +24,7 @@ use serde_json::Value;
 const JWXT_BASE_URL: &str = "https://jwxt.hbut.edu.cn";
 
 /// 考试信息实体
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Exam {
     /// 课程名称
     pub course_name: String,
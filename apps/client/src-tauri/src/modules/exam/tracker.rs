@@ -0,0 +1,754 @@
+//! 考试安排跟踪：按学期持久化快照，识别新增考试与时间 / 考场 / 座位号变化，
+//! 并按提前量（默认 7 天 / 1 天 / 2 小时）生成提醒。
+//!
+//! 快照存于 `exam_snapshot_cache`（键 `<学号>:<学期>`），同时记录已发送的提醒键：
+//! 同一场考试的同一提前量只提醒一次；考试改期后提醒键随开考时间变化，会重新提醒。
+//! 与成绩快照一样，只有后台检查推进快照，前台 `fetch_exams` 不会吞掉待通报的变化。
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::Exam;
+use crate::db;
+use crate::modules::ics_calendar::parse_clock;
+
+/// 考试快照表（与 `exams_cache` 分离）。
+pub const EXAM_SNAPSHOT_TABLE: &str = "exam_snapshot_cache";
+
+/// 默认提醒提前量（分钟）：7 天 / 1 天 / 2 小时。
+pub const DEFAULT_REMINDER_LEADS: [u32; 3] = [7 * 24 * 60, 24 * 60, 2 * 60];
+
+const REMINDER_LEADS_KEY: &str = "exam.reminder_leads";
+const MAX_REMINDER_LEADS: usize = 5;
+const MAX_LEAD_MINUTES: u32 = 30 * 24 * 60;
+
+impl Exam {
+    /// 由教务接口记录构造；`semester` 为快照所属学期。
+    pub fn from_record(exam: &crate::Exam, semester: &str) -> Self {
+        let exam_time = match (exam.start_time.trim(), exam.end_time.trim()) {
+            ("", _) => String::new(),
+            (start, "") => start.to_string(),
+            (start, end) => format!("{start}-{end}"),
+        };
+        Self {
+            course_name: exam.course_name.trim().to_string(),
+            exam_date: exam.date.trim().to_string(),
+            exam_time,
+            location: exam.location.trim().to_string(),
+            address: String::new(),
+            seat_number: exam
+                .seat_number
+                .as_deref()
+                .map(str::trim)
+                .filter(|seat| !seat.is_empty())
+                .map(str::to_string),
+            exam_type: exam.exam_type.trim().to_string(),
+            semester: semester.to_string(),
+        }
+    }
+
+    /// 身份键：学期 + 课程 + 考试性质（正常考试与补考分开跟踪）。
+    pub fn tracking_key(&self) -> String {
+        format!(
+            "{}|{}|{}",
+            self.semester.trim(),
+            self.course_name.trim(),
+            self.exam_type.trim()
+        )
+    }
+
+    /// 开考时间（本地时间）；时间段缺失时按第 1 节开始计算。
+    pub fn starts_at(&self) -> Option<NaiveDateTime> {
+        let date = self.exam_date.trim();
+        let date = NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d").ok()?;
+        let start = self
+            .exam_time
+            .split('-')
+            .next()
+            .and_then(parse_clock)
+            .or_else(|| NaiveTime::from_hms_opt(8, 20, 0))?;
+        Some(date.and_time(start))
+    }
+
+    fn room(&self) -> &str {
+        if self.location.trim().is_empty() {
+            self.address.trim()
+        } else {
+            self.location.trim()
+        }
+    }
+
+    fn schedule_text(&self) -> String {
+        format!("{} {}", self.exam_date.trim(), self.exam_time.trim())
+            .trim()
+            .to_string()
+    }
+
+    fn seat_text(&self) -> &str {
+        self.seat_number.as_deref().map(str::trim).unwrap_or("")
+    }
+}
+
+/// 变化的考试字段。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExamField {
+    Time,
+    Location,
+    Seat,
+}
+
+impl ExamField {
+    fn label(self) -> &'static str {
+        match self {
+            ExamField::Time => "时间",
+            ExamField::Location => "考场",
+            ExamField::Seat => "座位号",
+        }
+    }
+}
+
+/// 单个字段的新旧值。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExamFieldChange {
+    pub field: ExamField,
+    pub previous: String,
+    pub current: String,
+}
+
+/// 考试变化类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExamChangeKind {
+    /// 新排出的考试
+    New,
+    /// 时间 / 考场 / 座位号变化
+    Updated,
+}
+
+/// 单场考试的变化（每条对应一条通知）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExamChange {
+    pub kind: ExamChangeKind,
+    pub exam: Exam,
+    pub fields: Vec<ExamFieldChange>,
+}
+
+impl ExamChange {
+    pub fn title(&self) -> String {
+        match self.kind {
+            ExamChangeKind::New => "新的考试安排".to_string(),
+            ExamChangeKind::Updated => "考试安排变更".to_string(),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self.kind {
+            ExamChangeKind::New => {
+                let details = [self.exam.schedule_text().as_str(), self.exam.room()]
+                    .iter()
+                    .filter(|part| !part.is_empty())
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join("，");
+                format!("{}：{}", self.exam.course_name, details)
+            }
+            ExamChangeKind::Updated => {
+                let details = self
+                    .fields
+                    .iter()
+                    .map(|change| {
+                        format!(
+                            "{} {} → {}",
+                            change.field.label(),
+                            or_pending(&change.previous),
+                            or_pending(&change.current)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("；");
+                format!("{}：{}", self.exam.course_name, details)
+            }
+        }
+    }
+}
+
+fn or_pending(value: &str) -> &str {
+    if value.trim().is_empty() {
+        "未定"
+    } else {
+        value
+    }
+}
+
+/// 比对上一次快照与最新考试列表；消失的考试不通报。
+pub fn diff_exams(previous: &[Exam], current: &[Exam]) -> Vec<ExamChange> {
+    let previous = previous
+        .iter()
+        .map(|exam| (exam.tracking_key(), exam))
+        .collect::<HashMap<_, _>>();
+    current
+        .iter()
+        .filter_map(|exam| match previous.get(&exam.tracking_key()) {
+            None => Some(ExamChange {
+                kind: ExamChangeKind::New,
+                exam: exam.clone(),
+                fields: Vec::new(),
+            }),
+            Some(old) => {
+                let fields = [
+                    (ExamField::Time, old.schedule_text(), exam.schedule_text()),
+                    (
+                        ExamField::Location,
+                        old.room().to_string(),
+                        exam.room().to_string(),
+                    ),
+                    (
+                        ExamField::Seat,
+                        old.seat_text().to_string(),
+                        exam.seat_text().to_string(),
+                    ),
+                ]
+                .into_iter()
+                .filter(|(_, previous, current)| previous != current)
+                .map(|(field, previous, current)| ExamFieldChange {
+                    field,
+                    previous,
+                    current,
+                })
+                .collect::<Vec<_>>();
+                (!fields.is_empty()).then(|| ExamChange {
+                    kind: ExamChangeKind::Updated,
+                    exam: exam.clone(),
+                    fields,
+                })
+            }
+        })
+        .collect()
+}
+
+/// 一条到期的考试提醒。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExamReminder {
+    pub exam: Exam,
+    /// 触发的提前量（分钟）
+    pub lead_minutes: u32,
+    pub minutes_left: i64,
+    /// 本次提醒覆盖的提醒键（含被跳过的更早提前量）
+    #[serde(skip)]
+    pub keys: Vec<String>,
+}
+
+impl ExamReminder {
+    pub fn title(&self) -> String {
+        "考试提醒".to_string()
+    }
+
+    pub fn body(&self) -> String {
+        let mut details = vec![self.exam.schedule_text()];
+        if !self.exam.room().is_empty() {
+            details.push(self.exam.room().to_string());
+        }
+        if !self.exam.seat_text().is_empty() {
+            details.push(format!("座位 {}", self.exam.seat_text()));
+        }
+        format!(
+            "距 {} 开考还有 {}（{}）",
+            self.exam.course_name,
+            remaining_text(self.minutes_left),
+            details.join("，")
+        )
+    }
+}
+
+fn remaining_text(minutes: i64) -> String {
+    if minutes >= 24 * 60 {
+        format!("{} 天", minutes / (24 * 60))
+    } else if minutes >= 60 {
+        format!("{} 小时", minutes / 60)
+    } else {
+        format!("{} 分钟", minutes.max(1))
+    }
+}
+
+fn reminder_key(exam: &Exam, starts_at: NaiveDateTime, lead: u32) -> String {
+    format!(
+        "{}|{}|{lead}",
+        exam.tracking_key(),
+        starts_at.format("%Y-%m-%dT%H:%M")
+    )
+}
+
+/// 到期提醒：同一场考试只取已进入的最小提前量，更早的提前量一并标记为已提醒，
+/// 避免首次检查时连发多条。
+pub fn due_reminders(
+    exams: &[Exam],
+    leads: &[u32],
+    notified: &BTreeSet<String>,
+    now: NaiveDateTime,
+) -> Vec<ExamReminder> {
+    let mut leads = leads.to_vec();
+    leads.sort_unstable();
+    leads.dedup();
+
+    let mut reminders = Vec::new();
+    for exam in exams {
+        let Some(starts_at) = exam.starts_at().filter(|starts_at| *starts_at > now) else {
+            continue;
+        };
+        let minutes_left = (starts_at - now).num_minutes();
+        let entered = leads
+            .iter()
+            .copied()
+            .filter(|lead| minutes_left <= i64::from(*lead))
+            .collect::<Vec<_>>();
+        let Some(&lead) = entered.first() else {
+            continue;
+        };
+        if notified.contains(&reminder_key(exam, starts_at, lead)) {
+            continue;
+        }
+        reminders.push(ExamReminder {
+            exam: exam.clone(),
+            lead_minutes: lead,
+            minutes_left,
+            keys: entered
+                .iter()
+                .map(|lead| reminder_key(exam, starts_at, *lead))
+                .collect(),
+        });
+    }
+    reminders
+}
+
+/// 持久化的考试快照。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExamSnapshot {
+    pub semester: String,
+    pub exams: Vec<Exam>,
+    /// 已发送的提醒键
+    #[serde(default)]
+    pub notified: BTreeSet<String>,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// 一次后台检查的结果。
+#[derive(Debug, Clone, Default)]
+pub struct ExamCheckResult {
+    pub changes: Vec<ExamChange>,
+    pub reminders: Vec<ExamReminder>,
+    /// 首次检查：仅建立基线，不产出变化（提醒照常）
+    pub baseline: bool,
+}
+
+/// 比对并推进快照。`offline` 表示 `exams` 来自离线缓存：只计算提醒，不比对、不替换快照中的考试列表。
+pub fn track_exams<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    semester: &str,
+    exams: &[Exam],
+    offline: bool,
+    leads: &[u32],
+    now: NaiveDateTime,
+) -> Result<ExamCheckResult, String> {
+    let path = path.as_ref();
+    let key = format!("{}:{}", student_id.trim(), semester);
+    let previous = db::get_cache(path, EXAM_SNAPSHOT_TABLE, &key)
+        .ok()
+        .flatten()
+        .and_then(|(data, _)| serde_json::from_value::<ExamSnapshot>(data).ok());
+
+    let (changes, baseline) = match (&previous, offline) {
+        (_, true) => (Vec::new(), false),
+        (Some(previous), false) => (diff_exams(&previous.exams, exams), false),
+        (None, false) => (Vec::new(), true),
+    };
+    let mut notified = previous
+        .as_ref()
+        .map(|snapshot| snapshot.notified.clone())
+        .unwrap_or_default();
+    let reminders = due_reminders(exams, leads, &notified, now);
+    notified.extend(reminders.iter().flat_map(|r| r.keys.iter().cloned()));
+    // 只保留仍在列表中的考试的提醒键，防止快照无限增长
+    let live = exams
+        .iter()
+        .map(Exam::tracking_key)
+        .collect::<BTreeSet<_>>();
+    notified.retain(|key| {
+        key.rsplitn(3, '|')
+            .nth(2)
+            .is_some_and(|exam_key| live.contains(exam_key))
+    });
+
+    let snapshot = ExamSnapshot {
+        semester: semester.to_string(),
+        exams: match previous {
+            Some(previous) if offline => previous.exams,
+            _ => exams.to_vec(),
+        },
+        notified,
+        updated_at: Local::now().to_rfc3339(),
+    };
+    let payload = serde_json::to_value(&snapshot).map_err(|e| e.to_string())?;
+    db::save_cache(path, EXAM_SNAPSHOT_TABLE, &key, &payload).map_err(|e| e.to_string())?;
+
+    Ok(ExamCheckResult {
+        changes,
+        reminders,
+        baseline,
+    })
+}
+
+/// 快照所属学期：取课表缓存中的学期，缺失时回退 `current`（与 `exams_cache` 键约定一致）。
+pub fn tracking_semester<P: AsRef<Path>>(path: P, student_id: &str) -> String {
+    db::get_cache(path, "schedule_cache", student_id)
+        .ok()
+        .flatten()
+        .and_then(|(payload, _)| {
+            payload
+                .pointer("/meta/semester")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|semester| !semester.is_empty())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "current".to_string())
+}
+
+/// 考试缓存：先按学期键，再回退 `current` 键（`AcademicReadService::fetch_exams` 的缓存约定）。
+pub fn load_cached_exams<P: AsRef<Path>>(path: P, student_id: &str, semester: &str) -> Vec<Exam> {
+    let path = path.as_ref();
+    [semester, "current"]
+        .iter()
+        .find_map(|key| {
+            db::get_cache(path, "exams_cache", &format!("{student_id}:{key}"))
+                .ok()
+                .flatten()
+        })
+        .and_then(|(payload, _)| payload.get("data").cloned())
+        .and_then(|data| serde_json::from_value::<Vec<crate::Exam>>(data).ok())
+        .map(|exams| {
+            exams
+                .iter()
+                .map(|exam| Exam::from_record(exam, semester))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 倒计时条目（按开考时间升序，仅包含未开考的考试）。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExamCountdown {
+    pub course_name: String,
+    pub exam_date: String,
+    pub exam_time: String,
+    pub location: String,
+    pub seat_number: Option<String>,
+    /// `YYYY-MM-DD HH:MM`
+    pub starts_at: String,
+    pub minutes_left: i64,
+    /// 自然日差（今天考试为 0）
+    pub days_left: i64,
+}
+
+pub fn countdown(exams: &[Exam], now: NaiveDateTime) -> Vec<ExamCountdown> {
+    let mut upcoming = exams
+        .iter()
+        .filter_map(|exam| {
+            let starts_at = exam.starts_at().filter(|starts_at| *starts_at > now)?;
+            Some((starts_at, exam))
+        })
+        .collect::<Vec<_>>();
+    upcoming.sort_by_key(|(starts_at, _)| *starts_at);
+    upcoming
+        .into_iter()
+        .map(|(starts_at, exam)| ExamCountdown {
+            course_name: exam.course_name.clone(),
+            exam_date: exam.exam_date.clone(),
+            exam_time: exam.exam_time.clone(),
+            location: exam.room().to_string(),
+            seat_number: exam.seat_number.clone(),
+            starts_at: starts_at.format("%Y-%m-%d %H:%M").to_string(),
+            minutes_left: (starts_at - now).num_minutes(),
+            days_left: (starts_at.date() - now.date()).num_days(),
+        })
+        .collect()
+}
+
+/// 倒计时返回体（两通道一致）：基于 `exams_cache`，附带当前的提醒提前量。
+pub fn build_countdown_payload<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    semester: Option<&str>,
+) -> Value {
+    let path = path.as_ref();
+    let semester = semester
+        .map(str::trim)
+        .filter(|semester| !semester.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| tracking_semester(path, student_id));
+    let exams = load_cached_exams(path, student_id, &semester);
+    serde_json::json!({
+        "success": true,
+        "semester": semester,
+        "data": countdown(&exams, Local::now().naive_local()),
+        "reminder_leads": load_reminder_leads(path),
+    })
+}
+
+/// 读取提醒提前量（分钟，降序）；缺失或损坏时回退默认值。
+pub fn load_reminder_leads<P: AsRef<Path>>(path: P) -> Vec<u32> {
+    db::get_kv(path, REMINDER_LEADS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<Vec<u32>>(&raw).ok())
+        .and_then(|leads| normalize_leads(leads).ok())
+        .unwrap_or_else(|| DEFAULT_REMINDER_LEADS.to_vec())
+}
+
+/// 校验后写入提醒提前量；空列表表示只通报变更、不做提前提醒。
+pub fn save_reminder_leads<P: AsRef<Path>>(path: P, leads: Vec<u32>) -> Result<Vec<u32>, String> {
+    let leads = normalize_leads(leads)?;
+    let raw = serde_json::to_string(&leads).map_err(|e| e.to_string())?;
+    db::set_kv(path, REMINDER_LEADS_KEY, &raw).map_err(|e| e.to_string())?;
+    Ok(leads)
+}
+
+fn normalize_leads(mut leads: Vec<u32>) -> Result<Vec<u32>, String> {
+    leads.sort_unstable_by(|a, b| b.cmp(a));
+    leads.dedup();
+    if leads.len() > MAX_REMINDER_LEADS {
+        return Err(format!("最多设置 {MAX_REMINDER_LEADS} 个提醒时间"));
+    }
+    if leads
+        .iter()
+        .any(|lead| !(1..=MAX_LEAD_MINUTES).contains(lead))
+    {
+        return Err(format!("提醒提前量需在 1 到 {MAX_LEAD_MINUTES} 分钟之间"));
+    }
+    Ok(leads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_exam_tracker_{label}_{nanos}.db"))
+    }
+
+    fn exam(name: &str, date: &str, time: &str, location: &str, seat: Option<&str>) -> Exam {
+        Exam {
+            course_name: name.to_string(),
+            exam_date: date.to_string(),
+            exam_time: time.to_string(),
+            location: location.to_string(),
+            address: String::new(),
+            seat_number: seat.map(str::to_string),
+            exam_type: String::new(),
+            semester: "2024-2025-1".to_string(),
+        }
+    }
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn diff_reports_new_exams_and_changed_fields() {
+        let previous = vec![
+            exam("高数", "2025-01-10", "09:00-11:00", "6B-101", None),
+            exam("英语", "2025-01-12", "14:00-16:00", "4A-201", Some("12")),
+        ];
+        let current = vec![
+            exam("高数", "2025-01-10", "14:00-16:00", "6B-101", Some("30")),
+            exam("英语", "2025-01-12", "14:00-16:00", "4A-201", Some("12")),
+            exam("物理", "2025-01-15", "", "", None),
+        ];
+        let changes = diff_exams(&previous, &current);
+        assert_eq!(changes.len(), 2);
+
+        assert_eq!(changes[0].kind, ExamChangeKind::Updated);
+        let fields = changes[0]
+            .fields
+            .iter()
+            .map(|f| f.field)
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![ExamField::Time, ExamField::Seat]);
+        assert_eq!(
+            changes[0].body(),
+            "高数：时间 2025-01-10 09:00-11:00 → 2025-01-10 14:00-16:00；座位号 未定 → 30"
+        );
+
+        assert_eq!(changes[1].kind, ExamChangeKind::New);
+        assert_eq!(changes[1].body(), "物理：2025-01-15");
+    }
+
+    #[test]
+    fn reminders_fire_once_per_lead_and_skip_already_passed_leads() {
+        let exams = vec![exam(
+            "高数",
+            "2025-01-10",
+            "09:00-11:00",
+            "6B-101",
+            Some("30"),
+        )];
+        let leads = DEFAULT_REMINDER_LEADS;
+        let mut notified = BTreeSet::new();
+
+        // 8 天前：尚未进入任何提前量
+        assert!(due_reminders(&exams, &leads, &notified, at("2025-01-02 08:00")).is_empty());
+
+        // 首次检查已在 1 小时内：只发 2 小时提醒，7 天 / 1 天一并标记
+        let late = due_reminders(&exams, &leads, &notified, at("2025-01-10 08:00"));
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].lead_minutes, 120);
+        assert_eq!(late[0].keys.len(), 3);
+        assert_eq!(
+            late[0].body(),
+            "距 高数 开考还有 1 小时（2025-01-10 09:00-11:00，6B-101，座位 30）"
+        );
+
+        // 按时检查：7 天 → 1 天，各一次
+        let first = due_reminders(&exams, &leads, &notified, at("2025-01-04 12:00"));
+        assert_eq!(first[0].lead_minutes, 7 * 24 * 60);
+        notified.extend(first[0].keys.clone());
+        assert!(due_reminders(&exams, &leads, &notified, at("2025-01-05 12:00")).is_empty());
+        let second = due_reminders(&exams, &leads, &notified, at("2025-01-09 10:00"));
+        assert_eq!(second[0].lead_minutes, 24 * 60);
+
+        // 已开考不再提醒
+        assert!(due_reminders(&exams, &leads, &notified, at("2025-01-10 09:30")).is_empty());
+    }
+
+    #[test]
+    fn track_exams_builds_baseline_then_dedupes_and_rearms_on_reschedule() {
+        let path = temp_db_path("track");
+        init_db(&path).unwrap();
+        let leads = [24 * 60];
+        let original = vec![exam("高数", "2025-01-10", "09:00-11:00", "6B-101", None)];
+
+        let first = track_exams(
+            &path,
+            "20240001",
+            "2024-2025-1",
+            &original,
+            false,
+            &leads,
+            at("2025-01-09 10:00"),
+        )
+        .unwrap();
+        assert!(first.baseline);
+        assert!(first.changes.is_empty());
+        assert_eq!(first.reminders.len(), 1);
+
+        // 30 分钟后再次检查：同一提醒不再发送
+        let repeat = track_exams(
+            &path,
+            "20240001",
+            "2024-2025-1",
+            &original,
+            false,
+            &leads,
+            at("2025-01-09 10:30"),
+        )
+        .unwrap();
+        assert!(!repeat.baseline);
+        assert!(repeat.changes.is_empty());
+        assert!(repeat.reminders.is_empty());
+
+        // 改期：通报变更，并按新时间重新提醒
+        let moved = vec![exam("高数", "2025-01-10", "14:00-16:00", "6B-101", None)];
+        let rescheduled = track_exams(
+            &path,
+            "20240001",
+            "2024-2025-1",
+            &moved,
+            false,
+            &leads,
+            at("2025-01-09 15:00"),
+        )
+        .unwrap();
+        assert_eq!(rescheduled.changes.len(), 1);
+        assert_eq!(rescheduled.reminders.len(), 1);
+
+        // 离线：不比对、不替换快照中的考试
+        let offline = track_exams(
+            &path,
+            "20240001",
+            "2024-2025-1",
+            &original,
+            true,
+            &leads,
+            at("2025-01-09 15:30"),
+        )
+        .unwrap();
+        assert!(offline.changes.is_empty());
+        let (payload, _) = db::get_cache(&path, EXAM_SNAPSHOT_TABLE, "20240001:2024-2025-1")
+            .unwrap()
+            .unwrap();
+        let snapshot: ExamSnapshot = serde_json::from_value(payload).unwrap();
+        assert_eq!(snapshot.exams, moved);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn makeup_exam_is_tracked_apart_from_regular_exam() {
+        let typed = |date: &str, time: &str, exam_type: &str| Exam {
+            exam_type: exam_type.to_string(),
+            ..exam("高数", date, time, "6B-101", None)
+        };
+        let regular = typed("2025-01-10", "09:00-11:00", "正常");
+        let makeup = typed("2025-03-01", "14:00-16:00", "补考");
+        assert_ne!(regular.tracking_key(), makeup.tracking_key());
+
+        let changes = diff_exams(std::slice::from_ref(&regular), &[regular.clone(), makeup]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ExamChangeKind::New);
+        assert_eq!(changes[0].exam.exam_type, "补考");
+    }
+
+    #[test]
+    fn countdown_and_lead_config() {
+        let exams = vec![
+            exam("英语", "2025-01-12", "14:00-16:00", "", None),
+            exam("高数", "2025-01-10", "09:00-11:00", "6B-101", None),
+            exam("旧课", "2025-01-01", "09:00-11:00", "", None),
+        ];
+        let list = countdown(&exams, at("2025-01-09 20:00"));
+        assert_eq!(
+            list.iter()
+                .map(|c| c.course_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["高数", "英语"]
+        );
+        assert_eq!(list[0].days_left, 1);
+        assert_eq!(list[0].minutes_left, 13 * 60);
+
+        let path = temp_db_path("leads");
+        init_db(&path).unwrap();
+        assert_eq!(load_reminder_leads(&path), DEFAULT_REMINDER_LEADS.to_vec());
+        assert_eq!(
+            save_reminder_leads(&path, vec![60, 1440, 60]).unwrap(),
+            vec![1440, 60]
+        );
+        assert_eq!(load_reminder_leads(&path), vec![1440, 60]);
+        assert!(save_reminder_leads(&path, vec![0]).is_err());
+        assert!(save_reminder_leads(&path, vec![1, 2, 3, 4, 5, 6]).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
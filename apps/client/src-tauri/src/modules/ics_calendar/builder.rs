@@ -0,0 +1,810 @@
+//! RFC 5545 日历构建（纯函数，无 IO）。
+//!
+//! - 课程：每门课一个 VEVENT，`RRULE:FREQ=WEEKLY;COUNT=n` 覆盖首末周，
+//!   中间停课周以 `EXDATE` 排除；
+//! - 考试：每场一个独立 VEVENT（无具体时间时导出为全天事件）；
+//! - 时区：固定输出 `Asia/Shanghai` VTIMEZONE（UTC+8，无夏令时）；
+//! - 提醒：课程与考试各自可配置多个 VALARM（提前分钟数）；
+//! - UID：由学号 / 学期 / 课程身份（考试另加考试性质，不含日期）派生的稳定摘要，
+//!   重新导入或考试改期时日历客户端更新而非重复添加。
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::ics::{escape_ics_text, fold_ics_line};
+
+pub const SHANGHAI_TZID: &str = "Asia/Shanghai";
+
+/// 节次时间表（第 1-11 节起止），与前端 `features/schedule/constants.ts` 保持一致。
+pub const CLASS_PERIODS: [(&str, &str); 11] = [
+    ("08:20", "09:05"),
+    ("09:10", "09:55"),
+    ("10:15", "11:00"),
+    ("11:05", "11:50"),
+    ("14:00", "14:45"),
+    ("14:50", "15:35"),
+    ("15:55", "16:40"),
+    ("16:45", "17:30"),
+    ("18:30", "19:15"),
+    ("19:20", "20:05"),
+    ("20:10", "20:55"),
+];
+
+/// 周次上限（与自定义课程校验一致）。
+const MAX_WEEK: i32 = 30;
+
+/// 第 `period` 节的起止时间（1 起）。
+pub fn period_time(period: i32) -> Option<(NaiveTime, NaiveTime)> {
+    let index = usize::try_from(period.checked_sub(1)?).ok()?;
+    let (start, end) = CLASS_PERIODS.get(index)?;
+    Some((parse_clock(start)?, parse_clock(end)?))
+}
+
+/// 解析 `HH:MM` / `HH:MM:SS`。
+pub fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let text = text.trim();
+    NaiveTime::parse_from_str(text, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M:%S"))
+        .ok()
+}
+
+/// 待导出的课程（官方课表或自定义课程）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarCourse {
+    /// 课程身份：决定 UID，需在多次同步间保持稳定
+    pub key: String,
+    pub name: String,
+    pub teacher: String,
+    pub room: String,
+    /// 星期（1=周一）
+    pub weekday: i32,
+    /// 开始节次（1 起）
+    pub period: i32,
+    /// 持续节数
+    pub djs: i32,
+    pub weeks: Vec<i32>,
+}
+
+impl From<&crate::ScheduleCourse> for CalendarCourse {
+    fn from(course: &crate::ScheduleCourse) -> Self {
+        Self {
+            // 不含教室与周次：调课/换教室时仍更新同一事件
+            key: format!(
+                "course|{}|{}|{}|{}",
+                course.name.trim(),
+                course.teacher.trim(),
+                course.weekday,
+                course.period
+            ),
+            name: course.name.clone(),
+            teacher: course.teacher.clone(),
+            room: course.room.clone(),
+            weekday: course.weekday,
+            period: course.period,
+            djs: course.djs,
+            weeks: course.weeks.clone(),
+        }
+    }
+}
+
+impl From<&crate::db::CustomScheduleCourseRecord> for CalendarCourse {
+    fn from(course: &crate::db::CustomScheduleCourseRecord) -> Self {
+        Self {
+            key: format!("custom|{}", course.id),
+            name: course.name.clone(),
+            teacher: course.teacher.clone(),
+            room: course.room.clone(),
+            weekday: course.weekday,
+            period: course.period,
+            djs: course.djs,
+            weeks: course.weeks.clone(),
+        }
+    }
+}
+
+/// 待导出的考试。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarExam {
+    pub course_name: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    /// HH:MM（可空：导出为全天事件）
+    pub start_time: String,
+    pub end_time: String,
+    pub location: String,
+    pub seat_number: Option<String>,
+    /// 考试性质（正常 / 补考），参与 UID 派生；未知时留空
+    #[serde(default)]
+    pub exam_type: String,
+}
+
+impl From<&crate::Exam> for CalendarExam {
+    fn from(exam: &crate::Exam) -> Self {
+        Self {
+            course_name: exam.course_name.clone(),
+            date: exam.date.clone(),
+            start_time: exam.start_time.clone(),
+            end_time: exam.end_time.clone(),
+            location: exam.location.clone(),
+            seat_number: exam.seat_number.clone(),
+            exam_type: exam.exam_type.trim().to_string(),
+        }
+    }
+}
+
+impl From<&crate::modules::exam::Exam> for CalendarExam {
+    fn from(exam: &crate::modules::exam::Exam) -> Self {
+        let (start_time, end_time) = exam
+            .exam_time
+            .split_once('-')
+            .map(|(start, end)| (start.trim().to_string(), end.trim().to_string()))
+            .unwrap_or_else(|| (exam.exam_time.trim().to_string(), String::new()));
+        Self {
+            course_name: exam.course_name.clone(),
+            date: exam.exam_date.clone(),
+            start_time,
+            end_time,
+            location: if exam.location.trim().is_empty() {
+                exam.address.clone()
+            } else {
+                exam.location.clone()
+            },
+            seat_number: exam.seat_number.clone(),
+            exam_type: exam.exam_type.trim().to_string(),
+        }
+    }
+}
+
+/// 导出的日历类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarKind {
+    /// 课程与考试合并在同一日历
+    Combined,
+    Classes,
+    Exams,
+}
+
+impl CalendarKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Combined => "combined",
+            Self::Classes => "classes",
+            Self::Exams => "exams",
+        }
+    }
+}
+
+/// 导出选项（前端可部分传入，其余取默认）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IcsExportOptions {
+    /// 课程提醒：提前分钟数，空表示不提醒
+    pub class_alarms: Vec<u32>,
+    /// 考试提醒：提前分钟数
+    pub exam_alarms: Vec<u32>,
+    pub include_exams: bool,
+    /// 课程与考试拆分为两个日历
+    pub split_exams: bool,
+    /// 日历显示名前缀（缺省「HBUT」）
+    pub calendar_name: Option<String>,
+    /// 订阅刷新间隔（分钟）：输出 `REFRESH-INTERVAL` / `X-PUBLISHED-TTL`，一次性导出留空
+    pub refresh_interval_minutes: Option<u32>,
+}
+
+impl Default for IcsExportOptions {
+    fn default() -> Self {
+        Self {
+            class_alarms: vec![15],
+            exam_alarms: vec![24 * 60, 60],
+            include_exams: true,
+            split_exams: false,
+            calendar_name: None,
+            refresh_interval_minutes: None,
+        }
+    }
+}
+
+/// 导出上下文。
+#[derive(Debug, Clone)]
+pub struct CalendarContext {
+    pub student_id: String,
+    pub semester: String,
+    /// 第 1 周周一
+    pub semester_start: NaiveDate,
+    /// DTSTAMP / LAST-MODIFIED
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IcsCalendar {
+    pub kind: CalendarKind,
+    pub name: String,
+    pub content: String,
+    pub event_count: usize,
+}
+
+/// 未能导出的条目（节次/星期/日期不合法等），显式报告而非静默丢弃。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedEntry {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IcsBuild {
+    pub semester: String,
+    pub calendars: Vec<IcsCalendar>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// 构建日历：`split_exams` 时输出课程 / 考试两个日历，否则输出一个合并日历。
+pub fn build_calendars(
+    context: &CalendarContext,
+    courses: &[CalendarCourse],
+    exams: &[CalendarExam],
+    options: &IcsExportOptions,
+) -> IcsBuild {
+    let mut skipped = Vec::new();
+    let dtstamp = context.generated_at.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut class_events = Vec::new();
+    for course in merge_courses(courses) {
+        match course_event(context, &course, &dtstamp, &options.class_alarms) {
+            Ok(event) => class_events.push(event),
+            Err(reason) => skipped.push(SkippedEntry {
+                name: course.name.clone(),
+                reason,
+            }),
+        }
+    }
+
+    let mut exam_events = Vec::new();
+    if options.include_exams {
+        // 同一课程同一性质出现多场时按出现顺序编号，避免 UID 冲突
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        for exam in exams {
+            let mut uid_key = format!("{}|{}", exam.course_name.trim(), exam.exam_type.trim());
+            let ordinal = seen.entry(uid_key.clone()).or_insert(0);
+            if *ordinal > 0 {
+                uid_key.push_str(&format!("|{ordinal}"));
+            }
+            *ordinal += 1;
+            match exam_event(context, exam, &uid_key, &dtstamp, &options.exam_alarms) {
+                Ok(event) => exam_events.push(event),
+                Err(reason) => skipped.push(SkippedEntry {
+                    name: exam.course_name.clone(),
+                    reason,
+                }),
+            }
+        }
+    }
+
+    let prefix = options
+        .calendar_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or("HBUT");
+    let semester = context.semester.trim();
+    let calendars = if options.split_exams {
+        let mut calendars = vec![wrap_calendar(
+            CalendarKind::Classes,
+            format!("{prefix} 课表 {semester}"),
+            class_events,
+            options,
+        )];
+        if options.include_exams {
+            calendars.push(wrap_calendar(
+                CalendarKind::Exams,
+                format!("{prefix} 考试 {semester}"),
+                exam_events,
+                options,
+            ));
+        }
+        calendars
+    } else {
+        class_events.extend(exam_events);
+        vec![wrap_calendar(
+            CalendarKind::Combined,
+            format!("{prefix} 课表 {semester}"),
+            class_events,
+            options,
+        )]
+    };
+
+    IcsBuild {
+        semester: semester.to_string(),
+        calendars,
+        skipped,
+    }
+}
+
+/// 同一身份的多条记录（如 1-8 周与 10-16 周分拆）合并周次，保证 UID 唯一。
+fn merge_courses(courses: &[CalendarCourse]) -> Vec<CalendarCourse> {
+    let mut merged: BTreeMap<&str, CalendarCourse> = BTreeMap::new();
+    for course in courses {
+        merged
+            .entry(course.key.as_str())
+            .and_modify(|existing| existing.weeks.extend(&course.weeks))
+            .or_insert_with(|| course.clone());
+    }
+    merged.into_values().collect()
+}
+
+fn course_event(
+    context: &CalendarContext,
+    course: &CalendarCourse,
+    dtstamp: &str,
+    alarms: &[u32],
+) -> Result<String, String> {
+    if !(1..=7).contains(&course.weekday) {
+        return Err(format!("星期 {} 不合法", course.weekday));
+    }
+    let last_period = course.period + course.djs.max(1) - 1;
+    let (start_time, _) =
+        period_time(course.period).ok_or_else(|| format!("节次 {} 不合法", course.period))?;
+    let (_, end_time) =
+        period_time(last_period).ok_or_else(|| format!("节次 {} 不合法", last_period))?;
+
+    let mut weeks = course
+        .weeks
+        .iter()
+        .copied()
+        .filter(|week| (1..=MAX_WEEK).contains(week))
+        .collect::<Vec<_>>();
+    weeks.sort_unstable();
+    weeks.dedup();
+    let (Some(&first), Some(&last)) = (weeks.first(), weeks.last()) else {
+        return Err("无有效周次".to_string());
+    };
+
+    let date_of = |week: i32| {
+        context.semester_start + Duration::days(i64::from((week - 1) * 7 + (course.weekday - 1)))
+    };
+    let start = date_of(first).and_time(start_time);
+    let end = date_of(first).and_time(end_time);
+
+    let summary = course.name.trim();
+    let mut lines = vec![
+        format!("UID:{}", stable_uid("course", context, &course.key)),
+        format!("DTSTAMP:{dtstamp}"),
+        format!("LAST-MODIFIED:{dtstamp}"),
+        format!("DTSTART;TZID={SHANGHAI_TZID}:{}", local_stamp(start)),
+        format!("DTEND;TZID={SHANGHAI_TZID}:{}", local_stamp(end)),
+    ];
+    if last > first {
+        lines.push(format!("RRULE:FREQ=WEEKLY;COUNT={}", last - first + 1));
+        let excluded = (first..=last)
+            .filter(|week| weeks.binary_search(week).is_err())
+            .map(|week| local_stamp(date_of(week).and_time(start_time)))
+            .collect::<Vec<_>>();
+        if !excluded.is_empty() {
+            lines.push(format!(
+                "EXDATE;TZID={SHANGHAI_TZID}:{}",
+                excluded.join(",")
+            ));
+        }
+    }
+    lines.push(format!("SUMMARY:{}", escape_ics_text(summary)));
+    if !course.room.trim().is_empty() {
+        lines.push(format!("LOCATION:{}", escape_ics_text(course.room.trim())));
+    }
+    let mut description = Vec::new();
+    if !course.teacher.trim().is_empty() {
+        description.push(format!("教师：{}", course.teacher.trim()));
+    }
+    description.push(format!(
+        "第 {}-{} 节，第 {} 周",
+        course.period,
+        last_period,
+        format_weeks(&weeks)
+    ));
+    lines.push(format!(
+        "DESCRIPTION:{}",
+        escape_ics_text(&description.join("\n"))
+    ));
+    lines.push("CATEGORIES:课程".to_string());
+    Ok(render_event(lines, summary, alarms))
+}
+
+/// `uid_key` 只含课程与考试性质（学期已在 UID 上下文中），
+/// 改期后 UID 不变，日历客户端会原地更新而不是留下旧日期的事件。
+fn exam_event(
+    context: &CalendarContext,
+    exam: &CalendarExam,
+    uid_key: &str,
+    dtstamp: &str,
+    alarms: &[u32],
+) -> Result<String, String> {
+    let date = NaiveDate::parse_from_str(exam.date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("考试日期 {:?} 不合法", exam.date))?;
+    let summary = format!("考试：{}", exam.course_name.trim());
+    let mut lines = vec![
+        format!("UID:{}", stable_uid("exam", context, uid_key)),
+        format!("DTSTAMP:{dtstamp}"),
+        format!("LAST-MODIFIED:{dtstamp}"),
+    ];
+    match parse_clock(&exam.start_time) {
+        Some(start_time) => {
+            let start = date.and_time(start_time);
+            let end = parse_clock(&exam.end_time)
+                .map(|end_time| date.and_time(end_time))
+                .filter(|end| *end > start)
+                .unwrap_or(start + Duration::hours(2));
+            lines.push(format!(
+                "DTSTART;TZID={SHANGHAI_TZID}:{}",
+                local_stamp(start)
+            ));
+            lines.push(format!("DTEND;TZID={SHANGHAI_TZID}:{}", local_stamp(end)));
+        }
+        None => {
+            let next = date + Duration::days(1);
+            lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+            lines.push(format!("DTEND;VALUE=DATE:{}", next.format("%Y%m%d")));
+        }
+    }
+    lines.push(format!("SUMMARY:{}", escape_ics_text(&summary)));
+    if !exam.location.trim().is_empty() {
+        lines.push(format!(
+            "LOCATION:{}",
+            escape_ics_text(exam.location.trim())
+        ));
+    }
+    if let Some(seat) = exam
+        .seat_number
+        .as_deref()
+        .map(str::trim)
+        .filter(|seat| !seat.is_empty())
+    {
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_ics_text(&format!("座位号：{seat}"))
+        ));
+    }
+    lines.push("CATEGORIES:考试".to_string());
+    Ok(render_event(lines, &summary, alarms))
+}
+
+fn render_event(lines: Vec<String>, summary: &str, alarms: &[u32]) -> String {
+    let mut out = String::from("BEGIN:VEVENT\r\n");
+    for line in lines {
+        out.push_str(&fold_ics_line(&line));
+    }
+    let mut alarms = alarms.to_vec();
+    alarms.sort_unstable_by(|a, b| b.cmp(a));
+    alarms.dedup();
+    for minutes in alarms {
+        out.push_str("BEGIN:VALARM\r\n");
+        out.push_str("ACTION:DISPLAY\r\n");
+        out.push_str(&fold_ics_line(&format!(
+            "DESCRIPTION:{}",
+            escape_ics_text(summary)
+        )));
+        out.push_str(&format!("TRIGGER:-PT{minutes}M\r\n"));
+        out.push_str("END:VALARM\r\n");
+    }
+    out.push_str("END:VEVENT\r\n");
+    out
+}
+
+fn wrap_calendar(
+    kind: CalendarKind,
+    name: String,
+    events: Vec<String>,
+    options: &IcsExportOptions,
+) -> IcsCalendar {
+    let mut ics = String::new();
+    ics.push_str("BEGIN:VCALENDAR\r\n");
+    ics.push_str("VERSION:2.0\r\n");
+    ics.push_str("PRODID:-//Mini-HBUT//Schedule Calendar//CN\r\n");
+    ics.push_str("CALSCALE:GREGORIAN\r\n");
+    ics.push_str("METHOD:PUBLISH\r\n");
+    ics.push_str(&fold_ics_line(&format!(
+        "X-WR-CALNAME:{}",
+        escape_ics_text(&name)
+    )));
+    ics.push_str(&format!("X-WR-TIMEZONE:{SHANGHAI_TZID}\r\n"));
+    if let Some(minutes) = options.refresh_interval_minutes.filter(|m| *m > 0) {
+        ics.push_str(&format!("REFRESH-INTERVAL;VALUE=DURATION:PT{minutes}M\r\n"));
+        ics.push_str(&format!("X-PUBLISHED-TTL:PT{minutes}M\r\n"));
+    }
+    ics.push_str(&shanghai_vtimezone());
+    for event in &events {
+        ics.push_str(event);
+    }
+    ics.push_str("END:VCALENDAR\r\n");
+    IcsCalendar {
+        kind,
+        name,
+        content: ics,
+        event_count: events.len(),
+    }
+}
+
+/// Asia/Shanghai VTIMEZONE（UTC+8，无夏令时）。
+pub fn shanghai_vtimezone() -> String {
+    [
+        "BEGIN:VTIMEZONE",
+        "TZID:Asia/Shanghai",
+        "X-LIC-LOCATION:Asia/Shanghai",
+        "BEGIN:STANDARD",
+        "DTSTART:19700101T000000",
+        "TZOFFSETFROM:+0800",
+        "TZOFFSETTO:+0800",
+        "TZNAME:CST",
+        "END:STANDARD",
+        "END:VTIMEZONE",
+    ]
+    .iter()
+    .map(|line| format!("{line}\r\n"))
+    .collect()
+}
+
+/// 稳定 UID：同一学号 / 学期 / 身份每次导出都相同。
+fn stable_uid(kind: &str, context: &CalendarContext, key: &str) -> String {
+    let raw = format!(
+        "{}|{}|{}",
+        context.student_id.trim(),
+        context.semester.trim(),
+        key
+    );
+    format!("hbut-{kind}-{:x}@mini-hbut", md5::compute(raw.as_bytes()))
+}
+
+fn local_stamp(value: NaiveDateTime) -> String {
+    value.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// 周次压缩显示：`[1,2,3,5,7,8]` → `1-3,5,7-8`。
+fn format_weeks(weeks: &[i32]) -> String {
+    let mut parts = Vec::new();
+    let mut iter = weeks.iter().copied().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end += 1;
+            iter.next();
+        }
+        parts.push(if start == end {
+            start.to_string()
+        } else {
+            format!("{start}-{end}")
+        });
+    }
+    parts.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> CalendarContext {
+        CalendarContext {
+            student_id: "20240001".to_string(),
+            semester: "2024-2025-1".to_string(),
+            semester_start: NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+            generated_at: DateTime::parse_from_rfc3339("2024-09-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    fn course(key: &str, weekday: i32, period: i32, djs: i32, weeks: Vec<i32>) -> CalendarCourse {
+        CalendarCourse {
+            key: key.to_string(),
+            name: "高等数学".to_string(),
+            teacher: "张老师".to_string(),
+            room: "6-201".to_string(),
+            weekday,
+            period,
+            djs,
+            weeks,
+        }
+    }
+
+    fn exam(start: &str, end: &str) -> CalendarExam {
+        CalendarExam {
+            course_name: "线性代数".to_string(),
+            date: "2025-01-10".to_string(),
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            location: "2-101".to_string(),
+            seat_number: Some("12".to_string()),
+            exam_type: "正常".to_string(),
+        }
+    }
+
+    fn unfold(content: &str) -> String {
+        content.replace("\r\n ", "")
+    }
+
+    #[test]
+    fn weekly_course_emits_rrule_exdate_and_timezone() {
+        let build = build_calendars(
+            &context(),
+            &[course("math", 3, 1, 2, vec![1, 2, 3, 5, 6])],
+            &[],
+            &IcsExportOptions::default(),
+        );
+        assert!(build.skipped.is_empty());
+        let ics = unfold(&build.calendars[0].content);
+        assert!(ics.contains("BEGIN:VTIMEZONE\r\nTZID:Asia/Shanghai\r\n"));
+        // 第 1 周周三 08:20 - 第 2 节结束 09:55
+        assert!(ics.contains("DTSTART;TZID=Asia/Shanghai:20240904T082000\r\n"));
+        assert!(ics.contains("DTEND;TZID=Asia/Shanghai:20240904T095500\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;COUNT=6\r\n"));
+        assert!(ics.contains("EXDATE;TZID=Asia/Shanghai:20240925T082000\r\n"));
+        assert!(ics.contains("TRIGGER:-PT15M\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn single_week_course_has_no_rrule_and_uid_is_stable() {
+        let courses = [course("lab", 5, 9, 3, vec![4])];
+        let first = build_calendars(&context(), &courses, &[], &IcsExportOptions::default());
+        let mut later = context();
+        later.generated_at = Utc::now();
+        let second = build_calendars(&later, &courses, &[], &IcsExportOptions::default());
+
+        let uid_line = |content: &str| {
+            content
+                .lines()
+                .find(|line| line.starts_with("UID:"))
+                .map(str::to_string)
+        };
+        let ics = &first.calendars[0].content;
+        assert!(!ics.contains("RRULE"));
+        assert!(ics.contains("DTEND;TZID=Asia/Shanghai:20240927T205500\r\n"));
+        assert_eq!(uid_line(ics), uid_line(&second.calendars[0].content));
+    }
+
+    #[test]
+    fn split_records_of_same_course_are_merged_into_one_event() {
+        let build = build_calendars(
+            &context(),
+            &[
+                course("math", 1, 3, 2, vec![1, 2]),
+                course("math", 1, 3, 2, vec![4]),
+            ],
+            &[],
+            &IcsExportOptions::default(),
+        );
+        let ics = unfold(&build.calendars[0].content);
+        assert_eq!(build.calendars[0].event_count, 1);
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;COUNT=4\r\n"));
+        assert!(ics.contains("EXDATE;TZID=Asia/Shanghai:20240916T101500\r\n"));
+    }
+
+    #[test]
+    fn invalid_courses_and_exams_are_reported() {
+        let build = build_calendars(
+            &context(),
+            &[
+                course("bad-period", 1, 11, 2, vec![1]),
+                course("no-weeks", 1, 1, 2, Vec::new()),
+            ],
+            &[CalendarExam {
+                date: "待定".to_string(),
+                ..exam("", "")
+            }],
+            &IcsExportOptions::default(),
+        );
+        assert_eq!(build.calendars[0].event_count, 0);
+        assert_eq!(build.skipped.len(), 3);
+    }
+
+    #[test]
+    fn exams_can_be_split_into_separate_calendar_with_alarms() {
+        let options = IcsExportOptions {
+            split_exams: true,
+            ..IcsExportOptions::default()
+        };
+        let build = build_calendars(
+            &context(),
+            &[course("math", 1, 1, 2, vec![1, 2])],
+            &[
+                exam("14:00", "16:00"),
+                CalendarExam {
+                    course_name: "体育".to_string(),
+                    ..exam("", "")
+                },
+            ],
+            &options,
+        );
+        assert_eq!(build.calendars.len(), 2);
+        assert_eq!(build.calendars[0].kind, CalendarKind::Classes);
+        assert_eq!(build.calendars[0].event_count, 1);
+        let exams = unfold(&build.calendars[1].content);
+        assert_eq!(build.calendars[1].kind, CalendarKind::Exams);
+        assert!(exams.contains("X-WR-CALNAME:HBUT 考试 2024-2025-1\r\n"));
+        assert!(exams.contains("DTSTART;TZID=Asia/Shanghai:20250110T140000\r\n"));
+        assert!(exams.contains("DTSTART;VALUE=DATE:20250110\r\n"));
+        assert!(exams.contains("TRIGGER:-PT1440M\r\nEND:VALARM\r\nBEGIN:VALARM"));
+        assert!(exams.contains("TRIGGER:-PT60M\r\n"));
+
+        let combined = build_calendars(
+            &context(),
+            &[course("math", 1, 1, 2, vec![1, 2])],
+            &[exam("14:00", "")],
+            &IcsExportOptions::default(),
+        );
+        assert_eq!(combined.calendars.len(), 1);
+        assert_eq!(combined.calendars[0].event_count, 2);
+        assert!(combined.calendars[0]
+            .content
+            .contains("DTEND;TZID=Asia/Shanghai:20250110T160000\r\n"));
+        assert!(!combined.calendars[0].content.contains("REFRESH-INTERVAL"));
+    }
+
+    #[test]
+    fn subscription_calendars_advertise_refresh_interval() {
+        let options = IcsExportOptions {
+            refresh_interval_minutes: Some(60),
+            ..IcsExportOptions::default()
+        };
+        let build = build_calendars(&context(), &[], &[], &options);
+        let ics = &build.calendars[0].content;
+        assert!(ics.contains("REFRESH-INTERVAL;VALUE=DURATION:PT60M\r\n"));
+        assert!(ics.contains("X-PUBLISHED-TTL:PT60M\r\n"));
+    }
+
+    #[test]
+    fn legacy_exam_module_time_range_is_split() {
+        let exam = crate::modules::exam::Exam {
+            course_name: "大学物理".to_string(),
+            exam_date: "2025-01-12".to_string(),
+            exam_time: "09:00-11:00".to_string(),
+            location: String::new(),
+            address: "3-105".to_string(),
+            seat_number: None,
+            exam_type: "正常".to_string(),
+            semester: "2024-2025-1".to_string(),
+        };
+        let converted = CalendarExam::from(&exam);
+        assert_eq!(converted.start_time, "09:00");
+        assert_eq!(converted.end_time, "11:00");
+        assert_eq!(converted.location, "3-105");
+        assert_eq!(converted.exam_type, "正常");
+    }
+
+    #[test]
+    fn exam_uid_survives_reschedule_and_separates_makeup_exams() {
+        let uid_lines = |exams: &[CalendarExam]| -> Vec<String> {
+            build_calendars(&context(), &[], exams, &IcsExportOptions::default()).calendars[0]
+                .content
+                .lines()
+                .filter(|line| line.starts_with("UID:hbut-exam-"))
+                .map(str::to_string)
+                .collect()
+        };
+        let original = uid_lines(&[exam("14:00", "16:00")]);
+        let rescheduled = uid_lines(&[CalendarExam {
+            date: "2025-01-14".to_string(),
+            ..exam("09:00", "11:00")
+        }]);
+        assert_eq!(original.len(), 1);
+        assert_eq!(original, rescheduled);
+
+        let makeup = CalendarExam {
+            date: "2025-03-01".to_string(),
+            exam_type: "补考".to_string(),
+            ..exam("14:00", "16:00")
+        };
+        let both = uid_lines(&[exam("14:00", "16:00"), makeup]);
+        assert_eq!(both.len(), 2);
+        assert_ne!(both[0], both[1]);
+        assert_eq!(both[0], original[0]);
+
+        let duplicated = uid_lines(&[exam("14:00", "16:00"), exam("14:00", "16:00")]);
+        assert_ne!(duplicated[0], duplicated[1]);
+    }
+
+    #[test]
+    fn weeks_are_compacted_for_description() {
+        assert_eq!(format_weeks(&[1, 2, 3, 5, 7, 8]), "1-3,5,7-8");
+        assert_eq!(format_weeks(&[4]), "4");
+    }
+}
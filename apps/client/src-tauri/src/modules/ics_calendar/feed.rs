@@ -0,0 +1,234 @@
+//! 日历订阅（webcal）令牌与实时订阅源。
+//!
+//! - 令牌：每个「学号 + 设备」一个不透明随机串，与 Bridge 会话令牌 / 本地 JWT 无关；
+//!   `kv_store` 仅保存其 SHA-256 摘要（`ics_calendar.feed.<摘要>`），库文件泄露也无法还原订阅地址；
+//! - 订阅源：每次请求都从本地缓存重新构建，`sync_schedule` 写入缓存后下一次拉取即为最新；
+//!   UID 稳定（见 [`super::builder`]），日历客户端按 UID 原地更新事件。
+
+use base64::{engine::general_purpose, Engine as _};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use super::{build_schedule_ics, IcsExportOptions, ScheduleIcsRequest};
+use crate::db;
+
+const FEED_KEY_PREFIX: &str = "ics_calendar.feed.";
+
+/// 订阅源建议的刷新间隔（分钟）。
+pub const FEED_REFRESH_MINUTES: u32 = 60;
+
+/// 设备标签上限（字符）。
+const MAX_DEVICE_LABEL: usize = 32;
+
+/// 令牌绑定信息（存于 kv_store，不含令牌本身）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedTokenRecord {
+    pub student_id: String,
+    pub device: String,
+    pub created_at: String,
+}
+
+/// 签发 / 轮换订阅令牌的请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleFeedRequest {
+    pub student_id: Option<String>,
+    /// 设备标签（缺省 `default`）；同一设备重复签发会作废旧令牌
+    pub device: Option<String>,
+}
+
+/// 为学号 + 设备签发新令牌（作废该设备的旧令牌），返回明文令牌与绑定信息。
+pub fn issue_feed_token<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    device: Option<&str>,
+) -> Result<(String, FeedTokenRecord), String> {
+    let path = path.as_ref();
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    let device = normalize_device(device)?;
+    revoke_feed_tokens(path, sid, Some(&device))?;
+
+    let mut bytes = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+    let record = FeedTokenRecord {
+        student_id: sid.to_string(),
+        device,
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    let raw = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+    db::set_kv(path, &feed_key(&token), &raw).map_err(|e| e.to_string())?;
+    Ok((token, record))
+}
+
+/// 作废学号下的订阅令牌；`device` 为空时作废全部设备，返回作废数量。
+pub fn revoke_feed_tokens<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    device: Option<&str>,
+) -> Result<usize, String> {
+    let path = path.as_ref();
+    let device = match device {
+        Some(device) => Some(normalize_device(Some(device))?),
+        None => None,
+    };
+    let mut revoked = 0;
+    for (key, raw) in db::list_kv_by_prefix(path, FEED_KEY_PREFIX).map_err(|e| e.to_string())? {
+        let Ok(record) = serde_json::from_str::<FeedTokenRecord>(&raw) else {
+            continue;
+        };
+        if record.student_id == student_id.trim()
+            && device.as_deref().is_none_or(|d| d == record.device)
+        {
+            revoked += db::delete_kv(path, &key).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(revoked)
+}
+
+/// 令牌 → 绑定信息；未知令牌返回 `None`。
+pub fn resolve_feed_token<P: AsRef<Path>>(
+    path: P,
+    token: &str,
+) -> Result<Option<FeedTokenRecord>, String> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Ok(None);
+    }
+    let raw = db::get_kv(path, &feed_key(token)).map_err(|e| e.to_string())?;
+    Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+/// 按绑定学号从本地缓存实时构建订阅源（课程 + 自定义课程 + 考试合并为一个日历）。
+pub fn build_feed_calendar<P: AsRef<Path>>(
+    path: P,
+    record: &FeedTokenRecord,
+) -> Result<String, String> {
+    let req = ScheduleIcsRequest {
+        student_id: Some(record.student_id.clone()),
+        options: IcsExportOptions {
+            split_exams: false,
+            refresh_interval_minutes: Some(FEED_REFRESH_MINUTES),
+            ..IcsExportOptions::default()
+        },
+        ..ScheduleIcsRequest::default()
+    };
+    let build = build_schedule_ics(path, &record.student_id, &req)?;
+    build
+        .calendars
+        .into_iter()
+        .next()
+        .map(|calendar| calendar.content)
+        .ok_or_else(|| "订阅源为空".to_string())
+}
+
+/// 订阅路径（Bridge 相对路径，`.ics` 后缀便于日历客户端识别）。
+pub fn feed_path(token: &str) -> String {
+    format!("/webcal/{token}.ics")
+}
+
+/// 组装签发返回体（两通道一致）：`url` 供手动添加，`webcal_url` 可直接唤起系统日历订阅。
+pub fn build_feed_payload(addr: SocketAddr, token: &str, record: &FeedTokenRecord) -> Value {
+    let path = feed_path(token);
+    serde_json::json!({
+        "success": true,
+        "token": token,
+        "device": record.device,
+        "created_at": record.created_at,
+        "path": path,
+        "url": format!("http://{addr}{path}"),
+        "webcal_url": format!("webcal://{addr}{path}"),
+        "refresh_minutes": FEED_REFRESH_MINUTES,
+    })
+}
+
+fn feed_key(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    let hex = digest
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    format!("{FEED_KEY_PREFIX}{hex}")
+}
+
+fn normalize_device(device: Option<&str>) -> Result<String, String> {
+    let device = device
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .unwrap_or("default");
+    if device.chars().count() > MAX_DEVICE_LABEL {
+        return Err(format!("设备标签不能超过 {MAX_DEVICE_LABEL} 个字符"));
+    }
+    Ok(device.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_feed_{label}_{nanos}.db"))
+    }
+
+    #[test]
+    fn tokens_are_hashed_rotated_per_device_and_revocable() {
+        let path = temp_db_path("tokens");
+        init_db(&path).unwrap();
+
+        let (laptop, _) = issue_feed_token(&path, "20240001", Some("laptop")).unwrap();
+        let (desktop, _) = issue_feed_token(&path, "20240001", Some("desktop")).unwrap();
+        assert_ne!(laptop, desktop);
+        // 库中只存摘要
+        assert!(db::get_kv(&path, &format!("{FEED_KEY_PREFIX}{laptop}"))
+            .unwrap()
+            .is_none());
+        let record = resolve_feed_token(&path, &laptop).unwrap().unwrap();
+        assert_eq!(record.student_id, "20240001");
+        assert_eq!(record.device, "laptop");
+
+        // 同设备重新签发：旧令牌失效，其他设备不受影响
+        let (rotated, _) = issue_feed_token(&path, "20240001", Some("laptop")).unwrap();
+        assert!(resolve_feed_token(&path, &laptop).unwrap().is_none());
+        assert!(resolve_feed_token(&path, &rotated).unwrap().is_some());
+        assert!(resolve_feed_token(&path, &desktop).unwrap().is_some());
+
+        assert_eq!(revoke_feed_tokens(&path, "20240001", None).unwrap(), 2);
+        assert!(resolve_feed_token(&path, &desktop).unwrap().is_none());
+        assert!(resolve_feed_token(&path, "").unwrap().is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn device_label_defaults_and_is_bounded() {
+        assert_eq!(normalize_device(None).unwrap(), "default");
+        assert_eq!(normalize_device(Some("  ")).unwrap(), "default");
+        assert!(normalize_device(Some(&"x".repeat(33))).is_err());
+        assert_eq!(feed_path("abc"), "/webcal/abc.ics");
+
+        let record = FeedTokenRecord {
+            student_id: "20240001".to_string(),
+            device: "default".to_string(),
+            created_at: String::new(),
+        };
+        let payload = build_feed_payload(SocketAddr::from(([127, 0, 0, 1], 4399)), "abc", &record);
+        assert_eq!(payload["url"], "http://127.0.0.1:4399/webcal/abc.ics");
+        assert_eq!(
+            payload["webcal_url"],
+            "webcal://127.0.0.1:4399/webcal/abc.ics"
+        );
+    }
+}
@@ -0,0 +1,601 @@
+//! 外部日历（.ics）导入为自定义课程（纯函数，无 IO）。
+//!
+//! - 解析：VEVENT 的 DTSTART / DTEND / RRULE / EXDATE / SUMMARY / LOCATION，
+//!   日期时间以 [`parse_ics_property_datetime`] 为基础（UTC 换算为北京时间）；
+//! - 映射：按学期第 1 周周一把每次重复落到「周次 + 星期」，起止时间对齐到节次表；
+//! - 报告：全天事件、非每周重复、未对齐节次、超出学期周次等条目均进入 `skipped`，不静默丢弃；
+//! - 冲突：与官方课表按星期 / 节次区间 / 周次求交，仅预览不阻断导入。
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use super::builder::{parse_clock, CalendarCourse, SkippedEntry, CLASS_PERIODS, SHANGHAI_TZID};
+use crate::utils::ics::{parse_ics_property_datetime, unescape_ics_text, unfold_ics_lines};
+
+/// 周次上限（与导出、自定义课程校验一致）。
+const MAX_WEEK: i32 = 30;
+
+/// 起止时间与节次边界的容差（分钟）：社团/实验室排期常有几分钟出入。
+const ALIGN_TOLERANCE_MINUTES: i64 = 5;
+
+/// 视同北京时间的 TZID（无 DST，可直接按本地时间解释）。
+const LOCAL_TZIDS: [&str; 4] = [SHANGHAI_TZID, "Asia/Chongqing", "Asia/Hong_Kong", "PRC"];
+
+/// 解析出的单个 VEVENT（时间均已换算为北京时间）。
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    pub uid: String,
+    pub summary: String,
+    pub location: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub rrule: Option<String>,
+    pub exdates: Vec<NaiveDate>,
+}
+
+/// 导入的课程与官方课表的冲突（预览用）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportConflict {
+    pub name: String,
+    pub official_name: String,
+    pub weekday: i32,
+    /// 冲突节次区间（含端点）
+    pub periods: (i32, i32),
+    pub weeks: Vec<i32>,
+}
+
+/// 解析 .ics 文本：返回可用事件与无法解析的条目。
+pub fn parse_ics_events(content: &str) -> (Vec<IcsEvent>, Vec<SkippedEntry>) {
+    let mut events = Vec::new();
+    let mut skipped = Vec::new();
+    let mut current: Option<Vec<(String, String, String)>> = None;
+    // VALARM 等嵌套组件内的属性不属于事件本身
+    let mut nested = 0usize;
+
+    for line in unfold_ics_lines(content) {
+        let Some((head, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = head.split_once(';').unwrap_or((head, ""));
+        let name = name.trim().to_ascii_uppercase();
+        match (name.as_str(), value.trim().to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => {
+                current = Some(Vec::new());
+                nested = 0;
+            }
+            ("END", "VEVENT") => {
+                if let Some(props) = current.take() {
+                    match build_event(&props) {
+                        Ok(event) => events.push(event),
+                        Err(entry) => skipped.push(entry),
+                    }
+                }
+            }
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if current.is_some() => nested = nested.saturating_sub(1),
+            _ => {
+                if let Some(props) = current.as_mut().filter(|_| nested == 0) {
+                    props.push((name, params.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+    (events, skipped)
+}
+
+fn build_event(props: &[(String, String, String)]) -> Result<IcsEvent, SkippedEntry> {
+    let prop = |key: &str| props.iter().find(|(name, _, _)| name == key);
+    let text = |key: &str| {
+        prop(key)
+            .map(|(_, _, value)| unescape_ics_text(value.trim()))
+            .unwrap_or_default()
+    };
+    let summary = text("SUMMARY");
+    let name = if summary.trim().is_empty() {
+        "（无标题）".to_string()
+    } else {
+        summary.trim().to_string()
+    };
+    let fail = |reason: String| SkippedEntry {
+        name: name.clone(),
+        reason,
+    };
+
+    let (_, start_params, start_value) =
+        prop("DTSTART").ok_or_else(|| fail("缺少 DTSTART".to_string()))?;
+    if is_date_only(start_params, start_value) {
+        return Err(fail("全天事件无法对应课程节次".to_string()));
+    }
+    let start = parse_event_datetime(start_params, start_value).map_err(&fail)?;
+    let end = match prop("DTEND") {
+        Some((_, params, value)) => parse_event_datetime(params, value).map_err(&fail)?,
+        None => match prop("DURATION") {
+            Some((_, _, value)) => parse_duration(value)
+                .and_then(|duration| start.checked_add_signed(duration))
+                .ok_or_else(|| fail(format!("无法解析 DURATION {:?}", value.trim())))?,
+            None => return Err(fail("缺少 DTEND / DURATION".to_string())),
+        },
+    };
+    if end <= start {
+        return Err(fail("结束时间早于开始时间".to_string()));
+    }
+
+    let mut exdates = Vec::new();
+    for (_, params, value) in props.iter().filter(|(name, _, _)| name == "EXDATE") {
+        for item in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            let date = if is_date_only(params, item) {
+                NaiveDate::parse_from_str(item, "%Y%m%d").ok()
+            } else {
+                parse_event_datetime(params, item).ok().map(|dt| dt.date())
+            };
+            exdates.push(date.ok_or_else(|| fail(format!("无法解析 EXDATE {item:?}")))?);
+        }
+    }
+
+    Ok(IcsEvent {
+        uid: text("UID"),
+        summary: name.clone(),
+        location: text("LOCATION").trim().to_string(),
+        start,
+        end,
+        rrule: prop("RRULE").map(|(_, _, value)| value.trim().to_string()),
+        exdates,
+    })
+}
+
+fn is_date_only(params: &str, value: &str) -> bool {
+    let params = params.to_ascii_uppercase();
+    let value = value.trim();
+    (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME"))
+        || (value.len() == 8 && value.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 按 TZID 参数解释时间：北京时间同义 TZID 与浮动时间直接采用，其余时区无法换算时报告。
+fn parse_event_datetime(params: &str, value: &str) -> Result<NaiveDateTime, String> {
+    let tzid = params.split(';').find_map(|param| {
+        let (key, val) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("TZID")
+            .then(|| val.trim().trim_matches('"').to_string())
+    });
+    if let Some(tzid) = tzid.filter(|tz| !LOCAL_TZIDS.iter().any(|l| l.eq_ignore_ascii_case(tz))) {
+        return Err(format!("暂不支持时区 {tzid}，请以北京时间或 UTC 导出"));
+    }
+    parse_ics_property_datetime(value).ok_or_else(|| format!("无法解析时间 {:?}", value.trim()))
+}
+
+/// 解析 `PT1H30M` / `PT90M` / `P1D` 这类常见 DURATION（溢出视为无法解析）。
+fn parse_duration(value: &str) -> Option<Duration> {
+    let body = value.trim().strip_prefix('P')?;
+    let (date_part, time_part) = body.split_once('T').unwrap_or((body, ""));
+    let mut total = Duration::zero();
+    let mut take = |part: &str, units: &[(char, i64)]| -> Option<()> {
+        let mut number = String::new();
+        for ch in part.chars() {
+            if ch.is_ascii_digit() {
+                number.push(ch);
+                continue;
+            }
+            let seconds = units.iter().find(|(unit, _)| *unit == ch)?.1;
+            let part = Duration::try_seconds(number.parse::<i64>().ok()?.checked_mul(seconds)?)?;
+            total = total.checked_add(&part)?;
+            number.clear();
+        }
+        number.is_empty().then_some(())
+    };
+    take(date_part, &[('W', 7 * 86_400), ('D', 86_400)])?;
+    take(time_part, &[('H', 3_600), ('M', 60), ('S', 1)])?;
+    (total > Duration::zero()).then_some(total)
+}
+
+/// 事件 → 自定义课程（同一事件的 BYDAY 多天拆为多门，每门覆盖各自的周次）。
+pub fn map_events_to_courses(
+    semester_start: NaiveDate,
+    events: &[IcsEvent],
+) -> (Vec<CalendarCourse>, Vec<SkippedEntry>) {
+    let mut courses = Vec::new();
+    let mut skipped = Vec::new();
+    for event in events {
+        match map_event(semester_start, event) {
+            Ok((mapped, dropped)) => {
+                courses.extend(mapped);
+                if let Some(first) = dropped.first() {
+                    skipped.push(SkippedEntry {
+                        name: event.summary.clone(),
+                        reason: format!(
+                            "{} 次重复（{} 起）不在学期第 1-{} 周内，已忽略",
+                            dropped.len(),
+                            first.format("%Y-%m-%d"),
+                            MAX_WEEK
+                        ),
+                    });
+                }
+            }
+            Err(reason) => skipped.push(SkippedEntry {
+                name: event.summary.clone(),
+                reason,
+            }),
+        }
+    }
+    (courses, skipped)
+}
+
+fn map_event(
+    semester_start: NaiveDate,
+    event: &IcsEvent,
+) -> Result<(Vec<CalendarCourse>, Vec<NaiveDate>), String> {
+    if event.end.date() != event.start.date() {
+        return Err("跨天事件无法对应课程节次".to_string());
+    }
+    let (period, last_period) = align_periods(event.start.time(), event.end.time())?;
+
+    let occurrences = match event.rrule.as_deref() {
+        Some(rule) => expand_weekly(semester_start, event, rule)?,
+        None => vec![event.start.date()],
+    };
+    let mut by_weekday: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    let mut dropped = Vec::new();
+    for date in occurrences {
+        if event.exdates.contains(&date) {
+            continue;
+        }
+        match semester_week(semester_start, date) {
+            Some(week) => by_weekday
+                .entry(date.weekday().number_from_monday() as i32)
+                .or_default()
+                .push(week),
+            None => dropped.push(date),
+        }
+    }
+    if by_weekday.is_empty() {
+        return Err(if dropped.is_empty() {
+            "所有重复均被 EXDATE 排除".to_string()
+        } else {
+            format!("不在学期第 1-{MAX_WEEK} 周内")
+        });
+    }
+
+    let identity = if event.uid.trim().is_empty() {
+        format!("{}|{}", event.summary, event.start)
+    } else {
+        event.uid.trim().to_string()
+    };
+    let courses = by_weekday
+        .into_iter()
+        .map(|(weekday, mut weeks)| {
+            weeks.sort_unstable();
+            weeks.dedup();
+            CalendarCourse {
+                key: format!("ics|{identity}|{weekday}"),
+                name: event.summary.clone(),
+                teacher: String::new(),
+                room: event.location.clone(),
+                weekday,
+                period,
+                djs: last_period - period + 1,
+                weeks,
+            }
+        })
+        .collect();
+    Ok((courses, dropped))
+}
+
+/// 起止时间对齐到节次：开始贴近某节开始、结束贴近某节结束（容差内）。
+fn align_periods(start: NaiveTime, end: NaiveTime) -> Result<(i32, i32), String> {
+    let near = |a: NaiveTime, b: &str| {
+        parse_clock(b).is_some_and(|b| (a - b).num_minutes().abs() <= ALIGN_TOLERANCE_MINUTES)
+    };
+    let first = CLASS_PERIODS
+        .iter()
+        .position(|(period_start, _)| near(start, period_start));
+    let last = CLASS_PERIODS
+        .iter()
+        .rposition(|(_, period_end)| near(end, period_end));
+    match (first, last) {
+        (Some(first), Some(last)) if last >= first => Ok((first as i32 + 1, last as i32 + 1)),
+        _ => Err(format!(
+            "时间 {}-{} 未对齐课程节次",
+            start.format("%H:%M"),
+            end.format("%H:%M")
+        )),
+    }
+}
+
+/// 展开 `FREQ=WEEKLY` 规则（INTERVAL / BYDAY / COUNT / UNTIL）。
+/// 无终止条件的规则只展开到学期末；有 COUNT / UNTIL 时多展开一年，以便报告超出学期的重复。
+/// INTERVAL 限定在 1..=MAX_WEEK，日期运算越界时整条事件报告为跳过。
+fn expand_weekly(
+    semester_start: NaiveDate,
+    event: &IcsEvent,
+    rule: &str,
+) -> Result<Vec<NaiveDate>, String> {
+    let parts = rule
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim().to_string()))
+        .collect::<BTreeMap<_, _>>();
+    if parts.get("FREQ").map(String::as_str) != Some("WEEKLY") {
+        return Err(format!("仅支持每周重复（{rule}）"));
+    }
+    let interval = match parts.get("INTERVAL") {
+        Some(value) => value
+            .parse::<i64>()
+            .ok()
+            .filter(|v| (1..=i64::from(MAX_WEEK)).contains(v))
+            .ok_or_else(|| format!("INTERVAL {value:?} 不合法"))?,
+        None => 1,
+    };
+    let count = match parts.get("COUNT") {
+        Some(value) => Some(
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("COUNT {value:?} 不合法"))?,
+        ),
+        None => None,
+    };
+    let until = match parts.get("UNTIL") {
+        Some(value) if value.len() == 8 => Some(
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .map_err(|_| format!("UNTIL {value:?} 不合法"))?
+                .and_hms_opt(23, 59, 59)
+                .unwrap_or(event.start),
+        ),
+        Some(value) => Some(
+            parse_ics_property_datetime(value).ok_or_else(|| format!("UNTIL {value:?} 不合法"))?,
+        ),
+        None => None,
+    };
+    let mut weekdays = match parts.get("BYDAY") {
+        Some(value) => value
+            .split(',')
+            .map(|day| parse_weekday(day.trim()).ok_or_else(|| format!("BYDAY {day:?} 不支持")))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![event.start.weekday()],
+    };
+    weekdays.sort_by_key(|day| day.num_days_from_monday());
+    weekdays.dedup();
+
+    let out_of_range = || "重复日期超出可表示范围".to_string();
+    let extra_weeks = if count.is_some() || until.is_some() {
+        52
+    } else {
+        0
+    };
+    let horizon = semester_start
+        .checked_add_signed(Duration::weeks(i64::from(MAX_WEEK) + extra_weeks))
+        .ok_or_else(out_of_range)?;
+    let first_monday = event
+        .start
+        .date()
+        .checked_sub_signed(Duration::days(i64::from(
+            event.start.weekday().num_days_from_monday(),
+        )))
+        .ok_or_else(out_of_range)?;
+    let mut dates = Vec::new();
+    let mut emitted = 0usize;
+    let mut block = first_monday;
+    while block < horizon {
+        for weekday in &weekdays {
+            let date = block
+                .checked_add_signed(Duration::days(i64::from(weekday.num_days_from_monday())))
+                .ok_or_else(out_of_range)?;
+            if date < event.start.date() {
+                continue;
+            }
+            if until.is_some_and(|until| date.and_time(event.start.time()) > until)
+                || count.is_some_and(|count| emitted >= count)
+            {
+                return Ok(dates);
+            }
+            emitted += 1;
+            dates.push(date);
+        }
+        block = block
+            .checked_add_signed(Duration::weeks(interval))
+            .ok_or_else(out_of_range)?;
+    }
+    Ok(dates)
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    Some(match code.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// 日期 → 学期周次（1 起，超出 1..=MAX_WEEK 返回 None）。
+fn semester_week(semester_start: NaiveDate, date: NaiveDate) -> Option<i32> {
+    let days = (date - semester_start).num_days();
+    if days < 0 {
+        return None;
+    }
+    let week = i32::try_from(days / 7).ok()? + 1;
+    (week <= MAX_WEEK).then_some(week)
+}
+
+/// 与官方课表比对：同星期、节次区间相交且周次有交集即视为冲突。
+pub fn find_conflicts(
+    imported: &[CalendarCourse],
+    official: &[CalendarCourse],
+) -> Vec<ImportConflict> {
+    let mut conflicts = Vec::new();
+    for course in imported {
+        let last = course.period + course.djs.max(1) - 1;
+        for other in official.iter().filter(|o| o.weekday == course.weekday) {
+            let other_last = other.period + other.djs.max(1) - 1;
+            let (from, to) = (course.period.max(other.period), last.min(other_last));
+            if from > to {
+                continue;
+            }
+            let weeks = course
+                .weeks
+                .iter()
+                .copied()
+                .filter(|week| other.weeks.contains(week))
+                .collect::<Vec<_>>();
+            if !weeks.is_empty() {
+                conflicts.push(ImportConflict {
+                    name: course.name.clone(),
+                    official_name: other.name.clone(),
+                    weekday: course.weekday,
+                    periods: (from, to),
+                    weeks,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn semester_start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 9, 2).unwrap()
+    }
+
+    fn calendar(events: &[&str]) -> String {
+        let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n");
+        for event in events {
+            ics.push_str("BEGIN:VEVENT\r\n");
+            ics.push_str(event);
+            ics.push_str("END:VEVENT\r\n");
+        }
+        ics.push_str("END:VCALENDAR\r\n");
+        ics
+    }
+
+    fn import(content: &str) -> (Vec<CalendarCourse>, Vec<SkippedEntry>) {
+        let (events, mut skipped) = parse_ics_events(content);
+        let (courses, mapped_skipped) = map_events_to_courses(semester_start(), &events);
+        skipped.extend(mapped_skipped);
+        (courses, skipped)
+    }
+
+    #[test]
+    fn weekly_rrule_maps_to_weeks_weekday_and_periods() {
+        let (courses, skipped) = import(&calendar(&[
+            "UID:club-1\r\nSUMMARY:机器人社\\, 例会\r\nLOCATION:活动中心 201\r\n\
+             DTSTART;TZID=Asia/Shanghai:20240904T183000\r\n\
+             DTEND;TZID=Asia/Shanghai:20240904T200500\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=5\r\nEXDATE;TZID=Asia/Shanghai:20240918T183000\r\n\
+             BEGIN:VALARM\r\nTRIGGER:-PT15M\r\nDESCRIPTION:提醒\r\nEND:VALARM\r\n",
+        ]));
+        assert!(skipped.is_empty(), "{skipped:?}");
+        assert_eq!(courses.len(), 1);
+        let course = &courses[0];
+        assert_eq!(course.name, "机器人社, 例会");
+        assert_eq!(course.room, "活动中心 201");
+        assert_eq!((course.weekday, course.period, course.djs), (3, 9, 2));
+        assert_eq!(course.weeks, vec![1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn byday_interval_and_utc_are_honoured() {
+        // 00:20Z = 08:20 北京时间；隔周的周一、周四
+        let (courses, skipped) = import(&calendar(&[
+            "UID:lab\r\nSUMMARY:实验室值班\r\nDTSTART:20240902T002000Z\r\n\
+             DURATION:PT95M\r\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20240930T235959Z\r\n",
+        ]));
+        assert!(skipped.is_empty(), "{skipped:?}");
+        assert_eq!(courses.len(), 2);
+        assert_eq!(
+            (courses[0].weekday, courses[0].period, courses[0].djs),
+            (1, 1, 2)
+        );
+        assert_eq!(courses[0].weeks, vec![1, 3, 5]);
+        assert_eq!(courses[1].weekday, 4);
+        assert_eq!(courses[1].weeks, vec![1, 3]);
+    }
+
+    #[test]
+    fn unaligned_and_unsupported_events_are_reported() {
+        let (courses, skipped) = import(&calendar(&[
+            "SUMMARY:午间会议\r\nDTSTART:20240903T123000\r\nDTEND:20240903T130000\r\n",
+            "SUMMARY:运动会\r\nDTSTART;VALUE=DATE:20240910\r\nDTEND;VALUE=DATE:20240911\r\n",
+            "SUMMARY:每日打卡\r\nDTSTART:20240902T082000\r\nDTEND:20240902T090500\r\nRRULE:FREQ=DAILY\r\n",
+            "SUMMARY:纽约讲座\r\nDTSTART;TZID=America/New_York:20240902T082000\r\n\
+             DTEND;TZID=America/New_York:20240902T090500\r\n",
+            "SUMMARY:寒假集训\r\nDTSTART:20250407T082000\r\nDTEND:20250407T090500\r\n",
+        ]));
+        assert!(courses.is_empty());
+        let reasons = skipped
+            .iter()
+            .map(|s| s.reason.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(skipped.len(), 5, "{reasons:?}");
+        assert!(reasons[0].contains("全天事件"));
+        assert!(reasons.iter().any(|r| r.contains("12:30-13:00 未对齐")));
+        assert!(reasons.iter().any(|r| r.contains("仅支持每周重复")));
+        assert!(reasons.iter().any(|r| r.contains("America/New_York")));
+        assert!(reasons.iter().any(|r| r.contains("不在学期")));
+    }
+
+    #[test]
+    fn occurrences_beyond_semester_are_reported_but_rest_imported() {
+        let (courses, skipped) = import(&calendar(&[
+            "UID:late\r\nSUMMARY:合唱团\r\nDTSTART:20250317T140300\r\nDTEND:20250317T153500\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=3\r\n",
+        ]));
+        // 2025-03-17 为第 29 周：29、30 周导入，第 31 周报告；14:03 在节次容差内
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0].weeks, vec![29, 30]);
+        assert_eq!((courses[0].period, courses[0].djs), (5, 2));
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].reason.contains("2025-03-31"));
+    }
+
+    #[test]
+    fn overflowing_duration_and_interval_are_skipped() {
+        let (courses, skipped) = import(&calendar(&[
+            "SUMMARY:超长时长\r\nDTSTART:20240902T082000\r\nDURATION:P99999999999999W\r\n",
+            "SUMMARY:超大间隔\r\nDTSTART:20240902T082000\r\nDTEND:20240902T090500\r\n\
+             RRULE:FREQ=WEEKLY;INTERVAL=9223372036854775807;COUNT=2\r\n",
+            "SUMMARY:高数\r\nDTSTART:20240902T082000\r\nDURATION:PT45M\r\n",
+        ]));
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0].name, "高数");
+        let reasons = skipped
+            .iter()
+            .map(|s| s.reason.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(skipped.len(), 2, "{reasons:?}");
+        assert!(reasons[0].contains("DURATION"));
+        assert!(reasons[1].contains("INTERVAL"));
+    }
+
+    #[test]
+    fn conflicts_require_weekday_period_and_week_overlap() {
+        let course = |name: &str, weekday, period, djs, weeks: Vec<i32>| CalendarCourse {
+            key: name.to_string(),
+            name: name.to_string(),
+            teacher: String::new(),
+            room: String::new(),
+            weekday,
+            period,
+            djs,
+            weeks,
+        };
+        let imported = [course("社团", 3, 9, 2, vec![1, 2, 3])];
+        let official = [
+            course("高等数学", 3, 10, 2, vec![2, 3, 4]),
+            course("大学英语", 3, 9, 2, vec![5, 6]),
+            course("线性代数", 2, 9, 2, vec![1, 2, 3]),
+        ];
+        let conflicts = find_conflicts(&imported, &official);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].official_name, "高等数学");
+        assert_eq!(conflicts[0].periods, (10, 10));
+        assert_eq!(conflicts[0].weeks, vec![2, 3]);
+    }
+}
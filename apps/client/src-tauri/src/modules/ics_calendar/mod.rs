@@ -0,0 +1,309 @@
+//! 课表日历（RFC 5545）导出与导入。
+//!
+//! 数据来源均为本地：`schedule_cache`（官方课表 + 校历 `meta.start_date`）、
+//! `custom_schedule_courses`（自定义课程）与 `exams_cache`（考试安排）。
+//! 导出构建逻辑见 [`builder`]，外部 .ics 导入为自定义课程见 [`import`]，
+//! webcal 订阅令牌与实时订阅源见 [`feed`]；
+//! 本模块只负责装载本地数据并组装上下文，Tauri Command 与 HTTP Bridge 共用。
+
+mod builder;
+mod feed;
+mod import;
+
+pub use builder::{
+    build_calendars, parse_clock, period_time, shanghai_vtimezone, CalendarContext, CalendarCourse,
+    CalendarExam, CalendarKind, IcsBuild, IcsCalendar, IcsExportOptions, SkippedEntry,
+    CLASS_PERIODS, SHANGHAI_TZID,
+};
+pub use feed::{
+    build_feed_calendar, build_feed_payload, feed_path, issue_feed_token, resolve_feed_token,
+    revoke_feed_tokens, FeedTokenRecord, ScheduleFeedRequest, FEED_REFRESH_MINUTES,
+};
+pub use import::{
+    find_conflicts, map_events_to_courses, parse_ics_events, IcsEvent, ImportConflict,
+};
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::db;
+use crate::utils::ics::sanitize_filename_part;
+
+/// 导出请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleIcsRequest {
+    pub student_id: Option<String>,
+    /// 缺省取本地课表缓存的学期；指定时须与缓存一致
+    pub semester: Option<String>,
+    /// 第 1 周周一（YYYY-MM-DD）；缺省取课表缓存 `meta.start_date`
+    pub semester_start: Option<String>,
+    #[serde(default)]
+    pub options: IcsExportOptions,
+}
+
+/// 导入请求（Tauri / Bridge 入参）：`commit=false` 仅预览，`commit=true` 一次事务写入。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleIcsImportRequest {
+    pub student_id: Option<String>,
+    /// 缺省取本地课表缓存的学期；指定时须与缓存一致
+    pub semester: Option<String>,
+    /// 第 1 周周一（YYYY-MM-DD）；缺省取课表缓存 `meta.start_date`
+    pub semester_start: Option<String>,
+    /// .ics 文件文本
+    pub content: String,
+    /// 导入课程统一颜色（#RRGGBB，可空）
+    pub color: Option<String>,
+    #[serde(default)]
+    pub commit: bool,
+}
+
+/// 导入结果：待写入（或已写入）的自定义课程、与官方课表的冲突、未能对应节次的条目。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcsImport {
+    pub semester: String,
+    pub committed: bool,
+    pub courses: Vec<db::CustomScheduleCourseRecord>,
+    pub conflicts: Vec<ImportConflict>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// 课表缓存中的学期上下文（导出、导入与 `schedule_analysis` 共用）。
+pub(crate) struct ScheduleContext {
+    pub(crate) semester: String,
+    pub(crate) semester_start: NaiveDate,
+    pub(crate) official: Vec<crate::ScheduleCourse>,
+}
+
+pub(crate) fn load_schedule_context(
+    path: &Path,
+    sid: &str,
+    semester: Option<&str>,
+    semester_start: Option<&str>,
+) -> Result<ScheduleContext, String> {
+    let (payload, _) = db::get_cache(path, "schedule_cache", sid)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "暂无课表缓存，请先同步课表".to_string())?;
+    let meta = payload.get("meta").cloned().unwrap_or(Value::Null);
+    let cached_semester = meta_str(&meta, "semester");
+
+    let semester = match semester.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(requested) if cached_semester.as_deref().is_some_and(|c| c != requested) => {
+            return Err(format!(
+                "本地课表缓存为 {} 学期，请先同步 {} 学期课表",
+                cached_semester.unwrap_or_default(),
+                requested
+            ));
+        }
+        Some(requested) => requested.to_string(),
+        None => cached_semester.ok_or_else(|| "课表缓存缺少学期信息".to_string())?,
+    };
+
+    let semester_start = semester_start
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .or_else(|| meta_str(&meta, "start_date"))
+        .ok_or_else(|| "缺少学期开始日期（semester_start）".to_string())?;
+    let semester_start = NaiveDate::parse_from_str(&semester_start, "%Y-%m-%d")
+        .map_err(|_| format!("学期开始日期格式错误: {}", semester_start))?;
+
+    let official: Vec<crate::ScheduleCourse> = payload
+        .get("data")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("课表缓存解析失败: {e}"))?
+        .unwrap_or_default();
+    Ok(ScheduleContext {
+        semester,
+        semester_start,
+        official,
+    })
+}
+
+/// 本地数据 → 日历。
+pub fn build_schedule_ics<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &ScheduleIcsRequest,
+) -> Result<IcsBuild, String> {
+    let path = path.as_ref();
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    let ScheduleContext {
+        semester,
+        semester_start,
+        official,
+    } = load_schedule_context(
+        path,
+        sid,
+        req.semester.as_deref(),
+        req.semester_start.as_deref(),
+    )?;
+    let custom =
+        db::list_custom_schedule_courses(path, sid, &semester).map_err(|e| e.to_string())?;
+    let courses = official
+        .iter()
+        .map(CalendarCourse::from)
+        .chain(custom.iter().map(CalendarCourse::from))
+        .collect::<Vec<_>>();
+
+    let exams = if req.options.include_exams {
+        load_cached_exams(path, sid, &semester)
+    } else {
+        Vec::new()
+    };
+
+    let context = CalendarContext {
+        student_id: sid.to_string(),
+        semester,
+        semester_start,
+        generated_at: Utc::now(),
+    };
+    Ok(build_calendars(&context, &courses, &exams, &req.options))
+}
+
+/// 外部 .ics → 自定义课程：解析、对齐节次、比对官方课表；`commit` 时同一事务写入。
+pub fn import_schedule_ics<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &ScheduleIcsImportRequest,
+) -> Result<IcsImport, String> {
+    let path = path.as_ref();
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    if req.content.trim().is_empty() {
+        return Err("日历文件内容为空".to_string());
+    }
+    let color = db::normalize_course_color(req.color.as_deref())
+        .ok_or_else(|| "颜色格式不合法，请使用 #RRGGBB".to_string())?;
+    let context = load_schedule_context(
+        path,
+        sid,
+        req.semester.as_deref(),
+        req.semester_start.as_deref(),
+    )?;
+
+    let (events, mut skipped) = parse_ics_events(&req.content);
+    if events.is_empty() && skipped.is_empty() {
+        return Err("未在文件中找到日历事件（VEVENT）".to_string());
+    }
+    let (imported, unmapped) = map_events_to_courses(context.semester_start, &events);
+    skipped.extend(unmapped);
+    let official = context
+        .official
+        .iter()
+        .map(CalendarCourse::from)
+        .collect::<Vec<_>>();
+    let conflicts = find_conflicts(&imported, &official);
+
+    let now = chrono::Local::now().to_rfc3339();
+    let courses = imported
+        .iter()
+        .map(|course| db::CustomScheduleCourseRecord {
+            // 由来源事件派生：重复导入同一文件时覆盖而非重复添加
+            id: format!(
+                "c{:x}",
+                md5::compute(format!("{sid}|{}|{}", context.semester, course.key))
+            ),
+            student_id: sid.to_string(),
+            semester: context.semester.clone(),
+            name: course.name.clone(),
+            teacher: course.teacher.clone(),
+            room: course.room.clone(),
+            weekday: course.weekday,
+            period: course.period,
+            djs: course.djs,
+            weeks: course.weeks.clone(),
+            color: color.clone(),
+            created_at: now.clone(),
+            updated_at: now.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let committed = req.commit && !courses.is_empty();
+    if committed {
+        db::add_custom_schedule_courses(path, &courses).map_err(|e| e.to_string())?;
+    }
+    Ok(IcsImport {
+        semester: context.semester,
+        committed,
+        courses,
+        conflicts,
+        skipped,
+    })
+}
+
+/// 导出文件名：`schedule_<学号>_<学期>_<类别>.ics`（不含时间戳，便于覆盖旧文件）。
+pub fn calendar_filename(student_id: &str, semester: &str, kind: CalendarKind) -> String {
+    format!(
+        "schedule_{}_{}_{}.ics",
+        sanitize_filename_part(student_id),
+        sanitize_filename_part(semester),
+        kind.as_str()
+    )
+}
+
+/// 组装传输层返回体（两通道一致）。
+pub fn build_payload(student_id: &str, build: IcsBuild) -> Value {
+    let semester = build.semester.as_str();
+    let calendars = build
+        .calendars
+        .into_iter()
+        .map(|calendar| {
+            serde_json::json!({
+                "kind": calendar.kind,
+                "name": calendar.name,
+                "filename": calendar_filename(student_id, semester, calendar.kind),
+                "content_type": "text/calendar; charset=utf-8",
+                "event_count": calendar.event_count,
+                "content": calendar.content,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "success": true,
+        "calendars": calendars,
+        "semester": build.semester,
+        "skipped": build.skipped,
+    })
+}
+
+/// 组装导入返回体（两通道一致）。
+pub fn build_import_payload(import: IcsImport) -> Value {
+    serde_json::json!({
+        "success": true,
+        "semester": import.semester,
+        "committed": import.committed,
+        "imported": if import.committed { import.courses.len() } else { 0 },
+        "courses": import.courses,
+        "conflicts": import.conflicts,
+        "skipped": import.skipped,
+    })
+}
+
+/// 考试缓存（键约定见 [`crate::modules::exam::load_cached_exams`]）。
+pub(crate) fn load_cached_exams(
+    path: &Path,
+    student_id: &str,
+    semester: &str,
+) -> Vec<CalendarExam> {
+    crate::modules::exam::load_cached_exams(path, student_id, semester)
+        .iter()
+        .map(CalendarExam::from)
+        .collect()
+}
+
+fn meta_str(meta: &Value, key: &str) -> Option<String> {
+    meta.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
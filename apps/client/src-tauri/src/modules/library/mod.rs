@@ -0,0 +1,520 @@
+//! 图书馆读者账户：当前借阅、借阅历史、预约、欠款与续借。
+//!
+//! `HbutClient` 的读者中心接口返回 OPAC 原始 JSON（字段名随 OPAC 版本变化），
+//! 这里统一归一化为 [`LibraryLoan`] / [`LibraryHold`] / [`LibraryFine`]。归一化后的载荷
+//! 按账号写入 `library_account_cache`（键 `<学号>:<分区>`），读取经
+//! `AcademicReadService` 的「网络优先 + 缓存降级」路径；到期提醒见 [`reminder`]，
+//! 检索订阅与新书提醒见 [`saved_search`]。
+
+mod reminder;
+mod saved_search;
+
+pub use reminder::{
+    due_reminders, load_reminder_days, save_reminder_days, track_due_loans, LoanReminder,
+    DEFAULT_REMINDER_DAYS,
+};
+pub use saved_search::{
+    check_params, delete_saved_search, list_saved_searches, parse_search_hits, poll_saved_search,
+    record_search_check, run_saved_search, save_saved_search, LibrarySavedSearchIdRequest,
+    LibrarySavedSearchRequest, SavedSearch, SearchCheck, SearchHit, MAX_SAVED_SEARCHES,
+    REQUEST_SPACING,
+};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::db;
+use crate::http_client::HbutClient;
+
+/// 读者账户缓存表。
+pub const ACCOUNT_CACHE_TABLE: &str = "library_account_cache";
+/// 单次续借的册数上限。
+pub const MAX_RENEW_BATCH: usize = 20;
+
+/// 缓存分区。
+pub const SECTION_LOANS: &str = "loans";
+pub const SECTION_HOLDS: &str = "holds";
+pub const SECTION_FINES: &str = "fines";
+
+/// 读者账户缓存键；借阅历史按页缓存（`history:<页>:<每页条数>`）。
+pub fn account_cache_key(student_id: &str, section: &str) -> String {
+    format!("{}:{section}", student_id.trim())
+}
+
+pub fn history_cache_key(student_id: &str, page: i64, rows: i64) -> String {
+    account_cache_key(student_id, &format!("history:{page}:{rows}"))
+}
+
+/// 一条借阅记录（当前借阅与借阅历史共用）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryLoan {
+    /// 条码号（续借按条码提交）
+    pub barcode: String,
+    pub title: String,
+    pub author: String,
+    pub call_no: String,
+    pub location: String,
+    /// 日期统一为 `YYYY-MM-DD`
+    pub loan_date: Option<String>,
+    pub due_date: Option<String>,
+    /// 实际归还日期（借阅历史）
+    pub return_date: Option<String>,
+    pub renew_count: u32,
+    /// 上游未标注时为 `None`
+    pub renewable: Option<bool>,
+}
+
+impl LibraryLoan {
+    pub fn from_row(row: &Value) -> Self {
+        Self {
+            barcode: text(
+                row,
+                &[
+                    "barcode",
+                    "barCode",
+                    "itemBarcode",
+                    "propNo",
+                    "loanId",
+                    "id",
+                ],
+            ),
+            title: text(row, &["title", "bookName", "tm", "name"]),
+            author: text(row, &["author", "zz"]),
+            call_no: text(row, &["callNo", "callno", "searchCode", "ssh"]),
+            location: text(
+                row,
+                &["locationName", "curLocationName", "location", "libName"],
+            ),
+            loan_date: date(row, &["loanDate", "borrowDate", "lendDate", "jsrq"]),
+            due_date: date(
+                row,
+                &[
+                    "dueDate",
+                    "normReturnDate",
+                    "shouldReturnDate",
+                    "returnDate",
+                    "yhrq",
+                ],
+            ),
+            return_date: date(
+                row,
+                &["realReturnDate", "actualReturnDate", "backDate", "ghrq"],
+            ),
+            renew_count: number(row, &["renewCount", "renewTimes", "xjcs"])
+                .map(|count| count.max(0.0) as u32)
+                .unwrap_or(0),
+            renewable: flag(row, &["canRenew", "renewable", "isRenew"]),
+        }
+    }
+
+    pub fn due(&self) -> Option<NaiveDate> {
+        self.due_date
+            .as_deref()
+            .and_then(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d").ok())
+    }
+}
+
+/// 一条预约 / 预借记录。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryHold {
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    /// 取书地点
+    pub location: String,
+    pub status: String,
+    pub reserve_date: Option<String>,
+    /// 保留截止日期
+    pub expire_date: Option<String>,
+    /// 排队位置
+    pub queue_position: Option<u32>,
+}
+
+impl LibraryHold {
+    pub fn from_row(row: &Value) -> Self {
+        Self {
+            id: text(row, &["reservationId", "reserveId", "id", "barcode"]),
+            title: text(row, &["title", "bookName", "tm", "name"]),
+            author: text(row, &["author", "zz"]),
+            location: text(
+                row,
+                &["pickupLocationName", "pickupLocation", "locationName"],
+            ),
+            status: text(row, &["statusName", "status", "state"]),
+            reserve_date: date(row, &["reserveDate", "reservationDate", "createTime"]),
+            expire_date: date(row, &["expireDate", "pickupEndDate", "endDate", "deadline"]),
+            queue_position: number(row, &["queueNo", "queuePosition", "position"])
+                .filter(|position| *position >= 1.0)
+                .map(|position| position as u32),
+        }
+    }
+}
+
+/// 一条欠款（超期罚款、损坏赔偿等）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryFine {
+    pub id: String,
+    pub barcode: String,
+    pub title: String,
+    pub reason: String,
+    /// 金额（元）
+    pub amount: f64,
+    /// 上游未标注时为 `None`
+    pub paid: Option<bool>,
+    pub date: Option<String>,
+}
+
+impl LibraryFine {
+    pub fn from_row(row: &Value) -> Self {
+        Self {
+            id: text(row, &["fineId", "id"]),
+            barcode: text(row, &["barcode", "barCode", "propNo"]),
+            title: text(row, &["title", "bookName", "tm", "name"]),
+            reason: text(row, &["fineTypeName", "fineType", "reason", "type"]),
+            amount: number(row, &["amount", "fineAmount", "fine", "money", "je"]).unwrap_or(0.0),
+            paid: flag(row, &["isPaid", "paid", "payStatus", "status"]),
+            date: date(row, &["fineDate", "createTime", "date"]),
+        }
+    }
+}
+
+/// 续借的逐册结果。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenewOutcome {
+    pub barcode: String,
+    pub success: bool,
+    pub message: String,
+    /// 续借后的应还日期
+    pub due_date: Option<String>,
+}
+
+/// 续借请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryRenewRequest {
+    pub barcodes: Vec<String>,
+}
+
+/// 列表行：`data` 为数组，或 `data.list` / `data.records` / `data.rows` 等分页结构。
+fn rows(raw: &Value) -> Vec<&Value> {
+    let data = raw.get("data").unwrap_or(&Value::Null);
+    if let Some(items) = data.as_array() {
+        return items.iter().collect();
+    }
+    [
+        "list",
+        "records",
+        "rows",
+        "content",
+        "dataList",
+        "searchResult",
+    ]
+    .iter()
+    .find_map(|key| data.get(*key).and_then(Value::as_array))
+    .map(|items| items.iter().collect())
+    .unwrap_or_default()
+}
+
+fn total(raw: &Value, fallback: usize) -> u64 {
+    let data = raw.get("data").unwrap_or(&Value::Null);
+    ["total", "totalCount", "numFound", "totalElements"]
+        .iter()
+        .find_map(|key| {
+            data.get(*key).and_then(|value| {
+                value
+                    .as_u64()
+                    .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+            })
+        })
+        .unwrap_or(fallback as u64)
+}
+
+fn text(row: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| match row.get(*key) {
+            Some(Value::String(value)) if !value.trim().is_empty() => {
+                Some(value.trim().to_string())
+            }
+            Some(Value::Number(value)) => Some(value.to_string()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn number(row: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| match row.get(*key) {
+        Some(Value::Number(value)) => value.as_f64(),
+        Some(Value::String(value)) => value.trim().parse::<f64>().ok(),
+        _ => None,
+    })
+}
+
+fn flag(row: &Value, keys: &[&str]) -> Option<bool> {
+    keys.iter().find_map(|key| match row.get(*key) {
+        Some(Value::Bool(value)) => Some(*value),
+        Some(Value::Number(value)) => value.as_i64().map(|value| value != 0),
+        Some(Value::String(value)) => match value.trim() {
+            "1" | "true" | "是" | "可续借" | "已缴" | "已缴费" | "已支付" => Some(true),
+            "0" | "false" | "否" | "不可续借" | "未缴" | "未缴费" | "未支付" => {
+                Some(false)
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// 日期字段：`YYYY-MM-DD[ HH:MM:SS]`、`YYYY/MM/DD` 或毫秒 / 秒时间戳，统一为 `YYYY-MM-DD`。
+fn date(row: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match row.get(*key) {
+        Some(Value::String(value)) => {
+            let value = value.trim().replace('/', "-");
+            let day = value.get(..10).unwrap_or(&value);
+            NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.format("%Y-%m-%d").to_string())
+        }
+        Some(Value::Number(value)) => {
+            let stamp = value.as_i64().filter(|stamp| *stamp > 0)?;
+            let secs = if stamp > 100_000_000_000 {
+                stamp / 1000
+            } else {
+                stamp
+            };
+            Local
+                .timestamp_opt(secs, 0)
+                .single()
+                .map(|time| time.format("%Y-%m-%d").to_string())
+        }
+        _ => None,
+    })
+}
+
+pub fn parse_loans(raw: &Value) -> Vec<LibraryLoan> {
+    rows(raw).into_iter().map(LibraryLoan::from_row).collect()
+}
+
+pub fn parse_holds(raw: &Value) -> Vec<LibraryHold> {
+    rows(raw).into_iter().map(LibraryHold::from_row).collect()
+}
+
+pub fn parse_fines(raw: &Value) -> Vec<LibraryFine> {
+    rows(raw).into_iter().map(LibraryFine::from_row).collect()
+}
+
+/// 当前借阅载荷（写入 `library_account_cache`）。
+pub fn loans_payload(raw: &Value) -> Value {
+    let loans = parse_loans(raw);
+    json!({ "success": true, "total": loans.len(), "data": loans })
+}
+
+pub fn history_payload(raw: &Value, page: i64, rows: i64) -> Value {
+    let loans = parse_loans(raw);
+    json!({
+        "success": true,
+        "total": total(raw, loans.len()),
+        "page": page,
+        "rows": rows,
+        "data": loans,
+    })
+}
+
+pub fn holds_payload(raw: &Value) -> Value {
+    let holds = parse_holds(raw);
+    json!({ "success": true, "total": holds.len(), "data": holds })
+}
+
+/// 欠款载荷；`unpaid_amount` 为未标注已缴的金额合计（元）。
+pub fn fines_payload(raw: &Value) -> Value {
+    let fines = parse_fines(raw);
+    let unpaid = fines
+        .iter()
+        .filter(|fine| fine.paid != Some(true))
+        .map(|fine| fine.amount)
+        .sum::<f64>();
+    json!({
+        "success": true,
+        "total": fines.len(),
+        "unpaid_amount": (unpaid * 100.0).round() / 100.0,
+        "data": fines,
+    })
+}
+
+/// 解析续借响应：逐册结果按条码对齐请求；上游只给整体结果时每册沿用整体结果。
+pub fn parse_renew_outcomes(raw: &Value, barcodes: &[String]) -> Vec<RenewOutcome> {
+    let overall = raw.get("success").and_then(Value::as_bool).unwrap_or(false);
+    let overall_message = text(raw, &["message", "msg"]);
+    let items = rows(raw);
+    barcodes
+        .iter()
+        .map(|barcode| {
+            let item = items.iter().find(|item| {
+                text(item, &["barcode", "barCode", "itemBarcode", "propNo"]) == *barcode
+            });
+            match item {
+                Some(item) => RenewOutcome {
+                    barcode: barcode.clone(),
+                    success: flag(item, &["success", "result", "isSuccess"]).unwrap_or(overall),
+                    message: text(item, &["message", "msg", "reason"]),
+                    due_date: date(
+                        item,
+                        &["dueDate", "normReturnDate", "returnDate", "newReturnDate"],
+                    ),
+                },
+                None => RenewOutcome {
+                    barcode: barcode.clone(),
+                    success: overall,
+                    message: overall_message.clone(),
+                    due_date: None,
+                },
+            }
+        })
+        .collect()
+}
+
+fn normalize_barcodes(barcodes: Vec<String>) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    let barcodes = barcodes
+        .into_iter()
+        .map(|barcode| barcode.trim().to_string())
+        .filter(|barcode| !barcode.is_empty() && seen.insert(barcode.clone()))
+        .collect::<Vec<_>>();
+    if barcodes.is_empty() {
+        return Err("请选择要续借的图书".to_string());
+    }
+    if barcodes.len() > MAX_RENEW_BATCH {
+        return Err(format!("单次最多续借 {MAX_RENEW_BATCH} 册"));
+    }
+    Ok(barcodes)
+}
+
+/// 续借并刷新当前借阅缓存；续借后应还日期变化，到期提醒随之重新计算。
+///
+/// 刷新失败不影响续借结果，返回的 `loans` 为 `null`。
+pub async fn renew_loans<P: AsRef<Path>>(
+    client: &mut HbutClient,
+    path: P,
+    barcodes: Vec<String>,
+) -> Result<Value, String> {
+    let barcodes = normalize_barcodes(barcodes)?;
+    let raw = client
+        .renew_library_loans(&barcodes)
+        .await
+        .map_err(|e| e.to_string())?;
+    let results = parse_renew_outcomes(&raw, &barcodes);
+    let renewed = results.iter().filter(|outcome| outcome.success).count();
+
+    let loans = match client.fetch_library_current_loans().await {
+        Ok(raw) => {
+            let sync_time = Local::now().to_rfc3339();
+            let payload = crate::attach_sync_time(loans_payload(&raw), &sync_time, false);
+            if let Some(user) = client.user_info.as_ref() {
+                let key = account_cache_key(&user.student_id, SECTION_LOANS);
+                if let Err(e) = db::save_cache(path, ACCOUNT_CACHE_TABLE, &key, &payload) {
+                    crate::runtime_log::log_warn("library", format!("续借后写入借阅缓存失败: {e}"));
+                }
+            }
+            payload
+        }
+        Err(e) => {
+            crate::runtime_log::log_warn("library", format!("续借后刷新借阅失败: {e}"));
+            Value::Null
+        }
+    };
+
+    Ok(json!({
+        "success": true,
+        "renewed": renewed,
+        "results": results,
+        "loans": loans,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loans_normalize_field_variants_and_dates() {
+        let raw = json!({
+            "success": true,
+            "data": {
+                "total": 2,
+                "list": [
+                    {
+                        "barcode": "A001",
+                        "title": " 算法导论 ",
+                        "author": "Cormen",
+                        "callNo": "TP301.6",
+                        "locationName": "自然科学书库",
+                        "loanDate": "2025-03-01 10:12:00",
+                        "normReturnDate": "2025/04/01",
+                        "renewCount": "1",
+                        "canRenew": "1"
+                    },
+                    { "propNo": 20045, "tm": "线性代数", "yhrq": "bad", "canRenew": false }
+                ]
+            }
+        });
+        let loans = parse_loans(&raw);
+        assert_eq!(loans.len(), 2);
+        assert_eq!(loans[0].barcode, "A001");
+        assert_eq!(loans[0].title, "算法导论");
+        assert_eq!(loans[0].loan_date.as_deref(), Some("2025-03-01"));
+        assert_eq!(loans[0].due_date.as_deref(), Some("2025-04-01"));
+        assert_eq!(loans[0].renew_count, 1);
+        assert_eq!(loans[0].renewable, Some(true));
+        assert_eq!(loans[1].barcode, "20045");
+        assert_eq!(loans[1].due(), None);
+        assert_eq!(loans[1].renewable, Some(false));
+
+        let history = history_payload(&raw, 1, 20);
+        assert_eq!(history["total"], 2);
+        assert_eq!(history["data"][0]["call_no"], "TP301.6");
+    }
+
+    #[test]
+    fn fines_sum_unpaid_and_renew_outcomes_follow_request_order() {
+        let fines = fines_payload(&json!({
+            "success": true,
+            "data": [
+                { "fineId": "f1", "amount": 1.2, "payStatus": "未缴" },
+                { "fineId": "f2", "amount": "0.35" },
+                { "fineId": "f3", "amount": 5, "isPaid": true }
+            ]
+        }));
+        assert_eq!(fines["unpaid_amount"], 1.55);
+        assert_eq!(fines["total"], 3);
+
+        let barcodes = vec!["A001".to_string(), "B002".to_string()];
+        let per_item = parse_renew_outcomes(
+            &json!({
+                "success": true,
+                "data": [
+                    { "barcode": "B002", "success": false, "message": "超过续借次数" },
+                    { "barcode": "A001", "success": true, "returnDate": "2025-05-01" }
+                ]
+            }),
+            &barcodes,
+        );
+        assert!(per_item[0].success);
+        assert_eq!(per_item[0].due_date.as_deref(), Some("2025-05-01"));
+        assert!(!per_item[1].success);
+        assert_eq!(per_item[1].message, "超过续借次数");
+
+        let overall = parse_renew_outcomes(
+            &json!({ "success": true, "message": "续借成功" }),
+            &barcodes,
+        );
+        assert!(overall.iter().all(|outcome| outcome.success));
+        assert_eq!(overall[1].message, "续借成功");
+
+        assert!(normalize_barcodes(vec![" ".to_string()]).is_err());
+        assert_eq!(
+            normalize_barcodes(vec!["A".into(), " A ".into(), "B".into()]).unwrap(),
+            vec!["A", "B"]
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! 借阅到期提醒：按提前天数（默认 3 天 / 1 天）在应还日前提醒，逾期后再提醒一次。
+//!
+//! 已发送的提醒键按账号存于 kv_store `library.due_notified.<学号>`：同一册书的同一提前量
+//! 只提醒一次；键含应还日期，续借后应还日期变化会重新提醒。提前天数为全局设置。
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::LibraryLoan;
+use crate::db;
+
+/// 默认提醒提前量（天）。
+pub const DEFAULT_REMINDER_DAYS: [u32; 2] = [3, 1];
+
+const REMINDER_DAYS_KEY: &str = "library.reminder_days";
+const NOTIFIED_KV_PREFIX: &str = "library.due_notified.";
+const MAX_REMINDER_DAYS: usize = 5;
+const MAX_LEAD_DAYS: u32 = 30;
+const OVERDUE_LEAD: &str = "overdue";
+
+/// 一条到期（或逾期）提醒。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoanReminder {
+    pub loan: LibraryLoan,
+    /// 触发的提前量（天）；逾期提醒为 `None`
+    pub lead_days: Option<u32>,
+    /// 距应还日的天数，逾期为负
+    pub days_left: i64,
+    /// 本次提醒覆盖的提醒键（含被跳过的更早提前量）
+    #[serde(skip)]
+    pub keys: Vec<String>,
+}
+
+impl LoanReminder {
+    pub fn title(&self) -> String {
+        if self.days_left < 0 {
+            "图书逾期提醒".to_string()
+        } else {
+            "图书到期提醒".to_string()
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let due = self.loan.due_date.as_deref().unwrap_or_default();
+        match self.days_left {
+            days if days < 0 => format!(
+                "《{}》已逾期 {} 天（应还 {due}），请尽快归还",
+                self.loan.title, -days
+            ),
+            0 => format!(
+                "《{}》今天到期（应还 {due}），请及时归还或续借",
+                self.loan.title
+            ),
+            days => format!(
+                "《{}》还有 {days} 天到期（应还 {due}），请及时归还或续借",
+                self.loan.title
+            ),
+        }
+    }
+}
+
+/// 借阅的身份：条码缺失时退回书名。
+fn loan_identity(loan: &LibraryLoan) -> &str {
+    if loan.barcode.trim().is_empty() {
+        loan.title.trim()
+    } else {
+        loan.barcode.trim()
+    }
+}
+
+fn reminder_key(loan: &LibraryLoan, due: NaiveDate, lead: &str) -> String {
+    format!("{}|{}|{lead}", loan_identity(loan), due.format("%Y-%m-%d"))
+}
+
+/// 到期提醒：同一册书只取已进入的最小提前量，更早的提前量一并标记为已提醒；
+/// 逾期时发一次逾期提醒并覆盖全部提前量。已归还或没有应还日期的记录跳过。
+pub fn due_reminders(
+    loans: &[LibraryLoan],
+    days: &[u32],
+    notified: &BTreeSet<String>,
+    today: NaiveDate,
+) -> Vec<LoanReminder> {
+    let mut leads = days.to_vec();
+    leads.sort_unstable();
+    leads.dedup();
+
+    let mut reminders = Vec::new();
+    for loan in loans {
+        if loan.return_date.is_some() {
+            continue;
+        }
+        let Some(due) = loan.due() else {
+            continue;
+        };
+        let days_left = (due - today).num_days();
+        let entered = leads
+            .iter()
+            .copied()
+            .filter(|lead| days_left <= i64::from(*lead))
+            .collect::<Vec<_>>();
+        let lead_days = if days_left < 0 {
+            None
+        } else {
+            match entered.first() {
+                Some(&lead) => Some(lead),
+                None => continue,
+            }
+        };
+        let trigger = lead_days.map_or_else(|| OVERDUE_LEAD.to_string(), |lead| lead.to_string());
+        if notified.contains(&reminder_key(loan, due, &trigger)) {
+            continue;
+        }
+        let mut keys = entered
+            .iter()
+            .map(|lead| reminder_key(loan, due, &lead.to_string()))
+            .collect::<Vec<_>>();
+        if lead_days.is_none() {
+            keys.push(reminder_key(loan, due, OVERDUE_LEAD));
+        }
+        reminders.push(LoanReminder {
+            loan: loan.clone(),
+            lead_days,
+            days_left,
+            keys,
+        });
+    }
+    reminders
+}
+
+fn notified_key(student_id: &str) -> String {
+    format!("{NOTIFIED_KV_PREFIX}{}", student_id.trim())
+}
+
+/// 计算到期提醒并记录提醒键；只保留仍在借的书的提醒键，防止记录无限增长。
+pub fn track_due_loans<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    loans: &[LibraryLoan],
+    days: &[u32],
+    today: NaiveDate,
+) -> Result<Vec<LoanReminder>, String> {
+    let path = path.as_ref();
+    let kv_key = notified_key(student_id);
+    let previous = db::get_kv(path, &kv_key)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<BTreeSet<String>>(&raw).ok())
+        .unwrap_or_default();
+
+    let reminders = due_reminders(loans, days, &previous, today);
+    let mut notified = previous.clone();
+    notified.extend(reminders.iter().flat_map(|r| r.keys.iter().cloned()));
+    let live = loans
+        .iter()
+        .filter_map(|loan| {
+            loan.due()
+                .map(|due| format!("{}|{}", loan_identity(loan), due.format("%Y-%m-%d")))
+        })
+        .collect::<BTreeSet<_>>();
+    notified.retain(|key| {
+        key.rsplit_once('|')
+            .is_some_and(|(loan_key, _)| live.contains(loan_key))
+    });
+
+    if notified != previous {
+        let raw = serde_json::to_string(&notified).map_err(|e| e.to_string())?;
+        db::set_kv(path, &kv_key, &raw).map_err(|e| e.to_string())?;
+    }
+    Ok(reminders)
+}
+
+/// 读取提醒提前量（天，降序）；缺失或损坏时回退默认值。
+pub fn load_reminder_days<P: AsRef<Path>>(path: P) -> Vec<u32> {
+    db::get_kv(path, REMINDER_DAYS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<Vec<u32>>(&raw).ok())
+        .and_then(|days| normalize_days(days).ok())
+        .unwrap_or_else(|| DEFAULT_REMINDER_DAYS.to_vec())
+}
+
+/// 校验后写入提醒提前量；空列表表示只做逾期提醒，`0` 表示到期当天提醒。
+pub fn save_reminder_days<P: AsRef<Path>>(path: P, days: Vec<u32>) -> Result<Vec<u32>, String> {
+    let days = normalize_days(days)?;
+    let raw = serde_json::to_string(&days).map_err(|e| e.to_string())?;
+    db::set_kv(path, REMINDER_DAYS_KEY, &raw).map_err(|e| e.to_string())?;
+    Ok(days)
+}
+
+fn normalize_days(mut days: Vec<u32>) -> Result<Vec<u32>, String> {
+    days.sort_unstable_by(|a, b| b.cmp(a));
+    days.dedup();
+    if days.len() > MAX_REMINDER_DAYS {
+        return Err(format!("最多设置 {MAX_REMINDER_DAYS} 个提醒时间"));
+    }
+    if days.iter().any(|day| *day > MAX_LEAD_DAYS) {
+        return Err(format!("提醒提前量需在 0 到 {MAX_LEAD_DAYS} 天之间"));
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_library_reminder_{label}_{nanos}.db"))
+    }
+
+    fn loan(barcode: &str, title: &str, due: &str) -> LibraryLoan {
+        LibraryLoan {
+            barcode: barcode.to_string(),
+            title: title.to_string(),
+            due_date: Some(due.to_string()),
+            ..LibraryLoan::default()
+        }
+    }
+
+    fn day(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn reminders_fire_once_per_lead_then_once_when_overdue() {
+        let loans = vec![loan("A001", "算法导论", "2025-04-10")];
+        let days = DEFAULT_REMINDER_DAYS;
+        let mut notified = BTreeSet::new();
+
+        assert!(due_reminders(&loans, &days, &notified, day("2025-04-01")).is_empty());
+
+        // 首次检查已在 1 天内：只发 1 天提醒，3 天一并标记
+        let late = due_reminders(&loans, &days, &notified, day("2025-04-10"));
+        assert_eq!(late[0].lead_days, Some(1));
+        assert_eq!(late[0].keys.len(), 2);
+        assert_eq!(
+            late[0].body(),
+            "《算法导论》今天到期（应还 2025-04-10），请及时归还或续借"
+        );
+
+        let first = due_reminders(&loans, &days, &notified, day("2025-04-07"));
+        assert_eq!(first[0].lead_days, Some(3));
+        notified.extend(first[0].keys.clone());
+        assert!(due_reminders(&loans, &days, &notified, day("2025-04-08")).is_empty());
+        let second = due_reminders(&loans, &days, &notified, day("2025-04-09"));
+        assert_eq!(second[0].lead_days, Some(1));
+        notified.extend(second[0].keys.clone());
+
+        let overdue = due_reminders(&loans, &days, &notified, day("2025-04-12"));
+        assert_eq!(overdue[0].lead_days, None);
+        assert_eq!(overdue[0].title(), "图书逾期提醒");
+        assert_eq!(
+            overdue[0].body(),
+            "《算法导论》已逾期 2 天（应还 2025-04-10），请尽快归还"
+        );
+        notified.extend(overdue[0].keys.clone());
+        assert!(due_reminders(&loans, &days, &notified, day("2025-04-13")).is_empty());
+
+        // 已归还的借阅记录不提醒
+        let mut returned = loans[0].clone();
+        returned.return_date = Some("2025-04-09".to_string());
+        assert!(due_reminders(&[returned], &days, &BTreeSet::new(), day("2025-04-12")).is_empty());
+    }
+
+    #[test]
+    fn track_due_loans_dedupes_prunes_and_rearms_after_renewal() {
+        let path = temp_db_path("track");
+        init_db(&path).unwrap();
+        let days = [1];
+        let loans = vec![
+            loan("A001", "算法导论", "2025-04-10"),
+            loan("B002", "线性代数", "2025-04-10"),
+        ];
+
+        let first = track_due_loans(&path, "2023001", &loans, &days, day("2025-04-09")).unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(
+            track_due_loans(&path, "2023001", &loans, &days, day("2025-04-09"))
+                .unwrap()
+                .is_empty()
+        );
+        // 其他账号互不影响
+        assert_eq!(
+            track_due_loans(&path, "2023002", &loans, &days, day("2025-04-09"))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // A001 续借到 5 月，B002 已归还：旧提醒键被清理，新应还日重新提醒
+        let renewed = vec![loan("A001", "算法导论", "2025-05-10")];
+        assert!(
+            track_due_loans(&path, "2023001", &renewed, &days, day("2025-04-09"))
+                .unwrap()
+                .is_empty()
+        );
+        let raw = db::get_kv(&path, &notified_key("2023001"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(raw, "[]");
+        let again = track_due_loans(&path, "2023001", &renewed, &days, day("2025-05-09")).unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].loan.barcode, "A001");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reminder_days_config_round_trips() {
+        let path = temp_db_path("config");
+        init_db(&path).unwrap();
+        assert_eq!(load_reminder_days(&path), DEFAULT_REMINDER_DAYS.to_vec());
+        assert_eq!(
+            save_reminder_days(&path, vec![0, 7, 7, 2]).unwrap(),
+            vec![7, 2, 0]
+        );
+        assert_eq!(load_reminder_days(&path), vec![7, 2, 0]);
+        assert!(save_reminder_days(&path, vec![31]).is_err());
+        assert!(save_reminder_days(&path, vec![1, 2, 3, 4, 5, 6]).is_err());
+        assert_eq!(
+            save_reminder_days(&path, Vec::new()).unwrap(),
+            Vec::<u32>::new()
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
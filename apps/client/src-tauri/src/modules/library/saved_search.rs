@@ -0,0 +1,553 @@
+//! 图书检索订阅：保存检索条件，后台定期重跑，发现新上架书目或想借的书重新在架时提醒。
+//!
+//! 订阅为全局设置（OPAC 检索不区分读者），存于 kv_store `library.saved_search.<id>`，随资料包导出。
+//! 每条订阅记录已见书目及其在架状态：首次检查只建立基线；之后未见过的书目记为新上架，
+//! 此前不在架、本次在架的记为重新在架。勾选「仅在架」（`onlyOnShelf`）的订阅中，
+//! 完整结果里消失的书目视为已借出，下次出现即提醒重新在架。
+
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{number, rows, text, total};
+use crate::db;
+use crate::http_client::HbutClient;
+
+type DynError = Box<dyn Error + Send + Sync>;
+
+/// 最多保存的订阅数（每条订阅每次检查都会请求一次 OPAC）。
+pub const MAX_SAVED_SEARCHES: usize = 20;
+/// 重跑时取第 1 页的条数（按出版时间倒序，新书排在前面）。
+pub const CHECK_ROWS: i64 = 100;
+/// 后台任务依次检查多条订阅时的请求间隔。
+pub const REQUEST_SPACING: Duration = Duration::from_secs(2);
+
+const SAVED_SEARCH_KV_PREFIX: &str = "library.saved_search.";
+const MAX_NAME_CHARS: usize = 64;
+/// 每条订阅保留的已见书目上限；超出时只保留本次命中的书目。
+const MAX_KNOWN_RECORDS: usize = 500;
+/// 通知正文最多列出的书名数。
+const MAX_LISTED_TITLES: usize = 3;
+
+/// 检索命中的一条书目（归一化自 OPAC `searchResult`）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub record_id: String,
+    pub title: String,
+    pub author: String,
+    pub publisher: String,
+    pub publish_year: String,
+    pub isbn: String,
+    pub call_no: String,
+    /// 在架册数；上游未返回时为 `None`
+    pub on_shelf: Option<u32>,
+    /// 馆藏册数
+    pub holdings: Option<u32>,
+}
+
+impl SearchHit {
+    pub fn from_row(row: &Value) -> Self {
+        let first = |key: &str| {
+            row.get(key)
+                .and_then(Value::as_array)
+                .and_then(|items| items.iter().find_map(Value::as_str))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let count = |keys: &[&str]| number(row, keys).map(|value| value.max(0.0) as u32);
+        let isbn = text(row, &["isbn"]);
+        let call_no = text(row, &["callNoOne"]);
+        Self {
+            record_id: text(row, &["recordId", "id"]),
+            title: text(row, &["title"]),
+            author: text(row, &["author"]),
+            publisher: text(row, &["publisher"]),
+            publish_year: text(row, &["publishYear"]),
+            isbn: if isbn.is_empty() {
+                first("isbns")
+            } else {
+                isbn
+            },
+            call_no: if call_no.is_empty() {
+                first("callNo")
+            } else {
+                call_no
+            },
+            on_shelf: count(&["onShelfCountI", "onShelfCount"]),
+            holdings: count(&["physicalCount"]),
+        }
+    }
+
+    /// 书目身份：`recordId` 缺失时退回书名 + ISBN。
+    fn identity(&self) -> String {
+        if self.record_id.is_empty() {
+            format!("{}|{}", self.title, self.isbn)
+        } else {
+            self.record_id.clone()
+        }
+    }
+
+    /// 在架册数未知时按在架处理，避免误报「重新在架」。
+    fn available(&self) -> bool {
+        self.on_shelf != Some(0)
+    }
+}
+
+/// 解析 OPAC 检索响应中的书目。
+pub fn parse_search_hits(raw: &Value) -> Vec<SearchHit> {
+    rows(raw)
+        .into_iter()
+        .map(SearchHit::from_row)
+        .filter(|hit| !hit.title.is_empty() || !hit.record_id.is_empty())
+        .collect()
+}
+
+/// 一次订阅检查的结果。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchCheck {
+    pub search_id: String,
+    pub name: String,
+    pub checked_at: String,
+    /// 首次检查只建立基线，不提醒
+    pub baseline: bool,
+    /// 上游命中总数
+    pub total: u64,
+    pub new_hits: Vec<SearchHit>,
+    pub back_on_shelf: Vec<SearchHit>,
+}
+
+impl SearchCheck {
+    pub fn has_alert(&self) -> bool {
+        !self.new_hits.is_empty() || !self.back_on_shelf.is_empty()
+    }
+
+    pub fn title(&self) -> String {
+        format!("图书检索「{}」有新结果", self.name)
+    }
+
+    pub fn body(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.new_hits.is_empty() {
+            parts.push(format!("新上架 {}", listed_titles(&self.new_hits)));
+        }
+        if !self.back_on_shelf.is_empty() {
+            parts.push(format!("重新在架 {}", listed_titles(&self.back_on_shelf)));
+        }
+        parts.join("；")
+    }
+}
+
+fn listed_titles(hits: &[SearchHit]) -> String {
+    let titles = hits
+        .iter()
+        .take(MAX_LISTED_TITLES)
+        .map(|hit| format!("《{}》", hit.title))
+        .collect::<Vec<_>>()
+        .join("、");
+    if hits.len() > MAX_LISTED_TITLES {
+        format!("{titles} 等 {} 本", hits.len())
+    } else {
+        titles
+    }
+}
+
+/// 保存的检索订阅（kv_store 持久化）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    /// 检索条件，与 `search_library_books` 的入参一致
+    pub params: Value,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// 已见书目 → 上次检查时是否在架
+    #[serde(default)]
+    pub known: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub last_check: Option<SearchCheck>,
+}
+
+impl SavedSearch {
+    /// 是否只检索在架书目（`onlyOnShelf`）。
+    pub fn only_on_shelf(&self) -> bool {
+        match self.params.get("onlyOnShelf") {
+            Some(Value::Bool(value)) => *value,
+            Some(Value::String(value)) => matches!(value.trim(), "true" | "1"),
+            Some(Value::Number(value)) => value.as_i64().is_some_and(|value| value != 0),
+            _ => false,
+        }
+    }
+}
+
+/// 新建 / 更新订阅请求（Tauri / Bridge 入参）；带 `id` 时更新已有订阅。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibrarySavedSearchRequest {
+    pub id: Option<String>,
+    pub name: String,
+    pub params: Value,
+    pub enabled: Option<bool>,
+}
+
+/// 按 id 删除 / 立即检查订阅（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibrarySavedSearchIdRequest {
+    pub id: String,
+}
+
+fn search_key(id: &str) -> String {
+    format!("{SAVED_SEARCH_KV_PREFIX}{id}")
+}
+
+fn load_search(path: &Path, id: &str) -> Result<SavedSearch, String> {
+    db::get_kv(path, &search_key(id.trim()))
+        .map_err(|e| e.to_string())?
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .ok_or_else(|| "检索订阅不存在或已删除".to_string())
+}
+
+fn store_search(path: &Path, search: &SavedSearch) -> Result<(), String> {
+    let raw = serde_json::to_string(search).map_err(|e| e.to_string())?;
+    db::set_kv(path, &search_key(&search.id), &raw).map_err(|e| e.to_string())
+}
+
+/// 全部检索订阅（按创建时间排序）。
+pub fn list_saved_searches<P: AsRef<Path>>(path: P) -> Result<Vec<SavedSearch>, String> {
+    let mut searches = db::list_kv_by_prefix(path, SAVED_SEARCH_KV_PREFIX)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|(_, raw)| serde_json::from_str::<SavedSearch>(&raw).ok())
+        .collect::<Vec<_>>();
+    searches.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    Ok(searches)
+}
+
+/// 分页、排序由检查统一指定，不参与订阅条件。
+fn normalize_params(params: &Value) -> Result<Value, String> {
+    let Some(object) = params.as_object() else {
+        return Err("检索条件必须是对象".to_string());
+    };
+    let params = object
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "page" | "rows" | "sortField" | "sortClause"))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Map<_, _>>();
+    if params.is_empty() {
+        return Err("检索条件不能为空".to_string());
+    }
+    Ok(Value::Object(params))
+}
+
+/// 新建或更新订阅；检索条件变化时清空已见书目，下次检查重新建立基线。
+pub fn save_saved_search<P: AsRef<Path>>(
+    path: P,
+    req: &LibrarySavedSearchRequest,
+) -> Result<SavedSearch, String> {
+    let path = path.as_ref();
+    let name = req
+        .name
+        .trim()
+        .chars()
+        .take(MAX_NAME_CHARS)
+        .collect::<String>();
+    if name.is_empty() {
+        return Err("订阅名称不能为空".to_string());
+    }
+    let params = normalize_params(&req.params)?;
+    let now = Local::now().to_rfc3339();
+
+    let existing = match req.id.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+        Some(id) => Some(load_search(path, id)?),
+        None => {
+            if list_saved_searches(path)?.len() >= MAX_SAVED_SEARCHES {
+                return Err(format!("最多保存 {MAX_SAVED_SEARCHES} 个检索订阅"));
+            }
+            None
+        }
+    };
+    let search = match existing {
+        Some(search) if search.params == params => SavedSearch {
+            name,
+            enabled: req.enabled.unwrap_or(search.enabled),
+            updated_at: now,
+            ..search
+        },
+        Some(search) => SavedSearch {
+            name,
+            params,
+            enabled: req.enabled.unwrap_or(search.enabled),
+            updated_at: now,
+            known: BTreeMap::new(),
+            last_check: None,
+            ..search
+        },
+        None => SavedSearch {
+            id: format!(
+                "s{}{:04}",
+                Utc::now().timestamp_millis(),
+                rand::thread_rng().gen_range(0..10000)
+            ),
+            name,
+            params,
+            enabled: req.enabled.unwrap_or(true),
+            created_at: now.clone(),
+            updated_at: now,
+            known: BTreeMap::new(),
+            last_check: None,
+        },
+    };
+    store_search(path, &search)?;
+    Ok(search)
+}
+
+/// 删除订阅；不存在时返回 false。
+pub fn delete_saved_search<P: AsRef<Path>>(path: P, id: &str) -> Result<bool, String> {
+    let path = path.as_ref();
+    let key = search_key(id.trim());
+    let existed = db::get_kv(path, &key).map_err(|e| e.to_string())?.is_some();
+    if existed {
+        db::delete_kv(path, &key).map_err(|e| e.to_string())?;
+    }
+    Ok(existed)
+}
+
+/// 检查用的检索入参：第 1 页、按出版时间倒序取 [`CHECK_ROWS`] 条。
+pub fn check_params(search: &SavedSearch) -> Value {
+    let mut params = search.params.as_object().cloned().unwrap_or_default();
+    params.insert("page".to_string(), Value::from(1));
+    params.insert("rows".to_string(), Value::from(CHECK_ROWS));
+    params.insert("sortField".to_string(), Value::from("issued_sort"));
+    params.insert("sortClause".to_string(), Value::from("desc"));
+    Value::Object(params)
+}
+
+/// 按订阅条件检索一次，返回命中书目与上游命中总数，交由 [`record_search_check`] 比对落库。
+pub async fn poll_saved_search(
+    client: &mut HbutClient,
+    search: &SavedSearch,
+) -> Result<(Vec<SearchHit>, u64), DynError> {
+    let raw = client.search_library_books(check_params(search)).await?;
+    let hits = parse_search_hits(&raw);
+    let total = total(&raw, hits.len());
+    Ok((hits, total))
+}
+
+/// 与已见书目比对并推进状态，返回本次检查结果。
+///
+/// 只有命中总数不超过本次取回条数（结果完整）时，仅在架订阅才把消失的书目标记为不在架，
+/// 避免新书挤出第 1 页的旧书被误判为借出。
+pub fn record_search_check<P: AsRef<Path>>(
+    path: P,
+    id: &str,
+    hits: &[SearchHit],
+    total: u64,
+    now: DateTime<Local>,
+) -> Result<SearchCheck, String> {
+    let path = path.as_ref();
+    let mut search = load_search(path, id)?;
+    let baseline = search.last_check.is_none();
+    let only_on_shelf = search.only_on_shelf();
+
+    let mut seen = HashSet::new();
+    let mut new_hits = Vec::new();
+    let mut back_on_shelf = Vec::new();
+    for hit in hits {
+        let identity = hit.identity();
+        if !seen.insert(identity.clone()) {
+            continue;
+        }
+        let available = hit.available();
+        match search.known.get(&identity) {
+            None => new_hits.push(hit.clone()),
+            Some(false) if available => back_on_shelf.push(hit.clone()),
+            _ => {}
+        }
+        search.known.insert(identity, available);
+    }
+    if only_on_shelf && total <= hits.len() as u64 {
+        for (identity, available) in search.known.iter_mut() {
+            if !seen.contains(identity) {
+                *available = false;
+            }
+        }
+    }
+    if search.known.len() > MAX_KNOWN_RECORDS {
+        search.known.retain(|identity, _| seen.contains(identity));
+    }
+    if baseline {
+        new_hits.clear();
+        back_on_shelf.clear();
+    }
+
+    let check = SearchCheck {
+        search_id: search.id.clone(),
+        name: search.name.clone(),
+        checked_at: now.to_rfc3339(),
+        baseline,
+        total,
+        new_hits,
+        back_on_shelf,
+    };
+    search.last_check = Some(check.clone());
+    store_search(path, &search)?;
+    Ok(check)
+}
+
+/// 立即检查一条订阅（前台「立即检查」）；与后台任务共用已见书目，已展示的结果不会再次提醒。
+pub async fn run_saved_search<P: AsRef<Path>>(
+    client: &mut HbutClient,
+    path: P,
+    id: &str,
+) -> Result<SearchCheck, String> {
+    let path = path.as_ref();
+    let search = load_search(path, id)?;
+    let (hits, total) = poll_saved_search(client, &search)
+        .await
+        .map_err(|e| e.to_string())?;
+    record_search_check(path, &search.id, &hits, total, Local::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_library_saved_search_{label}_{nanos}.db"))
+    }
+
+    fn hit(record_id: &str, title: &str, on_shelf: u32) -> SearchHit {
+        SearchHit {
+            record_id: record_id.to_string(),
+            title: title.to_string(),
+            on_shelf: Some(on_shelf),
+            ..SearchHit::default()
+        }
+    }
+
+    fn save(path: &Path, params: Value) -> SavedSearch {
+        save_saved_search(
+            path,
+            &LibrarySavedSearchRequest {
+                name: "机器学习".to_string(),
+                params,
+                ..LibrarySavedSearchRequest::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_opac_search_results() {
+        let raw = json!({
+            "data": {
+                "numFound": 2,
+                "searchResult": [
+                    {
+                        "recordId": 1024,
+                        "title": "深度学习",
+                        "author": "Goodfellow",
+                        "isbns": ["978-7-115-46147-6"],
+                        "callNo": ["TP181/12"],
+                        "onShelfCountI": 2,
+                        "physicalCount": "3"
+                    },
+                    { "title": "统计学习方法", "isbn": "9787302517276", "callNoOne": "O212/7" }
+                ]
+            }
+        });
+        let hits = parse_search_hits(&raw);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].record_id, "1024");
+        assert_eq!(hits[0].isbn, "978-7-115-46147-6");
+        assert_eq!(hits[0].call_no, "TP181/12");
+        assert_eq!((hits[0].on_shelf, hits[0].holdings), (Some(2), Some(3)));
+        assert_eq!(hits[1].identity(), "统计学习方法|9787302517276");
+        assert_eq!(hits[1].call_no, "O212/7");
+        assert!(hits[1].available());
+        assert_eq!(total(&raw, hits.len()), 2);
+    }
+
+    #[test]
+    fn first_check_is_baseline_then_new_records_alert_once() {
+        let path = temp_db_path("new");
+        init_db(&path).unwrap();
+        let search = save(
+            &path,
+            json!({ "searchFieldContent": "机器学习", "page": 3 }),
+        );
+        assert_eq!(search.params, json!({ "searchFieldContent": "机器学习" }));
+        assert_eq!(check_params(&search)["sortClause"], "desc");
+
+        let first = vec![hit("1", "深度学习", 1), hit("2", "统计学习方法", 0)];
+        let check = record_search_check(&path, &search.id, &first, 2, Local::now()).unwrap();
+        assert!(check.baseline && !check.has_alert());
+
+        let second = vec![
+            hit("3", "机器学习实战", 2),
+            hit("1", "深度学习", 1),
+            hit("2", "统计学习方法", 1),
+        ];
+        let check = record_search_check(&path, &search.id, &second, 3, Local::now()).unwrap();
+        assert!(!check.baseline);
+        assert_eq!(check.new_hits, vec![hit("3", "机器学习实战", 2)]);
+        assert_eq!(check.back_on_shelf, vec![hit("2", "统计学习方法", 1)]);
+        assert_eq!(
+            check.body(),
+            "新上架 《机器学习实战》；重新在架 《统计学习方法》"
+        );
+
+        let check = record_search_check(&path, &search.id, &second, 3, Local::now()).unwrap();
+        assert!(!check.has_alert());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn on_shelf_searches_alert_when_missing_records_return() {
+        let path = temp_db_path("shelf");
+        init_db(&path).unwrap();
+        let search = save(
+            &path,
+            json!({ "searchFieldContent": "算法导论", "onlyOnShelf": true }),
+        );
+        assert!(search.only_on_shelf());
+
+        let wanted = vec![hit("7", "算法导论", 1)];
+        record_search_check(&path, &search.id, &wanted, 1, Local::now()).unwrap();
+        let check = record_search_check(&path, &search.id, &[], 0, Local::now()).unwrap();
+        assert!(!check.has_alert());
+        let check = record_search_check(&path, &search.id, &wanted, 1, Local::now()).unwrap();
+        assert_eq!(check.back_on_shelf, wanted);
+        assert!(check.new_hits.is_empty());
+
+        // 修改检索条件后重新建立基线
+        let updated = save_saved_search(
+            &path,
+            &LibrarySavedSearchRequest {
+                id: Some(search.id.clone()),
+                name: "算法".to_string(),
+                params: json!({ "searchFieldContent": "算法" }),
+                enabled: Some(false),
+            },
+        )
+        .unwrap();
+        assert!(updated.known.is_empty() && updated.last_check.is_none() && !updated.enabled);
+        assert_eq!(list_saved_searches(&path).unwrap().len(), 1);
+        assert!(delete_saved_search(&path, &search.id).unwrap());
+        assert!(!delete_saved_search(&path, &search.id).unwrap());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -13,14 +13,20 @@ pub mod chaoxing_class;
 pub mod chaoxing_sso;
 pub mod classroom;
 pub mod course_selection;
+pub mod degree_audit;
 pub mod electricity;
 pub mod exam;
+pub mod ics_calendar;
+pub mod library;
 pub mod module_bundle;
 pub mod notification;
 pub mod one_code;
 pub mod online_learning;
+pub mod plan_simulator;
 pub mod ranking;
 pub mod schedule;
+pub mod schedule_analysis;
+pub mod scheduler;
 pub mod school_inbox;
 pub mod school_website_embed;
 pub mod session_guard;
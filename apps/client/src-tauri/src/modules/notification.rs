@@ -1,136 +1,466 @@
 //! 通知模块（后台任务）。
 //!
-//! 负责后台定时任务与通知推送逻辑的初始化。
+//! 成绩变更、考试提醒、电费余额监控、选课余量监控、图书到期提醒与图书检索订阅六个后台任务的执行体；调度（间隔、静默时段、退避）由
+//! [`crate::modules::scheduler`] 负责，这里只做抓取、比对与通知推送。
+//!
+//! 成绩、考试与图书到期逐个已登记账号检查，多账号时通知标题前标注账号姓名；电费房间为全局关注列表，
+//! 只用活动账号查询；选课关注列表按账号保存，只检查活动账号；
+//! 图书检索订阅为全局列表，用活动账号的 OPAC 会话检索。
+//!
+//! 成绩与考试的变化在推送通知的同时发布到 [`crate::runtime_events`]（Bridge SSE 订阅）。
 
-use crate::AppState;
+use crate::application::{AcademicReadService, ApplicationContext};
+use crate::grade::service::{GradeService, SqliteGradeCache};
+use crate::http_client::HbutClient;
+use crate::modules::scheduler::{
+    account_handles, merge_account_runs, read_client_snapshot, write_back_electricity_session,
+    JobFailure, JobRun,
+};
+use crate::modules::{course_selection, electricity, exam, library};
+use crate::runtime_events::{self, ChangeNotice, RuntimeEvent};
+use crate::{AppState, DB_FILENAME};
 use chrono::Local;
+use serde_json::Value;
+use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_notification::NotificationExt;
-use tokio::time::{sleep, Duration};
+use tokio::sync::RwLock;
 
-// Default check interval: 30 minutes
-const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
-
-/// 初始化后台通知任务
-pub fn init_background_task(app: AppHandle) {
-    tauri::async_runtime::spawn(async move {
-        println!("[Background] Service started");
+/// 多账号时在通知标题前标注账号，单账号保持原标题。
+fn account_title(account: Option<&str>, title: String) -> String {
+    match account {
+        Some(account) => format!("[{account}] {title}"),
+        None => title,
+    }
+}
 
-        loop {
-            // Sleep first to avoid immediate check on startup (let app load)
-            sleep(Duration::from_secs(10)).await;
+/// 读取账号的学号与通知标注名（姓名为空时用学号）；未登录时返回 `None`。
+async fn account_identity(
+    handle: &Arc<RwLock<HbutClient>>,
+    labelled: bool,
+) -> Option<(String, Option<String>)> {
+    let client = handle.read().await;
+    let user = client.user_info.as_ref()?;
+    let label = labelled.then(|| {
+        let name = user.student_name.trim();
+        if name.is_empty() {
+            user.student_id.clone()
+        } else {
+            name.to_string()
+        }
+    });
+    Some((user.student_id.clone(), label))
+}
 
-            // Check settings (could be stored in DB or config)
-            // For now, we assume enabled
+/// 成绩变更检查：逐个账号、逐门课程推送新增/变更通知。
+pub(crate) async fn check_grades(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let handles = account_handles(app).await;
+    let labelled = handles.len() > 1;
+    let mut runs = Vec::new();
+    for handle in handles {
+        runs.push(check_account_grades(app, handle, labelled).await);
+    }
+    merge_account_runs(runs)
+}
 
-            if let Err(e) = check_grades(&app).await {
-                eprintln!("[Background] Grade check failed: {}", e);
-            }
+async fn check_account_grades(
+    app: &AppHandle,
+    client_handle: Arc<RwLock<HbutClient>>,
+    labelled: bool,
+) -> Result<JobRun, JobFailure> {
+    // 只在取 uid 时短暂持有读锁；抓取由 GradeService 经 GradeSource 读锁完成，
+    // 不会把用户的前台请求挡在写锁后面。
+    let Some((uid, account)) = account_identity(&client_handle, labelled).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
 
-            if let Err(e) = check_exams(&app).await {
-                eprintln!("[Background] Exam check failed: {}", e);
-            }
+    // 与 Tauri / Bridge 的 sync_grades 共用 GradeService：grades_cache 由服务统一写入，
+    // 变更比对基线（grade_snapshot_cache）同样经 GradeCacheStore 持久化。
+    let service = GradeService::new(client_handle, SqliteGradeCache);
+    let result = service
+        .check_grade_changes(&uid)
+        .await
+        .map_err(JobFailure::Failed)?;
+    if result.offline {
+        // 离线回退说明上游不可达，交给调度器按网络失败退避
+        return Err(JobFailure::Network("成绩抓取失败，已回退缓存".to_string()));
+    }
+    if result.baseline {
+        return Ok(JobRun::Completed);
+    }
 
-            sleep(CHECK_INTERVAL).await;
+    if !result.changes.is_empty() {
+        runtime_events::publish(RuntimeEvent::GradesChanged {
+            account: uid.clone(),
+            changes: result
+                .changes
+                .iter()
+                .map(|change| ChangeNotice {
+                    title: change.title(),
+                    body: change.body(),
+                })
+                .collect(),
+        });
+    }
+    // 只把已送达的变化记入快照：通知失败的变化下一轮重新比对并补发
+    let mut delivered = Vec::new();
+    let mut failure = None;
+    for change in &result.changes {
+        match app
+            .notification()
+            .builder()
+            .title(account_title(account.as_deref(), change.title()))
+            .body(change.body())
+            .show()
+        {
+            Ok(()) => delivered.push(change.key.clone()),
+            Err(e) => failure = Some(e.to_string()),
         }
-    });
-}
-
-async fn check_grades(app: &AppHandle) -> Result<(), String> {
-    let state = app.state::<AppState>();
-    // We need to lock the client but avoid holding it too long
-    // However, HbutClient inside Mutex is not easily cloneable if meaningful state updates are needed
-    // Ideally we use a new client or clone necessary cookies?
-    // Actually, we refer to the shared client.
+    }
+    service
+        .commit_grade_changes(&uid, &result, &delivered)
+        .map_err(JobFailure::Failed)?;
 
-    // NOTE: This might block other requests if fetching takes long, using async mutex
-    let client = state.client.write().await;
+    match failure {
+        Some(error) => Err(JobFailure::Failed(error)),
+        None => Ok(JobRun::Completed),
+    }
+}
 
-    // Only skip if not logged in
-    if client.user_info.is_none() {
-        return Ok(());
+/// 考试跟踪：逐个账号经 `AcademicReadService::fetch_exams` 拉取（与前台共用 exams_cache），
+/// 与考试快照比对后推送新增 / 变更通知，并按配置的提前量推送去重后的考前提醒。
+pub(crate) async fn check_exams(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let handles = account_handles(app).await;
+    let labelled = handles.len() > 1;
+    let mut runs = Vec::new();
+    for handle in handles {
+        runs.push(check_account_exams(app, handle, labelled).await);
     }
+    merge_account_runs(runs)
+}
 
-    // Capture previous count/hash
-    // For simplicity, we fetch grades. The client *refreshes* session if needed.
-    // Logic: fetch_grades() returns Vec<Grade>. Compare with DB (if accessible) or memory.
-    // Improving: We can store last_grade_count in AppState or just trust fetch_grades logic?
-    // Let's rely on a persisted "last_grade_count" in the DB.
+async fn check_account_exams(
+    app: &AppHandle,
+    handle: Arc<RwLock<HbutClient>>,
+    labelled: bool,
+) -> Result<JobRun, JobFailure> {
+    let Some((uid, account)) = account_identity(&handle, labelled).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
 
-    // We can't access DB easily from here without opening a new connection.
-    // Let's just fetch and see if we can detect changes.
-    // In a real robust app, we'd store a hash.
+    let service = AcademicReadService::new(ApplicationContext::new(handle, DB_FILENAME));
+    let payload = match service.fetch_exams(None).await {
+        Ok(payload) => payload,
+        Err(e) => return JobFailure::from_application(e),
+    };
+    let offline = payload
+        .get("offline")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let semester = exam::tracking_semester(DB_FILENAME, &uid);
+    let exams = payload
+        .get("data")
+        .cloned()
+        .and_then(|data| serde_json::from_value::<Vec<crate::Exam>>(data).ok())
+        .unwrap_or_default()
+        .iter()
+        .map(|record| exam::Exam::from_record(record, &semester))
+        .collect::<Vec<_>>();
 
-    // fetching...
-    let grades = client.fetch_grades().await.map_err(|e| e.to_string())?;
-    let student_id = &client.user_info.as_ref().unwrap().student_id;
+    // 离线快照仍可用于考前提醒，但不比对、不推进基线
+    let result = exam::track_exams(
+        DB_FILENAME,
+        &uid,
+        &semester,
+        &exams,
+        offline,
+        &exam::load_reminder_leads(DB_FILENAME),
+        Local::now().naive_local(),
+    )
+    .map_err(JobFailure::Failed)?;
 
-    // Retrieve previous count from DB
-    let cache = crate::db::get_cache(crate::DB_FILENAME, "grades_cache", student_id)
-        .map_err(|e| e.to_string())?;
+    if !result.changes.is_empty() {
+        runtime_events::publish(RuntimeEvent::ExamsChanged {
+            account: uid,
+            changes: result
+                .changes
+                .iter()
+                .map(|change| ChangeNotice {
+                    title: change.title(),
+                    body: change.body(),
+                })
+                .collect(),
+        });
+    }
+    let notices = result
+        .changes
+        .iter()
+        .map(|change| (change.title(), change.body()))
+        .chain(
+            result
+                .reminders
+                .iter()
+                .map(|reminder| (reminder.title(), reminder.body())),
+        );
+    for (title, body) in notices {
+        app.notification()
+            .builder()
+            .title(account_title(account.as_deref(), title))
+            .body(body)
+            .show()
+            .map_err(|e| JobFailure::Failed(e.to_string()))?;
+    }
 
-    let mut notify = false;
-    if let Some((json_val, _)) = cache {
-        if let Some(arr) = json_val.as_array() {
-            if arr.len() < grades.len() {
-                notify = true;
-            }
-        }
-    } else {
-        // First run or no cache, maybe don't notify? Or notify "Found X grades"?
-        // Let's only notify if we have previous data (detected change)
-        // OR we can just save it now.
+    if offline {
+        return Err(JobFailure::Network("考试抓取失败，已回退缓存".to_string()));
     }
+    Ok(JobRun::Completed)
+}
+
+/// 图书到期提醒：逐个账号经 `AcademicReadService::fetch_library_loans` 拉取当前借阅
+/// （与前台共用 library_account_cache），按提前天数推送去重后的到期 / 逾期提醒。
+pub(crate) async fn check_library_due(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let days = library::load_reminder_days(DB_FILENAME);
+    let handles = account_handles(app).await;
+    let labelled = handles.len() > 1;
+    let mut runs = Vec::new();
+    for handle in handles {
+        runs.push(check_account_library_due(app, handle, labelled, &days).await);
+    }
+    merge_account_runs(runs)
+}
 
-    if notify {
+async fn check_account_library_due(
+    app: &AppHandle,
+    handle: Arc<RwLock<HbutClient>>,
+    labelled: bool,
+    days: &[u32],
+) -> Result<JobRun, JobFailure> {
+    let Some((uid, account)) = account_identity(&handle, labelled).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+
+    let service = AcademicReadService::new(ApplicationContext::new(handle, DB_FILENAME));
+    let payload = match service.fetch_library_loans().await {
+        Ok(payload) => payload,
+        Err(e) => return JobFailure::from_application(e),
+    };
+    let offline = payload
+        .get("offline")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let loans = payload
+        .get("data")
+        .cloned()
+        .and_then(|data| serde_json::from_value::<Vec<library::LibraryLoan>>(data).ok())
+        .unwrap_or_default();
+
+    // 离线快照的应还日期仍然可信，照常提醒（续借过的书最迟在下次联网后重新计算）
+    let reminders =
+        library::track_due_loans(DB_FILENAME, &uid, &loans, days, Local::now().date_naive())
+            .map_err(JobFailure::Failed)?;
+    for reminder in &reminders {
         app.notification()
             .builder()
-            .title("成绩有更新")
-            .body("检测到新的成绩发布，请进入APP查看！")
+            .title(account_title(account.as_deref(), reminder.title()))
+            .body(reminder.body())
             .show()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| JobFailure::Failed(e.to_string()))?;
     }
 
-    // Save new cache
-    let json = serde_json::to_value(&grades).map_err(|e| e.to_string())?;
-    crate::db::save_cache(crate::DB_FILENAME, "grades_cache", student_id, &json).ok();
-
-    Ok(())
+    if offline {
+        return Err(JobFailure::Network("借阅抓取失败，已回退缓存".to_string()));
+    }
+    Ok(JobRun::Completed)
 }
 
-async fn check_exams(app: &AppHandle) -> Result<(), String> {
-    let state = app.state::<AppState>();
-    // 1. Get exams
-    // Note: This relies on the client being logged in
+/// 电费余额监控：逐个关注房间查询余额并追加读数，低于阈值或预计可用天数不足时推送提醒。
+///
+/// 查询在客户端快照上进行（一码通 token 可能在途中刷新，结束后回写共享客户端）；
+/// 单个房间查询失败不影响其他房间，全部失败时按网络失败退避。
+pub(crate) async fn check_electricity_balance(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let rooms = electricity::list_watched_rooms(DB_FILENAME).map_err(JobFailure::Failed)?;
+    if rooms.is_empty() {
+        return Ok(JobRun::Skipped("未关注房间"));
+    }
+    let Some(mut client) = read_client_snapshot(app).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+    if client.get_electricity_session().0.is_none() {
+        return Ok(JobRun::Skipped("未授权一码通"));
+    }
 
-    // Check if we already notified today?
-    // Ideally store "last_exam_check_date" in DB.
+    let mut failures = Vec::new();
+    for room in &rooms {
+        let response = match client
+            .query_electricity_account(room.location.clone())
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                failures.push(JobFailure::from_error(e.as_ref()));
+                continue;
+            }
+        };
+        let check =
+            match electricity::check_room(DB_FILENAME, room, &response, Local::now().timestamp()) {
+                Ok(check) => check,
+                Err(e) => {
+                    failures.push(JobFailure::Failed(e));
+                    continue;
+                }
+            };
+        for alert in &check.alerts {
+            app.notification()
+                .builder()
+                .title(alert.title())
+                .body(alert.body())
+                .show()
+                .map_err(|e| JobFailure::Failed(e.to_string()))?;
+        }
+    }
+    write_back_electricity_session(&app.state::<AppState>().client, &client)
+        .await
+        .map_err(JobFailure::Failed)?;
 
-    let client = state.client.write().await;
-    if client.user_info.is_none() {
-        return Ok(());
+    if failures.len() == rooms.len() {
+        if let Some(failure) = failures.into_iter().next() {
+            return Err(failure);
+        }
     }
+    Ok(JobRun::Completed)
+}
 
-    let exams = client.fetch_exams(None).await.map_err(|e| e.to_string())?;
+/// 选课余量监控：活动账号逐个关注批次查询余量，关注的教学班出现空位时推送提醒。
+///
+/// 批次结束后不再请求上游；批次之间与批次内的请求都按固定间隔错开。
+/// 单个批次失败不影响其他批次，全部失败时按网络失败退避。
+pub(crate) async fn check_course_selection_watch(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let Some(client) = read_client_snapshot(app).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+    let Some(student_id) = client.user_info.as_ref().map(|u| u.student_id.clone()) else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+    let rounds = course_selection::list_watch_rounds(DB_FILENAME, &student_id)
+        .map_err(JobFailure::Failed)?
+        .into_iter()
+        .filter(|round| !round.closed && !round.items.is_empty())
+        .collect::<Vec<_>>();
+    if rounds.is_empty() {
+        return Ok(JobRun::Skipped("未关注进行中的选课批次"));
+    }
 
-    let tomorrow = Local::now().date_naive().succ_opt().unwrap();
+    let mut failures = Vec::new();
+    for (index, round) in rounds.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(course_selection::REQUEST_SPACING).await;
+        }
+        let (remaining_seconds, observed) =
+            match course_selection::poll_watch_round(&client, round).await {
+                Ok(polled) => polled,
+                Err(e) => {
+                    failures.push(JobFailure::from_error(e.as_ref()));
+                    continue;
+                }
+            };
+        let check = match course_selection::record_round_check(
+            DB_FILENAME,
+            &student_id,
+            &round.pcid,
+            remaining_seconds,
+            &observed,
+            Local::now(),
+        ) {
+            Ok(check) => check,
+            Err(e) => {
+                failures.push(JobFailure::Failed(e));
+                continue;
+            }
+        };
+        // 余量已落库，通知失败不中断其余批次的轮询
+        for alert in &check.alerts {
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title(alert.title())
+                .body(alert.body())
+                .show()
+            {
+                crate::runtime_log::log_warn(
+                    "course_selection",
+                    format!("选课余量提醒推送失败: {e}"),
+                );
+            }
+        }
+    }
 
-    for exam in exams {
-        // Parse exam date: "2025-01-22"
-        if let Ok(date) = chrono::NaiveDate::parse_from_str(&exam.date, "%Y-%m-%d") {
-            if date == tomorrow {
-                app.notification()
-                    .builder()
-                    .title("考试提醒")
-                    .body(format!(
-                        "明天有一门考试：{}，请做好准备！",
-                        exam.course_name
-                    ))
-                    .show()
-                    .map_err(|e| e.to_string())?;
+    if failures.len() == rounds.len() {
+        if let Some(failure) = failures.into_iter().next() {
+            return Err(failure);
+        }
+    }
+    Ok(JobRun::Completed)
+}
+
+/// 图书检索订阅：按保存的检索条件逐条重跑，出现新上架书目或想借的书重新在架时推送提醒。
+///
+/// 检索直接在活动账号的共享客户端上进行（OPAC SSO 会话可能在途中建立，需要留在共享客户端上），
+/// 每条订阅只在检索期间持有写锁；订阅之间按固定间隔错开。
+/// 单条订阅失败不影响其他订阅，全部失败时按网络失败退避。
+pub(crate) async fn check_library_searches(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let searches = library::list_saved_searches(DB_FILENAME)
+        .map_err(JobFailure::Failed)?
+        .into_iter()
+        .filter(|search| search.enabled)
+        .collect::<Vec<_>>();
+    if searches.is_empty() {
+        return Ok(JobRun::Skipped("未保存启用的检索订阅"));
+    }
+    let handle = app.state::<AppState>().client.clone();
+    if handle.read().await.user_info.is_none() {
+        return Ok(JobRun::Skipped("未登录"));
+    }
+
+    let mut failures = Vec::new();
+    for (index, search) in searches.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(library::REQUEST_SPACING).await;
+        }
+        let polled = {
+            let mut client = handle.write().await;
+            library::poll_saved_search(&mut client, search).await
+        };
+        let (hits, total) = match polled {
+            Ok(polled) => polled,
+            Err(e) => {
+                failures.push(JobFailure::from_error(e.as_ref()));
+                continue;
             }
+        };
+        let check =
+            match library::record_search_check(DB_FILENAME, &search.id, &hits, total, Local::now())
+            {
+                Ok(check) => check,
+                Err(e) => {
+                    failures.push(JobFailure::Failed(e));
+                    continue;
+                }
+            };
+        if check.has_alert() {
+            app.notification()
+                .builder()
+                .title(check.title())
+                .body(check.body())
+                .show()
+                .map_err(|e| JobFailure::Failed(e.to_string()))?;
         }
     }
 
-    Ok(())
+    if failures.len() == searches.len() {
+        if let Some(failure) = failures.into_iter().next() {
+            return Err(failure);
+        }
+    }
+    Ok(JobRun::Completed)
 }
@@ -433,7 +433,7 @@ pub async fn chaoxing_get_knowledge_cards(
     // 预热学生页（部分课程要先打开 studentstudy 才出 mArg）
     let _ = client
         .client
-        .get(&study_referer)
+        .get(client.upstream_url(&study_referer))
         .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8")
         .header("Referer", "https://mooc1.chaoxing.com/visit/interaction")
         .header(
@@ -463,7 +463,7 @@ pub async fn chaoxing_get_knowledge_cards(
             let num_s = num.to_string();
             let resp = match client
                 .client
-                .get(base)
+                .get(client.upstream_url(base))
                 .query(&[
                     ("clazzid", clazz_id),
                     ("courseid", course_id),
@@ -692,7 +692,7 @@ pub async fn chaoxing_fetch_course_score(
     );
     let index_html = client
         .client
-        .get(&index_url)
+        .get(client.upstream_url(&index_url))
         .header("Referer", "https://mooc1.chaoxing.com/")
         .header(
             "User-Agent",
@@ -742,7 +742,7 @@ pub async fn chaoxing_fetch_course_score(
 
     let score_resp = client
         .client
-        .get(&score_url)
+        .get(client.upstream_url(&score_url))
         .header("Referer", &index_url)
         .timeout(Duration::from_secs(15))
         .send()
@@ -751,7 +751,7 @@ pub async fn chaoxing_fetch_course_score(
 
     let job_json = match client
         .client
-        .get(&job_url)
+        .get(client.upstream_url(&job_url))
         .header("Referer", &index_url)
         .timeout(Duration::from_secs(15))
         .send()
@@ -860,7 +860,9 @@ pub async fn chaoxing_get_video_status(
     propagate_chaoxing_key_cookies(client);
     let _ = client
         .client
-        .get("https://mooc1.chaoxing.com/ananas/modules/video/index.html?v=2026-0327-1642")
+        .get(client.upstream_url(
+            "https://mooc1.chaoxing.com/ananas/modules/video/index.html?v=2026-0327-1642",
+        ))
         .header("Referer", "https://mooc1.chaoxing.com/")
         .timeout(Duration::from_secs(8))
         .send()
@@ -871,7 +873,7 @@ pub async fn chaoxing_get_video_status(
     for url in candidates {
         let resp = match client
             .client
-            .get(&url)
+            .get(client.upstream_url(&url))
             .header(
                 "Referer",
                 "https://mooc1.chaoxing.com/ananas/modules/video/index.html?v=2026-0327-1642",
@@ -984,7 +986,7 @@ pub async fn chaoxing_report_progress(
     );
     let resp = client
         .client
-        .get(&url)
+        .get(client.upstream_url(&url))
         .header(
             "Referer",
             "https://mooc1.chaoxing.com/ananas/modules/video/index.html?v=2026-0327-1642",
@@ -228,7 +228,7 @@ async fn fetch_chaoxing_folder_courses(
     ] {
         if let Ok(resp) = client
             .client
-            .get(api)
+            .get(client.upstream_url(api))
             .header("Accept", "application/json, text/plain, */*")
             .header("Referer", "https://mooc1.chaoxing.com/visit/interaction")
             .timeout(Duration::from_secs(12))
@@ -274,7 +274,7 @@ async fn fetch_chaoxing_folder_courses(
 
     if let Ok(resp) = client
         .client
-        .get("https://mooc1.chaoxing.com/mooc-ans/visit/interaction")
+        .get(client.upstream_url("https://mooc1.chaoxing.com/mooc-ans/visit/interaction"))
         .header("Accept", "text/html,application/xhtml+xml")
         .header("Referer", "https://i.chaoxing.com/")
         .timeout(Duration::from_secs(15))
@@ -339,7 +339,7 @@ async fn fetch_chaoxing_folder_courses(
         let body = format!("courseType=1&courseFolderId={folder_id}&superstarClass=0");
         let resp = match client
             .client
-            .post("https://mooc1.chaoxing.com/mooc-ans/visit/courselistdata")
+            .post(client.upstream_url("https://mooc1.chaoxing.com/mooc-ans/visit/courselistdata"))
             .header("Content-Type", "application/x-www-form-urlencoded; charset=UTF-8")
             .header("Accept", "text/html, */*")
             .header("X-Requested-With", "XMLHttpRequest")
@@ -570,7 +570,7 @@ async fn fetch_fyportal_semester_options(client: &HbutClient) -> Vec<(String, St
     let url = "https://fycourse.fanya.chaoxing.com/fyportal/courselist/course?version=1&s=null";
     let resp = match client
         .client
-        .get(url)
+        .get(client.upstream_url(url))
         .header(
             "Accept",
             "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
@@ -613,7 +613,7 @@ async fn fetch_fyportal_courses_by_section(
     );
     let resp = match client
         .client
-        .get(&url)
+        .get(client.upstream_url(&url))
         .header("Accept", "text/html, */*")
         .header("X-Requested-With", "XMLHttpRequest")
         .header(
@@ -656,7 +656,7 @@ async fn fetch_chaoxing_courses_remote(client: &HbutClient) -> Result<Value, Dyn
     let url = "https://mooc1-api.chaoxing.com/mycourse/backclazzdata?view=json&rss=1";
     let resp = client
         .client
-        .get(url)
+        .get(client.upstream_url(url))
         .header("Accept", "application/json, text/plain, */*")
         .header("Referer", "https://i.chaoxing.com/base")
         .send()
@@ -43,7 +43,7 @@ async fn chaoxing_node_completion(
         );
         let resp = match client
             .client
-            .get(&url)
+            .get(client.upstream_url(&url))
             .header(
                 "Accept",
                 "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
@@ -290,7 +290,7 @@ async fn fetch_chaoxing_outline_remote(
 
     let resp = client
         .client
-        .get(&target)
+        .get(client.upstream_url(&target))
         .header(
             "Accept",
             "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
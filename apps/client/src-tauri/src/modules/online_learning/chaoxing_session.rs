@@ -244,11 +244,11 @@ fn resolve_student_password(client: &HbutClient, student_id: &str) -> Option<Str
 
 async fn ensure_portal_cas_session_ready(client: &mut HbutClient, student_id: &str) -> bool {
     let code_service = "https://code.hbut.edu.cn/server/auth/host/open?host=28&org=2";
-    let code_sso_url = format!(
+    let code_sso_url = client.upstream_url(&format!(
         "{}/login?service={}",
         crate::http_client::AUTH_BASE_URL,
         urlencoding::encode(code_service)
-    );
+    ));
     println!(
         "[调试] 学习通会话重建：检查融合门户 CAS 会话 {}",
         code_sso_url
@@ -350,7 +350,7 @@ pub(crate) async fn check_chaoxing_course_api_ready(client: &HbutClient) -> bool
     let url = "https://mooc1-api.chaoxing.com/mycourse/backclazzdata?view=json&rss=1";
     let resp = match client
         .client
-        .get(url)
+        .get(client.upstream_url(url))
         .header("Accept", "application/json, text/plain, */*")
         .header("Referer", "https://i.chaoxing.com/base")
         .send()
@@ -405,7 +405,7 @@ async fn try_chaoxing_password_login(
 ) -> Result<(), DynError> {
     let response = client
         .client
-        .get(CHAOXING_LOGIN_PAGE_URL)
+        .get(client.upstream_url(CHAOXING_LOGIN_PAGE_URL))
         .header(
             "Accept",
             "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
@@ -476,7 +476,7 @@ async fn try_chaoxing_password_login(
 
     let login_resp = client
         .client
-        .post(&format!("{}/fanyalogin", CHAOXING_BASE_URL))
+        .post(client.upstream_url(&format!("{}/fanyalogin", CHAOXING_BASE_URL)))
         .header("X-Requested-With", "XMLHttpRequest")
         .header("Origin", CHAOXING_BASE_URL)
         .header("Referer", login_page_url)
@@ -523,7 +523,7 @@ async fn try_chaoxing_password_login(
     if !redirect_url.trim().is_empty() {
         let _ = client
             .client
-            .get(&redirect_url)
+            .get(client.upstream_url(&redirect_url))
             .header("Referer", "https://i.chaoxing.com/")
             .send()
             .await;
@@ -7,6 +7,8 @@ use serde_json::{json, Value};
 
 use crate::db::{self, OnlineLearningPlatformStateRecord};
 use crate::http_client::HbutClient;
+#[cfg(feature = "mobile-full")]
+use crate::runtime_events::SyncSpan;
 
 use super::chaoxing_courses::chaoxing_fetch_courses;
 use super::chaoxing_session::{
@@ -141,24 +143,11 @@ pub async fn online_learning_sync_now(
     if platform.is_empty() || platform == "all" {
         let mut outputs = Vec::new();
         for key in [PLATFORM_CHAOXING, PLATFORM_YUKETANG] {
-            let result = match key {
-                PLATFORM_CHAOXING => chaoxing_fetch_courses(client, Some(&sid), force).await,
-                PLATFORM_YUKETANG => yuketang_fetch_courses(client, Some(&sid), force).await,
-                _ => unreachable!(),
-            };
-            match result {
+            match sync_platform(client, &sid, key, force).await {
                 Ok(payload) => {
-                    record_sync_run(&sid, key, "success", "同步完成", payload.clone());
                     outputs.push(json!({ "platform": key, "success": true, "payload": payload }));
                 }
                 Err(error) => {
-                    record_sync_run(
-                        &sid,
-                        key,
-                        "failed",
-                        &format!("同步失败: {}", error),
-                        json!({ "error": error.to_string() }),
-                    );
                     outputs.push(
                         json!({ "platform": key, "success": false, "error": error.to_string() }),
                     );
@@ -172,21 +161,35 @@ pub async fn online_learning_sync_now(
         }));
     }
 
-    let result = match platform.as_str() {
-        PLATFORM_CHAOXING => chaoxing_fetch_courses(client, Some(&sid), force).await,
-        PLATFORM_YUKETANG => yuketang_fetch_courses(client, Some(&sid), force).await,
+    sync_platform(client, &sid, &platform, force).await
+}
+
+/// 同步单个平台课程并记录同步流水；开始与结束各发布一条
+/// `online_learning.<platform>` 同步事件。
+#[cfg(feature = "mobile-full")]
+async fn sync_platform(
+    client: &mut HbutClient,
+    sid: &str,
+    platform: &str,
+    force: bool,
+) -> Result<Value, DynError> {
+    let span = SyncSpan::start(format!("online_learning.{platform}"), Some(sid));
+    let result = match platform {
+        PLATFORM_CHAOXING => chaoxing_fetch_courses(client, Some(sid), force).await,
+        PLATFORM_YUKETANG => yuketang_fetch_courses(client, Some(sid), force).await,
         _ => Err(err_box("不支持的在线学习平台")),
     };
+    span.finish(result.as_ref());
 
     match result {
         Ok(payload) => {
-            record_sync_run(&sid, &platform, "success", "同步完成", payload.clone());
+            record_sync_run(sid, platform, "success", "同步完成", payload.clone());
             Ok(payload)
         }
         Err(error) => {
             record_sync_run(
-                &sid,
-                &platform,
+                sid,
+                platform,
                 "failed",
                 &format!("同步失败: {}", error),
                 json!({ "error": error.to_string() }),
@@ -15,7 +15,7 @@ use super::yuketang_session::{restore_yuketang_cookie_blob, yuketang_cookie_blob
 async fn fetch_yuketang_courses_remote(client: &HbutClient) -> Result<Value, DynError> {
     let resp = client
         .client
-        .get("https://changjiang.yuketang.cn/v2/api/web/courses/list")
+        .get(client.upstream_url("https://changjiang.yuketang.cn/v2/api/web/courses/list"))
         .query(&[("identity", "2"), ("classroom_id", "0")])
         .header("Accept", "application/json, text/plain, */*")
         .header("xtbz", "ykt")
@@ -161,9 +161,11 @@ pub async fn yuketang_fetch_course_outline(
     }
 
     let sign = req.sign.as_deref().unwrap_or("").trim().to_string();
+    let chapter_url =
+        client.upstream_url("https://changjiang.yuketang.cn/mooc-api/v1/lms/learn/course/chapter");
     let resp = client
         .client
-        .get("https://changjiang.yuketang.cn/mooc-api/v1/lms/learn/course/chapter")
+        .get(chapter_url)
         .query(&[
             ("cid", classroom_id),
             ("sign", sign.as_str()),
@@ -263,10 +265,10 @@ pub async fn yuketang_fetch_course_progress(
     }
     let classroom_resp = client
         .client
-        .get(format!(
+        .get(client.upstream_url(&format!(
             "https://changjiang.yuketang.cn/v2/api/web/classrooms/{}",
             classroom_id
-        ))
+        )))
         .query(&[("role", "5")])
         .header("classroom-id", classroom_id)
         .send()
@@ -301,10 +303,10 @@ pub async fn yuketang_fetch_course_progress(
     } else {
         let detail_resp = client
             .client
-            .get(format!(
+            .get(client.upstream_url(&format!(
                 "https://changjiang.yuketang.cn/c27/online_courseware/schedule/score_detail/single/{}/0/",
                 sku_id
-            ))
+            )))
             .header("classroom-id", classroom_id)
             .send()
             .await?;
@@ -201,6 +201,7 @@ pub async fn yuketang_create_qr_login(
     }
 
     let reqwest_client = client.client.clone();
+    let web_login_url = client.upstream_url("https://changjiang.yuketang.cn/pc/web_login");
     let cookie_jar = client.cookie_jar.clone();
     let bg_session_id = session_id.clone();
     let bg_sid = sid.clone();
@@ -267,7 +268,7 @@ pub async fn yuketang_create_qr_login(
                         return;
                     }
                     let login_resp = reqwest_client
-                        .post("https://changjiang.yuketang.cn/pc/web_login")
+                        .post(&web_login_url)
                         .json(&json!({
                             "Auth": auth,
                             "UserID": user_id,
@@ -18,9 +18,11 @@ pub async fn yuketang_get_course_chapters(
     if !has_yuketang_session(client) {
         return Err(err_box("当前没有可用的雨课堂会话，请先扫码登录雨课堂"));
     }
+    let chapter_url =
+        client.upstream_url("https://changjiang.yuketang.cn/mooc-api/v1/lms/learn/course/chapter");
     let resp = client
         .client
-        .get("https://changjiang.yuketang.cn/mooc-api/v1/lms/learn/course/chapter")
+        .get(chapter_url)
         .query(&[
             ("cid", classroom_id),
             ("sign", sign),
@@ -115,10 +117,10 @@ pub async fn yuketang_get_leaf_info(
     }
     let resp = client
         .client
-        .get(&format!(
+        .get(client.upstream_url(&format!(
             "https://changjiang.yuketang.cn/mooc-api/v1/lms/learn/leaf_info/{}/{}/",
             classroom_id, leaf_id
-        ))
+        )))
         .query(&[("term", "latest")])
         .header("classroom-id", classroom_id)
         .header("xtbz", "ykt")
@@ -168,7 +170,7 @@ pub async fn yuketang_send_heartbeat(
     let body = json!({ "heart_data": events });
     let resp = client
         .client
-        .post("https://changjiang.yuketang.cn/video-log/heartbeat/")
+        .post(client.upstream_url("https://changjiang.yuketang.cn/video-log/heartbeat/"))
         .query(&[("classroom_id", classroom_id)])
         .header("classroom-id", classroom_id)
         .header("xtbz", "ykt")
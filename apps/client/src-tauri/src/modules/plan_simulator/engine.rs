@@ -0,0 +1,464 @@
+//! 排课草案模拟引擎（纯函数，无 IO）。
+//!
+//! 候选课程（选课列表 / 全校课表行或手填）规范为 [`DraftCourse`]，与现有课表（官方 +
+//! 自定义课程）合并为周课表网格；只报告涉及草案课程的时间冲突，并按学业审核类别汇总
+//! 计划学分与修读后的缺口。
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::CustomScheduleCourseRecord;
+use crate::modules::course_selection::parse_schedule_slots;
+use crate::modules::degree_audit::{AuditCategory, DegreeAudit, OutstandingCourse};
+use crate::modules::ics_calendar::{CalendarCourse, SkippedEntry};
+use crate::modules::schedule_analysis::{
+    conflicts_with, ScheduleConflict, ScheduleSlot, SlotSource,
+};
+
+/// 候选课程来源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateSource {
+    /// 选课列表（`fetch_course_selection_list`）
+    CourseSelection,
+    /// 全校课表（`fetch_qxzkb_list`）
+    PublicSchedule,
+    /// 手动填写
+    Manual,
+}
+
+/// 草案中的一门课（一个教学班）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftCourse {
+    pub source: CandidateSource,
+    /// 教学班 id；上游行缺失时取「课程名|上课时间」
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub class_name: String,
+    #[serde(default)]
+    pub teacher: String,
+    #[serde(default)]
+    pub room: String,
+    #[serde(default)]
+    pub credit: f64,
+    /// 课程性质（名称或数字代码）
+    #[serde(default)]
+    pub nature: String,
+    /// 上课时间地点原文（`sksjdd`）
+    #[serde(default)]
+    pub schedule: String,
+}
+
+fn field(row: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .filter_map(|key| match row.get(*key)? {
+            Value::String(v) => Some(v.trim().to_string()),
+            Value::Number(v) => Some(v.to_string()),
+            _ => None,
+        })
+        .find(|v| !v.is_empty())
+        .unwrap_or_default()
+}
+
+/// 课程名比较键（忽略空白）。
+pub(crate) fn name_key(name: &str) -> String {
+    name.split_whitespace().collect()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl DraftCourse {
+    /// 由上游行构造：兼容选课列表规范化后的字段（`id` / `teacher`）与全校课表原始字段
+    /// （`jxbid` / `skjs` / `skdd` / `xz`）。缺少课程名时返回 `None`。
+    pub fn from_row(source: CandidateSource, row: &Value) -> Option<Self> {
+        let name = field(row, &["kcmc", "name"]);
+        if name.is_empty() {
+            return None;
+        }
+        let schedule = field(row, &["sksjdd", "schedule"]);
+        let id = match field(row, &["id", "jxbid"]) {
+            id if id.is_empty() => format!("{name}|{schedule}"),
+            id => id,
+        };
+        let room = match field(row, &["skdd", "room"]) {
+            room if room.is_empty() => schedule
+                .split('【')
+                .nth(1)
+                .and_then(|rest| rest.split('】').next())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            room => room,
+        };
+        Some(Self {
+            source,
+            id,
+            class_name: field(row, &["jxbmc", "class_name"]),
+            teacher: field(row, &["teacher", "skjs"]),
+            room,
+            credit: field(row, &["xf", "xz", "credit"])
+                .parse::<f64>()
+                .unwrap_or(0.0),
+            nature: field(row, &["kcxz", "nature"]),
+            schedule,
+            name,
+        })
+    }
+
+    /// 计划学分归入的审核类别（按课程性质）。
+    pub fn category(&self) -> AuditCategory {
+        AuditCategory::classify(&self.nature, false)
+    }
+
+    /// 上课时间折算的占用；线上课程等无法识别时为空。
+    pub fn slots(&self) -> Vec<ScheduleSlot> {
+        parse_schedule_slots(&self.id, &self.name, &self.schedule)
+    }
+
+    /// 导出 / 网格用的日历课程（每个时间段一条）。
+    pub fn calendar_courses(&self) -> Vec<CalendarCourse> {
+        self.slots()
+            .into_iter()
+            .map(|slot| CalendarCourse {
+                key: format!("draft|{}|{}|{}", self.id, slot.weekday, slot.period),
+                name: self.name.clone(),
+                teacher: self.teacher.clone(),
+                room: self.room.clone(),
+                weekday: slot.weekday,
+                period: slot.period,
+                djs: slot.djs,
+                weeks: slot.weeks,
+            })
+            .collect()
+    }
+}
+
+/// 周课表网格中的一格：现有课程来源为 `official` / `custom`，草案课程为 `selection`。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridEntry {
+    pub source: SlotSource,
+    #[serde(flatten)]
+    pub course: CalendarCourse,
+}
+
+/// 模拟输入。
+pub struct SimulationInput<'a> {
+    pub official: &'a [crate::ScheduleCourse],
+    pub custom: &'a [CustomScheduleCourseRecord],
+    pub draft: &'a [DraftCourse],
+    /// 学业审核结果；缺失时类别只汇总计划学分
+    pub audit: Option<&'a DegreeAudit>,
+    pub max_credits_per_term: f64,
+}
+
+/// 学分合计。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditSummary {
+    /// 草案课程学分（同名课程只计一次，已在现有课表中的不计）
+    pub planned: f64,
+    /// 现有官方课表学分（同名课程只计一次；自定义课程无学分）
+    pub existing: f64,
+    pub total: f64,
+    pub max_credits_per_term: f64,
+    pub over_limit: bool,
+}
+
+/// 类别学分预测：假设在修与草案课程全部通过。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryForecast {
+    pub category: AuditCategory,
+    pub label: String,
+    pub required_credits: Option<f64>,
+    pub earned_credits: f64,
+    pub in_progress_credits: f64,
+    pub planned_credits: f64,
+    /// 学业审核给出的当前缺口
+    pub gap: Option<f64>,
+    pub gap_after_plan: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanSimulation {
+    pub grid: Vec<GridEntry>,
+    /// 草案课程与现有课程、草案课程之间的冲突（`left` 恒为草案课程）
+    pub conflicts: Vec<ScheduleConflict>,
+    /// 上课时间无法识别、未放入网格的草案课程
+    pub unplaced: Vec<SkippedEntry>,
+    /// 与现有课表同名的草案课程（已在课表中，不再放入网格、判冲突或计学分）
+    pub already_scheduled: Vec<String>,
+    pub credits: CreditSummary,
+    pub categories: Vec<CategoryForecast>,
+    /// 草案覆盖到的待修必修课
+    pub covered_required: Vec<OutstandingCourse>,
+}
+
+/// 合并现有课表与草案并汇总冲突、学分。
+pub fn simulate(input: &SimulationInput) -> PlanSimulation {
+    let existing_slots = input
+        .official
+        .iter()
+        .map(ScheduleSlot::from)
+        .chain(input.custom.iter().map(ScheduleSlot::from))
+        .collect::<Vec<_>>();
+    let existing_names = existing_slots
+        .iter()
+        .map(|slot| name_key(&slot.name))
+        .collect::<HashSet<_>>();
+
+    let mut grid = input
+        .official
+        .iter()
+        .map(|course| GridEntry {
+            source: SlotSource::Official,
+            course: CalendarCourse::from(course),
+        })
+        .chain(input.custom.iter().map(|course| GridEntry {
+            source: SlotSource::Custom,
+            course: CalendarCourse::from(course),
+        }))
+        .collect::<Vec<_>>();
+
+    let mut draft_slots = Vec::new();
+    let mut unplaced = Vec::new();
+    let mut already_scheduled = Vec::new();
+    let mut counted = HashSet::new();
+    let mut planned = [0.0; AuditCategory::ALL.len()];
+    for course in input.draft {
+        let key = name_key(&course.name);
+        if existing_names.contains(&key) {
+            already_scheduled.push(course.name.clone());
+            continue;
+        }
+        let calendar = course.calendar_courses();
+        if calendar.is_empty() {
+            unplaced.push(SkippedEntry {
+                name: course.name.clone(),
+                reason: "上课时间无法识别（线上课程或未排课），未放入课表".to_string(),
+            });
+        }
+        draft_slots.extend(course.slots());
+        grid.extend(calendar.into_iter().map(|course| GridEntry {
+            source: SlotSource::Selection,
+            course,
+        }));
+        if counted.insert(key) {
+            let index = AuditCategory::ALL
+                .iter()
+                .position(|category| *category == course.category())
+                .unwrap_or_default();
+            planned[index] += course.credit;
+        }
+    }
+    grid.sort_by(|a, b| {
+        (a.course.weekday, a.course.period, &a.course.name).cmp(&(
+            b.course.weekday,
+            b.course.period,
+            &b.course.name,
+        ))
+    });
+
+    let mut conflicts = Vec::new();
+    for (index, slot) in draft_slots.iter().enumerate() {
+        let others = existing_slots
+            .iter()
+            .chain(&draft_slots[index + 1..])
+            .filter(|other| other.name != slot.name)
+            .cloned()
+            .collect::<Vec<_>>();
+        conflicts.extend(conflicts_with(slot, &others));
+    }
+
+    let mut seen = HashSet::new();
+    let existing = input
+        .official
+        .iter()
+        .filter(|course| seen.insert(name_key(&course.name)))
+        .filter_map(|course| course.credit.trim().parse::<f64>().ok())
+        .sum::<f64>();
+    let planned_total = planned.iter().sum::<f64>();
+    let total = planned_total + existing;
+
+    let categories = AuditCategory::ALL
+        .iter()
+        .zip(planned)
+        .map(|(category, planned_credits)| {
+            let progress = input.audit.and_then(|audit| {
+                audit
+                    .categories
+                    .iter()
+                    .find(|progress| progress.category == *category)
+            });
+            let earned = progress.map_or(0.0, |p| p.earned_credits);
+            let in_progress = progress.map_or(0.0, |p| p.in_progress_credits);
+            let required = progress.and_then(|p| p.required_credits);
+            CategoryForecast {
+                category: *category,
+                label: category.label().to_string(),
+                required_credits: required,
+                earned_credits: earned,
+                in_progress_credits: in_progress,
+                planned_credits: round2(planned_credits),
+                gap: progress.and_then(|p| p.gap),
+                gap_after_plan: required.map(|required| {
+                    round2((required - earned - in_progress - planned_credits).max(0.0))
+                }),
+            }
+        })
+        .collect();
+
+    let draft_names = input
+        .draft
+        .iter()
+        .map(|course| name_key(&course.name))
+        .collect::<HashSet<_>>();
+    let covered_required = input
+        .audit
+        .map(|audit| {
+            audit
+                .outstanding_required
+                .iter()
+                .filter(|course| draft_names.contains(&name_key(&course.name)))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PlanSimulation {
+        grid,
+        conflicts,
+        unplaced,
+        already_scheduled,
+        credits: CreditSummary {
+            planned: round2(planned_total),
+            existing: round2(existing),
+            total: round2(total),
+            max_credits_per_term: input.max_credits_per_term,
+            over_limit: total > input.max_credits_per_term,
+        },
+        categories,
+        covered_required,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn official(name: &str, weekday: i32, period: i32, credit: &str) -> crate::ScheduleCourse {
+        crate::ScheduleCourse {
+            id: format!("o-{name}"),
+            name: name.to_string(),
+            teacher: "张老师".to_string(),
+            room: "6-101".to_string(),
+            room_code: String::new(),
+            building: String::new(),
+            weekday,
+            period,
+            djs: 2,
+            weeks: (1..=16).collect(),
+            weeks_text: "1-16".to_string(),
+            credit: credit.to_string(),
+            class_name: String::new(),
+        }
+    }
+
+    fn draft(name: &str, schedule: &str, credit: f64, nature: &str) -> DraftCourse {
+        DraftCourse::from_row(
+            CandidateSource::CourseSelection,
+            &json!({
+                "id": format!("jxb-{name}"),
+                "kcmc": name,
+                "xf": credit.to_string(),
+                "kcxz": nature,
+                "sksjdd": schedule,
+            }),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn normalizes_public_schedule_rows() {
+        let course = DraftCourse::from_row(
+            CandidateSource::PublicSchedule,
+            &json!({
+                "kcmc": " 编译原理 ",
+                "skjs": "李老师",
+                "xz": "3",
+                "kcxz": "44",
+                "jxbmc": "计科2201",
+                "sksjdd": "第1-16周 星期三 3-4节【2-305】",
+            }),
+        )
+        .unwrap();
+        assert_eq!(course.id, "编译原理|第1-16周 星期三 3-4节【2-305】");
+        assert_eq!(
+            (course.teacher.as_str(), course.room.as_str(), course.credit),
+            ("李老师", "2-305", 3.0)
+        );
+        assert_eq!(course.category(), AuditCategory::Required);
+        assert_eq!(course.slots().len(), 1);
+        assert!(DraftCourse::from_row(CandidateSource::Manual, &json!({"xf": "2"})).is_none());
+    }
+
+    #[test]
+    fn merges_schedule_and_reports_draft_conflicts_and_credits() {
+        let official = vec![
+            official("操作系统", 2, 1, "4"),
+            official("操作系统", 4, 1, "4"),
+        ];
+        let draft = vec![
+            draft("编译原理", "第1-16周 星期二 1-2节【2-305】", 3.0, "必修"),
+            draft("大学美育", "第1-8周 星期二 2-3节", 2.0, "通识选修"),
+            draft("操作系统", "第1-16周 星期二 1-2节", 4.0, "必修"),
+            draft("慕课", "线上", 1.0, "公共选修课"),
+        ];
+        let simulation = simulate(&SimulationInput {
+            official: &official,
+            custom: &[],
+            draft: &draft,
+            audit: None,
+            max_credits_per_term: 9.0,
+        });
+
+        // 草案中的「操作系统」已在课表中，只按现有课程参与冲突
+        let pairs = simulation
+            .conflicts
+            .iter()
+            .map(|c| (c.left.name.as_str(), c.right.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            vec![
+                ("编译原理", "操作系统"),
+                ("编译原理", "大学美育"),
+                ("大学美育", "操作系统"),
+            ]
+        );
+        assert_eq!(simulation.unplaced.len(), 1);
+        assert_eq!(simulation.already_scheduled, vec!["操作系统".to_string()]);
+        assert_eq!(simulation.grid.len(), 4);
+        assert_eq!(
+            (
+                simulation.credits.planned,
+                simulation.credits.existing,
+                simulation.credits.over_limit
+            ),
+            (6.0, 4.0, true)
+        );
+        let general = &simulation.categories[2];
+        assert_eq!(
+            (
+                general.category,
+                general.planned_credits,
+                general.gap_after_plan
+            ),
+            (AuditCategory::General, 3.0, None)
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! 排课模拟：选课开放前用候选教学班拼出学期课表草案。
+//!
+//! 候选课程来自选课列表或全校课表的行（也可手填），按账号存为命名草案于 kv_store
+//! `plan_simulator.<student_id>.<plan_id>`。模拟只读本地数据（`schedule_cache` 官方课表、
+//! 自定义课程与学业审核所需缓存），离线可用；纯计算见 [`engine`]。Tauri Command 与
+//! HTTP Bridge 共用。
+
+mod engine;
+
+pub use engine::{
+    simulate, CandidateSource, CategoryForecast, CreditSummary, DraftCourse, GridEntry,
+    PlanSimulation, SimulationInput,
+};
+
+use chrono::{Local, NaiveDate, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::db;
+use crate::modules::degree_audit::{
+    run_degree_audit, DegreeAuditRequest, DEFAULT_MAX_CREDITS_PER_TERM,
+};
+use crate::modules::ics_calendar::{
+    build_calendars, load_schedule_context, CalendarContext, CalendarCourse, IcsExportOptions,
+    ScheduleContext,
+};
+use crate::utils::ics::sanitize_filename_part;
+
+/// 每个账号保存的草案上限。
+pub const MAX_DRAFT_PLANS: usize = 10;
+/// 单个草案的课程上限。
+pub const MAX_DRAFT_COURSES: usize = 40;
+
+const PLAN_KV_PREFIX: &str = "plan_simulator.";
+const MAX_NAME_CHARS: usize = 64;
+
+/// 命名草案（kv_store 持久化）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftPlan {
+    pub id: String,
+    pub name: String,
+    pub semester: String,
+    pub courses: Vec<DraftCourse>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 草案中的候选课程：`row` 为选课列表 / 全校课表的原始行，或已保存的 [`DraftCourse`]。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanCourseInput {
+    /// 缺省为手动填写
+    pub source: Option<CandidateSource>,
+    pub row: Value,
+}
+
+/// 新建 / 覆盖草案（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanSaveRequest {
+    pub student_id: Option<String>,
+    /// 为空时新建
+    pub id: Option<String>,
+    pub name: String,
+    /// 缺省取课表缓存中的学期
+    pub semester: Option<String>,
+    #[serde(default)]
+    pub courses: Vec<PlanCourseInput>,
+}
+
+/// 按 id 操作草案（删除）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanRequest {
+    pub student_id: Option<String>,
+    pub id: String,
+}
+
+/// 模拟请求。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanSimulateRequest {
+    pub student_id: Option<String>,
+    pub id: String,
+    /// 学业进度方案（与 `fetch_academic_progress` 一致），缺省 1
+    pub fasz: Option<i32>,
+    /// 每学期学分上限，缺省 [`DEFAULT_MAX_CREDITS_PER_TERM`]
+    pub max_credits_per_term: Option<f64>,
+}
+
+/// 草案导出为 .ics 预览。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanIcsRequest {
+    pub student_id: Option<String>,
+    pub id: String,
+    /// 第 1 周周一（YYYY-MM-DD）；课表缓存属于草案学期时可省略
+    pub semester_start: Option<String>,
+    /// 同时导出现有官方课程与自定义课程
+    #[serde(default)]
+    pub include_existing: bool,
+    #[serde(default)]
+    pub options: IcsExportOptions,
+}
+
+/// 模拟结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanSimulationReport {
+    pub plan: DraftPlan,
+    #[serde(flatten)]
+    pub simulation: PlanSimulation,
+    /// 数据缺失时的说明（课表缓存学期不符、学业审核不可用等）
+    pub notes: Vec<String>,
+}
+
+fn require_student(student_id: &str) -> Result<&str, String> {
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    Ok(sid)
+}
+
+fn clip(value: &str) -> String {
+    value.trim().chars().take(MAX_NAME_CHARS).collect()
+}
+
+fn plan_prefix(student_id: &str) -> String {
+    format!("{PLAN_KV_PREFIX}{student_id}.")
+}
+
+fn plan_key(student_id: &str, plan_id: &str) -> String {
+    format!("{}{plan_id}", plan_prefix(student_id))
+}
+
+fn load_plan(path: &Path, student_id: &str, plan_id: &str) -> Result<DraftPlan, String> {
+    db::get_kv(path, &plan_key(student_id, plan_id.trim()))
+        .map_err(|e| e.to_string())?
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .ok_or_else(|| "草案不存在或已删除".to_string())
+}
+
+/// 课表缓存的学期与官方课程；缓存缺失时为 `None`。
+fn cached_schedule(path: &Path, sid: &str) -> Result<Option<(String, Value)>, String> {
+    Ok(db::get_cache(path, "schedule_cache", sid)
+        .map_err(|e| e.to_string())?
+        .map(|(payload, _)| {
+            let semester = payload
+                .pointer("/meta/semester")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            (
+                semester,
+                payload
+                    .get("data")
+                    .filter(|data| data.is_array())
+                    .cloned()
+                    .unwrap_or_else(|| Value::Array(Vec::new())),
+            )
+        }))
+}
+
+/// 账号下全部草案（按创建时间排序）。
+pub fn list_plans<P: AsRef<Path>>(path: P, student_id: &str) -> Result<Vec<DraftPlan>, String> {
+    let sid = require_student(student_id)?;
+    let mut plans = db::list_kv_by_prefix(path.as_ref(), &plan_prefix(sid))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|(_, raw)| serde_json::from_str::<DraftPlan>(&raw).ok())
+        .collect::<Vec<_>>();
+    plans.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    Ok(plans)
+}
+
+/// 新建或整体覆盖草案；同一教学班重复加入时只保留一条。
+pub fn save_plan<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &PlanSaveRequest,
+) -> Result<DraftPlan, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let name = clip(&req.name);
+    if name.is_empty() {
+        return Err("草案名称不能为空".to_string());
+    }
+    let mut seen = HashSet::new();
+    let courses = req
+        .courses
+        .iter()
+        .filter_map(|input| {
+            DraftCourse::from_row(input.source.unwrap_or(CandidateSource::Manual), &input.row)
+        })
+        .filter(|course| seen.insert(course.id.clone()))
+        .collect::<Vec<_>>();
+    if courses.len() > MAX_DRAFT_COURSES {
+        return Err(format!("单个草案最多 {MAX_DRAFT_COURSES} 门课程"));
+    }
+    let now = Local::now().to_rfc3339();
+
+    let existing = match req.id.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+        Some(id) => Some(load_plan(path, sid, id)?),
+        None => {
+            if list_plans(path, sid)?.len() >= MAX_DRAFT_PLANS {
+                return Err(format!("最多保存 {MAX_DRAFT_PLANS} 个草案"));
+            }
+            None
+        }
+    };
+    let semester = match req.semester.as_deref().map(str::trim) {
+        Some(semester) if !semester.is_empty() => semester.to_string(),
+        _ => existing
+            .as_ref()
+            .map(|plan| plan.semester.clone())
+            .or_else(|| {
+                cached_schedule(path, sid)
+                    .ok()
+                    .flatten()
+                    .map(|(semester, _)| semester)
+                    .filter(|semester| !semester.is_empty())
+            })
+            .ok_or_else(|| "请指定草案学期（课表缓存中没有学期信息）".to_string())?,
+    };
+    let plan = match existing {
+        Some(plan) => DraftPlan {
+            name,
+            semester,
+            courses,
+            updated_at: now,
+            ..plan
+        },
+        None => DraftPlan {
+            id: format!(
+                "p{}{:04}",
+                Utc::now().timestamp_millis(),
+                rand::thread_rng().gen_range(0..10000)
+            ),
+            name,
+            semester,
+            courses,
+            created_at: now.clone(),
+            updated_at: now,
+        },
+    };
+    let raw = serde_json::to_string(&plan).map_err(|e| e.to_string())?;
+    db::set_kv(path, &plan_key(sid, &plan.id), &raw).map_err(|e| e.to_string())?;
+    Ok(plan)
+}
+
+/// 删除草案；返回是否存在。
+pub fn delete_plan<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    plan_id: &str,
+) -> Result<bool, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let key = plan_key(sid, plan_id.trim());
+    let existed = db::get_kv(path, &key).map_err(|e| e.to_string())?.is_some();
+    if existed {
+        db::delete_kv(path, &key).map_err(|e| e.to_string())?;
+    }
+    Ok(existed)
+}
+
+/// 草案与本学期现有课表合并模拟，并对照学业审核汇总各类别学分。
+pub fn simulate_plan<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &PlanSimulateRequest,
+) -> Result<PlanSimulationReport, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let plan = load_plan(path, sid, &req.id)?;
+    let mut notes = Vec::new();
+
+    let official: Vec<crate::ScheduleCourse> = match cached_schedule(path, sid)? {
+        Some((semester, data)) if semester == plan.semester => {
+            serde_json::from_value(data).map_err(|e| format!("课表缓存解析失败: {e}"))?
+        }
+        Some((semester, _)) => {
+            notes.push(format!(
+                "本地课表缓存为 {semester} 学期，未合并官方课程；同步 {} 学期课表后可合并",
+                plan.semester
+            ));
+            Vec::new()
+        }
+        None => {
+            notes.push("暂无课表缓存，未合并官方课程".to_string());
+            Vec::new()
+        }
+    };
+    let custom =
+        db::list_custom_schedule_courses(path, sid, &plan.semester).map_err(|e| e.to_string())?;
+
+    let audit = match run_degree_audit(
+        path,
+        sid,
+        &DegreeAuditRequest {
+            fasz: req.fasz,
+            ..Default::default()
+        },
+    ) {
+        Ok(report) => Some(report.audit),
+        Err(e) => {
+            notes.push(format!("学业审核不可用（{e}），类别学分仅汇总草案课程"));
+            None
+        }
+    };
+
+    let simulation = simulate(&SimulationInput {
+        official: &official,
+        custom: &custom,
+        draft: &plan.courses,
+        audit: audit.as_ref(),
+        max_credits_per_term: req
+            .max_credits_per_term
+            .filter(|max| *max > 0.0)
+            .unwrap_or(DEFAULT_MAX_CREDITS_PER_TERM),
+    });
+    Ok(PlanSimulationReport {
+        plan,
+        simulation,
+        notes,
+    })
+}
+
+/// 草案导出为 .ics 预览（不写入任何本地数据）。
+///
+/// 开学日期优先取请求，其次取草案学期的课表缓存；`include_existing` 时合并现有官方课程
+/// （需课表缓存属于草案学期）与自定义课程。
+pub fn export_plan_ics<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &PlanIcsRequest,
+) -> Result<Value, String> {
+    let path = path.as_ref();
+    let sid = require_student(student_id)?;
+    let plan = load_plan(path, sid, &req.id)?;
+    let requested_start = req
+        .semester_start
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let (semester_start, official) =
+        match load_schedule_context(path, sid, Some(&plan.semester), requested_start) {
+            Ok(ScheduleContext {
+                semester_start,
+                official,
+                ..
+            }) => (semester_start, official),
+            Err(e) => match requested_start {
+                Some(start) => (
+                    NaiveDate::parse_from_str(start, "%Y-%m-%d")
+                        .map_err(|_| format!("学期开始日期格式错误: {start}"))?,
+                    Vec::new(),
+                ),
+                None => return Err(e),
+            },
+        };
+
+    let mut courses = plan
+        .courses
+        .iter()
+        .flat_map(DraftCourse::calendar_courses)
+        .collect::<Vec<_>>();
+    if req.include_existing {
+        let custom = db::list_custom_schedule_courses(path, sid, &plan.semester)
+            .map_err(|e| e.to_string())?;
+        courses.extend(official.iter().map(CalendarCourse::from));
+        courses.extend(custom.iter().map(CalendarCourse::from));
+    }
+
+    let mut options = req.options.clone();
+    options.include_exams = false;
+    options.split_exams = false;
+    if options
+        .calendar_name
+        .as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .is_none()
+    {
+        options.calendar_name = Some(format!("{}（草案）", plan.name));
+    }
+    let build = build_calendars(
+        &CalendarContext {
+            student_id: sid.to_string(),
+            semester: plan.semester.clone(),
+            semester_start,
+            generated_at: Utc::now(),
+        },
+        &courses,
+        &[],
+        &options,
+    );
+
+    let filename = format!(
+        "plan_{}_{}_{}.ics",
+        sanitize_filename_part(sid),
+        sanitize_filename_part(&plan.semester),
+        sanitize_filename_part(&plan.name)
+    );
+    let calendars = build
+        .calendars
+        .into_iter()
+        .map(|calendar| {
+            serde_json::json!({
+                "kind": calendar.kind,
+                "name": calendar.name,
+                "filename": filename,
+                "content_type": "text/calendar; charset=utf-8",
+                "event_count": calendar.event_count,
+                "content": calendar.content,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(serde_json::json!({
+        "success": true,
+        "plan_id": plan.id,
+        "calendars": calendars,
+        "semester": build.semester,
+        "skipped": build.skipped,
+    }))
+}
+
+/// 组装模拟返回体（两通道一致）。
+pub fn build_simulation_payload(report: PlanSimulationReport) -> Value {
+    serde_json::json!({
+        "success": true,
+        "offline": true,
+        "data": report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path =
+            std::env::temp_dir().join(format!("mini_hbut_plan_simulator_{label}_{nanos}.db"));
+        init_db(&path).unwrap();
+        path
+    }
+
+    fn save_request(id: Option<String>, rows: &[Value]) -> PlanSaveRequest {
+        PlanSaveRequest {
+            id,
+            name: "方案 A".to_string(),
+            semester: Some("2025-2026-1".to_string()),
+            courses: rows
+                .iter()
+                .map(|row| PlanCourseInput {
+                    source: Some(CandidateSource::CourseSelection),
+                    row: row.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn saves_simulates_exports_and_deletes_draft_plans() {
+        let path = temp_db_path("roundtrip");
+        let row = json!({
+            "id": "jxb1",
+            "kcmc": "编译原理",
+            "xf": "3",
+            "kcxz": "必修",
+            "sksjdd": "第1-16周 星期三 3-4节【2-305】",
+        });
+        let plan = save_request(None, &[row.clone(), row.clone()]);
+        let saved = save_plan(&path, "2024001", &plan).unwrap();
+        assert_eq!(saved.courses.len(), 1);
+
+        let renamed = save_plan(
+            &path,
+            "2024001",
+            &PlanSaveRequest {
+                name: "方案 B".to_string(),
+                ..save_request(Some(saved.id.clone()), &[row])
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            (renamed.id.as_str(), renamed.name.as_str()),
+            (saved.id.as_str(), "方案 B")
+        );
+        assert_eq!(list_plans(&path, "2024001").unwrap().len(), 1);
+
+        let report = simulate_plan(
+            &path,
+            "2024001",
+            &PlanSimulateRequest {
+                id: saved.id.clone(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(report.simulation.credits.planned, 3.0);
+        assert_eq!(report.simulation.grid.len(), 1);
+        // 无课表缓存、无学业审核缓存
+        assert_eq!(report.notes.len(), 2);
+
+        assert!(export_plan_ics(
+            &path,
+            "2024001",
+            &PlanIcsRequest {
+                id: saved.id.clone(),
+                ..Default::default()
+            },
+        )
+        .is_err());
+        let payload = export_plan_ics(
+            &path,
+            "2024001",
+            &PlanIcsRequest {
+                id: saved.id.clone(),
+                semester_start: Some("2025-09-01".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let calendar = &payload["calendars"][0];
+        assert_eq!(calendar["event_count"], 1);
+        assert!(calendar["content"]
+            .as_str()
+            .unwrap()
+            .contains("X-WR-CALNAME:方案 B（草案） 课表 2025-2026-1"));
+
+        assert!(delete_plan(&path, "2024001", &saved.id).unwrap());
+        assert!(!delete_plan(&path, "2024001", &saved.id).unwrap());
+        assert!(simulate_plan(
+            &path,
+            "2024001",
+            &PlanSimulateRequest {
+                id: saved.id,
+                ..Default::default()
+            },
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,422 @@
+//! 课表占用分析引擎（纯函数，无 IO）。
+//!
+//! 官方课程、自定义课程与考试统一折算为 [`ScheduleSlot`]（星期 + 节次区间 + 周次），
+//! 在此之上做两两冲突检测与按周空闲节次查询；多份课表取并集后再求空闲即为共同空闲时间。
+
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::ics_calendar::{parse_clock, period_time, CalendarCourse, CalendarExam};
+
+/// 每天节次数（与节次表 `CLASS_PERIODS` 一致）。
+pub const PERIODS_PER_DAY: i32 = 11;
+
+/// 周次上限（与自定义课程校验一致）。
+pub const MAX_WEEK: i32 = 30;
+
+/// 占用来源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotSource {
+    Official,
+    Custom,
+    Exam,
+    /// 他人导出的课表（共同空闲时间）
+    Peer,
+    /// 待选教学班（选课前冲突检查、排课草案）
+    Selection,
+}
+
+/// 一条占用：星期 + 节次区间，在 `weeks` 列出的周次生效。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleSlot {
+    pub source: SlotSource,
+    /// 来源内的身份（官方课程 id / 自定义课程 id / 考试课程名等）
+    pub id: String,
+    pub name: String,
+    /// 星期（1=周一）
+    pub weekday: i32,
+    /// 开始节次（1 起）
+    pub period: i32,
+    /// 持续节数
+    pub djs: i32,
+    pub weeks: Vec<i32>,
+}
+
+impl ScheduleSlot {
+    pub fn last_period(&self) -> i32 {
+        self.period + self.djs.max(1) - 1
+    }
+
+    /// 与另一条占用的重叠：节次交集与周次交集均非空时返回 `(节次区间, 周次)`。
+    pub fn overlap(&self, other: &ScheduleSlot) -> Option<((i32, i32), Vec<i32>)> {
+        if self.weekday != other.weekday {
+            return None;
+        }
+        let (from, to) = (
+            self.period.max(other.period),
+            self.last_period().min(other.last_period()),
+        );
+        if from > to {
+            return None;
+        }
+        let other_weeks = other.weeks.iter().copied().collect::<BTreeSet<_>>();
+        let weeks = self
+            .weeks
+            .iter()
+            .copied()
+            .filter(|week| other_weeks.contains(week))
+            .collect::<BTreeSet<_>>();
+        (!weeks.is_empty()).then(|| ((from, to), weeks.into_iter().collect()))
+    }
+}
+
+impl From<&crate::ScheduleCourse> for ScheduleSlot {
+    fn from(course: &crate::ScheduleCourse) -> Self {
+        Self {
+            source: SlotSource::Official,
+            id: course.id.clone(),
+            name: course.name.clone(),
+            weekday: course.weekday,
+            period: course.period,
+            djs: course.djs,
+            weeks: course.weeks.clone(),
+        }
+    }
+}
+
+impl From<&crate::db::CustomScheduleCourseRecord> for ScheduleSlot {
+    fn from(course: &crate::db::CustomScheduleCourseRecord) -> Self {
+        Self {
+            source: SlotSource::Custom,
+            id: course.id.clone(),
+            name: course.name.clone(),
+            weekday: course.weekday,
+            period: course.period,
+            djs: course.djs,
+            weeks: course.weeks.clone(),
+        }
+    }
+}
+
+impl ScheduleSlot {
+    /// 由日历课程（如解析他人导出的 .ics）构造。
+    pub fn from_calendar(course: &CalendarCourse, source: SlotSource) -> Self {
+        Self {
+            source,
+            id: course.key.clone(),
+            name: course.name.clone(),
+            weekday: course.weekday,
+            period: course.period,
+            djs: course.djs,
+            weeks: course.weeks.clone(),
+        }
+    }
+
+    /// 考试折算为单周占用：日期 → 周次/星期，起止时间覆盖到的节次均视为占用。
+    /// 日期不在学期内或没有具体时间时返回 `None`（调用方报告为未定位）。
+    pub fn from_exam(semester_start: NaiveDate, exam: &CalendarExam) -> Option<Self> {
+        let date = NaiveDate::parse_from_str(exam.date.trim(), "%Y-%m-%d").ok()?;
+        let days = (date - semester_start).num_days();
+        if days < 0 {
+            return None;
+        }
+        let week = i32::try_from(days / 7).ok()? + 1;
+        if week > MAX_WEEK {
+            return None;
+        }
+        let start = parse_clock(&exam.start_time)?;
+        let end = parse_clock(&exam.end_time)
+            .filter(|end| *end > start)
+            .unwrap_or(start + chrono::Duration::hours(2));
+        let (first, last) = periods_covering(start, end)?;
+        Some(Self {
+            source: SlotSource::Exam,
+            id: format!("{}|{}", exam.course_name.trim(), exam.date.trim()),
+            name: exam.course_name.trim().to_string(),
+            weekday: date.weekday().number_from_monday() as i32,
+            period: first,
+            djs: last - first + 1,
+            weeks: vec![week],
+        })
+    }
+}
+
+/// 与时间段 `[start, end)` 有交集的首末节次。
+fn periods_covering(start: NaiveTime, end: NaiveTime) -> Option<(i32, i32)> {
+    let covered = (1..=PERIODS_PER_DAY)
+        .filter(|period| {
+            period_time(*period)
+                .is_some_and(|(period_start, period_end)| start < period_end && period_start < end)
+        })
+        .collect::<Vec<_>>();
+    Some((*covered.first()?, *covered.last()?))
+}
+
+/// 两条占用的冲突。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleConflict {
+    pub left: ScheduleSlot,
+    pub right: ScheduleSlot,
+    pub weekday: i32,
+    /// 冲突节次区间（含端点）
+    pub periods: (i32, i32),
+    pub weeks: Vec<i32>,
+}
+
+/// 全量两两冲突检测。
+pub fn detect_conflicts(slots: &[ScheduleSlot]) -> Vec<ScheduleConflict> {
+    let mut conflicts = Vec::new();
+    for (index, left) in slots.iter().enumerate() {
+        for right in &slots[index + 1..] {
+            if let Some(conflict) = conflict_between(left, right) {
+                conflicts.push(conflict);
+            }
+        }
+    }
+    conflicts
+}
+
+/// 新增 / 修改单条占用时与其余占用的冲突（跳过同一来源同一身份的旧记录）。
+pub fn conflicts_with(candidate: &ScheduleSlot, others: &[ScheduleSlot]) -> Vec<ScheduleConflict> {
+    others
+        .iter()
+        .filter(|other| !(other.source == candidate.source && other.id == candidate.id))
+        .filter_map(|other| conflict_between(candidate, other))
+        .collect()
+}
+
+fn conflict_between(left: &ScheduleSlot, right: &ScheduleSlot) -> Option<ScheduleConflict> {
+    let (periods, weeks) = left.overlap(right)?;
+    Some(ScheduleConflict {
+        left: left.clone(),
+        right: right.clone(),
+        weekday: left.weekday,
+        periods,
+        weeks,
+    })
+}
+
+/// 空闲查询参数。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreeSlotQuery {
+    pub week_from: i32,
+    pub week_to: i32,
+    /// 参与查询的星期（空表示周一到周日）
+    pub weekdays: Vec<i32>,
+    /// 最少连续空闲节数（小于该长度的空档不返回）
+    pub min_periods: i32,
+}
+
+impl Default for FreeSlotQuery {
+    fn default() -> Self {
+        Self {
+            week_from: 1,
+            week_to: 1,
+            weekdays: Vec::new(),
+            min_periods: 1,
+        }
+    }
+}
+
+/// 一段连续空闲节次。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreeBlock {
+    pub week: i32,
+    pub weekday: i32,
+    pub start_period: i32,
+    pub end_period: i32,
+    /// HH:MM
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// 按周 / 星期列出连续空闲节次（输入为所有人的占用并集时即为共同空闲）。
+pub fn free_blocks(
+    slots: &[ScheduleSlot],
+    query: &FreeSlotQuery,
+) -> Result<Vec<FreeBlock>, String> {
+    if query.week_from < 1 || query.week_to > MAX_WEEK || query.week_from > query.week_to {
+        return Err(format!(
+            "周次范围不合法：{}-{}（允许 1-{MAX_WEEK}）",
+            query.week_from, query.week_to
+        ));
+    }
+    let weekdays = if query.weekdays.is_empty() {
+        (1..=7).collect::<Vec<_>>()
+    } else {
+        let mut days = query.weekdays.clone();
+        days.sort_unstable();
+        days.dedup();
+        if let Some(bad) = days.iter().find(|day| !(1..=7).contains(*day)) {
+            return Err(format!("星期 {bad} 不合法"));
+        }
+        days
+    };
+    let min_periods = query.min_periods.max(1);
+
+    let mut blocks = Vec::new();
+    for week in query.week_from..=query.week_to {
+        for &weekday in &weekdays {
+            let mut busy = [false; PERIODS_PER_DAY as usize];
+            for slot in slots
+                .iter()
+                .filter(|slot| slot.weekday == weekday && slot.weeks.contains(&week))
+            {
+                for period in slot.period.max(1)..=slot.last_period().min(PERIODS_PER_DAY) {
+                    busy[(period - 1) as usize] = true;
+                }
+            }
+            let mut period = 1;
+            while period <= PERIODS_PER_DAY {
+                if busy[(period - 1) as usize] {
+                    period += 1;
+                    continue;
+                }
+                let start = period;
+                while period <= PERIODS_PER_DAY && !busy[(period - 1) as usize] {
+                    period += 1;
+                }
+                let end = period - 1;
+                if end - start + 1 < min_periods {
+                    continue;
+                }
+                let (Some((start_time, _)), Some((_, end_time))) =
+                    (period_time(start), period_time(end))
+                else {
+                    continue;
+                };
+                blocks.push(FreeBlock {
+                    week,
+                    weekday,
+                    start_period: start,
+                    end_period: end,
+                    start_time: start_time.format("%H:%M").to_string(),
+                    end_time: end_time.format("%H:%M").to_string(),
+                });
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(
+        source: SlotSource,
+        id: &str,
+        weekday: i32,
+        period: i32,
+        djs: i32,
+        weeks: Vec<i32>,
+    ) -> ScheduleSlot {
+        ScheduleSlot {
+            source,
+            id: id.to_string(),
+            name: id.to_string(),
+            weekday,
+            period,
+            djs,
+            weeks,
+        }
+    }
+
+    #[test]
+    fn conflicts_need_same_weekday_overlapping_periods_and_weeks() {
+        let slots = [
+            slot(SlotSource::Official, "math", 1, 1, 2, vec![1, 2, 3]),
+            slot(SlotSource::Custom, "club", 1, 2, 2, vec![3, 4]),
+            slot(SlotSource::Custom, "gym", 1, 3, 2, vec![1, 2]),
+            slot(SlotSource::Custom, "tue", 2, 1, 2, vec![1, 2, 3]),
+        ];
+        let conflicts = detect_conflicts(&slots);
+        // math×club（第 2 节，第 3 周）与 club×gym（第 3 节，第 3 周无交集 → 无冲突）
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].left.id, "math");
+        assert_eq!(conflicts[0].right.id, "club");
+        assert_eq!(conflicts[0].periods, (2, 2));
+        assert_eq!(conflicts[0].weeks, vec![3]);
+    }
+
+    #[test]
+    fn updating_a_course_ignores_its_previous_version() {
+        let existing = [
+            slot(SlotSource::Custom, "club", 3, 9, 2, vec![1, 2]),
+            slot(SlotSource::Official, "english", 3, 10, 2, vec![2]),
+        ];
+        let moved = slot(SlotSource::Custom, "club", 3, 9, 3, vec![1, 2]);
+        let conflicts = conflicts_with(&moved, &existing);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].right.id, "english");
+        assert_eq!(conflicts[0].periods, (10, 11));
+    }
+
+    #[test]
+    fn exams_map_onto_week_weekday_and_covered_periods() {
+        let exam = CalendarExam {
+            course_name: "线性代数".to_string(),
+            date: "2024-09-18".to_string(),
+            start_time: "14:30".to_string(),
+            end_time: "16:30".to_string(),
+            location: String::new(),
+            seat_number: None,
+            exam_type: String::new(),
+        };
+        let start = NaiveDate::from_ymd_opt(2024, 9, 2).unwrap();
+        let slot = ScheduleSlot::from_exam(start, &exam).unwrap();
+        assert_eq!((slot.weekday, slot.weeks.clone()), (3, vec![3]));
+        // 14:30-16:30 覆盖第 5（14:00-14:45）到第 7（15:55-16:40）节
+        assert_eq!((slot.period, slot.djs), (5, 3));
+
+        let untimed = CalendarExam {
+            start_time: String::new(),
+            ..exam
+        };
+        assert!(ScheduleSlot::from_exam(start, &untimed).is_none());
+    }
+
+    #[test]
+    fn free_blocks_skip_busy_periods_and_honour_min_length() {
+        let slots = [
+            slot(SlotSource::Official, "math", 1, 1, 2, vec![1]),
+            slot(SlotSource::Peer, "peer", 1, 5, 4, vec![1, 2]),
+        ];
+        let query = FreeSlotQuery {
+            week_from: 1,
+            week_to: 2,
+            weekdays: vec![1],
+            min_periods: 2,
+        };
+        let blocks = free_blocks(&slots, &query).unwrap();
+        let spans = blocks
+            .iter()
+            .map(|b| (b.week, b.start_period, b.end_period))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(1, 3, 4), (1, 9, 11), (2, 1, 4), (2, 9, 11)]);
+        assert_eq!(blocks[0].start_time, "10:15");
+        assert_eq!(blocks[0].end_time, "11:50");
+
+        assert!(free_blocks(
+            &slots,
+            &FreeSlotQuery {
+                week_from: 3,
+                week_to: 2,
+                ..FreeSlotQuery::default()
+            }
+        )
+        .is_err());
+        assert!(free_blocks(
+            &slots,
+            &FreeSlotQuery {
+                weekdays: vec![8],
+                ..FreeSlotQuery::default()
+            }
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! 课表分析：冲突检测与空闲时间查询。
+//!
+//! 占用模型与纯计算见 [`engine`]；本模块只负责从本地数据装载占用
+//! （`schedule_cache` 官方课表、`custom_schedule_courses` 自定义课程、`exams_cache` 考试），
+//! 以及把他人导出的 .ics 课表折算到本学期周次，Tauri Command 与 HTTP Bridge 共用。
+
+mod engine;
+
+pub use engine::{
+    conflicts_with, detect_conflicts, free_blocks, FreeBlock, FreeSlotQuery, ScheduleConflict,
+    ScheduleSlot, SlotSource, MAX_WEEK, PERIODS_PER_DAY,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::db;
+use crate::modules::ics_calendar::{
+    load_cached_exams, load_schedule_context, map_events_to_courses, parse_ics_events,
+    ScheduleContext, SkippedEntry,
+};
+
+/// 一次查询最多合并的他人课表数量。
+const MAX_PEER_CALENDARS: usize = 8;
+
+/// 冲突检测请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConflictRequest {
+    pub student_id: Option<String>,
+    /// 缺省取课表缓存中的学期
+    pub semester: Option<String>,
+    /// 覆盖校历开学日期（YYYY-MM-DD），用于把考试日期折算为周次
+    pub semester_start: Option<String>,
+}
+
+/// 空闲时间查询请求（Tauri / Bridge 入参）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FreeSlotRequest {
+    pub student_id: Option<String>,
+    pub semester: Option<String>,
+    pub semester_start: Option<String>,
+    #[serde(flatten)]
+    pub query: FreeSlotQuery,
+    /// 同学导出的课表 .ics 内容；非空时返回与所有人共同的空闲时间
+    #[serde(default)]
+    pub peers: Vec<String>,
+}
+
+/// 本学期本地占用（官方 + 自定义 + 考试）。
+struct LocalSlots {
+    semester: String,
+    semester_start: chrono::NaiveDate,
+    slots: Vec<ScheduleSlot>,
+    unplaced: Vec<SkippedEntry>,
+}
+
+fn load_local_slots(
+    path: &Path,
+    sid: &str,
+    semester: Option<&str>,
+    semester_start: Option<&str>,
+) -> Result<LocalSlots, String> {
+    let ScheduleContext {
+        semester,
+        semester_start,
+        official,
+    } = load_schedule_context(path, sid, semester, semester_start)?;
+    let custom =
+        db::list_custom_schedule_courses(path, sid, &semester).map_err(|e| e.to_string())?;
+    let mut slots = official
+        .iter()
+        .map(ScheduleSlot::from)
+        .chain(custom.iter().map(ScheduleSlot::from))
+        .collect::<Vec<_>>();
+
+    let mut unplaced = Vec::new();
+    for exam in load_cached_exams(path, sid, &semester) {
+        match ScheduleSlot::from_exam(semester_start, &exam) {
+            Some(slot) => slots.push(slot),
+            None => unplaced.push(SkippedEntry {
+                name: exam.course_name.clone(),
+                reason: "考试缺少具体时间或不在本学期周次内，未参与分析".to_string(),
+            }),
+        }
+    }
+    Ok(LocalSlots {
+        semester,
+        semester_start,
+        slots,
+        unplaced,
+    })
+}
+
+/// 冲突检测结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictReport {
+    pub semester: String,
+    pub conflicts: Vec<ScheduleConflict>,
+    pub unplaced: Vec<SkippedEntry>,
+}
+
+/// 本学期官方课程、自定义课程与考试之间的全部冲突。
+pub fn analyze_conflicts<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &ScheduleConflictRequest,
+) -> Result<ConflictReport, String> {
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    let local = load_local_slots(
+        path.as_ref(),
+        sid,
+        req.semester.as_deref(),
+        req.semester_start.as_deref(),
+    )?;
+    Ok(ConflictReport {
+        semester: local.semester,
+        conflicts: detect_conflicts(&local.slots),
+        unplaced: local.unplaced,
+    })
+}
+
+/// 新增 / 修改自定义课程后与同学期其他占用的冲突（仅提示，不阻止保存）。
+///
+/// 课表缓存缺失或属于其他学期时只比对自定义课程。
+pub fn custom_course_conflicts<P: AsRef<Path>>(
+    path: P,
+    course: &db::CustomScheduleCourseRecord,
+) -> Vec<ScheduleConflict> {
+    let path = path.as_ref();
+    let others = match load_local_slots(path, &course.student_id, Some(&course.semester), None) {
+        Ok(local) => local.slots,
+        Err(_) => db::list_custom_schedule_courses(path, &course.student_id, &course.semester)
+            .unwrap_or_default()
+            .iter()
+            .map(ScheduleSlot::from)
+            .collect(),
+    };
+    conflicts_with(&ScheduleSlot::from(course), &others)
+}
+
+/// 待选教学班与本学期官方课程、自定义课程的冲突（选课前检查，不含考试）。
+///
+/// 与同名课程的重叠视为换班，不算冲突。
+pub fn candidate_conflicts<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    candidates: &[ScheduleSlot],
+) -> Result<Vec<ScheduleConflict>, String> {
+    let local = load_local_slots(path.as_ref(), student_id.trim(), None, None)?;
+    Ok(candidates
+        .iter()
+        .flat_map(|candidate| {
+            let others = local
+                .slots
+                .iter()
+                .filter(|slot| slot.source != SlotSource::Exam && slot.name != candidate.name)
+                .cloned()
+                .collect::<Vec<_>>();
+            conflicts_with(candidate, &others)
+        })
+        .collect())
+}
+
+/// 空闲时间查询结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct FreeSlotReport {
+    pub semester: String,
+    /// 参与合并的他人课表数量
+    pub peers: usize,
+    pub blocks: Vec<FreeBlock>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// 指定周次范围内的连续空闲节次；附带他人课表时取所有人的共同空闲。
+pub fn find_free_slots<P: AsRef<Path>>(
+    path: P,
+    student_id: &str,
+    req: &FreeSlotRequest,
+) -> Result<FreeSlotReport, String> {
+    let sid = student_id.trim();
+    if sid.is_empty() {
+        return Err("student_id 不能为空".to_string());
+    }
+    if req.peers.len() > MAX_PEER_CALENDARS {
+        return Err(format!("一次最多合并 {MAX_PEER_CALENDARS} 份课表"));
+    }
+    let LocalSlots {
+        semester,
+        semester_start,
+        mut slots,
+        unplaced: mut skipped,
+    } = load_local_slots(
+        path.as_ref(),
+        sid,
+        req.semester.as_deref(),
+        req.semester_start.as_deref(),
+    )?;
+
+    for (index, content) in req.peers.iter().enumerate() {
+        let label = format!("课表 {}", index + 1);
+        let (events, unparsed) = parse_ics_events(content);
+        if events.is_empty() {
+            return Err(format!("{label} 中没有可用的日历事件（VEVENT）"));
+        }
+        let (courses, unmapped) = map_events_to_courses(semester_start, &events);
+        skipped.extend(
+            unparsed
+                .into_iter()
+                .chain(unmapped)
+                .map(|entry| SkippedEntry {
+                    name: format!("{label}：{}", entry.name),
+                    reason: entry.reason,
+                }),
+        );
+        slots.extend(
+            courses
+                .iter()
+                .map(|course| ScheduleSlot::from_calendar(course, SlotSource::Peer)),
+        );
+    }
+
+    Ok(FreeSlotReport {
+        semester,
+        peers: req.peers.len(),
+        blocks: free_blocks(&slots, &req.query)?,
+        skipped,
+    })
+}
+
+/// 组装冲突检测返回体（两通道一致）。
+pub fn build_conflicts_payload(report: ConflictReport) -> Value {
+    serde_json::json!({
+        "success": true,
+        "semester": report.semester,
+        "count": report.conflicts.len(),
+        "conflicts": report.conflicts,
+        "unplaced": report.unplaced,
+    })
+}
+
+/// 组装空闲时间返回体（两通道一致）。
+pub fn build_free_slots_payload(report: FreeSlotReport) -> Value {
+    serde_json::json!({
+        "success": true,
+        "semester": report.semester,
+        "peers": report.peers,
+        "count": report.blocks.len(),
+        "blocks": report.blocks,
+        "skipped": report.skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_analysis_{label}_{nanos}.db"))
+    }
+
+    fn custom(id: &str, weekday: i32, period: i32, djs: i32) -> db::CustomScheduleCourseRecord {
+        db::CustomScheduleCourseRecord {
+            id: id.to_string(),
+            student_id: "20240001".to_string(),
+            semester: "2024-2025-1".to_string(),
+            name: id.to_string(),
+            teacher: String::new(),
+            room: String::new(),
+            weekday,
+            period,
+            djs,
+            weeks: vec![1, 2, 3],
+            color: "#3B82F6".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn custom_course_conflicts_fall_back_to_custom_courses_without_cache() {
+        let path = temp_db_path("custom");
+        init_db(&path).unwrap();
+        db::add_custom_schedule_course(&path, &custom("club", 2, 9, 2)).unwrap();
+
+        let overlapping = custom("band", 2, 10, 2);
+        let conflicts = custom_course_conflicts(&path, &overlapping);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].right.id, "club");
+        assert_eq!(conflicts[0].periods, (10, 10));
+
+        // 修改自身不算冲突
+        assert!(custom_course_conflicts(&path, &custom("club", 2, 9, 3)).is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn free_slot_request_accepts_flat_query_fields() {
+        let req: FreeSlotRequest = serde_json::from_value(serde_json::json!({
+            "student_id": "20240001",
+            "week_from": 3,
+            "week_to": 5,
+            "weekdays": [6, 7],
+        }))
+        .unwrap();
+        assert_eq!((req.query.week_from, req.query.week_to), (3, 5));
+        assert_eq!(req.query.weekdays, vec![6, 7]);
+        assert_eq!(req.query.min_periods, 1);
+        assert!(req.peers.is_empty());
+    }
+}
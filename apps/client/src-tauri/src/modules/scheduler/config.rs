@@ -0,0 +1,405 @@
+//! 后台任务配置：任务命名、间隔/静默时段的 kv_store 持久化，以及退避/抖动计算。
+//!
+//! 本文件只含纯逻辑与持久化，不依赖 Tauri 运行时，便于单测覆盖。
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::db;
+
+/// 间隔下限（分钟）：避免把教务系统刷成高频轮询。
+pub const MIN_INTERVAL_MINUTES: u32 = 5;
+/// 间隔上限（分钟）：一天。
+pub const MAX_INTERVAL_MINUTES: u32 = 24 * 60;
+/// 数据库定时备份的间隔范围（分钟）：每天到每周。
+pub const BACKUP_MIN_INTERVAL_MINUTES: u32 = 24 * 60;
+pub const BACKUP_MAX_INTERVAL_MINUTES: u32 = 7 * 24 * 60;
+/// 选课余量监控的间隔上限（分钟）：选课批次通常只开放数天，间隔过长没有意义。
+pub const COURSE_WATCH_MAX_INTERVAL_MINUTES: u32 = 2 * 60;
+/// 图书检索订阅的间隔下限（分钟）：新书上架按天计，每条订阅都会检索一次 OPAC。
+pub const LIBRARY_SEARCH_MIN_INTERVAL_MINUTES: u32 = 60;
+/// 网络失败后的首次重试延迟。
+pub const RETRY_BASE: Duration = Duration::from_secs(5 * 60);
+/// 网络失败退避的上限。
+pub const RETRY_MAX: Duration = Duration::from_secs(6 * 60 * 60);
+/// 抖动比例：实际延迟在 ±20% 范围内浮动，避免多端同时打到上游。
+pub const JITTER_RATIO: f64 = 0.2;
+
+const KV_PREFIX: &str = "scheduler.job.";
+
+/// 已注册的后台任务。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Grades,
+    Exams,
+    Electricity,
+    ElectricityBalance,
+    Inbox,
+    OnlineLearning,
+    DatabaseBackup,
+    CourseSelectionWatch,
+    LibraryDue,
+    LibrarySearches,
+}
+
+impl JobKind {
+    /// 全部任务（固定顺序，用于快照输出）。
+    pub const ALL: [JobKind; 10] = [
+        JobKind::Grades,
+        JobKind::Exams,
+        JobKind::Electricity,
+        JobKind::ElectricityBalance,
+        JobKind::Inbox,
+        JobKind::OnlineLearning,
+        JobKind::DatabaseBackup,
+        JobKind::CourseSelectionWatch,
+        JobKind::LibraryDue,
+        JobKind::LibrarySearches,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Grades => "grades",
+            JobKind::Exams => "exams",
+            JobKind::Electricity => "electricity",
+            JobKind::ElectricityBalance => "electricity_balance",
+            JobKind::Inbox => "inbox",
+            JobKind::OnlineLearning => "online_learning",
+            JobKind::DatabaseBackup => "database_backup",
+            JobKind::CourseSelectionWatch => "course_selection_watch",
+            JobKind::LibraryDue => "library_due",
+            JobKind::LibrarySearches => "library_searches",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL.into_iter().find(|job| job.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            JobKind::Grades => "成绩变更检查",
+            JobKind::Exams => "考试提醒",
+            JobKind::Electricity => "电费会话保活",
+            JobKind::ElectricityBalance => "电费余额监控",
+            JobKind::Inbox => "学校消息同步",
+            JobKind::OnlineLearning => "在线学习同步",
+            JobKind::DatabaseBackup => "数据库定时备份",
+            JobKind::CourseSelectionWatch => "选课余量监控",
+            JobKind::LibraryDue => "图书到期提醒",
+            JobKind::LibrarySearches => "图书检索订阅",
+        }
+    }
+
+    /// 未配置时的默认间隔（分钟）。
+    pub fn default_interval_minutes(self) -> u32 {
+        match self {
+            JobKind::Grades => 30,
+            // 2 小时考前提醒需要至少每小时检查一次
+            JobKind::Exams => 60,
+            JobKind::Electricity => 2 * 60,
+            JobKind::ElectricityBalance => 3 * 60,
+            JobKind::Inbox => 30,
+            JobKind::OnlineLearning => 3 * 60,
+            JobKind::DatabaseBackup => 24 * 60,
+            JobKind::CourseSelectionWatch => 10,
+            // 提前量按天计算，每天检查几次即可
+            JobKind::LibraryDue => 6 * 60,
+            JobKind::LibrarySearches => 12 * 60,
+        }
+    }
+
+    /// 允许配置的间隔范围（分钟）。
+    pub fn interval_bounds(self) -> (u32, u32) {
+        match self {
+            JobKind::DatabaseBackup => (BACKUP_MIN_INTERVAL_MINUTES, BACKUP_MAX_INTERVAL_MINUTES),
+            JobKind::CourseSelectionWatch => {
+                (MIN_INTERVAL_MINUTES, COURSE_WATCH_MAX_INTERVAL_MINUTES)
+            }
+            JobKind::LibrarySearches => (LIBRARY_SEARCH_MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES),
+            _ => (MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES),
+        }
+    }
+
+    fn kv_key(self) -> String {
+        format!("{KV_PREFIX}{}", self.as_str())
+    }
+}
+
+/// 静默时段（本地时间，`HH:MM`）；`start > end` 表示跨午夜，如 23:00–07:00。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn bounds(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let start = NaiveTime::parse_from_str(self.start.trim(), "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(self.end.trim(), "%H:%M").ok()?;
+        Some((start, end))
+    }
+
+    /// 给定时刻是否处于静默时段；start == end 视为未设置。
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let Some((start, end)) = self.bounds() else {
+            return false;
+        };
+        if start == end {
+            false
+        } else if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+
+    /// 若 `now` 处于静默时段，返回静默结束的时刻。
+    pub fn resume_at(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        if !self.contains(now.time()) {
+            return None;
+        }
+        let (_, end) = self.bounds()?;
+        let mut date = now.date_naive();
+        if now.time() >= end {
+            date = date.succ_opt()?;
+        }
+        Local
+            .from_local_datetime(&date.and_time(end))
+            .earliest()
+            .or_else(|| Some(now + ChronoDuration::hours(1)))
+    }
+}
+
+/// 单个任务的持久化配置（kv_store: `scheduler.job.<name>`）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl JobConfig {
+    /// 默认关闭：通知监控目前由前端统一承担，Rust 侧任务需用户显式开启，避免重复提醒。
+    pub fn default_for(job: JobKind) -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: job.default_interval_minutes(),
+            quiet_hours: None,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.interval_minutes) * 60)
+    }
+
+    /// 按任务的间隔范围校验并规范化（去空白、空静默时段视为未设置）。
+    pub fn validated(mut self, job: JobKind) -> Result<Self, String> {
+        let (min, max) = job.interval_bounds();
+        if !(min..=max).contains(&self.interval_minutes) {
+            return Err(format!("任务间隔需在 {} 到 {} 分钟之间", min, max));
+        }
+        if let Some(quiet) = self.quiet_hours.take() {
+            let quiet = QuietHours {
+                start: quiet.start.trim().to_string(),
+                end: quiet.end.trim().to_string(),
+            };
+            if !(quiet.start.is_empty() && quiet.end.is_empty()) {
+                if quiet.bounds().is_none() {
+                    return Err("静默时段格式应为 HH:MM".to_string());
+                }
+                self.quiet_hours = Some(quiet);
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// 读取任务配置；缺失或损坏时回退默认值。
+pub fn load_job_config<P: AsRef<Path>>(path: P, job: JobKind) -> JobConfig {
+    db::get_kv(path, &job.kv_key())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<JobConfig>(&raw).ok())
+        .and_then(|config| config.validated(job).ok())
+        .unwrap_or_else(|| JobConfig::default_for(job))
+}
+
+/// 校验后写入任务配置，返回规范化后的配置。
+pub fn save_job_config<P: AsRef<Path>>(
+    path: P,
+    job: JobKind,
+    config: JobConfig,
+) -> Result<JobConfig, String> {
+    let config = config.validated(job)?;
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    db::set_kv(path, &job.kv_key(), &raw).map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// 网络失败的退避延迟：5 分钟起步逐次翻倍，封顶 6 小时。
+pub fn backoff_delay(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    RETRY_BASE
+        .checked_mul(1u32 << exponent)
+        .map(|delay| delay.min(RETRY_MAX))
+        .unwrap_or(RETRY_MAX)
+}
+
+/// 按 `factor ∈ [-1, 1]` 施加 ±[`JITTER_RATIO`] 抖动。
+pub fn apply_jitter(delay: Duration, factor: f64) -> Duration {
+    let factor = factor.clamp(-1.0, 1.0);
+    delay.mul_f64(1.0 + factor * JITTER_RATIO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("mini_hbut_scheduler_{label}_{nanos}.db"))
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn job_kind_round_trips_through_name() {
+        for job in JobKind::ALL {
+            assert_eq!(JobKind::parse(job.as_str()), Some(job));
+        }
+        assert_eq!(
+            JobKind::parse(" online_learning "),
+            Some(JobKind::OnlineLearning)
+        );
+        assert_eq!(JobKind::parse("weather"), None);
+    }
+
+    #[test]
+    fn quiet_hours_handle_same_day_and_overnight_windows() {
+        let overnight = QuietHours {
+            start: "23:00".to_string(),
+            end: "07:00".to_string(),
+        };
+        assert!(overnight.contains(at(23, 30)));
+        assert!(overnight.contains(at(3, 0)));
+        assert!(!overnight.contains(at(7, 0)));
+        assert!(!overnight.contains(at(12, 0)));
+
+        let noon = QuietHours {
+            start: "12:00".to_string(),
+            end: "14:00".to_string(),
+        };
+        assert!(noon.contains(at(13, 59)));
+        assert!(!noon.contains(at(14, 0)));
+        assert!(!noon.contains(at(11, 59)));
+    }
+
+    #[test]
+    fn quiet_hours_resume_at_rolls_to_next_day_for_late_night() {
+        let overnight = QuietHours {
+            start: "23:00".to_string(),
+            end: "07:00".to_string(),
+        };
+        let late = Local.with_ymd_and_hms(2026, 3, 10, 23, 30, 0).unwrap();
+        let resume = overnight.resume_at(late).unwrap();
+        assert_eq!(resume.date_naive().to_string(), "2026-03-11");
+        assert_eq!(resume.time(), at(7, 0));
+
+        let early = Local.with_ymd_and_hms(2026, 3, 11, 2, 0, 0).unwrap();
+        let resume = overnight.resume_at(early).unwrap();
+        assert_eq!(resume.date_naive().to_string(), "2026-03-11");
+
+        let daytime = Local.with_ymd_and_hms(2026, 3, 11, 9, 0, 0).unwrap();
+        assert!(overnight.resume_at(daytime).is_none());
+    }
+
+    #[test]
+    fn validated_rejects_bad_interval_and_time_format() {
+        let mut config = JobConfig::default_for(JobKind::Grades);
+        config.interval_minutes = 1;
+        assert!(config.clone().validated(JobKind::Grades).is_err());
+
+        config.interval_minutes = 30;
+        config.quiet_hours = Some(QuietHours {
+            start: "25:00".to_string(),
+            end: "07:00".to_string(),
+        });
+        assert!(config.clone().validated(JobKind::Grades).is_err());
+
+        config.quiet_hours = Some(QuietHours {
+            start: " ".to_string(),
+            end: "".to_string(),
+        });
+        assert_eq!(config.validated(JobKind::Grades).unwrap().quiet_hours, None);
+    }
+
+    #[test]
+    fn database_backup_interval_ranges_from_daily_to_weekly() {
+        let mut config = JobConfig::default_for(JobKind::DatabaseBackup);
+        assert_eq!(config.interval_minutes, 24 * 60);
+        config.interval_minutes = 7 * 24 * 60;
+        assert!(config.clone().validated(JobKind::DatabaseBackup).is_ok());
+        // 周备份间隔超出普通任务上限
+        assert!(config.clone().validated(JobKind::Grades).is_err());
+        config.interval_minutes = 60;
+        assert!(config.validated(JobKind::DatabaseBackup).is_err());
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(1), RETRY_BASE);
+        assert_eq!(backoff_delay(2), RETRY_BASE * 2);
+        assert_eq!(backoff_delay(3), RETRY_BASE * 4);
+        assert_eq!(backoff_delay(40), RETRY_MAX);
+
+        let base = Duration::from_secs(1000);
+        assert_eq!(apply_jitter(base, 1.0), Duration::from_secs(1200));
+        assert_eq!(apply_jitter(base, -1.0), Duration::from_secs(800));
+        assert_eq!(apply_jitter(base, 5.0), Duration::from_secs(1200));
+    }
+
+    #[test]
+    fn job_config_persists_in_kv_store_and_defaults_when_missing() {
+        let path = temp_db_path("config");
+        crate::db::init_db(&path).unwrap();
+
+        assert_eq!(
+            load_job_config(&path, JobKind::Exams),
+            JobConfig::default_for(JobKind::Exams)
+        );
+
+        let saved = save_job_config(
+            &path,
+            JobKind::Exams,
+            JobConfig {
+                enabled: true,
+                interval_minutes: 120,
+                quiet_hours: Some(QuietHours {
+                    start: " 22:30".to_string(),
+                    end: "07:00 ".to_string(),
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(saved.quiet_hours.as_ref().unwrap().start, "22:30");
+        assert_eq!(load_job_config(&path, JobKind::Exams), saved);
+        // 其它任务不受影响
+        assert!(!load_job_config(&path, JobKind::Grades).enabled);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,122 @@
+//! 各后台任务的执行体分发；成绩/考试/电费余额/选课余量/图书到期/图书检索订阅的通知逻辑位于
+//! [`crate::modules::notification`]。
+//!
+//! 成绩、考试、图书到期与电费保活逐个已登记账号执行；学校消息、在线学习、选课余量与图书检索订阅只处理活动账号；
+//! 数据库定时备份与账号无关，按文件修改时间去重，频繁重启不会重复备份。
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tauri::AppHandle;
+use tokio::sync::RwLock;
+
+use super::{
+    account_handles, merge_account_runs, read_client_snapshot, snapshot_of,
+    write_back_electricity_session, JobFailure, JobKind, JobRun,
+};
+use crate::http_client::HbutClient;
+use crate::modules::notification;
+
+pub(super) async fn run_job(app: &AppHandle, job: JobKind) -> Result<JobRun, JobFailure> {
+    match job {
+        JobKind::Grades => notification::check_grades(app).await,
+        JobKind::Exams => notification::check_exams(app).await,
+        JobKind::Electricity => refresh_electricity_session(app).await,
+        JobKind::ElectricityBalance => notification::check_electricity_balance(app).await,
+        JobKind::Inbox => sync_school_inbox(app).await,
+        JobKind::OnlineLearning => sync_online_learning(app).await,
+        JobKind::DatabaseBackup => backup_database_on_schedule().await,
+        JobKind::CourseSelectionWatch => notification::check_course_selection_watch(app).await,
+        JobKind::LibraryDue => notification::check_library_due(app).await,
+        JobKind::LibrarySearches => notification::check_library_searches(app).await,
+    }
+}
+
+/// 数据库定时备份：写入 `backup/scheduled`，距上次定时备份未满一个周期时跳过。
+async fn backup_database_on_schedule() -> Result<JobRun, JobFailure> {
+    let period =
+        super::config::load_job_config(crate::DB_FILENAME, JobKind::DatabaseBackup).interval();
+    let report = crate::db::run_blocking(move || {
+        crate::db::run_scheduled_backup(crate::DB_FILENAME, period, SystemTime::now())
+    })
+    .await
+    .map_err(JobFailure::Failed)?;
+    match report {
+        Some(report) => {
+            crate::runtime_log::log_info(
+                "scheduler",
+                format!("定时备份完成: {}", report.backup_path.display()),
+            );
+            Ok(JobRun::Completed)
+        }
+        None => Ok(JobRun::Skipped("距上次备份未满一个周期")),
+    }
+}
+
+/// 电费会话保活：逐账号在快照上刷新一码通 token，成功后回写该账号客户端并落库。
+async fn refresh_electricity_session(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let mut runs = Vec::new();
+    for handle in account_handles(app).await {
+        runs.push(refresh_account_electricity_session(&handle).await);
+    }
+    merge_account_runs(runs)
+}
+
+async fn refresh_account_electricity_session(
+    handle: &Arc<RwLock<HbutClient>>,
+) -> Result<JobRun, JobFailure> {
+    let Some(mut client) = snapshot_of(handle).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+    if client.get_electricity_session().0.is_none() {
+        return Ok(JobRun::Skipped("未授权一码通"));
+    }
+
+    client
+        .ensure_electricity_token()
+        .await
+        .map_err(|e| JobFailure::from_error(e.as_ref()))?;
+
+    write_back_electricity_session(handle, &client)
+        .await
+        .map_err(JobFailure::Failed)?;
+    Ok(JobRun::Completed)
+}
+
+/// 学校消息同步：刷新消息中心缓存，供前台打开时直接命中。
+async fn sync_school_inbox(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let Some(mut client) = read_client_snapshot(app).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+    let login_mode = if client.chaoxing_login_mode() {
+        "chaoxing"
+    } else {
+        "portal"
+    };
+    crate::modules::school_inbox::fetch_school_inbox_ex(&mut client, login_mode, false)
+        .await
+        .map_err(JobFailure::Failed)?;
+    Ok(JobRun::Completed)
+}
+
+/// 在线学习同步：等价于前台的「全部平台立即同步」，结果写入同步记录。
+#[cfg(feature = "mobile-full")]
+async fn sync_online_learning(app: &AppHandle) -> Result<JobRun, JobFailure> {
+    let Some(mut client) = read_client_snapshot(app).await else {
+        return Ok(JobRun::Skipped("未登录"));
+    };
+    crate::modules::online_learning::service::online_learning_sync_now(
+        &mut client,
+        None,
+        "all",
+        false,
+    )
+    .await
+    .map_err(|e| JobFailure::from_error(e.as_ref()))?;
+    Ok(JobRun::Completed)
+}
+
+#[cfg(not(feature = "mobile-full"))]
+async fn sync_online_learning(_app: &AppHandle) -> Result<JobRun, JobFailure> {
+    Ok(JobRun::Skipped("当前构建未包含在线学习同步"))
+}
@@ -0,0 +1,625 @@
+//! 桌面端后台任务调度器。
+//!
+//! 取代旧的 `init_background_task` 固定 30 分钟循环：
+//! - 命名任务（成绩 / 考试 / 电费保活 / 电费余额 / 学校消息 / 在线学习），各自的间隔与静默时段
+//!   持久化在 kv_store（见 [`config`]），默认全部关闭；
+//! - 网络失败按指数退避重试，所有延迟都带 ±20% 抖动；
+//! - 任务执行体只拿 `HbutClient` 的读快照（clone 共享 cookie jar），
+//!   不再为整个抓取过程持有写锁阻塞前台请求。
+//!
+//! 移动端后台由 `tauri-plugin-hbut-background`（WorkManager / BGTask）承载，
+//! 调度循环只在桌面端启动（见 `lib.rs` setup）；移动端仍可读写配置与快照。
+
+pub mod config;
+mod jobs;
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, Notify, RwLock};
+
+use crate::application::{ApplicationError, ApplicationErrorKind};
+use crate::http_client::HbutClient;
+use crate::AppState;
+
+pub use config::{JobConfig, JobKind, QuietHours};
+
+/// 调度循环的检查粒度。
+const TICK: Duration = Duration::from_secs(30);
+/// 启动/启用后首次执行前的预热延迟，让登录态恢复与前台首屏请求先完成。
+const WARMUP: Duration = Duration::from_secs(60);
+
+/// 单次执行的正常结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobRun {
+    Completed,
+    /// 前置条件不满足（未登录、未绑定等），不计为失败。
+    Skipped(&'static str),
+}
+
+/// 单次执行的失败；`Network` 触发退避，`Failed` 按正常间隔重试。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobFailure {
+    Network(String),
+    Failed(String),
+}
+
+impl JobFailure {
+    /// 按错误链识别传输层失败（超时 / 连接 / 请求发送）。
+    pub fn from_error(err: &(dyn std::error::Error + Send + Sync + 'static)) -> Self {
+        if crate::http_client::is_transport_error(err) {
+            JobFailure::Network(err.to_string())
+        } else {
+            JobFailure::Failed(err.to_string())
+        }
+    }
+
+    /// 按应用层错误分类给出本次结论：会话 / 验证码类需要用户重新登录，记为跳过；
+    /// 可重试的网络 / 上游错误触发退避；其余按普通失败处理。
+    pub fn from_application(err: ApplicationError) -> Result<JobRun, Self> {
+        match err.kind {
+            ApplicationErrorKind::SessionExpired | ApplicationErrorKind::CaptchaRequired => {
+                Ok(JobRun::Skipped("会话已失效，需重新登录"))
+            }
+            kind if kind.retryable() => Err(JobFailure::Network(err.message)),
+            _ => Err(JobFailure::Failed(err.message)),
+        }
+    }
+}
+
+/// 最近一次执行的结论（对外快照用）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    Success,
+    Skipped,
+    NetworkError,
+    Failed,
+}
+
+/// 对外暴露的任务状态快照。
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub job: JobKind,
+    pub label: &'static str,
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    pub quiet_hours: Option<QuietHours>,
+    pub in_quiet_hours: bool,
+    pub running: bool,
+    pub last_started_at: Option<String>,
+    pub last_finished_at: Option<String>,
+    pub last_outcome: Option<JobOutcome>,
+    pub last_message: Option<String>,
+    pub consecutive_failures: u32,
+    pub next_run_at: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct JobRuntime {
+    running: bool,
+    last_started_at: Option<DateTime<Local>>,
+    last_finished_at: Option<DateTime<Local>>,
+    last_outcome: Option<JobOutcome>,
+    last_message: Option<String>,
+    consecutive_failures: u32,
+    next_run_at: Option<DateTime<Local>>,
+}
+
+#[derive(Debug)]
+struct JobEntry {
+    config: JobConfig,
+    runtime: JobRuntime,
+}
+
+/// 调度器状态（Tauri managed state，Tauri command 与 Bridge 共用同一实例）。
+pub struct JobScheduler {
+    jobs: Mutex<BTreeMap<JobKind, JobEntry>>,
+    wake: Notify,
+    started: AtomicBool,
+}
+
+impl Default for JobScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobScheduler {
+    pub fn new() -> Self {
+        let jobs = JobKind::ALL
+            .into_iter()
+            .map(|job| {
+                (
+                    job,
+                    JobEntry {
+                        config: JobConfig::default_for(job),
+                        runtime: JobRuntime::default(),
+                    },
+                )
+            })
+            .collect();
+        Self {
+            jobs: Mutex::new(jobs),
+            wake: Notify::new(),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// 从 kv_store 载入全部任务配置（数据库初始化之后调用）。
+    pub async fn load_configs<P: AsRef<Path>>(&self, path: P) {
+        let now = Local::now();
+        let mut jobs = self.jobs.lock().await;
+        for (job, entry) in jobs.iter_mut() {
+            entry.config = config::load_job_config(path.as_ref(), *job);
+            entry.runtime.next_run_at = entry
+                .config
+                .enabled
+                .then(|| now + to_chrono(jittered(WARMUP)));
+        }
+    }
+
+    /// 启动调度循环（需先 [`Self::load_configs`]）；重复调用无副作用。
+    pub fn start(app: AppHandle) {
+        let scheduler = app.state::<JobScheduler>();
+        if scheduler.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            let scheduler = app.state::<JobScheduler>();
+            crate::runtime_log::log_info("scheduler", "后台任务调度器已启动");
+            loop {
+                for job in scheduler.claim_due(Local::now()).await {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let result = jobs::run_job(&app, job).await;
+                        if let Err(failure) = &result {
+                            crate::runtime_log::log_warn(
+                                "scheduler",
+                                &format!("{} 执行失败: {:?}", job.as_str(), failure),
+                            );
+                        }
+                        app.state::<JobScheduler>()
+                            .finish(job, result, Local::now())
+                            .await;
+                    });
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(TICK) => {}
+                    _ = scheduler.wake.notified() => {}
+                }
+            }
+        });
+    }
+
+    /// 全部任务的状态快照。
+    pub async fn snapshot(&self) -> Vec<JobStatus> {
+        let now = Local::now();
+        let jobs = self.jobs.lock().await;
+        jobs.iter()
+            .map(|(job, entry)| status_of(*job, entry, now))
+            .collect()
+    }
+
+    /// 校验并持久化新配置，返回更新后的状态。
+    pub async fn update<P: AsRef<Path>>(
+        &self,
+        path: P,
+        job: JobKind,
+        config: JobConfig,
+    ) -> Result<JobStatus, String> {
+        let config = config::save_job_config(path, job, config)?;
+        let status = self.apply_config(job, config, Local::now()).await;
+        self.wake.notify_one();
+        Ok(status)
+    }
+
+    /// 仅切换启用状态，其余配置保持不变。
+    pub async fn set_enabled<P: AsRef<Path>>(
+        &self,
+        path: P,
+        job: JobKind,
+        enabled: bool,
+    ) -> Result<JobStatus, String> {
+        let mut config = {
+            let jobs = self.jobs.lock().await;
+            jobs.get(&job)
+                .map(|entry| entry.config.clone())
+                .unwrap_or_else(|| JobConfig::default_for(job))
+        };
+        config.enabled = enabled;
+        self.update(path, job, config).await
+    }
+
+    /// 让已启用的任务在下一个检查点执行（静默时段内仍会顺延）。
+    pub async fn run_now(&self, job: JobKind) -> Result<JobStatus, String> {
+        let now = Local::now();
+        let status = {
+            let mut jobs = self.jobs.lock().await;
+            let entry = jobs
+                .get_mut(&job)
+                .ok_or_else(|| format!("未知任务: {}", job.as_str()))?;
+            if !entry.config.enabled {
+                return Err(format!("任务未启用: {}", job.label()));
+            }
+            entry.runtime.next_run_at = Some(now);
+            status_of(job, entry, now)
+        };
+        self.wake.notify_one();
+        Ok(status)
+    }
+
+    async fn apply_config(
+        &self,
+        job: JobKind,
+        config: JobConfig,
+        now: DateTime<Local>,
+    ) -> JobStatus {
+        let mut jobs = self.jobs.lock().await;
+        let entry = jobs.entry(job).or_insert_with(|| JobEntry {
+            config: JobConfig::default_for(job),
+            runtime: JobRuntime::default(),
+        });
+        let was_enabled = entry.config.enabled;
+        let interval_changed = entry.config.interval_minutes != config.interval_minutes;
+        entry.config = config;
+        if !entry.config.enabled {
+            entry.runtime.next_run_at = None;
+            entry.runtime.consecutive_failures = 0;
+        } else if !was_enabled {
+            // 刚启用：尽快跑一次建立基线
+            entry.runtime.next_run_at = Some(now);
+            entry.runtime.consecutive_failures = 0;
+        } else if interval_changed && entry.runtime.consecutive_failures == 0 {
+            entry.runtime.next_run_at = Some(now + to_chrono(jittered(entry.config.interval())));
+        }
+        status_of(job, entry, now)
+    }
+
+    /// 取出到期任务并标记为运行中；静默时段内的任务顺延到静默结束。
+    async fn claim_due(&self, now: DateTime<Local>) -> Vec<JobKind> {
+        let mut due = Vec::new();
+        let mut jobs = self.jobs.lock().await;
+        for (job, entry) in jobs.iter_mut() {
+            if !entry.config.enabled || entry.runtime.running {
+                continue;
+            }
+            let Some(next_run_at) = entry.runtime.next_run_at else {
+                entry.runtime.next_run_at =
+                    Some(now + to_chrono(jittered(entry.config.interval())));
+                continue;
+            };
+            if next_run_at > now {
+                continue;
+            }
+            if let Some(resume_at) = entry
+                .config
+                .quiet_hours
+                .as_ref()
+                .and_then(|quiet| quiet.resume_at(now))
+            {
+                entry.runtime.next_run_at = Some(resume_at + to_chrono(jittered(WARMUP)));
+                continue;
+            }
+            entry.runtime.running = true;
+            entry.runtime.last_started_at = Some(now);
+            due.push(*job);
+        }
+        due
+    }
+
+    /// 记录执行结果并计算下一次执行时间。
+    async fn finish(&self, job: JobKind, result: Result<JobRun, JobFailure>, now: DateTime<Local>) {
+        let mut jobs = self.jobs.lock().await;
+        let Some(entry) = jobs.get_mut(&job) else {
+            return;
+        };
+        let runtime = &mut entry.runtime;
+        runtime.running = false;
+        runtime.last_finished_at = Some(now);
+        let delay = match result {
+            Ok(JobRun::Completed) => {
+                runtime.last_outcome = Some(JobOutcome::Success);
+                runtime.last_message = None;
+                runtime.consecutive_failures = 0;
+                entry.config.interval()
+            }
+            Ok(JobRun::Skipped(reason)) => {
+                runtime.last_outcome = Some(JobOutcome::Skipped);
+                runtime.last_message = Some(reason.to_string());
+                runtime.consecutive_failures = 0;
+                entry.config.interval()
+            }
+            Err(JobFailure::Network(message)) => {
+                runtime.last_outcome = Some(JobOutcome::NetworkError);
+                runtime.last_message = Some(message);
+                runtime.consecutive_failures = runtime.consecutive_failures.saturating_add(1);
+                config::backoff_delay(runtime.consecutive_failures)
+            }
+            Err(JobFailure::Failed(message)) => {
+                runtime.last_outcome = Some(JobOutcome::Failed);
+                runtime.last_message = Some(message);
+                runtime.consecutive_failures = runtime.consecutive_failures.saturating_add(1);
+                entry.config.interval()
+            }
+        };
+        // 执行期间被关闭的任务不再排期
+        runtime.next_run_at = entry
+            .config
+            .enabled
+            .then(|| now + to_chrono(jittered(delay)));
+    }
+}
+
+/// 取 `HbutClient` 的读快照：短暂持有读锁后 clone（cookie jar / reqwest 连接池共享）。
+/// 未登录时返回 `None`。
+pub(crate) async fn read_client_snapshot(app: &AppHandle) -> Option<HbutClient> {
+    let state = app.state::<AppState>();
+    snapshot_of(&state.client).await
+}
+
+/// 指定账号句柄的读快照；未登录时返回 `None`。
+pub(crate) async fn snapshot_of(handle: &Arc<RwLock<HbutClient>>) -> Option<HbutClient> {
+    let client = handle.read().await;
+    client.user_info.as_ref()?;
+    Some(client.clone())
+}
+
+/// 所有已登记账号的客户端句柄（活动账号在前），供按账号执行的任务逐个处理。
+pub(crate) async fn account_handles(app: &AppHandle) -> Vec<Arc<RwLock<HbutClient>>> {
+    app.state::<AppState>().account_handles().await
+}
+
+/// 合并逐账号的执行结果：有账号失败时上报首个失败（网络失败优先，以便整体退避）；
+/// 否则任一账号完成即为完成，全部跳过时沿用首个跳过原因。
+pub(crate) fn merge_account_runs(
+    runs: Vec<Result<JobRun, JobFailure>>,
+) -> Result<JobRun, JobFailure> {
+    let mut completed = false;
+    let mut skipped = None;
+    let mut failure = None;
+    for run in runs {
+        match run {
+            Ok(JobRun::Completed) => completed = true,
+            Ok(JobRun::Skipped(reason)) => {
+                skipped.get_or_insert(reason);
+            }
+            Err(e @ JobFailure::Network(_)) if !matches!(failure, Some(JobFailure::Network(_))) => {
+                failure = Some(e);
+            }
+            Err(e) => {
+                failure.get_or_insert(e);
+            }
+        }
+    }
+    match failure {
+        Some(failure) => Err(failure),
+        None if completed => Ok(JobRun::Completed),
+        None => Ok(JobRun::Skipped(skipped.unwrap_or("未登录"))),
+    }
+}
+
+/// 把快照上刷新过的一码通 token 回写该账号的共享客户端并落库（只在回写时短暂持有写锁）。
+pub(crate) async fn write_back_electricity_session(
+    handle: &Arc<RwLock<HbutClient>>,
+    client: &HbutClient,
+) -> Result<(), String> {
+    let (token, refresh_token, expires_at) = client.get_electricity_session();
+    let Some(token) = token.filter(|t| !t.trim().is_empty()) else {
+        return Ok(());
+    };
+    {
+        let mut shared = handle.write().await;
+        shared.set_electricity_session(token.clone(), refresh_token.clone(), expires_at);
+    }
+    if let Some(info) = client.user_info.as_ref() {
+        crate::db::save_electricity_tokens(
+            crate::DB_FILENAME,
+            &info.student_id,
+            &token,
+            &refresh_token.unwrap_or_default(),
+            &expires_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn status_of(job: JobKind, entry: &JobEntry, now: DateTime<Local>) -> JobStatus {
+    let quiet_hours = entry.config.quiet_hours.clone();
+    JobStatus {
+        job,
+        label: job.label(),
+        enabled: entry.config.enabled,
+        interval_minutes: entry.config.interval_minutes,
+        in_quiet_hours: quiet_hours
+            .as_ref()
+            .map(|quiet| quiet.contains(now.time()))
+            .unwrap_or(false),
+        quiet_hours,
+        running: entry.runtime.running,
+        last_started_at: entry.runtime.last_started_at.map(|t| t.to_rfc3339()),
+        last_finished_at: entry.runtime.last_finished_at.map(|t| t.to_rfc3339()),
+        last_outcome: entry.runtime.last_outcome,
+        last_message: entry.runtime.last_message.clone(),
+        consecutive_failures: entry.runtime.consecutive_failures,
+        next_run_at: entry.runtime.next_run_at.map(|t| t.to_rfc3339()),
+    }
+}
+
+fn jittered(delay: Duration) -> Duration {
+    config::apply_jitter(delay, rand::random::<f64>() * 2.0 - 1.0)
+}
+
+fn to_chrono(delay: Duration) -> ChronoDuration {
+    ChronoDuration::from_std(delay).unwrap_or_else(|_| ChronoDuration::hours(6))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn noon() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    async fn enabled_scheduler(job: JobKind, quiet_hours: Option<QuietHours>) -> JobScheduler {
+        let scheduler = JobScheduler::new();
+        let config = JobConfig {
+            enabled: true,
+            interval_minutes: 30,
+            quiet_hours,
+        };
+        scheduler.apply_config(job, config, noon()).await;
+        scheduler
+    }
+
+    #[tokio::test]
+    async fn enabling_schedules_immediate_run_and_claim_marks_running() {
+        let scheduler = enabled_scheduler(JobKind::Grades, None).await;
+
+        assert_eq!(scheduler.claim_due(noon()).await, vec![JobKind::Grades]);
+        // 运行中不会被重复领取
+        assert!(scheduler.claim_due(noon()).await.is_empty());
+
+        scheduler
+            .finish(JobKind::Grades, Ok(JobRun::Completed), noon())
+            .await;
+        let status = &scheduler.snapshot().await[0];
+        assert_eq!(status.job, JobKind::Grades);
+        assert!(!status.running);
+        assert_eq!(status.last_outcome, Some(JobOutcome::Success));
+        // 下一次在 30 分钟 ±20% 之内
+        let next = DateTime::parse_from_rfc3339(status.next_run_at.as_deref().unwrap()).unwrap();
+        let minutes = (next.with_timezone(&Local) - noon()).num_minutes();
+        assert!((24..=36).contains(&minutes), "unexpected delay {minutes}");
+    }
+
+    #[tokio::test]
+    async fn network_failures_back_off_and_success_resets() {
+        let scheduler = enabled_scheduler(JobKind::Exams, None).await;
+        let mut now = noon();
+        let mut delays = Vec::new();
+        for _ in 0..3 {
+            assert_eq!(scheduler.claim_due(now).await, vec![JobKind::Exams]);
+            scheduler
+                .finish(
+                    JobKind::Exams,
+                    Err(JobFailure::Network("timeout".to_string())),
+                    now,
+                )
+                .await;
+            let jobs = scheduler.jobs.lock().await;
+            let next = jobs[&JobKind::Exams].runtime.next_run_at.unwrap();
+            delays.push((next - now).num_seconds());
+            now = next;
+        }
+        // 5 / 10 / 20 分钟（±20%）
+        assert!((240..=360).contains(&delays[0]), "{delays:?}");
+        assert!((480..=720).contains(&delays[1]), "{delays:?}");
+        assert!((960..=1440).contains(&delays[2]), "{delays:?}");
+
+        assert_eq!(scheduler.claim_due(now).await, vec![JobKind::Exams]);
+        scheduler
+            .finish(JobKind::Exams, Ok(JobRun::Skipped("未登录")), now)
+            .await;
+        let status = scheduler
+            .snapshot()
+            .await
+            .into_iter()
+            .find(|s| s.job == JobKind::Exams)
+            .unwrap();
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.last_outcome, Some(JobOutcome::Skipped));
+        assert_eq!(status.last_message.as_deref(), Some("未登录"));
+    }
+
+    #[tokio::test]
+    async fn quiet_hours_defer_due_job_until_window_ends() {
+        let quiet = QuietHours {
+            start: "11:00".to_string(),
+            end: "13:00".to_string(),
+        };
+        let scheduler = enabled_scheduler(JobKind::Inbox, Some(quiet)).await;
+
+        assert!(scheduler.claim_due(noon()).await.is_empty());
+        let jobs = scheduler.jobs.lock().await;
+        let next = jobs[&JobKind::Inbox].runtime.next_run_at.unwrap();
+        assert!(next >= Local.with_ymd_and_hms(2026, 3, 10, 13, 0, 0).unwrap());
+        assert!(!jobs[&JobKind::Inbox].runtime.running);
+    }
+
+    #[test]
+    fn account_runs_merge_failures_before_completion() {
+        assert_eq!(
+            merge_account_runs(Vec::new()),
+            Ok(JobRun::Skipped("未登录"))
+        );
+        assert_eq!(
+            merge_account_runs(vec![
+                Ok(JobRun::Skipped("未授权一码通")),
+                Ok(JobRun::Completed)
+            ]),
+            Ok(JobRun::Completed)
+        );
+        assert_eq!(
+            merge_account_runs(vec![
+                Ok(JobRun::Completed),
+                Err(JobFailure::Failed("a".into())),
+                Err(JobFailure::Network("b".into())),
+                Err(JobFailure::Network("c".into())),
+            ]),
+            Err(JobFailure::Network("b".into()))
+        );
+    }
+
+    #[test]
+    fn application_errors_skip_back_off_or_fail_by_kind() {
+        assert_eq!(
+            JobFailure::from_application(ApplicationError::session_expired("会话已过期")),
+            Ok(JobRun::Skipped("会话已失效，需重新登录"))
+        );
+        assert_eq!(
+            JobFailure::from_application(ApplicationError::network("timeout")),
+            Err(JobFailure::Network("timeout".into()))
+        );
+        assert_eq!(
+            JobFailure::from_application(ApplicationError::upstream("系统维护中")),
+            Err(JobFailure::Network("系统维护中".into()))
+        );
+        assert_eq!(
+            JobFailure::from_application(ApplicationError::internal("解析失败")),
+            Err(JobFailure::Failed("解析失败".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn disabling_while_running_drops_next_run() {
+        let scheduler = enabled_scheduler(JobKind::Electricity, None).await;
+        assert_eq!(
+            scheduler.claim_due(noon()).await,
+            vec![JobKind::Electricity]
+        );
+
+        let mut config = JobConfig::default_for(JobKind::Electricity);
+        config.enabled = false;
+        scheduler
+            .apply_config(JobKind::Electricity, config, noon())
+            .await;
+        scheduler
+            .finish(JobKind::Electricity, Ok(JobRun::Completed), noon())
+            .await;
+
+        {
+            let jobs = scheduler.jobs.lock().await;
+            assert_eq!(jobs[&JobKind::Electricity].runtime.next_run_at, None);
+        }
+        assert!(scheduler.run_now(JobKind::Electricity).await.is_err());
+    }
+}
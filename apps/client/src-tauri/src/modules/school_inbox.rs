@@ -16,16 +16,8 @@ const CHAOXING_NOTICE_LIST_BASE: &str =
 const CHAOXING_NOTICE_MAX_PAGES: usize = 20;
 /// 首次/缓存未命中时先拉的页数，避免每次进收件箱等很久
 const CHAOXING_NOTICE_FAST_PAGES: usize = 3;
-/// 内存缓存 TTL（秒）
-const INBOX_CACHE_TTL_SECS: i64 = 180;
-
-static INBOX_CACHE: std::sync::OnceLock<
-    std::sync::Mutex<Option<(i64, String, SchoolInboxResponse)>>,
-> = std::sync::OnceLock::new();
-
-fn inbox_cache() -> &'static std::sync::Mutex<Option<(i64, String, SchoolInboxResponse)>> {
-    INBOX_CACHE.get_or_init(|| std::sync::Mutex::new(None))
-}
+/// 短时缓存表（key = `学号:来源`，TTL 见 `db::cache_policy`）
+const INBOX_CACHE_TABLE: &str = "school_inbox_cache";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -444,7 +436,7 @@ async fn fetch_chaoxing_inbox(
 }
 
 /// 按登录方式抓取学校消息中心并归一化。
-/// `force=true` 时忽略短时缓存并尽量拉全页。
+/// `force=true` 时忽略短时缓存并尽量拉全页；未登录（无学号）时不读写缓存。
 pub async fn fetch_school_inbox(
     client: &mut HbutClient,
     login_mode: &str,
@@ -460,27 +452,29 @@ pub async fn fetch_school_inbox_ex(
     let use_chaoxing = is_chaoxing_login_mode(login_mode);
     client.set_chaoxing_login_mode(use_chaoxing);
     let source = if use_chaoxing { "chaoxing" } else { "portal" };
-    let cache_key = format!(
-        "{source}:{}",
-        client
-            .user_info
-            .as_ref()
-            .map(|u| u.student_id.as_str())
-            .unwrap_or("-")
-    );
-
-    if !force {
-        if let Ok(guard) = inbox_cache().lock() {
-            if let Some((ts, key, cached)) = guard.as_ref() {
-                let age = Local::now().timestamp() - *ts;
-                if key == &cache_key && age >= 0 && age < INBOX_CACHE_TTL_SECS {
+    let cache_key = client
+        .user_info
+        .as_ref()
+        .map(|u| u.student_id.trim())
+        .filter(|sid| !sid.is_empty())
+        .map(|sid| format!("{sid}:{source}"));
+
+    if let Some(key) = cache_key.clone().filter(|_| !force) {
+        let now = Local::now().timestamp();
+        let entry = crate::db::run_blocking(move || {
+            crate::db::read_cache_entry(crate::DB_FILENAME, INBOX_CACHE_TABLE, &key, now)
+        })
+        .await;
+        if let Ok(Some(entry)) = entry {
+            if entry.freshness == crate::db::CacheFreshness::Fresh {
+                if let Ok(cached) = serde_json::from_value::<SchoolInboxResponse>(entry.data) {
                     crate::hbut_session_log!(
                         "SchoolInbox",
-                        "命中内存缓存 age={}s count={}",
-                        age,
+                        "命中缓存 age={}s count={}",
+                        entry.age_secs.unwrap_or_default(),
                         cached.items.len()
                     );
-                    return Ok(cached.clone());
+                    return Ok(cached);
                 }
             }
         }
@@ -513,8 +507,12 @@ pub async fn fetch_school_inbox_ex(
         source: source.to_string(),
         error: None,
     };
-    if let Ok(mut guard) = inbox_cache().lock() {
-        *guard = Some((Local::now().timestamp(), cache_key, resp.clone()));
+    if let Some(key) = cache_key.as_deref() {
+        if let Ok(payload) = serde_json::to_value(&resp) {
+            let _ =
+                crate::db::save_cache_async(crate::DB_FILENAME, INBOX_CACHE_TABLE, key, &payload)
+                    .await;
+        }
     }
     Ok(resp)
 }
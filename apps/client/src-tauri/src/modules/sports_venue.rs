@@ -161,14 +161,15 @@ fn clean_role_id(role_id: Option<&str>) -> Option<String> {
 
 /// 场馆 HTTP：自动 SM2 加解密 + token 头
 async fn venue_post(
-    client: &reqwest::Client,
+    client: &crate::http_client::HbutClient,
     path: &str,
     token: Option<&str>,
     role_id: Option<&str>,
     body: Option<Value>,
 ) -> Result<Value, String> {
-    let url = format!("{VENUE_BASE}{path}");
+    let url = client.upstream_url(&format!("{VENUE_BASE}{path}"));
     let mut req = client
+        .client
         .post(&url)
         .header("Accept", "application/json, text/plain, */*")
         .header("Content-Type", "application/json")
@@ -282,11 +283,11 @@ async fn fetch_venue_sso_token(
     access: &str,
     redirect_target: &str,
 ) -> Result<String, String> {
-    let open_url = format!(
+    let open_url = client.upstream_url(&format!(
         "{CODE_BASE}/server/third/open?redirectUrl={}&accessToken={}",
         url_encode(redirect_target),
         url_encode(access)
-    );
+    ));
 
     // 1) 自动跟随
     if let Ok(resp) = client
@@ -380,7 +381,7 @@ async fn fetch_venue_sso_token(
             current = if location.starts_with("http") {
                 location.clone()
             } else if location.starts_with('/') {
-                format!("{CODE_BASE}{location}")
+                client.upstream_url(&format!("{CODE_BASE}{location}"))
             } else {
                 location.clone()
             };
@@ -449,7 +450,7 @@ async fn login_venue(
 
     // authentication：body 为加密后的 token 字符串
     let auth = venue_post(
-        &client.client,
+        client,
         "/reserve/index/authentication",
         None,
         None,
@@ -486,7 +487,7 @@ pub async fn sports_venue_bootstrap(state: State<'_, AppState>) -> Result<Value,
     let role = role_id_of(&user);
 
     let list = venue_post(
-        &client.client,
+        &client,
         "/reserve/stadium/listAll",
         Some(&token),
         role.as_deref(),
@@ -547,7 +548,7 @@ pub async fn sports_venue_detail(
     });
     crate::runtime_log::log_info("SportsVenue", format!("detail body={body}"));
     venue_post(
-        &client.client,
+        &client,
         "/reserve/place/detailByStadiumId",
         Some(token.trim()),
         role_id.as_deref(),
@@ -566,7 +567,7 @@ pub async fn sports_venue_reserve(
 ) -> Result<Value, String> {
     let client = state.client.read().await;
     venue_post(
-        &client.client,
+        &client,
         "/reserve/place/reserve",
         Some(token.trim()),
         role_id.as_deref(),
@@ -592,7 +593,7 @@ pub async fn sports_venue_orders(
         "dateRange": date_range.unwrap_or(Value::Null)
     });
     venue_post(
-        &client.client,
+        &client,
         "/reserve/orderInfo/list",
         Some(token.trim()),
         role_id.as_deref(),
@@ -616,7 +617,7 @@ pub async fn sports_venue_records(
         "pageSize": page_size.unwrap_or(10)
     });
     venue_post(
-        &client.client,
+        &client,
         "/reserve/reserveRecord/list",
         Some(token.trim()),
         role_id.as_deref(),
@@ -638,7 +639,7 @@ pub async fn sports_venue_pay(
     let client = state.client.read().await;
     // 部分流程先 callPay 再 pay
     let _ = venue_post(
-        &client.client,
+        &client,
         "/reserve/orderInfo/callPay",
         Some(token.trim()),
         role_id.as_deref(),
@@ -647,7 +648,7 @@ pub async fn sports_venue_pay(
     .await;
 
     venue_post(
-        &client.client,
+        &client,
         "/reserve/orderInfo/pay",
         Some(token.trim()),
         role_id.as_deref(),
@@ -670,7 +671,7 @@ pub async fn sports_venue_cancel_pay(
 ) -> Result<Value, String> {
     let client = state.client.read().await;
     venue_post(
-        &client.client,
+        &client,
         "/reserve/orderInfo/cancelPay",
         Some(token.trim()),
         role_id.as_deref(),
@@ -37,6 +37,9 @@ pub struct TrainingPlanOptions {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Course {
     pub id: String,
+    /// 课程编号 (kcbh，列表接口可能不返回)
+    #[serde(default)]
+    pub code: String,
     pub name: String,
     pub course_type: String,
     pub required: bool,
@@ -50,6 +53,36 @@ pub struct Course {
     pub department: String,
 }
 
+impl Course {
+    /// 从课程列表（jqgrid `results` / 缓存 `data`）的一行解析；缺少课程名时返回 `None`。
+    pub fn from_plan_row(row: &Value) -> Option<Self> {
+        let text = |key: &str| match row.get(key) {
+            Some(Value::String(v)) => v.trim().to_string(),
+            Some(Value::Number(v)) => v.to_string(),
+            _ => String::new(),
+        };
+        let name = text("kcmc");
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            id: text("id"),
+            code: text("kcbh"),
+            name,
+            course_type: text("kcxz"),
+            required: matches!(text("sfbx").as_str(), "是" | "1" | "必修"),
+            grade: text("gradename"),
+            semester: text("kkxq"),
+            credit: text("xf"),
+            total_hours: text("zongxs"),
+            theory_hours: text("llxs"),
+            practice_hours: text("shijianxs"),
+            college: text("kkyxmc"),
+            department: text("kkjysmc"),
+        })
+    }
+}
+
 pub struct TrainingPlanModule {
     client: Client,
 }
@@ -3,14 +3,15 @@
 //! 主要职责:
 //! 1. 调用 Open-Meteo 免费 API 获取实时天气数据
 //! 2. 返回当前天气 + 3 天预报
-//! 3. 内置 5 分钟缓存，避免频繁请求
+//! 3. 结果写入 `weather_public_cache`（5 分钟新鲜期，1 小时内先返回旧值并后台刷新），
+//!    重启后仍可复用，网络失败时以旧数据兜底
 //!
 //! API: https://api.open-meteo.com/v1/forecast (无需 API Key)
 //! 坐标: 湖北工业大学 (30.67°N, 114.35°E)
 
-use serde::Serialize;
-use std::sync::{Mutex, OnceLock};
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::{db, DB_FILENAME};
 
 /// 湖北工业大学坐标
 const LATITUDE: f64 = 30.67;
@@ -19,13 +20,14 @@ const LONGITUDE: f64 = 114.35;
 /// Open-Meteo API 地址
 const API_URL: &str = "https://api.open-meteo.com/v1/forecast";
 
-/// 缓存有效期 (5 分钟)
-const CACHE_TTL_SECS: u64 = 300;
+/// 缓存表与 key（TTL / 陈旧窗口见 `db::cache_policy`）
+const CACHE_TABLE: &str = "weather_public_cache";
+const CACHE_KEY: &str = "hbut";
 
 // ─── 数据结构 ───────────────────────────────────────────────
 
 /// 天气数据（返回给前端）
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherData {
     /// 当前温度 (°C)
     pub temp: i32,
@@ -48,7 +50,7 @@ pub struct WeatherData {
 }
 
 /// 单日预报
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForecastDay {
     /// 日期标签（今天/明天/后天）
     pub day: String,
@@ -63,7 +65,7 @@ pub struct ForecastDay {
 }
 
 /// 逐时预报项
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HourlyItem {
     /// 时间标签（如 "14:00" 或 "现在"）
     pub time: String,
@@ -75,43 +77,21 @@ pub struct HourlyItem {
     pub icon: String,
 }
 
-// ─── 缓存 ───────────────────────────────────────────────────
-
-static WEATHER_CACHE: OnceLock<Mutex<Option<(Instant, WeatherData)>>> = OnceLock::new();
-
-fn get_cache() -> &'static Mutex<Option<(Instant, WeatherData)>> {
-    WEATHER_CACHE.get_or_init(|| Mutex::new(None))
-}
-
 // ─── Tauri Command ──────────────────────────────────────────
 
-/// 获取天气数据（带 5 分钟缓存）
+/// 获取天气数据（stale-while-revalidate 持久缓存）
 #[tauri::command]
 pub async fn fetch_weather() -> Result<WeatherData, String> {
-    // 检查缓存
-    {
-        let cache = get_cache()
-            .lock()
-            .map_err(|e| format!("缓存锁异常: {}", e))?;
-        if let Some((ts, data)) = cache.as_ref() {
-            if ts.elapsed().as_secs() < CACHE_TTL_SECS {
-                return Ok(data.clone());
-            }
-        }
-    }
-
-    // 缓存过期或不存在，请求 API
-    let data = request_weather().await?;
-
-    // 写入缓存
-    {
-        let mut cache = get_cache()
-            .lock()
-            .map_err(|e| format!("缓存锁异常: {}", e))?;
-        *cache = Some((Instant::now(), data.clone()));
+    let read = db::read_through_cache(DB_FILENAME, CACHE_TABLE, CACHE_KEY, || async {
+        let data = request_weather().await?;
+        serde_json::to_value(data).map_err(|e| format!("序列化天气数据失败: {}", e))
+    })
+    .await?;
+    match serde_json::from_value(read.data) {
+        Ok(data) => Ok(data),
+        // 缓存内容无法解析（结构变更未递增版本等）：直接请求最新数据
+        Err(_) => request_weather().await,
     }
-
-    Ok(data)
 }
 
 // ─── API 请求与解析 ─────────────────────────────────────────
@@ -546,7 +546,7 @@ fn extract_text_from_html(html_str: &str) -> String {
     re.replace_all(html_str, "").trim().to_string()
 }
 
-fn parse_weeks(weeks_str: &str) -> Vec<i32> {
+pub(crate) fn parse_weeks(weeks_str: &str) -> Vec<i32> {
     let mut weeks = Vec::new();
 
     // 检测单双周标记（半角+全角括号）
@@ -669,6 +669,14 @@ pub fn parse_exams(json: &Value) -> Result<Vec<Exam>, Box<dyn std::error::Error
             .unwrap_or("")
             .to_string();
 
+        // 考试性质（正常 / 补考）
+        let exam_type = item
+            .get("kslxmc")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim()
+            .to_string();
+
         let exam = Exam {
             course_name,
             date,
@@ -680,6 +688,7 @@ pub fn parse_exams(json: &Value) -> Result<Vec<Exam>, Box<dyn std::error::Error
                 location
             },
             seat_number,
+            exam_type,
         };
         exams.push(exam);
     }
@@ -0,0 +1,624 @@
+//! 进程内运行时事件总线（始终可用，不依赖 debug_assertions）
+//!
+//! - 同步开始 / 结束、成绩 / 考试变化、会话失效、后台插件事件与运行时日志统一编号
+//! - 事件按单调 id 保存在环形缓冲中，HTTP bridge `GET /events/stream` 以 SSE
+//!   推送，并按 `Last-Event-ID` 补发缓冲内的历史事件
+//! - id 以进程启动时刻（微秒）为起点，重启后不会回到 0：上一进程的 id 一律落在缓冲之前，
+//!   续传时按缺口处理（发送 `resync`）而不是被当作“尚未发生”的事件静默丢弃
+//! - 日志单独成环，避免高频日志把同步 / 变更事件挤出缓冲
+
+use chrono::Local;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tauri_plugin_hbut_background::BackgroundEvent;
+use tokio::sync::broadcast;
+
+use crate::runtime_log::RuntimeLogItem;
+
+const MAX_EVENTS: usize = 500;
+const MAX_LOG_EVENTS: usize = 1000;
+/// 实时通道容量；订阅方落后更多时按 id 从环形缓冲补发
+const CHANNEL_CAPACITY: usize = 256;
+
+/// 变化摘要（与推送通知的标题 / 正文一致）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangeNotice {
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOutcome {
+    Success,
+    /// 上游失败，返回了本地缓存快照
+    Offline,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuntimeEvent {
+    SyncStarted {
+        domain: String,
+        account: Option<String>,
+    },
+    SyncFinished {
+        domain: String,
+        account: Option<String>,
+        outcome: SyncOutcome,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        elapsed_ms: u64,
+    },
+    GradesChanged {
+        account: String,
+        changes: Vec<ChangeNotice>,
+    },
+    ExamsChanged {
+        account: String,
+        changes: Vec<ChangeNotice>,
+    },
+    SessionExpired {
+        message: String,
+    },
+    /// hbut-background 插件收件箱中的事件（只读转发，不代为消费）
+    Background {
+        event: BackgroundEvent,
+    },
+    Log {
+        entry: RuntimeLogItem,
+    },
+}
+
+impl RuntimeEvent {
+    /// SSE `event:` 字段，与序列化后的 `type` 一致。
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuntimeEvent::SyncStarted { .. } => "sync_started",
+            RuntimeEvent::SyncFinished { .. } => "sync_finished",
+            RuntimeEvent::GradesChanged { .. } => "grades_changed",
+            RuntimeEvent::ExamsChanged { .. } => "exams_changed",
+            RuntimeEvent::SessionExpired { .. } => "session_expired",
+            RuntimeEvent::Background { .. } => "background",
+            RuntimeEvent::Log { .. } => "log",
+        }
+    }
+
+    pub fn category(&self) -> EventCategory {
+        match self {
+            RuntimeEvent::SyncStarted { .. } | RuntimeEvent::SyncFinished { .. } => {
+                EventCategory::Sync
+            }
+            RuntimeEvent::GradesChanged { .. } => EventCategory::Grades,
+            RuntimeEvent::ExamsChanged { .. } => EventCategory::Exams,
+            RuntimeEvent::SessionExpired { .. } => EventCategory::Session,
+            RuntimeEvent::Background { .. } => EventCategory::Background,
+            RuntimeEvent::Log { .. } => EventCategory::Log,
+        }
+    }
+}
+
+/// 订阅过滤用的事件类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventCategory {
+    Sync,
+    Grades,
+    Exams,
+    Session,
+    Background,
+    Log,
+}
+
+impl EventCategory {
+    pub const ALL: [EventCategory; 6] = [
+        EventCategory::Sync,
+        EventCategory::Grades,
+        EventCategory::Exams,
+        EventCategory::Session,
+        EventCategory::Background,
+        EventCategory::Log,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventCategory::Sync => "sync",
+            EventCategory::Grades => "grades",
+            EventCategory::Exams => "exams",
+            EventCategory::Session => "session",
+            EventCategory::Background => "background",
+            EventCategory::Log => "log",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL.into_iter().find(|item| item.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeEventEnvelope {
+    pub id: u64,
+    /// 毫秒时间戳
+    pub ts: i64,
+    #[serde(flatten)]
+    pub event: RuntimeEvent,
+}
+
+/// 日志级别序（未知级别按 info 处理）。
+fn log_level_rank(level: &str) -> u8 {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "debug" | "trace" => 0,
+        "warn" | "warning" => 2,
+        "error" => 3,
+        _ => 1,
+    }
+}
+
+/// 订阅过滤：类别白名单 + 日志最低级别。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    categories: Vec<EventCategory>,
+    min_log_rank: u8,
+}
+
+impl EventFilter {
+    /// `types` 为逗号分隔的类别；缺省时订阅除日志外的全部类别。
+    /// 指定 `log_level` 即订阅该级别及以上的日志；只在 `types` 中列出 `log` 时默认 info。
+    pub fn parse(types: Option<&str>, log_level: Option<&str>) -> Result<Self, String> {
+        let mut categories = Vec::new();
+        match types.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(types) => {
+                for raw in types.split(',').filter(|raw| !raw.trim().is_empty()) {
+                    let category = EventCategory::parse(raw)
+                        .ok_or_else(|| format!("未知事件类别: {}", raw.trim()))?;
+                    if !categories.contains(&category) {
+                        categories.push(category);
+                    }
+                }
+            }
+            None => categories.extend(
+                EventCategory::ALL
+                    .into_iter()
+                    .filter(|category| *category != EventCategory::Log),
+            ),
+        }
+        let log_level = log_level.map(str::trim).filter(|value| !value.is_empty());
+        if let Some(level) = log_level {
+            if !matches!(
+                level.to_ascii_lowercase().as_str(),
+                "debug" | "info" | "warn" | "error"
+            ) {
+                return Err(format!("未知日志级别: {level}"));
+            }
+            if !categories.contains(&EventCategory::Log) {
+                categories.push(EventCategory::Log);
+            }
+        }
+        Ok(Self {
+            categories,
+            min_log_rank: log_level.map(log_level_rank).unwrap_or(1),
+        })
+    }
+
+    /// 是否订阅日志环中的事件。
+    fn wants_logs(&self) -> bool {
+        self.categories.contains(&EventCategory::Log)
+    }
+
+    /// 是否订阅事件环（日志以外）中的类别。
+    fn wants_events(&self) -> bool {
+        self.categories
+            .iter()
+            .any(|category| *category != EventCategory::Log)
+    }
+
+    pub fn matches(&self, envelope: &RuntimeEventEnvelope) -> bool {
+        if !self.categories.contains(&envelope.event.category()) {
+            return false;
+        }
+        match &envelope.event {
+            RuntimeEvent::Log { entry } => log_level_rank(&entry.level) >= self.min_log_rank,
+            _ => true,
+        }
+    }
+}
+
+/// 按 `Last-Event-ID` 补发的结果。
+#[derive(Debug, Default)]
+pub struct Replay {
+    /// 过滤后待补发的事件（按 id 升序）
+    pub events: Vec<Arc<RuntimeEventEnvelope>>,
+    /// 请求的 id 之后已有订阅范围内的事件被挤出缓冲（或 id 不属于本进程），
+    /// 客户端应重新拉取全量状态
+    pub gap: bool,
+    /// 补发覆盖到的最大 id（含被过滤掉的事件），实时通道按此去重
+    pub cursor: u64,
+}
+
+struct Ring {
+    items: VecDeque<Arc<RuntimeEventEnvelope>>,
+    capacity: usize,
+    /// 已被挤出缓冲的最大 id（初始为起始序号，早于本进程的 id 视同已挤出）
+    evicted_through: u64,
+}
+
+impl Ring {
+    fn new(capacity: usize, base: u64) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity.min(128)),
+            capacity,
+            evicted_through: base,
+        }
+    }
+
+    fn push(&mut self, envelope: Arc<RuntimeEventEnvelope>) {
+        self.items.push_back(envelope);
+        while self.items.len() > self.capacity {
+            if let Some(evicted) = self.items.pop_front() {
+                self.evicted_through = evicted.id;
+            }
+        }
+    }
+}
+
+struct EventBus {
+    seq: u64,
+    events: Ring,
+    logs: Ring,
+    sender: broadcast::Sender<Arc<RuntimeEventEnvelope>>,
+    /// 上一次读取插件收件箱时看到的事件 id（收件箱有容量上限，集合随之有界）
+    background_seen: HashSet<String>,
+}
+
+impl EventBus {
+    fn new(max_events: usize, max_logs: usize) -> Self {
+        Self::starting_at(max_events, max_logs, 0)
+    }
+
+    /// 首个事件 id 为 `base + 1`。
+    fn starting_at(max_events: usize, max_logs: usize, base: u64) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            seq: base,
+            events: Ring::new(max_events, base),
+            logs: Ring::new(max_logs, base),
+            sender,
+            background_seen: HashSet::new(),
+        }
+    }
+
+    fn publish(&mut self, event: RuntimeEvent) -> Arc<RuntimeEventEnvelope> {
+        self.seq = self.seq.saturating_add(1);
+        let is_log = matches!(event, RuntimeEvent::Log { .. });
+        let envelope = Arc::new(RuntimeEventEnvelope {
+            id: self.seq,
+            ts: Local::now().timestamp_millis(),
+            event,
+        });
+        if is_log {
+            self.logs.push(envelope.clone());
+        } else {
+            self.events.push(envelope.clone());
+        }
+        // 无订阅者时发送失败属正常情况
+        let _ = self.sender.send(envelope.clone());
+        envelope
+    }
+
+    fn replay_since(&self, last_id: u64, filter: &EventFilter) -> Replay {
+        // 超过当前序号的 id 不是本进程发出的（时钟回拨或客户端自造）：从缓冲起点整体重放
+        let future = last_id > self.seq;
+        let last_id = if future { 0 } else { last_id };
+        // 只有订阅范围内的环被挤出才算缺口，日志刷屏不影响只订阅同步 / 变更事件的客户端
+        let gap = future
+            || (filter.wants_events() && last_id < self.events.evicted_through)
+            || (filter.wants_logs() && last_id < self.logs.evicted_through);
+        let mut events = self
+            .events
+            .items
+            .iter()
+            .chain(self.logs.items.iter())
+            .filter(|envelope| envelope.id > last_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        events.sort_by_key(|envelope| envelope.id);
+        let cursor = events.last().map_or(last_id, |envelope| envelope.id);
+        events.retain(|envelope| filter.matches(envelope));
+        Replay {
+            events,
+            gap,
+            cursor,
+        }
+    }
+
+    /// 返回收件箱中尚未转发过的事件，并以本次快照替换已见集合。
+    fn unseen_background(&mut self, inbox: Vec<BackgroundEvent>) -> Vec<BackgroundEvent> {
+        let current = inbox
+            .iter()
+            .map(|event| event.id.clone())
+            .collect::<HashSet<_>>();
+        let fresh = inbox
+            .into_iter()
+            .filter(|event| !self.background_seen.contains(&event.id))
+            .collect();
+        self.background_seen = current;
+        fresh
+    }
+}
+
+fn bus() -> &'static Mutex<EventBus> {
+    static BUS: OnceLock<Mutex<EventBus>> = OnceLock::new();
+    BUS.get_or_init(|| {
+        let base = u64::try_from(Local::now().timestamp_micros()).unwrap_or(0);
+        Mutex::new(EventBus::starting_at(MAX_EVENTS, MAX_LOG_EVENTS, base))
+    })
+}
+
+fn lock_bus() -> std::sync::MutexGuard<'static, EventBus> {
+    match bus().lock() {
+        Ok(g) => g,
+        Err(e) => e.into_inner(),
+    }
+}
+
+/// 发布事件，返回分配的 id。
+///
+/// 持锁期间不得写运行时日志（日志本身也经此发布）。
+pub fn publish(event: RuntimeEvent) -> u64 {
+    lock_bus().publish(event).id
+}
+
+/// 订阅实时事件；应先订阅再 [`replay_since`]，按 id 去重衔接。
+pub fn subscribe() -> broadcast::Receiver<Arc<RuntimeEventEnvelope>> {
+    lock_bus().sender.subscribe()
+}
+
+/// 缓冲中 id 大于 `last_id` 且符合 `filter` 的事件（按 id 升序）。
+pub fn replay_since(last_id: u64, filter: &EventFilter) -> Replay {
+    lock_bus().replay_since(last_id, filter)
+}
+
+/// 转发 hbut-background 插件收件箱中的新事件，返回转发条数。
+pub fn ingest_background_events(inbox: Vec<BackgroundEvent>) -> usize {
+    let fresh = lock_bus().unseen_background(inbox);
+    let count = fresh.len();
+    for event in fresh {
+        publish(RuntimeEvent::Background { event });
+    }
+    count
+}
+
+/// 会话失效提示（Bridge / Tauri 错误映射处调用）。
+pub fn publish_session_expired(message: &str) {
+    publish(RuntimeEvent::SessionExpired {
+        message: message.to_string(),
+    });
+}
+
+/// 同步计时辅助：创建时发布 `sync_started`，结束时发布带结论与耗时的 `sync_finished`。
+pub struct SyncSpan {
+    domain: String,
+    account: Option<String>,
+    start: Instant,
+}
+
+impl SyncSpan {
+    pub fn start(domain: impl Into<String>, account: Option<&str>) -> Self {
+        let domain = domain.into();
+        let account = account.map(str::to_string);
+        publish(RuntimeEvent::SyncStarted {
+            domain: domain.clone(),
+            account: account.clone(),
+        });
+        Self {
+            domain,
+            account,
+            start: Instant::now(),
+        }
+    }
+
+    /// 按同步结果收尾：payload 带 `offline=true` 视为缓存降级。
+    pub fn finish<E: Display>(self, result: Result<&Value, &E>) {
+        let (outcome, message) = match result {
+            Ok(payload) if payload.get("offline").and_then(Value::as_bool) == Some(true) => {
+                (SyncOutcome::Offline, None)
+            }
+            Ok(_) => (SyncOutcome::Success, None),
+            Err(error) => (SyncOutcome::Failed, Some(error.to_string())),
+        };
+        publish(RuntimeEvent::SyncFinished {
+            domain: self.domain,
+            account: self.account,
+            outcome,
+            message,
+            elapsed_ms: self.start.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tauri_plugin_hbut_background::{BackgroundSource, BG_SCHEMA_VERSION};
+
+    fn log_entry(id: u64, level: &str) -> RuntimeLogItem {
+        RuntimeLogItem {
+            id,
+            ts: 0,
+            ts_text: String::new(),
+            level: level.to_string(),
+            scope: "test".to_string(),
+            message: "line".to_string(),
+            details: None,
+            elapsed_ms: 0,
+        }
+    }
+
+    fn background_event(id: &str) -> BackgroundEvent {
+        BackgroundEvent {
+            schema: BG_SCHEMA_VERSION,
+            id: id.to_string(),
+            source: BackgroundSource::Android,
+            kind: "grades_changed".to_string(),
+            scope: Some("2024010101".to_string()),
+            occurred_at: "2026-01-01T00:00:00Z".to_string(),
+            payload: json!({}),
+        }
+    }
+
+    fn session_event() -> RuntimeEvent {
+        RuntimeEvent::SessionExpired {
+            message: "会话已过期".to_string(),
+        }
+    }
+
+    #[test]
+    fn envelope_serializes_flat_with_type_tag() {
+        let mut bus = EventBus::new(4, 4);
+        let envelope = bus.publish(RuntimeEvent::SyncFinished {
+            domain: "grades".to_string(),
+            account: Some("2024010101".to_string()),
+            outcome: SyncOutcome::Offline,
+            message: None,
+            elapsed_ms: 12,
+        });
+        let value = serde_json::to_value(envelope.as_ref()).unwrap();
+        assert_eq!(value["id"], 1);
+        assert_eq!(value["type"], envelope.event.name());
+        assert_eq!(value["outcome"], "offline");
+        assert!(value.get("message").is_none());
+    }
+
+    #[test]
+    fn replay_merges_rings_in_id_order_and_reports_gaps() {
+        let mut bus = EventBus::new(2, 3);
+        bus.publish(session_event()); // 1
+        bus.publish(RuntimeEvent::Log {
+            entry: log_entry(1, "info"),
+        }); // 2
+        bus.publish(session_event()); // 3
+        bus.publish(RuntimeEvent::Log {
+            entry: log_entry(2, "warn"),
+        }); // 4
+
+        let all = EventFilter::parse(Some("session,log"), None).unwrap();
+        let replay = bus.replay_since(0, &all);
+        assert!(!replay.gap);
+        let ids = replay.events.iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+
+        bus.publish(session_event()); // 5，挤出 1
+        let replay = bus.replay_since(0, &all);
+        assert!(replay.gap);
+        assert_eq!(replay.events.first().map(|e| e.id), Some(2));
+        let replay = bus.replay_since(1, &all);
+        assert!(!replay.gap);
+        let ids = replay.events.iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![2, 3, 4, 5]);
+        assert_eq!(replay.cursor, 5);
+        assert!(bus.replay_since(5, &all).events.is_empty());
+    }
+
+    #[test]
+    fn gaps_only_count_rings_the_filter_covers() {
+        let mut bus = EventBus::new(4, 1);
+        bus.publish(session_event()); // 1
+        bus.publish(RuntimeEvent::Log {
+            entry: log_entry(1, "info"),
+        }); // 2
+        bus.publish(RuntimeEvent::Log {
+            entry: log_entry(2, "info"),
+        }); // 3，挤出日志 2
+
+        let session = EventFilter::parse(Some("session"), None).unwrap();
+        let replay = bus.replay_since(1, &session);
+        assert!(!replay.gap);
+        assert!(replay.events.is_empty());
+        // 被过滤的日志也推进游标，避免实时通道重复判断
+        assert_eq!(replay.cursor, 3);
+
+        let logs = EventFilter::parse(Some("log"), None).unwrap();
+        assert!(bus.replay_since(1, &logs).gap);
+    }
+
+    #[test]
+    fn ids_from_a_previous_process_force_resync() {
+        let filter = EventFilter::parse(None, None).unwrap();
+        let mut bus = EventBus::starting_at(4, 4, 1_000);
+        let first = bus.publish(session_event());
+        assert_eq!(first.id, 1_001);
+
+        // 上一进程的 id 早于本进程起点
+        let stale = bus.replay_since(42, &filter);
+        assert!(stale.gap);
+        assert_eq!(stale.events.len(), 1);
+
+        // 超过当前序号：同样要求重新同步，并从缓冲起点重放
+        let future = bus.replay_since(5_000, &filter);
+        assert!(future.gap);
+        assert_eq!(future.cursor, 1_001);
+        assert_eq!(future.events.first().map(|e| e.id), Some(1_001));
+
+        assert!(!bus.replay_since(1_001, &filter).gap);
+    }
+
+    #[test]
+    fn live_subscribers_receive_published_events() {
+        let mut bus = EventBus::new(4, 4);
+        let mut receiver = bus.sender.subscribe();
+        bus.publish(session_event());
+        let received = receiver.try_recv().unwrap();
+        assert_eq!(received.id, 1);
+        assert_eq!(received.event.category(), EventCategory::Session);
+    }
+
+    #[test]
+    fn background_inbox_is_forwarded_once_per_event() {
+        let mut bus = EventBus::new(4, 4);
+        let first = bus.unseen_background(vec![background_event("a"), background_event("b")]);
+        assert_eq!(first.len(), 2);
+        let second = bus.unseen_background(vec![background_event("b"), background_event("c")]);
+        assert_eq!(
+            second.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+            vec!["c"]
+        );
+        assert!(bus
+            .unseen_background(vec![background_event("c")])
+            .is_empty());
+    }
+
+    #[test]
+    fn filter_defaults_exclude_logs_and_honour_levels() {
+        let mut bus = EventBus::new(8, 8);
+        let session = bus.publish(session_event());
+        let info = bus.publish(RuntimeEvent::Log {
+            entry: log_entry(1, "info"),
+        });
+        let warn = bus.publish(RuntimeEvent::Log {
+            entry: log_entry(2, "warn"),
+        });
+
+        let default = EventFilter::parse(None, None).unwrap();
+        assert!(default.matches(&session));
+        assert!(!default.matches(&info));
+
+        let warn_only = EventFilter::parse(None, Some("warn")).unwrap();
+        assert!(warn_only.matches(&session));
+        assert!(!warn_only.matches(&info));
+        assert!(warn_only.matches(&warn));
+
+        let logs = EventFilter::parse(Some("log"), None).unwrap();
+        assert!(!logs.matches(&session));
+        assert!(logs.matches(&info));
+
+        assert!(EventFilter::parse(Some("sync,nope"), None).is_err());
+        assert!(EventFilter::parse(None, Some("verbose")).is_err());
+    }
+}
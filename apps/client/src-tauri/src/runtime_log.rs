@@ -2,6 +2,7 @@
 //!
 //! - 前端调试窗 / HTTP bridge 可拉取
 //! - 覆盖重登、学习通、收件箱等关键路径
+//! - 每条日志同时发布到 [`crate::runtime_events`]，供 SSE 订阅
 
 use chrono::Local;
 use serde::Serialize;
@@ -72,10 +73,13 @@ fn push_inner(level: &str, scope: &str, message: &str, details: Option<Value>) {
             eprintln!("  details: {s}");
         }
     }
-    guard.items.push_back(item);
+    guard.items.push_back(item.clone());
     while guard.items.len() > MAX_LOGS {
         guard.items.pop_front();
     }
+    // 释放日志锁后再发布，事件总线不会反向写日志
+    drop(guard);
+    crate::runtime_events::publish(crate::runtime_events::RuntimeEvent::Log { entry: item });
 }
 
 pub fn log_debug(scope: impl AsRef<str>, message: impl AsRef<str>) {
@@ -82,6 +82,31 @@ fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
     diff == 0
 }
 
+/// 由用户口令派生 32 字节主密钥（PBKDF2-HMAC-SHA256，单块输出）。
+///
+/// 用于不依赖密钥环的可移植加密（如跨设备迁移的资料包）；盐与迭代次数需随密文一起保存。
+pub fn derive_passphrase_key(
+    passphrase: &str,
+    salt: &[u8],
+    iterations: u32,
+) -> Result<[u8; 32], SecretEnvelopeError> {
+    if passphrase.is_empty() || iterations == 0 {
+        return Err(SecretEnvelopeError::InvalidKey);
+    }
+    let password = passphrase.as_bytes();
+    let mut block = salt.to_vec();
+    block.extend_from_slice(&1u32.to_be_bytes());
+    let mut u = hmac_sha256(password, &block);
+    let mut output = u;
+    for _ in 1..iterations {
+        u = hmac_sha256(password, &u);
+        for (out, byte) in output.iter_mut().zip(u.iter()) {
+            *out ^= byte;
+        }
+    }
+    Ok(output)
+}
+
 pub fn is_encrypted_secret(value: &str) -> bool {
     value.starts_with(SECRET_ENVELOPE_PREFIX)
 }
@@ -195,6 +220,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn passphrase_key_matches_pbkdf2_vectors() {
+        let hex = |bytes: [u8; 32]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        assert_eq!(
+            hex(derive_passphrase_key("password", b"salt", 1).expect("derive")),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            hex(derive_passphrase_key("password", b"salt", 4096).expect("derive")),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+        assert_eq!(
+            derive_passphrase_key("", b"salt", 1),
+            Err(SecretEnvelopeError::InvalidKey)
+        );
+    }
+
     #[test]
     fn rejects_wrong_key() {
         let encrypted = encrypt_string(&key(), "secret").expect("encrypt");
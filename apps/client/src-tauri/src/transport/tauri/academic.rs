@@ -1,6 +1,7 @@
-//! 教务/学术领域 Tauri commands：考试、排名、学籍、学期、教室、培养方案、校历、图书馆。
+//! 教务/学术领域 Tauri commands：考试、排名、学籍、学期、教室、培养方案、校历、图书馆（含读者账户与检索订阅）。
 //!
-//! 本模块只做传输适配：参数透传、构造 [`AcademicReadService`] 并映射错误；
+//! 本模块只做传输适配：参数透传、构造 [`AcademicReadService`] 并把错误映射为
+//! 结构化的 [`CommandError`]（带错误码与重试提示）；
 //! 网络获取 / 缓存 / 离线降级语义全部收敛在 application 层（Tauri 与 HTTP 共用），
 //! 不再复制业务分支。DTO（Exam/Ranking/Classroom/CalendarEvent）为前端契约保留。
 
@@ -9,6 +10,7 @@ use tauri::State;
 
 use crate::app_state::AppState;
 use crate::application;
+use crate::transport::tauri::common::CommandError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Exam {
@@ -18,6 +20,9 @@ pub struct Exam {
     pub end_time: String,
     pub location: String,
     pub seat_number: Option<String>,
+    /// 考试性质（正常 / 补考）；早期缓存缺少该字段时为空
+    #[serde(default)]
+    pub exam_type: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,49 +53,88 @@ pub struct CalendarEvent {
     pub event_type: String,
 }
 
-/// 构造共享只读服务（快照克隆，网络 await 不持锁）。
-fn service(state: &AppState) -> application::AcademicReadService {
-    application::AcademicReadService::new(application::ApplicationContext::new(
-        state.client.clone(),
-        crate::DB_FILENAME,
+/// 构造共享只读服务（快照克隆，网络 await 不持锁）；`account` 为空时作用于活动账号。
+async fn service(
+    state: &AppState,
+    account: Option<String>,
+) -> Result<application::AcademicReadService, CommandError> {
+    let handle = state.client_for(account.as_deref()).await?;
+    Ok(application::AcademicReadService::new(
+        application::ApplicationContext::new(handle, crate::DB_FILENAME),
     ))
 }
 
-fn map_error(error: application::ApplicationError) -> String {
-    error.to_string()
-}
-
 #[tauri::command]
 pub(crate) async fn fetch_exams(
     state: State<'_, AppState>,
     semester: Option<String>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_exams(semester)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
+/// 未开考考试的倒计时（读 exams_cache，不发网络请求）。
 #[tauri::command]
-pub(crate) async fn fetch_ranking(
+pub(crate) async fn get_exam_countdown(
     state: State<'_, AppState>,
     student_id: Option<String>,
     semester: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    service(&state)
+    let sid = match student_id.filter(|sid| !sid.trim().is_empty()) {
+        Some(sid) => sid.trim().to_string(),
+        None => {
+            let client = state.client.read().await;
+            client
+                .user_info
+                .as_ref()
+                .map(|u| u.student_id.clone())
+                .ok_or_else(|| "当前未登录".to_string())?
+        }
+    };
+    Ok(crate::modules::exam::build_countdown_payload(
+        crate::DB_FILENAME,
+        &sid,
+        semester.as_deref(),
+    ))
+}
+
+/// 更新考前提醒提前量（分钟）；空列表表示只通报考试变更。
+#[tauri::command]
+pub(crate) async fn update_exam_reminder_leads(
+    leads: Vec<u32>,
+) -> Result<serde_json::Value, String> {
+    let leads = crate::modules::exam::save_reminder_leads(crate::DB_FILENAME, leads)?;
+    Ok(serde_json::json!({ "success": true, "reminder_leads": leads }))
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_ranking(
+    state: State<'_, AppState>,
+    student_id: Option<String>,
+    semester: Option<String>,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_ranking(student_id, semester)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_student_info(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_student_info()
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -98,28 +142,37 @@ pub(crate) async fn fetch_personal_login_access_info(
     state: State<'_, AppState>,
     page: Option<i32>,
     page_size: Option<i32>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_personal_login_access_info(page, page_size)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_semesters(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    service(&state).fetch_semesters().await.map_err(map_error)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
+        .fetch_semesters()
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_classroom_buildings(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_classroom_buildings()
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -129,32 +182,38 @@ pub(crate) async fn fetch_classrooms(
     weekday: Option<i32>,
     periods: Option<Vec<i32>>,
     building: Option<String>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_classrooms(week, weekday, periods, building)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_training_plan_options(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_training_plan_options()
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_training_plan_jys(
     state: State<'_, AppState>,
     yxid: String,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_training_plan_jys(yxid)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -170,13 +229,15 @@ pub(crate) async fn fetch_training_plan_courses(
     kcmc: Option<String>,
     page: Option<i32>,
     page_size: Option<i32>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_training_plan_courses(
             grade, kkxq, kkyx, kkjys, kcxz, kcgs, kcbh, kcmc, page, page_size,
         )
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -195,48 +256,56 @@ pub(crate) async fn fetch_calendar(
 pub(crate) async fn fetch_calendar_data(
     state: State<'_, AppState>,
     semester: Option<String>,
-) -> Result<serde_json::Value, String> {
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
     // #489 校历语义由 AcademicReadService::fetch_calendar_data 统一实现：
     // 仅成功响应写缓存并刷新 sync_time（if success 才写 calendar_public_cache），
     // 会话失效（if need_login）原样透传不写缓存；本命令只做传输适配。
-    service(&state)
+    service(&state, account)
+        .await?
         .fetch_calendar_data(semester)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_academic_progress(
     state: State<'_, AppState>,
     fasz: Option<i32>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_academic_progress(fasz)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_library_dict(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
     // 前端契约：返回含 success 的包装（attach_sync_time 平铺 OPAC JSON，
     // data 字段即字典内容），缓存降级语义由 AcademicReadService 统一。
-    service(&state)
+    service(&state, account)
+        .await?
         .fetch_library_dict()
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub(crate) async fn search_library_books(
     state: State<'_, AppState>,
     params: serde_json::Value,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .search_library_books(params)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -245,9 +314,133 @@ pub(crate) async fn fetch_library_book_detail(
     title: String,
     isbn: String,
     record_id: Option<i64>,
-) -> Result<serde_json::Value, String> {
-    service(&state)
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
         .fetch_library_book_detail(title, isbn, record_id)
         .await
-        .map_err(map_error)
+        .map_err(CommandError::from)
+}
+
+/// 图书馆当前借阅（含应还日期），经 library_account_cache 缓存降级。
+#[tauri::command]
+pub(crate) async fn fetch_library_loans(
+    state: State<'_, AppState>,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
+        .fetch_library_loans()
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_library_loan_history(
+    state: State<'_, AppState>,
+    page: Option<i64>,
+    rows: Option<i64>,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
+        .fetch_library_loan_history(page, rows)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_library_holds(
+    state: State<'_, AppState>,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
+        .fetch_library_holds()
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_library_fines(
+    state: State<'_, AppState>,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    service(&state, account)
+        .await?
+        .fetch_library_fines()
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 按条码续借，返回逐册结果与刷新后的当前借阅。
+#[tauri::command]
+pub(crate) async fn renew_library_loans(
+    state: State<'_, AppState>,
+    barcodes: Vec<String>,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    let handle = state.client_for(account.as_deref()).await?;
+    let mut client = handle.write().await;
+    crate::modules::library::renew_loans(&mut client, crate::DB_FILENAME, barcodes)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub(crate) async fn get_library_reminder_days() -> Result<serde_json::Value, CommandError> {
+    let days = crate::modules::library::load_reminder_days(crate::DB_FILENAME);
+    Ok(serde_json::json!({ "success": true, "reminder_days": days }))
+}
+
+/// 更新图书到期提醒提前量（天）；空列表表示只做逾期提醒。
+#[tauri::command]
+pub(crate) async fn update_library_reminder_days(
+    days: Vec<u32>,
+) -> Result<serde_json::Value, CommandError> {
+    let days = crate::modules::library::save_reminder_days(crate::DB_FILENAME, days)?;
+    Ok(serde_json::json!({ "success": true, "reminder_days": days }))
+}
+
+#[tauri::command]
+pub(crate) async fn list_library_saved_searches() -> Result<serde_json::Value, CommandError> {
+    let searches = crate::modules::library::list_saved_searches(crate::DB_FILENAME)?;
+    Ok(serde_json::json!({
+        "success": true,
+        "searches": searches,
+        "max_searches": crate::modules::library::MAX_SAVED_SEARCHES,
+    }))
+}
+
+/// 新建 / 更新图书检索订阅；检索条件变化时下次检查重新建立基线。
+#[tauri::command]
+pub(crate) async fn save_library_saved_search(
+    request: crate::modules::library::LibrarySavedSearchRequest,
+) -> Result<serde_json::Value, CommandError> {
+    let search = crate::modules::library::save_saved_search(crate::DB_FILENAME, &request)?;
+    Ok(serde_json::json!({ "success": true, "search": search }))
+}
+
+#[tauri::command]
+pub(crate) async fn delete_library_saved_search(
+    id: String,
+) -> Result<serde_json::Value, CommandError> {
+    let removed = crate::modules::library::delete_saved_search(crate::DB_FILENAME, &id)?;
+    Ok(serde_json::json!({ "success": true, "removed": removed }))
+}
+
+/// 立即检查一条图书检索订阅，返回新上架 / 重新在架的书目（不推送通知）。
+#[tauri::command]
+pub(crate) async fn run_library_saved_search(
+    state: State<'_, AppState>,
+    id: String,
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    let handle = state.client_for(account.as_deref()).await?;
+    let mut client = handle.write().await;
+    let check = crate::modules::library::run_saved_search(&mut client, crate::DB_FILENAME, &id)
+        .await
+        .map_err(CommandError::from)?;
+    Ok(serde_json::json!({ "success": true, "check": check }))
 }
@@ -0,0 +1,39 @@
+//! 多账号 Tauri commands：列出、切换、移除已登记账号。
+//!
+//! 账号注册表与客户端停放语义见 [`crate::app_state::accounts`]；其余领域命令通过可选的
+//! `account` 参数指定学号，缺省作用于活动账号。
+
+use tauri::State;
+
+use crate::app_state::{AccountSummary, AppState};
+use crate::transport::tauri::common::CommandError;
+
+#[tauri::command]
+pub(crate) async fn account_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountSummary>, CommandError> {
+    Ok(state.account_summaries().await)
+}
+
+/// 切换活动账号；`student_id` 为空表示停放当前账号，随后可登录新账号。
+#[tauri::command]
+pub(crate) async fn account_switch(
+    state: State<'_, AppState>,
+    student_id: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    let user = state.switch_account(student_id.as_deref()).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "user": user,
+        "accounts": state.account_summaries().await,
+    }))
+}
+
+#[tauri::command]
+pub(crate) async fn account_remove(
+    state: State<'_, AppState>,
+    student_id: String,
+) -> Result<Vec<AccountSummary>, CommandError> {
+    state.remove_account(&student_id).await?;
+    Ok(state.account_summaries().await)
+}
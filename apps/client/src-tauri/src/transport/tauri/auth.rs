@@ -20,7 +20,7 @@ use crate::db;
 use crate::http_client::HbutClient;
 use crate::modules;
 use crate::transport::tauri::common::{
-    spawn_chaoxing_sso_warmup, spawn_electricity_session_warmup,
+    spawn_chaoxing_sso_warmup, spawn_electricity_session_warmup, CommandError,
 };
 use crate::DB_FILENAME;
 
@@ -1315,7 +1315,7 @@ pub(crate) async fn login(
     captcha: Option<String>,
     lt: Option<String>,
     execution: Option<String>,
-) -> Result<UserInfo, String> {
+) -> Result<UserInfo, CommandError> {
     println!("[调试] Command login called with: username={}, password len={}, captcha={:?}, lt={:?}, execution={:?}",
              username, password.len(), captcha, lt, execution);
     let service = application::AuthService::new(application::ApplicationContext::new(
@@ -1324,8 +1324,7 @@ pub(crate) async fn login(
     ));
     let user_info = service
         .login(&username, &password, captcha, lt, execution)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     let session_key = if user_info.student_id.trim().is_empty() {
         username.clone()
     } else {
@@ -1356,14 +1355,14 @@ pub(crate) async fn logout(state: State<'_, AppState>) -> Result<(), String> {
 pub(crate) async fn restore_session(
     state: State<'_, AppState>,
     cookies: String,
-) -> Result<UserInfo, String> {
+) -> Result<UserInfo, CommandError> {
     application::AuthService::new(application::ApplicationContext::new(
         state.client.clone(),
         DB_FILENAME,
     ))
     .restore_session(&cookies)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -1501,14 +1500,13 @@ pub(crate) async fn get_cookies(state: State<'_, AppState>) -> Result<String, St
 }
 
 #[tauri::command]
-pub(crate) async fn refresh_session(state: State<'_, AppState>) -> Result<UserInfo, String> {
+pub(crate) async fn refresh_session(state: State<'_, AppState>) -> Result<UserInfo, CommandError> {
     let info = application::AuthService::new(application::ApplicationContext::new(
         state.client.clone(),
         DB_FILENAME,
     ))
     .refresh_session()
-    .await
-    .map_err(|e| e.to_string())?;
+    .await?;
     // #351：keep-alive（约 20min）顺带后台轻量学习通补票；不阻塞本次返回
     let client_arc = state.client.clone();
     let sid = info.student_id.clone();
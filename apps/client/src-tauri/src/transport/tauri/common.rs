@@ -1,11 +1,13 @@
 //! Tauri 传输层共享工具（跨领域命令复用）。
 //!
-//! 从 lib.rs 拆分：会话预热、payload 包装等通用传输辅助。
+//! 从 lib.rs 拆分：会话预热、payload 包装、命令错误等通用传输辅助。
 
 use std::sync::Arc;
 
+use serde::Serialize;
 use tokio::sync::RwLock;
 
+use crate::application::{ApplicationError, ApplicationErrorKind};
 use crate::db;
 use crate::http_client::HbutClient;
 use crate::modules;
@@ -112,3 +114,49 @@ pub(crate) fn attach_sync_time(
         }),
     }
 }
+
+/// Tauri 命令的结构化错误（前端收到 `{ code, message, retryable, retry_after_secs?, action? }`）。
+///
+/// `code` 取自 [`ApplicationErrorKind::code`]，与 HTTP Bridge `error.code` 一致；
+/// 前端 `errorMessage()` / `err.message` 读取方式不变。
+///
+/// 新增命令一律返回 `Result<_, CommandError>`；存量命令仍有不少返回 `Result<_, String>`
+/// （前端收到纯字符串，无 `code`），随所在模块改造逐步迁移，迁移时经 `From<String>` 归类。
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CommandError {
+    code: &'static str,
+    message: String,
+    retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<&'static str>,
+}
+
+impl From<ApplicationError> for CommandError {
+    fn from(error: ApplicationError) -> Self {
+        let retry_after_secs = error.retry_after_secs();
+        let kind = error.kind;
+        if kind == ApplicationErrorKind::SessionExpired {
+            crate::runtime_events::publish_session_expired(&error.message);
+        }
+        Self {
+            code: kind.code(),
+            message: error.message,
+            retryable: kind.retryable(),
+            retry_after_secs,
+            action: kind.action(),
+        }
+    }
+}
+
+/// 未经应用层归类的字符串错误：按已知文案归类，无法识别时为 internal。
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        let kind = ApplicationErrorKind::classify_message(&message)
+            .unwrap_or(ApplicationErrorKind::Internal);
+        let mut error = ApplicationError::upstream(message);
+        error.kind = kind;
+        error.into()
+    }
+}
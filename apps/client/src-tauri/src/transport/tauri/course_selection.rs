@@ -5,6 +5,9 @@ use tauri::State;
 
 use crate::app_state::AppState;
 use crate::modules;
+use crate::modules::course_selection::{
+    self, CourseSelectionUnwatchRequest, CourseSelectionWatchRequest,
+};
 use crate::transport::tauri::common::attach_sync_time;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +45,12 @@ pub struct CourseSelectionSelectRequest {
     pub jxbid: String,
     pub zjxbid: Option<String>,
     pub from: Option<String>,
+    /// 上课时间地点，用于选课前的课表冲突检查；缺省时查关注列表
+    pub sksjdd: Option<String>,
+    pub kcmc: Option<String>,
+    /// 明知冲突仍要选课：跳过选课前的课表冲突检查
+    #[serde(default)]
+    pub ignore_conflicts: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -193,3 +202,48 @@ pub(crate) async fn fetch_course_selection_detail_teacher(
         Err(e) => Err(e.to_string()),
     }
 }
+
+async fn current_student_id(state: &State<'_, AppState>) -> Result<String, String> {
+    let client = state.client.read().await;
+    client
+        .user_info
+        .as_ref()
+        .map(|u| u.student_id.clone())
+        .ok_or_else(|| "当前未登录".to_string())
+}
+
+/// 当前账号的选课关注列表（按批次）。
+#[tauri::command]
+pub(crate) async fn course_selection_watchlist(
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let sid = current_student_id(&state).await?;
+    let rounds = course_selection::list_watch_rounds(crate::DB_FILENAME, &sid)?;
+    Ok(serde_json::json!({
+        "success": true,
+        "rounds": rounds,
+        "max_rounds": course_selection::MAX_WATCH_ROUNDS,
+        "max_items": course_selection::MAX_WATCH_ITEMS,
+    }))
+}
+
+/// 关注教学班 / 子教学班，后台任务出现空位时提醒。
+#[tauri::command]
+pub(crate) async fn course_selection_watch_course(
+    state: State<'_, AppState>,
+    req: CourseSelectionWatchRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = current_student_id(&state).await?;
+    let round = course_selection::watch_course(crate::DB_FILENAME, &sid, &req)?;
+    Ok(serde_json::json!({ "success": true, "round": round }))
+}
+
+#[tauri::command]
+pub(crate) async fn course_selection_unwatch_course(
+    state: State<'_, AppState>,
+    req: CourseSelectionUnwatchRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = current_student_id(&state).await?;
+    let removed = course_selection::unwatch_course(crate::DB_FILENAME, &sid, &req)?;
+    Ok(serde_json::json!({ "success": true, "removed": removed }))
+}
@@ -4,6 +4,7 @@ use tauri::State;
 
 use crate::app_state::AppState;
 use crate::db;
+use crate::modules::electricity::{self, ElectricityWatchRequest};
 use crate::transport::tauri::common::{attach_sync_time, persist_electricity_tokens};
 use crate::DB_FILENAME;
 
@@ -49,9 +50,17 @@ pub(crate) async fn electricity_query_account(
     let uid = client.user_info.as_ref().map(|u| u.student_id.clone());
     let key_suffix = payload.to_string();
     let cache_key = uid.as_ref().map(|u| format!("{}:acct:{}", u, key_suffix));
+    let location = payload.clone();
 
     match client.query_electricity_account(payload).await {
         Ok(data) => {
+            // 关注房间顺带追加一条余额读数
+            let _ = electricity::record_account_response(
+                DB_FILENAME,
+                &location,
+                &data,
+                chrono::Local::now().timestamp(),
+            );
             let sync_time = chrono::Local::now().to_rfc3339();
             let payload = attach_sync_time(data, &sync_time, false);
             if let Some(key) = cache_key.as_ref() {
@@ -73,6 +82,36 @@ pub(crate) async fn electricity_query_account(
     }
 }
 
+/// 关注房间（位置或室友分享码）/ 更新提醒阈值。
+#[tauri::command]
+pub(crate) async fn electricity_watch_room(
+    request: ElectricityWatchRequest,
+) -> Result<serde_json::Value, String> {
+    let room = electricity::watch_room(DB_FILENAME, &request)?;
+    let share_code = electricity::share_code(&room);
+    Ok(serde_json::json!({ "success": true, "room": room, "share_code": share_code }))
+}
+
+#[tauri::command]
+pub(crate) async fn electricity_unwatch_room(room_id: String) -> Result<serde_json::Value, String> {
+    let removed = electricity::unwatch_room(DB_FILENAME, &room_id)?;
+    Ok(serde_json::json!({ "success": true, "removed": removed }))
+}
+
+/// 关注房间概览：最新余额、日均消耗、预计可用天数与近 `days` 天读数。
+#[tauri::command]
+pub(crate) async fn electricity_monitor_overview(
+    room_id: Option<String>,
+    days: Option<u32>,
+) -> Result<serde_json::Value, String> {
+    electricity::build_overview_payload(
+        DB_FILENAME,
+        room_id.as_deref(),
+        days,
+        chrono::Local::now().timestamp(),
+    )
+}
+
 #[tauri::command]
 pub(crate) async fn refresh_electricity_token(state: State<'_, AppState>) -> Result<bool, String> {
     let mut client = state.client.write().await;
@@ -12,9 +12,14 @@ use crate::DB_FILENAME;
 pub(crate) async fn sync_grades(
     state: State<'_, AppState>,
     current_only: Option<bool>,
+    analytics: Option<grade::analytics::AnalyticsRequest>,
+    account: Option<String>,
 ) -> Result<serde_json::Value, String> {
     let current_only = current_only.unwrap_or(false);
-    let client_handle = state.client.clone();
+    let client_handle = state
+        .client_for(account.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
     let uid = {
         let client = client_handle.read().await;
         client.user_info.as_ref().map(|u| u.student_id.clone())
@@ -27,7 +32,65 @@ pub(crate) async fn sync_grades(
     if let Some(job) = result.enrichment {
         service.spawn_enrichment(job);
     }
-    Ok(result.payload)
+    Ok(match analytics {
+        Some(request) => service.attach_analytics(result.payload, &request),
+        None => result.payload,
+    })
+}
+
+/// 基于本地成绩缓存计算 GPA / 学分统计（不触发远端同步；what-if 推演走此入口）。
+#[tauri::command]
+pub(crate) async fn get_grade_analytics(
+    state: State<'_, AppState>,
+    student_id: Option<String>,
+    request: Option<grade::analytics::AnalyticsRequest>,
+) -> Result<grade::analytics::GradeAnalytics, String> {
+    let sid = match student_id
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    {
+        Some(sid) => sid,
+        None => {
+            let client = state.client.read().await;
+            client
+                .user_info
+                .as_ref()
+                .map(|u| u.student_id.clone())
+                .ok_or_else(|| "当前未登录".to_string())?
+        }
+    };
+    let service =
+        grade::service::GradeService::new(state.client.clone(), grade::service::SqliteGradeCache);
+    service.analyze_cached(&sid, &request.unwrap_or_default())
+}
+
+/// 学业审核：培养方案 × 学业进度 × 成绩缓存对账（离线计算，不触发远端同步）。
+#[tauri::command]
+pub(crate) async fn get_degree_audit(
+    state: State<'_, AppState>,
+    req: Option<crate::modules::degree_audit::DegreeAuditRequest>,
+) -> Result<serde_json::Value, String> {
+    let req = req.unwrap_or_default();
+    let sid = match req
+        .student_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(sid) => sid.to_string(),
+        None => {
+            let client = state.client.read().await;
+            client
+                .user_info
+                .as_ref()
+                .map(|u| u.student_id.clone())
+                .ok_or_else(|| "当前未登录".to_string())?
+        }
+    };
+    let report = crate::modules::degree_audit::run_degree_audit(DB_FILENAME, &sid, &req)?;
+    Ok(crate::modules::degree_audit::build_degree_audit_payload(
+        report,
+    ))
 }
 
 #[tauri::command]
@@ -4,6 +4,7 @@
 //! 跨领域共享的传输辅助位于 [`common`]。
 
 pub mod academic;
+pub mod accounts;
 pub mod auth;
 pub mod chaoxing;
 pub mod common;
@@ -15,6 +16,7 @@ pub mod grades;
 pub mod notification;
 pub mod qxzkb;
 pub mod schedule;
+pub mod scheduler;
 pub mod system;
 pub mod teaching_eval;
 pub mod update;
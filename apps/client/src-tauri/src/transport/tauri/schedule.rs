@@ -10,6 +10,7 @@ use tauri::State;
 use crate::app_state::AppState;
 use crate::application;
 use crate::db;
+use crate::transport::tauri::common::CommandError;
 use crate::transport::tauri::config::get_temp_upload_endpoint_config;
 use crate::utils::ics::{
     escape_ics_text, fold_ics_line, parse_ics_datetime, sanitize_filename_part,
@@ -99,16 +100,15 @@ pub struct UpdateCustomScheduleCourseRequest {
 pub(crate) async fn sync_schedule(
     state: State<'_, AppState>,
     semester: Option<String>,
-) -> Result<serde_json::Value, String> {
+    account: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
     // 统一课表同步用例：Tauri 与 HTTP Bridge 走同一 ScheduleService
     // （学期解析 → 抓取 → 成功写缓存 → 失败保留 offline 快照），本 handler 只做传输适配。
-    application::ScheduleService::new(application::ApplicationContext::new(
-        state.client.clone(),
-        DB_FILENAME,
-    ))
-    .sync_schedule(semester)
-    .await
-    .map_err(|e| e.to_string())
+    let handle = state.client_for(account.as_deref()).await?;
+    application::ScheduleService::new(application::ApplicationContext::new(handle, DB_FILENAME))
+        .sync_schedule(semester)
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -301,9 +301,11 @@ pub(crate) async fn add_custom_schedule_course(
     let saved = db::get_custom_schedule_course(DB_FILENAME, sid.as_str(), record.id.as_str())
         .map_err(|e| e.to_string())?
         .unwrap_or(record);
+    let conflicts = crate::modules::schedule_analysis::custom_course_conflicts(DB_FILENAME, &saved);
     Ok(serde_json::json!({
         "success": true,
-        "data": custom_course_to_payload(&saved)
+        "data": custom_course_to_payload(&saved),
+        "conflicts": conflicts
     }))
 }
 
@@ -452,12 +454,198 @@ pub(crate) async fn update_custom_schedule_course(
     let updated = db::get_custom_schedule_course(DB_FILENAME, sid.as_str(), record.id.as_str())
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "更新后未找到课程记录".to_string())?;
+    let conflicts =
+        crate::modules::schedule_analysis::custom_course_conflicts(DB_FILENAME, &updated);
+    Ok(serde_json::json!({
+        "success": true,
+        "data": custom_course_to_payload(&updated),
+        "conflicts": conflicts
+    }))
+}
+
+/// 由本地课表 / 自定义课程 / 考试缓存生成 RFC 5545 日历（周重复 + 停课周排除 + 提醒）。
+#[tauri::command]
+pub(crate) async fn export_schedule_ics(
+    state: State<'_, AppState>,
+    req: crate::modules::ics_calendar::ScheduleIcsRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = match req
+        .student_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(sid) => sid.to_string(),
+        None => {
+            let client = state.client.read().await;
+            client
+                .user_info
+                .as_ref()
+                .map(|u| u.student_id.clone())
+                .ok_or_else(|| "当前未登录".to_string())?
+        }
+    };
+    let build = crate::modules::ics_calendar::build_schedule_ics(DB_FILENAME, &sid, &req)?;
+    Ok(crate::modules::ics_calendar::build_payload(&sid, build))
+}
+
+/// 外部 .ics → 自定义课程：`commit=false` 预览冲突与未对齐条目，`commit=true` 一次事务写入。
+#[tauri::command]
+pub(crate) async fn import_schedule_ics(
+    state: State<'_, AppState>,
+    req: crate::modules::ics_calendar::ScheduleIcsImportRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = match req
+        .student_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(sid) => sid.to_string(),
+        None => {
+            let client = state.client.read().await;
+            client
+                .user_info
+                .as_ref()
+                .map(|u| u.student_id.clone())
+                .ok_or_else(|| "当前未登录".to_string())?
+        }
+    };
+    let import = crate::modules::ics_calendar::import_schedule_ics(DB_FILENAME, &sid, &req)?;
+    Ok(crate::modules::ics_calendar::build_import_payload(import))
+}
+
+/// 签发（轮换）当前设备的 webcal 订阅令牌；订阅源由本地 Bridge 提供，故仅在 bridge 构建中注册。
+#[cfg(feature = "bridge")]
+#[tauri::command]
+pub(crate) async fn issue_schedule_feed(
+    state: State<'_, AppState>,
+    req: crate::modules::ics_calendar::ScheduleFeedRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let (token, record) =
+        crate::modules::ics_calendar::issue_feed_token(DB_FILENAME, &sid, req.device.as_deref())?;
+    Ok(crate::modules::ics_calendar::build_feed_payload(
+        crate::http_server::bridge_listen_addr(),
+        &token,
+        &record,
+    ))
+}
+
+/// 作废 webcal 订阅令牌（`device` 为空时作废该学号全部设备）。
+#[cfg(feature = "bridge")]
+#[tauri::command]
+pub(crate) async fn revoke_schedule_feed(
+    state: State<'_, AppState>,
+    req: crate::modules::ics_calendar::ScheduleFeedRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let revoked =
+        crate::modules::ics_calendar::revoke_feed_tokens(DB_FILENAME, &sid, req.device.as_deref())?;
+    Ok(serde_json::json!({ "success": true, "revoked": revoked }))
+}
+
+/// 本学期官方课程、自定义课程与考试之间的时间冲突。
+#[tauri::command]
+pub(crate) async fn detect_schedule_conflicts(
+    state: State<'_, AppState>,
+    req: crate::modules::schedule_analysis::ScheduleConflictRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let report = crate::modules::schedule_analysis::analyze_conflicts(DB_FILENAME, &sid, &req)?;
+    Ok(crate::modules::schedule_analysis::build_conflicts_payload(
+        report,
+    ))
+}
+
+/// 按周次范围查询连续空闲节次；附带同学导出的 .ics 时返回共同空闲时间。
+#[tauri::command]
+pub(crate) async fn find_schedule_free_slots(
+    state: State<'_, AppState>,
+    req: crate::modules::schedule_analysis::FreeSlotRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let report = crate::modules::schedule_analysis::find_free_slots(DB_FILENAME, &sid, &req)?;
+    Ok(crate::modules::schedule_analysis::build_free_slots_payload(
+        report,
+    ))
+}
+
+/// 当前账号的排课草案列表。
+#[tauri::command]
+pub(crate) async fn schedule_plan_list(
+    state: State<'_, AppState>,
+    student_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, student_id.as_deref()).await?;
+    let plans = crate::modules::plan_simulator::list_plans(DB_FILENAME, &sid)?;
     Ok(serde_json::json!({
         "success": true,
-        "data": custom_course_to_payload(&updated)
+        "plans": plans,
+        "max_plans": crate::modules::plan_simulator::MAX_DRAFT_PLANS,
+        "max_courses": crate::modules::plan_simulator::MAX_DRAFT_COURSES,
     }))
 }
 
+/// 新建 / 覆盖排课草案（候选课程取选课列表或全校课表的行）。
+#[tauri::command]
+pub(crate) async fn schedule_plan_save(
+    state: State<'_, AppState>,
+    req: crate::modules::plan_simulator::PlanSaveRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let plan = crate::modules::plan_simulator::save_plan(DB_FILENAME, &sid, &req)?;
+    Ok(serde_json::json!({ "success": true, "plan": plan }))
+}
+
+#[tauri::command]
+pub(crate) async fn schedule_plan_delete(
+    state: State<'_, AppState>,
+    req: crate::modules::plan_simulator::PlanRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let removed = crate::modules::plan_simulator::delete_plan(DB_FILENAME, &sid, &req.id)?;
+    Ok(serde_json::json!({ "success": true, "removed": removed }))
+}
+
+/// 草案与现有课表合并：周课表网格、时间冲突与培养方案学分对照（离线计算）。
+#[tauri::command]
+pub(crate) async fn schedule_plan_simulate(
+    state: State<'_, AppState>,
+    req: crate::modules::plan_simulator::PlanSimulateRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    let report = crate::modules::plan_simulator::simulate_plan(DB_FILENAME, &sid, &req)?;
+    Ok(crate::modules::plan_simulator::build_simulation_payload(
+        report,
+    ))
+}
+
+/// 草案导出为 .ics 预览。
+#[tauri::command]
+pub(crate) async fn schedule_plan_export_ics(
+    state: State<'_, AppState>,
+    req: crate::modules::plan_simulator::PlanIcsRequest,
+) -> Result<serde_json::Value, String> {
+    let sid = request_student_id(&state, req.student_id.as_deref()).await?;
+    crate::modules::plan_simulator::export_plan_ics(DB_FILENAME, &sid, &req)
+}
+
+async fn request_student_id(
+    state: &State<'_, AppState>,
+    student_id: Option<&str>,
+) -> Result<String, String> {
+    if let Some(sid) = student_id.map(str::trim).filter(|v| !v.is_empty()) {
+        return Ok(sid.to_string());
+    }
+    let client = state.client.read().await;
+    client
+        .user_info
+        .as_ref()
+        .map(|u| u.student_id.clone())
+        .ok_or_else(|| "当前未登录".to_string())
+}
+
 fn export_upload_endpoint(req: &ScheduleExportRequest) -> String {
     if let Some(v) = req.upload_endpoint.as_ref() {
         if !v.trim().is_empty() {
@@ -0,0 +1,46 @@
+//! 后台任务调度 Tauri commands：任务状态快照、启停、配置更新与立即执行。
+
+use tauri::State;
+
+use crate::modules::scheduler::{JobConfig, JobKind, JobScheduler, JobStatus};
+use crate::DB_FILENAME;
+
+fn parse_job(job: &str) -> Result<JobKind, String> {
+    JobKind::parse(job).ok_or_else(|| format!("未知后台任务: {}", job))
+}
+
+#[tauri::command]
+pub(crate) async fn get_background_jobs(
+    scheduler: State<'_, JobScheduler>,
+) -> Result<Vec<JobStatus>, String> {
+    Ok(scheduler.snapshot().await)
+}
+
+#[tauri::command]
+pub(crate) async fn set_background_job_enabled(
+    scheduler: State<'_, JobScheduler>,
+    job: String,
+    enabled: bool,
+) -> Result<JobStatus, String> {
+    let job = parse_job(&job)?;
+    scheduler.set_enabled(DB_FILENAME, job, enabled).await
+}
+
+#[tauri::command]
+pub(crate) async fn update_background_job(
+    scheduler: State<'_, JobScheduler>,
+    job: String,
+    config: JobConfig,
+) -> Result<JobStatus, String> {
+    let job = parse_job(&job)?;
+    scheduler.update(DB_FILENAME, job, config).await
+}
+
+#[tauri::command]
+pub(crate) async fn run_background_job_now(
+    scheduler: State<'_, JobScheduler>,
+    job: String,
+) -> Result<JobStatus, String> {
+    let job = parse_job(&job)?;
+    scheduler.run_now(job).await
+}
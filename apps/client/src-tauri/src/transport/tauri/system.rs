@@ -1,4 +1,5 @@
-//! 系统级 Tauri commands：应用退出/外部打开、数据库备份、资源分享（WebDAV）、运行时诊断日志。
+//! 系统级 Tauri commands：应用退出/外部打开、数据库备份与恢复、迁移资料包、缓存占用、资源分享（WebDAV）、运行时诊断日志、
+//! Bridge API 令牌管理。
 
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
@@ -7,7 +8,9 @@ use tauri::{Manager, State};
 use tauri_plugin_shell::ShellExt;
 
 use crate::app_state::AppState;
+use crate::application::ApplicationError;
 use crate::runtime_log;
+use crate::transport::tauri::common::CommandError;
 
 /// 显式备份数据库（#550）：备份到应用数据目录 backup 子目录，保留最近 keep 份。
 /// 只备份不恢复、不覆盖正式库；失败时返回错误信息。
@@ -35,6 +38,168 @@ pub(crate) async fn backup_database_now(
     }))
 }
 
+/// 恢复只接受正式库 backup 目录（含子目录）内的文件，避免前端传入任意路径。
+fn resolve_backup_file(backup_path: &str) -> Result<std::path::PathBuf, String> {
+    let root = crate::db::default_backup_dir(crate::DB_FILENAME)
+        .canonicalize()
+        .map_err(|e| format!("备份目录不可用: {}", e))?;
+    let path = std::path::Path::new(backup_path.trim())
+        .canonicalize()
+        .map_err(|e| format!("备份文件不存在: {}", e))?;
+    if !path.starts_with(&root) || !path.is_file() {
+        return Err("只能从应用备份目录中的备份恢复".to_string());
+    }
+    Ok(path)
+}
+
+/// 列出手动、定时、迁移前与恢复前的全部备份（设置页「备份与恢复」）。
+#[tauri::command]
+pub(crate) async fn backup_list() -> Result<serde_json::Value, CommandError> {
+    let backups = crate::db::run_blocking(|| crate::db::list_backup_files(crate::DB_FILENAME))
+        .await
+        .map_err(ApplicationError::storage)?;
+    Ok(serde_json::json!({
+        "success": true,
+        "backup_dir": crate::db::default_backup_dir(crate::DB_FILENAME).to_string_lossy().to_string(),
+        "backups": backups,
+    }))
+}
+
+/// 预览从备份恢复的逐表差异（新增 / 删除 / 变更行），不写入任何数据。
+#[tauri::command]
+pub(crate) async fn backup_restore_preview(
+    backup_path: String,
+) -> Result<serde_json::Value, CommandError> {
+    let path = resolve_backup_file(&backup_path).map_err(ApplicationError::validation)?;
+    let preview =
+        crate::db::run_blocking(move || crate::db::preview_restore(crate::DB_FILENAME, path))
+            .await
+            .map_err(ApplicationError::storage)?;
+    Ok(serde_json::json!({ "success": true, "preview": preview }))
+}
+
+/// 从备份恢复选中的表；恢复前自动为正式库生成一份 pre-restore 备份。
+#[tauri::command]
+pub(crate) async fn backup_restore_tables(
+    backup_path: String,
+    tables: Vec<String>,
+) -> Result<serde_json::Value, CommandError> {
+    let path = resolve_backup_file(&backup_path).map_err(ApplicationError::validation)?;
+    let source = path.to_string_lossy().to_string();
+    let report = crate::db::run_blocking(move || {
+        crate::db::restore_tables(crate::DB_FILENAME, path, &tables)
+    })
+    .await
+    .map_err(|e| {
+        runtime_log::log_warn("backup", format!("从备份恢复失败: {}", e));
+        ApplicationError::storage(e)
+    })?;
+    runtime_log::log_info(
+        "backup",
+        format!(
+            "已从 {} 恢复 {} 张表，恢复前备份: {}",
+            source,
+            report.restored.len(),
+            report.safety_backup.display()
+        ),
+    );
+    Ok(serde_json::json!({ "success": true, "report": report }))
+}
+
+/// 导出跨设备迁移资料包（口令加密，不含 Cookie 与密钥环凭据）。
+/// 未指定 `destination` 时写入应用数据目录 `profile/` 子目录；`settings` 为前端挑选的设置项。
+/// 口令过短为 `validation`，其余失败（目录不可写等）为 `storage`。
+#[tauri::command]
+pub(crate) async fn profile_export(
+    app: tauri::AppHandle,
+    passphrase: String,
+    settings: Option<serde_json::Map<String, serde_json::Value>>,
+    destination: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    if passphrase.chars().count() < crate::db::PROFILE_MIN_PASSPHRASE_CHARS {
+        return Err(ApplicationError::validation(format!(
+            "口令至少需要 {} 个字符",
+            crate::db::PROFILE_MIN_PASSPHRASE_CHARS
+        ))
+        .into());
+    }
+    let destination = match destination.filter(|value| !value.trim().is_empty()) {
+        Some(value) => std::path::PathBuf::from(value.trim()),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| ApplicationError::storage(format!("获取应用数据目录失败: {}", e)))?
+            .join("profile")
+            .join(format!(
+                "mini-hbut-{}.{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                crate::db::PROFILE_FILE_EXTENSION
+            )),
+    };
+    let settings = settings.unwrap_or_default();
+    let report = crate::db::run_blocking(move || {
+        crate::db::export_profile(crate::DB_FILENAME, destination, &passphrase, &settings)
+    })
+    .await
+    .map_err(ApplicationError::storage)?;
+    runtime_log::log_info(
+        "profile",
+        format!("已导出迁移资料包: {}", report.path.display()),
+    );
+    Ok(serde_json::json!({ "success": true, "report": report }))
+}
+
+/// 导入迁移资料包：只合并不覆盖，内容不同的条目保留本地并在 `conflicts` 中列出。
+/// `dry_run` 为 true 时只预览合并结果；前端设置合并结果在 `merged_settings` 中返回。
+/// 失败多为口令错误或文件损坏，统一归为 `validation`。
+#[tauri::command]
+pub(crate) async fn profile_import(
+    bundle_path: String,
+    passphrase: String,
+    current_settings: Option<serde_json::Map<String, serde_json::Value>>,
+    dry_run: Option<bool>,
+) -> Result<serde_json::Value, CommandError> {
+    let dry_run = dry_run.unwrap_or(false);
+    let current_settings = current_settings.unwrap_or_default();
+    let report = crate::db::run_blocking(move || {
+        crate::db::import_profile(
+            crate::DB_FILENAME,
+            bundle_path.trim(),
+            &passphrase,
+            &current_settings,
+            dry_run,
+        )
+    })
+    .await
+    .map_err(ApplicationError::validation)?;
+    if !dry_run {
+        runtime_log::log_info(
+            "profile",
+            format!(
+                "已导入迁移资料包（{}）: 新增 {} 项，冲突 {} 项",
+                report.manifest.created_at,
+                report.added.values().sum::<usize>(),
+                report.conflicts.len()
+            ),
+        );
+    }
+    Ok(serde_json::json!({ "success": true, "report": report }))
+}
+
+/// 各缓存表的行数与 payload 字节数（设置页「存储占用」）。
+#[tauri::command]
+pub(crate) async fn cache_usage() -> Result<serde_json::Value, CommandError> {
+    let tables = crate::db::run_blocking(|| crate::db::cache_usage(crate::DB_FILENAME))
+        .await
+        .map_err(ApplicationError::storage)?;
+    let total_bytes: i64 = tables.iter().map(|usage| usage.bytes).sum();
+    Ok(serde_json::json!({
+        "success": true,
+        "total_bytes": total_bytes,
+        "tables": tables,
+    }))
+}
+
 #[tauri::command]
 pub(crate) fn exit_app(app_handle: tauri::AppHandle) -> Result<(), String> {
     app_handle.exit(0);
@@ -462,3 +627,51 @@ pub(crate) async fn get_runtime_diag(
         "time": chrono::Local::now().to_rfc3339(),
     }))
 }
+
+/// 签发限定权限的 Bridge API 令牌；明文仅在此返回一次。
+#[cfg(feature = "bridge")]
+#[tauri::command]
+pub(crate) async fn bridge_token_issue(
+    req: crate::http_server::api_tokens::IssueBridgeTokenRequest,
+) -> Result<serde_json::Value, CommandError> {
+    let (token, info) = crate::http_server::api_tokens::issue_bridge_token(
+        crate::DB_FILENAME,
+        &req,
+        chrono::Utc::now().timestamp(),
+    )?;
+    runtime_log::log_info(
+        "bridge",
+        format!("签发 API 令牌 {}（{}）", info.id, info.scopes.join(" ")),
+    );
+    Ok(serde_json::json!({ "success": true, "token": token, "info": info }))
+}
+
+/// 列出全部 Bridge API 令牌（含已过期 / 已吊销）与可选权限。
+#[cfg(feature = "bridge")]
+#[tauri::command]
+pub(crate) async fn bridge_token_list() -> Result<serde_json::Value, CommandError> {
+    let tokens = crate::http_server::api_tokens::list_bridge_tokens(
+        crate::DB_FILENAME,
+        chrono::Utc::now().timestamp(),
+    )?;
+    Ok(serde_json::json!({
+        "success": true,
+        "tokens": tokens,
+        "scopes": crate::http_server::api_tokens::scope_catalog(),
+    }))
+}
+
+/// 吊销 Bridge API 令牌，立即生效。
+#[cfg(feature = "bridge")]
+#[tauri::command]
+pub(crate) async fn bridge_token_revoke(id: String) -> Result<serde_json::Value, CommandError> {
+    let revoked = crate::http_server::api_tokens::revoke_bridge_token(
+        crate::DB_FILENAME,
+        &id,
+        chrono::Utc::now().timestamp(),
+    )?;
+    if revoked {
+        runtime_log::log_info("bridge", format!("吊销 API 令牌 {}", id.trim()));
+    }
+    Ok(serde_json::json!({ "success": true, "revoked": revoked }))
+}
@@ -1,4 +1,4 @@
-//! ICS 导出共享工具函数（Tauri 命令通道与 HTTP Bridge 通道复用）。
+//! ICS 导出 / 导入共享工具函数（Tauri 命令通道与 HTTP Bridge 通道复用）。
 //!
 //! 这些纯函数此前在 `lib.rs` 与 `http_server.rs` 中各有一份逐字等价的实现，
 //! 阶段 3 架构收敛统一收束到本模块，避免两通道行为漂移。
@@ -57,6 +57,56 @@ pub fn parse_ics_datetime(input: &str) -> Option<chrono::NaiveDateTime> {
         .or_else(|| chrono::NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S").ok())
 }
 
+/// RFC 5545 文本反转义（[`escape_ics_text`] 的逆过程）。
+pub fn unescape_ics_text(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// RFC 5545 §3.1 行展开：续行（以空格或 Tab 开头）拼回上一行，兼容 CRLF 与 LF。
+pub fn unfold_ics_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match raw.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str(rest);
+                }
+            }
+            _ if raw.trim().is_empty() => {}
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// 解析 RFC 5545 DATE-TIME 值：`20240301T083000`（本地）/ `20240301T083000Z`（UTC，换算为 UTC+8），
+/// 其余格式回退 [`parse_ics_datetime`]。
+pub fn parse_ics_property_datetime(input: &str) -> Option<chrono::NaiveDateTime> {
+    let input = input.trim();
+    if let Some(utc) = input.strip_suffix('Z') {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S") {
+            return Some(dt + chrono::Duration::hours(8));
+        }
+    }
+    chrono::NaiveDateTime::parse_from_str(input, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| parse_ics_datetime(input))
+}
+
 #[cfg(test)]
 mod unit {
     use super::*;
@@ -158,4 +208,36 @@ mod unit {
         assert!(parse_ics_datetime("2024-13-01T00:00:00").is_none());
         assert!(parse_ics_datetime("2024-03-01").is_none());
     }
+
+    #[test]
+    fn unescape_reverses_escape() {
+        let raw = "a\\b;c,d\nline";
+        assert_eq!(unescape_ics_text(&escape_ics_text(raw)), raw);
+        assert_eq!(unescape_ics_text(r"x\Ny"), "x\ny");
+        assert_eq!(unescape_ics_text("tail\\"), "tail\\");
+    }
+
+    #[test]
+    fn unfold_joins_continuation_lines() {
+        let folded = fold_ics_line(&format!("SUMMARY:{}", "课".repeat(40)));
+        let content = format!("BEGIN:VEVENT\r\n{folded}\n\tTAIL\nEND:VEVENT\n");
+        let lines = unfold_ics_lines(&content);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], format!("SUMMARY:{}TAIL", "课".repeat(40)));
+        assert_eq!(lines[2], "END:VEVENT");
+    }
+
+    #[test]
+    fn parse_property_datetime_handles_basic_and_utc() {
+        let local = parse_ics_property_datetime("20240301T083000").unwrap();
+        assert_eq!(
+            local.format("%Y-%m-%d %H:%M").to_string(),
+            "2024-03-01 08:30"
+        );
+        let utc = parse_ics_property_datetime("20240301T003000Z").unwrap();
+        assert_eq!(utc.format("%Y-%m-%d %H:%M").to_string(), "2024-03-01 08:30");
+        let fallback = parse_ics_property_datetime("2024-03-01T08:30:00").unwrap();
+        assert_eq!(fallback, local);
+        assert!(parse_ics_property_datetime("20240301").is_none());
+    }
 }
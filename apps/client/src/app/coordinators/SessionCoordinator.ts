@@ -36,6 +36,7 @@ import { startNotificationMonitor } from '../../utils/notify_center.js'
 import { resetCloudSyncCooldownForSession, runAutoCloudSyncAfterLogin } from '../../utils/cloud_sync.js'
 import { invokeNative, isTauriRuntime } from '../../platform/native'
 import { runExclusiveLogin, isLoginInFlight } from './sessionGate'
+import { errorCode, errorRetryAfterSecs } from '../../utils/login_errors'
 
 export const createSessionCoordinator = (runtime: AppRuntime): SessionCoordinator => {
   const { state } = runtime
@@ -342,11 +343,12 @@ export const createSessionCoordinator = (runtime: AppRuntime): SessionCoordinato
       await doLogin()
       return true
     } catch (e) {
-      // 检测登录冷却错误，等待后重试一次
+      // 检测登录冷却错误，等待后重试一次（优先用结构化 rate_limited + retry_after_secs）
       const msg = String((e as Error)?.message || e || '')
       const cooldownMatch = msg.match(/登录频率过高，请(\d+)秒后再试/)
-      if (cooldownMatch) {
-        const waitSec = parseInt(cooldownMatch[1], 10)
+      const hintedWait = errorCode(e) === 'rate_limited' ? errorRetryAfterSecs(e) : null
+      if (hintedWait !== null || cooldownMatch) {
+        const waitSec = hintedWait ?? parseInt(cooldownMatch?.[1] || '0', 10)
         if (waitSec > 0 && waitSec <= 120) {
           console.info(`[Session] 登录冷却中，${waitSec}秒后重试...`)
           state.jwxtSessionLastError.value = `登录冷却中，${waitSec} 秒后重试`
@@ -373,6 +375,10 @@ export const createSessionCoordinator = (runtime: AppRuntime): SessionCoordinato
 
   // 会话刷新错误分类：网络类错误 ≠ 会话过期，避免误报「会话已过期」诱导用户反复登录
   const classifySessionError = (err: unknown): 'network' | 'auth' | 'unknown' => {
+    // 后端结构化错误码优先；旧格式 / 字符串错误再回退到文案匹配
+    const code = errorCode(err)
+    if (code === 'network' || code === 'upstream_maintenance') return 'network'
+    if (code === 'session_expired' || code === 'captcha_required') return 'auth'
     const raw = String((err as Error)?.message || err || '').toLowerCase()
     if (
       /error sending request|timed? ?out|timeout|connection|connect |network|econnrefused|econnreset|dns |resolve|socket|eof|broken pipe/i.test(raw)
@@ -182,6 +182,11 @@ export const cleanMessage = (value) => {
   return text
 }
 
+/** 选课前课表冲突检查拒绝提交时的错误前缀（与后端 `describe_conflicts` 一致）。 */
+export const SCHEDULE_CONFLICT_PREFIX = '与现有课表冲突'
+
+export const isScheduleConflictMessage = (message) => safeText(message).includes(SCHEDULE_CONFLICT_PREFIX)
+
 export const resolveErrorMessage = (error, fallback = '请求失败') => {
   const responseData = error?.response?.data
   const messageCandidates = [
@@ -1,5 +1,5 @@
 import { describe, expect, it } from 'vitest'
-import { friendlyLoginError, readableErrorText } from './login_errors'
+import { errorCode, errorRetryAfterSecs, friendlyLoginError, readableErrorText } from './login_errors'
 
 describe('readableErrorText', () => {
   it('Error 实例取 message', () => {
@@ -71,7 +71,28 @@ describe('friendlyLoginError', () => {
     )
   })
 
+  it('结构化 network 错误码不依赖英文原文', () => {
+    expect(friendlyLoginError({ code: 'network', message: 'dns error', retryable: true })).toBe(
+      '无法连接教务系统，请检查网络后重试'
+    )
+  })
+
   it('其它技术英文给兜底前缀', () => {
     expect(friendlyLoginError('weird internal error')).toBe('登录失败：weird internal error')
   })
 })
+
+describe('errorCode / errorRetryAfterSecs', () => {
+  it('读取 CommandError / Bridge error 的结构化字段', () => {
+    const err = { code: 'rate_limited', message: '登录频率过高，请42秒后再试', retryable: true, retry_after_secs: 42 }
+    expect(errorCode(err)).toBe('rate_limited')
+    expect(errorRetryAfterSecs(err)).toBe(42)
+  })
+
+  it('字符串与旧格式错误返回空值', () => {
+    expect(errorCode('会话已过期')).toBe('')
+    expect(errorCode({ kind: '业务错误', message: 'x' })).toBe('')
+    expect(errorRetryAfterSecs({ code: 'session_expired', message: 'x' })).toBeNull()
+    expect(errorRetryAfterSecs(null)).toBeNull()
+  })
+})
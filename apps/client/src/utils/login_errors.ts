@@ -24,6 +24,24 @@ export const readableErrorText = (raw: unknown): string => {
   return String(raw).trim()
 }
 
+/**
+ * 后端结构化错误码（Tauri `CommandError.code` / Bridge `error.code`），
+ * 取值：session_expired / captcha_required / upstream_maintenance / rate_limited /
+ * validation / network / storage / internal / forbidden；旧格式或字符串错误返回空串。
+ */
+export const errorCode = (raw: unknown): string => {
+  if (raw === null || typeof raw !== 'object') return ''
+  const code = (raw as { code?: unknown }).code
+  return typeof code === 'string' ? code : ''
+}
+
+/** 后端建议的等待秒数（登录冷却剩余时间、限流 / 维护退避），未提供时返回 null。 */
+export const errorRetryAfterSecs = (raw: unknown): number | null => {
+  if (raw === null || typeof raw !== 'object') return null
+  const secs = (raw as { retry_after_secs?: unknown }).retry_after_secs
+  return typeof secs === 'number' && Number.isFinite(secs) && secs >= 0 ? secs : null
+}
+
 /** 网络/连接层失败（reqwest、fetch 原文等）。 */
 const NETWORK_ERROR_RE =
   /error sending request|timed out|timeout|connection (?:failed|closed|reset|refused)|failed to fetch|network (?:error|request)|ECONN|ENOTFOUND|ETIMEDOUT|无法连接|无法访问/i
@@ -50,8 +68,8 @@ export const friendlyLoginError = (raw: unknown): string => {
     return '验证码识别服务暂不可用，请稍后重试'
   }
 
-  // 网络/连接层失败：不把 reqwest 英文原文暴露给用户
-  if (NETWORK_ERROR_RE.test(text)) {
+  // 网络/连接层失败：优先看结构化错误码，不把 reqwest 英文原文暴露给用户
+  if (errorCode(raw) === 'network' || NETWORK_ERROR_RE.test(text)) {
     return '无法连接教务系统，请检查网络后重试'
   }
 